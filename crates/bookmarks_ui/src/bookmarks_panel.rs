@@ -0,0 +1,279 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    SharedString, Subscription, Task, WeakEntity, actions, px,
+};
+use project::bookmark_store::BookmarkStore;
+use text::{Point, ToPoint as _};
+use ui::{ListItem, Tooltip, prelude::*};
+use workspace::{
+    OpenOptions, Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    bookmarks_panel,
+    [
+        /// Toggles focus on the bookmarks panel.
+        ToggleFocus
+    ]
+);
+
+const DEFAULT_WIDTH: Pixels = px(240.);
+
+/// Lists the bookmarks in the project, grouped by file.
+pub struct BookmarksPanel {
+    workspace: WeakEntity<Workspace>,
+    bookmark_store: Entity<BookmarkStore>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    width: Option<Pixels>,
+    _subscription: Subscription,
+}
+
+struct BookmarkEntry {
+    row: u32,
+    text: SharedString,
+    has_label: bool,
+}
+
+impl BookmarksPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, _, cx| {
+                let bookmark_store = workspace.project().read(cx).bookmark_store();
+                let workspace = workspace.weak_handle();
+                cx.new(|cx| Self {
+                    _subscription: cx.observe(&bookmark_store, |_, _, cx| cx.notify()),
+                    workspace,
+                    bookmark_store,
+                    focus_handle: cx.focus_handle(),
+                    position: DockPosition::Right,
+                    width: None,
+                })
+            })
+        })
+    }
+
+    fn entries(&self, cx: &App) -> Vec<(Arc<Path>, Vec<BookmarkEntry>)> {
+        self.bookmark_store
+            .read(cx)
+            .all_bookmarks()
+            .map(|(path, buffer, bookmarks)| {
+                let snapshot = buffer.read(cx).snapshot();
+                let entries = bookmarks
+                    .iter()
+                    .map(|bookmark| {
+                        let row = bookmark.position.to_point(&snapshot).row;
+                        let (text, has_label) = match &bookmark.label {
+                            Some(label) => (label.clone(), true),
+                            None => {
+                                let line = snapshot
+                                    .text_for_range(
+                                        Point::new(row, 0)..Point::new(row, snapshot.line_len(row)),
+                                    )
+                                    .collect::<String>();
+                                (line.trim().to_string().into(), false)
+                            }
+                        };
+                        BookmarkEntry {
+                            row,
+                            text,
+                            has_label,
+                        }
+                    })
+                    .collect();
+                (path.clone(), entries)
+            })
+            .collect()
+    }
+
+    fn open_bookmark(&self, path: Arc<Path>, row: u32, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open_task = workspace.update(cx, |workspace, cx| {
+            workspace.open_abs_path(path.to_path_buf(), OpenOptions::default(), window, cx)
+        });
+        window
+            .spawn(cx, async move |cx| {
+                let item = open_task.await?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor.update_in(cx, |editor, window, cx| {
+                        editor.go_to_singleton_buffer_point(Point::new(row, 0), window, cx);
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_file(&self, path: &Path, cx: &App) -> impl IntoElement {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let directory = self.workspace.upgrade().and_then(|workspace| {
+            let project = workspace.read(cx).project().read(cx);
+            let (_, relative_path) = project.find_worktree(path, cx)?;
+            Some(relative_path.parent()?.to_string_lossy().to_string())
+        });
+        h_flex()
+            .px_2()
+            .pt_2()
+            .gap_1()
+            .child(Label::new(file_name).size(LabelSize::Small))
+            .when_some(
+                directory.filter(|dir| !dir.is_empty()),
+                |this, directory| {
+                    this.child(
+                        Label::new(directory)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    )
+                },
+            )
+    }
+
+    fn render_bookmark(
+        &self,
+        ix: usize,
+        path: Arc<Path>,
+        entry: BookmarkEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let row = entry.row;
+        ListItem::new(ix)
+            .inset(true)
+            .start_slot(
+                Label::new((row + 1).to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(entry.text)
+                    .size(LabelSize::Small)
+                    .color(if entry.has_label {
+                        Color::Default
+                    } else {
+                        Color::Muted
+                    })
+                    .truncate(),
+            )
+            .end_slot(
+                IconButton::new(("remove-bookmark", ix), IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Remove Bookmark"))
+                    .on_click({
+                        let path = path.clone();
+                        cx.listener(move |this, _, _, cx| {
+                            this.bookmark_store.update(cx, |bookmark_store, cx| {
+                                bookmark_store.remove_bookmark(&path, row, cx)
+                            });
+                        })
+                    }),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.open_bookmark(path.clone(), row, window, cx);
+            }))
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self.entries(cx);
+        let mut list = v_flex()
+            .id("bookmarks")
+            .size_full()
+            .overflow_y_scroll()
+            .pb_2();
+        let mut ix = 0;
+        for (path, bookmarks) in entries {
+            list = list.child(self.render_file(&path, cx));
+            for entry in bookmarks {
+                list = list.child(self.render_bookmark(ix, path.clone(), entry, cx));
+                ix += 1;
+            }
+        }
+
+        v_flex()
+            .key_context("BookmarksPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .map(|this| {
+                if ix == 0 {
+                    this.justify_center().items_center().child(
+                        Label::new("No bookmarks yet. Use `editor: toggle bookmark` to add one.")
+                            .color(Color::Muted),
+                    )
+                } else {
+                    this.child(list)
+                }
+            })
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "Bookmarks Panel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.width.unwrap_or(DEFAULT_WIDTH)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        self.bookmark_store
+            .read(cx)
+            .all_bookmarks()
+            .next()
+            .is_some()
+            .then_some(IconName::Pin)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
@@ -0,0 +1,11 @@
+pub mod bookmarks_panel;
+mod label_bookmark_modal;
+
+use gpui::App;
+
+pub use bookmarks_panel::BookmarksPanel;
+use label_bookmark_modal::LabelBookmarkModal;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(LabelBookmarkModal::register).detach();
+}
@@ -0,0 +1,137 @@
+use editor::{
+    Editor,
+    actions::{LabelBookmark, SelectAll},
+};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    prelude::*,
+};
+use language::Buffer;
+use project::bookmark_store::BookmarkStore;
+use text::ToPoint as _;
+use ui::prelude::*;
+use workspace::ModalView;
+
+/// Prompts for the label of the bookmark on the line containing the newest cursor.
+pub(crate) struct LabelBookmarkModal {
+    label_editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    buffer: Entity<Buffer>,
+    position: text::Anchor,
+    row: u32,
+}
+
+impl ModalView for LabelBookmarkModal {}
+
+impl EventEmitter<DismissEvent> for LabelBookmarkModal {}
+
+impl Focusable for LabelBookmarkModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl LabelBookmarkModal {
+    pub(crate) fn register(
+        editor: &mut Editor,
+        _window: Option<&mut Window>,
+        cx: &mut Context<Editor>,
+    ) {
+        let handle = cx.entity().downgrade();
+        editor
+            .register_action(move |_: &LabelBookmark, window, cx| {
+                let Some(editor) = handle.upgrade() else {
+                    return;
+                };
+                let Some(workspace) = editor.read(cx).workspace() else {
+                    return;
+                };
+                let bookmark_store = workspace.read(cx).project().read(cx).bookmark_store();
+                let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+                    let head = editor.selections.newest_anchor().head();
+                    editor.buffer().read(cx).text_anchor_for_position(head, cx)
+                }) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, move |window, cx| {
+                        LabelBookmarkModal::new(bookmark_store, buffer, position, window, cx)
+                    });
+                });
+            })
+            .detach();
+    }
+
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        let current_label = bookmark_store
+            .read(cx)
+            .bookmarks(&buffer, None, &snapshot, cx)
+            .find(|bookmark| bookmark.position.to_point(&snapshot).row == row)
+            .and_then(|bookmark| bookmark.label.clone());
+
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label", window, cx);
+            if let Some(label) = current_label {
+                editor.set_text(label, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            position,
+            row,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let label = SharedString::from(self.label_editor.read(cx).text(cx));
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_label(self.buffer.clone(), self.position, Some(label), cx)
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for LabelBookmarkModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("LabelBookmarkModal")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new(format!(
+                        "Label the bookmark on line {}. Leave empty to clear the label.",
+                        self.row + 1
+                    ))
+                    .color(Color::Muted),
+                ),
+            )
+    }
+}
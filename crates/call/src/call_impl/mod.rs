@@ -101,6 +101,8 @@ impl ActiveCall {
             _subscriptions: vec![
                 client.add_request_handler(cx.weak_entity(), Self::handle_incoming_call),
                 client.add_message_handler(cx.weak_entity(), Self::handle_call_canceled),
+                client.add_message_handler(cx.weak_entity(), Self::handle_removed_from_room),
+                client.add_message_handler(cx.weak_entity(), Self::handle_muted_in_room),
             ],
             client,
             user_store,
@@ -155,6 +157,37 @@ impl ActiveCall {
         Ok(())
     }
 
+    async fn handle_removed_from_room(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RemovedFromRoom>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if this
+                .room()
+                .is_some_and(|room| room.read(cx).id() == envelope.payload.room_id)
+            {
+                this.hang_up(cx).detach_and_log_err(cx);
+            }
+        })?;
+        Ok(())
+    }
+
+    async fn handle_muted_in_room(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::MutedInRoom>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let Some(room) = this.room()
+                && room.read(cx).id() == envelope.payload.room_id
+            {
+                room.update(cx, |room, cx| room.mute(cx));
+            }
+        })?;
+        Ok(())
+    }
+
     pub fn global(cx: &App) -> Entity<Self> {
         cx.global::<GlobalActiveCall>().0.clone()
     }
@@ -587,6 +587,28 @@ impl Room {
         })
     }
 
+    pub fn remove_participant(&mut self, user_id: u64, cx: &Context<Self>) -> Task<Result<()>> {
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.spawn(async move |_, _| {
+            client
+                .request(proto::RemoveRoomParticipant { room_id, user_id })
+                .await
+                .map(|_| ())
+        })
+    }
+
+    pub fn mute_participant(&mut self, user_id: u64, cx: &Context<Self>) -> Task<Result<()>> {
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.spawn(async move |_, _| {
+            client
+                .request(proto::MuteRoomParticipant { room_id, user_id })
+                .await
+                .map(|_| ())
+        })
+    }
+
     pub fn pending_participants(&self) -> &[Arc<User>] {
         &self.pending_participants
     }
@@ -1480,6 +1502,13 @@ impl Room {
         }
     }
 
+    /// Mutes the microphone, unless it's already muted.
+    pub fn mute(&mut self, cx: &mut Context<Self>) {
+        if self.live_kit.is_some() && !self.is_muted() {
+            self.toggle_mute(cx);
+        }
+    }
+
     pub fn toggle_deafen(&mut self, cx: &mut Context<Self>) {
         if let Some(live_kit) = self.live_kit.as_mut() {
             // When deafening, mute the microphone if it was not already muted.
@@ -0,0 +1,508 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use editor::{Editor, SelectionEffects, icon_for_symbol_kind, scroll::Autoscroll};
+use gpui::{
+    Action, AnyElement, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, Pixels, Task, WeakEntity, actions, px,
+};
+use language::{Bias, Buffer, PointUtf16, Unclipped, point_from_lsp};
+use project::{CallHierarchyCall, CallHierarchyDirection, CallHierarchyItem, Project};
+use ui::{ListItem, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{
+    OpenOptions, Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    call_hierarchy_panel,
+    [
+        /// Toggles focus on the call hierarchy panel.
+        ToggleFocus,
+        /// Shows the calls to the symbol under the cursor in the call hierarchy panel.
+        ShowIncomingCalls,
+        /// Shows the calls made by the symbol under the cursor in the call hierarchy panel.
+        ShowOutgoingCalls,
+    ]
+);
+
+const DEFAULT_WIDTH: Pixels = px(240.);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
+            workspace
+                .register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+                    show_call_hierarchy(workspace, CallHierarchyDirection::Incoming, window, cx);
+                })
+                .register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+                    show_call_hierarchy(workspace, CallHierarchyDirection::Outgoing, window, cx);
+                });
+        },
+    )
+    .detach();
+}
+
+fn show_call_hierarchy(
+    workspace: &mut Workspace,
+    direction: CallHierarchyDirection,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<CallHierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| {
+            panel.show_call_hierarchy(buffer, position, direction, cx)
+        });
+    }
+}
+
+/// Shows the calls to or from a symbol as a tree, whose nodes load their own calls when they're
+/// expanded.
+pub struct CallHierarchyPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    width: Option<Pixels>,
+    direction: CallHierarchyDirection,
+    /// The buffer that the hierarchy was shown from, whose language server is asked for the
+    /// calls of its nodes.
+    buffer: Option<Entity<Buffer>>,
+    roots: Vec<CallNode>,
+    message: Option<SharedString>,
+    /// Incremented whenever the shown hierarchy changes, so that calls that were requested for
+    /// the previous one are discarded.
+    generation: usize,
+    _prepare_task: Task<()>,
+}
+
+struct CallNode {
+    item: CallHierarchyItem,
+    /// Where the calls that this node represents are made, within the file of the calling
+    /// symbol. Empty for the roots of the hierarchy.
+    call_ranges: Vec<Range<Unclipped<PointUtf16>>>,
+    /// The calls to or from this node's symbol, or `None` if they haven't been loaded yet.
+    children: Option<Vec<CallNode>>,
+    expanded: bool,
+}
+
+impl CallNode {
+    fn new(item: CallHierarchyItem, call_ranges: Vec<Range<Unclipped<PointUtf16>>>) -> Self {
+        Self {
+            item,
+            call_ranges,
+            children: None,
+            expanded: false,
+        }
+    }
+}
+
+impl CallHierarchyPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, _, cx| {
+                let project = workspace.project().clone();
+                let workspace = workspace.weak_handle();
+                cx.new(|cx| Self {
+                    workspace,
+                    project,
+                    focus_handle: cx.focus_handle(),
+                    position: DockPosition::Right,
+                    width: None,
+                    direction: CallHierarchyDirection::Incoming,
+                    buffer: None,
+                    roots: Vec::new(),
+                    message: None,
+                    generation: 0,
+                    _prepare_task: Task::ready(()),
+                })
+            })
+        })
+    }
+
+    /// Replaces the shown hierarchy with the one of the symbol at `position`.
+    pub fn show_call_hierarchy(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        direction: CallHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) {
+        self.direction = direction;
+        self.generation += 1;
+        self.roots.clear();
+        self.message = Some("Loading…".into());
+        let items = self.project.update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, position, cx)
+        });
+        self.buffer = Some(buffer);
+
+        let generation = self.generation;
+        self._prepare_task = cx.spawn(async move |this, cx| {
+            let items = items.await;
+            this.update(cx, |this, cx| {
+                if this.generation != generation {
+                    return;
+                }
+                match items {
+                    Ok(items) if items.is_empty() => {
+                        this.message = Some("No symbol to show calls for at the cursor.".into());
+                    }
+                    Ok(items) => {
+                        this.message = None;
+                        this.roots = items
+                            .into_iter()
+                            .map(|item| CallNode::new(item, Vec::new()))
+                            .collect();
+                        this.expand_roots(cx);
+                    }
+                    Err(error) => {
+                        this.message = Some(format!("Failed to find calls: {error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+        cx.notify();
+    }
+
+    fn set_direction(&mut self, direction: CallHierarchyDirection, cx: &mut Context<Self>) {
+        if self.direction == direction {
+            return;
+        }
+        self.direction = direction;
+        self.generation += 1;
+        for root in &mut self.roots {
+            root.children = None;
+        }
+        self.expand_roots(cx);
+        cx.notify();
+    }
+
+    fn expand_roots(&mut self, cx: &mut Context<Self>) {
+        for ix in 0..self.roots.len() {
+            self.roots[ix].expanded = true;
+            self.load_calls(vec![ix], cx);
+        }
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut CallNode> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get_mut(*first)?;
+        for ix in rest {
+            node = node.children.as_mut()?.get_mut(*ix)?;
+        }
+        Some(node)
+    }
+
+    fn toggle_expanded(&mut self, path: &[usize], cx: &mut Context<Self>) {
+        let Some(node) = self.node_mut(path) else {
+            return;
+        };
+        node.expanded = !node.expanded;
+        if node.expanded && node.children.is_none() {
+            self.load_calls(path.to_vec(), cx);
+        }
+        cx.notify();
+    }
+
+    fn load_calls(&mut self, path: Vec<usize>, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffer.clone() else {
+            return;
+        };
+        let Some(item) = self.node_mut(&path).map(|node| node.item.clone()) else {
+            return;
+        };
+        let direction = self.direction;
+        let calls = self.project.update(cx, |project, cx| {
+            project.call_hierarchy_calls(&buffer, item, direction, cx)
+        });
+
+        let generation = self.generation;
+        cx.spawn(async move |this, cx| {
+            let calls = calls.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                if this.generation != generation {
+                    return;
+                }
+                if let Some(node) = this.node_mut(&path) {
+                    node.children = Some(
+                        calls
+                            .into_iter()
+                            .map(|CallHierarchyCall { item, ranges }| CallNode::new(item, ranges))
+                            .collect(),
+                    );
+                    cx.notify();
+                }
+            })
+            .log_err();
+        })
+        .detach();
+    }
+
+    /// Opens the place that the node at `path` stands for: the call site of an incoming call,
+    /// or the symbol itself otherwise.
+    fn open_node(&mut self, path: &[usize], window: &mut Window, cx: &mut Context<Self>) {
+        let direction = self.direction;
+        let Some(node) = self.node_mut(path) else {
+            return;
+        };
+        let Ok(abs_path) = node.item.lsp_item.uri.to_file_path() else {
+            return;
+        };
+        let position = match node.call_ranges.first() {
+            Some(call_range) if direction == CallHierarchyDirection::Incoming => call_range.start,
+            _ => point_from_lsp(node.item.lsp_item.selection_range.start),
+        };
+
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open_task = workspace.update(cx, |workspace, cx| {
+            workspace.open_abs_path(abs_path, OpenOptions::default(), window, cx)
+        });
+        window
+            .spawn(cx, async move |cx| {
+                let item = open_task.await?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor.update_in(cx, |editor, window, cx| {
+                        let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                            return;
+                        };
+                        let position = buffer.read(cx).clip_point_utf16(position, Bias::Left);
+                        editor.change_selections(
+                            SelectionEffects::scroll(Autoscroll::center()),
+                            window,
+                            cx,
+                            |s| s.select_ranges([position..position]),
+                        );
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_nodes(
+        &self,
+        nodes: &[CallNode],
+        path: &mut Vec<usize>,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        for (ix, node) in nodes.iter().enumerate() {
+            path.push(ix);
+            rows.push(self.render_node(rows.len(), node, path, cx));
+            if node.expanded {
+                match &node.children {
+                    Some(children) if children.is_empty() => {
+                        let text = match self.direction {
+                            CallHierarchyDirection::Incoming => "No incoming calls",
+                            CallHierarchyDirection::Outgoing => "No outgoing calls",
+                        };
+                        rows.push(self.render_placeholder(rows.len(), path.len(), text));
+                    }
+                    Some(children) => self.render_nodes(children, path, rows, cx),
+                    None => rows.push(self.render_placeholder(rows.len(), path.len(), "Loading…")),
+                }
+            }
+            path.pop();
+        }
+    }
+
+    fn render_node(
+        &self,
+        ix: usize,
+        node: &CallNode,
+        path: &[usize],
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let lsp_item = &node.item.lsp_item;
+        let detail = lsp_item.detail.clone().or_else(|| {
+            let file_name = lsp_item.uri.to_file_path().ok()?.file_name()?.to_owned();
+            Some(file_name.to_string_lossy().into_owned())
+        });
+        let call_count = node.call_ranges.len();
+
+        ListItem::new(ix)
+            .inset(true)
+            .indent_level(path.len() - 1)
+            .indent_step_size(px(12.))
+            .toggle(node.expanded)
+            .on_toggle(cx.listener({
+                let path = path.to_vec();
+                move |this, _, _, cx| this.toggle_expanded(&path, cx)
+            }))
+            .start_slot(
+                Icon::new(icon_for_symbol_kind(lsp_item.kind))
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(lsp_item.name.clone()).size(LabelSize::Small))
+                    .when_some(detail, |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when(call_count > 1, |item| {
+                item.end_slot(
+                    Label::new(format!("{call_count} calls"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .on_click(cx.listener({
+                let path = path.to_vec();
+                move |this, _, window, cx| this.open_node(&path, window, cx)
+            }))
+            .into_any_element()
+    }
+
+    fn render_placeholder(&self, ix: usize, indent_level: usize, text: &'static str) -> AnyElement {
+        ListItem::new(ix)
+            .inset(true)
+            .selectable(false)
+            .indent_level(indent_level)
+            .indent_step_size(px(12.))
+            .child(Label::new(text).size(LabelSize::Small).color(Color::Muted))
+            .into_any_element()
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (title, other_direction, tooltip) = match self.direction {
+            CallHierarchyDirection::Incoming => (
+                "Incoming Calls",
+                CallHierarchyDirection::Outgoing,
+                "Show Outgoing Calls",
+            ),
+            CallHierarchyDirection::Outgoing => (
+                "Outgoing Calls",
+                CallHierarchyDirection::Incoming,
+                "Show Incoming Calls",
+            ),
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small))
+            .child(
+                IconButton::new("toggle-call-direction", IconName::ArrowRightLeft)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text(tooltip))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.set_direction(other_direction, cx);
+                    })),
+            )
+    }
+}
+
+impl Render for CallHierarchyPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = Vec::new();
+        self.render_nodes(&self.roots, &mut Vec::new(), &mut rows, cx);
+
+        v_flex()
+            .key_context("CallHierarchyPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .map(|this| {
+                if rows.is_empty() {
+                    let message = self.message.clone().unwrap_or_else(|| {
+                        "Use `call hierarchy panel: show incoming calls` on a symbol to explore \
+                         its calls."
+                            .into()
+                    });
+                    this.justify_center()
+                        .items_center()
+                        .px_2()
+                        .child(Label::new(message).color(Color::Muted))
+                } else {
+                    this.child(self.render_header(cx)).child(
+                        v_flex()
+                            .id("call-hierarchy")
+                            .size_full()
+                            .overflow_y_scroll()
+                            .pb_2()
+                            .children(rows),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for CallHierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for CallHierarchyPanel {}
+
+impl Panel for CallHierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Call Hierarchy Panel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.width.unwrap_or(DEFAULT_WIDTH)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        (!self.roots.is_empty()).then_some(IconName::ListTree)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Call Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
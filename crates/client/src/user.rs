@@ -93,6 +93,8 @@ pub struct Contact {
     pub user: Arc<User>,
     pub online: bool,
     pub busy: bool,
+    /// Whether the contact is in a focus session and doesn't want to be disturbed.
+    pub focused: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -117,6 +119,7 @@ pub struct UserStore {
     outgoing_contact_requests: Vec<Arc<User>>,
     pending_contact_requests: HashMap<u64, usize>,
     invite_info: Option<InviteInfo>,
+    focused: bool,
     client: Weak<Client>,
     _maintain_contacts: Task<()>,
     _maintain_current_user: Task<Result<()>>,
@@ -194,6 +197,7 @@ impl UserStore {
             participant_indices: Default::default(),
             outgoing_contact_requests: Default::default(),
             invite_info: None,
+            focused: false,
             client: Arc::downgrade(&client),
             update_contacts_tx,
             _maintain_contacts: cx.spawn(async move |this, cx| {
@@ -220,6 +224,14 @@ impl UserStore {
                         Status::Authenticated
                         | Status::Reauthenticated
                         | Status::Connected { .. } => {
+                            // The server forgets the focus status of dropped connections.
+                            if matches!(status, Status::Connected { .. })
+                                && this.read_with(cx, |this, _| this.focused).unwrap_or(false)
+                            {
+                                client
+                                    .send(proto::UpdateFocusStatus { focused: true })
+                                    .log_err();
+                            }
                             if let Some(user_id) = client.user_id() {
                                 let response = client
                                     .cloud_client()
@@ -515,6 +527,19 @@ impl UserStore {
         self.perform_contact_request(user_id, proto::RemoveContact { user_id }, cx)
     }
 
+    /// Sets whether the user is in a focus session, which is shown to their contacts.
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.focused == focused {
+            return;
+        }
+        self.focused = focused;
+        if let Some(client) = self.client.upgrade()
+            && matches!(*client.status().borrow(), Status::Connected { .. })
+        {
+            client.send(proto::UpdateFocusStatus { focused }).log_err();
+        }
+    }
+
     pub fn has_incoming_contact_request(&self, user_id: u64) -> bool {
         self.incoming_contact_requests
             .iter()
@@ -935,6 +960,7 @@ impl Contact {
             user,
             online: contact.online,
             busy: contact.busy,
+            focused: contact.focused,
         })
     }
 }
@@ -0,0 +1,269 @@
+use editor::{ClipboardHistory, ClipboardHistoryEntry, Editor};
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity,
+    Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::ModalView;
+
+actions!(
+    clipboard_history,
+    [
+        /// Opens a picker of recently copied text to paste at the cursors.
+        PasteFromHistory
+    ]
+);
+
+/// Entries are previewed with at most this many characters of their text.
+const MAX_PREVIEW_LEN: usize = 120;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(ClipboardHistoryModal::register).detach();
+}
+
+pub struct ClipboardHistoryModal {
+    picker: Entity<Picker<ClipboardHistoryDelegate>>,
+}
+
+impl ClipboardHistoryModal {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action(move |_: &PasteFromHistory, window, cx| {
+                Self::toggle(&editor_handle, window, cx);
+            })
+            .detach();
+    }
+
+    fn toggle(editor: &WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = editor
+            .update(cx, |editor, _| editor.workspace())
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        let entries = cx
+            .try_global::<ClipboardHistory>()
+            .map(|history| history.entries().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let editor = editor.clone();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                ClipboardHistoryModal::new(editor, entries, window, cx)
+            });
+        })
+    }
+
+    fn new(
+        editor: WeakEntity<Editor>,
+        entries: Vec<ClipboardHistoryEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = ClipboardHistoryDelegate::new(cx.entity().downgrade(), editor, entries);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for ClipboardHistoryModal {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for ClipboardHistoryModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ClipboardHistoryModal {}
+impl ModalView for ClipboardHistoryModal {}
+
+struct ClipboardHistoryDelegate {
+    modal: WeakEntity<ClipboardHistoryModal>,
+    editor: WeakEntity<Editor>,
+    entries: Vec<ClipboardHistoryEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl ClipboardHistoryDelegate {
+    fn new(
+        modal: WeakEntity<ClipboardHistoryModal>,
+        editor: WeakEntity<Editor>,
+        entries: Vec<ClipboardHistoryEntry>,
+    ) -> Self {
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| StringMatchCandidate::new(ix, &preview(&entry.text)))
+            .collect();
+        Self {
+            modal,
+            editor,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+/// Joins the lines of `text` with spaces, so that it fits on a single line.
+fn preview(text: &str) -> String {
+    let mut preview = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((ix, _)) = preview.char_indices().nth(MAX_PREVIEW_LEN) {
+        preview.truncate(ix);
+        preview.push('…');
+    }
+    preview
+}
+
+impl PickerDelegate for ClipboardHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search clipboard history…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.entries.is_empty() {
+            "Nothing has been copied yet".into()
+        } else {
+            "No matches".into()
+        })
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.entries.get(mat.candidate_id))
+        {
+            self.editor
+                .update(cx, |editor, cx| {
+                    editor.paste_clipboard_history_entry(entry, window, cx)
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the most recent copies first, like when there's no query.
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = 0;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let entry = self.entries.get(mat.candidate_id)?;
+
+        let mut details = Vec::new();
+        if let Some(file_name) = entry.source_path.as_ref().and_then(|path| path.file_name()) {
+            details.push(file_name.to_string_lossy().into_owned());
+        }
+        let segment_count = entry
+            .selections
+            .as_ref()
+            .map_or(1, |selections| selections.len());
+        if segment_count > 1 {
+            details.push(format!("{segment_count} selections"));
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .when(!details.is_empty(), |item| {
+                    item.end_slot(
+                        Label::new(details.join(" · "))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                }),
+        )
+    }
+}
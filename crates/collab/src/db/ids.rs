@@ -167,7 +167,7 @@ impl ChannelRole {
         }
     }
 
-    /// True if the role can share screen/microphone/projects into rooms.
+    /// True if the role can share screen/microphone into rooms.
     pub fn can_use_microphone(&self) -> bool {
         use ChannelRole::*;
         match self {
@@ -176,6 +176,24 @@ impl ChannelRole {
         }
     }
 
+    /// True if the role can share projects into rooms.
+    pub fn can_share_projects(&self) -> bool {
+        use ChannelRole::*;
+        match self {
+            Admin | Member => true,
+            Talker | Guest | Banned => false,
+        }
+    }
+
+    /// True if the role can edit the channel notes.
+    pub fn can_edit_notes(&self) -> bool {
+        use ChannelRole::*;
+        match self {
+            Admin | Member => true,
+            Talker | Guest | Banned => false,
+        }
+    }
+
     /// True if the role can edit shared projects.
     pub fn can_edit_projects(&self) -> bool {
         use ChannelRole::*;
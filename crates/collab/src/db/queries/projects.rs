@@ -56,7 +56,7 @@ impl Database {
             if !participant
                 .role
                 .unwrap_or(ChannelRole::Member)
-                .can_edit_projects()
+                .can_share_projects()
             {
                 return Err(anyhow!("guests cannot share projects"))?;
             }
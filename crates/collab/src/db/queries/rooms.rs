@@ -1152,6 +1152,46 @@ impl Database {
         .await
     }
 
+    /// Returns the connection with which the given user joined the room, so that an admin of
+    /// the room can mute them or remove them from it. Admins can't be moderated.
+    pub async fn room_participant_connection_for_moderation(
+        &self,
+        admin_id: UserId,
+        room_id: RoomId,
+        user_id: UserId,
+    ) -> Result<ConnectionId> {
+        self.transaction(|tx| async move {
+            let admin = room_participant::Entity::find()
+                .filter(
+                    Condition::all()
+                        .add(room_participant::Column::RoomId.eq(room_id))
+                        .add(room_participant::Column::UserId.eq(admin_id))
+                        .add(room_participant::Column::Role.eq(ChannelRole::Admin)),
+                )
+                .one(&*tx)
+                .await?
+                .context("only admins can moderate participants")?;
+
+            let participant = room_participant::Entity::find()
+                .filter(
+                    Condition::all()
+                        .add(room_participant::Column::RoomId.eq(room_id))
+                        .add(room_participant::Column::UserId.eq(user_id)),
+                )
+                .one(&*tx)
+                .await?
+                .context("no such room participant")?;
+            let admin_role = admin.role.unwrap_or(ChannelRole::Admin);
+            if !admin_role.should_override(participant.role.unwrap_or(ChannelRole::Member)) {
+                Err(anyhow!("admins cannot be moderated"))?;
+            }
+            Ok(participant
+                .answering_connection()
+                .context("participant has not joined the room")?)
+        })
+        .await
+    }
+
     async fn check_user_has_signed_cla(
         &self,
         user_id: UserId,
@@ -319,9 +319,12 @@ impl Server {
             .add_request_handler(rejoin_room)
             .add_request_handler(leave_room)
             .add_request_handler(set_room_participant_role)
+            .add_request_handler(remove_room_participant)
+            .add_request_handler(mute_room_participant)
             .add_request_handler(call)
             .add_request_handler(cancel_call)
             .add_message_handler(decline_call)
+            .add_message_handler(update_focus_status)
             .add_request_handler(update_participant_location)
             .add_request_handler(share_project)
             .add_message_handler(unshare_project)
@@ -338,12 +341,16 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::FindSearchCandidates>)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentHighlights>)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentSymbols>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetIncomingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetOutgoingCalls>)
             .add_request_handler(forward_read_only_project_request::<proto::GetProjectSymbols>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferForSymbol>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferById>)
             .add_request_handler(forward_read_only_project_request::<proto::SynchronizeBuffers>)
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
+            .add_request_handler(forward_read_only_project_request::<proto::ResolveCodeLens>)
             .add_request_handler(forward_read_only_project_request::<proto::GetColorPresentation>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
             .add_request_handler(forward_read_only_project_request::<proto::GitGetBranches>)
@@ -1332,7 +1339,7 @@ async fn connection_lost(
         _ = executor.sleep(RECONNECT_TIMEOUT).fuse() => {
 
             log::info!("connection lost, removing all resources for user:{}, connection:{:?}", session.user_id(), session.connection_id);
-            leave_room_for_session(&session, session.connection_id).await.trace_err();
+            leave_room_for_session(&session, session.user_id(), session.connection_id).await.trace_err();
             leave_channel_buffers_for_session(&session)
                 .await
                 .trace_err();
@@ -1654,7 +1661,7 @@ async fn leave_room(
     response: Response<proto::LeaveRoom>,
     session: MessageContext,
 ) -> Result<()> {
-    leave_room_for_session(&session, session.connection_id).await?;
+    leave_room_for_session(&session, session.user_id(), session.connection_id).await?;
     response.send(proto::Ack {})?;
     Ok(())
 }
@@ -1707,6 +1714,75 @@ async fn set_room_participant_role(
     Ok(())
 }
 
+/// Removes someone else from the room.
+async fn remove_room_participant(
+    request: proto::RemoveRoomParticipant,
+    response: Response<proto::RemoveRoomParticipant>,
+    session: MessageContext,
+) -> Result<()> {
+    let room_id = RoomId::from_proto(request.room_id);
+    let user_id = UserId::from_proto(request.user_id);
+    let connection_id = session
+        .db()
+        .await
+        .room_participant_connection_for_moderation(session.user_id(), room_id, user_id)
+        .await?;
+
+    leave_room_for_session(&session, user_id, connection_id).await?;
+    session
+        .peer
+        .send(
+            connection_id,
+            proto::RemovedFromRoom {
+                room_id: room_id.to_proto(),
+            },
+        )
+        .trace_err();
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Mutes someone else's microphone in the room. They can unmute themselves again.
+async fn mute_room_participant(
+    request: proto::MuteRoomParticipant,
+    response: Response<proto::MuteRoomParticipant>,
+    session: MessageContext,
+) -> Result<()> {
+    let room_id = RoomId::from_proto(request.room_id);
+    let connection_id = session
+        .db()
+        .await
+        .room_participant_connection_for_moderation(
+            session.user_id(),
+            room_id,
+            UserId::from_proto(request.user_id),
+        )
+        .await?;
+
+    session.peer.send(
+        connection_id,
+        proto::MutedInRoom {
+            room_id: room_id.to_proto(),
+        },
+    )?;
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Updates whether the user is in a focus session, which is shown to their contacts.
+async fn update_focus_status(
+    message: proto::UpdateFocusStatus,
+    session: MessageContext,
+) -> Result<()> {
+    session
+        .connection_pool()
+        .await
+        .set_focused(session.connection_id, message.focused);
+    update_user_contacts(session.user_id(), &session).await
+}
+
 /// Call someone else into the current room
 async fn call(
     request: proto::Call,
@@ -2095,7 +2171,7 @@ async fn leave_project(request: proto::LeaveProject, session: MessageContext) ->
         "leave project"
     );
 
-    project_left(project, &session);
+    project_left(project, sender_id, &session);
     if let Some(room) = room {
         room_updated(room, &session.peer);
     }
@@ -3313,7 +3389,7 @@ async fn join_channel_internal(
                 "cleaning up stale connection",
             );
             drop(db);
-            leave_room_for_session(&session, connection).await?;
+            leave_room_for_session(&session, session.user_id(), connection).await?;
             db = session.db().await;
         }
 
@@ -3883,6 +3959,7 @@ fn contact_for_user(user_id: UserId, busy: bool, pool: &ConnectionPool) -> proto
         user_id: user_id.to_proto(),
         online: pool.is_user_online(user_id),
         busy,
+        focused: pool.is_user_focused(user_id),
     }
 }
 
@@ -3972,7 +4049,11 @@ async fn update_user_contacts(user_id: UserId, session: &Session) -> Result<()>
     Ok(())
 }
 
-async fn leave_room_for_session(session: &Session, connection_id: ConnectionId) -> Result<()> {
+async fn leave_room_for_session(
+    session: &Session,
+    user_id: UserId,
+    connection_id: ConnectionId,
+) -> Result<()> {
     let mut contacts_to_update = HashSet::default();
 
     let room_id;
@@ -3983,10 +4064,10 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
     let channel;
 
     if let Some(mut left_room) = session.db().await.leave_room(connection_id).await? {
-        contacts_to_update.insert(session.user_id());
+        contacts_to_update.insert(user_id);
 
         for project in left_room.left_projects.values() {
-            project_left(project, session);
+            project_left(project, connection_id, session);
         }
 
         room_id = RoomId::from_proto(left_room.room.id);
@@ -4034,7 +4115,7 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
 
     if let Some(live_kit) = session.app_state.livekit_client.as_ref() {
         live_kit
-            .remove_participant(livekit_room.clone(), session.user_id().to_string())
+            .remove_participant(livekit_room.clone(), user_id.to_string())
             .await
             .trace_err();
 
@@ -4068,13 +4149,13 @@ async fn leave_channel_buffers_for_session(session: &Session) -> Result<()> {
     Ok(())
 }
 
-fn project_left(project: &db::LeftProject, session: &Session) {
-    for connection_id in &project.connection_ids {
+fn project_left(project: &db::LeftProject, connection_id: ConnectionId, session: &Session) {
+    for recipient_id in &project.connection_ids {
         if project.should_unshare {
             session
                 .peer
                 .send(
-                    *connection_id,
+                    *recipient_id,
                     proto::UnshareProject {
                         project_id: project.id.to_proto(),
                     },
@@ -4084,10 +4165,10 @@ fn project_left(project: &db::LeftProject, session: &Session) {
             session
                 .peer
                 .send(
-                    *connection_id,
+                    *recipient_id,
                     proto::RemoveProjectCollaborator {
                         project_id: project.id.to_proto(),
-                        peer_id: Some(session.connection_id.into()),
+                        peer_id: Some(connection_id.into()),
                     },
                 )
                 .trace_err();
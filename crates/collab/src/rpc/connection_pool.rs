@@ -51,6 +51,8 @@ pub struct Connection {
     pub user_id: UserId,
     pub admin: bool,
     pub zed_version: ZedVersion,
+    /// Whether the user is in a focus session on this connection.
+    pub focused: bool,
 }
 
 impl ConnectionPool {
@@ -78,6 +80,7 @@ impl ConnectionPool {
                 user_id,
                 admin,
                 zed_version,
+                focused: false,
             },
         );
         let connected_user = self.connected_users.entry(user_id).or_default();
@@ -159,6 +162,18 @@ impl ConnectionPool {
         self.channels.unsubscribe(user_id, channel_id);
     }
 
+    pub fn set_focused(&mut self, connection_id: ConnectionId, focused: bool) {
+        if let Some(connection) = self.connections.get_mut(&connection_id) {
+            connection.focused = focused;
+        }
+    }
+
+    /// Whether the user is in a focus session on any of their connections.
+    pub fn is_user_focused(&self, user_id: UserId) -> bool {
+        self.user_connections(user_id)
+            .any(|connection| connection.focused)
+    }
+
     pub fn is_user_online(&self, user_id: UserId) -> bool {
         !self
             .connected_users
@@ -307,3 +307,106 @@ async fn test_channel_requires_zed_cla(cx_a: &mut TestAppContext, cx_b: &mut Tes
         assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
     });
 }
+
+#[gpui::test]
+async fn test_channel_participant_moderation(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    // C is another admin of the channel, B joins it as a guest.
+    let user_c = client_c.user_id().unwrap();
+    client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.invite_member(channel_id, user_c, proto::ChannelRole::Admin, cx)
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    client_c
+        .channel_store()
+        .update(cx_c, |channel_store, cx| {
+            channel_store.respond_to_channel_invite(channel_id, true, cx)
+        })
+        .await
+        .unwrap();
+
+    for (active_call, cx) in [
+        (&active_call_a, &mut *cx_a),
+        (&active_call_b, &mut *cx_b),
+        (&active_call_c, &mut *cx_c),
+    ] {
+        active_call
+            .update(cx, |call, cx| call.join_channel(channel_id, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+    }
+
+    // B can't mute or remove A, as they're not an admin.
+    let user_a = client_a.user_id().unwrap();
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    assert!(
+        room_b
+            .update(cx_b, |room, cx| room.mute_participant(user_a, cx))
+            .await
+            .is_err()
+    );
+    assert!(
+        room_b
+            .update(cx_b, |room, cx| room.remove_participant(user_a, cx))
+            .await
+            .is_err()
+    );
+
+    // Admins can't be moderated, not even by other admins.
+    let room_c = active_call_c.read_with(cx_c, |call, _| call.room().unwrap().clone());
+    assert!(
+        room_c
+            .update(cx_c, |room, cx| room.mute_participant(user_a, cx))
+            .await
+            .is_err()
+    );
+    assert!(
+        room_c
+            .update(cx_c, |room, cx| room.remove_participant(user_a, cx))
+            .await
+            .is_err()
+    );
+
+    // A mutes B, then removes them from the call.
+    let user_b = client_b.user_id().unwrap();
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    room_a
+        .update(cx_a, |room, cx| room.mute_participant(user_b, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    assert!(room_b.read_with(cx_b, |room, _| room.is_muted()));
+
+    room_a
+        .update(cx_a, |room, cx| room.remove_participant(user_b, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    assert!(active_call_b.read_with(cx_b, |call, _| call.room().is_none()));
+    room_a.read_with(cx_a, |room, _| {
+        assert!(!room.contains_participant(user_b));
+        assert!(room.contains_participant(user_c));
+        assert_eq!(room.remote_participants().len(), 1);
+    });
+}
@@ -6206,6 +6206,49 @@ async fn test_contacts(
     }
 }
 
+#[gpui::test]
+async fn test_contact_focus_status(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let is_a_focused = |cx: &mut TestAppContext| {
+        client_b.user_store().read_with(cx, |store, _| {
+            store
+                .contacts()
+                .iter()
+                .find(|contact| contact.user.github_login == "user_a")
+                .map(|contact| contact.focused)
+        })
+    };
+    assert_eq!(is_a_focused(cx_b), Some(false));
+
+    client_a
+        .user_store()
+        .update(cx_a, |store, _| store.set_focused(true));
+    executor.run_until_parked();
+    assert_eq!(is_a_focused(cx_b), Some(true));
+
+    // The focus status is sent again after reconnecting.
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+    assert_eq!(is_a_focused(cx_b), Some(true));
+
+    client_a
+        .user_store()
+        .update(cx_a, |store, _| store.set_focused(false));
+    executor.run_until_parked();
+    assert_eq!(is_a_focused(cx_b), Some(false));
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_contact_requests(
     executor: BackgroundExecutor,
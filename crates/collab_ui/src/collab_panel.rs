@@ -1199,7 +1199,47 @@ impl CollabPanel {
                 );
             }
 
-            context_menu
+            if role == proto::ChannelRole::Member {
+                context_menu = context_menu.entry(
+                    "Mute Microphone",
+                    None,
+                    window.handler_for(&this, move |_, window, cx| {
+                        ActiveCall::global(cx)
+                            .update(cx, |call, cx| {
+                                let Some(room) = call.room() else {
+                                    return Task::ready(Ok(()));
+                                };
+                                room.update(cx, |room, cx| room.mute_participant(user_id, cx))
+                            })
+                            .detach_and_prompt_err(
+                                "Failed to mute microphone",
+                                window,
+                                cx,
+                                |_, _, _| None,
+                            )
+                    }),
+                );
+            }
+
+            context_menu.separator().entry(
+                "Remove from Call",
+                None,
+                window.handler_for(&this, move |_, window, cx| {
+                    ActiveCall::global(cx)
+                        .update(cx, |call, cx| {
+                            let Some(room) = call.room() else {
+                                return Task::ready(Ok(()));
+                            };
+                            room.update(cx, |room, cx| room.remove_participant(user_id, cx))
+                        })
+                        .detach_and_prompt_err(
+                            "Failed to remove from call",
+                            window,
+                            cx,
+                            |_, _, _| None,
+                        )
+                }),
+            )
         });
 
         window.focus(&context_menu.focus_handle(cx));
@@ -2496,6 +2536,7 @@ impl CollabPanel {
     ) -> impl IntoElement {
         let online = contact.online;
         let busy = contact.busy || calling;
+        let focused = contact.focused;
         let github_login = contact.user.github_login.clone();
         let item = ListItem::new(github_login.clone())
             .indent_level(1)
@@ -2538,7 +2579,7 @@ impl CollabPanel {
                 // todo handle contacts with no avatar
                 Avatar::new(contact.user.avatar_uri.clone())
                     .indicator::<AvatarAvailabilityIndicator>(if online {
-                        Some(AvatarAvailabilityIndicator::new(match busy {
+                        Some(AvatarAvailabilityIndicator::new(match busy || focused {
                             true => ui::CollaboratorAvailability::Busy,
                             false => ui::CollaboratorAvailability::Free,
                         }))
@@ -2556,6 +2597,11 @@ impl CollabPanel {
                     format!(" {} is offline", &github_login)
                 } else if busy {
                     format!(" {} is on a call", &github_login)
+                } else if focused {
+                    format!(
+                        " {} is focusing, so calls are shown to them once they're done",
+                        &github_login
+                    )
                 } else {
                     let room = ActiveCall::global(cx).read(cx).room();
                     if room.is_some() {
@@ -1,5 +1,6 @@
 pub mod channel_view;
 pub mod collab_panel;
+pub mod focus_timer;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
@@ -11,7 +12,9 @@ use gpui::{
     App, Pixels, PlatformDisplay, Size, WindowBackgroundAppearance, WindowBounds,
     WindowDecorations, WindowKind, WindowOptions, point,
 };
-pub use panel_settings::{CollaborationPanelSettings, NotificationPanelSettings};
+pub use panel_settings::{
+    CollaborationPanelSettings, FocusTimerSettings, NotificationPanelSettings,
+};
 use release_channel::ReleaseChannel;
 use settings::Settings;
 use ui::px;
@@ -20,9 +23,11 @@ use workspace::AppState;
 pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
     CollaborationPanelSettings::register(cx);
     NotificationPanelSettings::register(cx);
+    FocusTimerSettings::register(cx);
 
     channel_view::init(cx);
     collab_panel::init(cx);
+    focus_timer::init(app_state, cx);
     notification_panel::init(cx);
     notifications::init(app_state, cx);
     title_bar::init(cx);
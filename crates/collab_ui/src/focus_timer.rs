@@ -0,0 +1,287 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use client::UserStore;
+use gpui::{
+    App, Context, Entity, EventEmitter, Global, IntoElement, Render, Subscription, Task,
+    WeakEntity, Window, actions,
+};
+use settings::Settings;
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    AppState, ItemHandle, StatusItemView, Toast, Workspace, notifications::NotificationId,
+};
+
+use crate::panel_settings::FocusTimerSettings;
+
+actions!(
+    focus_timer,
+    [
+        /// Starts a focus session, or ends the one that is running.
+        ToggleFocusSession
+    ]
+);
+
+pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
+    let focus_timer = cx.new(|_| FocusTimer::new(app_state.user_store.downgrade()));
+    cx.subscribe(&focus_timer, |_, event, cx| match event {
+        FocusTimerEvent::SessionEnded { completed: true } => show_session_completed_toast(cx),
+        FocusTimerEvent::SessionEnded { completed: false } => {}
+    })
+    .detach();
+    cx.set_global(GlobalFocusTimer(focus_timer));
+    cx.on_action(|_: &ToggleFocusSession, cx| {
+        FocusTimer::global(cx).update(cx, |focus_timer, cx| focus_timer.toggle(cx));
+    });
+}
+
+/// Shows the toast in a single workspace, rather than in every open window.
+fn show_session_completed_toast(cx: &mut App) {
+    let workspace = cx
+        .active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+        .or_else(|| workspace::local_workspace_windows(cx).into_iter().next());
+    if let Some(workspace) = workspace {
+        workspace
+            .update(cx, |workspace, _, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<FocusTimer>(),
+                        "Focus session complete",
+                    ),
+                    cx,
+                )
+            })
+            .log_err();
+    }
+}
+
+struct GlobalFocusTimer(Entity<FocusTimer>);
+
+impl Global for GlobalFocusTimer {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusTimerEvent {
+    /// The focus session ended, either because its time was up or because it was stopped early.
+    SessionEnded { completed: bool },
+}
+
+/// A running focus session, during which collaboration notifications can be held back
+/// and contacts see that the user is focusing.
+pub struct FocusTimer {
+    session: Option<FocusSession>,
+    user_store: WeakEntity<UserStore>,
+}
+
+struct FocusSession {
+    started_at: Instant,
+    duration: Duration,
+    _tick: Task<()>,
+}
+
+impl EventEmitter<FocusTimerEvent> for FocusTimer {}
+
+impl FocusTimer {
+    pub fn new(user_store: WeakEntity<UserStore>) -> Self {
+        Self {
+            session: None,
+            user_store,
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalFocusTimer>().0.clone()
+    }
+
+    /// Whether collaboration notifications should currently be held back.
+    pub fn suppresses_notifications(cx: &App) -> bool {
+        cx.try_global::<GlobalFocusTimer>()
+            .is_some_and(|focus_timer| focus_timer.0.read(cx).session.is_some())
+            && FocusTimerSettings::get_global(cx).suppress_notifications
+    }
+
+    pub fn remaining(&self, cx: &App) -> Option<Duration> {
+        let session = self.session.as_ref()?;
+        Some(
+            session.duration.saturating_sub(
+                cx.background_executor()
+                    .now()
+                    .saturating_duration_since(session.started_at),
+            ),
+        )
+    }
+
+    pub fn toggle(&mut self, cx: &mut Context<Self>) {
+        if self.session.is_some() {
+            self.stop(cx);
+        } else {
+            let duration = FocusTimerSettings::get_global(cx).duration;
+            self.start(duration, cx);
+        }
+    }
+
+    pub fn start(&mut self, duration: Duration, cx: &mut Context<Self>) {
+        let tick = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                let completed = this.update(cx, |this, cx| {
+                    let completed = this
+                        .remaining(cx)
+                        .is_none_or(|remaining| remaining.is_zero());
+                    if completed {
+                        this.end_session(true, cx);
+                    }
+                    cx.notify();
+                    completed
+                });
+                if completed.unwrap_or(true) {
+                    break;
+                }
+            }
+        });
+        self.session = Some(FocusSession {
+            started_at: cx.background_executor().now(),
+            duration,
+            _tick: tick,
+        });
+        self.set_focused(true, cx);
+        cx.notify();
+    }
+
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        self.end_session(false, cx);
+        cx.notify();
+    }
+
+    fn end_session(&mut self, completed: bool, cx: &mut Context<Self>) {
+        if self.session.take().is_some() {
+            self.set_focused(false, cx);
+            cx.emit(FocusTimerEvent::SessionEnded { completed });
+        }
+    }
+
+    fn set_focused(&self, focused: bool, cx: &mut Context<Self>) {
+        self.user_store
+            .update(cx, |user_store, _| user_store.set_focused(focused))
+            .ok();
+    }
+}
+
+/// Shows the time left in the current focus session in the status bar.
+pub struct FocusTimerIndicator {
+    focus_timer: Entity<FocusTimer>,
+    _subscription: Subscription,
+}
+
+impl FocusTimerIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let focus_timer = FocusTimer::global(cx);
+        Self {
+            _subscription: cx.observe(&focus_timer, |_, _, cx| cx.notify()),
+            focus_timer,
+        }
+    }
+}
+
+impl Render for FocusTimerIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let remaining = self.focus_timer.read(cx).remaining(cx);
+        if remaining.is_none() && !FocusTimerSettings::get_global(cx).button {
+            return div().into_any_element();
+        }
+
+        match remaining {
+            Some(remaining) => {
+                let seconds = remaining.as_secs();
+                Button::new(
+                    "focus-timer",
+                    format!("{}:{:02}", seconds / 60, seconds % 60),
+                )
+                .icon(IconName::CountdownTimer)
+                .icon_position(IconPosition::Start)
+                .icon_size(IconSize::Small)
+                .icon_color(Color::Accent)
+                .label_size(LabelSize::Small)
+                .tooltip(|window, cx| {
+                    Tooltip::for_action("End Focus Session", &ToggleFocusSession, window, cx)
+                })
+                .on_click(|_, window, cx| window.dispatch_action(Box::new(ToggleFocusSession), cx))
+                .into_any_element()
+            }
+            None => IconButton::new("focus-timer", IconName::CountdownTimer)
+                .icon_size(IconSize::Small)
+                .icon_color(Color::Muted)
+                .tooltip(|window, cx| {
+                    Tooltip::for_action("Start Focus Session", &ToggleFocusSession, window, cx)
+                })
+                .on_click(|_, window, cx| window.dispatch_action(Box::new(ToggleFocusSession), cx))
+                .into_any_element(),
+        }
+    }
+}
+
+impl StatusItemView for FocusTimerIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_focus_session_ends_once(cx: &mut TestAppContext) {
+        let focus_timer = cx.new(|_| FocusTimer::new(WeakEntity::new_invalid()));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&focus_timer, move |_, event, _| {
+                events.borrow_mut().push(*event)
+            })
+            .detach();
+        });
+
+        focus_timer.update(cx, |focus_timer, cx| {
+            focus_timer.start(Duration::from_secs(5 * 60), cx)
+        });
+        cx.executor().advance_clock(Duration::from_secs(60));
+        cx.run_until_parked();
+        focus_timer.read_with(cx, |focus_timer, cx| {
+            assert_eq!(focus_timer.remaining(cx), Some(Duration::from_secs(4 * 60)))
+        });
+        assert!(events.borrow_mut().is_empty());
+
+        cx.executor().advance_clock(Duration::from_secs(5 * 60));
+        cx.run_until_parked();
+        focus_timer.read_with(cx, |focus_timer, cx| {
+            assert_eq!(focus_timer.remaining(cx), None)
+        });
+        assert_eq!(
+            events.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [FocusTimerEvent::SessionEnded { completed: true }]
+        );
+
+        // Stopping a session early ends it without completing it.
+        focus_timer.update(cx, |focus_timer, cx| {
+            focus_timer.start(Duration::from_secs(5 * 60), cx);
+            focus_timer.stop(cx);
+            focus_timer.stop(cx);
+        });
+        cx.executor().advance_clock(Duration::from_secs(10 * 60));
+        cx.run_until_parked();
+        assert_eq!(
+            events.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [FocusTimerEvent::SessionEnded { completed: false }]
+        );
+    }
+}
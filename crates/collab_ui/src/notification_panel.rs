@@ -1,4 +1,5 @@
 use crate::NotificationPanelSettings;
+use crate::focus_timer::FocusTimer;
 use anyhow::Result;
 use channel::ChannelStore;
 use client::{ChannelId, Client, Notification, User, UserStore};
@@ -453,6 +454,9 @@ impl NotificationPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if FocusTimer::suppresses_notifications(cx) {
+            return;
+        }
         let Some(NotificationPresenter { actor, text, .. }) = self.present_notification(entry, cx)
         else {
             return;
@@ -1,7 +1,8 @@
+use crate::focus_timer::{FocusTimer, FocusTimerEvent};
 use crate::notification_window_options;
 use crate::notifications::collab_notification::CollabNotification;
 use call::{ActiveCall, IncomingCall};
-use futures::StreamExt;
+use futures::{FutureExt as _, StreamExt, channel::mpsc};
 use gpui::{App, WindowHandle, prelude::*};
 
 use std::sync::{Arc, Weak};
@@ -11,10 +12,33 @@ use workspace::AppState;
 
 pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
     let app_state = Arc::downgrade(app_state);
-    let mut incoming_call = ActiveCall::global(cx).read(cx).incoming();
+    let mut incoming_calls = ActiveCall::global(cx).read(cx).incoming();
+    let (focus_session_ended_tx, mut focus_session_ended_rx) = mpsc::unbounded();
+    cx.subscribe(&FocusTimer::global(cx), move |_, event, _| match event {
+        FocusTimerEvent::SessionEnded { .. } => {
+            focus_session_ended_tx.unbounded_send(()).ok();
+        }
+    })
+    .detach();
     cx.spawn(async move |cx| {
         let mut notification_windows: Vec<WindowHandle<IncomingCallNotification>> = Vec::new();
-        while let Some(incoming_call) = incoming_call.next().await {
+        loop {
+            let incoming_call = futures::select_biased! {
+                incoming_call = incoming_calls.next().fuse() => {
+                    let Some(incoming_call) = incoming_call else {
+                        break;
+                    };
+                    incoming_call
+                }
+                _ = focus_session_ended_rx.next().fuse() => {
+                    // A call that came in during the focus session was held back until now.
+                    if !notification_windows.is_empty() {
+                        continue;
+                    }
+                    incoming_calls.borrow().clone()
+                }
+            };
+
             for window in notification_windows.drain(..) {
                 window
                     .update(cx, |_, window, _| {
@@ -23,7 +47,11 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
                     .log_err();
             }
 
-            if let Some(incoming_call) = incoming_call {
+            if let Some(incoming_call) = incoming_call
+                && !cx
+                    .update(|cx| FocusTimer::suppresses_notifications(cx))
+                    .unwrap_or(false)
+            {
                 let unique_screens = cx.update(|cx| cx.displays()).unwrap();
                 let window_size = gpui::Size {
                     width: px(400.),
@@ -1,3 +1,4 @@
+use crate::focus_timer::FocusTimer;
 use crate::notification_window_options;
 use crate::notifications::collab_notification::CollabNotification;
 use call::{ActiveCall, room};
@@ -20,6 +21,10 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut App) {
             project_id,
             worktree_root_names,
         } => {
+            if FocusTimer::suppresses_notifications(cx) {
+                return;
+            }
+
             let window_size = Size {
                 width: px(400.),
                 height: px(72.),
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use gpui::Pixels;
 use settings::Settings;
 use ui::px;
@@ -17,6 +19,13 @@ pub struct NotificationPanelSettings {
     pub default_width: Pixels,
 }
 
+#[derive(Debug)]
+pub struct FocusTimerSettings {
+    pub button: bool,
+    pub duration: Duration,
+    pub suppress_notifications: bool,
+}
+
 impl Settings for CollaborationPanelSettings {
     fn from_settings(content: &settings::SettingsContent, _cx: &mut ui::App) -> Self {
         let panel = content.collaboration_panel.as_ref().unwrap();
@@ -39,3 +48,38 @@ impl Settings for NotificationPanelSettings {
         };
     }
 }
+
+impl Settings for FocusTimerSettings {
+    fn from_settings(content: &settings::SettingsContent, _cx: &mut ui::App) -> Self {
+        let focus_timer = content.focus_timer.as_ref().unwrap();
+        Self {
+            button: focus_timer.button.unwrap(),
+            duration: focus_session_duration(focus_timer.duration_minutes.unwrap()),
+            suppress_notifications: focus_timer.suppress_notifications.unwrap(),
+        }
+    }
+}
+
+/// The longest focus session, so that a mistyped duration can't hold back notifications
+/// indefinitely.
+const MAX_FOCUS_SESSION_MINUTES: u64 = 24 * 60;
+
+/// Focus sessions last at least a minute, as shorter ones would end right away.
+fn focus_session_duration(minutes: u64) -> Duration {
+    Duration::from_secs(minutes.clamp(1, MAX_FOCUS_SESSION_MINUTES) * 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_session_duration() {
+        assert_eq!(focus_session_duration(25), Duration::from_secs(25 * 60));
+        assert_eq!(focus_session_duration(0), Duration::from_secs(60));
+        assert_eq!(
+            focus_session_duration(u64::MAX),
+            Duration::from_secs(MAX_FOCUS_SESSION_MINUTES * 60)
+        );
+    }
+}
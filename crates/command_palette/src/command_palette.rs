@@ -390,6 +390,11 @@ impl PickerDelegate for CommandPaletteDelegate {
             source = "command palette",
             action = command.name
         );
+        usage_stats::record_action(
+            command.action.name(),
+            usage_stats::ActionSource::CommandPalette,
+            cx,
+        );
         self.matches.clear();
         self.commands.clear();
         let command_name = command.name.clone();
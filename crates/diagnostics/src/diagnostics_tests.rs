@@ -1271,6 +1271,100 @@ async fn test_diagnostics_with_links(cx: &mut TestAppContext) {
     cx.update_editor(|editor, _, _| assert!(editor.hover_state.diagnostic_popover.is_some()))
 }
 
+#[gpui::test]
+async fn test_hover_overlapping_diagnostics(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        fn func(abˇc def: i32) -> u32 {
+        }
+    "});
+    let lsp_store =
+        cx.update_editor(|editor, _, cx| editor.project().unwrap().read(cx).lsp_store());
+
+    cx.update(|_, cx| {
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.update_diagnostics(
+                LanguageServerId(0),
+                lsp::PublishDiagnosticsParams {
+                    uri: lsp::Uri::from_file_path(path!("/root/file")).unwrap(),
+                    version: None,
+                    diagnostics: vec![
+                        lsp::Diagnostic {
+                            range: lsp::Range::new(
+                                lsp::Position::new(0, 8),
+                                lsp::Position::new(0, 16),
+                            ),
+                            severity: Some(lsp::DiagnosticSeverity::WARNING),
+                            message: "unused parameter".to_string(),
+                            ..Default::default()
+                        },
+                        lsp::Diagnostic {
+                            range: lsp::Range::new(
+                                lsp::Position::new(0, 8),
+                                lsp::Position::new(0, 11),
+                            ),
+                            severity: Some(lsp::DiagnosticSeverity::ERROR),
+                            message: "expected `,`".to_string(),
+                            related_information: Some(vec![lsp::DiagnosticRelatedInformation {
+                                location: lsp::Location {
+                                    uri: lsp::Uri::from_file_path(path!("/root/other.rs")).unwrap(),
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(4, 0),
+                                        lsp::Position::new(4, 3),
+                                    ),
+                                },
+                                message: "declared here".to_string(),
+                            }]),
+                            ..Default::default()
+                        },
+                    ],
+                },
+                None,
+                DiagnosticSourceKind::Pushed,
+                &[],
+                cx,
+            )
+        })
+    })
+    .unwrap();
+    cx.run_until_parked();
+    cx.update_editor(|editor, window, cx| {
+        editor::hover_popover::hover(editor, &Default::default(), window, cx)
+    });
+    cx.run_until_parked();
+
+    cx.update_editor(|editor, _, _| {
+        let diagnostic_popover = editor.hover_state.diagnostic_popover.as_mut().unwrap();
+        let diagnostic = &diagnostic_popover.local_diagnostic().diagnostic;
+        assert_eq!(diagnostic.message, "expected `,`");
+        let markdown = diagnostic.markdown.as_deref().unwrap();
+        assert!(
+            markdown.contains(r"[other\.rs:5]("),
+            "related information should link to other.rs: {markdown}"
+        );
+        assert!(markdown.ends_with("#L5): declared here"), "{markdown}");
+
+        diagnostic_popover.cycle(false);
+        assert_eq!(
+            diagnostic_popover.local_diagnostic().diagnostic.message,
+            "unused parameter"
+        );
+        diagnostic_popover.cycle(false);
+        assert_eq!(
+            diagnostic_popover.local_diagnostic().diagnostic.message,
+            "expected `,`"
+        );
+        diagnostic_popover.cycle(true);
+        assert_eq!(
+            diagnostic_popover.local_diagnostic().diagnostic.message,
+            "unused parameter"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_hover_diagnostic_and_info_popovers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -54,6 +54,17 @@ pub struct DeleteToBeginningOfLine {
     pub(super) stop_at_indent: bool,
 }
 
+/// Copies an image of the selected lines, or of the cursor's line when nothing is selected,
+/// rendered with the current theme and buffer font in a window frame, to the clipboard.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct CopySelectionAsImage {
+    /// Whether to show line numbers. Defaults to the `gutter.line_numbers` setting.
+    #[serde(default)]
+    pub line_numbers: Option<bool>,
+}
+
 /// Moves the cursor up by one page.
 #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
 #[action(namespace = editor)]
@@ -253,6 +264,34 @@ pub struct DeleteToPreviousWordStart {
     pub ignore_brackets: bool,
 }
 
+/// Deletes from the cursor to the end of the next subword, stopping at `camelCase` humps and
+/// underscores within words.
+/// Stops before the end of the next subword, if whitespace sequences of length >= 2 are encountered.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteToNextSubwordEnd {
+    #[serde(default)]
+    pub ignore_newlines: bool,
+    // Whether to stop before the end of the next subword, if language-defined bracket is encountered.
+    #[serde(default)]
+    pub ignore_brackets: bool,
+}
+
+/// Deletes from the cursor to the start of the previous subword, stopping at `camelCase` humps and
+/// underscores within words.
+/// Stops before the start of the previous subword, if whitespace sequences of length >= 2 are encountered.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteToPreviousSubwordStart {
+    #[serde(default)]
+    pub ignore_newlines: bool,
+    // Whether to stop before the start of the previous subword, if language-defined bracket is encountered.
+    #[serde(default)]
+    pub ignore_brackets: bool,
+}
+
 /// Cuts from cursor to end of line.
 #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
 #[action(namespace = editor)]
@@ -300,6 +339,20 @@ pub struct SplitSelectionIntoLines {
     pub keep_selections: bool,
 }
 
+/// Pastes from clipboard, adjusting the pasted text to the code around the cursor.
+///
+/// The indentation shared by the pasted lines is replaced by that of the cursor's line, and
+/// line endings are normalized. Outside of strings and comments, string literals are rewritten
+/// to use the language's default quote.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct SmartPaste {
+    /// Whether to rewrite the quotes of pasted string literals.
+    #[serde(default = "default_true")]
+    pub rewrite_quotes: bool,
+}
+
 /// Goes to the next diagnostic in the file.
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
 #[action(namespace = editor)]
@@ -413,6 +466,8 @@ actions!(
         CopyFileLocation,
         /// Copies the highlighted text as JSON.
         CopyHighlightJson,
+        /// Copies the original text of the diff hunks intersecting the selections to the clipboard.
+        CopyHunkOriginalText,
         /// Copies the current file name to the clipboard.
         CopyFileName,
         /// Copies the file name without extension to the clipboard.
@@ -427,10 +482,6 @@ actions!(
         DeleteLine,
         /// Deletes from cursor to end of line.
         DeleteToEndOfLine,
-        /// Deletes to the end of the next subword.
-        DeleteToNextSubwordEnd,
-        /// Deletes to the start of the previous subword.
-        DeleteToPreviousSubwordStart,
         /// Diffs the text stored in the clipboard against the current selection.
         DiffClipboardWithSelection,
         /// Displays names of all active cursors.
@@ -441,11 +492,15 @@ actions!(
         DuplicateLineUp,
         /// Duplicates the current selection.
         DuplicateSelection,
+        /// Opens a color picker for the color literal at the cursor.
+        EditColor,
         /// Expands all diff hunks in the editor.
         #[action(deprecated_aliases = ["editor::ExpandAllHunkDiffs"])]
         ExpandAllDiffHunks,
         /// Expands macros recursively at cursor position.
         ExpandMacroRecursively,
+        /// Expands excerpts to the function enclosing their match.
+        ExpandExcerptsToFunction,
         /// Finds all references to the symbol at cursor.
         FindAllReferences,
         /// Finds the next match in the search.
@@ -456,8 +511,14 @@ actions!(
         Fold,
         /// Folds all foldable regions in the editor.
         FoldAll,
+        /// Folds the bodies of all classes, structs, traits and the like in the editor.
+        FoldClassBodies,
+        /// Folds all comments in the editor, keeping their first line visible.
+        FoldComments,
         /// Folds all function bodies in the editor.
         FoldFunctionBodies,
+        /// Folds all groups of imports in the editor, keeping their first line visible.
+        FoldImports,
         /// Folds the current code block and all its children.
         FoldRecursive,
         /// Folds the selected ranges.
@@ -482,6 +543,9 @@ actions!(
         GoToDefinition,
         /// Goes to definition in a split pane.
         GoToDefinitionSplit,
+        /// Opens the source of the dependency imported at cursor, such as a crate in the
+        /// Cargo registry or a package in `node_modules`.
+        GoToDependencySource,
         /// Goes to the next diff hunk.
         GoToHunk,
         /// Goes to the previous diff hunk.
@@ -492,10 +556,26 @@ actions!(
         GoToImplementationSplit,
         /// Goes to the next change in the file.
         GoToNextChange,
+        /// Goes to the start of the next class, struct, etc.
+        GoToNextClass,
+        /// Goes to the start of the next function, method, etc.
+        GoToNextFunction,
+        /// Goes to the start of the next sibling syntax node.
+        GoToNextSiblingNode,
         /// Goes to the parent module of the current file.
         GoToParentModule,
+        /// Goes to the end of the enclosing syntax node.
+        GoToParentNodeEnd,
+        /// Goes to the start of the enclosing syntax node.
+        GoToParentNodeStart,
         /// Goes to the previous change in the file.
         GoToPreviousChange,
+        /// Goes to the start of the previous class, struct, etc.
+        GoToPreviousClass,
+        /// Goes to the start of the previous function, method, etc.
+        GoToPreviousFunction,
+        /// Goes to the start of the previous sibling syntax node.
+        GoToPreviousSiblingNode,
         /// Goes to the type definition of the symbol at cursor.
         GoToTypeDefinition,
         /// Goes to type definition in a split pane.
@@ -536,6 +616,12 @@ actions!(
         MoveLineUp,
         /// Moves cursor right.
         MoveRight,
+        /// Swaps the syntax node under the cursor, such as a statement or a list element,
+        /// with the next one.
+        MoveSyntaxNodeDown,
+        /// Swaps the syntax node under the cursor, such as a statement or a list element,
+        /// with the previous one.
+        MoveSyntaxNodeUp,
         /// Moves cursor to the beginning of the document.
         MoveToBeginning,
         /// Moves cursor to the enclosing bracket.
@@ -605,12 +691,17 @@ actions!(
         PageUp,
         /// Pastes from clipboard.
         Paste,
+        /// Pastes from clipboard without adjusting the indentation of the pasted text.
+        PasteWithoutReindent,
         /// Navigates to the previous edit prediction.
         PreviousEditPrediction,
         /// Redoes the last undone edit.
         Redo,
         /// Redoes the last selection change.
         RedoSelection,
+        /// Removes bidirectional controls, zero-width spaces and byte order marks from the
+        /// selections, or from the whole buffer when nothing is selected.
+        RemoveInvisibleCharacters,
         /// Renames the symbol at cursor.
         Rename,
         /// Restarts the language server for the current file.
@@ -705,6 +796,10 @@ actions!(
         SortLinesCaseInsensitive,
         /// Sorts selected lines case-sensitively.
         SortLinesCaseSensitive,
+        /// Sorts selected lines case-sensitively, in descending order.
+        SortLinesDescending,
+        /// Sorts selected lines so that numbers within them are ordered by value.
+        SortLinesNatural,
         /// Stops the language server for the current file.
         StopLanguageServer,
         /// Switches between source and header files.
@@ -715,6 +810,14 @@ actions!(
         Backtab,
         /// Toggles a breakpoint at the current line.
         ToggleBreakpoint,
+        /// Toggles a bookmark at the current line.
+        ToggleBookmark,
+        /// Moves the cursor to the next bookmark in the editor.
+        GoToNextBookmark,
+        /// Moves the cursor to the previous bookmark in the editor.
+        GoToPreviousBookmark,
+        /// Labels the bookmark at the current line, adding one if needed.
+        LabelBookmark,
         /// Toggles the case of selected text.
         ToggleCase,
         /// Disables the breakpoint at the current line.
@@ -735,6 +838,10 @@ actions!(
         ToggleIndentGuides,
         /// Toggles inlay hints display.
         ToggleInlayHints,
+        /// Toggles the display of type inlay hints.
+        ToggleTypeInlayHints,
+        /// Toggles the display of parameter inlay hints.
+        ToggleParameterInlayHints,
         /// Toggles inline values display.
         ToggleInlineValues,
         /// Toggles inline diagnostics display.
@@ -754,6 +861,11 @@ actions!(
         /// Toggles diff display for selected hunks.
         #[action(deprecated_aliases = ["editor::ToggleHunkDiff"])]
         ToggleSelectedDiffHunks,
+        /// Toggles reading mode for the language of the current buffer.
+        ToggleReadingMode,
+        /// Opens the file related to the current one, such as its header or its tests,
+        /// creating it if it doesn't exist.
+        ToggleRelatedFile,
         /// Toggles the selection menu.
         ToggleSelectionMenu,
         /// Toggles soft wrap mode.
@@ -764,6 +876,9 @@ actions!(
         Transpose,
         /// Undoes the last edit.
         Undo,
+        /// Reverts the changes most recently applied from a proposed changes
+        /// editor, across all of the buffers they touched.
+        UndoApplyAllDiffHunks,
         /// Undoes the last selection change.
         UndoSelection,
         /// Unfolds all folded regions.
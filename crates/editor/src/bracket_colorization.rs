@@ -0,0 +1,215 @@
+use std::{cmp::Reverse, ops::Range};
+
+use collections::HashMap;
+use gpui::{Context, HighlightStyle};
+use language::{BracketMatch, BufferSnapshot};
+use multi_buffer::{Anchor, ToPoint as _};
+use settings::Settings as _;
+use text::{Bias, Point, ToOffset as _};
+use theme::ActiveTheme as _;
+
+use crate::{ColorizeBrackets, Editor, EditorSettings};
+
+enum ColorizedBracketHighlight {}
+
+/// Colors the visible brackets by their nesting depth, which is derived from the pairs matched by
+/// the languages' bracket queries rather than from the characters themselves, so that brackets in
+/// strings and comments don't throw the colors off.
+pub fn refresh_bracket_colors(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let mode = EditorSettings::get_global(cx).colorize_brackets;
+    if mode == ColorizeBrackets::Off || !editor.mode().is_full() {
+        editor.clear_keyed_highlights::<ColorizedBracketHighlight>(cx);
+        return;
+    }
+
+    let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+    let visible_start = editor
+        .scroll_manager
+        .anchor()
+        .anchor
+        .to_point(&multi_buffer_snapshot);
+    let visible_end = multi_buffer_snapshot.clip_point(
+        visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+        Bias::Left,
+    );
+    let cursor = editor.selections.newest_anchor().head();
+
+    let accents = cx.theme().accents().clone();
+    let mut ranges_by_color = HashMap::<usize, Vec<Range<Anchor>>>::default();
+    for (buffer, buffer_range, excerpt_id) in
+        multi_buffer_snapshot.range_to_buffer_ranges(visible_start..visible_end)
+    {
+        let mut pairs = bracket_pairs_with_depth(buffer, buffer_range.clone());
+        if mode == ColorizeBrackets::Enclosing {
+            let cursor_offset =
+                (cursor.excerpt_id == excerpt_id).then(|| cursor.text_anchor.to_offset(buffer));
+            pairs = cursor_offset
+                .and_then(|cursor_offset| {
+                    pairs
+                        .into_iter()
+                        .filter(|(pair, _)| {
+                            pair.open_range.end <= cursor_offset
+                                && cursor_offset <= pair.close_range.start
+                        })
+                        .min_by_key(|(pair, _)| pair.close_range.end - pair.open_range.start)
+                })
+                .into_iter()
+                .collect();
+        }
+
+        for (pair, depth) in pairs {
+            for bracket_range in [pair.open_range, pair.close_range] {
+                if bracket_range.end < buffer_range.start || bracket_range.start > buffer_range.end
+                {
+                    continue;
+                }
+                let (Some(start), Some(end)) = (
+                    multi_buffer_snapshot
+                        .anchor_in_excerpt(excerpt_id, buffer.anchor_after(bracket_range.start)),
+                    multi_buffer_snapshot
+                        .anchor_in_excerpt(excerpt_id, buffer.anchor_before(bracket_range.end)),
+                ) else {
+                    continue;
+                };
+                ranges_by_color
+                    .entry(depth % accents.0.len())
+                    .or_default()
+                    .push(start..end);
+            }
+        }
+    }
+
+    editor.clear_keyed_highlights::<ColorizedBracketHighlight>(cx);
+    for (color_ix, ranges) in ranges_by_color {
+        editor.highlight_text_key::<ColorizedBracketHighlight>(
+            color_ix,
+            ranges,
+            HighlightStyle {
+                color: Some(accents.color_for_index(color_ix as u32)),
+                ..HighlightStyle::default()
+            },
+            cx,
+        );
+    }
+}
+
+/// Returns the bracket pairs around `range` that should be colored, along with the number of
+/// such pairs enclosing each of them.
+fn bracket_pairs_with_depth(
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+) -> Vec<(BracketMatch, usize)> {
+    let mut pairs = buffer
+        .bracket_ranges(range)
+        .filter(|pair| !pair.rainbow_exclude)
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|pair| (pair.open_range.start, Reverse(pair.close_range.end)));
+    pairs.dedup_by(|a, b| a.open_range == b.open_range && a.close_range == b.close_range);
+
+    let mut enclosing_ends = Vec::new();
+    pairs
+        .into_iter()
+        .map(|pair| {
+            while enclosing_ends
+                .last()
+                .is_some_and(|end| *end <= pair.open_range.start)
+            {
+                enclosing_ends.pop();
+            }
+            let depth = enclosing_ends.len();
+            enclosing_ends.push(pair.close_range.end);
+            (pair, depth)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_bracket_colorization(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let language = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_brackets_query(indoc! {r#"
+            ("(" @open ")" @close)
+            ("[" @open "]" @close)
+            ("{" @open "}" @close)
+            (("\"" @open "\"" @close) (#set! rainbow.exclude))
+        "#})
+        .unwrap();
+        let mut cx = EditorLspTestContext::new(language, Default::default(), cx).await;
+        cx.update_editor(|editor, window, cx| editor.set_visible_line_count(10., window, cx));
+        update_colorize_brackets(ColorizeBrackets::All, &mut cx);
+
+        cx.set_state(indoc! {r#"
+            fn main() { let a = [b("(", [1]ˇ)]; }
+        "#});
+        cx.run_until_parked();
+        assert_eq!(colorized_brackets(&mut cx), "(0 )0 {0 [1 (2 [3 ]3 )2 ]1 }0");
+
+        update_colorize_brackets(ColorizeBrackets::Enclosing, &mut cx);
+        cx.run_until_parked();
+        assert_eq!(colorized_brackets(&mut cx), "(2 )2");
+
+        update_colorize_brackets(ColorizeBrackets::Off, &mut cx);
+        cx.run_until_parked();
+        assert_eq!(colorized_brackets(&mut cx), "");
+    }
+
+    fn update_colorize_brackets(mode: ColorizeBrackets, cx: &mut EditorLspTestContext) {
+        cx.update(|_, cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.editor.colorize_brackets = Some(mode);
+                });
+            });
+        });
+    }
+
+    /// Returns the colored brackets in the order they appear in, each followed by the index of
+    /// its color in the theme's accents.
+    fn colorized_brackets(cx: &mut EditorLspTestContext) -> String {
+        cx.update_editor(|editor, window, cx| {
+            let accents = cx.theme().accents().clone();
+            let snapshot = editor.snapshot(window, cx);
+            let text = snapshot.buffer_snapshot.text();
+            let mut brackets = Vec::new();
+            for (style, ranges) in editor.all_text_highlights(window, cx) {
+                let Some(color_ix) = style
+                    .color
+                    .and_then(|color| accents.0.iter().position(|accent| *accent == color))
+                else {
+                    continue;
+                };
+                for range in ranges {
+                    let range = range.start.to_offset(&snapshot, Bias::Left)
+                        ..range.end.to_offset(&snapshot, Bias::Right);
+                    brackets.push((range.start, format!("{}{color_ix}", &text[range])));
+                }
+            }
+            brackets.sort();
+            brackets
+                .into_iter()
+                .map(|(_, bracket)| bracket)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use gpui::{App, ClipboardEntry, ClipboardItem, Global};
+
+use crate::{ClipboardSelection, Editor};
+
+/// How many copies are remembered before the oldest ones are forgotten.
+const MAX_ENTRIES: usize = 50;
+
+/// Text that was copied or cut from an editor.
+#[derive(Clone, Debug)]
+pub struct ClipboardHistoryEntry {
+    pub text: String,
+    /// The segments of `text` that were copied from each selection, so that they can be pasted
+    /// into as many cursors again.
+    pub selections: Option<Vec<ClipboardSelection>>,
+    /// The path of the file that the newest selection was copied from.
+    pub source_path: Option<PathBuf>,
+}
+
+/// The text recently copied or cut from any editor, shared by all of them.
+#[derive(Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardHistoryEntry>,
+}
+
+impl Global for ClipboardHistory {}
+
+impl ClipboardHistory {
+    /// Returns the remembered copies, starting with the most recent one.
+    pub fn entries(&self) -> impl Iterator<Item = &ClipboardHistoryEntry> {
+        self.entries.iter()
+    }
+
+    fn push(&mut self, entry: ClipboardHistoryEntry) {
+        self.entries.retain(|existing| existing.text != entry.text);
+        self.entries.push_front(entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+/// Remembers the text of `item`, which was copied from `editor`'s selections.
+pub(crate) fn record_clipboard_history(editor: &Editor, item: &ClipboardItem, cx: &mut App) {
+    let Some(ClipboardEntry::String(string)) = item.entries().first() else {
+        return;
+    };
+    if string.text().is_empty() {
+        return;
+    }
+
+    let newest_head = editor.selections.newest_anchor().head();
+    let source_path = editor
+        .buffer()
+        .read(cx)
+        .buffer_for_anchor(newest_head, cx)
+        .and_then(|buffer| Some(buffer.read(cx).file()?.full_path(cx)));
+    let entry = ClipboardHistoryEntry {
+        text: string.text().clone(),
+        selections: string.metadata_json(),
+        source_path,
+    };
+    cx.default_global::<ClipboardHistory>().push(entry);
+}
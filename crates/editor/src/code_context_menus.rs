@@ -30,6 +30,7 @@ use std::{
 use task::ResolvedTask;
 use ui::{Color, IntoElement, ListItem, Pixels, Popover, Styled, prelude::*};
 use util::ResultExt;
+use util::markdown::MarkdownCodeBlock;
 
 use crate::CodeActionSource;
 use crate::hover_popover::{hover_markdown_style, open_markdown_url};
@@ -619,13 +620,9 @@ impl CompletionsMenu {
         }
         let candidate_id = entries[index].candidate_id;
         let completions = self.completions.borrow();
-        match &completions[candidate_id].documentation {
-            Some(CompletionDocumentation::MultiLineMarkdown(source)) if !source.is_empty() => self
-                .get_or_create_markdown(candidate_id, Some(source), false, &completions, cx)
-                .map(|(_, markdown)| markdown),
-            Some(_) => None,
-            _ => None,
-        }
+        let source = aside_markdown_source(&completions[candidate_id])?;
+        self.get_or_create_markdown(candidate_id, Some(&source), false, &completions, cx)
+            .map(|(_, markdown)| markdown)
     }
 
     fn get_or_create_markdown(
@@ -917,16 +914,32 @@ impl CompletionsMenu {
 
         let mat = &self.entries.borrow()[self.selected_item];
         let completions = self.completions.borrow_mut();
-        let multiline_docs = match completions[mat.candidate_id].documentation.as_ref() {
-            Some(CompletionDocumentation::MultiLinePlainText(text)) => div().child(text.clone()),
-            Some(CompletionDocumentation::SingleLineAndMultiLinePlainText {
+        let completion = &completions[mat.candidate_id];
+        let multiline_docs = match completion.documentation.as_ref() {
+            Some(CompletionDocumentation::MultiLinePlainText(text))
+            | Some(CompletionDocumentation::SingleLineAndMultiLinePlainText {
                 plain_text: Some(text),
                 ..
-            }) => div().child(text.clone()),
-            Some(CompletionDocumentation::MultiLineMarkdown(source)) if !source.is_empty() => {
+            }) => div()
+                .children(
+                    completion_detail(completion)
+                        .map(|detail| Label::new(detail).buffer_font(cx).mb_1()),
+                )
+                .child(text.clone()),
+            documentation => {
+                let source = aside_markdown_source(completion);
+                if source.is_none() && documentation.is_some() {
+                    return None;
+                }
+                // When there is no source, documentation hasn't yet been resolved but there may be
+                // a `new_text` match in the cache.
+                //
+                // TODO: It's inconsistent that documentation caching based on matching `new_text`
+                // only works for markdown. Consider generally caching the results of resolving
+                // completions.
                 let Some((false, markdown)) = self.get_or_create_markdown(
                     mat.candidate_id,
-                    Some(source),
+                    source.as_ref(),
                     true,
                     &completions,
                     cx,
@@ -935,29 +948,6 @@ impl CompletionsMenu {
                 };
                 Self::render_markdown(markdown, window, cx)
             }
-            None => {
-                // Handle the case where documentation hasn't yet been resolved but there's a
-                // `new_text` match in the cache.
-                //
-                // TODO: It's inconsistent that documentation caching based on matching `new_text`
-                // only works for markdown. Consider generally caching the results of resolving
-                // completions.
-                let Some((false, markdown)) =
-                    self.get_or_create_markdown(mat.candidate_id, None, true, &completions, cx)
-                else {
-                    return None;
-                };
-                Self::render_markdown(markdown, window, cx)
-            }
-            Some(CompletionDocumentation::MultiLineMarkdown(_)) => return None,
-            Some(CompletionDocumentation::SingleLine(_)) => return None,
-            Some(CompletionDocumentation::Undocumented) => return None,
-            Some(CompletionDocumentation::SingleLineAndMultiLinePlainText {
-                plain_text: None,
-                ..
-            }) => {
-                return None;
-            }
         };
 
         Some(
@@ -1013,6 +1003,7 @@ impl CompletionsMenu {
                                 this.set_filter_results(matches, provider, window, cx);
                             }
                         });
+                        editor.confirm_single_completion_match(window, cx);
                     })
                     .ok();
             });
@@ -1218,15 +1209,15 @@ impl CompletionsMenu {
                 MarkdownCacheKey::ForCompletionMatch { .. } => true,
                 MarkdownCacheKey::ForCandidate { candidate_id } => {
                     if let Some(completion) = prev_completions.get(*candidate_id) {
-                        match &completion.documentation {
-                            Some(CompletionDocumentation::MultiLineMarkdown(source)) => {
+                        match aside_markdown_source(completion) {
+                            Some(source) => {
                                 *key = MarkdownCacheKey::ForCompletionMatch {
                                     new_text: completion.new_text.clone(),
-                                    markdown_source: source.clone(),
+                                    markdown_source: source,
                                 };
                                 true
                             }
-                            _ => false,
+                            None => false,
                         }
                     } else {
                         false
@@ -1253,6 +1244,39 @@ impl CompletionsMenu {
     }
 }
 
+/// The detail of an LSP completion, such as a function's signature, unless the
+/// label already shows it. Some servers only provide it once the item is resolved.
+fn completion_detail(completion: &Completion) -> Option<String> {
+    let lsp_completion = completion.source.lsp_completion(false)?;
+    let detail = lsp_completion.detail.as_deref()?.trim();
+    (!detail.is_empty() && !completion.label.text.contains(detail)).then(|| detail.to_string())
+}
+
+/// The markdown shown beside the menu for a completion: its detail as a code
+/// block, highlighted in the buffer's language, followed by its markdown
+/// documentation.
+fn aside_markdown_source(completion: &Completion) -> Option<SharedString> {
+    let documentation = match &completion.documentation {
+        Some(CompletionDocumentation::MultiLineMarkdown(source)) if !source.is_empty() => {
+            Some(source)
+        }
+        _ => None,
+    };
+    let Some(detail) = completion_detail(completion) else {
+        return documentation.cloned();
+    };
+    let mut source = MarkdownCodeBlock {
+        tag: "",
+        text: &detail,
+    }
+    .to_string();
+    if let Some(documentation) = documentation {
+        source.push('\n');
+        source.push_str(documentation);
+    }
+    Some(source.into())
+}
+
 #[derive(Clone)]
 pub struct AvailableCodeAction {
     pub excerpt_id: ExcerptId,
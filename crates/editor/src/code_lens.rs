@@ -0,0 +1,329 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{AnyElement, Entity, Task, WeakEntity};
+use itertools::Itertools as _;
+use language::{Buffer, Point};
+use multi_buffer::ExcerptId;
+use project::{CodeAction, LspAction};
+use settings::Settings as _;
+use text::{BufferId, ToPoint as _};
+use ui::{App, Context, Window, prelude::*};
+use util::ResultExt as _;
+
+use crate::{
+    Editor, EditorSettings,
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+};
+
+/// How long to wait after an edit before querying for code lenses, as the
+/// blocks they are rendered in shift the lines below them around.
+const CODE_LENS_DEBOUNCE: Duration = Duration::from_millis(250);
+
+pub(super) struct CodeLensData {
+    enabled: bool,
+    blocks: HashMap<BufferId, Vec<CodeLensBlock>>,
+    refresh_tasks: HashMap<BufferId, Task<()>>,
+}
+
+/// A block showing the code lenses of a line, in one of the excerpts of its buffer.
+struct CodeLensBlock {
+    id: CustomBlockId,
+    excerpt_id: ExcerptId,
+    line_start: text::Anchor,
+}
+
+impl CodeLensData {
+    pub fn new(cx: &App) -> Self {
+        Self {
+            enabled: EditorSettings::get_global(cx).code_lens,
+            blocks: HashMap::default(),
+            refresh_tasks: HashMap::default(),
+        }
+    }
+}
+
+impl Editor {
+    pub(super) fn code_lens_settings_changed(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let enabled = EditorSettings::get_global(cx).code_lens;
+        let Some(code_lenses) = self.code_lenses.as_mut() else {
+            return;
+        };
+        if code_lenses.enabled != enabled {
+            code_lenses.enabled = enabled;
+            self.refresh_code_lenses(None, window, cx);
+        }
+    }
+
+    pub(super) fn refresh_code_lenses(
+        &mut self,
+        buffer_id: Option<BufferId>,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode().is_full() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if self
+            .code_lenses
+            .as_ref()
+            .is_none_or(|code_lenses| !code_lenses.enabled)
+        {
+            self.clear_code_lenses(cx);
+            return;
+        }
+
+        let buffers = self
+            .visible_excerpts(None, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer.read(cx).remote_id())
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        for buffer in buffers {
+            let buffer_id = buffer.read(cx).remote_id();
+            let project = project.clone();
+            let task = cx.spawn_in(window, async move |editor, cx| {
+                cx.background_executor().timer(CODE_LENS_DEBOUNCE).await;
+                let Ok(lenses_task) = project.update(cx, |project, cx| {
+                    let len = buffer.read(cx).len();
+                    project.code_lens_actions(&buffer, 0..len, cx)
+                }) else {
+                    return;
+                };
+                let lenses = lenses_task.await.log_err().flatten().unwrap_or_default();
+
+                // Lenses may be sent without a command, which is where their
+                // title is, until they are resolved.
+                let Ok(resolve_tasks) = project.update(cx, |project, cx| {
+                    lenses
+                        .into_iter()
+                        .map(|lens| project.resolve_code_lens(&buffer, lens, cx))
+                        .collect::<Vec<_>>()
+                }) else {
+                    return;
+                };
+                let lenses = join_all(resolve_tasks)
+                    .await
+                    .into_iter()
+                    .filter_map(|lens| lens.log_err())
+                    .filter(|lens| match &lens.lsp_action {
+                        LspAction::CodeLens(lens) => lens.command.is_some(),
+                        _ => false,
+                    })
+                    .collect::<Vec<_>>();
+
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.set_code_lens_blocks(&buffer, lenses, cx);
+                    })
+                    .ok();
+            });
+            if let Some(code_lenses) = self.code_lenses.as_mut() {
+                code_lenses.refresh_tasks.insert(buffer_id, task);
+            }
+        }
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn code_lens_block_ids(&self) -> HashSet<CustomBlockId> {
+        self.code_lenses
+            .iter()
+            .flat_map(|code_lenses| code_lenses.blocks.values().flatten())
+            .map(|block| block.id)
+            .collect()
+    }
+
+    pub(super) fn clear_code_lenses(&mut self, cx: &mut Context<Self>) {
+        let Some(code_lenses) = self.code_lenses.as_mut() else {
+            return;
+        };
+        code_lenses.refresh_tasks.clear();
+        let blocks = code_lenses
+            .blocks
+            .drain()
+            .flat_map(|(_, blocks)| blocks)
+            .map(|block| block.id)
+            .collect::<HashSet<_>>();
+        if !blocks.is_empty() {
+            self.remove_blocks(blocks, None, cx);
+        }
+    }
+
+    fn set_code_lens_blocks(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        lenses: Vec<CodeAction>,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let Some(code_lenses) = self.code_lenses.as_mut() else {
+            return;
+        };
+        code_lenses.refresh_tasks.remove(&buffer_id);
+
+        // Blocks that are still on a line with lenses are kept and only re-rendered, so that
+        // refreshing the lenses after an edit doesn't shift the lines around them.
+        let mut blocks_to_remove = HashSet::default();
+        let mut old_blocks = HashMap::default();
+        for block in code_lenses.blocks.remove(&buffer_id).unwrap_or_default() {
+            let row = block.line_start.to_point(&buffer_snapshot).row;
+            if let Some(displaced) = old_blocks.insert((block.excerpt_id, row), block) {
+                blocks_to_remove.insert(displaced.id);
+            }
+        }
+
+        let mut lenses_by_row = BTreeMap::<u32, Vec<CodeAction>>::new();
+        for lens in lenses {
+            let row = lens.range.start.to_point(&buffer_snapshot).row;
+            lenses_by_row.entry(row).or_default().push(lens);
+        }
+
+        let editor = cx.entity().downgrade();
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let excerpts = multi_buffer.excerpts_for_buffer(buffer_id, cx);
+        let mut kept_blocks = Vec::new();
+        let mut renderers = HashMap::default();
+        let mut new_blocks = Vec::new();
+        let mut new_block_properties = Vec::new();
+        for (row, lenses) in lenses_by_row {
+            let line_start = buffer_snapshot.anchor_before(Point::new(row, 0));
+            let indent = buffer_snapshot.indent_size_for_line(row).len;
+            let lenses: Arc<[CodeAction]> = lenses.into();
+            for (excerpt_id, excerpt_range) in &excerpts {
+                if excerpt_range
+                    .context
+                    .start
+                    .cmp(&line_start, &buffer_snapshot)
+                    .is_gt()
+                    || excerpt_range
+                        .context
+                        .end
+                        .cmp(&line_start, &buffer_snapshot)
+                        .is_lt()
+                {
+                    continue;
+                }
+                let Some(anchor) = multi_buffer_snapshot.anchor_in_excerpt(*excerpt_id, line_start)
+                else {
+                    continue;
+                };
+                let buffer = buffer.clone();
+                let editor = editor.clone();
+                let lenses = lenses.clone();
+                let render: RenderBlock =
+                    Arc::new(move |cx| render_code_lenses(&lenses, &buffer, indent, &editor, cx));
+                if let Some(block) = old_blocks.remove(&(*excerpt_id, row)) {
+                    renderers.insert(block.id, render);
+                    kept_blocks.push(block);
+                } else {
+                    new_blocks.push((*excerpt_id, line_start));
+                    new_block_properties.push(BlockProperties {
+                        placement: BlockPlacement::Above(anchor),
+                        height: Some(1),
+                        style: BlockStyle::Flex,
+                        render,
+                        priority: 0,
+                    });
+                }
+            }
+        }
+
+        blocks_to_remove.extend(old_blocks.into_values().map(|block| block.id));
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+        if !renderers.is_empty() {
+            self.replace_blocks(renderers, None, cx);
+        }
+        let new_block_ids = self.insert_blocks(new_block_properties, None, cx);
+        kept_blocks.extend(new_blocks.into_iter().zip(new_block_ids).map(
+            |((excerpt_id, line_start), id)| CodeLensBlock {
+                id,
+                excerpt_id,
+                line_start,
+            },
+        ));
+        if let Some(code_lenses) = self.code_lenses.as_mut() {
+            code_lenses.blocks.insert(buffer_id, kept_blocks);
+        }
+    }
+
+    fn apply_code_lens(
+        &mut self,
+        buffer: Entity<Buffer>,
+        lens: CodeAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let title = lens.lsp_action.title().to_owned();
+        let apply_code_action = project.update(cx, |project, cx| {
+            project.apply_code_action(buffer, lens, true, cx)
+        });
+        let workspace = workspace.downgrade();
+        cx.spawn_in(window, async move |editor, cx| {
+            let project_transaction = apply_code_action.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn render_code_lenses(
+    lenses: &[CodeAction],
+    buffer: &Entity<Buffer>,
+    indent: u32,
+    editor: &WeakEntity<Editor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .w_full()
+        .pl(cx.margins.gutter.full_width() + cx.em_width * indent as f32)
+        .gap_1()
+        .children(lenses.iter().enumerate().map(|(ix, lens)| {
+            let buffer = buffer.clone();
+            let editor = editor.clone();
+            let lens = lens.clone();
+            h_flex()
+                .gap_1()
+                .when(ix > 0, |this| {
+                    this.child(
+                        Label::new("|")
+                            .size(LabelSize::XSmall)
+                            .color(Color::Disabled),
+                    )
+                })
+                .child(
+                    Button::new(("code-lens", ix), lens.lsp_action.title().to_owned())
+                        .label_size(LabelSize::XSmall)
+                        .color(Color::Muted)
+                        .on_click(move |_, window, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.apply_code_lens(buffer.clone(), lens.clone(), window, cx)
+                                })
+                                .ok();
+                        }),
+                )
+        }))
+        .into_any_element()
+}
@@ -0,0 +1,248 @@
+use std::ops::Range;
+
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Hsla, Rgba, Subscription,
+};
+use language::ColorLiteral;
+use multi_buffer::{Anchor, ToOffset as _};
+use ui::{IconButton, IconName, IconSize, Tooltip, prelude::*};
+use workspace::ModalView;
+
+use crate::{Editor, EditorEvent, actions::EditColor};
+
+/// How much each step of the picker's buttons changes the hue, in degrees, and the
+/// saturation and lightness, in percentage points.
+const HUE_STEP: f32 = 10.;
+const PERCENTAGE_STEP: f32 = 5.;
+
+#[derive(Clone, Copy)]
+enum HslChannel {
+    Hue,
+    Saturation,
+    Lightness,
+}
+
+impl HslChannel {
+    fn label(&self) -> &'static str {
+        match self {
+            HslChannel::Hue => "Hue",
+            HslChannel::Saturation => "Saturation",
+            HslChannel::Lightness => "Lightness",
+        }
+    }
+}
+
+/// A modal for editing a color literal in an editor, which writes the edited color back
+/// in the format the literal was written in.
+pub struct ColorPicker {
+    color_editor: Entity<Editor>,
+    target_editor: Entity<Editor>,
+    range: Range<Anchor>,
+    original: ColorLiteral,
+    _subscription: Subscription,
+}
+
+impl ModalView for ColorPicker {}
+
+impl EventEmitter<DismissEvent> for ColorPicker {}
+
+impl Focusable for ColorPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.color_editor.focus_handle(cx)
+    }
+}
+
+impl ColorPicker {
+    fn new(
+        target_editor: Entity<Editor>,
+        range: Range<Anchor>,
+        original_text: &str,
+        original: ColorLiteral,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let color_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(original_text, window, cx);
+            editor.select_all(&crate::actions::SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&color_editor, |_, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                cx.notify();
+            }
+        });
+        Self {
+            color_editor,
+            target_editor,
+            range,
+            original,
+            _subscription: subscription,
+        }
+    }
+
+    /// The color currently entered in the picker, in any supported format.
+    fn entered_color(&self, cx: &App) -> Option<Rgba> {
+        ColorLiteral::parse(&self.color_editor.read(cx).text(cx)).map(|literal| literal.color)
+    }
+
+    fn step(
+        &mut self,
+        channel: HslChannel,
+        delta: f32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let color = self.entered_color(cx).unwrap_or(self.original.color);
+        let mut hsla = Hsla::from(color);
+        match channel {
+            HslChannel::Hue => hsla.h = (hsla.h + delta * HUE_STEP / 360.).rem_euclid(1.),
+            HslChannel::Saturation => {
+                hsla.s = (hsla.s + delta * PERCENTAGE_STEP / 100.).clamp(0., 1.)
+            }
+            HslChannel::Lightness => {
+                hsla.l = (hsla.l + delta * PERCENTAGE_STEP / 100.).clamp(0., 1.)
+            }
+        }
+        let text = self.original.format.format(Rgba::from(hsla));
+        self.color_editor.update(cx, |editor, cx| {
+            editor.set_text(text, window, cx);
+        });
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(color) = self.entered_color(cx) else {
+            return;
+        };
+        let text = self.original.format.format(color);
+        let range = self.range.clone();
+        self.target_editor.update(cx, |editor, cx| {
+            editor.transact(window, cx, |editor, _, cx| {
+                editor.edit([(range, text)], cx);
+            });
+            editor.focus_handle(cx).focus(window);
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn render_channel_stepper(
+        &self,
+        channel: HslChannel,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let label = channel.label();
+        h_flex()
+            .gap_0p5()
+            .child(
+                IconButton::new(
+                    SharedString::from(format!("decrease-{label}")),
+                    IconName::Dash,
+                )
+                .icon_size(IconSize::XSmall)
+                .tooltip(Tooltip::text(format!("Decrease {}", label.to_lowercase())))
+                .on_click(
+                    cx.listener(move |this, _, window, cx| this.step(channel, -1., window, cx)),
+                ),
+            )
+            .child(Label::new(label).size(LabelSize::Small))
+            .child(
+                IconButton::new(
+                    SharedString::from(format!("increase-{label}")),
+                    IconName::Plus,
+                )
+                .icon_size(IconSize::XSmall)
+                .tooltip(Tooltip::text(format!("Increase {}", label.to_lowercase())))
+                .on_click(
+                    cx.listener(move |this, _, window, cx| this.step(channel, 1., window, cx)),
+                ),
+            )
+    }
+}
+
+impl Render for ColorPicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entered_color = self.entered_color(cx);
+        let preview = div()
+            .flex_none()
+            .size_5()
+            .rounded_sm()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .when_some(entered_color, |preview, color| preview.bg(color));
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("ColorPicker")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                h_flex()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .child(preview)
+                    .child(self.color_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .justify_between()
+                    .child(self.render_channel_stepper(HslChannel::Hue, cx))
+                    .child(self.render_channel_stepper(HslChannel::Saturation, cx))
+                    .child(self.render_channel_stepper(HslChannel::Lightness, cx)),
+            )
+            .when(entered_color.is_none(), |this| {
+                this.child(
+                    div().px_2().pb_1().child(
+                        Label::new("Enter a hex, rgb() or hsl() color")
+                            .size(LabelSize::Small)
+                            .color(Color::Error),
+                    ),
+                )
+            })
+    }
+}
+
+impl Editor {
+    pub fn edit_color(&mut self, _: &EditColor, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.selections.newest::<usize>(cx).head();
+        if let Some(range) = self.document_color_range_at(offset, cx) {
+            self.open_color_picker(range, window, cx);
+        }
+    }
+
+    /// Opens a color picker for the color literal in `range`, if it's written in a format
+    /// that can be edited.
+    pub(crate) fn open_color_picker(
+        &mut self,
+        range: Range<Anchor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let snapshot = self.buffer().read(cx).snapshot(cx);
+        let text = snapshot
+            .text_for_range(range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot))
+            .collect::<String>();
+        let Some(original) = ColorLiteral::parse(&text) else {
+            return;
+        };
+        let editor = cx.entity();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                ColorPicker::new(editor, range, &text, original, window, cx)
+            });
+        });
+    }
+}
@@ -0,0 +1,400 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result, anyhow};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{Context, Task, Window};
+use language::{Point, ToPoint as _};
+use workspace::{OpenOptions, OpenVisible};
+
+use crate::{Editor, GoToDependencySource};
+
+/// A package imported at the cursor.
+#[derive(Debug, PartialEq)]
+enum Dependency {
+    /// A crate, found through `Cargo.lock` in the Cargo registry.
+    Crate(String),
+    /// A package, found in the closest `node_modules` directory that contains it.
+    NodePackage(String),
+}
+
+impl Editor {
+    /// Opens the source of the dependency imported at the cursor, such as a crate in the Cargo
+    /// registry or a package in `node_modules`.
+    ///
+    /// Dependencies are opened read-only, in worktrees that aren't visible in the project panel.
+    pub fn go_to_dependency_source(
+        &mut self,
+        _: &GoToDependencySource,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let workspace = self.workspace()?;
+        let project = self.project.clone()?;
+        if !project.read(cx).is_local() {
+            return None;
+        }
+
+        let head = self.selections.newest_anchor().head();
+        let (buffer, position) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let buffer = buffer.read(cx);
+        let language = buffer.language_at(position)?;
+        let point = position.to_point(buffer);
+        let line = buffer
+            .text_for_range(
+                Point::new(point.row, 0)..Point::new(point.row, buffer.line_len(point.row)),
+            )
+            .collect::<String>();
+        let dependency = dependency_at(&line, point.column as usize, language.name().0.as_ref())?;
+        let file_path = buffer.file()?.as_local()?.abs_path(cx);
+        let fs = project.read(cx).fs().clone();
+
+        Some(cx.spawn_in(window, async move |_, cx| {
+            let source_path = match &dependency {
+                Dependency::Crate(name) => crate_source(fs.as_ref(), &file_path, name).await,
+                Dependency::NodePackage(name) => {
+                    node_package_source(fs.as_ref(), &file_path, name).await
+                }
+            }?;
+
+            let item = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_abs_path(
+                        source_path,
+                        OpenOptions {
+                            visible: Some(OpenVisible::None),
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    )
+                })?
+                .await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(cx, |editor, cx| {
+                    editor.set_read_only(true);
+                    cx.notify();
+                })?;
+            }
+            Ok(())
+        }))
+    }
+}
+
+fn dependency_at(line: &str, column: usize, language_name: &str) -> Option<Dependency> {
+    match language_name {
+        "Rust" => rust_crate_at(line, column).map(Dependency::Crate),
+        "JavaScript" | "TypeScript" | "TSX" => {
+            node_package_at(line, column).map(Dependency::NodePackage)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the crate that the path at `column` starts with, or that the line's `use` declaration
+/// imports from.
+fn rust_crate_at(line: &str, column: usize) -> Option<String> {
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let column = column.min(line.len());
+    let start = line[..column]
+        .rfind(|c| !is_path_char(c))
+        .map_or(0, |ix| ix + 1);
+    let end = line[column..]
+        .find(|c| !is_path_char(c))
+        .map_or(line.len(), |ix| column + ix);
+    let path_at_cursor = &line[start..end];
+
+    let declaration = line.trim_start();
+    let declaration = declaration.strip_prefix("pub ").unwrap_or(declaration);
+    let path = if path_at_cursor.contains("::") {
+        path_at_cursor
+    } else if let Some(name) = declaration.strip_prefix("extern crate ") {
+        name
+    } else {
+        declaration.strip_prefix("use ")?
+    };
+
+    let name = path
+        .trim_start_matches("::")
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()?;
+    match name {
+        "" | "crate" | "self" | "super" | "std" | "core" | "alloc" => None,
+        _ => Some(name.to_string()),
+    }
+}
+
+/// Returns the package that the module specifier at `column`, or the only one on the line,
+/// belongs to.
+fn node_package_at(line: &str, column: usize) -> Option<String> {
+    let mut specifiers = Vec::new();
+    let mut open_quote = None;
+    for (ix, c) in line.char_indices() {
+        if !matches!(c, '"' | '\'' | '`') {
+            continue;
+        }
+        match open_quote {
+            Some((quote, start)) if quote == c => {
+                specifiers.push(start..ix);
+                open_quote = None;
+            }
+            Some(_) => {}
+            None => open_quote = Some((c, ix + 1)),
+        }
+    }
+    let specifier = match specifiers
+        .iter()
+        .find(|range| range.start <= column && column <= range.end)
+    {
+        Some(range) => &line[range.clone()],
+        None if specifiers.len() == 1 => &line[specifiers[0].clone()],
+        None => return None,
+    };
+
+    if specifier.is_empty() || specifier.starts_with(['.', '/']) || specifier.starts_with("node:") {
+        return None;
+    }
+    let segment_count = if specifier.starts_with('@') { 2 } else { 1 };
+    let name = specifier
+        .split('/')
+        .take(segment_count)
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(name)
+}
+
+/// Returns the entry point of the locked version of the crate in the Cargo registry.
+async fn crate_source(fs: &dyn Fs, file_path: &Path, crate_name: &str) -> Result<PathBuf> {
+    let mut cargo_lock = None;
+    for dir in file_path.ancestors().skip(1) {
+        let path = dir.join("Cargo.lock");
+        if fs.is_file(&path).await {
+            cargo_lock = Some(path);
+            break;
+        }
+    }
+    let cargo_lock = cargo_lock.context("no Cargo.lock found")?;
+    let cargo_lock = fs.load(&cargo_lock).await?;
+    let packages = locked_packages(&cargo_lock, crate_name);
+    if packages.is_empty() {
+        return Err(anyhow!("crate {crate_name} is not in Cargo.lock"));
+    }
+
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| util::paths::home_dir().join(".cargo"));
+    let mut registries = fs
+        .read_dir(&cargo_home.join("registry").join("src"))
+        .await?;
+    while let Some(registry) = registries.next().await {
+        let registry = registry?;
+        for (name, version) in packages.iter().rev() {
+            let package_dir = registry.join(format!("{name}-{version}"));
+            let lib = package_dir.join("src").join("lib.rs");
+            if fs.is_file(&lib).await {
+                return Ok(lib);
+            } else if fs.is_dir(&package_dir).await {
+                return Ok(package_dir.join("Cargo.toml"));
+            }
+        }
+    }
+    Err(anyhow!("crate {crate_name} is not in the Cargo registry"))
+}
+
+/// Returns the names and versions of the packages in `Cargo.lock` whose library is imported as
+/// `crate_name`.
+fn locked_packages<'a>(cargo_lock: &'a str, crate_name: &str) -> Vec<(&'a str, &'a str)> {
+    cargo_lock
+        .split("[[package]]")
+        .filter_map(|package| {
+            let mut name = None;
+            let mut version = None;
+            for line in package.lines() {
+                if let Some(value) = line.strip_prefix("name = ") {
+                    name = Some(value.trim_matches('"'));
+                } else if let Some(value) = line.strip_prefix("version = ") {
+                    version = Some(value.trim_matches('"'));
+                }
+            }
+            let name = name?;
+            (name.replace('-', "_") == crate_name).then_some((name, version?))
+        })
+        .collect()
+}
+
+/// Returns the entry point of the package, as declared in its `package.json`, or the
+/// `package.json` itself.
+async fn node_package_source(fs: &dyn Fs, file_path: &Path, package: &str) -> Result<PathBuf> {
+    for dir in file_path.ancestors().skip(1) {
+        let package_dir = dir.join("node_modules").join(package);
+        let manifest_path = package_dir.join("package.json");
+        if !fs.is_file(&manifest_path).await {
+            continue;
+        }
+
+        let manifest = fs.load(&manifest_path).await?;
+        let entry = serde_json::from_str::<serde_json::Value>(&manifest)
+            .ok()
+            .and_then(|manifest| {
+                ["module", "main"]
+                    .into_iter()
+                    .find_map(|field| Some(manifest.get(field)?.as_str()?.to_string()))
+            })
+            .unwrap_or_else(|| "index.js".to_string());
+        let entry = package_dir.join(entry);
+        for path in [
+            entry.clone(),
+            entry.with_extension("js"),
+            entry.join("index.js"),
+        ] {
+            if fs.is_file(&path).await {
+                return Ok(path);
+            }
+        }
+        return Ok(manifest_path);
+    }
+    Err(anyhow!("package {package} is not in node_modules"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::path;
+
+    #[test]
+    fn test_dependency_at() {
+        let rust = |line: &str| {
+            let column = line.find('|').unwrap();
+            dependency_at(&line.replace('|', ""), column, "Rust")
+        };
+        let crate_ = |name: &str| Some(Dependency::Crate(name.to_string()));
+        assert_eq!(
+            rust("use serde::{|Deserialize, Serialize};"),
+            crate_("serde")
+        );
+        assert_eq!(rust("pub use gpui::|App;"), crate_("gpui"));
+        assert_eq!(
+            rust("    let x = serde_json::fr|om_str(s);"),
+            crate_("serde_json")
+        );
+        assert_eq!(rust("extern crate |libc;"), crate_("libc"));
+        assert_eq!(rust("use crate::|editor;"), None);
+        assert_eq!(rust("use std::sync::|Arc;"), None);
+        assert_eq!(rust("let |x = 1;"), None);
+
+        let typescript = |line: &str| {
+            let column = line.find('|').unwrap();
+            dependency_at(&line.replace('|', ""), column, "TypeScript")
+        };
+        let package = |name: &str| Some(Dependency::NodePackage(name.to_string()));
+        assert_eq!(
+            typescript("import { |debounce } from \"lodash/debounce\";"),
+            package("lodash")
+        );
+        assert_eq!(
+            typescript("import { render } from '@testing-library/re|act';"),
+            package("@testing-library/react")
+        );
+        assert_eq!(
+            typescript("const a = require('a'), b = require('|b');"),
+            package("b")
+        );
+        assert_eq!(typescript("import { a } from './|a';"), None);
+        assert_eq!(typescript("import fs from 'node:fs|';"), None);
+    }
+
+    #[test]
+    fn test_locked_packages() {
+        let cargo_lock = r#"
+# This file is automatically @generated by Cargo.
+version = 4
+
+[[package]]
+name = "tree-sitter"
+version = "0.25.6"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "windows-sys"
+version = "0.52.0"
+
+[[package]]
+name = "windows-sys"
+version = "0.59.0"
+"#;
+        assert_eq!(
+            locked_packages(cargo_lock, "tree_sitter"),
+            [("tree-sitter", "0.25.6")]
+        );
+        assert_eq!(
+            locked_packages(cargo_lock, "windows_sys"),
+            [("windows-sys", "0.52.0"), ("windows-sys", "0.59.0")]
+        );
+        assert_eq!(locked_packages(cargo_lock, "serde"), Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_node_package_source(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "node_modules": {
+                    "lodash": {
+                        "package.json": r#"{ "main": "lodash" }"#,
+                        "lodash.js": "",
+                    },
+                    "@scope": {
+                        "types-only": {
+                            "package.json": r#"{ "types": "index.d.ts" }"#,
+                        },
+                    },
+                },
+                "packages": {
+                    "app": {
+                        "node_modules": {
+                            "lodash": {
+                                "package.json": r#"{ "module": "esm/index.js" }"#,
+                                "esm": { "index.js": "" },
+                            },
+                        },
+                        "src": { "main.ts": "" },
+                    },
+                },
+                "src": { "main.ts": "" },
+            }),
+        )
+        .await;
+
+        let source = |file_path: &'static str, package: &'static str| {
+            let fs = fs.clone();
+            async move {
+                node_package_source(fs.as_ref(), Path::new(file_path), package)
+                    .await
+                    .ok()
+            }
+        };
+        assert_eq!(
+            source(path!("/project/src/main.ts"), "lodash").await,
+            Some(PathBuf::from(path!(
+                "/project/node_modules/lodash/lodash.js"
+            )))
+        );
+        assert_eq!(
+            source(path!("/project/packages/app/src/main.ts"), "lodash").await,
+            Some(PathBuf::from(path!(
+                "/project/packages/app/node_modules/lodash/esm/index.js"
+            )))
+        );
+        assert_eq!(
+            source(path!("/project/src/main.ts"), "@scope/types-only").await,
+            Some(PathBuf::from(path!(
+                "/project/node_modules/@scope/types-only/package.json"
+            )))
+        );
+        assert_eq!(source(path!("/project/src/main.ts"), "react").await, None);
+    }
+}
@@ -41,13 +41,17 @@ use fold_map::FoldSnapshot;
 pub use fold_map::{
     ChunkRenderer, ChunkRendererContext, ChunkRendererId, Fold, FoldId, FoldPlaceholder, FoldPoint,
 };
-use gpui::{App, Context, Entity, Font, HighlightStyle, LineLayout, Pixels, UnderlineStyle};
+use gpui::{
+    App, Context, Entity, Font, HighlightStyle, IntoElement as _, LineLayout, ParentElement as _,
+    Pixels, Styled as _, UnderlineStyle,
+};
 pub use inlay_map::Inlay;
 use inlay_map::InlaySnapshot;
 pub use inlay_map::{InlayOffset, InlayPoint};
-pub use invisibles::{is_invisible, replacement};
+pub use invisibles::{is_deceptive_invisible, is_invisible, replacement};
 use language::{
-    OffsetUtf16, Point, Subscription as BufferSubscription, language_settings::language_settings,
+    BlockCommentConfig, OffsetUtf16, Point, Subscription as BufferSubscription,
+    language_settings::language_settings_with_detected_indent,
 };
 use multi_buffer::{
     Anchor, AnchorRangeExt, ExcerptId, MultiBuffer, MultiBufferPoint, MultiBufferRow,
@@ -68,7 +72,7 @@ use std::{
 use sum_tree::{Bias, TreeMap};
 use tab_map::TabSnapshot;
 use text::{BufferId, LineIndent};
-use ui::{SharedString, px};
+use ui::{SharedString, h_flex, px};
 use unicode_segmentation::UnicodeSegmentation;
 use wrap_map::{WrapMap, WrapSnapshot};
 
@@ -121,6 +125,9 @@ pub struct DisplayMap {
     pub clip_at_line_ends: bool,
     pub(crate) masked: bool,
     pub(crate) diagnostics_max_severity: DiagnosticSeverity,
+    /// The snapshot of each buffer as of the last [`DisplayMap::take_reparsed_rows`] call,
+    /// to find which of its rows later reparses affected.
+    reparsed_buffers: HashMap<BufferId, language::BufferSnapshot>,
 }
 
 impl DisplayMap {
@@ -163,7 +170,73 @@ impl DisplayMap {
             inlay_highlights: Default::default(),
             clip_at_line_ends: false,
             masked: false,
+            reparsed_buffers: HashMap::default(),
+        }
+    }
+
+    /// Returns the display rows whose syntax highlighting may have changed since the last call for
+    /// `buffer_id`, based on the regions the buffer's reparses changed in between. Returns `None`
+    /// when any row may have changed, such as on the first call for a buffer.
+    pub fn take_reparsed_rows(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut Context<Self>,
+    ) -> Option<Vec<Range<DisplayRow>>> {
+        let multi_buffer = self.buffer.read(cx);
+        let Some(buffer) = multi_buffer.buffer(buffer_id) else {
+            self.reparsed_buffers.remove(&buffer_id);
+            return None;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let excerpts = multi_buffer.excerpts_for_buffer(buffer_id, cx);
+        let changed_regions = self
+            .reparsed_buffers
+            .insert(buffer_id, buffer_snapshot.clone())
+            .and_then(|old_snapshot| buffer_snapshot.syntax_changed_since(&old_snapshot))?;
+
+        let snapshot = self.snapshot(cx);
+        let mut rows = Vec::<Range<DisplayRow>>::new();
+        for region in changed_regions {
+            for (excerpt_id, excerpt_range) in &excerpts {
+                let context = &excerpt_range.context;
+                if region
+                    .range
+                    .start
+                    .cmp(&context.end, &buffer_snapshot)
+                    .is_gt()
+                    || region
+                        .range
+                        .end
+                        .cmp(&context.start, &buffer_snapshot)
+                        .is_lt()
+                {
+                    continue;
+                }
+                let (Some(start), Some(end)) = (
+                    snapshot
+                        .buffer_snapshot
+                        .anchor_in_excerpt(*excerpt_id, region.range.start),
+                    snapshot
+                        .buffer_snapshot
+                        .anchor_in_excerpt(*excerpt_id, region.range.end),
+                ) else {
+                    continue;
+                };
+                let start_row = start.to_display_point(&snapshot).row();
+                let end_row = end.to_display_point(&snapshot).row();
+                rows.push(start_row..DisplayRow(end_row.0 + 1));
+            }
         }
+        rows.sort_by_key(|range| range.start);
+        rows.dedup_by(|range, prev_range| {
+            if range.start <= prev_range.end {
+                prev_range.end = prev_range.end.max(range.end);
+                true
+            } else {
+                false
+            }
+        });
+        Some(rows)
     }
 
     pub fn snapshot(&mut self, cx: &mut Context<Self>) -> DisplaySnapshot {
@@ -529,6 +602,16 @@ impl DisplayMap {
         cleared
     }
 
+    pub fn clear_keyed_highlights(&mut self, type_id: TypeId) -> bool {
+        let mut cleared = false;
+        self.text_highlights.retain(|key, _| {
+            let remove = matches!(key, HighlightKey::TypePlus(id, _) if *id == type_id);
+            cleared |= remove;
+            !remove
+        });
+        cleared
+    }
+
     pub fn set_font(&self, font: Font, font_size: Pixels, cx: &mut Context<Self>) -> bool {
         self.wrap_map
             .update(cx, |map, cx| map.set_font_with_size(font, font_size, cx))
@@ -539,6 +622,11 @@ impl DisplayMap {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    pub fn set_wrap_hanging_indent(&self, hanging_indent: u32, cx: &mut Context<Self>) -> bool {
+        self.wrap_map
+            .update(cx, |map, cx| map.set_hanging_indent(hanging_indent, cx))
+    }
+
     pub fn update_fold_widths(
         &mut self,
         widths: impl IntoIterator<Item = (ChunkRendererId, Pixels)>,
@@ -620,7 +708,8 @@ impl DisplayMap {
             .and_then(|buffer| buffer.language())
             .map(|l| l.name());
         let file = buffer.and_then(|buffer| buffer.file());
-        language_settings(language, file, cx).tab_size
+        let detected_indent = buffer.and_then(|buffer| buffer.detected_indent());
+        language_settings_with_detected_indent(language, file, detected_indent, cx).tab_size
     }
 
     #[cfg(test)]
@@ -1306,6 +1395,8 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(crease) = self.fold_region_crease(buffer_row) {
+            Some(crease)
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
@@ -1354,6 +1445,82 @@ impl DisplaySnapshot {
         }
     }
 
+    pub fn starts_fold_region(&self, buffer_row: MultiBufferRow) -> bool {
+        matches!(
+            self.fold_region_marker(buffer_row),
+            Some(FoldRegionMarker::Start(_))
+        )
+    }
+
+    /// Returns the crease for the user-defined folding region starting at the given row, which
+    /// spans from its start marker to the matching end marker and is labeled with its name.
+    fn fold_region_crease(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
+        let Some(FoldRegionMarker::Start(name)) = self.fold_region_marker(buffer_row) else {
+            return None;
+        };
+
+        let mut depth = 0;
+        for row in buffer_row.0 + 1..=self.buffer_snapshot.max_row().0 {
+            match self.fold_region_marker(MultiBufferRow(row)) {
+                Some(FoldRegionMarker::Start(_)) => depth += 1,
+                Some(FoldRegionMarker::End) if depth > 0 => depth -= 1,
+                Some(FoldRegionMarker::End) => {
+                    let start = Point::new(
+                        buffer_row.0,
+                        self.line_indent_for_buffer_row(buffer_row).raw_len(),
+                    );
+                    let end = Point::new(row, self.buffer_snapshot.line_len(MultiBufferRow(row)));
+                    return Some(Crease::Inline {
+                        range: start..end,
+                        placeholder: self.fold_region_placeholder(name),
+                        render_toggle: None,
+                        render_trailer: None,
+                        metadata: None,
+                    });
+                }
+                None => {}
+            }
+        }
+        None
+    }
+
+    fn fold_region_marker(&self, buffer_row: MultiBufferRow) -> Option<FoldRegionMarker> {
+        let line_start = Point::new(buffer_row.0, 0);
+        let line_end = Point::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
+        let line = self
+            .buffer_snapshot
+            .text_for_range(line_start..line_end)
+            .collect::<String>();
+        if !line.contains("region") {
+            return None;
+        }
+        let scope = self.buffer_snapshot.language_scope_at(line_start)?;
+        parse_fold_region_marker(
+            line.trim(),
+            scope.line_comment_prefixes(),
+            scope.block_comment(),
+        )
+    }
+
+    /// Returns a placeholder showing the name of a folded region before the ellipsis.
+    fn fold_region_placeholder(&self, name: SharedString) -> FoldPlaceholder {
+        if name.is_empty() {
+            return self.fold_placeholder.clone();
+        }
+        let render = self.fold_placeholder.render.clone();
+        FoldPlaceholder {
+            render: Arc::new(move |fold_id, fold_range, cx| {
+                h_flex()
+                    .gap_1()
+                    .child(name.clone())
+                    .child(render(fold_id, fold_range, cx))
+                    .into_any_element()
+            }),
+            constrain_width: false,
+            ..self.fold_placeholder.clone()
+        }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn text_highlight_ranges<Tag: ?Sized + 'static>(
         &self,
@@ -1508,6 +1675,39 @@ impl ToDisplayPoint for Point {
     }
 }
 
+/// A comment marking the start or the end of a user-defined folding region, such as
+/// `// #region Name` and `// #endregion`.
+#[derive(Debug, PartialEq)]
+enum FoldRegionMarker {
+    Start(SharedString),
+    End,
+}
+
+fn parse_fold_region_marker(
+    line: &str,
+    line_comment_prefixes: &[Arc<str>],
+    block_comment: Option<&BlockCommentConfig>,
+) -> Option<FoldRegionMarker> {
+    let comment = line_comment_prefixes
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix.trim_end()))
+        .or_else(|| {
+            let block_comment = block_comment?;
+            line.strip_prefix(block_comment.start.trim_end())?
+                .strip_suffix(block_comment.end.trim_start())
+        })?
+        .trim();
+    let marker = comment.strip_prefix('#').unwrap_or(comment);
+    let is_keyword_end = |rest: &str| rest.is_empty() || rest.starts_with(char::is_whitespace);
+    if let Some(rest) = marker.strip_prefix("endregion") {
+        is_keyword_end(rest).then_some(FoldRegionMarker::End)
+    } else if let Some(rest) = marker.strip_prefix("region") {
+        is_keyword_end(rest).then(|| FoldRegionMarker::Start(rest.trim().to_string().into()))
+    } else {
+        None
+    }
+}
+
 impl ToDisplayPoint for Anchor {
     fn to_display_point(&self, map: &DisplaySnapshot) -> DisplayPoint {
         self.to_point(&map.buffer_snapshot).to_display_point(map)
@@ -2153,6 +2353,75 @@ pub mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_take_reparsed_rows(cx: &mut gpui::TestAppContext) {
+        let text = r#"
+            fn a() {}
+
+            fn b() {}
+
+            fn c() {
+                let x = 1;
+            }
+        "#
+        .unindent();
+        let language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        ));
+
+        cx.update(|cx| init_test(cx, |_| {}));
+
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+        cx.condition(&buffer, |buf, _| !buf.is_parsing()).await;
+        let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+        let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+        let map = cx.new(|cx| {
+            DisplayMap::new(
+                multi_buffer,
+                font("Courier"),
+                px(16.0),
+                None,
+                1,
+                1,
+                FoldPlaceholder::test(),
+                DiagnosticSeverity::Warning,
+                cx,
+            )
+        });
+
+        // Nothing is known about the buffer's syntax on the first call.
+        assert_eq!(
+            map.update(cx, |map, cx| map.take_reparsed_rows(buffer_id, cx)),
+            None
+        );
+        assert_eq!(
+            map.update(cx, |map, cx| map.take_reparsed_rows(buffer_id, cx)),
+            Some(Vec::new())
+        );
+
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit(
+                [(Point::new(5, 12)..Point::new(5, 13), "(1 + 2)")],
+                None,
+                cx,
+            )
+        });
+        cx.condition(&buffer, |buf, _| !buf.is_parsing()).await;
+        let rows = map
+            .update(cx, |map, cx| map.take_reparsed_rows(buffer_id, cx))
+            .unwrap();
+        assert!(!rows.is_empty());
+        assert!(
+            rows.iter()
+                .all(|rows| rows.start >= DisplayRow(4) && rows.end <= DisplayRow(7)),
+            "unexpected reparsed rows {rows:?}"
+        );
+    }
+
     #[gpui::test]
     async fn test_chunks_with_syntax_highlighting_across_blocks(cx: &mut gpui::TestAppContext) {
         cx.background_executor
@@ -2776,6 +3045,33 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_parse_fold_region_marker() {
+        let line_comments: [Arc<str>; 2] = ["// ".into(), "# ".into()];
+        let block_comment = BlockCommentConfig {
+            start: "<!-- ".into(),
+            end: " -->".into(),
+            prefix: "".into(),
+            tab_size: 0,
+        };
+        let parse = |line| parse_fold_region_marker(line, &line_comments, Some(&block_comment));
+
+        assert_eq!(
+            parse("// #region Helpers"),
+            Some(FoldRegionMarker::Start("Helpers".into()))
+        );
+        assert_eq!(parse("# region"), Some(FoldRegionMarker::Start("".into())));
+        assert_eq!(
+            parse("<!-- #region Header -->"),
+            Some(FoldRegionMarker::Start("Header".into()))
+        );
+        assert_eq!(parse("//#endregion"), Some(FoldRegionMarker::End));
+        assert_eq!(parse("<!-- #endregion -->"), Some(FoldRegionMarker::End));
+        assert_eq!(parse("// #regions"), None);
+        assert_eq!(parse("// a #region"), None);
+        assert_eq!(parse("region"), None);
+    }
+
     #[gpui::test]
     fn test_tabs_with_multibyte_chars(cx: &mut gpui::App) {
         init_test(cx, |_| {});
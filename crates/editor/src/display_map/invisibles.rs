@@ -42,6 +42,24 @@ pub fn is_invisible(c: char) -> bool {
         false
     }
 }
+
+/// Whether `c` is one of the invisible characters that can make code read differently from
+/// how it's compiled, as in "Trojan Source" attacks: bidirectional controls, zero-width
+/// spaces and byte order marks.
+pub fn is_deceptive_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{61c}'
+            | '\u{200b}'
+            | '\u{200e}'
+            | '\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{feff}'
+    )
+}
+
 // ASCII control characters have fancy unicode glyphs, everything else
 // is replaced by a space - unless it is used in combining characters in
 // which case we need to leave it in the string.
@@ -24,6 +24,7 @@ pub struct WrapMap {
     interpolated_edits: Patch<u32>,
     edits_since_sync: Patch<u32>,
     wrap_width: Option<Pixels>,
+    hanging_indent: u32,
     background_task: Option<Task<()>>,
     font_with_size: (Font, Pixels),
 }
@@ -96,6 +97,7 @@ impl WrapMap {
             let mut this = Self {
                 font_with_size: (font, font_size),
                 wrap_width: None,
+                hanging_indent: 0,
                 pending_edits: Default::default(),
                 interpolated_edits: Default::default(),
                 edits_since_sync: Default::default(),
@@ -161,6 +163,16 @@ impl WrapMap {
         true
     }
 
+    pub fn set_hanging_indent(&mut self, hanging_indent: u32, cx: &mut Context<Self>) -> bool {
+        if hanging_indent == self.hanging_indent {
+            return false;
+        }
+
+        self.hanging_indent = hanging_indent;
+        self.rewrap(cx);
+        true
+    }
+
     fn rewrap(&mut self, cx: &mut Context<Self>) {
         self.background_task.take();
         self.interpolated_edits.clear();
@@ -168,6 +180,7 @@ impl WrapMap {
 
         if let Some(wrap_width) = self.wrap_width {
             let mut new_snapshot = self.snapshot.clone();
+            let hanging_indent = self.hanging_indent;
 
             let text_system = cx.text_system().clone();
             let (font, font_size) = self.font_with_size.clone();
@@ -183,6 +196,7 @@ impl WrapMap {
                             new: range.clone(),
                         }],
                         wrap_width,
+                        hanging_indent,
                         &mut line_wrapper,
                     )
                     .await;
@@ -254,6 +268,7 @@ impl WrapMap {
         {
             let pending_edits = self.pending_edits.clone();
             let mut snapshot = self.snapshot.clone();
+            let hanging_indent = self.hanging_indent;
             let text_system = cx.text_system().clone();
             let (font, font_size) = self.font_with_size.clone();
             let update_task = cx.background_spawn(async move {
@@ -261,7 +276,13 @@ impl WrapMap {
                 let mut line_wrapper = text_system.line_wrapper(font, font_size);
                 for (tab_snapshot, tab_edits) in pending_edits {
                     let wrap_edits = snapshot
-                        .update(tab_snapshot, &tab_edits, wrap_width, &mut line_wrapper)
+                        .update(
+                            tab_snapshot,
+                            &tab_edits,
+                            wrap_width,
+                            hanging_indent,
+                            &mut line_wrapper,
+                        )
                         .await;
                     edits = edits.compose(&wrap_edits);
                 }
@@ -400,6 +421,7 @@ impl WrapSnapshot {
         new_tab_snapshot: TabSnapshot,
         tab_edits: &[TabEdit],
         wrap_width: Pixels,
+        hanging_indent: u32,
         line_wrapper: &mut LineWrapper,
     ) -> Patch<u32> {
         #[derive(Debug)]
@@ -488,7 +510,11 @@ impl WrapSnapshot {
                     }
 
                     let mut prev_boundary_ix = 0;
-                    for boundary in line_wrapper.wrap_line(&line_fragments, wrap_width) {
+                    for boundary in line_wrapper.wrap_line_with_hanging_indent(
+                        &line_fragments,
+                        wrap_width,
+                        hanging_indent,
+                    ) {
                         let wrapped = &line[prev_boundary_ix..boundary.ix];
                         push_isomorphic(&mut edit_transforms, TextSummary::from(wrapped));
                         edit_transforms.push(Transform::wrap(boundary.next_indent));
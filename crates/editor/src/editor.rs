@@ -14,8 +14,13 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 mod blink_manager;
+mod bracket_colorization;
 mod clangd_ext;
+mod clipboard_history;
 pub mod code_context_menus;
+mod code_lens;
+mod color_picker;
+mod dependency_source;
 pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
@@ -25,9 +30,12 @@ mod highlight_matching_bracket;
 mod hover_links;
 pub mod hover_popover;
 mod indent_guides;
+mod injection_tints;
 mod inlay_hint_cache;
+mod invisible_characters;
 pub mod items;
 mod jsx_tag_auto_close;
+mod large_file_banner;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
@@ -35,9 +43,14 @@ mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod proposed_changes_editor;
+mod related_file;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_image;
 mod selections_collection;
+mod smart_paste;
+mod spell_check;
+mod sticky_scroll;
 pub mod tasks;
 
 #[cfg(test)]
@@ -51,11 +64,12 @@ mod signature_help;
 pub mod test;
 
 pub(crate) use actions::*;
+pub use clipboard_history::{ClipboardHistory, ClipboardHistoryEntry};
 pub use display_map::{ChunkRenderer, ChunkRendererContext, DisplayPoint, FoldPlaceholder};
 pub use edit_prediction::Direction;
 pub use editor_settings::{
-    CurrentLineHighlight, DocumentColorsRenderMode, EditorSettings, HideMouseMode,
-    ScrollBeyondLastLine, ScrollbarAxes, SearchSettings, ShowMinimap,
+    ColorizeBrackets, CurrentLineHighlight, DocumentColorsRenderMode, EditorSettings,
+    HideMouseMode, ScrollBeyondLastLine, ScrollbarAxes, SearchSettings, ShowMinimap,
 };
 pub use editor_settings_controls::*;
 pub use element::{
@@ -64,6 +78,7 @@ pub use element::{
 pub use git::blame::BlameRenderer;
 pub use hover_popover::hover_markdown_style;
 pub use items::MAX_TAB_TITLE_LEN;
+pub use large_file_banner::LargeFileBanner;
 pub use lsp::CompletionContext;
 pub use lsp_ext::lsp_tasks;
 pub use multi_buffer::{
@@ -82,13 +97,16 @@ use ::git::{
 use aho_corasick::AhoCorasick;
 use anyhow::{Context as _, Result, anyhow};
 use blink_manager::BlinkManager;
+use bracket_colorization::refresh_bracket_colors;
 use buffer_diff::DiffHunkStatus;
 use client::{Collaborator, ParticipantIndex};
+use clipboard_history::record_clipboard_history;
 use clock::{AGENT_REPLICA_ID, ReplicaId};
 use code_context_menus::{
     AvailableCodeAction, CodeActionContents, CodeActionsItem, CodeActionsMenu, CodeContextMenu,
     CompletionsMenu, ContextMenuOrigin,
 };
+use code_lens::CodeLensData;
 use collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use dap::TelemetrySpawnLocation;
@@ -117,7 +135,9 @@ use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight, find_file};
 use hover_popover::{HoverState, hide_hover};
 use indent_guides::ActiveIndentGuidesState;
+use injection_tints::refresh_injection_tints;
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
+use invisible_characters::refresh_invisible_character_warnings;
 use itertools::{Either, Itertools};
 use language::{
     AutoindentMode, BlockCommentConfig, BracketMatch, BracketPair, Buffer, BufferRow,
@@ -126,8 +146,8 @@ use language::{
     Language, OffsetRangeExt, Point, Runnable, RunnableRange, Selection, SelectionGoal, TextObject,
     TransactionId, TreeSitterOptions, WordsQuery,
     language_settings::{
-        self, InlayHintSettings, LspInsertMode, RewrapBehavior, WordsCompletionMode,
-        all_language_settings, language_settings,
+        self, InlayHintKind, InlayHintSettings, LspInsertMode, ReadingModeSettings, RewrapBehavior,
+        WordsCompletionMode, all_language_settings, language_settings,
     },
     point_from_lsp, point_to_lsp, text_diff_with_options,
 };
@@ -151,6 +171,7 @@ use project::{
     CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint,
     Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectPath,
     ProjectTransaction, TaskSourceKind,
+    bookmark_store::BookmarkStore,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -172,6 +193,7 @@ use serde::{Deserialize, Serialize};
 use settings::{GitGutterSetting, Settings, SettingsLocation, SettingsStore, update_settings_file};
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
+use spell_check::{SpellCheckCodeActionProvider, refresh_spell_check};
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
@@ -546,6 +568,9 @@ pub enum SoftWrap {
     Column(u32),
     /// Soft wrap line at the preferred line length or the editor width (whichever is smaller).
     Bounded(u32),
+    /// Soft wrap lines at the preferred line length, indenting the continuation lines by
+    /// `hanging_indent` columns more than the line they continue.
+    HangingIndent { column: u32, hanging_indent: u32 },
 }
 
 #[derive(Clone)]
@@ -959,14 +984,15 @@ enum SelectionDragState {
     /// State when no drag related activity is detected.
     None,
     /// State when the mouse is down on a selection that is about to be dragged.
+    /// All non-empty selections are dragged together.
     ReadyToDrag {
-        selection: Selection<Anchor>,
+        selections: Arc<[Selection<Anchor>]>,
         click_position: gpui::Point<Pixels>,
         mouse_down_time: Instant,
     },
-    /// State when the mouse is dragging the selection in the editor.
+    /// State when the mouse is dragging the selections in the editor.
     Dragging {
-        selection: Selection<Anchor>,
+        selections: Arc<[Selection<Anchor>]>,
         drop_cursor: Selection<Anchor>,
         hide_drop_cursor: bool,
     },
@@ -1030,6 +1056,9 @@ pub struct Editor {
     word_completions_enabled: bool,
     inline_diagnostics: Vec<(Anchor, InlineDiagnostic)>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
+    /// Whether the editor shows a file that was opened in large file mode, and
+    /// so keeps soft wrap and inlay hints off.
+    large_file_mode: bool,
     hard_wrap: Option<usize>,
     project: Option<Entity<Project>>,
     semantics_provider: Option<Rc<dyn SemanticsProvider>>,
@@ -1038,6 +1067,7 @@ pub struct Editor {
     blink_manager: Entity<BlinkManager>,
     show_cursor_names: bool,
     hovered_cursors: HashMap<HoveredCursor, Task<()>>,
+    remote_cursor_activity: HashMap<ReplicaId, RemoteCursorActivity>,
     pub show_local_selections: bool,
     mode: EditorMode,
     show_breadcrumbs: bool,
@@ -1144,9 +1174,12 @@ pub struct Editor {
     last_bounds: Option<Bounds<Pixels>>,
     last_position_map: Option<Rc<PositionMap>>,
     expect_bounds_change: Option<Bounds<Pixels>>,
+    search_origin: Option<Arc<[Selection<Anchor>]>>,
+    spell_check_task: Option<Task<()>>,
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
@@ -1165,6 +1198,7 @@ pub struct Editor {
     _scroll_cursor_center_top_bottom_task: Task<()>,
     serialize_selections: Task<()>,
     serialize_folds: Task<()>,
+    serialize_file_state: Task<()>,
     mouse_cursor_hidden: bool,
     minimap: Option<Entity<Self>>,
     hide_mouse_mode: HideMouseMode,
@@ -1173,6 +1207,7 @@ pub struct Editor {
     selection_drag_state: SelectionDragState,
     next_color_inlay_id: usize,
     colors: Option<LspColorData>,
+    code_lenses: Option<CodeLensData>,
     folding_newlines: Task<()>,
     pub lookup_key: Option<Box<dyn Any + Send + Sync>>,
 }
@@ -1270,6 +1305,11 @@ struct SelectionHistoryEntry {
     select_next_state: Option<SelectNextState>,
     select_prev_state: Option<SelectNextState>,
     add_selections_state: Option<AddSelectionsState>,
+    /// The larger syntax node selections that led here, so that undoing or redoing to this
+    /// entry lets `SelectSmallerSyntaxNode` keep shrinking from it. Only valid as long as the
+    /// buffer hasn't been edited since, as they're stored as offsets.
+    select_syntax_node_stack: Vec<SelectSyntaxNodeHistoryState>,
+    buffer_edit_count: usize,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -1286,6 +1326,14 @@ struct HoveredCursor {
     selection_id: usize,
 }
 
+/// When a collaborator last moved their cursors, for fading out the cursors of idle
+/// collaborators.
+struct RemoteCursorActivity {
+    heads: Vec<Anchor>,
+    last_active: Instant,
+    _repaint_when_idle: Task<()>,
+}
+
 impl Default for SelectionHistoryMode {
     fn default() -> Self {
         Self::Normal
@@ -1483,6 +1531,7 @@ struct SelectNextState {
     query: AhoCorasick,
     wordwise: bool,
     done: bool,
+    options: OccurrenceSearchOptions,
 }
 
 impl std::fmt::Debug for SelectNextState {
@@ -1490,10 +1539,47 @@ impl std::fmt::Debug for SelectNextState {
         f.debug_struct(std::any::type_name::<Self>())
             .field("wordwise", &self.wordwise)
             .field("done", &self.done)
+            .field("options", &self.options)
             .finish()
     }
 }
 
+/// The options used to find occurrences of the selected text when selecting the next, previous
+/// or all of them. Buffer search updates these when its options are toggled, so that both find
+/// the same occurrences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OccurrenceSearchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+}
+
+impl Default for OccurrenceSearchOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            whole_word: false,
+        }
+    }
+}
+
+impl Global for OccurrenceSearchOptions {}
+
+impl OccurrenceSearchOptions {
+    pub fn get(cx: &App) -> Self {
+        cx.try_global::<Self>().copied().unwrap_or_default()
+    }
+
+    pub fn set(self, cx: &mut App) {
+        cx.set_global(self);
+    }
+
+    fn query(&self, text: &str) -> Result<AhoCorasick> {
+        Ok(AhoCorasick::builder()
+            .ascii_case_insensitive(!self.case_sensitive)
+            .build([text])?)
+    }
+}
+
 #[derive(Debug)]
 struct AutocloseRegion {
     selection_id: usize,
@@ -1579,6 +1665,7 @@ impl SelectSyntaxNodeHistory {
     }
 }
 
+#[derive(Clone, Debug)]
 enum SelectSyntaxNodeScrollBehavior {
     CursorTop,
     FitSelection,
@@ -1605,6 +1692,7 @@ pub enum GotoDefinitionKind {
 enum InlayHintRefreshReason {
     ModifiersChanged(bool),
     Toggle(bool),
+    ToggleKind(Option<InlayHintKind>),
     SettingsChange(InlayHintSettings),
     NewLinesShown,
     BufferEdited(HashSet<Arc<Language>>),
@@ -1617,6 +1705,7 @@ impl InlayHintRefreshReason {
         match self {
             Self::ModifiersChanged(_) => "modifiers changed",
             Self::Toggle(_) => "toggle",
+            Self::ToggleKind(_) => "toggle kind",
             Self::SettingsChange(_) => "settings change",
             Self::NewLinesShown => "new lines shown",
             Self::BufferEdited(_) => "buffer edited",
@@ -1849,8 +1938,13 @@ impl Editor {
             blink_manager
         });
 
-        let soft_wrap_mode_override =
-            matches!(mode, EditorMode::SingleLine).then(|| language_settings::SoftWrap::None);
+        let large_file_mode = full_mode
+            && buffer
+                .read(cx)
+                .as_singleton()
+                .is_some_and(|buffer| buffer.read(cx).is_large_file());
+        let soft_wrap_mode_override = (matches!(mode, EditorMode::SingleLine) || large_file_mode)
+            .then(|| language_settings::SoftWrap::None);
 
         let mut project_subscriptions = Vec::new();
         if full_mode && let Some(project) = project.as_ref() {
@@ -1859,7 +1953,7 @@ impl Editor {
                 window,
                 |editor, _, event, window, cx| match event {
                     project::Event::RefreshCodeLens => {
-                        // we always query lens with actions, without storing them, always refreshing them
+                        editor.refresh_code_lenses(None, window, cx);
                     }
                     project::Event::RefreshInlayHints => {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
@@ -2013,8 +2107,9 @@ impl Editor {
 
         let buffer_snapshot = buffer.read(cx).snapshot(cx);
 
-        let inlay_hint_settings =
+        let mut inlay_hint_settings =
             inlay_hint_settings(selections.newest_anchor().head(), &buffer_snapshot, cx);
+        inlay_hint_settings.enabled &= !large_file_mode;
         let focus_handle = cx.focus_handle();
         if !is_minimap {
             cx.on_focus(&focus_handle, window, Self::handle_focus)
@@ -2040,6 +2135,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -2055,6 +2154,7 @@ impl Editor {
                 .shared(),
             );
             code_action_providers.push(Rc::new(project) as Rc<_>);
+            code_action_providers.push(Rc::new(SpellCheckCodeActionProvider) as Rc<_>);
         }
 
         let mut editor = Self {
@@ -2082,6 +2182,7 @@ impl Editor {
             inline_diagnostics_update: Task::ready(()),
             inline_diagnostics: Vec::new(),
             soft_wrap_mode_override,
+            large_file_mode,
             diagnostics_max_severity,
             hard_wrap: None,
             completion_provider: project.clone().map(|project| Rc::new(project) as _),
@@ -2169,10 +2270,13 @@ impl Editor {
             last_bounds: None,
             last_position_map: None,
             expect_bounds_change: None,
+            search_origin: None,
+            spell_check_task: None,
             gutter_dimensions: GutterDimensions::default(),
             style: None,
             show_cursor_names: false,
             hovered_cursors: HashMap::default(),
+            remote_cursor_activity: HashMap::default(),
             next_editor_action_id: EditorActionId::default(),
             editor_actions: Rc::default(),
             edit_predictions_hidden_for_vim_mode: false,
@@ -2198,6 +2302,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
@@ -2228,6 +2333,7 @@ impl Editor {
             tasks_update_task: None,
             pull_diagnostics_task: Task::ready(()),
             colors: None,
+            code_lenses: None,
             next_color_inlay_id: 0,
             linked_edit_ranges: Default::default(),
             in_project_search: false,
@@ -2242,6 +2348,7 @@ impl Editor {
             toggle_fold_multiple_buffers: Task::ready(()),
             serialize_selections: Task::ready(()),
             serialize_folds: Task::ready(()),
+            serialize_file_state: Task::ready(()),
             text_style_refinement: None,
             load_diff_task: load_uncommitted_diff,
             temporary_diff_override: false,
@@ -2268,6 +2375,13 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmarks) = editor.bookmark_store.as_ref() {
+            editor
+                ._subscriptions
+                .push(cx.observe(bookmarks, |_, _, cx| {
+                    cx.notify();
+                }));
+        }
         editor.tasks_update_task = Some(editor.refresh_runnables(window, cx));
         editor._subscriptions.extend(project_subscriptions);
 
@@ -2377,6 +2491,7 @@ impl Editor {
             editor.minimap =
                 editor.create_minimap(EditorSettings::get_global(cx).minimap, window, cx);
             editor.colors = Some(LspColorData::new(cx));
+            editor.code_lenses = Some(CodeLensData::new(cx));
             editor.update_lsp_data(false, None, window, cx);
         }
 
@@ -2714,7 +2829,7 @@ impl Editor {
 
         EditorSnapshot {
             mode: self.mode.clone(),
-            show_gutter: self.show_gutter,
+            show_gutter: self.show_gutter && self.reading_mode(cx).is_none(),
             show_line_numbers: self.show_line_numbers,
             show_git_diff_gutter: self.show_git_diff_gutter,
             show_code_actions: self.show_code_actions,
@@ -3152,6 +3267,9 @@ impl Editor {
             self.refresh_document_highlights(cx);
             self.refresh_selected_text_highlights(false, window, cx);
             refresh_matching_bracket_highlights(self, window, cx);
+            if EditorSettings::get_global(cx).colorize_brackets == ColorizeBrackets::Enclosing {
+                refresh_bracket_colors(self, cx);
+            }
             self.update_visible_edit_prediction(window, cx);
             self.edit_prediction_requires_modifier_in_indent_conflict = true;
             linked_editing_ranges::refresh_linked_ranges(self, window, cx);
@@ -3337,6 +3455,8 @@ impl Editor {
                 select_next_state: self.select_next_state.clone(),
                 select_prev_state: self.select_prev_state.clone(),
                 add_selections_state: self.add_selections_state.clone(),
+                select_syntax_node_stack: self.select_syntax_node_history.stack.clone(),
+                buffer_edit_count: self.buffer.read(cx).read(cx).edit_count(),
             },
         };
         let (changed, result) = self.selections.change_with(cx, change);
@@ -4016,6 +4136,7 @@ impl Editor {
         }
 
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        self.confirm_completion_on_commit_character(&text, window, cx);
 
         let selections = self.selections.all_adjusted(cx);
         let mut bracket_inserted = false;
@@ -4241,14 +4362,27 @@ impl Editor {
             if !self.linked_edit_ranges.is_empty() {
                 let start_anchor = snapshot.anchor_before(selection.start);
 
-                let is_word_char = text.chars().next().is_none_or(|char| {
-                    let classifier = snapshot
-                        .char_classifier_at(start_anchor.to_offset(&snapshot))
-                        .ignore_punctuation(true);
-                    classifier.is_word(char)
+                let matches_word_pattern = start_anchor.buffer_id.and_then(|buffer_id| {
+                    let buffer = self.buffer.read(cx).buffer(buffer_id)?;
+                    self.linked_edit_ranges.edit_matches_word_pattern(
+                        buffer_id,
+                        start_anchor.text_anchor..anchor.text_anchor,
+                        &text,
+                        &buffer.read(cx),
+                    )
+                });
+                // Without a word pattern from the language server, only word characters keep
+                // the ranges linked.
+                let keeps_ranges_linked = matches_word_pattern.unwrap_or_else(|| {
+                    text.chars().next().is_none_or(|char| {
+                        let classifier = snapshot
+                            .char_classifier_at(start_anchor.to_offset(&snapshot))
+                            .ignore_punctuation(true);
+                        classifier.is_word(char)
+                    })
                 });
 
-                if is_word_char {
+                if keeps_ranges_linked {
                     if let Some(ranges) = self
                         .linked_editing_ranges_for(start_anchor.text_anchor..anchor.text_anchor, cx)
                     {
@@ -4353,9 +4487,9 @@ impl Editor {
             }
 
             let editor_settings = EditorSettings::get_global(cx);
-            if bracket_inserted
-                && (editor_settings.auto_signature_help
-                    || editor_settings.show_signature_help_after_edits)
+            if (editor_settings.auto_signature_help
+                || editor_settings.show_signature_help_after_edits)
+                && (bracket_inserted || this.is_signature_help_trigger(text, cx))
             {
                 this.show_signature_help(&ShowSignatureHelp, window, cx);
             }
@@ -4944,6 +5078,21 @@ impl Editor {
         }
     }
 
+    fn is_signature_help_trigger(&self, text: &str, cx: &App) -> bool {
+        let Some(project) = self.project.as_ref() else {
+            return false;
+        };
+        let position = self.selections.newest_anchor().head();
+        let Some((buffer, _)) = self.buffer.read(cx).text_anchor_for_position(position, cx) else {
+            return false;
+        };
+        project
+            .read(cx)
+            .lsp_store()
+            .read(cx)
+            .is_signature_help_trigger(&buffer, text, self.signature_help_state.is_shown(), cx)
+    }
+
     fn is_completion_trigger(
         &self,
         text: &str,
@@ -5136,10 +5285,38 @@ impl Editor {
         );
     }
 
+    pub fn toggle_type_inlay_hints(
+        &mut self,
+        _: &ToggleTypeInlayHints,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.refresh_inlay_hints(
+            InlayHintRefreshReason::ToggleKind(Some(InlayHintKind::Type)),
+            cx,
+        );
+    }
+
+    pub fn toggle_parameter_inlay_hints(
+        &mut self,
+        _: &ToggleParameterInlayHints,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.refresh_inlay_hints(
+            InlayHintRefreshReason::ToggleKind(Some(InlayHintKind::Parameter)),
+            cx,
+        );
+    }
+
     pub fn inlay_hints_enabled(&self) -> bool {
         self.inlay_hint_cache.enabled
     }
 
+    pub fn inlay_hint_kind_enabled(&self, kind: InlayHintKind) -> bool {
+        self.inlay_hint_cache.hint_kind_enabled(Some(kind))
+    }
+
     pub fn inline_values_enabled(&self) -> bool {
         self.inline_value_cache.enabled
     }
@@ -5173,6 +5350,7 @@ impl Editor {
             reason,
             InlayHintRefreshReason::SettingsChange(_)
                 | InlayHintRefreshReason::Toggle(_)
+                | InlayHintRefreshReason::ToggleKind(_)
                 | InlayHintRefreshReason::ExcerptsRemoved(_)
                 | InlayHintRefreshReason::ModifiersChanged(_)
         );
@@ -5218,6 +5396,20 @@ impl Editor {
                     return;
                 }
             }
+            InlayHintRefreshReason::ToggleKind(kind) => {
+                if let Some(InlaySplice {
+                    to_remove,
+                    to_insert,
+                }) = self.inlay_hint_cache.toggle_hint_kind(
+                    kind,
+                    &self.buffer,
+                    self.visible_inlay_hints(cx),
+                    cx,
+                ) {
+                    self.splice_inlays(&to_remove, to_insert, cx);
+                }
+                return;
+            }
             InlayHintRefreshReason::SettingsChange(new_settings) => {
                 match self.inlay_hint_cache.update_settings(
                     &self.buffer,
@@ -5675,6 +5867,7 @@ impl Editor {
             }) else {
                 return;
             };
+            let typed_query = query.is_some();
 
             // TODO: Ideally completions from different sources would be selectively re-queried, so
             // that having one source with `is_incomplete: true` doesn't cause all to be re-queried.
@@ -5812,6 +6005,9 @@ impl Editor {
                             editor.discard_edit_prediction(false, cx);
                         }
 
+                        if typed_query {
+                            editor.confirm_single_completion_match(window, cx);
+                        }
                         cx.notify();
                         return;
                     }
@@ -5898,6 +6094,65 @@ impl Editor {
         self.do_completion(action.item_ix, CompletionIntent::Compose, window, cx)
     }
 
+    /// Accepts the selected completion if `text` is one of its commit characters, so that
+    /// typing e.g. `.` or `(` completes the word before the character is inserted.
+    fn confirm_completion_on_commit_character(
+        &mut self,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let is_commit_character = match self.context_menu.borrow().as_ref() {
+            Some(CodeContextMenu::Completions(menu)) => {
+                let entries = menu.entries.borrow();
+                let completions = menu.completions.borrow();
+                entries
+                    .get(menu.selected_item)
+                    .and_then(|mat| completions.get(mat.candidate_id))
+                    .and_then(|completion| completion.source.lsp_completion(true))
+                    .and_then(|lsp_completion| {
+                        let commit_characters = lsp_completion.commit_characters.as_ref()?;
+                        Some(commit_characters.iter().any(|character| character == text))
+                    })
+                    .unwrap_or(false)
+            }
+            _ => false,
+        };
+        if is_commit_character
+            && let Some(task) = self.do_completion(None, CompletionIntent::Complete, window, cx)
+        {
+            task.detach_and_notify_err(window, cx);
+        }
+    }
+
+    /// Accepts the only completion left in the menu once typing has narrowed it down to it,
+    /// if `completions.auto_confirm_single_match` is enabled for the language at the cursor.
+    fn confirm_single_completion_match(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let is_single_match = match self.context_menu.borrow().as_ref() {
+            Some(CodeContextMenu::Completions(menu)) => {
+                menu.source == CompletionsMenuSource::Normal
+                    && !menu.is_incomplete
+                    && menu.entries.borrow().len() == 1
+            }
+            _ => false,
+        };
+        if !is_single_match {
+            return;
+        }
+        let cursor = self.selections.newest_anchor().head();
+        let auto_confirm = self
+            .buffer
+            .read(cx)
+            .language_settings_at(cursor, cx)
+            .completions
+            .auto_confirm_single_match;
+        if auto_confirm
+            && let Some(task) = self.do_completion(None, CompletionIntent::Complete, window, cx)
+        {
+            task.detach_and_notify_err(window, cx);
+        }
+    }
+
     fn do_completion(
         &mut self,
         item_ix: Option<usize>,
@@ -6813,6 +7068,25 @@ impl Editor {
             } else {
                 None
             };
+            // Without highlights from a language server, fall back to highlighting the
+            // identifiers in the syntax tree that match the one under the cursor.
+            let highlights = match highlights {
+                Some(highlights) if !highlights.is_empty() => Some(highlights),
+                _ => Some(
+                    cx.background_spawn(async move {
+                        snapshot
+                            .matching_identifier_ranges(start_word_range)
+                            .into_iter()
+                            .map(|range| DocumentHighlight {
+                                range: snapshot.anchor_after(range.start)
+                                    ..snapshot.anchor_before(range.end),
+                                kind: lsp::DocumentHighlightKind::TEXT,
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .await,
+                ),
+            };
 
             if let Some(highlights) = highlights {
                 this.update(cx, |this, cx| {
@@ -7341,6 +7615,67 @@ impl Editor {
         self.show_cursor_names(window, cx);
     }
 
+    /// Records which collaborators moved their cursors since the last time this was called, and
+    /// returns the replicas of those who haven't moved them for longer than the
+    /// `collaborator_cursors.fade_after_ms` setting.
+    pub(crate) fn idle_remote_replicas(
+        &mut self,
+        snapshot: &EditorSnapshot,
+        cx: &mut Context<Self>,
+    ) -> HashSet<ReplicaId> {
+        let fade_after = Duration::from_millis(
+            EditorSettings::get_global(cx)
+                .collaborator_cursors
+                .fade_after_ms,
+        );
+        let Some(collaboration_hub) = self.collaboration_hub.as_ref() else {
+            return HashSet::default();
+        };
+
+        let range = Anchor::min()..Anchor::max();
+        let mut heads_by_replica = HashMap::<ReplicaId, Vec<Anchor>>::default();
+        for selection in snapshot.remote_selections_in_range(&range, collaboration_hub.as_ref(), cx)
+        {
+            heads_by_replica
+                .entry(selection.replica_id)
+                .or_default()
+                .push(selection.selection.head());
+        }
+        self.remote_cursor_activity
+            .retain(|replica_id, _| heads_by_replica.contains_key(replica_id));
+
+        let now = cx.background_executor().now();
+        let mut idle_replicas = HashSet::default();
+        for (replica_id, heads) in heads_by_replica {
+            if let Some(activity) = self.remote_cursor_activity.get(&replica_id)
+                && activity.heads == heads
+            {
+                if !fade_after.is_zero() && now.duration_since(activity.last_active) >= fade_after {
+                    idle_replicas.insert(replica_id);
+                }
+                continue;
+            }
+
+            let repaint_when_idle = if fade_after.is_zero() {
+                Task::ready(())
+            } else {
+                cx.spawn(async move |editor, cx| {
+                    cx.background_executor().timer(fade_after).await;
+                    editor.update(cx, |_, cx| cx.notify()).ok();
+                })
+            };
+            self.remote_cursor_activity.insert(
+                replica_id,
+                RemoteCursorActivity {
+                    heads,
+                    last_active: now,
+                    _repaint_when_idle: repaint_when_idle,
+                },
+            );
+        }
+        idle_replicas
+    }
+
     fn show_cursor_names(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.show_cursor_names = true;
         cx.notify();
@@ -10428,21 +10763,9 @@ impl Editor {
         self.transact(window, cx, |this, window, cx| {
             for row_range in row_ranges.into_iter().rev() {
                 for row in row_range.iter_rows().rev() {
-                    let end_of_line = Point::new(row.0, snapshot.line_len(row));
-                    let next_line_row = row.next_row();
-                    let indent = snapshot.indent_size_for_line(next_line_row);
-                    let start_of_next_line = Point::new(next_line_row.0, indent.len);
-
-                    let replace =
-                        if snapshot.line_len(next_line_row) > indent.len && insert_whitespace {
-                            " "
-                        } else {
-                            ""
-                        };
-
-                    this.buffer.update(cx, |buffer, cx| {
-                        buffer.edit([(end_of_line..start_of_next_line, replace)], None, cx)
-                    });
+                    let (range, replace) = join_line_edit(&snapshot, row, insert_whitespace);
+                    this.buffer
+                        .update(cx, |buffer, cx| buffer.edit([(range, replace)], None, cx));
                 }
             }
 
@@ -10466,6 +10789,26 @@ impl Editor {
         self.manipulate_immutable_lines(window, cx, |lines| lines.sort())
     }
 
+    pub fn sort_lines_descending(
+        &mut self,
+        _: &SortLinesDescending,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.manipulate_immutable_lines(window, cx, |lines| lines.sort_by(|a, b| b.cmp(a)))
+    }
+
+    pub fn sort_lines_natural(
+        &mut self,
+        _: &SortLinesNatural,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.manipulate_immutable_lines(window, cx, |lines| {
+            lines.sort_by(|a, b| util::paths::natural_sort(a, b))
+        })
+    }
+
     pub fn sort_lines_by_length(
         &mut self,
         _: &SortLinesByLength,
@@ -10633,6 +10976,47 @@ impl Editor {
         self.restore_hunks_in_ranges(selections, window, cx);
     }
 
+    pub fn copy_hunk_original_text(
+        &mut self,
+        _: &CopyHunkOriginalText,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selections = self
+            .selections
+            .all(cx)
+            .into_iter()
+            .map(|s| s.range())
+            .collect();
+        self.copy_original_text_of_hunks_in_ranges(selections, window, cx);
+    }
+
+    /// Copies the text the diff hunks intersecting `ranges` replaced, as it was in the diff
+    /// base, to the clipboard.
+    pub fn copy_original_text_of_hunks_in_ranges(
+        &mut self,
+        ranges: Vec<Range<Point>>,
+        window: &mut Window,
+        cx: &mut Context<Editor>,
+    ) {
+        let hunks = self.snapshot(window, cx).hunks_for_ranges(ranges);
+        let buffer = self.buffer.read(cx);
+        let mut original_text = String::new();
+        for hunk in hunks {
+            let Some(diff) = buffer.diff_for(hunk.buffer_id) else {
+                continue;
+            };
+            original_text.extend(
+                diff.read(cx)
+                    .base_text()
+                    .text_for_range(hunk.diff_base_byte_range.clone()),
+            );
+        }
+        if !original_text.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(original_text));
+        }
+    }
+
     pub fn restore_hunks_in_ranges(
         &mut self,
         ranges: Vec<Range<Point>>,
@@ -10977,6 +11361,116 @@ impl Editor {
         self.breakpoint_store.clone()
     }
 
+    pub fn toggle_bookmark(
+        &mut self,
+        _: &crate::actions::ToggleBookmark,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut toggled_rows = HashSet::default();
+        for selection in self.selections.disjoint_anchors_arc().iter() {
+            let head = selection.head();
+            // Multiple cursors on the same line should only toggle its bookmark once.
+            if !toggled_rows.insert(head.to_point(&snapshot).row) {
+                continue;
+            }
+            let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+            else {
+                continue;
+            };
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.toggle_bookmark(buffer, position, cx)
+            });
+        }
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &crate::actions::GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Next, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &crate::actions::GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Prev, window, cx);
+    }
+
+    /// Moves the cursor to the closest bookmark in the given direction,
+    /// wrapping around at either end of the editor.
+    fn go_to_bookmark(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let bookmarks = self.bookmarks_in_range(Anchor::min()..Anchor::max(), &snapshot, cx);
+        let cursor_row = self
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(&snapshot)
+            .row;
+        let bookmark_row = |anchor: &Anchor| anchor.to_point(&snapshot).row;
+        let target = match direction {
+            Direction::Next => bookmarks
+                .iter()
+                .find(|anchor| bookmark_row(anchor) > cursor_row)
+                .or(bookmarks.first()),
+            Direction::Prev => bookmarks
+                .iter()
+                .rev()
+                .find(|anchor| bookmark_row(anchor) < cursor_row)
+                .or(bookmarks.last()),
+        };
+        let Some(target) = target.copied() else {
+            return;
+        };
+        self.change_selections(Default::default(), window, cx, |selections| {
+            selections.select_anchor_ranges([target..target]);
+        });
+    }
+
+    /// Returns the positions of the bookmarks within `range`, in order.
+    pub fn bookmarks_in_range(
+        &self,
+        range: Range<Anchor>,
+        snapshot: &MultiBufferSnapshot,
+        cx: &App,
+    ) -> Vec<Anchor> {
+        let Some(bookmark_store) = &self.bookmark_store else {
+            return Vec::new();
+        };
+        let bookmark_store = bookmark_store.read(cx);
+        let mut bookmarks = Vec::new();
+        for (buffer_snapshot, buffer_range, excerpt_id) in snapshot.range_to_buffer_ranges(range) {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let buffer_range = buffer_snapshot.anchor_before(buffer_range.start)
+                ..buffer_snapshot.anchor_after(buffer_range.end);
+            bookmarks.extend(
+                bookmark_store
+                    .bookmarks(&buffer, Some(buffer_range), buffer_snapshot, cx)
+                    .filter_map(|bookmark| {
+                        snapshot.anchor_in_excerpt(excerpt_id, bookmark.position)
+                    }),
+            );
+        }
+        bookmarks
+    }
+
     pub fn prepare_restore_change(
         &self,
         revert_changes: &mut HashMap<BufferId, Vec<(Range<text::Anchor>, Rope)>>,
@@ -11479,9 +11973,11 @@ impl Editor {
         });
     }
 
-    pub fn move_selection_on_drop(
+    /// Moves or copies the given selections to `target`. When several selections are dropped,
+    /// their text is joined by newlines, as when copying them.
+    pub fn move_selections_on_drop(
         &mut self,
-        selection: &Selection<Anchor>,
+        selections: &[Selection<Anchor>],
         target: DisplayPoint,
         is_cut: bool,
         window: &mut Window,
@@ -11493,11 +11989,15 @@ impl Editor {
         let insert_point = display_map
             .clip_point(target, Bias::Left)
             .to_point(&display_map);
-        let text = buffer
-            .text_for_range(selection.start..selection.end)
-            .collect::<String>();
-        if is_cut {
-            edits.push(((selection.start..selection.end), String::new()));
+        let mut text = String::new();
+        for (ix, selection) in selections.iter().enumerate() {
+            if ix > 0 {
+                text.push('\n');
+            }
+            text.extend(buffer.text_for_range(selection.start..selection.end));
+            if is_cut {
+                edits.push(((selection.start..selection.end), String::new()));
+            }
         }
         let insert_anchor = buffer.anchor_before(insert_point);
         edits.push(((insert_anchor..insert_anchor), text));
@@ -11814,18 +12314,95 @@ impl Editor {
         });
     }
 
-    pub fn transpose(&mut self, _: &Transpose, window: &mut Window, cx: &mut Context<Self>) {
-        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
-        let text_layout_details = &self.text_layout_details(window);
-        self.transact(window, cx, |this, window, cx| {
-            let edits = this.change_selections(Default::default(), window, cx, |s| {
-                let mut edits: Vec<(Range<usize>, String)> = Default::default();
-                s.move_with(|display_map, selection| {
-                    if !selection.is_empty() {
-                        return;
-                    }
-
-                    let mut head = selection.head();
+    pub fn move_syntax_node_up(
+        &mut self,
+        _: &MoveSyntaxNodeUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_syntax_nodes(false, window, cx);
+    }
+
+    pub fn move_syntax_node_down(
+        &mut self,
+        _: &MoveSyntaxNodeDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_syntax_nodes(true, window, cx);
+    }
+
+    /// Swaps the syntax node around each selection with its next or previous sibling,
+    /// keeping the separators between them in place.
+    fn move_syntax_nodes(&mut self, next: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut swapped_ranges: Vec<Range<usize>> = Vec::new();
+        let mut new_selections = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let Some((node, sibling)) = buffer.syntax_node_to_swap(selection.range(), next) else {
+                new_selections.push(selection);
+                continue;
+            };
+            let spanned_range = node.start.min(sibling.start)..node.end.max(sibling.end);
+            if swapped_ranges.iter().any(|swapped| {
+                swapped.start < spanned_range.end && spanned_range.start < swapped.end
+            }) {
+                new_selections.push(selection);
+                continue;
+            }
+
+            // Swapping the nodes keeps the length of the text spanning them, so only the
+            // selections within the moved node change position.
+            let new_node_start = if next {
+                node.start + sibling.len() + (sibling.start - node.end)
+            } else {
+                sibling.start
+            };
+            new_selections.push(Selection {
+                id: selection.id,
+                start: selection.start - node.start + new_node_start,
+                end: selection.end - node.start + new_node_start,
+                reversed: selection.reversed,
+                goal: SelectionGoal::None,
+            });
+
+            let node_text = buffer.text_for_range(node.clone()).collect::<String>();
+            let sibling_text = buffer.text_for_range(sibling.clone()).collect::<String>();
+            swapped_ranges.push(spanned_range);
+            edits.push((node, sibling_text));
+            edits.push((sibling, node_text));
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Default::default(), window, cx, |s| s.select(new_selections));
+        });
+    }
+
+    pub fn transpose(&mut self, _: &Transpose, window: &mut Window, cx: &mut Context<Self>) {
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        let text_layout_details = &self.text_layout_details(window);
+        self.transact(window, cx, |this, window, cx| {
+            let edits = this.change_selections(Default::default(), window, cx, |s| {
+                let mut edits: Vec<(Range<usize>, String)> = Default::default();
+                s.move_with(|display_map, selection| {
+                    if !selection.is_empty() {
+                        return;
+                    }
+
+                    let mut head = selection.head();
                     let mut transpose_offset = head.to_offset(display_map, Bias::Right);
                     if head.column() == display_map.line_len(head.row()) {
                         transpose_offset = display_map
@@ -11978,7 +12555,18 @@ impl Editor {
                                     }
                                 })
                             })
-                            .flatten();
+                            .flatten()
+                            .or_else(|| {
+                                // List items in comments are wrapped like Markdown's.
+                                let prefix = match comment_delimiters.as_ref()? {
+                                    CommentFormat::Line(prefix)
+                                    | CommentFormat::BlockLine(prefix) => prefix,
+                                    _ => return None,
+                                };
+                                let text_after_prefix =
+                                    line_text_after_indent.strip_prefix(prefix.as_str())?;
+                                Some(comment_list_item_prefix(text_after_prefix)?.to_string())
+                            });
                         (comment_delimiters, rewrap_prefix)
                     } else {
                         (None, None)
@@ -12049,7 +12637,7 @@ impl Editor {
             wrap_range,
             mut indent_size,
             comment_prefix,
-            rewrap_prefix,
+            mut rewrap_prefix,
             from_empty_selection,
         ) in wrap_ranges
         {
@@ -12101,14 +12689,31 @@ impl Editor {
                 continue;
             }
 
+            // Rows that start list items in comments end the paragraph above them.
+            let comment_list_item_at = |row: u32| {
+                let line_start = Point::new(row, 0);
+                let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+                if !inside_comment || !buffer.contains_str_at(line_start, &line_prefix) {
+                    return None;
+                }
+                let line = buffer
+                    .text_for_range(line_start..line_end)
+                    .collect::<String>();
+                comment_list_item_prefix(&line[line_prefix.len()..]).map(str::to_string)
+            };
+
             if from_empty_selection {
-                'expand_upwards: while start_row > 0 {
+                'expand_upwards: while start_row > 0 && rewrap_prefix.is_none() {
                     let prev_row = start_row - 1;
                     if buffer.contains_str_at(Point::new(prev_row, 0), &line_prefix)
                         && buffer.line_len(MultiBufferRow(prev_row)) as usize > line_prefix.len()
                         && !buffer.is_line_blank(MultiBufferRow(prev_row))
                     {
                         start_row = prev_row;
+                        if let Some(list_item_prefix) = comment_list_item_at(prev_row) {
+                            rewrap_prefix = Some(list_item_prefix);
+                            break 'expand_upwards;
+                        }
                     } else {
                         break 'expand_upwards;
                     }
@@ -12119,6 +12724,7 @@ impl Editor {
                     if buffer.contains_str_at(Point::new(next_row, 0), &line_prefix)
                         && buffer.line_len(MultiBufferRow(next_row)) as usize > line_prefix.len()
                         && !buffer.is_line_blank(MultiBufferRow(next_row))
+                        && comment_list_item_at(next_row).is_none()
                     {
                         end_row = next_row;
                     } else {
@@ -12139,7 +12745,12 @@ impl Editor {
                 .map(|(ix, line)| {
                     let line_trimmed = line.trim_start();
                     if rewrap_prefix.is_some() && ix > 0 {
-                        Ok(line_trimmed)
+                        // The continuation lines of list items are aligned with their text,
+                        // after any comment prefix.
+                        Ok(line_trimmed
+                            .strip_prefix(line_prefix.trim_start())
+                            .unwrap_or(line_trimmed)
+                            .trim_start())
                     } else if let Some(
                         CommentFormat::BlockCommentWithStart(BlockCommentConfig {
                             start,
@@ -12201,7 +12812,7 @@ impl Editor {
             });
 
             let subsequent_lines_prefix = if let Some(rewrap_prefix_str) = &rewrap_prefix {
-                format!("{}{}", indent_prefix, " ".repeat(rewrap_prefix_str.len()))
+                format!("{line_prefix}{}", " ".repeat(rewrap_prefix_str.len()))
             } else {
                 line_prefix.clone()
             };
@@ -12309,6 +12920,7 @@ impl Editor {
     pub fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
         let item = self.cut_common(true, window, cx);
+        record_clipboard_history(self, &item, cx);
         cx.write_to_clipboard(item);
     }
 
@@ -12432,17 +13044,49 @@ impl Editor {
             }
         }
 
-        cx.write_to_clipboard(ClipboardItem::new_string_with_json_metadata(
+        drop(buffer);
+
+        let item = ClipboardItem::new_string_with_json_metadata(text, clipboard_selections);
+        record_clipboard_history(self, &item, cx);
+        cx.write_to_clipboard(item);
+    }
+
+    /// Pastes text from the clipboard history, putting each of the segments that were copied
+    /// from multiple selections at its own cursor when there are as many cursors as segments.
+    pub fn paste_clipboard_history_entry(
+        &mut self,
+        entry: &ClipboardHistoryEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        self.do_paste(&entry.text, entry.selections.clone(), true, window, cx);
+    }
+
+    pub fn do_paste(
+        &mut self,
+        text: &String,
+        clipboard_selections: Option<Vec<ClipboardSelection>>,
+        handle_entire_lines: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.do_paste_inner(
             text,
             clipboard_selections,
-        ));
+            handle_entire_lines,
+            true,
+            window,
+            cx,
+        );
     }
 
-    pub fn do_paste(
+    fn do_paste_inner(
         &mut self,
         text: &String,
         clipboard_selections: Option<Vec<ClipboardSelection>>,
         handle_entire_lines: bool,
+        reindent: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -12469,9 +13113,10 @@ impl Editor {
 
                 this.buffer.update(cx, |buffer, cx| {
                     let snapshot = buffer.read(cx);
-                    auto_indent_on_paste = snapshot
-                        .language_settings_at(cursor_offset, cx)
-                        .auto_indent_on_paste;
+                    auto_indent_on_paste = reindent
+                        && snapshot
+                            .language_settings_at(cursor_offset, cx)
+                            .auto_indent_on_paste;
 
                     let mut start_offset = 0;
                     let mut edits = Vec::new();
@@ -12571,6 +13216,24 @@ impl Editor {
     }
 
     pub fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        self.paste_from_clipboard(true, window, cx);
+    }
+
+    pub fn paste_without_reindent(
+        &mut self,
+        _: &PasteWithoutReindent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.paste_from_clipboard(false, window, cx);
+    }
+
+    fn paste_from_clipboard(
+        &mut self,
+        reindent: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
         if let Some(item) = cx.read_from_clipboard() {
             let entries = item.entries();
@@ -12578,19 +13241,37 @@ impl Editor {
             match entries.first() {
                 // For now, we only support applying metadata if there's one string. In the future, we can incorporate all the selections
                 // of all the pasted entries.
-                Some(ClipboardEntry::String(clipboard_string)) if entries.len() == 1 => self
-                    .do_paste(
-                        clipboard_string.text(),
-                        clipboard_string.metadata_json::<Vec<ClipboardSelection>>(),
-                        true,
-                        window,
-                        cx,
-                    ),
-                _ => self.do_paste(&item.text().unwrap_or_default(), None, true, window, cx),
+                Some(ClipboardEntry::String(clipboard_string)) if entries.len() == 1 => {
+                    let text = clipboard_string.text();
+                    let clipboard_selections = clipboard_string
+                        .metadata_json::<Vec<ClipboardSelection>>()
+                        .or_else(|| Self::external_clipboard_selections(text));
+                    self.do_paste_inner(text, clipboard_selections, true, reindent, window, cx)
+                }
+                _ => {
+                    let text = item.text().unwrap_or_default();
+                    let clipboard_selections = Self::external_clipboard_selections(&text);
+                    self.do_paste_inner(&text, clipboard_selections, true, reindent, window, cx)
+                }
             }
         }
     }
 
+    /// Text copied from outside of Zed has no selection metadata. Treat multi-line text as
+    /// a single block, using the indentation of its first line, so that it's reindented to
+    /// match the place it's pasted into.
+    fn external_clipboard_selections(text: &str) -> Option<Vec<ClipboardSelection>> {
+        if !text.contains('\n') {
+            return None;
+        }
+        let first_line_indent = text.chars().take_while(|c| *c == ' ' || *c == '\t').count() as u32;
+        Some(vec![ClipboardSelection {
+            len: text.len(),
+            is_entire_line: false,
+            first_line_indent,
+        }])
+    }
+
     pub fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
         if self.read_only(cx) {
             return;
@@ -13232,7 +13913,7 @@ impl Editor {
 
     pub fn delete_to_previous_subword_start(
         &mut self,
-        _: &DeleteToPreviousSubwordStart,
+        action: &DeleteToPreviousSubwordStart,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -13242,9 +13923,17 @@ impl Editor {
             this.change_selections(Default::default(), window, cx, |s| {
                 s.move_with(|map, selection| {
                     if selection.is_empty() {
-                        let mut cursor = movement::previous_subword_start(map, selection.head());
-                        cursor =
-                            movement::adjust_greedy_deletion(map, selection.head(), cursor, false);
+                        let mut cursor = if action.ignore_newlines {
+                            movement::previous_subword_start(map, selection.head())
+                        } else {
+                            movement::previous_subword_start_or_newline(map, selection.head())
+                        };
+                        cursor = movement::adjust_greedy_deletion(
+                            map,
+                            selection.head(),
+                            cursor,
+                            action.ignore_brackets,
+                        );
                         selection.set_head(cursor, SelectionGoal::None);
                     }
                 });
@@ -13341,7 +14030,7 @@ impl Editor {
 
     pub fn delete_to_next_subword_end(
         &mut self,
-        _: &DeleteToNextSubwordEnd,
+        action: &DeleteToNextSubwordEnd,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -13350,9 +14039,17 @@ impl Editor {
             this.change_selections(Default::default(), window, cx, |s| {
                 s.move_with(|map, selection| {
                     if selection.is_empty() {
-                        let mut cursor = movement::next_subword_end(map, selection.head());
-                        cursor =
-                            movement::adjust_greedy_deletion(map, selection.head(), cursor, false);
+                        let mut cursor = if action.ignore_newlines {
+                            movement::next_subword_end(map, selection.head())
+                        } else {
+                            movement::next_subword_end_or_newline(map, selection.head())
+                        };
+                        cursor = movement::adjust_greedy_deletion(
+                            map,
+                            selection.head(),
+                            cursor,
+                            action.ignore_brackets,
+                        );
                         selection.set_head(cursor, SelectionGoal::None);
                     }
                 });
@@ -14181,6 +14878,14 @@ impl Editor {
     ) -> Result<()> {
         let buffer = &display_map.buffer_snapshot;
         let mut selections = self.selections.all::<usize>(cx);
+        let options = OccurrenceSearchOptions::get(cx);
+        if self
+            .select_next_state
+            .as_ref()
+            .is_some_and(|state| state.options != options)
+        {
+            self.select_next_state = None;
+        }
         if let Some(mut select_next_state) = self.select_next_state.take() {
             let query = &select_next_state.query;
             if !select_next_state.done {
@@ -14295,9 +15000,10 @@ impl Editor {
                         .collect::<String>();
                     let is_empty = query.is_empty();
                     let select_state = SelectNextState {
-                        query: AhoCorasick::new(&[query])?,
+                        query: options.query(&query)?,
                         wordwise: true,
                         done: is_empty,
+                        options,
                     };
                     self.select_next_state = Some(select_state);
                 } else {
@@ -14305,9 +15011,10 @@ impl Editor {
                 }
             } else if let Some(selected_text) = selected_text {
                 self.select_next_state = Some(SelectNextState {
-                    query: AhoCorasick::new(&[selected_text])?,
-                    wordwise: false,
+                    query: options.query(&selected_text)?,
+                    wordwise: options.whole_word,
                     done: false,
+                    options,
                 });
                 self.select_next_match_internal(
                     display_map,
@@ -14406,6 +15113,14 @@ impl Editor {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = &display_map.buffer_snapshot;
         let mut selections = self.selections.all::<usize>(cx);
+        let options = OccurrenceSearchOptions::get(cx);
+        if self
+            .select_prev_state
+            .as_ref()
+            .is_some_and(|state| state.options != options)
+        {
+            self.select_prev_state = None;
+        }
         if let Some(mut select_prev_state) = self.select_prev_state.take() {
             let query = &select_prev_state.query;
             if !select_prev_state.done {
@@ -14513,9 +15228,10 @@ impl Editor {
                         .collect::<String>();
                     let is_empty = query.is_empty();
                     let select_state = SelectNextState {
-                        query: AhoCorasick::new(&[query.chars().rev().collect::<String>()])?,
+                        query: options.query(&query.chars().rev().collect::<String>())?,
                         wordwise: true,
                         done: is_empty,
+                        options,
                     };
                     self.select_prev_state = Some(select_state);
                 } else {
@@ -14523,9 +15239,10 @@ impl Editor {
                 }
             } else if let Some(selected_text) = selected_text {
                 self.select_prev_state = Some(SelectNextState {
-                    query: AhoCorasick::new(&[selected_text.chars().rev().collect::<String>()])?,
-                    wordwise: false,
+                    query: options.query(&selected_text.chars().rev().collect::<String>())?,
+                    wordwise: options.whole_word,
                     done: false,
+                    options,
                 });
                 self.select_previous(action, window, cx)?;
             }
@@ -14679,19 +15396,6 @@ impl Editor {
 
             // TODO: Handle selections that cross excerpts
             for selection in &mut selections {
-                let start_column = snapshot
-                    .indent_size_for_line(MultiBufferRow(selection.start.row))
-                    .len;
-                let language = if let Some(language) =
-                    snapshot.language_scope_at(Point::new(selection.start.row, start_column))
-                {
-                    language
-                } else {
-                    continue;
-                };
-
-                selection_edit_ranges.clear();
-
                 // If multiple selections contain a given row, avoid processing that
                 // row more than once.
                 let mut start_row = MultiBufferRow(selection.start.row);
@@ -14710,109 +15414,153 @@ impl Editor {
                     continue;
                 }
 
-                // If the language has line comments, toggle those.
-                let mut full_comment_prefixes = language.line_comment_prefixes().to_vec();
-
-                // If ignore_indent is set, trim spaces from the right side of all full_comment_prefixes
-                if ignore_indent {
-                    full_comment_prefixes = full_comment_prefixes
-                        .into_iter()
-                        .map(|s| Arc::from(s.trim_end()))
-                        .collect();
+                // Selections can span several syntax layers, e.g. a `<script>` tag and
+                // its contents, so toggle each run of rows with the comment syntax of
+                // the language at the start of those rows.
+                let mut row_groups =
+                    Vec::<(language::LanguageScope, MultiBufferRow, MultiBufferRow)>::new();
+                let mut leading_blank_row = None;
+                for row in start_row.0..=end_row.0 {
+                    let row = MultiBufferRow(row);
+                    let indent = snapshot.indent_size_for_line(row).len;
+                    let scope = if start_row < end_row && snapshot.is_line_blank(row) {
+                        None
+                    } else {
+                        snapshot.language_scope_at(Point::new(row.0, indent))
+                    };
+                    let Some(scope) = scope else {
+                        match row_groups.last_mut() {
+                            Some((_, _, group_end_row)) => *group_end_row = row,
+                            None => {
+                                leading_blank_row.get_or_insert(row);
+                            }
+                        }
+                        continue;
+                    };
+                    match row_groups.last_mut() {
+                        Some((group_scope, _, group_end_row))
+                            if group_scope.line_comment_prefixes()
+                                == scope.line_comment_prefixes()
+                                && group_scope.block_comment() == scope.block_comment() =>
+                        {
+                            *group_end_row = row;
+                        }
+                        _ => {
+                            let group_start_row = leading_blank_row.take().unwrap_or(row);
+                            row_groups.push((scope, group_start_row, row));
+                        }
+                    }
                 }
 
-                if !full_comment_prefixes.is_empty() {
-                    let first_prefix = full_comment_prefixes
-                        .first()
-                        .expect("prefixes is non-empty");
-                    let prefix_trimmed_lengths = full_comment_prefixes
-                        .iter()
-                        .map(|p| p.trim_end_matches(' ').len())
-                        .collect::<SmallVec<[usize; 4]>>();
+                for (language, start_row, end_row) in row_groups {
+                    selection_edit_ranges.clear();
 
-                    let mut all_selection_lines_are_comments = true;
+                    // If the language has line comments, toggle those.
+                    let mut full_comment_prefixes = language.line_comment_prefixes().to_vec();
 
-                    for row in start_row.0..=end_row.0 {
-                        let row = MultiBufferRow(row);
-                        if start_row < end_row && snapshot.is_line_blank(row) {
-                            continue;
-                        }
+                    // If ignore_indent is set, trim spaces from the right side of all full_comment_prefixes
+                    if ignore_indent {
+                        full_comment_prefixes = full_comment_prefixes
+                            .into_iter()
+                            .map(|s| Arc::from(s.trim_end()))
+                            .collect();
+                    }
 
-                        let prefix_range = full_comment_prefixes
-                            .iter()
-                            .zip(prefix_trimmed_lengths.iter().copied())
-                            .map(|(prefix, trimmed_prefix_len)| {
-                                comment_prefix_range(
-                                    snapshot.deref(),
-                                    row,
-                                    &prefix[..trimmed_prefix_len],
-                                    &prefix[trimmed_prefix_len..],
-                                    ignore_indent,
-                                )
-                            })
-                            .max_by_key(|range| range.end.column - range.start.column)
+                    if !full_comment_prefixes.is_empty() {
+                        let first_prefix = full_comment_prefixes
+                            .first()
                             .expect("prefixes is non-empty");
+                        let prefix_trimmed_lengths = full_comment_prefixes
+                            .iter()
+                            .map(|p| p.trim_end_matches(' ').len())
+                            .collect::<SmallVec<[usize; 4]>>();
 
-                        if prefix_range.is_empty() {
-                            all_selection_lines_are_comments = false;
-                        }
+                        let mut all_selection_lines_are_comments = true;
 
-                        selection_edit_ranges.push(prefix_range);
-                    }
+                        for row in start_row.0..=end_row.0 {
+                            let row = MultiBufferRow(row);
+                            if start_row < end_row && snapshot.is_line_blank(row) {
+                                continue;
+                            }
 
-                    if all_selection_lines_are_comments {
-                        edits.extend(
-                            selection_edit_ranges
+                            let prefix_range = full_comment_prefixes
                                 .iter()
-                                .cloned()
-                                .map(|range| (range, empty_str.clone())),
+                                .zip(prefix_trimmed_lengths.iter().copied())
+                                .map(|(prefix, trimmed_prefix_len)| {
+                                    comment_prefix_range(
+                                        snapshot.deref(),
+                                        row,
+                                        &prefix[..trimmed_prefix_len],
+                                        &prefix[trimmed_prefix_len..],
+                                        ignore_indent,
+                                    )
+                                })
+                                .max_by_key(|range| range.end.column - range.start.column)
+                                .expect("prefixes is non-empty");
+
+                            if prefix_range.is_empty() {
+                                all_selection_lines_are_comments = false;
+                            }
+
+                            selection_edit_ranges.push(prefix_range);
+                        }
+
+                        if all_selection_lines_are_comments {
+                            edits.extend(
+                                selection_edit_ranges
+                                    .iter()
+                                    .cloned()
+                                    .map(|range| (range, empty_str.clone())),
+                            );
+                        } else {
+                            let min_column = selection_edit_ranges
+                                .iter()
+                                .map(|range| range.start.column)
+                                .min()
+                                .unwrap_or(0);
+                            edits.extend(selection_edit_ranges.iter().map(|range| {
+                                let position = Point::new(range.start.row, min_column);
+                                (position..position, first_prefix.clone())
+                            }));
+                        }
+                    } else if let Some(BlockCommentConfig {
+                        start: full_comment_prefix,
+                        end: comment_suffix,
+                        ..
+                    }) = language.block_comment()
+                    {
+                        let comment_prefix = full_comment_prefix.trim_end_matches(' ');
+                        let comment_prefix_whitespace =
+                            &full_comment_prefix[comment_prefix.len()..];
+                        let prefix_range = comment_prefix_range(
+                            snapshot.deref(),
+                            start_row,
+                            comment_prefix,
+                            comment_prefix_whitespace,
+                            ignore_indent,
+                        );
+                        let suffix_range = comment_suffix_range(
+                            snapshot.deref(),
+                            end_row,
+                            comment_suffix.trim_start_matches(' '),
+                            comment_suffix.starts_with(' '),
                         );
-                    } else {
-                        let min_column = selection_edit_ranges
-                            .iter()
-                            .map(|range| range.start.column)
-                            .min()
-                            .unwrap_or(0);
-                        edits.extend(selection_edit_ranges.iter().map(|range| {
-                            let position = Point::new(range.start.row, min_column);
-                            (position..position, first_prefix.clone())
-                        }));
-                    }
-                } else if let Some(BlockCommentConfig {
-                    start: full_comment_prefix,
-                    end: comment_suffix,
-                    ..
-                }) = language.block_comment()
-                {
-                    let comment_prefix = full_comment_prefix.trim_end_matches(' ');
-                    let comment_prefix_whitespace = &full_comment_prefix[comment_prefix.len()..];
-                    let prefix_range = comment_prefix_range(
-                        snapshot.deref(),
-                        start_row,
-                        comment_prefix,
-                        comment_prefix_whitespace,
-                        ignore_indent,
-                    );
-                    let suffix_range = comment_suffix_range(
-                        snapshot.deref(),
-                        end_row,
-                        comment_suffix.trim_start_matches(' '),
-                        comment_suffix.starts_with(' '),
-                    );
 
-                    if prefix_range.is_empty() || suffix_range.is_empty() {
-                        edits.push((
-                            prefix_range.start..prefix_range.start,
-                            full_comment_prefix.clone(),
-                        ));
-                        edits.push((suffix_range.end..suffix_range.end, comment_suffix.clone()));
-                        suffixes_inserted.push((end_row, comment_suffix.len()));
+                        if prefix_range.is_empty() || suffix_range.is_empty() {
+                            edits.push((
+                                prefix_range.start..prefix_range.start,
+                                full_comment_prefix.clone(),
+                            ));
+                            edits
+                                .push((suffix_range.end..suffix_range.end, comment_suffix.clone()));
+                            suffixes_inserted.push((end_row, comment_suffix.len()));
+                        } else {
+                            edits.push((prefix_range, empty_str.clone()));
+                            edits.push((suffix_range, empty_str.clone()));
+                        }
                     } else {
-                        edits.push((prefix_range, empty_str.clone()));
-                        edits.push((suffix_range, empty_str.clone()));
+                        continue;
                     }
-                } else {
-                    continue;
                 }
             }
 
@@ -15270,7 +16018,174 @@ impl Editor {
             })
             .collect::<Vec<_>>();
 
-        if selected_sibling {
+        if selected_sibling {
+            self.change_selections(
+                SelectionEffects::scroll(Autoscroll::fit()),
+                window,
+                cx,
+                |s| {
+                    s.select(new_selections);
+                },
+            );
+        }
+    }
+
+    pub fn go_to_next_function(
+        &mut self,
+        _: &GoToNextFunction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, range| {
+            adjacent_text_object_start(
+                buffer,
+                offset,
+                range,
+                TextObject::AroundFunction,
+                Direction::Next,
+            )
+        });
+    }
+
+    pub fn go_to_previous_function(
+        &mut self,
+        _: &GoToPreviousFunction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, range| {
+            adjacent_text_object_start(
+                buffer,
+                offset,
+                range,
+                TextObject::AroundFunction,
+                Direction::Prev,
+            )
+        });
+    }
+
+    pub fn go_to_next_class(
+        &mut self,
+        _: &GoToNextClass,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, range| {
+            adjacent_text_object_start(
+                buffer,
+                offset,
+                range,
+                TextObject::AroundClass,
+                Direction::Next,
+            )
+        });
+    }
+
+    pub fn go_to_previous_class(
+        &mut self,
+        _: &GoToPreviousClass,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, range| {
+            adjacent_text_object_start(
+                buffer,
+                offset,
+                range,
+                TextObject::AroundClass,
+                Direction::Prev,
+            )
+        });
+    }
+
+    pub fn go_to_next_sibling_node(
+        &mut self,
+        _: &GoToNextSiblingNode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, _| {
+            sibling_node_start(buffer, offset, Direction::Next)
+        });
+    }
+
+    pub fn go_to_previous_sibling_node(
+        &mut self,
+        _: &GoToPreviousSiblingNode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, _| {
+            sibling_node_start(buffer, offset, Direction::Prev)
+        });
+    }
+
+    pub fn go_to_parent_node_start(
+        &mut self,
+        _: &GoToParentNodeStart,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, _| {
+            enclosing_node_boundary(buffer, offset, |node| node.start_byte())
+        });
+    }
+
+    pub fn go_to_parent_node_end(
+        &mut self,
+        _: &GoToParentNodeEnd,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to_syntax_offset(window, cx, |buffer, offset, _| {
+            enclosing_node_boundary(buffer, offset, |node| node.end_byte())
+        });
+    }
+
+    /// Moves every cursor to the buffer offset returned by `target_offset`, which is
+    /// given the buffer containing the cursor, the cursor's offset in that buffer and
+    /// the range of the buffer that is visible in the cursor's excerpt.
+    fn move_to_syntax_offset(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        target_offset: impl Fn(&BufferSnapshot, usize, Range<usize>) -> Option<usize>,
+    ) {
+        self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut moved = false;
+        let new_selections = self
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .map(|selection| {
+                let head = selection.head();
+                let target = buffer
+                    .excerpt_containing(head..head)
+                    .and_then(|mut excerpt| {
+                        let offset = excerpt.map_offset_to_buffer(head);
+                        let target =
+                            target_offset(excerpt.buffer(), offset, excerpt.buffer_range())?;
+                        excerpt
+                            .contains_buffer_range(target..target)
+                            .then(|| excerpt.map_offset_from_buffer(target))
+                    });
+                let Some(target) = target else {
+                    return selection;
+                };
+                moved = true;
+                Selection {
+                    id: selection.id,
+                    start: target,
+                    end: target,
+                    goal: SelectionGoal::None,
+                    reversed: false,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if moved {
             self.change_selections(
                 SelectionEffects::scroll(Autoscroll::fit()),
                 window,
@@ -15527,6 +16442,19 @@ impl Editor {
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
         self.change_selections(Default::default(), window, cx, |s| {
             s.move_offsets_with(|snapshot, selection| {
+                // When on a keyword such as `#if` or `fi`, cycle through the keywords that belong
+                // together with it instead.
+                let head = selection.head();
+                if let Some(keyword_ranges) = snapshot.matching_keyword_ranges(head..head)
+                    && let Some(ix) = keyword_ranges
+                        .iter()
+                        .position(|keyword| keyword.start <= head && head < keyword.end)
+                {
+                    let next = &keyword_ranges[(ix + 1) % keyword_ranges.len()];
+                    selection.collapse_to(next.start, SelectionGoal::None);
+                    return;
+                }
+
                 let Some(enclosing_bracket_ranges) =
                     snapshot.enclosing_bracket_ranges(selection.start..selection.end)
                 else {
@@ -15595,9 +16523,7 @@ impl Editor {
             });
             self.selection_history.mode = SelectionHistoryMode::Normal;
 
-            self.select_next_state = entry.select_next_state;
-            self.select_prev_state = entry.select_prev_state;
-            self.add_selections_state = entry.add_selections_state;
+            self.restore_selection_history_state(entry, cx);
         }
     }
 
@@ -15621,9 +16547,20 @@ impl Editor {
             });
             self.selection_history.mode = SelectionHistoryMode::Normal;
 
-            self.select_next_state = entry.select_next_state;
-            self.select_prev_state = entry.select_prev_state;
-            self.add_selections_state = entry.add_selections_state;
+            self.restore_selection_history_state(entry, cx);
+        }
+    }
+
+    fn restore_selection_history_state(
+        &mut self,
+        entry: SelectionHistoryEntry,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_next_state = entry.select_next_state;
+        self.select_prev_state = entry.select_prev_state;
+        self.add_selections_state = entry.add_selections_state;
+        if entry.buffer_edit_count == self.buffer.read(cx).read(cx).edit_count() {
+            self.select_syntax_node_history.stack = entry.select_syntax_node_stack;
         }
     }
 
@@ -15681,6 +16618,68 @@ impl Editor {
         })
     }
 
+    pub fn expand_excerpts_to_function(
+        &mut self,
+        _: &ExpandExcerptsToFunction,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selections = self.selections.disjoint_anchors_arc();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let excerpt_ids = selections
+            .iter()
+            .flat_map(|selection| snapshot.excerpt_ids_for_range(selection.range()))
+            .collect::<Vec<_>>();
+        self.expand_excerpts_to_enclosing_function(excerpt_ids, cx);
+    }
+
+    /// Expands each excerpt up and down to the innermost function containing its primary
+    /// range. Excerpts outside of any function are left as they are.
+    pub fn expand_excerpts_to_enclosing_function(
+        &mut self,
+        excerpt_ids: impl IntoIterator<Item = ExcerptId>,
+        cx: &mut Context<Self>,
+    ) {
+        let excerpt_ids = excerpt_ids.into_iter().collect::<HashSet<_>>();
+        self.buffer.update(cx, |buffer, cx| {
+            let snapshot = buffer.snapshot(cx);
+            let mut expansions = Vec::new();
+            for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+                if !excerpt_ids.contains(&excerpt_id) {
+                    continue;
+                }
+                let primary_range = excerpt_range.primary.to_offset(buffer_snapshot);
+                let Some(function_range) = buffer_snapshot
+                    .text_object_ranges(primary_range.clone(), TreeSitterOptions::default())
+                    .filter(|(range, object)| {
+                        *object == TextObject::AroundFunction
+                            && range.start <= primary_range.start
+                            && primary_range.end <= range.end
+                    })
+                    .map(|(range, _)| range)
+                    .min_by_key(|range| range.len())
+                else {
+                    continue;
+                };
+                let context_range = excerpt_range.context.to_point(buffer_snapshot);
+                let function_range = function_range.to_point(buffer_snapshot);
+                expansions.push((
+                    excerpt_id,
+                    context_range
+                        .start
+                        .row
+                        .saturating_sub(function_range.start.row),
+                    function_range.end.row.saturating_sub(context_range.end.row),
+                ));
+            }
+
+            for (excerpt_id, lines_up, lines_down) in expansions {
+                buffer.expand_excerpts([excerpt_id], lines_up, ExpandExcerptDirection::Up, cx);
+                buffer.expand_excerpts([excerpt_id], lines_down, ExpandExcerptDirection::Down, cx);
+            }
+        });
+    }
+
     pub fn expand_excerpt(
         &mut self,
         excerpt: ExcerptId,
@@ -16138,7 +17137,7 @@ impl Editor {
 
     pub fn go_to_declaration_split(
         &mut self,
-        _: &GoToDeclaration,
+        _: &GoToDeclarationSplit,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<Navigated>> {
@@ -18001,6 +19000,84 @@ impl Editor {
         self.fold_creases(creases, true, window, cx);
     }
 
+    pub fn fold_class_bodies(
+        &mut self,
+        _: &actions::FoldClassBodies,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fold_text_objects(TextObject::InsideClass, window, cx);
+    }
+
+    pub fn fold_comments(
+        &mut self,
+        _: &actions::FoldComments,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fold_text_objects(TextObject::AroundComment, window, cx);
+    }
+
+    pub fn fold_imports(
+        &mut self,
+        _: &actions::FoldImports,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fold_text_objects(TextObject::AroundImport, window, cx);
+    }
+
+    /// Folds all syntax nodes of the given kind. Consecutive lines of "around" objects, such as
+    /// comments and imports, are folded together, keeping their first line visible.
+    fn fold_text_objects(&mut self, kind: TextObject, window: &mut Window, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut ranges = snapshot
+            .text_object_ranges(0..snapshot.len(), TreeSitterOptions::default())
+            .filter_map(|(range, obj)| (obj == kind).then_some(range))
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+
+        let spans_lines = matches!(kind, TextObject::AroundComment | TextObject::AroundImport);
+        let mut merged_ranges = Vec::<Range<usize>>::new();
+        for range in ranges {
+            let adjoins_last = merged_ranges.last().is_some_and(|last| {
+                if range.start <= last.end {
+                    return true;
+                }
+                let gap = snapshot
+                    .text_for_range(last.end..range.start)
+                    .collect::<String>();
+                spans_lines && gap.trim().is_empty() && gap.matches('\n').count() <= 1
+            });
+            if adjoins_last && let Some(last) = merged_ranges.last_mut() {
+                last.end = last.end.max(range.end);
+            } else {
+                merged_ranges.push(range);
+            }
+        }
+
+        let placeholder = self.display_map.read(cx).fold_placeholder.clone();
+        let creases = merged_ranges
+            .into_iter()
+            .filter_map(|range| {
+                let start = range.start.to_point(&snapshot);
+                let mut end = range.end.to_point(&snapshot);
+                if !spans_lines {
+                    return Some(Crease::simple(start..end, placeholder.clone()));
+                }
+                if end.column == 0 && end.row > start.row {
+                    end = Point::new(end.row - 1, snapshot.line_len(MultiBufferRow(end.row - 1)));
+                }
+                (end.row > start.row).then(|| {
+                    let start = Point::new(start.row, snapshot.line_len(MultiBufferRow(start.row)));
+                    Crease::simple(start..end, placeholder.clone())
+                })
+            })
+            .collect();
+
+        self.fold_creases(creases, true, window, cx);
+    }
+
     pub fn fold_recursive(
         &mut self,
         _: &actions::FoldRecursive,
@@ -18886,7 +19963,7 @@ impl Editor {
     pub fn wrap_guides(&self, cx: &App) -> SmallVec<[(usize, bool); 2]> {
         let mut wrap_guides = smallvec![];
 
-        if self.show_wrap_guides == Some(false) {
+        if self.show_wrap_guides == Some(false) || self.reading_mode(cx).is_some() {
             return wrap_guides;
         }
 
@@ -18899,6 +19976,9 @@ impl Editor {
                 SoftWrap::Bounded(soft_wrap) => {
                     wrap_guides.push((soft_wrap as usize, true));
                 }
+                SoftWrap::HangingIndent { column, .. } => {
+                    wrap_guides.push((column as usize, true));
+                }
                 SoftWrap::GitDiff | SoftWrap::None | SoftWrap::EditorWidth => {}
             }
             wrap_guides.extend(settings.wrap_guides.iter().map(|guide| (*guide, false)))
@@ -18908,6 +19988,11 @@ impl Editor {
     }
 
     pub fn soft_wrap_mode(&self, cx: &App) -> SoftWrap {
+        if self.soft_wrap_mode_override.is_none()
+            && let Some(reading_mode) = self.reading_mode(cx)
+        {
+            return SoftWrap::Bounded(reading_mode.measure);
+        }
         let settings = self.buffer.read(cx).language_settings(cx);
         let mode = self.soft_wrap_mode_override.unwrap_or(settings.soft_wrap);
         match mode {
@@ -18921,6 +20006,10 @@ impl Editor {
             language_settings::SoftWrap::Bounded => {
                 SoftWrap::Bounded(settings.preferred_line_length)
             }
+            language_settings::SoftWrap::HangingIndent => SoftWrap::HangingIndent {
+                column: settings.preferred_line_length,
+                hanging_indent: settings.soft_wrap_hanging_indent,
+            },
         }
     }
 
@@ -18981,6 +20070,50 @@ impl Editor {
         }
     }
 
+    // Called by the element, like `set_wrap_width`.
+    pub(crate) fn set_wrap_hanging_indent(&self, hanging_indent: u32, cx: &mut App) -> bool {
+        if self.is_empty(cx) {
+            self.placeholder_display_map
+                .as_ref()
+                .map_or(false, |display_map| {
+                    display_map.update(cx, |map, cx| {
+                        map.set_wrap_hanging_indent(hanging_indent, cx)
+                    })
+                })
+        } else {
+            self.display_map.update(cx, |map, cx| {
+                map.set_wrap_hanging_indent(hanging_indent, cx)
+            })
+        }
+    }
+
+    /// Whether the editor shows a file that was opened in large file mode, with
+    /// syntax highlighting, language servers, soft wrap and inlay hints off.
+    pub fn is_in_large_file_mode(&self) -> bool {
+        self.large_file_mode
+    }
+
+    /// Turns the features that large file mode disabled back on for the file.
+    pub fn enable_large_file_features(&mut self, cx: &mut Context<Self>) {
+        if !self.large_file_mode {
+            return;
+        }
+        self.large_file_mode = false;
+        if let Some(buffer) = self.buffer.read(cx).as_singleton() {
+            buffer.update(cx, |buffer, cx| buffer.set_large_file(false, cx));
+        }
+        self.soft_wrap_mode_override = None;
+        self.refresh_inlay_hints(
+            InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
+                self.selections.newest_anchor().head(),
+                &self.buffer.read(cx).snapshot(cx),
+                cx,
+            )),
+            cx,
+        );
+        cx.notify();
+    }
+
     pub fn set_soft_wrap(&mut self) {
         self.soft_wrap_mode_override = Some(language_settings::SoftWrap::EditorWidth)
     }
@@ -18992,9 +20125,10 @@ impl Editor {
             let soft_wrap = match self.soft_wrap_mode(cx) {
                 SoftWrap::GitDiff => return,
                 SoftWrap::None => language_settings::SoftWrap::EditorWidth,
-                SoftWrap::EditorWidth | SoftWrap::Column(_) | SoftWrap::Bounded(_) => {
-                    language_settings::SoftWrap::None
-                }
+                SoftWrap::EditorWidth
+                | SoftWrap::Column(_)
+                | SoftWrap::Bounded(_)
+                | SoftWrap::HangingIndent { .. } => language_settings::SoftWrap::None,
             };
             self.soft_wrap_mode_override = Some(soft_wrap);
         }
@@ -19012,6 +20146,49 @@ impl Editor {
         });
     }
 
+    /// Returns the reading mode settings when the editor presents its buffer in reading mode,
+    /// which is only done for full editors of a single buffer.
+    pub fn reading_mode(&self, cx: &App) -> Option<ReadingModeSettings> {
+        if !self.mode.is_full() || !self.buffer.read(cx).is_singleton() {
+            return None;
+        }
+        let reading_mode = self.buffer.read(cx).language_settings(cx).reading_mode;
+        reading_mode.enabled.then_some(reading_mode)
+    }
+
+    pub fn toggle_reading_mode(
+        &mut self,
+        _: &ToggleReadingMode,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some(language) = self
+            .buffer
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).language().cloned())
+        else {
+            return;
+        };
+        let fs = workspace.read(cx).app_state().fs.clone();
+        let enabled = self.reading_mode(cx).is_some();
+        update_settings_file(fs, cx, move |settings, _| {
+            settings
+                .project
+                .all_languages
+                .languages
+                .0
+                .entry(language.name().0)
+                .or_default()
+                .reading_mode
+                .get_or_insert_default()
+                .enabled = Some(!enabled);
+        });
+    }
+
     pub fn toggle_indent_guides(
         &mut self,
         _: &ToggleIndentGuides,
@@ -19989,6 +21166,21 @@ impl Editor {
         Some(text_highlights)
     }
 
+    pub fn clear_background_highlights_key<T: 'static>(
+        &mut self,
+        key: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<BackgroundHighlight> {
+        let highlights = self
+            .background_highlights
+            .remove(&HighlightKey::TypePlus(TypeId::of::<T>(), key))?;
+        if !highlights.1.is_empty() {
+            self.scrollbar_marker_state.dirty = true;
+            cx.notify();
+        }
+        Some(highlights)
+    }
+
     pub fn highlight_gutter<T: 'static>(
         &mut self,
         ranges: impl Into<Vec<Range<Anchor>>>,
@@ -20350,6 +21542,16 @@ impl Editor {
         }
     }
 
+    /// Clears the text highlights added with [`Self::highlight_text_key`] for all keys.
+    pub fn clear_keyed_highlights<T: 'static>(&mut self, cx: &mut Context<Self>) {
+        let cleared = self
+            .display_map
+            .update(cx, |map, _| map.clear_keyed_highlights(TypeId::of::<T>()));
+        if cleared {
+            cx.notify();
+        }
+    }
+
     pub fn show_local_cursors(&self, window: &mut Window, cx: &mut App) -> bool {
         (self.read_only(cx) || self.blink_manager.read(cx).visible())
             && self.focus_handle.is_focused(window)
@@ -20364,6 +21566,18 @@ impl Editor {
         cx.notify();
     }
 
+    /// Whether any of `rows` is on screen, where `None` stands for every row.
+    fn any_row_visible(&self, rows: Option<&[Range<DisplayRow>]>, cx: &mut Context<Self>) -> bool {
+        let (Some(rows), Some(visible_line_count)) = (rows, self.visible_line_count()) else {
+            return true;
+        };
+        let first_visible_row = DisplayRow(self.scroll_position(cx).y.floor() as u32);
+        let end_visible_row =
+            DisplayRow(first_visible_row.0 + visible_line_count.ceil() as u32 + 1);
+        rows.iter()
+            .any(|rows| rows.start < end_visible_row && rows.end > first_visible_row)
+    }
+
     fn on_debug_session_event(
         &mut self,
         _session: Entity<Session>,
@@ -20483,6 +21697,10 @@ impl Editor {
                 self.refresh_selected_text_highlights(true, window, cx);
                 self.refresh_single_line_folds(window, cx);
                 refresh_matching_bracket_highlights(self, window, cx);
+                refresh_bracket_colors(self, cx);
+                refresh_injection_tints(self, cx);
+                refresh_invisible_character_warnings(self, cx);
+                refresh_spell_check(self, cx);
                 if self.has_active_edit_prediction() {
                     self.update_visible_edit_prediction(window, cx);
                 }
@@ -20607,6 +21825,14 @@ impl Editor {
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                let reparsed_rows = self.display_map.update(cx, |display_map, cx| {
+                    display_map.take_reparsed_rows(*buffer_id, cx)
+                });
+                if self.any_row_visible(reparsed_rows.as_deref(), cx) {
+                    refresh_bracket_colors(self, cx);
+                    refresh_injection_tints(self, cx);
+                    refresh_spell_check(self, cx);
+                }
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
@@ -20616,6 +21842,9 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                refresh_bracket_colors(self, cx);
+                refresh_injection_tints(self, cx);
+                refresh_spell_check(self, cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
@@ -20688,12 +21917,14 @@ impl Editor {
         self.update_edit_prediction_settings(cx);
         self.refresh_edit_prediction(true, false, window, cx);
         self.refresh_inline_values(cx);
+        let mut inlay_hint_settings = inlay_hint_settings(
+            self.selections.newest_anchor().head(),
+            &self.buffer.read(cx).snapshot(cx),
+            cx,
+        );
+        inlay_hint_settings.enabled &= !self.large_file_mode;
         self.refresh_inlay_hints(
-            InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
-                self.selections.newest_anchor().head(),
-                &self.buffer.read(cx).snapshot(cx),
-                cx,
-            )),
+            InlayHintRefreshReason::SettingsChange(inlay_hint_settings),
             cx,
         );
 
@@ -20758,6 +21989,11 @@ impl Editor {
             }
             self.refresh_colors(false, None, window, cx);
         }
+        self.code_lens_settings_changed(window, cx);
+        refresh_bracket_colors(self, cx);
+        refresh_injection_tints(self, cx);
+        refresh_invisible_character_warnings(self, cx);
+        refresh_spell_check(self, cx);
 
         cx.notify();
     }
@@ -21675,6 +22911,7 @@ impl Editor {
     ) {
         self.pull_diagnostics(for_buffer, window, cx);
         self.refresh_colors(ignore_cache, for_buffer, window, cx);
+        self.refresh_code_lenses(for_buffer, window, cx);
     }
 }
 
@@ -21686,6 +22923,44 @@ fn vim_enabled(cx: &App) -> bool {
         == Some(true)
 }
 
+/// Resolves the value of a snippet variable such as `$TM_FILENAME` for a
+/// completion inserted at `cursor_point` in `buffer`.
+fn snippet_variable(name: &str, buffer: &Buffer, cursor_point: Point, cx: &App) -> Option<String> {
+    let file = buffer.file();
+    let abs_path = || {
+        let file = file?;
+        Some(match file.as_local() {
+            Some(local_file) => local_file.abs_path(cx),
+            None => file.full_path(cx),
+        })
+    };
+    match name {
+        "TM_FILENAME" => Some(file?.file_name(cx).to_string_lossy().into_owned()),
+        "TM_FILENAME_BASE" => Some(
+            Path::new(file?.file_name(cx))
+                .file_stem()?
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        "TM_FILEPATH" => Some(abs_path()?.to_string_lossy().into_owned()),
+        "TM_DIRECTORY" => Some(abs_path()?.parent()?.to_string_lossy().into_owned()),
+        "RELATIVE_FILEPATH" => Some(file?.path().to_string_lossy().into_owned()),
+        "TM_LINE_INDEX" => Some(cursor_point.row.to_string()),
+        "TM_LINE_NUMBER" => Some((cursor_point.row + 1).to_string()),
+        "TM_CURRENT_LINE" => {
+            let row = cursor_point.row;
+            Some(
+                buffer
+                    .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                    .collect(),
+            )
+        }
+        "CLIPBOARD" => cx.read_from_clipboard()?.text(),
+        "UUID" => Some(uuid::Uuid::new_v4().to_string()),
+        _ => None,
+    }
+}
+
 fn process_completion_for_edit(
     completion: &Completion,
     intent: CompletionIntent,
@@ -21712,7 +22987,9 @@ fn process_completion_for_edit(
         {
             snippet_source = label;
         }
-        match Snippet::parse(&snippet_source).log_err() {
+        let cursor_point = text::ToPoint::to_point(cursor_position, buffer);
+        let resolve_variable = |name: &str| snippet_variable(name, buffer, cursor_point, cx);
+        match Snippet::parse_with_variables(&snippet_source, &resolve_variable).log_err() {
             Some(parsed_snippet) => (Some(parsed_snippet.clone()), parsed_snippet.text),
             None => (None, completion.new_text.clone()),
         }
@@ -22143,6 +23420,23 @@ fn test_word_breaking_tokenizer() {
     }
 }
 
+/// Returns the marker of a Markdown list item at the start of `text`, like `- ` or `1. `, along
+/// with the whitespace after it.
+fn comment_list_item_prefix(text: &str) -> Option<&str> {
+    let marker_len = if text.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digit_count = text.bytes().take_while(u8::is_ascii_digit).count();
+        if digit_count == 0 || !text[digit_count..].starts_with(['.', ')']) {
+            return None;
+        }
+        digit_count + 1
+    };
+    let after_marker = &text[marker_len..];
+    let whitespace_len = after_marker.len() - after_marker.trim_start_matches([' ', '\t']).len();
+    (whitespace_len > 0).then(|| &text[..marker_len + whitespace_len])
+}
+
 fn wrap_with_prefix(
     first_line_prefix: String,
     subsequent_lines_prefix: String,
@@ -22919,6 +24213,165 @@ fn inlay_hint_settings(
     language_settings(language, file, cx).inlay_hints
 }
 
+/// Returns the range to replace and its replacement when joining `row` with the
+/// line after it.
+///
+/// When whitespace is inserted, the language at the join point is taken into
+/// account: the comment prefix of the next line is dropped when joining into a
+/// comment, a trailing comma is dropped when joining into a closing bracket, and
+/// no space is inserted just inside parentheses and square brackets.
+fn join_line_edit(
+    snapshot: &MultiBufferSnapshot,
+    row: MultiBufferRow,
+    insert_whitespace: bool,
+) -> (Range<Point>, &'static str) {
+    let line_len = snapshot.line_len(row);
+    let trailing_whitespace_len = snapshot
+        .reversed_chars_at(Point::new(row.0, line_len))
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .count() as u32;
+    let mut end_of_line = Point::new(row.0, line_len - trailing_whitespace_len);
+    let next_line_row = row.next_row();
+    let next_line_len = snapshot.line_len(next_line_row);
+    let indent = snapshot.indent_size_for_line(next_line_row);
+    let mut start_of_next_line = Point::new(next_line_row.0, indent.len);
+    if !insert_whitespace {
+        return (end_of_line..start_of_next_line, "");
+    }
+
+    let line_start = Point::new(row.0, snapshot.indent_size_for_line(row).len).min(end_of_line);
+    let line_text = snapshot
+        .text_for_range(line_start..end_of_line)
+        .collect::<String>();
+    let next_line_text = snapshot
+        .text_for_range(start_of_next_line..Point::new(next_line_row.0, next_line_len))
+        .collect::<String>();
+    let mut pad = true;
+
+    if let Some(scope) = snapshot.language_scope_at(end_of_line)
+        && scope.override_name() != Some("string")
+    {
+        let comment_prefix = scope
+            .line_comment_prefixes()
+            .iter()
+            .map(|prefix| prefix.trim_end())
+            .filter(|prefix| !prefix.is_empty() && next_line_text.starts_with(prefix))
+            .max_by_key(|prefix| prefix.len())
+            .filter(|prefix| {
+                scope.override_name() == Some("comment") || line_text.starts_with(prefix)
+            });
+        let brackets = scope
+            .brackets()
+            .filter(|(pair, enabled)| {
+                *enabled && pair.close && pair.start.len() == 1 && pair.start != pair.end
+            })
+            .map(|(pair, _)| pair)
+            .collect::<Vec<_>>();
+        let opening_bracket = brackets
+            .iter()
+            .find(|pair| line_text.ends_with(pair.start.as_str()));
+        let closing_bracket = brackets
+            .iter()
+            .find(|pair| next_line_text.starts_with(pair.end.as_str()));
+
+        if let Some(comment_prefix) = comment_prefix {
+            let comment = next_line_text[comment_prefix.len()..].trim_start();
+            start_of_next_line.column += (next_line_text.len() - comment.len()) as u32;
+        } else if let Some(closing_bracket) = closing_bracket {
+            if opening_bracket.is_none() && line_text.ends_with(',') {
+                end_of_line.column -= 1;
+            }
+            // Braces keep their padding, as most formatters put spaces inside them.
+            pad = closing_bracket.end == "}" && opening_bracket.is_none();
+        } else if let Some(opening_bracket) = opening_bracket {
+            pad = opening_bracket.start == "{";
+        }
+    }
+
+    // Collapse the whitespace around the joined newline into a single space,
+    // unless either side of the join is blank.
+    let replace = if pad && end_of_line.column > 0 && start_of_next_line.column < next_line_len {
+        " "
+    } else {
+        ""
+    };
+    (end_of_line..start_of_next_line, replace)
+}
+
+/// Returns the start of the closest text object of the given kind that starts
+/// after or before `offset`, within `range`.
+fn adjacent_text_object_start(
+    buffer: &BufferSnapshot,
+    offset: usize,
+    range: Range<usize>,
+    object: TextObject,
+    direction: Direction,
+) -> Option<usize> {
+    let search_range = match direction {
+        Direction::Prev => range.start..offset,
+        Direction::Next => offset..range.end,
+    };
+    let starts = buffer
+        .text_object_ranges(search_range, TreeSitterOptions::max_start_depth(4))
+        .filter(|(_, text_object)| *text_object == object)
+        .map(|(range, _)| range.start);
+    match direction {
+        Direction::Prev => starts.filter(|start| *start < offset).max(),
+        Direction::Next => starts.filter(|start| *start > offset).min(),
+    }
+}
+
+/// Returns the start of the named syntax node after or before the largest node
+/// starting at `offset`.
+fn sibling_node_start(
+    buffer: &BufferSnapshot,
+    offset: usize,
+    direction: Direction,
+) -> Option<usize> {
+    let mut node = buffer.syntax_ancestor(offset..offset)?;
+    while let Some(parent) = node.parent()
+        && parent.parent().is_some()
+        && parent.start_byte() == node.start_byte()
+    {
+        node = parent;
+    }
+
+    loop {
+        let sibling = match direction {
+            Direction::Next => buffer.syntax_next_sibling(node.byte_range())?,
+            Direction::Prev => buffer.syntax_prev_sibling(node.byte_range())?,
+        };
+        let advanced = match direction {
+            Direction::Next => sibling.start_byte() > node.start_byte(),
+            Direction::Prev => sibling.start_byte() < node.start_byte(),
+        };
+        if !advanced {
+            return None;
+        }
+        if sibling.is_named() {
+            return Some(sibling.start_byte());
+        }
+        node = sibling;
+    }
+}
+
+/// Returns the `boundary` of the smallest syntax node enclosing `offset` whose
+/// boundary is not `offset` itself.
+fn enclosing_node_boundary(
+    buffer: &BufferSnapshot,
+    offset: usize,
+    boundary: impl Fn(&language::Node) -> usize,
+) -> Option<usize> {
+    let mut range = offset..offset;
+    loop {
+        let node = buffer.syntax_ancestor(range)?;
+        if boundary(&node) != offset {
+            return Some(boundary(&node));
+        }
+        range = node.byte_range();
+    }
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -23239,11 +24692,23 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_indent(buffer_row) || self.starts_fold_region(buffer_row);
 
-        if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
+        // Unfolded regions with folds inside them are always marked, so that those folds can be
+        // found without hovering over the gutter.
+        let contains_folds = !folded
+            && is_foldable
+            && self
+                .crease_for_buffer_row(buffer_row)
+                .is_some_and(|crease| self.folds_in_range(crease.range().clone()).next().is_some());
+
+        if folded || contains_folds || (is_foldable && (row_contains_cursor || self.gutter_hovered))
+        {
             Some(
                 Disclosure::new(("gutter_crease", buffer_row.0), !folded)
+                    .when(contains_folds, |this| {
+                        this.opened_icon(IconName::ChevronUpDown)
+                    })
                     .toggle_state(folded)
                     .on_click(window.listener_for(&editor, move |this, _e, window, cx| {
                         if folded {
@@ -23854,6 +25319,33 @@ pub fn diagnostic_style(severity: lsp::DiagnosticSeverity, colors: &StatusColors
     }
 }
 
+/// Returns the icon that represents symbols of the given kind.
+pub fn icon_for_symbol_kind(kind: lsp::SymbolKind) -> IconName {
+    match kind {
+        lsp::SymbolKind::FILE => IconName::File,
+        lsp::SymbolKind::MODULE | lsp::SymbolKind::NAMESPACE | lsp::SymbolKind::PACKAGE => {
+            IconName::Library
+        }
+        lsp::SymbolKind::CLASS
+        | lsp::SymbolKind::INTERFACE
+        | lsp::SymbolKind::ENUM
+        | lsp::SymbolKind::STRUCT
+        | lsp::SymbolKind::OBJECT => IconName::Blocks,
+        lsp::SymbolKind::METHOD
+        | lsp::SymbolKind::FUNCTION
+        | lsp::SymbolKind::CONSTRUCTOR
+        | lsp::SymbolKind::OPERATOR => IconName::Code,
+        lsp::SymbolKind::CONSTANT
+        | lsp::SymbolKind::ENUM_MEMBER
+        | lsp::SymbolKind::NUMBER
+        | lsp::SymbolKind::BOOLEAN => IconName::Hash,
+        lsp::SymbolKind::STRING => IconName::Quote,
+        lsp::SymbolKind::EVENT => IconName::BoltOutlined,
+        lsp::SymbolKind::KEY => IconName::Keyboard,
+        _ => IconName::Circle,
+    }
+}
+
 pub fn styled_runs_for_code_label<'a>(
     label: &'a CodeLabel,
     syntax_theme: &'a theme::SyntaxTheme,
@@ -24390,6 +25882,39 @@ fn render_diff_hunk_controls(
                 })
                 .disabled(is_created_file),
         )
+        .when(!status.is_added(), |el| {
+            el.child(
+                IconButton::new(("copy-original", row as u64), IconName::Copy)
+                    .shape(IconButtonShape::Square)
+                    .icon_size(IconSize::Small)
+                    .tooltip({
+                        let focus_handle = editor.focus_handle(cx);
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Copy Original Text",
+                                &CopyHunkOriginalText,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click({
+                        let editor = editor.clone();
+                        move |_event, window, cx| {
+                            editor.update(cx, |editor, cx| {
+                                let snapshot = editor.snapshot(window, cx);
+                                let point = hunk_range.start.to_point(&snapshot.buffer_snapshot);
+                                editor.copy_original_text_of_hunks_in_ranges(
+                                    vec![point..point],
+                                    window,
+                                    cx,
+                                );
+                            });
+                        }
+                    }),
+            )
+        })
         .when(
             !editor.read(cx).buffer().read(cx).all_diff_hunks_expanded(),
             |el| {
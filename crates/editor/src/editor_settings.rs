@@ -5,10 +5,10 @@ use gpui::App;
 use language::CursorShape;
 use project::project_settings::DiagnosticSeverity;
 pub use settings::{
-    CurrentLineHighlight, DisplayIn, DocumentColorsRenderMode, DoubleClickInMultibuffer,
-    GoToDefinitionFallback, HideMouseMode, MinimapThumb, MinimapThumbBorder, MultiCursorModifier,
-    ScrollBeyondLastLine, ScrollbarDiagnostics, SeedQuerySetting, ShowMinimap, SnippetSortOrder,
-    VsCodeSettings,
+    ColorizeBrackets, CurrentLineHighlight, DisplayIn, DocumentColorsRenderMode,
+    DoubleClickInMultibuffer, GoToDefinitionFallback, HideMouseMode, MinimapThumb,
+    MinimapThumbBorder, MultiCursorModifier, ScrollBeyondLastLine, ScrollbarDiagnostics,
+    SeedQuerySetting, ShowMinimap, SnippetSortOrder, VsCodeSettings,
 };
 use settings::{Settings, SettingsContent};
 use ui::scrollbars::{ScrollbarVisibility, ShowScrollbar};
@@ -56,6 +56,10 @@ pub struct EditorSettings {
     pub diagnostics_max_severity: Option<DiagnosticSeverity>,
     pub inline_code_actions: bool,
     pub drag_and_drop_selection: DragAndDropSelection,
+    pub sticky_scroll: StickyScroll,
+    pub collaborator_cursors: CollaboratorCursors,
+    pub code_lens: bool,
+    pub colorize_brackets: ColorizeBrackets,
     pub lsp_document_colors: DocumentColorsRenderMode,
     pub minimum_contrast_for_highlights: f32,
 }
@@ -108,6 +112,7 @@ pub struct Minimap {
     pub thumb_border: MinimapThumbBorder,
     pub current_line_highlight: Option<CurrentLineHighlight>,
     pub max_width_columns: num::NonZeroU32,
+    pub markers: bool,
 }
 
 impl Minimap {
@@ -165,6 +170,31 @@ pub struct DragAndDropSelection {
     pub delay: u64,
 }
 
+/// Whether to pin the headers of the scopes enclosing the top of the editor.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct StickyScroll {
+    /// When true, the first lines of the outline items enclosing the topmost
+    /// visible line stay pinned to the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: bool,
+}
+
+/// How the cursors and selections of collaborators are shown.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct CollaboratorCursors {
+    /// Whether to highlight the text selected by collaborators, in addition to their cursors.
+    ///
+    /// Default: true
+    pub show_selections: bool,
+
+    /// The number of milliseconds after which the cursors of idle collaborators fade out, or 0
+    /// to never fade them.
+    ///
+    /// Default: 10000
+    pub fade_after_ms: u64,
+}
+
 /// Default options for buffer and project search items.
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct SearchSettings {
@@ -199,6 +229,8 @@ impl Settings for EditorSettings {
         let toolbar = editor.toolbar.unwrap();
         let search = editor.search.unwrap();
         let drag_and_drop_selection = editor.drag_and_drop_selection.unwrap();
+        let sticky_scroll = editor.sticky_scroll.unwrap();
+        let collaborator_cursors = editor.collaborator_cursors.unwrap();
         Self {
             cursor_blink: editor.cursor_blink.unwrap(),
             cursor_shape: editor.cursor_shape.map(Into::into),
@@ -239,6 +271,7 @@ impl Settings for EditorSettings {
                 thumb_border: minimap.thumb_border.unwrap(),
                 current_line_highlight: minimap.current_line_highlight,
                 max_width_columns: minimap.max_width_columns.unwrap(),
+                markers: minimap.markers.unwrap(),
             },
             gutter: Gutter {
                 min_line_number_digits: gutter.min_line_number_digits.unwrap(),
@@ -284,6 +317,15 @@ impl Settings for EditorSettings {
                 enabled: drag_and_drop_selection.enabled.unwrap(),
                 delay: drag_and_drop_selection.delay.unwrap(),
             },
+            sticky_scroll: StickyScroll {
+                enabled: sticky_scroll.enabled.unwrap(),
+            },
+            collaborator_cursors: CollaboratorCursors {
+                show_selections: collaborator_cursors.show_selections.unwrap(),
+                fade_after_ms: collaborator_cursors.fade_after_ms.unwrap(),
+            },
+            code_lens: editor.code_lens.unwrap(),
+            colorize_brackets: editor.colorize_brackets.unwrap(),
             lsp_document_colors: editor.lsp_document_colors.unwrap(),
             minimum_contrast_for_highlights: editor.minimum_contrast_for_highlights.unwrap(),
         }
@@ -491,5 +533,19 @@ impl Settings for EditorSettings {
         if minimap != settings::MinimapContent::default() {
             current.editor.minimap = Some(minimap)
         }
+
+        if let Some(enabled) = vscode.read_bool("editor.stickyScroll.enabled") {
+            current.editor.sticky_scroll = Some(settings::StickyScrollContent {
+                enabled: Some(enabled),
+            });
+        }
+        vscode.bool_setting("editor.codeLens", &mut current.editor.code_lens);
+        if let Some(enabled) = vscode.read_bool("editor.bracketPairColorization.enabled") {
+            current.editor.colorize_brackets = Some(if enabled {
+                ColorizeBrackets::All
+            } else {
+                ColorizeBrackets::Off
+            });
+        }
     }
 }
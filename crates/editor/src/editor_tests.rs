@@ -25,8 +25,8 @@ use language::{
     DiagnosticSourceKind, FakeLspAdapter, IndentGuideSettings, LanguageConfig,
     LanguageConfigOverride, LanguageMatcher, LanguageName, Override, Point,
     language_settings::{
-        CompletionSettingsContent, FormatterList, LanguageSettingsContent, LspInsertMode,
-        SelectedFormatter,
+        CodeActionsOnFormatOrder, CompletionSettingsContent, FormatterList,
+        LanguageSettingsContent, LspInsertMode, SelectedFormatter,
     },
     tree_sitter_python,
 };
@@ -43,7 +43,7 @@ use project::{
 use serde_json::{self, json};
 use settings::{
     AllLanguageSettingsContent, IndentGuideBackgroundColoring, IndentGuideColoring,
-    ProjectSettingsContent,
+    ProjectSettingsContent, ReadingModeSettingsContent,
 };
 use std::{cell::RefCell, future::Future, rc::Rc, sync::atomic::AtomicBool, time::Instant};
 use std::{
@@ -973,6 +973,55 @@ fn test_fold_action(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_fold_regions(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.set_state(indoc! {"
+        impl Foo {
+            // #region Accessors
+            fn a() {}
+            // #region Nested
+            fn b() {}
+            // #endregion
+            ˇfn c() {}
+            // #endregion
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.fold(&Fold, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                impl Foo {
+                    ⋯
+                }
+            "}
+        );
+
+        editor.unfold_lines(&UnfoldLines, window, cx);
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(4, 4)..Point::new(4, 4)])
+        });
+        editor.fold(&Fold, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                impl Foo {
+                    // #region Accessors
+                    fn a() {}
+                    ⋯
+                    fn c() {}
+                    // #endregion
+                }
+            "}
+        );
+    });
+}
+
 #[gpui::test]
 fn test_fold_action_whitespace_sensitive_language(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1335,6 +1384,24 @@ fn test_move_cursor(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_reporting_accessibility_caret(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("oneˇ\ntwo\nthree");
+    cx.simulate_frame();
+    let caret = cx
+        .accessibility_caret()
+        .expect("the caret should be reported once a frame is drawn");
+
+    cx.update_editor(|editor, window, cx| editor.move_down(&MoveDown, window, cx));
+    cx.simulate_frame();
+    let moved_caret = cx.accessibility_caret().unwrap();
+    assert_eq!(moved_caret.origin.x, caret.origin.x);
+    assert!(moved_caret.origin.y > caret.origin.y);
+}
+
 #[gpui::test]
 fn test_move_cursor_multibyte(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2015,6 +2082,39 @@ fn test_prev_next_word_boundary(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_soft_wrap_hanging_indent(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.soft_wrap = Some(language_settings::SoftWrap::HangingIndent);
+        settings.defaults.preferred_line_length = Some(16);
+        settings.defaults.soft_wrap_hanging_indent = Some(4);
+    });
+
+    let editor = cx.add_window(|window, cx| {
+        let buffer = MultiBuffer::build_simple("    aaaa bbbb cccc dddd", cx);
+        build_editor(buffer, window, cx)
+    });
+    _ = editor.update(cx, |editor, _, cx| {
+        assert!(matches!(
+            editor.soft_wrap_mode(cx),
+            SoftWrap::HangingIndent {
+                column: 16,
+                hanging_indent: 4
+            }
+        ));
+        assert_eq!(editor.wrap_guides(cx).as_slice(), &[(16, true)]);
+
+        editor.set_wrap_width(Some(140.0.into()), cx);
+        assert_eq!(editor.display_text(cx), "    aaaa bbbb \n    cccc dddd");
+
+        editor.set_wrap_hanging_indent(4, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "    aaaa bbbb \n        cccc \n        dddd"
+        );
+    });
+}
+
 #[gpui::test]
 fn test_prev_next_word_bounds_with_soft_wrap(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2942,6 +3042,38 @@ fn test_delete_to_next_word_end_or_newline(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_delete_to_previous_subword_start_or_newline(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|window, cx| {
+        let buffer = MultiBuffer::build_simple("fooBar_baz\nquxQuux", cx);
+        build_editor(buffer, window, cx)
+    });
+    let del_to_prev_subword_start = DeleteToPreviousSubwordStart {
+        ignore_newlines: false,
+        ignore_brackets: false,
+    };
+
+    _ = editor.update(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(1), 7)..DisplayPoint::new(DisplayRow(1), 7)
+            ])
+        });
+        editor.delete_to_previous_subword_start(&del_to_prev_subword_start, window, cx);
+        assert_eq!(editor.buffer.read(cx).read(cx).text(), "fooBar_baz\nqux");
+        editor.delete_to_previous_subword_start(&del_to_prev_subword_start, window, cx);
+        assert_eq!(editor.buffer.read(cx).read(cx).text(), "fooBar_baz\n");
+        editor.delete_to_previous_subword_start(&del_to_prev_subword_start, window, cx);
+        assert_eq!(editor.buffer.read(cx).read(cx).text(), "fooBar_baz");
+        editor.delete_to_previous_subword_start(&del_to_prev_subword_start, window, cx);
+        assert_eq!(editor.buffer.read(cx).read(cx).text(), "fooBar_");
+        editor.delete_to_previous_subword_start(&del_to_prev_subword_start, window, cx);
+        assert_eq!(editor.buffer.read(cx).read(cx).text(), "foo");
+    });
+}
+
 #[gpui::test]
 fn test_newline(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4362,6 +4494,18 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         editor.join_lines(&JoinLines, window, cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb c d");
 
+        // Trailing whitespace is collapsed into the inserted space
+        editor.buffer.update(cx, |buffer, cx| {
+            buffer.edit(
+                [(Point::new(0, 11)..Point::new(0, 11), "\t \n  e")],
+                None,
+                cx,
+            )
+        });
+        assert_eq!(buffer.read(cx).text(), "aaa bbb c d\t \n  e");
+        editor.join_lines(&JoinLines, window, cx);
+        assert_eq!(buffer.read(cx).text(), "aaa bbb c d e");
+
         editor
     });
 }
@@ -4453,6 +4597,97 @@ async fn test_join_lines_with_git_diff_base(executor: BackgroundExecutor, cx: &m
     );
 }
 
+#[gpui::test]
+async fn test_join_lines_with_language(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let bracket_pair = |start: &str, end: &str| BracketPair {
+        start: start.to_string(),
+        end: end.to_string(),
+        close: true,
+        surround: true,
+        newline: true,
+    };
+    let language = Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into(), "/// ".into()],
+            brackets: BracketPairConfig {
+                pairs: vec![
+                    bracket_pair("{", "}"),
+                    bracket_pair("(", ")"),
+                    bracket_pair("[", "]"),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    )
+    .with_override_query(
+        r#"
+            (line_comment) @comment.inclusive
+            (string_literal) @string
+        "#,
+    )
+    .unwrap();
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(Arc::new(language)), cx));
+
+    // The comment prefix of the joined line is dropped
+    cx.set_state(indoc! {"
+        ˇ/// one
+        /// two
+    "});
+    cx.update_editor(|editor, window, cx| editor.join_lines(&JoinLines, window, cx));
+    cx.assert_editor_state(indoc! {"
+        /// oneˇ two
+    "});
+
+    // ... also when joining into a trailing comment
+    cx.set_state(indoc! {"
+        let a = 1; ˇ// one
+            // two
+    "});
+    cx.update_editor(|editor, window, cx| editor.join_lines(&JoinLines, window, cx));
+    cx.assert_editor_state(indoc! {"
+        let a = 1; // oneˇ two
+    "});
+
+    // Trailing commas before a closing bracket are dropped, and no space is
+    // inserted just inside parentheses
+    cx.set_state(indoc! {"
+        «foo(
+            a,
+            b,
+        )ˇ»;
+    "});
+    cx.update_editor(|editor, window, cx| editor.join_lines(&JoinLines, window, cx));
+    cx.assert_editor_state(indoc! {"
+        foo(a, bˇ);
+    "});
+
+    // Braces keep their padding
+    cx.set_state(indoc! {"
+        «let s = S {
+            a,
+        }ˇ»;
+    "});
+    cx.update_editor(|editor, window, cx| editor.join_lines(&JoinLines, window, cx));
+    cx.assert_editor_state(indoc! {"
+        let s = S { aˇ };
+    "});
+
+    // Empty brackets are joined without a space
+    cx.set_state(indoc! {"
+        ˇfoo(
+        );
+    "});
+    cx.update_editor(|editor, window, cx| editor.join_lines(&JoinLines, window, cx));
+    cx.assert_editor_state(indoc! {"
+        foo(ˇ);
+    "});
+}
+
 #[gpui::test]
 async fn test_custom_newlines_cause_no_false_positive_diffs(
     executor: BackgroundExecutor,
@@ -4544,6 +4779,34 @@ async fn test_manipulate_immutable_lines_with_single_selection(cx: &mut TestAppC
         5ˇ»
     "});
 
+    // Test sort_lines_descending()
+    cx.set_state(indoc! {"
+        «b
+        c
+        a
+        Bˇ»
+    "});
+    cx.update_editor(|e, window, cx| e.sort_lines_descending(&SortLinesDescending, window, cx));
+    cx.assert_editor_state(indoc! {"
+        «c
+        b
+        a
+        Bˇ»
+    "});
+
+    // Test sort_lines_natural()
+    cx.set_state(indoc! {"
+        «item10
+        item2
+        item1ˇ»
+    "});
+    cx.update_editor(|e, window, cx| e.sort_lines_natural(&SortLinesNatural, window, cx));
+    cx.assert_editor_state(indoc! {"
+        «item1
+        item2
+        item10ˇ»
+    "});
+
     // Skip testing shuffle_line()
 
     // From here on out, test more complex cases of manipulate_immutable_lines() with a single driver method: sort_lines_case_sensitive()
@@ -5508,6 +5771,38 @@ fn test_duplicate_line(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_move_selections_on_drop(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("«oneˇ» two «threeˇ» four\n");
+    cx.update_editor(|editor, window, cx| {
+        let selections = editor.selections.disjoint_anchors().to_vec();
+        editor.move_selections_on_drop(
+            &selections,
+            DisplayPoint::new(DisplayRow(0), 18),
+            true,
+            window,
+            cx,
+        );
+    });
+    cx.assert_editor_state(" two  four«one\nthreeˇ»\n");
+
+    cx.set_state("«oneˇ» two «threeˇ» four\n");
+    cx.update_editor(|editor, window, cx| {
+        let selections = editor.selections.disjoint_anchors().to_vec();
+        editor.move_selections_on_drop(
+            &selections,
+            DisplayPoint::new(DisplayRow(0), 3),
+            false,
+            window,
+            cx,
+        );
+    });
+    cx.assert_editor_state("one«one\nthreeˇ» two three four\n");
+}
+
 #[gpui::test]
 fn test_move_line_up_down(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -6181,7 +6476,7 @@ async fn test_rewrap(cx: &mut TestAppContext) {
                // This is the second long comment block
                // to be wrapped.ˇ»
            "},
-        rust_language,
+        rust_language.clone(),
         &mut cx,
     );
 
@@ -6226,6 +6521,40 @@ async fn test_rewrap(cx: &mut TestAppContext) {
         &mut cx,
     );
 
+    // Test that list items in comments are wrapped separately, aligned with their text
+    assert_rewrap(
+        indoc! {"
+            // Steps:
+            // - ˇFirst step that is long enough to need wrapping here.
+            // - Second step.
+        "},
+        indoc! {"
+            // Steps:
+            // - ˇFirst step that is long enough to
+            //   need wrapping here.
+            // - Second step.
+        "},
+        rust_language.clone(),
+        &mut cx,
+    );
+
+    // Test rewrapping from the continuation line of a list item in a comment
+    assert_rewrap(
+        indoc! {"
+            // - First item.
+            // - Second item that is long and
+            //   coˇntinues here with more words to wrap.
+        "},
+        indoc! {"
+            // - First item.
+            // - Second item that is long and
+            //   coˇntinues here with more words to
+            //   wrap.
+        "},
+        rust_language,
+        &mut cx,
+    );
+
     #[track_caller]
     fn assert_rewrap(
         unwrapped_text: &str,
@@ -6872,6 +7201,44 @@ async fn test_clipboard(cx: &mut TestAppContext) {
         tˇhe lazy dog"});
 }
 
+#[gpui::test]
+async fn test_clipboard_history(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("«one ˇ»two «three ˇ»four");
+    cx.update_editor(|e, window, cx| e.copy(&Copy, window, cx));
+    cx.set_state("one two three «fourˇ»");
+    cx.update_editor(|e, window, cx| e.cut(&Cut, window, cx));
+    // Copying the same text again moves it to the front of the history.
+    cx.set_state("«one ˇ»two «three ˇ»");
+    cx.update_editor(|e, window, cx| e.copy(&Copy, window, cx));
+
+    let entries = cx.update(|_, cx| {
+        cx.global::<ClipboardHistory>()
+            .entries()
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| entry.text.as_str())
+            .collect::<Vec<_>>(),
+        ["one \nthree ", "four"]
+    );
+
+    // Each segment copied from multiple selections is pasted at its own cursor again.
+    cx.set_state("ˇa ˇb");
+    cx.update_editor(|e, window, cx| e.paste_clipboard_history_entry(&entries[0], window, cx));
+    cx.assert_editor_state("one ˇa three ˇb");
+
+    cx.set_state("ˇa ˇb");
+    cx.update_editor(|e, window, cx| e.paste_clipboard_history_entry(&entries[1], window, cx));
+    cx.assert_editor_state("fourˇa fourˇb");
+}
+
 #[gpui::test]
 async fn test_copy_trim(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -7266,6 +7633,38 @@ async fn test_paste_content_from_other_app(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_paste_without_reindent(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    cx.write_to_clipboard(ClipboardItem::new_string(
+        "    d(\n        e\n    );\n".into(),
+    ));
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+
+    cx.set_state(indoc! {"
+        fn a() {
+            if c() {
+        ˇ
+            }
+        }
+    "});
+
+    cx.update_editor(|e, window, cx| e.paste_without_reindent(&PasteWithoutReindent, window, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            if c() {
+            d(
+                e
+            );
+        ˇ
+            }
+        }
+    "});
+}
+
 #[gpui::test]
 fn test_select_all(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -8126,26 +8525,83 @@ async fn test_select_all_matches(cx: &mut TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_select_all_matches_does_not_scroll(cx: &mut TestAppContext) {
+async fn test_select_matches_with_occurrence_search_options(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
 
     let mut cx = EditorTestContext::new(cx).await;
 
-    let large_body_1 = "\nd".repeat(200);
-    let large_body_2 = "\ne".repeat(200);
-
-    cx.set_state(&format!(
-        "abc\nabc{large_body_1} «ˇa»bc{large_body_2}\nefabc\nabc"
-    ));
-    let initial_scroll_position = cx.update_editor(|editor, _, cx| {
-        let scroll_position = editor.scroll_position(cx);
-        assert!(scroll_position.y > 0.0, "Initial selection is between two large bodies and should have the editor scrolled to it");
-        scroll_position
+    cx.set_state("«abcˇ» ABC abcd abc");
+    cx.update(|_, cx| {
+        OccurrenceSearchOptions {
+            case_sensitive: false,
+            whole_word: false,
+        }
+        .set(cx)
     });
+    cx.update_editor(|e, window, cx| e.select_all_matches(&SelectAllMatches, window, cx))
+        .unwrap();
+    cx.assert_editor_state("«abcˇ» «ABCˇ» «abcˇ»d «abcˇ»");
 
+    cx.set_state("«abcˇ» ABC abcd abc");
+    cx.update(|_, cx| {
+        OccurrenceSearchOptions {
+            case_sensitive: true,
+            whole_word: true,
+        }
+        .set(cx)
+    });
     cx.update_editor(|e, window, cx| e.select_all_matches(&SelectAllMatches, window, cx))
         .unwrap();
-    cx.assert_editor_state(&format!(
+    cx.assert_editor_state("«abcˇ» ABC abcd «abcˇ»");
+
+    // Changing the options restarts the search from the current selections.
+    cx.set_state("abcd «abcˇ» abc");
+    cx.update_editor(|e, window, cx| {
+        e.select_next(
+            &SelectNext {
+                replace_newest: false,
+            },
+            window,
+            cx,
+        )
+    })
+    .unwrap();
+    cx.assert_editor_state("abcd «abcˇ» «abcˇ»");
+    cx.update(|_, cx| OccurrenceSearchOptions::default().set(cx));
+    cx.update_editor(|e, window, cx| {
+        e.select_next(
+            &SelectNext {
+                replace_newest: false,
+            },
+            window,
+            cx,
+        )
+    })
+    .unwrap();
+    cx.assert_editor_state("«abcˇ»d «abcˇ» «abcˇ»");
+}
+
+#[gpui::test]
+async fn test_select_all_matches_does_not_scroll(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let large_body_1 = "\nd".repeat(200);
+    let large_body_2 = "\ne".repeat(200);
+
+    cx.set_state(&format!(
+        "abc\nabc{large_body_1} «ˇa»bc{large_body_2}\nefabc\nabc"
+    ));
+    let initial_scroll_position = cx.update_editor(|editor, _, cx| {
+        let scroll_position = editor.scroll_position(cx);
+        assert!(scroll_position.y > 0.0, "Initial selection is between two large bodies and should have the editor scrolled to it");
+        scroll_position
+    });
+
+    cx.update_editor(|e, window, cx| e.select_all_matches(&SelectAllMatches, window, cx))
+        .unwrap();
+    cx.assert_editor_state(&format!(
         "«ˇa»bc\n«ˇa»bc{large_body_1} «ˇa»bc{large_body_2}\nef«ˇa»bc\n«ˇa»bc"
     ));
     let scroll_position_after_selection =
@@ -8522,6 +8978,60 @@ async fn test_select_previous_with_single_selection(cx: &mut TestAppContext) {
     cx.assert_editor_state("«ˇabc»\n«ˇabc» «ˇabc»\ndef«ˇabc»\n«ˇabc»");
 }
 
+#[gpui::test]
+async fn test_undo_selection_after_select_larger_syntax_node(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    ));
+    let text = "fn main() { let x = foo(1, 2); }";
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    let (initial, first_larger) = editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(0), 24)..DisplayPoint::new(DisplayRow(0), 24)
+            ]);
+        });
+        let initial = editor.selections.display_ranges(cx);
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        let first_larger = editor.selections.display_ranges(cx);
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        assert_ne!(editor.selections.display_ranges(cx), first_larger);
+        (initial, first_larger)
+    });
+
+    // Undoing a selection keeps the syntax node history, so the selection can
+    // keep shrinking from where the undo left it.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.undo_selection(&UndoSelection, window, cx);
+        assert_eq!(editor.selections.display_ranges(cx), first_larger);
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+        assert_eq!(editor.selections.display_ranges(cx), initial);
+    });
+
+    // Once the buffer is edited, the stored history no longer applies.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        editor
+            .buffer
+            .update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+        editor.undo_selection(&UndoSelection, window, cx);
+        let selections = editor.selections.display_ranges(cx);
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+        assert_eq!(editor.selections.display_ranges(cx), selections);
+    });
+}
+
 #[gpui::test]
 async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -8710,6 +9220,142 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_structural_navigation(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.set_state(indoc! {"
+        fn a() {
+            foo(ˇ1, bar(2));
+        }
+
+        fn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_next_sibling_node(&GoToNextSiblingNode, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            foo(1, ˇbar(2));
+        }
+
+        fn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_parent_node_end(&GoToParentNodeEnd, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            foo(1, barˇ(2));
+        }
+
+        fn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_previous_sibling_node(&GoToPreviousSiblingNode, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            foo(1, ˇbar(2));
+        }
+
+        fn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_previous_sibling_node(&GoToPreviousSiblingNode, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            foo(ˇ1, bar(2));
+        }
+
+        fn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_parent_node_start(&GoToParentNodeStart, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            fooˇ(1, bar(2));
+        }
+
+        fn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_next_function(&GoToNextFunction, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            foo(1, bar(2));
+        }
+
+        ˇfn b() {}
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_previous_function(&GoToPreviousFunction, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        ˇfn a() {
+            foo(1, bar(2));
+        }
+
+        fn b() {}
+    "});
+}
+
+#[gpui::test]
+async fn test_expand_excerpts_to_function(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = cx.new(|cx| {
+        Buffer::local(
+            indoc! {"
+                fn a() {
+                    let x = 1;
+                    foo(x);
+                    bar(x);
+                }
+
+                fn b() {}
+            "},
+            cx,
+        )
+        .with_language(rust_lang(), cx)
+    });
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::new(ReadWrite);
+        multibuffer.push_excerpts(
+            buffer.clone(),
+            [ExcerptRange {
+                context: Point::new(2, 0)..Point::new(2, 11),
+                primary: Point::new(2, 4)..Point::new(2, 7),
+            }],
+            cx,
+        );
+        multibuffer
+    });
+    let editor = cx.add_window(|window, cx| build_editor(multibuffer, window, cx));
+    cx.run_until_parked();
+
+    _ = editor.update(cx, |editor, window, cx| {
+        assert_eq!(editor.text(cx), "    foo(x);");
+        editor.expand_excerpts_to_function(&ExpandExcerptsToFunction, window, cx);
+        assert_eq!(
+            editor.text(cx),
+            "fn a() {\n    let x = 1;\n    foo(x);\n    bar(x);\n}"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_select_larger_syntax_node_for_cursor_at_end(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -9153,6 +9799,107 @@ async fn test_unwrap_syntax_nodes(cx: &mut gpui::TestAppContext) {
     cx.assert_editor_state(indoc! { r#"use mod1::{mod2::«mod3ˇ», mod5::«mod7ˇ»};"# });
 }
 
+#[gpui::test]
+async fn test_move_syntax_nodes(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    ));
+
+    cx.update_buffer(|buffer, cx| {
+        buffer.set_language(Some(language), cx);
+    });
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let v = [ˇa, b, c,];
+            foo(
+                1,
+            );
+        }
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.move_syntax_node_down(&MoveSyntaxNodeDown, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let v = [b, ˇa, c,];
+            foo(
+                1,
+            );
+        }
+    "});
+
+    // The trailing comma stays in place.
+    cx.update_editor(|editor, window, cx| {
+        editor.move_syntax_node_down(&MoveSyntaxNodeDown, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let v = [b, c, ˇa,];
+            foo(
+                1,
+            );
+        }
+    "});
+
+    // Without a next element, the enclosing statement is moved instead.
+    cx.update_editor(|editor, window, cx| {
+        editor.move_syntax_node_down(&MoveSyntaxNodeDown, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            foo(
+                1,
+            );
+            let v = [b, c, ˇa,];
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.move_syntax_node_up(&MoveSyntaxNodeUp, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            foo(
+                1,
+            );
+            let v = [b, ˇa, c,];
+        }
+    "});
+
+    // A function isn't swapped with its arguments.
+    cx.set_state(indoc! {"
+        fn main() {
+            let v = 1;
+            ˇfoo(1);
+        }
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.move_syntax_node_down(&MoveSyntaxNodeDown, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let v = 1;
+            ˇfoo(1);
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.move_syntax_node_up(&MoveSyntaxNodeUp, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            ˇfoo(1);
+            let v = 1;
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_fold_function_bodies(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -9256,6 +10003,58 @@ async fn test_fold_function_bodies(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_fold_imports_and_comments(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+    cx.set_state(indoc! {"
+        ˇuse std::fmt;
+        use std::io;
+
+        use crate::a;
+
+        // The first line of a comment
+        // and its second line.
+        fn main() {
+            // A single line comment.
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.fold_imports(&FoldImports, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                use std::fmt;⋯
+
+                use crate::a;
+
+                // The first line of a comment
+                // and its second line.
+                fn main() {
+                    // A single line comment.
+                }
+            "}
+        );
+
+        editor.fold_comments(&FoldComments, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                use std::fmt;⋯
+
+                use crate::a;
+
+                // The first line of a comment⋯
+                fn main() {
+                    // A single line comment.
+                }
+            "}
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_autoindent(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -11675,6 +12474,69 @@ async fn test_range_format_respects_language_tab_size_override(cx: &mut TestAppC
     save.await;
 }
 
+#[gpui::test]
+async fn test_format_selections_without_range_formatting(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), Default::default()).await;
+
+    let project = Project::test(fs, [path!("/").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_formatting_provider: Some(lsp::OneOf::Left(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+    cx.executor().start_waiting();
+    let _fake_server = fake_servers.next().await.unwrap();
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_text(
+            "fn a() {\nlet x = 1;\n        let y = 2;\n}\nfn b() {\nlet z = 3;\n}\n",
+            window,
+            cx,
+        );
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(2, 0)])
+        });
+    });
+    cx.run_until_parked();
+
+    // The language server can't format ranges, so the selected lines are reindented instead,
+    // leaving the rest of the buffer untouched.
+    editor
+        .update_in(cx, |editor, window, cx| {
+            editor.format_selections(&FormatSelections, window, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            editor.text(cx),
+            "fn a() {\n    let x = 1;\n    let y = 2;\n}\nfn b() {\nlet z = 3;\n}\n"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_document_format_manual_trigger(cx: &mut TestAppContext) {
     init_test(cx, |settings| {
@@ -11800,6 +12662,91 @@ async fn test_document_format_manual_trigger(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_document_format_with_multiple_servers(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.formatter = Some(SelectedFormatter::List(FormatterList::Single(
+            Formatter::LanguageServer { name: None },
+        )))
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), Default::default()).await;
+
+    let project = Project::test(fs, [path!("/").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers_without_formatting = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "tailwindcss-language-server",
+            ..FakeLspAdapter::default()
+        },
+    );
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_formatting_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            name: "rust-analyzer",
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_text("one\ntwo\nthree\n", window, cx)
+    });
+
+    cx.executor().start_waiting();
+    let fake_server_without_formatting = fake_servers_without_formatting.next().await.unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server_without_formatting.set_request_handler::<lsp::request::Formatting, _, _>(
+        move |_, _| async move { panic!("Should not be called") },
+    );
+
+    // The first server can't format, so the next one that can is used.
+    let format = editor
+        .update_in(cx, |editor, window, cx| {
+            editor.perform_format(
+                project.clone(),
+                FormatTrigger::Manual,
+                FormatTarget::Buffers(editor.buffer().read(cx).all_buffers()),
+                window,
+                cx,
+            )
+        })
+        .unwrap();
+    fake_server
+        .set_request_handler::<lsp::request::Formatting, _, _>(move |_, _| async move {
+            Ok(Some(vec![lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(1, 0)),
+                ", ".to_string(),
+            )]))
+        })
+        .next()
+        .await;
+    cx.executor().start_waiting();
+    format.await;
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "one, two\nthree\n"
+    );
+}
+
 #[gpui::test]
 async fn test_multiple_formatters(cx: &mut TestAppContext) {
     init_test(cx, |settings| {
@@ -12056,10 +13003,126 @@ async fn test_multiple_formatters(cx: &mut TestAppContext) {
             .unindent()
         );
 
-        // All the formatting (including the command, which completed after the manual edit)
-        // is undone together.
+        // All the formatting (including the command, which completed after the manual edit)
+        // is undone together.
+        editor.undo(&Default::default(), window, cx);
+        assert_eq!(editor.text(cx), "one  \ntwo   \nthree");
+    });
+}
+
+#[gpui::test]
+async fn test_code_actions_on_format_order_and_timeout(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.formatter = Some(SelectedFormatter::List(FormatterList::Single(
+            Formatter::LanguageServer { name: None },
+        )));
+        settings.defaults.code_actions_on_format = Some(
+            [("source.organizeImports".into(), true)]
+                .into_iter()
+                .collect(),
+        );
+        settings.defaults.code_actions_on_format_order = Some(CodeActionsOnFormatOrder::After);
+        settings.defaults.code_actions_on_format_timeout_ms = Some(1000);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), "one\n".into()).await;
+
+    let project = Project::test(fs, [path!("/").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_formatting_provider: Some(lsp::OneOf::Left(true)),
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::Formatting, _, _>(
+        move |_params, _| async move {
+            Ok(Some(vec![lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                "formatted\n".to_string(),
+            )]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CodeActionRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(
+                params.context.only,
+                Some(vec!["source.organizeImports".into()])
+            );
+            let uri = lsp::Uri::from_file_path(path!("/file.rs")).unwrap();
+            Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                lsp::CodeAction {
+                    kind: Some("source.organizeImports".into()),
+                    edit: Some(lsp::WorkspaceEdit::new(
+                        [(
+                            uri,
+                            vec![lsp::TextEdit::new(
+                                lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                                "organized-imports\n".to_string(),
+                            )],
+                        )]
+                        .into_iter()
+                        .collect(),
+                    )),
+                    ..Default::default()
+                },
+            )]))
+        },
+    );
+
+    let perform_format = |editor: &Entity<Editor>, cx: &mut VisualTestContext| {
+        editor
+            .update_in(cx, |editor, window, cx| {
+                editor.perform_format(
+                    project.clone(),
+                    FormatTrigger::Manual,
+                    FormatTarget::Buffers(editor.buffer().read(cx).all_buffers()),
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+    };
+
+    // The code actions run after the formatter, so their edits come last.
+    perform_format(&editor, cx).await;
+    editor.update_in(cx, |editor, window, cx| {
+        assert_eq!(editor.text(cx), "organized-imports\nformatted\none\n");
         editor.undo(&Default::default(), window, cx);
-        assert_eq!(editor.text(cx), "one  \ntwo   \nthree");
+        assert_eq!(editor.text(cx), "one\n");
+    });
+
+    // A server that doesn't provide the code actions in time doesn't prevent formatting.
+    fake_server.set_request_handler::<lsp::request::CodeActionRequest, _, _>(move |_, _| {
+        futures::future::pending()
+    });
+    let format = perform_format(&editor, cx);
+    cx.run_until_parked();
+    cx.executor().advance_clock(Duration::from_millis(1000));
+    format.await;
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "formatted\none\n");
     });
 }
 
@@ -13139,6 +14202,65 @@ async fn test_signature_help_multiple_signatures(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_signature_help_trigger_characters(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings.editor.auto_signature_help = Some(true);
+            });
+        });
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            signature_help_provider: Some(lsp::SignatureHelpOptions {
+                trigger_characters: Some(vec!["<".to_string()]),
+                retrigger_characters: Some(vec![",".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn main() {
+            sample::ˇ
+        }
+    "});
+
+    let mocked_response = lsp::SignatureHelp {
+        signatures: vec![lsp::SignatureInformation {
+            label: "fn sample<A, B>(param1: A, param2: B)".to_string(),
+            documentation: None,
+            parameters: None,
+            active_parameter: None,
+        }],
+        active_signature: Some(0),
+        active_parameter: Some(0),
+    };
+
+    // A retrigger character does not open signature help on its own.
+    cx.update_editor(|editor, window, cx| editor.handle_input(",", window, cx));
+    cx.run_until_parked();
+    cx.editor(|editor, _, _| assert!(!editor.signature_help_state.is_shown()));
+
+    cx.update_editor(|editor, window, cx| editor.handle_input("<", window, cx));
+    handle_signature_help_request(&mut cx, mocked_response.clone()).await;
+    cx.condition(|editor, _| editor.signature_help_state.is_shown())
+        .await;
+
+    // Once shown, typing a retrigger character requests signature help again.
+    cx.update_editor(|editor, window, cx| editor.handle_input("A", window, cx));
+    cx.update_editor(|editor, window, cx| editor.handle_input(",", window, cx));
+    handle_signature_help_request(&mut cx, mocked_response).await;
+    cx.condition(|editor, _| editor.signature_help_state.is_shown())
+        .await;
+}
+
 #[gpui::test]
 async fn test_completion_mode(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -14054,6 +15176,118 @@ async fn test_completion(cx: &mut TestAppContext) {
     apply_additional_edits.await.unwrap();
 }
 
+#[gpui::test]
+async fn test_completion_commit_characters(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state("oneˇ");
+    cx.simulate_keystroke(".");
+    cx.set_request_handler::<lsp::request::Completion, _, _>(move |_, _, _| async move {
+        Ok(Some(lsp::CompletionResponse::Array(vec![
+            lsp::CompletionItem {
+                label: "first".to_string(),
+                commit_characters: Some(vec![";".to_string()]),
+                ..Default::default()
+            },
+            lsp::CompletionItem {
+                label: "second".to_string(),
+                ..Default::default()
+            },
+        ])))
+    })
+    .next()
+    .await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+
+    // Typing a commit character of the selected completion accepts it first.
+    cx.simulate_keystroke(";");
+    cx.assert_editor_state("one.first;ˇ");
+    cx.update_editor(|editor, _, _| assert!(!editor.context_menu_visible()));
+
+    cx.set_state("oneˇ");
+    cx.simulate_keystroke(".");
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.update_editor(|editor, window, cx| {
+        editor.context_menu_next(&Default::default(), window, cx);
+    });
+
+    // Other completions are not accepted by it.
+    cx.simulate_keystroke(";");
+    cx.assert_editor_state("one.;ˇ");
+}
+
+#[gpui::test]
+async fn test_completion_auto_confirm_single_match(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    update_test_language_settings(cx, |settings| {
+        settings.defaults.completions = Some(CompletionSettingsContent {
+            words: Some(WordsCompletionMode::Disabled),
+            auto_confirm_single_match: Some(true),
+            ..Default::default()
+        });
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    // Both completions match, so the menu stays open.
+    cx.set_state("one.ˇ");
+    cx.simulate_keystroke("a");
+    handle_completion_request(
+        "one.<a|>",
+        vec!["apple", "banana"],
+        false,
+        counter.clone(),
+        &mut cx,
+    )
+    .await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.assert_editor_state("one.aˇ");
+
+    cx.update_editor(|editor, window, cx| {
+        editor.hide_context_menu(window, cx);
+    });
+    cx.set_state("one.ˇ");
+    cx.simulate_keystroke("b");
+    handle_completion_request(
+        "one.<b|>",
+        vec!["apple", "banana"],
+        false,
+        counter.clone(),
+        &mut cx,
+    )
+    .await;
+    cx.run_until_parked();
+    cx.assert_editor_state("one.bananaˇ");
+    cx.update_editor(|editor, _, _| assert!(!editor.context_menu_visible()));
+    assert_eq!(counter.load(atomic::Ordering::Acquire), 2);
+}
+
 #[gpui::test]
 async fn test_completion_reuse(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -15686,6 +16920,35 @@ async fn test_toggle_block_comment(cx: &mut TestAppContext) {
         "#
         .unindent(),
     );
+
+    // A selection spanning several languages toggles each of its lines
+    // with the comment syntax of the language at the start of that line.
+    cx.set_state(
+        &r#"
+            <p>A</p>
+            «<script>
+                var x = new Y();
+
+                var z = x;
+            </script>ˇ»
+        "#
+        .unindent(),
+    );
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_comments(&ToggleComments::default(), window, cx)
+    });
+    cx.assert_editor_state(
+        &r#"
+            <p>A</p>
+            <!-- «<script> -->
+                // var x = new Y();
+
+                // var z = x;
+            <!-- </script>ˇ» -->
+        "#
+        .unindent(),
+    );
 }
 
 #[gpui::test]
@@ -16857,6 +18120,7 @@ async fn test_on_type_formatting_not_triggered(cx: &mut TestAppContext) {
                     newline: true,
                 }],
                 disabled_scopes_by_bracket_ix: Vec::new(),
+                enabled_scopes_by_bracket_ix: Vec::new(),
             },
             ..Default::default()
         },
@@ -18980,6 +20244,56 @@ async fn test_toggle_selected_diff_hunks(executor: BackgroundExecutor, cx: &mut
     );
 }
 
+#[gpui::test]
+async fn test_copy_hunk_original_text(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let diff_base = r#"
+        use some::mod;
+
+        fn main() {
+            println!("hello");
+        }
+        "#
+    .unindent();
+
+    cx.set_state(
+        &r#"
+        use some::modified;
+
+        fn main() {
+            println!("hello there");ˇ
+            println!("world");
+        }
+        "#
+        .unindent(),
+    );
+
+    cx.set_head_text(&diff_base);
+    executor.run_until_parked();
+
+    cx.update_editor(|editor, window, cx| {
+        editor.copy_hunk_original_text(&CopyHunkOriginalText, window, cx);
+    });
+    assert_eq!(
+        cx.read_from_clipboard()
+            .and_then(|item| item.text().as_deref().map(str::to_string)),
+        Some("    println!(\"hello\");\n".to_string())
+    );
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_all(&SelectAll, window, cx);
+        editor.copy_hunk_original_text(&CopyHunkOriginalText, window, cx);
+    });
+    assert_eq!(
+        cx.read_from_clipboard()
+            .and_then(|item| item.text().as_deref().map(str::to_string)),
+        Some("use some::mod;\n    println!(\"hello\");\n".to_string())
+    );
+}
+
 #[gpui::test]
 async fn test_diff_base_change_with_expanded_diff_hunks(
     executor: BackgroundExecutor,
@@ -19897,6 +21211,31 @@ async fn test_stage_and_unstage_added_file_hunk(
     cx.assert_index_text(None);
 }
 
+#[gpui::test]
+fn test_reading_mode(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.reading_mode = Some(ReadingModeSettingsContent {
+            enabled: Some(true),
+            measure: Some(60),
+            typewriter_scrolling: None,
+        });
+    });
+
+    let editor = cx.add_window(|window, cx| {
+        let buffer = MultiBuffer::build_simple("one two three", cx);
+        build_editor(buffer, window, cx)
+    });
+    _ = editor.update(cx, |editor, window, cx| {
+        assert!(matches!(editor.soft_wrap_mode(cx), SoftWrap::Bounded(60)));
+        assert!(editor.wrap_guides(cx).is_empty());
+        assert!(!editor.snapshot(window, cx).show_gutter);
+
+        // Explicitly changing the soft wrap mode takes precedence over reading mode.
+        editor.set_soft_wrap_mode(language_settings::SoftWrap::None, cx);
+        assert!(matches!(editor.soft_wrap_mode(cx), SoftWrap::None));
+    });
+}
+
 async fn setup_indent_guides_editor(
     text: &str,
     cx: &mut TestAppContext,
@@ -20538,6 +21877,40 @@ async fn test_active_indent_guide_non_matching_indent(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_active_indent_guide_follows_syntax(cx: &mut TestAppContext) {
+    let (buffer_id, mut cx) = setup_indent_guides_editor(
+        &"
+    fn main() {
+        let a = 1;
+            // comment
+        let b = 2;
+    }"
+        .unindent(),
+        cx,
+    )
+    .await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.run_until_parked();
+
+    cx.update_editor(|editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+        });
+    });
+
+    // The more indented comment below the cursor doesn't start a block.
+    assert_indent_guides(
+        0..5,
+        vec![
+            indent_guide(buffer_id, 1, 3, 0),
+            indent_guide(buffer_id, 2, 2, 1),
+        ],
+        Some(vec![0]),
+        &mut cx,
+    );
+}
+
 #[gpui::test]
 async fn test_indent_guide_with_expanded_diff_hunks(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -21389,6 +22762,91 @@ async fn test_goto_definition_no_fallback(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_go_to_declaration_and_implementation(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            declaration_provider: Some(lsp::DeclarationCapability::Simple(true)),
+            implementation_provider: Some(lsp::ImplementationProviderCapability::Simple(true)),
+            ..lsp::ServerCapabilities::default()
+        },
+        cx,
+    )
+    .await;
+
+    let initial_state = r#"
+        trait Shape {
+            fn area(&self);
+        }
+        impl Shape for A { fn area(&self) {} }
+        impl Shape for B { fn area(&self) {} }
+        fn f(shape: &dyn Shape) { shape.ˇarea() }"#
+        .unindent();
+    cx.set_state(&initial_state);
+    let mut declaration_requests = cx.set_request_handler::<lsp::request::GotoDeclaration, _, _>(
+        move |url, _, _| async move {
+            Ok(Some(lsp::GotoDefinitionResponse::Scalar(lsp::Location {
+                uri: url,
+                range: lsp::Range::new(lsp::Position::new(1, 7), lsp::Position::new(1, 11)),
+            })))
+        },
+    );
+    cx.set_request_handler::<lsp::request::GotoImplementation, _, _>(move |url, _, _| async move {
+        Ok(Some(lsp::GotoDefinitionResponse::Array(vec![
+            lsp::Location {
+                uri: url.clone(),
+                range: lsp::Range::new(lsp::Position::new(3, 22), lsp::Position::new(3, 26)),
+            },
+            lsp::Location {
+                uri: url,
+                range: lsp::Range::new(lsp::Position::new(4, 22), lsp::Position::new(4, 26)),
+            },
+        ])))
+    });
+
+    // The split variant is handled separately from the plain one.
+    cx.dispatch_action(GoToDeclarationSplit);
+    declaration_requests.next().await;
+    cx.run_until_parked();
+
+    let navigated = cx
+        .update_editor(|editor, window, cx| editor.go_to_declaration(&GoToDeclaration, window, cx))
+        .await
+        .expect("Failed to navigate to declaration");
+    assert_eq!(navigated, Navigated::Yes);
+    declaration_requests.next().await;
+    cx.assert_editor_state(
+        &r#"
+        trait Shape {
+            fn «areaˇ»(&self);
+        }
+        impl Shape for A { fn area(&self) {} }
+        impl Shape for B { fn area(&self) {} }
+        fn f(shape: &dyn Shape) { shape.area() }"#
+            .unindent(),
+    );
+
+    cx.set_state(&initial_state);
+    let editor_count = |cx: &mut EditorLspTestContext| {
+        cx.update_workspace(|workspace, _, cx| workspace.items_of_type::<Editor>(cx).count())
+    };
+    let editors_before = editor_count(&mut cx);
+    let navigated = cx
+        .update_editor(|editor, window, cx| {
+            editor.go_to_implementation(&GoToImplementation, window, cx)
+        })
+        .await
+        .expect("Failed to navigate to implementations");
+    assert_eq!(navigated, Navigated::Yes);
+    cx.assert_editor_state(&initial_state);
+    assert_eq!(
+        editor_count(&mut cx),
+        editors_before + 1,
+        "Multiple implementations should be opened in a new multibuffer"
+    );
+}
+
 #[gpui::test]
 async fn test_find_all_references_editor_reuse(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -23328,6 +24786,152 @@ async fn test_apply_code_lens_actions_with_commands(cx: &mut gpui::TestAppContex
     );
 }
 
+#[gpui::test]
+async fn test_code_lens_blocks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| settings.editor.code_lens = Some(true));
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "function a() {}\nfunction b() {}\nfunction c() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["ts".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+    )));
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                    commands: vec!["_the/command".to_string()],
+                    ..lsp::ExecuteCommandOptions::default()
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let editor = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_abs_path(
+                PathBuf::from(path!("/dir/a.ts")),
+                OpenOptions::default(),
+                window,
+                cx,
+            )
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let command = lsp::Command {
+        title: "2 references".to_owned(),
+        command: "_the/command".to_owned(),
+        arguments: None,
+    };
+    fake_server.set_request_handler::<lsp::request::CodeLensRequest, _, _>({
+        let command = command.clone();
+        move |_, _| {
+            let command = command.clone();
+            async move {
+                Ok(Some(vec![
+                    lsp::CodeLens {
+                        range: lsp::Range::new(lsp::Position::new(0, 9), lsp::Position::new(0, 10)),
+                        command: Some(command),
+                        data: None,
+                    },
+                    lsp::CodeLens {
+                        range: lsp::Range::new(lsp::Position::new(2, 9), lsp::Position::new(2, 10)),
+                        command: None,
+                        data: Some(json!("unresolved")),
+                    },
+                ]))
+            }
+        }
+    });
+    let resolve_requests = Arc::new(AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::CodeLensResolve, _, _>({
+        let resolve_requests = resolve_requests.clone();
+        move |mut lens, _| {
+            resolve_requests.fetch_add(1, atomic::Ordering::Release);
+            lens.command = Some(command.clone());
+            async move { Ok(lens) }
+        }
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.refresh_code_lenses(None, window, cx);
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    let block_ids = editor.update(cx, |editor, _| editor.code_lens_block_ids());
+    assert_eq!(
+        block_ids.len(),
+        2,
+        "Should show a block above each line with code lenses"
+    );
+    assert_eq!(
+        resolve_requests.load(atomic::Ordering::Acquire),
+        1,
+        "Should resolve only the code lens without a command"
+    );
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.refresh_code_lenses(None, window, cx);
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    editor.update(cx, |editor, _| {
+        assert_eq!(
+            editor.code_lens_block_ids(),
+            block_ids,
+            "Should keep the blocks of lines that still have code lenses"
+        );
+    });
+
+    cx.update(|_, cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| settings.editor.code_lens = Some(false));
+        });
+    });
+    cx.executor().run_until_parked();
+    editor.update(cx, |editor, _| {
+        assert!(
+            editor.code_lens_block_ids().is_empty(),
+            "Should remove the code lens blocks when disabled in settings"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_editor_restore_data_different_in_panes(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -23866,7 +25470,7 @@ async fn test_html_linked_edits_on_completion(cx: &mut TestAppContext) {
             buffer.anchor_before(Point::new(0, 6))..buffer.anchor_after(Point::new(0, 8));
         let mut linked_ranges = HashMap::default();
         linked_ranges.insert(buffer_id, vec![(opening_range, vec![closing_range])]);
-        editor.linked_edit_ranges = LinkedEditingRanges(linked_ranges);
+        editor.linked_edit_ranges = LinkedEditingRanges(linked_ranges, Default::default());
     });
     let mut completion_handle =
         fake_server.set_request_handler::<lsp::request::Completion, _, _>(move |_, _| async move {
@@ -23910,6 +25514,47 @@ async fn test_html_linked_edits_on_completion(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_linked_edits_word_pattern(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let link_tag_names = |cx: &mut EditorTestContext, word_pattern: Option<&str>| {
+        cx.update_editor(|editor, _, cx| {
+            let buffer = editor.buffer.read(cx).as_singleton().unwrap();
+            let buffer = buffer.read(cx);
+            let buffer_id = buffer.remote_id();
+            let opening_range =
+                buffer.anchor_before(Point::new(0, 1))..buffer.anchor_after(Point::new(0, 3));
+            let closing_range =
+                buffer.anchor_before(Point::new(0, 6))..buffer.anchor_after(Point::new(0, 8));
+            let mut linked_ranges = HashMap::default();
+            linked_ranges.insert(buffer_id, vec![(opening_range, vec![closing_range])]);
+            let mut word_patterns = HashMap::default();
+            if let Some(word_pattern) = word_pattern {
+                word_patterns.insert(buffer_id, regex::Regex::new(word_pattern).unwrap());
+            }
+            editor.linked_edit_ranges = LinkedEditingRanges(linked_ranges, word_patterns);
+        });
+    };
+
+    // Without a word pattern, whitespace stops the linked editing.
+    cx.set_state("<abˇ></ab>");
+    link_tag_names(&mut cx, None);
+    cx.update_editor(|editor, window, cx| editor.handle_input(" ", window, cx));
+    cx.assert_editor_state("<ab ˇ></ab>");
+
+    // With one, edits are mirrored as long as the ranges keep matching it.
+    cx.set_state("<abˇ></ab>");
+    link_tag_names(&mut cx, Some("^[^\\s<>/]+$"));
+    cx.update_editor(|editor, window, cx| editor.handle_input("-", window, cx));
+    cx.assert_editor_state("<ab-ˇ></ab->");
+    cx.update_editor(|editor, window, cx| editor.handle_input("/", window, cx));
+    cx.assert_editor_state("<ab-/ˇ></ab->");
+    cx.update_editor(|editor, window, cx| editor.handle_input("c", window, cx));
+    cx.assert_editor_state("<ab-/cˇ></ab->");
+}
+
 #[gpui::test]
 async fn test_invisible_worktree_servers(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -25899,3 +27544,165 @@ fn extract_color_inlays(editor: &Editor, cx: &App) -> Vec<Rgba> {
         .map(Rgba::from)
         .collect()
 }
+
+#[gpui::test]
+async fn test_proposed_changes_apply_all_and_undo(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer_1 = cx.new(|cx| Buffer::local("one\ntwo\nthree\n", cx));
+    let buffer_2 = cx.new(|cx| Buffer::local("four\nfive\n", cx));
+    let window = cx.add_window(|window, cx| {
+        ProposedChangesEditor::new(
+            "Proposed changes",
+            Vec::<ProposedChangeLocation<usize>>::new(),
+            None,
+            window,
+            cx,
+        )
+    });
+    let cx = &mut VisualTestContext::from_window(*window, cx);
+    let proposed_changes_editor = window.root(cx).unwrap();
+
+    proposed_changes_editor.update(cx, |editor, cx| {
+        editor.stage_edits(&buffer_1, vec![(4..7, "TWO".to_string())], cx);
+        editor.stage_edits(&buffer_2, vec![(0..4, "FOUR".to_string())], cx);
+    });
+    // Staging leaves the buffers themselves untouched.
+    assert_eq!(
+        buffer_1.read_with(cx, |buffer, _| buffer.text()),
+        "one\ntwo\nthree\n"
+    );
+    assert_eq!(
+        buffer_2.read_with(cx, |buffer, _| buffer.text()),
+        "four\nfive\n"
+    );
+
+    proposed_changes_editor.update_in(cx, |editor, window, cx| {
+        editor.apply_all(&ApplyAllDiffHunks, window, cx);
+        assert!(editor.can_undo_apply_all());
+    });
+    assert_eq!(
+        buffer_1.read_with(cx, |buffer, _| buffer.text()),
+        "one\nTWO\nthree\n"
+    );
+    assert_eq!(
+        buffer_2.read_with(cx, |buffer, _| buffer.text()),
+        "FOUR\nfive\n"
+    );
+
+    proposed_changes_editor.update_in(cx, |editor, window, cx| {
+        editor.undo_apply_all(&UndoApplyAllDiffHunks, window, cx);
+        assert!(!editor.can_undo_apply_all());
+    });
+    assert_eq!(
+        buffer_1.read_with(cx, |buffer, _| buffer.text()),
+        "one\ntwo\nthree\n"
+    );
+    assert_eq!(
+        buffer_2.read_with(cx, |buffer, _| buffer.text()),
+        "four\nfive\n"
+    );
+}
+
+#[gpui::test]
+async fn test_bookmark_toggling_and_navigation(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": "one\ntwo\nthree\nfour\nfive",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(1, 2)..Point::new(1, 2)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(3, 0)..Point::new(3, 0)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+
+    // Bookmarks follow their lines as text is inserted above them.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.insert("zero\n", window, cx);
+    });
+    let bookmark_rows = |editor: &Editor, cx: &App| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor
+            .bookmarks_in_range(Anchor::min()..Anchor::max(), &snapshot, cx)
+            .into_iter()
+            .map(|anchor| anchor.to_point(&snapshot).row)
+            .collect::<Vec<_>>()
+    };
+    editor.update(cx, |editor, cx| {
+        assert_eq!(bookmark_rows(editor, cx), vec![2, 4]);
+    });
+
+    let cursor_row = |editor: &mut Editor, cx: &mut Context<Editor>| {
+        editor.selections.newest::<Point>(cx).head().row
+    };
+    editor.update_in(cx, |editor, window, cx| {
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+        assert_eq!(cursor_row(editor, cx), 2);
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+        assert_eq!(cursor_row(editor, cx), 4);
+        // Wraps around to the first bookmark.
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+        assert_eq!(cursor_row(editor, cx), 2);
+        editor.go_to_previous_bookmark(&actions::GoToPreviousBookmark, window, cx);
+        assert_eq!(cursor_row(editor, cx), 4);
+
+        // Toggling again removes the bookmark.
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        assert_eq!(bookmark_rows(editor, cx), vec![2]);
+    });
+}
+
+#[gpui::test]
+async fn test_remove_invisible_characters(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Only the selected text is cleaned up when there's a selection.
+    cx.set_state("a\u{200b}«b\u{202e}cˇ»\u{2066}d\n");
+    cx.update_editor(|editor, window, cx| {
+        editor.remove_invisible_characters(&actions::RemoveInvisibleCharacters, window, cx)
+    });
+    cx.assert_editor_state("a\u{200b}«bcˇ»\u{2066}d\n");
+
+    // The whole buffer is cleaned up otherwise, leaving joiners used by emoji alone.
+    cx.set_state("ˇ\u{feff}let x = 1;\u{2067} // 👩\u{200d}💻\n");
+    cx.update_editor(|editor, window, cx| {
+        editor.remove_invisible_characters(&actions::RemoveInvisibleCharacters, window, cx)
+    });
+    cx.assert_editor_state("ˇlet x = 1; // 👩\u{200d}💻\n");
+}
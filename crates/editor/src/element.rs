@@ -8,8 +8,8 @@ use crate::{
     HandleInput, HoveredCursor, InlayHintRefreshReason, JumpData, LineDown, LineHighlight, LineUp,
     MAX_LINE_LEN, MINIMAP_FONT_SIZE, MULTI_BUFFER_EXCERPT_HEADER_HEIGHT, OpenExcerpts, PageDown,
     PageUp, PhantomBreakpointIndicator, Point, RowExt, RowRangeExt, SelectPhase,
-    SelectedTextHighlight, Selection, SelectionDragState, SoftWrap, StickyHeaderExcerpt, ToPoint,
-    ToggleFold, ToggleFoldAll,
+    SelectedTextHighlight, Selection, SelectionDragState, SelectionEffects, SoftWrap,
+    StickyHeaderExcerpt, ToPoint, ToggleFold, ToggleFoldAll,
     code_context_menus::{CodeActionsMenu, MENU_ASIDE_MAX_WIDTH, MENU_ASIDE_MIN_WIDTH, MENU_GAP},
     display_map::{
         Block, BlockContext, BlockStyle, ChunkRendererId, DisplaySnapshot, EditorMargins,
@@ -28,7 +28,7 @@ use crate::{
     inlay_hint_settings,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition},
-    scroll::{ActiveScrollbarState, ScrollbarThumbState, scroll_amount::ScrollAmount},
+    scroll::{ActiveScrollbarState, Autoscroll, ScrollbarThumbState, scroll_amount::ScrollAmount},
 };
 use buffer_diff::{DiffHunkStatus, DiffHunkStatusKind};
 use collections::{BTreeMap, HashMap};
@@ -46,9 +46,9 @@ use gpui::{
     KeybindingKeystroke, Length, ModifiersChangedEvent, MouseButton, MouseClickEvent,
     MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
     ScrollHandle, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
-    Style, Styled, TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
-    linear_color_stop, linear_gradient, outline, point, px, quad, relative, size, solid_background,
-    transparent_black,
+    Style, Styled, StyledText, TextRun, TextStyleRefinement, WeakEntity, Window, anchored,
+    deferred, div, fill, linear_color_stop, linear_gradient, outline, point, px, quad, relative,
+    size, solid_background, transparent_black,
 };
 use itertools::Itertools;
 use language::{IndentGuideSettings, language_settings::ShowWhitespaceSetting};
@@ -236,12 +236,15 @@ impl EditorElement {
         register_action(editor, window, Editor::join_lines);
         register_action(editor, window, Editor::sort_lines_by_length);
         register_action(editor, window, Editor::sort_lines_case_sensitive);
+        register_action(editor, window, Editor::sort_lines_descending);
+        register_action(editor, window, Editor::sort_lines_natural);
         register_action(editor, window, Editor::sort_lines_case_insensitive);
         register_action(editor, window, Editor::reverse_lines);
         register_action(editor, window, Editor::shuffle_lines);
         register_action(editor, window, Editor::convert_indentation_to_spaces);
         register_action(editor, window, Editor::convert_indentation_to_tabs);
         register_action(editor, window, Editor::convert_to_upper_case);
+        register_action(editor, window, Editor::remove_invisible_characters);
         register_action(editor, window, Editor::convert_to_lower_case);
         register_action(editor, window, Editor::convert_to_title_case);
         register_action(editor, window, Editor::convert_to_snake_case);
@@ -265,6 +268,8 @@ impl EditorElement {
         register_action(editor, window, Editor::duplicate_selection);
         register_action(editor, window, Editor::move_line_up);
         register_action(editor, window, Editor::move_line_down);
+        register_action(editor, window, Editor::move_syntax_node_up);
+        register_action(editor, window, Editor::move_syntax_node_down);
         register_action(editor, window, Editor::transpose);
         register_action(editor, window, Editor::rewrap);
         register_action(editor, window, Editor::cut);
@@ -274,6 +279,8 @@ impl EditorElement {
         register_action(editor, window, Editor::copy_and_trim);
         register_action(editor, window, Editor::diff_clipboard_with_selection);
         register_action(editor, window, Editor::paste);
+        register_action(editor, window, Editor::paste_without_reindent);
+        register_action(editor, window, Editor::smart_paste);
         register_action(editor, window, Editor::undo);
         register_action(editor, window, Editor::redo);
         register_action(editor, window, Editor::move_page_up);
@@ -368,6 +375,15 @@ impl EditorElement {
         register_action(editor, window, Editor::select_smaller_syntax_node);
         register_action(editor, window, Editor::select_next_syntax_node);
         register_action(editor, window, Editor::select_prev_syntax_node);
+        register_action(editor, window, Editor::edit_color);
+        register_action(editor, window, Editor::go_to_next_function);
+        register_action(editor, window, Editor::go_to_previous_function);
+        register_action(editor, window, Editor::go_to_next_class);
+        register_action(editor, window, Editor::go_to_previous_class);
+        register_action(editor, window, Editor::go_to_next_sibling_node);
+        register_action(editor, window, Editor::go_to_previous_sibling_node);
+        register_action(editor, window, Editor::go_to_parent_node_start);
+        register_action(editor, window, Editor::go_to_parent_node_end);
         register_action(editor, window, Editor::unwrap_syntax_node);
         register_action(editor, window, Editor::select_enclosing_symbol);
         register_action(editor, window, Editor::move_to_enclosing_bracket);
@@ -377,6 +393,7 @@ impl EditorElement {
             register_action(editor, window, Editor::expand_excerpts);
             register_action(editor, window, Editor::expand_excerpts_up);
             register_action(editor, window, Editor::expand_excerpts_down);
+            register_action(editor, window, Editor::expand_excerpts_to_function);
         }
         register_action(editor, window, Editor::go_to_diagnostic);
         register_action(editor, window, Editor::go_to_prev_diagnostic);
@@ -394,6 +411,13 @@ impl EditorElement {
                 .go_to_definition_split(action, window, cx)
                 .detach_and_log_err(cx);
         });
+        register_action(editor, window, |editor, action, window, cx| {
+            if let Some(task) = editor.go_to_dependency_source(action, window, cx) {
+                task.detach_and_notify_err(window, cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(editor, window, |editor, action, window, cx| {
             editor
                 .go_to_declaration(action, window, cx)
@@ -430,6 +454,9 @@ impl EditorElement {
         register_action(editor, window, Editor::fold_at_level);
         register_action(editor, window, Editor::fold_all);
         register_action(editor, window, Editor::fold_function_bodies);
+        register_action(editor, window, Editor::fold_class_bodies);
+        register_action(editor, window, Editor::fold_comments);
+        register_action(editor, window, Editor::fold_imports);
         register_action(editor, window, Editor::fold_recursive);
         register_action(editor, window, Editor::toggle_fold);
         register_action(editor, window, Editor::toggle_fold_recursive);
@@ -448,10 +475,14 @@ impl EditorElement {
         register_action(editor, window, Editor::open_proposed_changes_editor);
         register_action(editor, window, Editor::toggle_soft_wrap);
         register_action(editor, window, Editor::toggle_tab_bar);
+        register_action(editor, window, Editor::toggle_reading_mode);
+        register_action(editor, window, Editor::toggle_related_file);
         register_action(editor, window, Editor::toggle_line_numbers);
         register_action(editor, window, Editor::toggle_relative_line_numbers);
         register_action(editor, window, Editor::toggle_indent_guides);
         register_action(editor, window, Editor::toggle_inlay_hints);
+        register_action(editor, window, Editor::toggle_type_inlay_hints);
+        register_action(editor, window, Editor::toggle_parameter_inlay_hints);
         register_action(editor, window, Editor::toggle_edit_predictions);
         if editor.read(cx).diagnostics_enabled() {
             register_action(editor, window, Editor::toggle_diagnostics);
@@ -469,6 +500,7 @@ impl EditorElement {
         register_action(editor, window, Editor::copy_file_name);
         register_action(editor, window, Editor::copy_file_name_without_extension);
         register_action(editor, window, Editor::copy_highlight_json);
+        register_action(editor, window, Editor::copy_selection_as_image);
         register_action(editor, window, Editor::copy_permalink_to_line);
         register_action(editor, window, Editor::open_permalink_to_line);
         register_action(editor, window, Editor::copy_file_location);
@@ -580,6 +612,7 @@ impl EditorElement {
         register_action(editor, window, Editor::accept_edit_prediction);
         register_action(editor, window, Editor::restore_file);
         register_action(editor, window, Editor::git_restore);
+        register_action(editor, window, Editor::copy_hunk_original_text);
         register_action(editor, window, Editor::apply_all_diff_hunks);
         register_action(editor, window, Editor::apply_selected_diff_hunks);
         register_action(editor, window, Editor::open_active_item_in_terminal);
@@ -589,6 +622,9 @@ impl EditorElement {
         register_action(editor, window, Editor::insert_uuid_v7);
         register_action(editor, window, Editor::open_selections_in_multibuffer);
         register_action(editor, window, Editor::toggle_breakpoint);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_previous_bookmark);
         register_action(editor, window, Editor::edit_log_breakpoint);
         register_action(editor, window, Editor::enable_breakpoint);
         register_action(editor, window, Editor::disable_breakpoint);
@@ -664,17 +700,39 @@ impl EditorElement {
             return;
         }
 
+        // Color swatches are inlays, so a click on one lands between two valid positions.
+        if click_count == 1
+            && !modifiers.modified()
+            && point_for_position.column_overshoot_after_line_end == 0
+            && point_for_position.as_valid().is_none()
+            && let Some(color_range) = editor.document_color_swatch_at(
+                point_for_position
+                    .previous_valid
+                    .to_offset(&position_map.snapshot, Bias::Left),
+                cx,
+            )
+        {
+            editor.open_color_picker(color_range, window, cx);
+            cx.stop_propagation();
+            return;
+        }
+
         if EditorSettings::get_global(cx)
             .drag_and_drop_selection
             .enabled
             && click_count == 1
         {
-            let newest_anchor = editor.selections.newest_anchor();
             let snapshot = editor.snapshot(window, cx);
-            let selection = newest_anchor.map(|anchor| anchor.to_display_point(&snapshot));
-            if point_for_position.intersects_selection(&selection) {
+            let selections: Arc<[Selection<Anchor>]> = editor
+                .selections
+                .disjoint_anchors()
+                .iter()
+                .filter(|selection| selection.start != selection.end)
+                .cloned()
+                .collect();
+            if point_for_position.intersects_any_selection(&selections, &snapshot) {
                 editor.selection_drag_state = SelectionDragState::ReadyToDrag {
-                    selection: newest_anchor.clone(),
+                    selections,
                     click_position: event.position,
                     mouse_down_time: Instant::now(),
                 };
@@ -874,7 +932,7 @@ impl EditorElement {
 
         match editor.selection_drag_state {
             SelectionDragState::ReadyToDrag {
-                selection: _,
+                selections: _,
                 ref click_position,
                 mouse_down_time: _,
             } => {
@@ -895,16 +953,15 @@ impl EditorElement {
                     debug_panic!("drag state can never be in ready state after drag")
                 }
             }
-            SelectionDragState::Dragging { ref selection, .. } => {
+            SelectionDragState::Dragging { ref selections, .. } => {
                 let snapshot = editor.snapshot(window, cx);
-                let selection_display = selection.map(|anchor| anchor.to_display_point(&snapshot));
-                if !point_for_position.intersects_selection(&selection_display)
+                if !point_for_position.intersects_any_selection(selections, &snapshot)
                     && text_hitbox.is_hovered(window)
                 {
                     let is_cut = !(cfg!(target_os = "macos") && event.modifiers.alt
                         || cfg!(not(target_os = "macos")) && event.modifiers.control);
-                    editor.move_selection_on_drop(
-                        &selection.clone(),
+                    editor.move_selections_on_drop(
+                        &selections.clone(),
                         point_for_position.previous_valid,
                         is_cut,
                         window,
@@ -1052,7 +1109,7 @@ impl EditorElement {
                     cx.notify();
                 }
                 SelectionDragState::ReadyToDrag {
-                    ref selection,
+                    ref selections,
                     ref click_position,
                     ref mouse_down_time,
                 } => {
@@ -1068,7 +1125,7 @@ impl EditorElement {
                             goal: SelectionGoal::None,
                         };
                         editor.selection_drag_state = SelectionDragState::Dragging {
-                            selection: selection.clone(),
+                            selections: selections.clone(),
                             drop_cursor,
                             hide_drop_cursor: false,
                         };
@@ -1363,6 +1420,19 @@ impl EditorElement {
 
         editor_with_selections.update(cx, |editor, cx| {
             if editor.show_local_selections {
+                // When following someone, the newest local cursor is theirs, so label it with
+                // their name.
+                let leader_name = editor.leader_id.and_then(|leader_id| match leader_id {
+                    CollaboratorId::PeerId(peer_id) => {
+                        let collaboration_hub = editor.collaboration_hub.as_ref()?;
+                        let collaborator = collaboration_hub.collaborators(cx).get(&peer_id)?;
+                        collaboration_hub
+                            .user_names(cx)
+                            .get(&collaborator.user_id)
+                            .cloned()
+                    }
+                    CollaboratorId::Agent => None,
+                });
                 let mut layouts = Vec::new();
                 let newest = editor.selections.newest(cx);
                 for selection in local_selections.iter().cloned() {
@@ -1376,7 +1446,7 @@ impl EditorElement {
                         &snapshot.display_snapshot,
                         is_newest,
                         editor.leader_id.is_none(),
-                        None,
+                        if is_newest { leader_name.clone() } else { None },
                     );
                     if is_newest {
                         newest_selection_head = Some(layout.head);
@@ -1397,19 +1467,21 @@ impl EditorElement {
                 selections.push((player, layouts));
 
                 if let SelectionDragState::Dragging {
-                    ref selection,
+                    ref selections,
                     ref drop_cursor,
                     ref hide_drop_cursor,
                 } = editor.selection_drag_state
                     && !hide_drop_cursor
-                    && (drop_cursor
-                        .start
-                        .cmp(&selection.start, &snapshot.buffer_snapshot)
-                        .eq(&Ordering::Less)
-                        || drop_cursor
-                            .end
-                            .cmp(&selection.end, &snapshot.buffer_snapshot)
-                            .eq(&Ordering::Greater))
+                    && selections.iter().all(|selection| {
+                        drop_cursor
+                            .start
+                            .cmp(&selection.start, &snapshot.buffer_snapshot)
+                            .eq(&Ordering::Less)
+                            || drop_cursor
+                                .end
+                                .cmp(&selection.end, &snapshot.buffer_snapshot)
+                                .eq(&Ordering::Greater)
+                    })
                 {
                     let drag_cursor_layout = SelectionLayout::new(
                         drop_cursor.clone(),
@@ -1451,6 +1523,14 @@ impl EditorElement {
                     }
                 }
 
+                const IDLE_REMOTE_CURSOR_OPACITY: f32 = 0.35;
+                let idle_replicas = editor.idle_remote_replicas(snapshot, cx);
+                let show_selections = EditorSettings::get_global(cx)
+                    .collaborator_cursors
+                    .show_selections;
+                let Some(collaboration_hub) = &editor.collaboration_hub else {
+                    return;
+                };
                 let mut remote_selections = HashMap::default();
                 for selection in snapshot.remote_selections_in_range(
                     &(start_anchor..end_anchor),
@@ -1470,13 +1550,28 @@ impl EditorElement {
                     let is_shown =
                         editor.show_cursor_names || editor.hovered_cursors.contains_key(&key);
 
+                    let mut color = selection.color;
+                    if idle_replicas.contains(&selection.replica_id) {
+                        color.cursor = color.cursor.opacity(IDLE_REMOTE_CURSOR_OPACITY);
+                        color.selection = color.selection.opacity(IDLE_REMOTE_CURSOR_OPACITY);
+                    }
+                    let mut remote_selection = selection.selection;
+                    let mut line_mode = selection.line_mode;
+                    if !show_selections {
+                        let head = remote_selection.head();
+                        remote_selection.start = head;
+                        remote_selection.end = head;
+                        remote_selection.reversed = false;
+                        line_mode = false;
+                    }
+
                     remote_selections
                         .entry(selection.replica_id)
-                        .or_insert((selection.color, Vec::new()))
+                        .or_insert((color, Vec::new()))
                         .1
                         .push(SelectionLayout::new(
-                            selection.selection,
-                            selection.line_mode,
+                            remote_selection,
+                            line_mode,
                             selection.cursor_shape,
                             &snapshot.display_snapshot,
                             false,
@@ -1938,8 +2033,21 @@ impl EditorElement {
             minimap.prepaint(window, cx)
         });
 
+        let markers = if minimap_settings.markers {
+            self.layout_minimap_markers(
+                snapshot,
+                minimap_bounds,
+                minimap_line_height,
+                minimap_scroll_top,
+                cx,
+            )
+        } else {
+            Vec::new()
+        };
+
         Some(MinimapLayout {
             minimap,
+            markers,
             thumb_layout: layout,
             thumb_border_style: minimap_settings.thumb_border,
             minimap_line_height,
@@ -1948,6 +2056,120 @@ impl EditorElement {
         })
     }
 
+    /// Lays out the git diff, search result and diagnostic markers along the
+    /// sides of the minimap. Only the rows visible in the minimap are looked at,
+    /// so that the cost doesn't grow with the size of the buffer.
+    fn layout_minimap_markers(
+        &self,
+        snapshot: &EditorSnapshot,
+        minimap_bounds: Bounds<Pixels>,
+        minimap_line_height: Pixels,
+        minimap_scroll_top: f32,
+        cx: &App,
+    ) -> Vec<PaintQuad> {
+        let scrollbar_settings = EditorSettings::get_global(cx).scrollbar;
+        let theme = cx.theme();
+        let buffer = &snapshot.buffer_snapshot;
+
+        let start_row = DisplayRow(minimap_scroll_top as u32);
+        let end_row = DisplayRow(
+            (minimap_scroll_top + minimap_bounds.size.height / minimap_line_height).ceil() as u32,
+        )
+        .min(snapshot.max_point().row());
+        let visible_range = snapshot
+            .display_point_to_anchor(DisplayPoint::new(start_row, 0), Bias::Left)
+            ..snapshot.display_point_to_anchor(
+                DisplayPoint::new(end_row, snapshot.line_len(end_row)),
+                Bias::Right,
+            );
+
+        let marker = |rows: Range<DisplayRow>, lane: Range<Pixels>, color: Hsla| {
+            let top = (rows.start.as_f32() - minimap_scroll_top) * minimap_line_height;
+            let bottom = (rows.end.as_f32() + 1. - minimap_scroll_top) * minimap_line_height;
+            fill(
+                Bounds::from_corners(
+                    minimap_bounds.origin + point(lane.start, top),
+                    minimap_bounds.origin + point(lane.end, bottom),
+                ),
+                color,
+            )
+        };
+        let left_lane = Pixels::ZERO..MinimapLayout::MARKER_WIDTH;
+        let right_lane =
+            minimap_bounds.size.width - MinimapLayout::MARKER_WIDTH..minimap_bounds.size.width;
+
+        let mut markers = Vec::new();
+        if scrollbar_settings.git_diff {
+            for hunk in buffer.diff_hunks_in_range(visible_range.clone()) {
+                let start = MultiBufferPoint::new(hunk.row_range.start.0, 0)
+                    .to_display_point(snapshot)
+                    .row();
+                let mut end = MultiBufferPoint::new(hunk.row_range.end.0, 0)
+                    .to_display_point(snapshot)
+                    .row();
+                if end != start {
+                    end.0 -= 1;
+                }
+                let color = match hunk.status().kind {
+                    DiffHunkStatusKind::Added => theme.colors().version_control_added,
+                    DiffHunkStatusKind::Modified => theme.colors().version_control_modified,
+                    DiffHunkStatusKind::Deleted => theme.colors().version_control_deleted,
+                };
+                markers.push(marker(start..end, left_lane.clone(), color));
+            }
+        }
+
+        if scrollbar_settings.search_results {
+            let editor = self.editor.read(cx);
+            let search_highlights = editor
+                .background_highlights
+                .get(&HighlightKey::Type(TypeId::of::<BufferSearchHighlights>()));
+            if let Some((_, ranges)) = search_highlights {
+                let start_ix = ranges
+                    .partition_point(|range| range.end.cmp(&visible_range.start, buffer).is_le());
+                for range in ranges[start_ix..]
+                    .iter()
+                    .take_while(|range| range.start.cmp(&visible_range.end, buffer).is_lt())
+                {
+                    let start = range.start.to_display_point(snapshot).row();
+                    let end = range.end.to_display_point(snapshot).row();
+                    markers.push(marker(start..end, right_lane.clone(), theme.status().info));
+                }
+            }
+        }
+
+        if scrollbar_settings.diagnostics != ScrollbarDiagnostics::None {
+            let max_severity = match scrollbar_settings.diagnostics {
+                ScrollbarDiagnostics::Error => lsp::DiagnosticSeverity::ERROR,
+                ScrollbarDiagnostics::Warning => lsp::DiagnosticSeverity::WARNING,
+                ScrollbarDiagnostics::Information => lsp::DiagnosticSeverity::INFORMATION,
+                ScrollbarDiagnostics::All | ScrollbarDiagnostics::None => {
+                    lsp::DiagnosticSeverity::HINT
+                }
+            };
+            let diagnostics = buffer
+                .diagnostics_in_range::<Point>(
+                    visible_range.start.to_point(buffer)..visible_range.end.to_point(buffer),
+                )
+                .filter(|diagnostic| diagnostic.diagnostic.severity <= max_severity)
+                // Paint the most severe diagnostics last, on top of the others.
+                .sorted_by_key(|diagnostic| cmp::Reverse(diagnostic.diagnostic.severity));
+            for diagnostic in diagnostics {
+                let start = diagnostic.range.start.to_display_point(snapshot).row();
+                let end = diagnostic.range.end.to_display_point(snapshot).row();
+                let color = match diagnostic.diagnostic.severity {
+                    lsp::DiagnosticSeverity::ERROR => theme.status().error,
+                    lsp::DiagnosticSeverity::WARNING => theme.status().warning,
+                    lsp::DiagnosticSeverity::INFORMATION => theme.status().info,
+                    _ => theme.status().hint,
+                };
+                markers.push(marker(start..end, right_lane.clone(), color));
+            }
+        }
+
+        markers
+    }
+
     fn get_minimap_line_height(
         &self,
         font_size: AbsoluteLength,
@@ -2155,12 +2377,16 @@ impl EditorElement {
                 None
             };
 
+        let inline_settings = ProjectSettings::get_global(cx).diagnostics.inline;
         let diagnostics_by_rows = self.editor.update(cx, |editor, cx| {
             let snapshot = editor.snapshot(window, cx);
             editor
                 .inline_diagnostics
                 .iter()
-                .filter(|(_, diagnostic)| diagnostic.severity <= max_severity)
+                .filter(|(_, diagnostic)| {
+                    diagnostic.severity <= max_severity
+                        && inline_settings.severities.shows(diagnostic.severity)
+                })
                 .filter(|(_, diagnostic)| match active_diagnostics_group {
                     Some(active_diagnostics_group) => {
                         // Active diagnostics are all shown in the editor already, no need to display them inline
@@ -2192,14 +2418,8 @@ impl EditorElement {
             _ => Color::Error,
         };
 
-        let padding = ProjectSettings::get_global(cx).diagnostics.inline.padding as f32 * em_width;
-        let min_x = self.column_pixels(
-            ProjectSettings::get_global(cx)
-                .diagnostics
-                .inline
-                .min_column as usize,
-            window,
-        );
+        let padding = inline_settings.padding as f32 * em_width;
+        let min_x = self.column_pixels(inline_settings.min_column as usize, window);
 
         let mut elements = HashMap::default();
         for (row, mut diagnostics) in diagnostics_by_rows {
@@ -2251,6 +2471,17 @@ impl EditorElement {
                 1.0
             };
 
+            let message = diagnostic_to_render
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default();
+            let message = match inline_settings.max_length {
+                Some(max_length) => util::truncate_and_trailoff(message, max_length as usize),
+                None => message.to_string(),
+            };
+            let severity_color = severity_to_color(&diagnostic_to_render.severity).color(cx);
+
             let mut element = h_flex()
                 .id(("diagnostic", row.0))
                 .h(line_height)
@@ -2258,13 +2489,18 @@ impl EditorElement {
                 .px_1()
                 .rounded_xs()
                 .opacity(opacity)
-                .bg(severity_to_color(&diagnostic_to_render.severity)
-                    .color(cx)
-                    .opacity(0.05))
-                .text_color(severity_to_color(&diagnostic_to_render.severity).color(cx))
+                .map(|element| {
+                    if inline_settings.dimmed {
+                        element.text_color(severity_color.opacity(0.6))
+                    } else {
+                        element
+                            .bg(severity_color.opacity(0.05))
+                            .text_color(severity_color)
+                    }
+                })
                 .text_sm()
                 .font_family(style.text.font().family)
-                .child(diagnostic_to_render.message.clone())
+                .child(message)
                 .into_any();
 
             element.prepaint_as_root(point(pos_x, pos_y), AvailableSpace::min_size(), window, cx);
@@ -3051,15 +3287,28 @@ impl EditorElement {
                     .selected_icon_color(Color::Custom(cx.theme().colors().editor_foreground))
                     .icon_size(IconSize::Custom(rems(editor_font_size / window.rem_size())))
                     .width(width)
-                    .on_click(move |_, window, cx| {
+                    .on_click(move |event, window, cx| {
                         editor.update(cx, |editor, cx| {
-                            editor.expand_excerpt(excerpt_id, direction, window, cx);
+                            if event.modifiers().alt {
+                                editor.expand_excerpts_to_enclosing_function([excerpt_id], cx);
+                            } else {
+                                editor.expand_excerpt(excerpt_id, direction, window, cx);
+                            }
                         });
                     })
-                    .tooltip(Tooltip::for_action_title(
-                        "Expand Excerpt",
-                        &crate::actions::ExpandExcerpts::default(),
-                    ))
+                    .tooltip(|window, cx| {
+                        Tooltip::with_meta(
+                            "Expand Excerpt",
+                            Some(&crate::actions::ExpandExcerpts::default()),
+                            if cfg!(target_os = "macos") {
+                                "Option-click to expand to the enclosing function"
+                            } else {
+                                "Alt-click to expand to the enclosing function"
+                            },
+                            window,
+                            cx,
+                        )
+                    })
                     .into_any_element();
 
                 let position = point(
@@ -4388,6 +4637,74 @@ impl EditorElement {
         header
     }
 
+    fn layout_sticky_scroll_headers(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: f32,
+        line_height: Pixels,
+        em_width: Pixels,
+        right_margin: Pixels,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
+        let headers = snapshot.sticky_scroll_headers(scroll_position, Some(cx.theme().syntax()));
+        if headers.is_empty() {
+            return None;
+        }
+
+        let colors = cx.theme().colors();
+        let (background, hover_background, border_color) = (
+            colors.editor_background,
+            colors.editor_active_line_background,
+            colors.border_variant,
+        );
+        let available_width = text_hitbox.bounds.size.width - right_margin;
+        let text_offset = content_origin.x - text_hitbox.origin.x;
+        let mut element = v_flex()
+            .id("sticky-scroll-headers")
+            .w(available_width)
+            .bg(background)
+            .border_b_1()
+            .border_color(border_color)
+            .occlude()
+            .children(headers.into_iter().enumerate().map(|(ix, item)| {
+                let start = item.range.start;
+                let column = start.to_display_point(snapshot).column();
+                h_flex()
+                    .id(ix)
+                    .h(line_height)
+                    .pl(text_offset + em_width * column as f32)
+                    .cursor_pointer()
+                    .hover(|style| style.bg(hover_background))
+                    .child(
+                        StyledText::new(item.text)
+                            .with_default_highlights(&self.style.text, item.highlight_ranges),
+                    )
+                    .on_click(
+                        window.listener_for(&self.editor, move |editor, _, window, cx| {
+                            // Keep the header at the row it was clicked on, below
+                            // the ones enclosing it.
+                            editor.change_selections(
+                                SelectionEffects::scroll(Autoscroll::top_relative(ix)),
+                                window,
+                                cx,
+                                |selections| selections.select_anchor_ranges([start..start]),
+                            );
+                        }),
+                    )
+            }))
+            .into_any_element();
+
+        let size = size(
+            AvailableSpace::Definite(available_width),
+            AvailableSpace::MinContent,
+        );
+        element.prepaint_as_root(text_hitbox.origin, size, window, cx);
+        Some(element)
+    }
+
     fn layout_cursor_popovers(
         &self,
         line_height: Pixels,
@@ -6816,6 +7133,9 @@ impl EditorElement {
             window.paint_layer(layout.thumb_layout.hitbox.bounds, |window| {
                 window.with_element_namespace("minimap", |window| {
                     layout.minimap.paint(window, cx);
+                    for marker in layout.markers.drain(..) {
+                        window.paint_quad(marker);
+                    }
                     if let Some(thumb_bounds) = layout.thumb_layout.thumb_bounds {
                         let minimap_thumb_color = match layout.thumb_layout.thumb_state {
                             ScrollbarThumbState::Idle => {
@@ -8425,7 +8745,8 @@ impl Element for EditorElement {
                             snapshot
                         } else {
                             let wrap_width_for = |column: u32| (column as f32 * em_advance).ceil();
-                            let wrap_width = match editor.soft_wrap_mode(cx) {
+                            let soft_wrap = editor.soft_wrap_mode(cx);
+                            let wrap_width = match soft_wrap {
                                 SoftWrap::GitDiff => None,
                                 SoftWrap::None => Some(wrap_width_for(MAX_LINE_LEN as u32 / 2)),
                                 SoftWrap::EditorWidth => Some(editor_width),
@@ -8433,9 +8754,17 @@ impl Element for EditorElement {
                                 SoftWrap::Bounded(column) => {
                                     Some(editor_width.min(wrap_width_for(column)))
                                 }
+                                SoftWrap::HangingIndent { column, .. } => {
+                                    Some(wrap_width_for(column))
+                                }
+                            };
+                            let hanging_indent = match soft_wrap {
+                                SoftWrap::HangingIndent { hanging_indent, .. } => hanging_indent,
+                                _ => 0,
                             };
 
-                            if editor.set_wrap_width(wrap_width, cx) {
+                            let rewrapped = editor.set_wrap_width(wrap_width, cx);
+                            if editor.set_wrap_hanging_indent(hanging_indent, cx) || rewrapped {
                                 editor.snapshot(window, cx)
                             } else {
                                 snapshot
@@ -8620,12 +8949,27 @@ impl Element for EditorElement {
                             )
                         })
                         .unwrap_or_default();
-                    let highlighted_gutter_ranges =
+                    let mut highlighted_gutter_ranges =
                         self.editor.read(cx).gutter_highlights_in_range(
                             start_anchor..end_anchor,
                             &snapshot.display_snapshot,
                             cx,
                         );
+                    let bookmark_color = cx.theme().status().info;
+                    highlighted_gutter_ranges.extend(
+                        self.editor
+                            .read(cx)
+                            .bookmarks_in_range(
+                                start_anchor..end_anchor,
+                                &snapshot.buffer_snapshot,
+                                cx,
+                            )
+                            .into_iter()
+                            .map(|anchor| {
+                                let point = anchor.to_display_point(&snapshot.display_snapshot);
+                                (point..point, bookmark_color)
+                            }),
+                    );
 
                     let document_colors = self
                         .editor
@@ -8932,6 +9276,26 @@ impl Element for EditorElement {
                         })
                     });
 
+                    let sticky_scroll_headers =
+                        if EditorSettings::get_global(cx).sticky_scroll.enabled
+                            && !is_minimap
+                            && snapshot.buffer_snapshot.is_singleton()
+                        {
+                            self.layout_sticky_scroll_headers(
+                                &snapshot,
+                                scroll_position.y,
+                                line_height,
+                                em_width,
+                                right_margin,
+                                &text_hitbox,
+                                content_origin,
+                                window,
+                                cx,
+                            )
+                        } else {
+                            None
+                        };
+
                     let start_buffer_row =
                         MultiBufferRow(start_anchor.to_point(&snapshot.buffer_snapshot).row);
                     let end_buffer_row =
@@ -9433,6 +9797,7 @@ impl Element for EditorElement {
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scroll_headers,
                         expand_toggles,
                     }
                 })
@@ -9503,6 +9868,10 @@ impl Element for EditorElement {
                         }
                     });
 
+                    if let Some(mut sticky_scroll_headers) = layout.sticky_scroll_headers.take() {
+                        sticky_scroll_headers.paint(window, cx);
+                    }
+
                     self.paint_minimap(layout, window, cx);
                     self.paint_scrollbars(layout, window, cx);
                     self.paint_edit_prediction_popover(layout, window, cx);
@@ -9611,6 +9980,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scroll_headers: Option<AnyElement>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
@@ -9999,6 +10369,7 @@ impl ScrollbarLayout {
 
 struct MinimapLayout {
     pub minimap: AnyElement,
+    pub markers: Vec<PaintQuad>,
     pub thumb_layout: ScrollbarLayout,
     pub minimap_scroll_top: f32,
     pub minimap_line_height: Pixels,
@@ -10011,6 +10382,8 @@ impl MinimapLayout {
     const MINIMAP_MIN_WIDTH_COLUMNS: f32 = 20.;
     /// The minimap width as a percentage of the editor width.
     const MINIMAP_WIDTH_PCT: f32 = 0.15;
+    /// The width of the lanes that markers are painted in along the sides of the minimap.
+    const MARKER_WIDTH: Pixels = px(3.);
     /// Calculates the scroll top offset the minimap editor has to have based on the
     /// current scroll progress.
     fn calculate_minimap_top_offset(
@@ -10068,6 +10441,16 @@ impl PointForPosition {
         }
     }
 
+    pub fn intersects_any_selection(
+        &self,
+        selections: &[Selection<Anchor>],
+        snapshot: &EditorSnapshot,
+    ) -> bool {
+        selections.iter().any(|selection| {
+            self.intersects_selection(&selection.map(|anchor| anchor.to_display_point(snapshot)))
+        })
+    }
+
     pub fn intersects_selection(&self, selection: &Selection<DisplayPoint>) -> bool {
         let Some(valid_point) = self.as_valid() else {
             return false;
@@ -32,26 +32,34 @@ pub fn refresh_matching_bracket_highlights(
         tail += 1;
     }
 
-    if let Some((opening_range, closing_range)) = snapshot
+    let ranges = if let Some((opening_range, closing_range)) = snapshot
         .buffer_snapshot
         .innermost_enclosing_bracket_ranges(head..tail, None)
     {
-        editor.highlight_text::<MatchingBracketHighlight>(
-            vec![
-                opening_range.to_anchors(&snapshot.buffer_snapshot),
-                closing_range.to_anchors(&snapshot.buffer_snapshot),
-            ],
-            HighlightStyle {
-                background_color: Some(
-                    cx.theme()
-                        .colors()
-                        .editor_document_highlight_bracket_background,
-                ),
-                ..Default::default()
-            },
-            cx,
-        )
-    }
+        vec![opening_range, closing_range]
+    } else if let Some(keyword_ranges) =
+        snapshot.buffer_snapshot.matching_keyword_ranges(head..head)
+    {
+        keyword_ranges
+    } else {
+        return;
+    };
+
+    editor.highlight_text::<MatchingBracketHighlight>(
+        ranges
+            .into_iter()
+            .map(|range| range.to_anchors(&snapshot.buffer_snapshot))
+            .collect(),
+        HighlightStyle {
+            background_color: Some(
+                cx.theme()
+                    .colors()
+                    .editor_document_highlight_bracket_background,
+            ),
+            ..Default::default()
+        },
+        cx,
+    )
 }
 
 #[cfg(test)]
@@ -5,7 +5,6 @@ use crate::{
     hover_links::{InlayHighlight, RangeInEditor},
     scroll::ScrollAmount,
 };
-use anyhow::Context as _;
 use gpui::{
     AnyElement, AsyncWindowContext, Context, Entity, Focusable as _, FontWeight, Hsla,
     InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, ScrollHandle, Size,
@@ -309,8 +308,8 @@ fn show_hover(
             }
 
             let offset = anchor.to_offset(&snapshot.buffer_snapshot);
-            let local_diagnostic = if all_diagnostics_active {
-                None
+            let local_diagnostics = if all_diagnostics_active {
+                Vec::new()
             } else {
                 snapshot
                     .buffer_snapshot
@@ -318,11 +317,15 @@ fn show_hover(
                     .filter(|(_, diagnostic)| {
                         Some(diagnostic.diagnostic.group_id) != active_group_id
                     })
-                    // Find the entry with the most specific range
-                    .min_by_key(|(_, entry)| entry.range.len())
+                    // Start with the entry with the most specific range
+                    .sorted_by_key(|(_, entry)| entry.range.len())
+                    .unique_by(|(buffer_id, entry)| (*buffer_id, entry.diagnostic.group_id))
+                    .collect()
             };
 
-            let diagnostic_popover = if let Some((buffer_id, local_diagnostic)) = local_diagnostic {
+            let mut entries = Vec::new();
+            let mut subscriptions = Vec::new();
+            for (buffer_id, local_diagnostic) in local_diagnostics {
                 let group = snapshot
                     .buffer_snapshot
                     .diagnostic_group(buffer_id, local_diagnostic.diagnostic.group_id)
@@ -335,40 +338,21 @@ fn show_hover(
                         .range
                         .end
                         .to_point(&snapshot.buffer_snapshot);
-                let markdown = cx.update(|_, cx| {
-                    renderer
-                        .as_ref()
-                        .and_then(|renderer| {
-                            renderer.render_hover(group, point_range, buffer_id, cx)
-                        })
-                        .context("no rendered diagnostic")
-                })??;
+                let Some(markdown) = cx.update(|_, cx| {
+                    renderer.as_ref().and_then(|renderer| {
+                        renderer.render_hover(group, point_range, buffer_id, cx)
+                    })
+                })?
+                else {
+                    continue;
+                };
 
                 let (background_color, border_color) = cx.update(|_, cx| {
-                    let status_colors = cx.theme().status();
-                    match local_diagnostic.diagnostic.severity {
-                        DiagnosticSeverity::ERROR => {
-                            (status_colors.error_background, status_colors.error_border)
-                        }
-                        DiagnosticSeverity::WARNING => (
-                            status_colors.warning_background,
-                            status_colors.warning_border,
-                        ),
-                        DiagnosticSeverity::INFORMATION => {
-                            (status_colors.info_background, status_colors.info_border)
-                        }
-                        DiagnosticSeverity::HINT => {
-                            (status_colors.hint_background, status_colors.hint_border)
-                        }
-                        _ => (
-                            status_colors.ignored_background,
-                            status_colors.ignored_border,
-                        ),
-                    }
+                    diagnostic_popover_colors(local_diagnostic.diagnostic.severity, cx)
                 })?;
 
-                let subscription =
-                    this.update(cx, |_, cx| cx.observe(&markdown, |_, _, cx| cx.notify()))?;
+                subscriptions
+                    .push(this.update(cx, |_, cx| cx.observe(&markdown, |_, _, cx| cx.notify()))?);
 
                 let local_diagnostic = DiagnosticEntry {
                     diagnostic: local_diagnostic.diagnostic,
@@ -380,21 +364,22 @@ fn show_hover(
                             .anchor_after(local_diagnostic.range.end),
                 };
 
-                let scroll_handle = ScrollHandle::new();
-
-                Some(DiagnosticPopover {
+                entries.push(DiagnosticPopoverEntry {
                     local_diagnostic,
                     markdown,
                     border_color,
-                    scroll_handle,
                     background_color,
-                    keyboard_grace: Rc::new(RefCell::new(ignore_timeout)),
-                    anchor,
-                    _subscription: subscription,
-                })
-            } else {
-                None
-            };
+                });
+            }
+
+            let diagnostic_popover = (!entries.is_empty()).then(|| DiagnosticPopover {
+                entries,
+                active_entry_ix: 0,
+                scroll_handle: ScrollHandle::new(),
+                keyboard_grace: Rc::new(RefCell::new(ignore_timeout)),
+                anchor,
+                _subscriptions: subscriptions,
+            });
 
             this.update(cx, |this, _| {
                 this.hover_state.diagnostic_popover = diagnostic_popover;
@@ -564,7 +549,7 @@ fn same_diagnostic_hover(editor: &Editor, snapshot: &EditorSnapshot, anchor: Anc
         .as_ref()
         .map(|diagnostic| {
             let hover_range = diagnostic
-                .local_diagnostic
+                .local_diagnostic()
                 .range
                 .to_offset(&snapshot.buffer_snapshot);
             let offset = anchor.to_offset(&snapshot.buffer_snapshot);
@@ -781,7 +766,7 @@ impl HoverState {
         let anchor = self
             .diagnostic_popover
             .as_ref()
-            .map(|diagnostic_popover| &diagnostic_popover.local_diagnostic.range.start)
+            .map(|diagnostic_popover| &diagnostic_popover.local_diagnostic().range.start)
             .or_else(|| {
                 self.info_popovers.iter().find_map(|info_popover| {
                     match &info_popover.symbol_range {
@@ -828,6 +813,7 @@ impl HoverState {
         }
         if let Some(diagnostic_popover) = &self.diagnostic_popover
             && diagnostic_popover
+                .active_entry()
                 .markdown
                 .focus_handle(cx)
                 .is_focused(window)
@@ -908,17 +894,44 @@ impl InfoPopover {
 }
 
 pub struct DiagnosticPopover {
-    pub(crate) local_diagnostic: DiagnosticEntry<Anchor>,
-    markdown: Entity<Markdown>,
-    border_color: Hsla,
-    background_color: Hsla,
+    /// The diagnostics at the hovered position, from the most specific one to the least.
+    entries: Vec<DiagnosticPopoverEntry>,
+    active_entry_ix: usize,
     pub keyboard_grace: Rc<RefCell<bool>>,
     pub anchor: Anchor,
-    _subscription: Subscription,
+    _subscriptions: Vec<Subscription>,
     pub scroll_handle: ScrollHandle,
 }
 
+struct DiagnosticPopoverEntry {
+    local_diagnostic: DiagnosticEntry<Anchor>,
+    markdown: Entity<Markdown>,
+    border_color: Hsla,
+    background_color: Hsla,
+}
+
 impl DiagnosticPopover {
+    /// The diagnostic currently shown by the popover.
+    pub fn local_diagnostic(&self) -> &DiagnosticEntry<Anchor> {
+        &self.active_entry().local_diagnostic
+    }
+
+    fn active_entry(&self) -> &DiagnosticPopoverEntry {
+        &self.entries[self.active_entry_ix]
+    }
+
+    /// Shows the next diagnostic at the hovered position, or the previous one if `reverse` is
+    /// set, wrapping around at either end.
+    pub fn cycle(&mut self, reverse: bool) {
+        let len = self.entries.len();
+        self.active_entry_ix = if reverse {
+            (self.active_entry_ix + len - 1) % len
+        } else {
+            (self.active_entry_ix + 1) % len
+        };
+        self.scroll_handle.set_offset(gpui::Point::default());
+    }
+
     pub fn render(
         &self,
         max_size: Size<Pixels>,
@@ -927,6 +940,7 @@ impl DiagnosticPopover {
     ) -> AnyElement {
         let keyboard_grace = Rc::clone(&self.keyboard_grace);
         let this = cx.entity().downgrade();
+        let entry = self.active_entry();
         div()
             .id("diagnostic")
             .occlude()
@@ -950,10 +964,13 @@ impl DiagnosticPopover {
                 div()
                     .py_1()
                     .px_2()
-                    .bg(self.background_color)
+                    .bg(entry.background_color)
                     .border_1()
-                    .border_color(self.border_color)
+                    .border_color(entry.border_color)
                     .rounded_lg()
+                    .when(self.entries.len() > 1, |this| {
+                        this.child(self.render_cycle_controls(cx))
+                    })
                     .child(
                         div()
                             .id("diagnostic-content-container")
@@ -963,7 +980,7 @@ impl DiagnosticPopover {
                             .track_scroll(&self.scroll_handle)
                             .child(
                                 MarkdownElement::new(
-                                    self.markdown.clone(),
+                                    entry.markdown.clone(),
                                     diagnostics_markdown_style(window, cx),
                                 )
                                 .on_url_click(
@@ -988,6 +1005,58 @@ impl DiagnosticPopover {
             )
             .into_any_element()
     }
+
+    fn render_cycle_controls(&self, cx: &mut Context<Editor>) -> impl IntoElement {
+        let mut cycle = |reverse| {
+            cx.listener(move |editor: &mut Editor, _, _, cx| {
+                if let Some(diagnostic_popover) = editor.hover_state.diagnostic_popover.as_mut() {
+                    diagnostic_popover.cycle(reverse);
+                    cx.notify();
+                }
+            })
+        };
+        h_flex()
+            .pb_1()
+            .gap_1()
+            .child(
+                IconButton::new("previous-diagnostic", IconName::ChevronLeft)
+                    .icon_size(IconSize::XSmall)
+                    .on_click(cycle(true)),
+            )
+            .child(
+                Label::new(format!(
+                    "{} of {}",
+                    self.active_entry_ix + 1,
+                    self.entries.len()
+                ))
+                .size(LabelSize::XSmall)
+                .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("next-diagnostic", IconName::ChevronRight)
+                    .icon_size(IconSize::XSmall)
+                    .on_click(cycle(false)),
+            )
+    }
+}
+
+fn diagnostic_popover_colors(severity: DiagnosticSeverity, cx: &App) -> (Hsla, Hsla) {
+    let status_colors = cx.theme().status();
+    match severity {
+        DiagnosticSeverity::ERROR => (status_colors.error_background, status_colors.error_border),
+        DiagnosticSeverity::WARNING => (
+            status_colors.warning_background,
+            status_colors.warning_border,
+        ),
+        DiagnosticSeverity::INFORMATION => {
+            (status_colors.info_background, status_colors.info_border)
+        }
+        DiagnosticSeverity::HINT => (status_colors.hint_background, status_colors.hint_border),
+        _ => (
+            status_colors.ignored_background,
+            status_colors.ignored_border,
+        ),
+    }
 }
 
 #[cfg(test)]
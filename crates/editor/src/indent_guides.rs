@@ -206,11 +206,16 @@ async fn resolve_indented_range(
     snapshot: DisplaySnapshot,
     buffer_row: MultiBufferRow,
 ) -> Option<ActiveIndentedRange> {
-    snapshot
-        .buffer_snapshot
-        .enclosing_indent(buffer_row)
-        .await
-        .map(|(row_range, indent)| ActiveIndentedRange { row_range, indent })
+    let (row_range, indent) = match snapshot.buffer_snapshot.enclosing_syntax_indent(buffer_row) {
+        Some(enclosing_indent) => enclosing_indent,
+        None => {
+            snapshot
+                .buffer_snapshot
+                .enclosing_indent(buffer_row)
+                .await?
+        }
+    };
+    Some(ActiveIndentedRange { row_range, indent })
 }
 
 fn should_recalculate_indented_range(
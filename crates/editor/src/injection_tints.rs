@@ -0,0 +1,95 @@
+use std::{cmp, ops::Range};
+
+use gpui::{Context, Hsla};
+use language::language_settings::language_settings;
+use multi_buffer::{Anchor, ToPoint as _};
+use text::{Bias, Point};
+use theme::Theme;
+
+use crate::Editor;
+
+enum InjectionTint {}
+
+/// Layers nested deeper than this are tinted the same as those at this depth.
+const MAX_TINTED_DEPTH: usize = 3;
+
+const TINT_COLORS: [fn(&Theme) -> Hsla; MAX_TINTED_DEPTH] = [
+    tint_for_depth::<1>,
+    tint_for_depth::<2>,
+    tint_for_depth::<3>,
+];
+
+fn tint_for_depth<const DEPTH: usize>(theme: &Theme) -> Hsla {
+    theme
+        .accents()
+        .color_for_index(0)
+        .opacity(0.05 * DEPTH as f32)
+}
+
+/// Tints the background of the visible regions that belong to injected languages, more
+/// strongly the deeper they're nested, so that it's clear where each language begins and ends.
+///
+/// Whether a region is tinted depends on the `tint_injections` setting of the injected
+/// language. Hidden languages, like Markdown's inline grammar, and injections of the host
+/// language into itself are never tinted.
+pub fn refresh_injection_tints(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let mut ranges_by_depth: [Vec<Range<Anchor>>; MAX_TINTED_DEPTH] = Default::default();
+    if editor.mode().is_full() {
+        let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+        let visible_start = editor
+            .scroll_manager
+            .anchor()
+            .anchor
+            .to_point(&multi_buffer_snapshot);
+        let visible_end = multi_buffer_snapshot.clip_point(
+            visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+            Bias::Left,
+        );
+
+        for (buffer, buffer_range, excerpt_id) in
+            multi_buffer_snapshot.range_to_buffer_ranges(visible_start..visible_end)
+        {
+            let host_language = buffer.language().map(|language| language.id());
+            for layer in buffer.syntax_layers_for_range(buffer_range.clone(), false) {
+                if layer.depth() == 0
+                    || Some(layer.language.id()) == host_language
+                    || !language_settings(Some(layer.language.name()), buffer.file(), cx)
+                        .tint_injections
+                {
+                    continue;
+                }
+
+                let depth_ix = cmp::min(layer.depth(), MAX_TINTED_DEPTH) - 1;
+                for range in layer.included_ranges() {
+                    let start = cmp::max(range.start, buffer_range.start);
+                    let end = cmp::min(range.end, buffer_range.end);
+                    if start >= end {
+                        continue;
+                    }
+                    let (Some(start), Some(end)) = (
+                        multi_buffer_snapshot
+                            .anchor_in_excerpt(excerpt_id, buffer.anchor_after(start)),
+                        multi_buffer_snapshot
+                            .anchor_in_excerpt(excerpt_id, buffer.anchor_before(end)),
+                    ) else {
+                        continue;
+                    };
+                    ranges_by_depth[depth_ix].push(start..end);
+                }
+            }
+        }
+    }
+
+    for (depth_ix, ranges) in ranges_by_depth.iter().enumerate() {
+        if ranges.is_empty() {
+            editor.clear_background_highlights_key::<InjectionTint>(depth_ix, cx);
+        } else {
+            editor.highlight_background_key::<InjectionTint>(
+                depth_ix,
+                ranges,
+                TINT_COLORS[depth_ix],
+                cx,
+            );
+        }
+    }
+}
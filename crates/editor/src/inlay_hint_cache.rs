@@ -36,6 +36,7 @@ use util::{ResultExt, post_inc};
 pub struct InlayHintCache {
     hints: HashMap<ExcerptId, Arc<RwLock<CachedExcerptHints>>>,
     allowed_hint_kinds: HashSet<Option<InlayHintKind>>,
+    allowed_hint_kinds_in_settings: HashSet<Option<InlayHintKind>>,
     version: usize,
     pub(super) enabled: bool,
     modifiers_override: bool,
@@ -267,6 +268,7 @@ impl InlayHintCache {
     pub(super) fn new(inlay_hint_settings: InlayHintSettings) -> Self {
         Self {
             allowed_hint_kinds: inlay_hint_settings.enabled_inlay_hint_kinds(),
+            allowed_hint_kinds_in_settings: inlay_hint_settings.enabled_inlay_hint_kinds(),
             enabled: inlay_hint_settings.enabled,
             modifiers_override: false,
             enabled_in_settings: inlay_hint_settings.enabled,
@@ -304,7 +306,15 @@ impl InlayHintCache {
         };
         self.invalidate_debounce = debounce_value(new_hint_settings.edit_debounce_ms);
         self.append_debounce = debounce_value(new_hint_settings.scroll_debounce_ms);
-        let new_allowed_hint_kinds = new_hint_settings.enabled_inlay_hint_kinds();
+        // Same as with `enabled`, keep the hint kinds toggled in the editor unless the
+        // settings for them have changed.
+        let settings_hint_kinds = new_hint_settings.enabled_inlay_hint_kinds();
+        let new_allowed_hint_kinds = if settings_hint_kinds != self.allowed_hint_kinds_in_settings {
+            self.allowed_hint_kinds_in_settings = settings_hint_kinds.clone();
+            settings_hint_kinds
+        } else {
+            self.allowed_hint_kinds.clone()
+        };
         match (old_enabled, self.enabled) {
             (false, false) => {
                 self.allowed_hint_kinds = new_allowed_hint_kinds;
@@ -362,6 +372,40 @@ impl InlayHintCache {
         }
     }
 
+    /// Shows or hides the hints of the given kind, without querying LSP again.
+    /// Generates the inlay_map splice to apply if inlay hints are enabled.
+    pub(super) fn toggle_hint_kind(
+        &mut self,
+        kind: Option<InlayHintKind>,
+        multi_buffer: &Entity<MultiBuffer>,
+        visible_hints: Vec<Inlay>,
+        cx: &mut Context<Editor>,
+    ) -> Option<InlaySplice> {
+        let mut new_allowed_hint_kinds = self.allowed_hint_kinds.clone();
+        if !new_allowed_hint_kinds.remove(&kind) {
+            new_allowed_hint_kinds.insert(kind);
+        }
+        let new_splice = if self.enabled {
+            self.new_allowed_hint_kinds_splice(
+                multi_buffer,
+                &visible_hints,
+                &new_allowed_hint_kinds,
+                cx,
+            )
+        } else {
+            None
+        };
+        if new_splice.is_some() {
+            self.version += 1;
+        }
+        self.allowed_hint_kinds = new_allowed_hint_kinds;
+        new_splice
+    }
+
+    pub(super) fn hint_kind_enabled(&self, kind: Option<InlayHintKind>) -> bool {
+        self.allowed_hint_kinds.contains(&kind)
+    }
+
     pub(super) fn toggle(&mut self, enabled: bool) -> bool {
         if self.enabled == enabled {
             return false;
@@ -3317,6 +3361,145 @@ pub mod tests {
         }).unwrap();
     }
 
+    #[gpui::test]
+    async fn test_toggle_inlay_hint_kinds(cx: &mut gpui::TestAppContext) {
+        let hint_settings = InlayHintSettingsContent {
+            show_value_hints: Some(true),
+            enabled: Some(true),
+            edit_debounce_ms: Some(0),
+            scroll_debounce_ms: Some(0),
+            show_type_hints: Some(true),
+            show_parameter_hints: Some(true),
+            show_other_hints: Some(true),
+            show_background: Some(false),
+            toggle_on_modifiers_press: None,
+        };
+        init_test(cx, |settings| {
+            settings.defaults.inlay_hints = Some(hint_settings.clone())
+        });
+
+        let lsp_request_count = Arc::new(AtomicU32::new(0));
+        let (_, editor, _fake_server) = prepare_test_objects(cx, {
+            let lsp_request_count = lsp_request_count.clone();
+            move |fake_server, _| {
+                let lsp_request_count = lsp_request_count.clone();
+                fake_server.set_request_handler::<lsp::request::InlayHintRequest, _, _>(
+                    move |_, _| {
+                        lsp_request_count.fetch_add(1, Ordering::SeqCst);
+                        async move {
+                            Ok(Some(vec![
+                                lsp::InlayHint {
+                                    position: lsp::Position::new(0, 1),
+                                    label: lsp::InlayHintLabel::String("type hint".to_string()),
+                                    kind: Some(lsp::InlayHintKind::TYPE),
+                                    text_edits: None,
+                                    tooltip: None,
+                                    padding_left: None,
+                                    padding_right: None,
+                                    data: None,
+                                },
+                                lsp::InlayHint {
+                                    position: lsp::Position::new(0, 2),
+                                    label: lsp::InlayHintLabel::String(
+                                        "parameter hint".to_string(),
+                                    ),
+                                    kind: Some(lsp::InlayHintKind::PARAMETER),
+                                    text_edits: None,
+                                    tooltip: None,
+                                    padding_left: None,
+                                    padding_right: None,
+                                    data: None,
+                                },
+                            ]))
+                        }
+                    },
+                );
+            }
+        })
+        .await;
+        cx.executor().run_until_parked();
+        let all_hints = vec!["parameter hint".to_string(), "type hint".to_string()];
+        editor
+            .update(cx, |editor, _, cx| {
+                assert_eq!(
+                    all_hints,
+                    visible_hint_labels(editor, cx)
+                        .into_iter()
+                        .sorted()
+                        .collect::<Vec<_>>()
+                );
+            })
+            .unwrap();
+
+        editor
+            .update(cx, |editor, window, cx| {
+                editor.toggle_type_inlay_hints(&crate::ToggleTypeInlayHints, window, cx)
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, _, cx| {
+                assert!(!editor.inlay_hint_kind_enabled(InlayHintKind::Type));
+                assert_eq!(
+                    all_hints,
+                    cached_hint_labels(editor)
+                        .into_iter()
+                        .sorted()
+                        .collect::<Vec<_>>(),
+                    "Should keep the hidden kind of hints cached"
+                );
+                assert_eq!(
+                    vec!["parameter hint".to_string()],
+                    visible_hint_labels(editor, cx)
+                );
+            })
+            .unwrap();
+
+        // Unrelated settings changes should not bring the toggled off hints back.
+        update_test_language_settings(cx, |settings| {
+            settings.defaults.inlay_hints = Some(InlayHintSettingsContent {
+                show_background: Some(true),
+                ..hint_settings.clone()
+            })
+        });
+        editor
+            .update(cx, |editor, window, cx| {
+                editor.toggle_parameter_inlay_hints(&crate::ToggleParameterInlayHints, window, cx)
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, _, cx| {
+                assert!(!editor.inlay_hint_kind_enabled(InlayHintKind::Parameter));
+                assert!(visible_hint_labels(editor, cx).is_empty());
+            })
+            .unwrap();
+
+        editor
+            .update(cx, |editor, window, cx| {
+                editor.toggle_type_inlay_hints(&crate::ToggleTypeInlayHints, window, cx);
+                editor.toggle_parameter_inlay_hints(&crate::ToggleParameterInlayHints, window, cx);
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, _, cx| {
+                assert_eq!(
+                    all_hints,
+                    visible_hint_labels(editor, cx)
+                        .into_iter()
+                        .sorted()
+                        .collect::<Vec<_>>()
+                );
+            })
+            .unwrap();
+        assert_eq!(
+            lsp_request_count.load(Ordering::SeqCst),
+            1,
+            "Toggling hint kinds should not query the language server again"
+        );
+    }
+
     #[gpui::test]
     async fn test_inlays_at_the_same_place(cx: &mut gpui::TestAppContext) {
         init_test(cx, |settings| {
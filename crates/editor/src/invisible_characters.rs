@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use gpui::{Context, HighlightStyle, UnderlineStyle, Window, px};
+use multi_buffer::{MultiBufferSnapshot, ToOffset as _, ToPoint as _};
+use text::{Bias, Point};
+use theme::ActiveTheme as _;
+
+use crate::{Editor, actions::RemoveInvisibleCharacters, display_map::is_deceptive_invisible};
+
+enum InvisibleCharacterWarning {}
+
+/// Underlines the visible characters that don't render but can change how the code around them
+/// reads, like bidirectional overrides and zero-width spaces, so that they can't be used to
+/// disguise what the code does.
+pub fn refresh_invisible_character_warnings(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        editor.clear_highlights::<InvisibleCharacterWarning>(cx);
+        return;
+    }
+
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let visible_start = editor.scroll_manager.anchor().anchor.to_point(&snapshot);
+    let visible_end = snapshot.clip_point(
+        visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+        Bias::Left,
+    );
+    let ranges = deceptive_invisible_ranges(&snapshot, visible_start..visible_end)
+        .map(|range| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end))
+        .collect::<Vec<_>>();
+    if ranges.is_empty() {
+        editor.clear_highlights::<InvisibleCharacterWarning>(cx);
+        return;
+    }
+
+    let status = cx.theme().status();
+    let style = HighlightStyle {
+        background_color: Some(status.warning_background),
+        underline: Some(UnderlineStyle {
+            color: Some(status.warning),
+            thickness: px(1.),
+            wavy: true,
+        }),
+        ..HighlightStyle::default()
+    };
+    editor.highlight_text::<InvisibleCharacterWarning>(ranges, style, cx);
+}
+
+fn deceptive_invisible_ranges(
+    snapshot: &MultiBufferSnapshot,
+    range: Range<Point>,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut chunk_offset = range.start.to_offset(snapshot);
+    snapshot.text_for_range(range).flat_map(move |chunk| {
+        let start = chunk_offset;
+        chunk_offset += chunk.len();
+        chunk
+            .char_indices()
+            .filter(|(_, c)| is_deceptive_invisible(*c))
+            .map(move |(ix, c)| start + ix..start + ix + c.len_utf8())
+    })
+}
+
+impl Editor {
+    /// Deletes the bidirectional controls, zero-width spaces and byte order marks within the
+    /// selections, or within the whole buffer when nothing is selected.
+    pub fn remove_invisible_characters(
+        &mut self,
+        _: &RemoveInvisibleCharacters,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<Point>(cx);
+        let ranges = if selections.iter().all(|selection| selection.is_empty()) {
+            vec![Point::zero()..snapshot.max_point()]
+        } else {
+            selections
+                .into_iter()
+                .filter(|selection| !selection.is_empty())
+                .map(|selection| selection.range())
+                .collect()
+        };
+        let edits = ranges
+            .into_iter()
+            .flat_map(|range| deceptive_invisible_ranges(&snapshot, range))
+            .map(|range| (range, ""))
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(window, cx, |this, _, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        });
+    }
+}
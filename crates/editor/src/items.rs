@@ -4,7 +4,9 @@ use crate::{
     SelectionEffects, ToPoint as _,
     display_map::HighlightKey,
     editor_settings::SeedQuerySetting,
-    persistence::{DB, SerializedEditor},
+    persistence::{
+        DB, SerializedEditor, SerializedFileState, SerializedPosition, SerializedUndoHistory,
+    },
     scroll::ScrollAnchor,
 };
 use anyhow::{Context as _, Result, anyhow};
@@ -51,13 +53,17 @@ use workspace::{
     item::{Dedup, ItemSettings, SerializableItem, TabContentParams},
 };
 use workspace::{
-    OpenVisible, Pane, WorkspaceSettings,
+    OpenVisible, Pane, SERIALIZATION_THROTTLE_TIME, WorkspaceSettings,
     item::{BreadcrumbText, FollowEvent, ProjectItemKind},
     searchable::SearchOptions,
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
 
+/// Undo histories are only persisted for files up to this size, and only as
+/// many of their most recent transactions as fit in it.
+const MAX_SERIALIZED_UNDO_HISTORY_LEN: usize = 1024 * 1024;
+
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
         self.remote_id
@@ -1161,6 +1167,8 @@ impl SerializableItem for Editor {
                 mtime,
                 ..
             } => {
+                let restore_undo_history = contents.is_none()
+                    && ProjectSettings::get_global(cx).session.restore_undo_history;
                 let opened_buffer = project.update(cx, |project, cx| {
                     let (worktree, path) = project.find_worktree(&abs_path, cx)?;
                     let project_path = ProjectPath {
@@ -1199,6 +1207,13 @@ impl SerializableItem for Editor {
                                 })?;
                             }
 
+                            if restore_undo_history {
+                                restore_undo_history_from_db(&buffer, item_id, workspace_id, cx)
+                                    .await
+                                    .context("failed to restore undo history")
+                                    .log_err();
+                            }
+
                             cx.update(|window, cx| {
                                 cx.new(|cx| {
                                     let mut editor =
@@ -1299,6 +1314,13 @@ impl SerializableItem for Editor {
 
         let snapshot = buffer.read(cx).snapshot();
 
+        // The history is only restored into unmodified files, so it's only
+        // worth recording once the buffer matches what's on disk.
+        let undo_history = (ProjectSettings::get_global(cx).session.restore_undo_history
+            && !is_dirty
+            && abs_path.is_some())
+        .then(|| undo_history_versions(buffer.read(cx)));
+
         Some(cx.spawn_in(window, async move |_this, cx| {
             cx.background_spawn(async move {
                 let (contents, language) = if serialize_dirty_buffers && is_dirty {
@@ -1318,7 +1340,17 @@ impl SerializableItem for Editor {
                 log::debug!("Serializing editor {item_id:?} in workspace {workspace_id:?}");
                 DB.save_serialized_editor(item_id, workspace_id, editor)
                     .await
-                    .context("failed to save serialized editor")
+                    .context("failed to save serialized editor")?;
+
+                if let Some((versions, undone_count)) = undo_history {
+                    let undo_history = serialize_undo_history(&snapshot, versions, undone_count)
+                        .map(|history| serde_json::to_string(&history))
+                        .transpose()?;
+                    DB.save_undo_history(item_id, workspace_id, undo_history)
+                        .await
+                        .context("failed to save undo history")?;
+                }
+                anyhow::Ok(())
             })
             .await
             .context("failed to save contents of buffer")?;
@@ -1335,12 +1367,160 @@ impl SerializableItem for Editor {
     }
 }
 
+/// Returns the versions the buffer had before each transaction in its undo
+/// and redo history and after the last of them, along with how many of those
+/// transactions have been undone.
+fn undo_history_versions(buffer: &Buffer) -> (Vec<clock::Global>, usize) {
+    let mut versions = buffer
+        .undo_transactions()
+        .chain(buffer.redo_transactions())
+        .map(|transaction| transaction.start.clone())
+        .collect::<Vec<_>>();
+    let undone_count = buffer.redo_transactions().count();
+    if let Some(transaction) = buffer.redo_transactions().last() {
+        let mut version = transaction.start.clone();
+        for edit_id in &transaction.edit_ids {
+            version.observe(*edit_id);
+        }
+        versions.push(version);
+    } else if !versions.is_empty() {
+        versions.push(buffer.version());
+    }
+    (versions, undone_count)
+}
+
+fn serialize_undo_history(
+    snapshot: &BufferSnapshot,
+    versions: Vec<clock::Global>,
+    undone_count: usize,
+) -> Option<SerializedUndoHistory> {
+    if snapshot.len() > MAX_SERIALIZED_UNDO_HISTORY_LEN {
+        return None;
+    }
+
+    // Walk the history backwards so that the oldest transactions are the ones
+    // dropped once the size limit is reached.
+    let mut versions = versions.iter().rev();
+    let mut text = snapshot.rope_for_version(versions.next()?).to_string();
+    let mut transactions = Vec::new();
+    let mut remaining_undone_count = undone_count;
+    let mut len = 0;
+    for version in versions {
+        let previous_text = snapshot.rope_for_version(version).to_string();
+        let edits = language::text_diff(&previous_text, &text)
+            .into_iter()
+            .map(|(range, new_text)| (range, new_text.to_string()))
+            .collect::<Vec<_>>();
+        len += edits
+            .iter()
+            .map(|(_, new_text)| new_text.len() + 2 * size_of::<usize>())
+            .sum::<usize>();
+        if len > MAX_SERIALIZED_UNDO_HISTORY_LEN {
+            break;
+        }
+
+        let is_undone = remaining_undone_count > 0;
+        remaining_undone_count = remaining_undone_count.saturating_sub(1);
+        if edits.is_empty() {
+            continue;
+        }
+        transactions.push((edits, is_undone));
+        text = previous_text;
+    }
+
+    transactions.reverse();
+    let undone_count = transactions
+        .iter()
+        .filter(|(_, is_undone)| *is_undone)
+        .count();
+    Some(SerializedUndoHistory {
+        base_text: text,
+        transactions: transactions.into_iter().map(|(edits, _)| edits).collect(),
+        undone_count,
+    })
+}
+
+/// Returns the text the history leaves the buffer with, or `None` if its edits
+/// don't apply to its own base text.
+fn replay_undo_history(history: &SerializedUndoHistory) -> Option<String> {
+    let mut text = history.base_text.clone();
+    let applied_count = history
+        .transactions
+        .len()
+        .checked_sub(history.undone_count)?;
+    for edits in &history.transactions[..applied_count] {
+        for (range, new_text) in edits.iter().rev() {
+            if range.end > text.len()
+                || !text.is_char_boundary(range.start)
+                || !text.is_char_boundary(range.end)
+            {
+                return None;
+            }
+            text.replace_range(range.clone(), new_text);
+        }
+    }
+    Some(text)
+}
+
+/// Rebuilds the buffer's undo and redo history by replaying it from its base
+/// text, leaving the buffer with the same text and saved state it had before.
+fn apply_undo_history(
+    buffer: &mut Buffer,
+    history: SerializedUndoHistory,
+    cx: &mut Context<Buffer>,
+) {
+    let saved_mtime = buffer.saved_mtime();
+    buffer.set_text(history.base_text, cx);
+    if let Some(entry) = buffer.peek_undo_stack() {
+        buffer.forget_transaction(entry.transaction_id());
+    }
+    for edits in history.transactions {
+        buffer.start_transaction();
+        buffer.edit(edits, None, cx);
+        buffer.end_transaction(cx);
+        buffer.finalize_last_transaction();
+    }
+    for _ in 0..history.undone_count {
+        buffer.undo(cx);
+    }
+    buffer.did_reload(buffer.version(), buffer.line_ending(), saved_mtime, cx);
+}
+
+async fn restore_undo_history_from_db(
+    buffer: &Entity<Buffer>,
+    item_id: ItemId,
+    workspace_id: WorkspaceId,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let Some(undo_history) = DB.get_undo_history(item_id, workspace_id)? else {
+        return Ok(());
+    };
+    let text = buffer.read_with(cx, |buffer, _| buffer.text())?;
+    let history = cx
+        .background_spawn(async move {
+            let history = serde_json::from_str::<SerializedUndoHistory>(&undo_history)?;
+            // If the file changed on disk since the history was recorded, it no
+            // longer leads to the buffer's text and is discarded.
+            anyhow::Ok((replay_undo_history(&history).as_ref() == Some(&text)).then_some(history))
+        })
+        .await?;
+
+    if let Some(history) = history {
+        buffer.update(cx, |buffer, cx| {
+            if !buffer.is_dirty() && buffer.peek_undo_stack().is_none() {
+                apply_undo_history(buffer, history, cx);
+            }
+        })?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Default)]
 struct EditorRestorationData {
     entries: HashMap<PathBuf, RestorationData>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct RestorationData {
     pub scroll_position: (BufferRow, gpui::Point<f32>),
     pub folds: Vec<Range<Point>>,
@@ -1362,35 +1542,23 @@ impl ProjectItem for Editor {
         cx: &mut Context<Self>,
     ) -> Self {
         let mut editor = Self::for_buffer(buffer.clone(), Some(project), window, cx);
-        if let Some((excerpt_id, buffer_id, snapshot)) =
-            editor.buffer().read(cx).snapshot(cx).as_singleton()
+        if editor.is_singleton(cx)
             && WorkspaceSettings::get(None, cx).restore_on_file_reopen
-            && let Some(restoration_data) = Self::project_item_kind()
+            && let Some(file) = project::File::from_dyn(buffer.read(cx).file())
+        {
+            let abs_path = file.abs_path(cx);
+            let restoration_data = Self::project_item_kind()
                 .and_then(|kind| pane.as_ref()?.project_item_restoration_data.get(&kind))
                 .and_then(|data| data.downcast_ref::<EditorRestorationData>())
-                .and_then(|data| {
-                    let file = project::File::from_dyn(buffer.read(cx).file())?;
-                    data.entries.get(&file.abs_path(cx))
-                })
-        {
-            editor.fold_ranges(
-                clip_ranges(&restoration_data.folds, snapshot),
-                false,
-                window,
-                cx,
-            );
-            if !restoration_data.selections.is_empty() {
-                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-                    s.select_ranges(clip_ranges(&restoration_data.selections, snapshot));
-                });
+                .and_then(|data| data.entries.get(&abs_path));
+            match restoration_data {
+                Some(restoration_data) => editor.restore_file_state(restoration_data, window, cx),
+                // Files that weren't open in this pane during the session fall back to the state
+                // stored for the workspace, which isn't known until the editor is added to it.
+                None => cx.defer_in(window, move |editor, window, cx| {
+                    editor.restore_file_state_from_db(abs_path, window, cx)
+                }),
             }
-            let (top_row, offset) = restoration_data.scroll_position;
-            let anchor = Anchor::in_buffer(
-                *excerpt_id,
-                buffer_id,
-                snapshot.anchor_before(Point::new(top_row, 0)),
-            );
-            editor.set_scroll_anchor(ScrollAnchor { anchor, offset }, window, cx);
         }
 
         editor
@@ -1420,9 +1588,145 @@ fn clip_ranges<'a>(
         .collect()
 }
 
+/// How many characters of a line are stored to find it again when its file changed.
+const SERIALIZED_LINE_LEN: usize = 120;
+/// How many rows away from where it was a line is looked for when its file changed.
+const MAX_LINE_RELOCATION_DISTANCE: u32 = 1000;
+
+fn line_prefix(row: u32, snapshot: &BufferSnapshot) -> String {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .flat_map(str::chars)
+        .take(SERIALIZED_LINE_LEN)
+        .collect()
+}
+
+fn serialize_position(point: Point, snapshot: &BufferSnapshot) -> SerializedPosition {
+    SerializedPosition {
+        row: point.row,
+        column: point.column,
+        line: line_prefix(point.row, snapshot),
+    }
+}
+
+/// Finds where a position's line went, looking for it closest to its old row first.
+fn relocate_position(position: &SerializedPosition, snapshot: &BufferSnapshot) -> Option<Point> {
+    let max_row = snapshot.max_point().row;
+    let row = (0..=MAX_LINE_RELOCATION_DISTANCE)
+        .flat_map(|distance| {
+            [
+                position.row.checked_add(distance),
+                position.row.checked_sub(distance),
+            ]
+        })
+        .flatten()
+        .filter(|row| *row <= max_row)
+        .find(|row| line_prefix(*row, snapshot) == position.line)?;
+    Some(snapshot.clip_point(Point::new(row, position.column), Bias::Left))
+}
+
+fn serialize_file_state(data: &RestorationData, snapshot: &BufferSnapshot) -> SerializedFileState {
+    let serialize_range = |range: &Range<Point>| {
+        serialize_position(range.start, snapshot)..serialize_position(range.end, snapshot)
+    };
+    let (top_row, offset) = data.scroll_position;
+    SerializedFileState {
+        scroll_top: serialize_position(Point::new(top_row, 0), snapshot),
+        scroll_offset: (offset.x, offset.y),
+        folds: data.folds.iter().map(serialize_range).collect(),
+        selections: data.selections.iter().map(serialize_range).collect(),
+    }
+}
+
+/// Restores a file's state, moving positions along with their lines if the file changed since
+/// it was stored.
+fn deserialize_file_state(
+    state: &SerializedFileState,
+    snapshot: &BufferSnapshot,
+) -> RestorationData {
+    let relocate_range = |range: &Range<SerializedPosition>| {
+        Some(relocate_position(&range.start, snapshot)?..relocate_position(&range.end, snapshot)?)
+            .filter(|range| range.start <= range.end)
+    };
+    let scroll_top_row = relocate_position(&state.scroll_top, snapshot)
+        .map_or(state.scroll_top.row, |position| position.row);
+    RestorationData {
+        scroll_position: (
+            scroll_top_row.min(snapshot.max_point().row),
+            point(state.scroll_offset.0, state.scroll_offset.1),
+        ),
+        // Folds whose lines are gone are dropped, rather than folding unrelated code.
+        folds: state.folds.iter().filter_map(relocate_range).collect(),
+        selections: state
+            .selections
+            .iter()
+            .map(|range| {
+                relocate_range(range).unwrap_or_else(|| {
+                    Point::new(range.start.row, range.start.column)
+                        ..Point::new(range.end.row, range.end.column)
+                })
+            })
+            .collect(),
+    }
+}
+
 impl EventEmitter<SearchEvent> for Editor {}
 
 impl Editor {
+    fn restore_file_state(
+        &mut self,
+        restoration_data: &RestorationData,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let multi_buffer_snapshot = self.buffer().read(cx).snapshot(cx);
+        let Some((excerpt_id, buffer_id, snapshot)) = multi_buffer_snapshot.as_singleton() else {
+            return;
+        };
+        self.fold_ranges(
+            clip_ranges(&restoration_data.folds, snapshot),
+            false,
+            window,
+            cx,
+        );
+        if !restoration_data.selections.is_empty() {
+            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_ranges(clip_ranges(&restoration_data.selections, snapshot));
+            });
+        }
+        let (top_row, offset) = restoration_data.scroll_position;
+        let anchor = Anchor::in_buffer(
+            *excerpt_id,
+            buffer_id,
+            snapshot.anchor_before(Point::new(top_row, 0)),
+        );
+        self.set_scroll_anchor(ScrollAnchor { anchor, offset }, window, cx);
+    }
+
+    fn restore_file_state_from_db(
+        &mut self,
+        abs_path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|workspace| workspace.1) else {
+            return;
+        };
+        let Some(buffer) = self.buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(state) = DB
+            .get_file_state(workspace_id, abs_path)
+            .log_err()
+            .flatten()
+            .and_then(|state| serde_json::from_str::<SerializedFileState>(&state).log_err())
+        else {
+            return;
+        };
+        let restoration_data = deserialize_file_state(&state, &buffer.read(cx).text_snapshot());
+        self.restore_file_state(&restoration_data, window, cx);
+    }
+
     pub fn update_restoration_data(
         &self,
         cx: &mut Context<Self>,
@@ -1439,7 +1743,7 @@ impl Editor {
                 let pane = editor.workspace()?.read(cx).pane_for(&cx.entity())?;
                 let buffer = editor.buffer().read(cx).as_singleton()?;
                 let file_abs_path = project::File::from_dyn(buffer.read(cx).file())?.abs_path(cx);
-                pane.update(cx, |pane, _| {
+                let data = pane.update(cx, |pane, _| {
                     let data = pane
                         .project_item_restoration_data
                         .entry(kind)
@@ -1453,10 +1757,27 @@ impl Editor {
                         }
                     };
 
-                    let data = data.entries.entry(file_abs_path).or_default();
+                    let data = data.entries.entry(file_abs_path.clone()).or_default();
                     write(data);
-                    Some(())
-                })
+                    data.clone()
+                });
+
+                let workspace_id = editor.workspace.as_ref()?.1?;
+                let state = serialize_file_state(&data, &buffer.read(cx).text_snapshot());
+                let background_executor = cx.background_executor().clone();
+                editor.serialize_file_state = cx.background_spawn(async move {
+                    background_executor.timer(SERIALIZATION_THROTTLE_TIME).await;
+                    let Some(state) = serde_json::to_string(&state).log_err() else {
+                        return;
+                    };
+                    DB.save_file_state(workspace_id, file_abs_path, state)
+                        .await
+                        .with_context(|| {
+                            format!("persisting editor state for workspace {workspace_id:?}")
+                        })
+                        .log_err();
+                });
+                Some(())
             });
         });
     }
@@ -1795,8 +2116,29 @@ impl SearchableItem for Editor {
         )
     }
 
-    fn search_bar_visibility_changed(&mut self, _: bool, _: &mut Window, _: &mut Context<Self>) {
+    fn search_bar_visibility_changed(
+        &mut self,
+        visible: bool,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
         self.expect_bounds_change = self.last_bounds;
+        if !visible {
+            self.search_origin = None;
+        } else if self.search_origin.is_none() {
+            self.search_origin = Some(self.selections.disjoint_anchors_arc());
+        }
+    }
+
+    fn restore_search_origin(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(origin) = self.search_origin.clone() {
+            self.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |s| s.select_anchors(origin.to_vec()),
+            );
+        }
     }
 }
 
@@ -1944,6 +2286,75 @@ mod tests {
         assert_eq!(path_for_file(&file, 0, false, cx), None);
     }
 
+    #[gpui::test]
+    fn test_undo_history_round_trip(cx: &mut App) {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local("one two three", cx);
+            for (range, new_text) in [(0..3, "1"), (2..5, "2"), (4..9, "3")] {
+                buffer.start_transaction();
+                buffer.edit([(range, new_text)], None, cx);
+                buffer.end_transaction(cx);
+                buffer.finalize_last_transaction();
+            }
+            buffer.undo(cx);
+            buffer
+        });
+        assert_eq!(buffer.read(cx).text(), "1 2 three");
+
+        let (versions, undone_count) = undo_history_versions(buffer.read(cx));
+        let history =
+            serialize_undo_history(&buffer.read(cx).snapshot(), versions, undone_count).unwrap();
+        assert_eq!(history.base_text, "one two three");
+        assert_eq!(history.transactions.len(), 3);
+        assert_eq!(history.undone_count, 1);
+        assert_eq!(replay_undo_history(&history).as_deref(), Some("1 2 three"));
+
+        let restored = cx.new(|cx| {
+            let mut buffer = Buffer::local("1 2 three", cx);
+            apply_undo_history(&mut buffer, history, cx);
+            buffer
+        });
+        restored.update(cx, |buffer, cx| {
+            assert_eq!(buffer.text(), "1 2 three");
+            assert!(!buffer.is_dirty());
+
+            buffer.redo(cx);
+            assert_eq!(buffer.text(), "1 2 3");
+            buffer.undo(cx);
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "1 two three");
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "one two three");
+            assert!(buffer.undo(cx).is_none());
+        });
+    }
+
+    #[gpui::test]
+    fn test_file_state_follows_moved_lines(cx: &mut App) {
+        let buffer = cx.new(|cx| Buffer::local("fn a() {\n    1\n}\nfn b() {\n    2\n}\n", cx));
+        let data = RestorationData {
+            scroll_position: (3, point(0., 0.5)),
+            folds: vec![
+                Point::new(0, 8)..Point::new(2, 0),
+                Point::new(3, 8)..Point::new(5, 0),
+            ],
+            selections: vec![Point::new(4, 4)..Point::new(4, 5)],
+        };
+        let state = serialize_file_state(&data, &buffer.read(cx).text_snapshot());
+
+        // While the file was closed, a header was added and `a` was removed.
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_text("// header\n\nfn b() {\n    2\n}\n", cx)
+        });
+        let restored = deserialize_file_state(&state, &buffer.read(cx).text_snapshot());
+        assert_eq!(restored.scroll_position, (2, point(0., 0.5)));
+        assert_eq!(restored.folds, vec![Point::new(2, 8)..Point::new(4, 0)]);
+        assert_eq!(
+            restored.selections,
+            vec![Point::new(3, 4)..Point::new(3, 5)]
+        );
+    }
+
     async fn deserialize_editor(
         item_id: ItemId,
         workspace_id: WorkspaceId,
@@ -303,6 +303,55 @@ pub(crate) fn generate_auto_close_edits(
     Ok(edits)
 }
 
+/// Returns the tag names of both the opening and the closing tag of the element
+/// whose tag name contains `position`, so that editing one of them can be mirrored
+/// into the other.
+///
+/// Used as a fallback for languages without a language server providing linked
+/// editing ranges.
+pub(crate) fn linked_tag_name_ranges(
+    buffer: &BufferSnapshot,
+    position: usize,
+) -> Option<Vec<Range<usize>>> {
+    let language = buffer.language_at(position)?;
+    let config = language.config().jsx_tag_auto_close.as_ref()?;
+    let layer = buffer.smallest_syntax_layer_containing(position..position)?;
+    let tag_name = |tag: Node| {
+        tag.named_child(TS_NODE_TAG_NAME_CHILD_INDEX)
+            .filter(|node| {
+                node.kind() == config.tag_name_node_name
+                    || config
+                        .tag_name_node_name_alternates
+                        .iter()
+                        .any(|alternate| alternate == node.kind())
+            })
+    };
+
+    let mut tag = layer.node().descendant_for_byte_range(position, position)?;
+    while tag.kind() != config.open_tag_node_name && tag.kind() != config.close_tag_node_name {
+        tag = tag.parent()?;
+    }
+    let name = tag_name(tag)?;
+    if !name.byte_range().contains(&position) && name.end_byte() != position {
+        return None;
+    }
+
+    let element = tag
+        .parent()
+        .filter(|node| node.kind() == config.jsx_element_node_name)?;
+    let other_tag_kind = if tag.kind() == config.open_tag_node_name {
+        &config.close_tag_node_name
+    } else {
+        &config.open_tag_node_name
+    };
+    let mut cursor = element.walk();
+    let other_tag = element
+        .named_children(&mut cursor)
+        .find(|node| node.kind() == other_tag_kind)?;
+    let other_name = tag_name(other_tag)?;
+    Some(vec![name.byte_range(), other_name.byte_range()])
+}
+
 pub(crate) fn refresh_enabled_in_any_buffer(
     editor: &mut Editor,
     multi_buffer: &Entity<MultiBuffer>,
@@ -857,4 +906,20 @@ mod jsx_tag_autoclose_tests {
 
         cx.assert_editor_state("<div>ˇ</div>\n<pre>ˇ</pre>\n<span>ˇ");
     }
+
+    #[gpui::test]
+    async fn test_linked_tag_name_ranges(cx: &mut TestAppContext) {
+        let mut cx = test_setup(cx).await;
+        cx.set_state("<div><Foo.Bar></Foo.Bar></div>ˇ");
+        cx.run_until_parked();
+
+        let snapshot = cx.update_buffer(|buffer, _| buffer.snapshot());
+        let ranges = |position| linked_tag_name_ranges(&snapshot, position);
+        assert_eq!(ranges(2), Some(vec![1..4, 26..29]));
+        assert_eq!(ranges(4), Some(vec![1..4, 26..29]));
+        assert_eq!(ranges(27), Some(vec![26..29, 1..4]));
+        assert_eq!(ranges(9), Some(vec![6..13, 16..23]));
+        assert_eq!(ranges(14), None);
+        assert_eq!(ranges(30), None);
+    }
 }
@@ -0,0 +1,87 @@
+use gpui::{Entity, EventEmitter, Subscription};
+use ui::{Banner, prelude::*};
+use workspace::{ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+use crate::Editor;
+
+/// Tells the user when the active file was opened in large file mode, and lets
+/// them turn the disabled features back on.
+#[derive(Default)]
+pub struct LargeFileBanner {
+    editor: Option<Entity<Editor>>,
+    _observe_editor: Option<Subscription>,
+}
+
+impl LargeFileBanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn location(&self, cx: &App) -> ToolbarItemLocation {
+        if self
+            .editor
+            .as_ref()
+            .is_some_and(|editor| editor.read(cx).is_in_large_file_mode())
+        {
+            ToolbarItemLocation::Secondary
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for LargeFileBanner {}
+
+impl Render for LargeFileBanner {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(editor) = self
+            .editor
+            .clone()
+            .filter(|editor| editor.read(cx).is_in_large_file_mode())
+        else {
+            return div().into_any_element();
+        };
+
+        Banner::new()
+            .severity(Severity::Info)
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(Label::new("This file was opened in large file mode").mt_0p5())
+                    .child(
+                        Label::new(
+                            "Syntax highlighting, language servers, soft wrap and inlay hints are off to keep it responsive.",
+                        )
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .action_slot(
+                Button::new("enable-large-file-features", "Enable Anyway")
+                    .label_size(LabelSize::Small)
+                    .on_click(move |_, _, cx| {
+                        editor.update(cx, |editor, cx| editor.enable_large_file_features(cx));
+                    }),
+            )
+            .into_any_element()
+    }
+}
+
+impl ToolbarItemView for LargeFileBanner {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ToolbarItemLocation {
+        self.editor = active_pane_item.and_then(|item| item.downcast::<Editor>());
+        self._observe_editor = self.editor.as_ref().map(|editor| {
+            cx.observe(editor, |this, _, cx| {
+                let location = this.location(cx);
+                cx.emit(ToolbarItemEvent::ChangeLocation(location));
+                cx.notify();
+            })
+        });
+        self.location(cx)
+    }
+}
@@ -1,16 +1,21 @@
 use collections::HashMap;
 use gpui::{Context, Window};
 use itertools::Itertools;
+use language::language_settings::language_settings;
+use regex::Regex;
 use std::{ops::Range, time::Duration};
-use text::{AnchorRangeExt, BufferId, ToPoint};
+use text::{AnchorRangeExt, BufferId, ToOffset, ToPoint};
 use util::ResultExt;
 
-use crate::Editor;
+use crate::{Editor, jsx_tag_auto_close};
 
 #[derive(Clone, Default)]
 pub(super) struct LinkedEditingRanges(
     /// Ranges are non-overlapping and sorted by .0 (thus, [x + 1].start > [x].end must hold)
     pub HashMap<BufferId, Vec<(Range<text::Anchor>, Vec<Range<text::Anchor>>)>>,
+    /// The patterns the contents of each buffer's linked ranges must match, for the buffers whose
+    /// language server provided one.
+    pub HashMap<BufferId, Regex>,
 );
 
 impl LinkedEditingRanges {
@@ -31,12 +36,33 @@ impl LinkedEditingRanges {
             .get(lower_bound - 1)
             .filter(|(range, _)| range.end.cmp(&anchor.end, snapshot).is_ge())
     }
+
+    /// Whether replacing `range` with `text` leaves the linked range containing it matching the
+    /// word pattern provided by the language server, or `None` if there's no such pattern.
+    pub(super) fn edit_matches_word_pattern(
+        &self,
+        id: BufferId,
+        range: Range<text::Anchor>,
+        text: &str,
+        snapshot: &text::BufferSnapshot,
+    ) -> Option<bool> {
+        let word_pattern = self.1.get(&id)?;
+        let (linked_range, _) = self.get(id, range.clone(), snapshot)?;
+        let mut new_text = snapshot
+            .text_for_range(linked_range.start..range.start)
+            .collect::<String>();
+        new_text.push_str(text);
+        new_text.extend(snapshot.text_for_range(range.end..linked_range.end));
+        Some(word_pattern.is_match(&new_text))
+    }
+
     pub(super) fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
     pub(super) fn clear(&mut self) {
         self.0.clear();
+        self.1.clear();
     }
 }
 
@@ -96,8 +122,34 @@ pub(super) fn refresh_linked_ranges(
                     let buffer_id = buffer.read(cx).remote_id();
 
                     let linked_edits_task = project.linked_edits(buffer, *start, cx);
+                    // Without a language server providing the ranges, fall back to
+                    // linking the tag names of the element under the cursor.
+                    let position = start.to_offset(&snapshot);
+                    let tag_name_ranges = snapshot
+                        .language_at(position)
+                        .filter(|language| {
+                            language_settings(Some(language.name()), snapshot.file(), cx)
+                                .linked_edits
+                        })
+                        .and_then(|_| {
+                            jsx_tag_auto_close::linked_tag_name_ranges(&snapshot, position)
+                        })
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|range| {
+                            snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+                        })
+                        .collect::<Vec<_>>();
                     let highlights = move || async move {
-                        let edits = linked_edits_task.await.log_err()?;
+                        let linked_edits = linked_edits_task.await.log_err()?;
+                        let mut edits = linked_edits.ranges;
+                        // The pattern has to match the whole contents of a range.
+                        let word_pattern = linked_edits
+                            .word_pattern
+                            .and_then(|pattern| Regex::new(&format!("^(?:{pattern})$")).log_err());
+                        if edits.is_empty() {
+                            edits = tag_name_ranges;
+                        }
                         // Find the range containing our current selection.
                         // We might not find one, because the selection contains both the start and end of the contained range
                         // (think of selecting <`html>foo`</html> - even though there's a matching closing tag, the selection goes beyond the range of the opening tag)
@@ -126,7 +178,7 @@ pub(super) fn refresh_linked_ranges(
                         }
                         let mut siblings: Vec<(_, _)> = siblings.into_iter().collect();
                         siblings.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0, &snapshot));
-                        Some((buffer_id, siblings))
+                        Some((buffer_id, siblings, word_pattern))
                     };
                     linked_edits_tasks.push(highlights());
                 }
@@ -138,16 +190,19 @@ pub(super) fn refresh_linked_ranges(
 
         editor
             .update(cx, |this, cx| {
-                this.linked_edit_ranges.0.clear();
+                this.linked_edit_ranges.clear();
                 if this.pending_rename.is_some() {
                     return;
                 }
-                for (buffer_id, ranges) in highlights.into_iter().flatten() {
+                for (buffer_id, ranges, word_pattern) in highlights.into_iter().flatten() {
                     this.linked_edit_ranges
                         .0
                         .entry(buffer_id)
                         .or_default()
                         .extend(ranges);
+                    if let Some(word_pattern) = word_pattern {
+                        this.linked_edit_ranges.1.insert(buffer_id, word_pattern);
+                    }
                 }
                 for (buffer_id, values) in this.linked_edit_ranges.0.iter_mut() {
                     let Some(snapshot) = this
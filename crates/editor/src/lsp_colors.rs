@@ -5,7 +5,7 @@ use futures::future::join_all;
 use gpui::{Hsla, Rgba};
 use itertools::Itertools;
 use language::point_from_lsp;
-use multi_buffer::Anchor;
+use multi_buffer::{Anchor, ToOffset as _};
 use project::{DocumentColor, lsp_store::LspFetchStrategy};
 use settings::Settings as _;
 use text::{Bias, BufferId, OffsetRangeExt as _};
@@ -143,6 +143,45 @@ impl LspColorData {
 }
 
 impl Editor {
+    /// Returns the range of the document color that contains, or ends at, `offset`.
+    pub(super) fn document_color_range_at(&self, offset: usize, cx: &App) -> Option<Range<Anchor>> {
+        let colors = self.colors.as_ref()?;
+        if colors.render_mode == DocumentColorsRenderMode::None {
+            return None;
+        }
+        let snapshot = self.buffer().read(cx).snapshot(cx);
+        colors
+            .buffer_colors
+            .values()
+            .flat_map(|buffer_colors| &buffer_colors.colors)
+            .map(|(range, ..)| range)
+            .find(|range| {
+                range.start.to_offset(&snapshot) <= offset
+                    && offset <= range.end.to_offset(&snapshot)
+            })
+            .cloned()
+    }
+
+    /// Returns the range of the document color whose inlay swatch is shown at `offset`.
+    pub(super) fn document_color_swatch_at(
+        &self,
+        offset: usize,
+        cx: &App,
+    ) -> Option<Range<Anchor>> {
+        let colors = self.colors.as_ref()?;
+        if colors.render_mode != DocumentColorsRenderMode::Inlay {
+            return None;
+        }
+        let snapshot = self.buffer().read(cx).snapshot(cx);
+        colors
+            .buffer_colors
+            .values()
+            .flat_map(|buffer_colors| &buffer_colors.colors)
+            .map(|(range, ..)| range)
+            .find(|range| range.start.to_offset(&snapshot) == offset)
+            .cloned()
+    }
+
     pub(super) fn refresh_colors(
         &mut self,
         ignore_cache: bool,
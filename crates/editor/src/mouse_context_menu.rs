@@ -3,7 +3,7 @@ use crate::{
     EvaluateSelectedText, FindAllReferences, GoToDeclaration, GoToDefinition, GoToImplementation,
     GoToTypeDefinition, Paste, Rename, RevealInFileManager, RunToCursor, SelectMode,
     SelectionEffects, SelectionExt, ToDisplayPoint, ToggleCodeActions,
-    actions::{Format, FormatSelections},
+    actions::{DiffClipboardWithSelection, Format, FormatSelections},
     selections_collection::SelectionsCollection,
 };
 use gpui::prelude::FluentBuilder;
@@ -198,6 +198,9 @@ pub fn deploy_context_menu(
                     .is_some()
             });
 
+        let has_clipboard_text = cx
+            .read_from_clipboard()
+            .is_some_and(|item| item.text().is_some());
         let evaluate_selection = window.is_action_available(&EvaluateSelectedText, cx);
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
 
@@ -237,6 +240,15 @@ pub fn deploy_context_menu(
                 .action("Copy", Box::new(Copy))
                 .action("Copy and Trim", Box::new(CopyAndTrim))
                 .action("Paste", Box::new(Paste))
+                .action_disabled_when(
+                    !has_clipboard_text,
+                    if has_selections {
+                        "Compare Selection with Clipboard"
+                    } else {
+                        "Compare File with Clipboard"
+                    },
+                    Box::new(DiffClipboardWithSelection),
+                )
                 .separator()
                 .action_disabled_when(
                     !has_reveal_target,
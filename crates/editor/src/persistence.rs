@@ -10,7 +10,8 @@ use db::{
 };
 use fs::MTime;
 use itertools::Itertools as _;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, path::PathBuf};
 
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
@@ -87,6 +88,35 @@ impl Column for SerializedEditor {
     }
 }
 
+/// A buffer's undo and redo history, stored as the text before the oldest
+/// transaction and the edits each transaction made to the text before it.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct SerializedUndoHistory {
+    pub(crate) base_text: String,
+    pub(crate) transactions: Vec<Vec<(Range<usize>, String)>>,
+    /// How many of the trailing transactions have been undone and can be redone.
+    pub(crate) undone_count: usize,
+}
+
+/// The folds, selections and scroll position of a file's editor, restored when the file is
+/// reopened in the same workspace.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct SerializedFileState {
+    pub(crate) scroll_top: SerializedPosition,
+    pub(crate) scroll_offset: (f32, f32),
+    pub(crate) folds: Vec<Range<SerializedPosition>>,
+    pub(crate) selections: Vec<Range<SerializedPosition>>,
+}
+
+/// A position in a file, along with the start of the text of its line, so that it can follow
+/// the line if the file was changed while it was closed.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct SerializedPosition {
+    pub(crate) row: u32,
+    pub(crate) column: u32,
+    pub(crate) line: String,
+}
+
 pub struct EditorDb(db::sqlez::thread_safe_connection::ThreadSafeConnection);
 
 impl Domain for EditorDb {
@@ -104,6 +134,7 @@ impl Domain for EditorDb {
     //   language: Option<String>,
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
+    //   undo_history: Option<String>,
     // )
     //
     // editor_selections(
@@ -121,6 +152,12 @@ impl Domain for EditorDb {
     //   start: usize,
     //   end: usize,
     // )
+    //
+    // editor_file_states(
+    //   workspace_id: usize,
+    //   abs_path: PathBuf,
+    //   state: String,
+    // )
 
     const MIGRATIONS: &[&str] = &[
         sql! (
@@ -197,6 +234,19 @@ impl Domain for EditorDb {
                 ON DELETE CASCADE
             ) STRICT;
         ),
+        sql! (
+            ALTER TABLE editors ADD COLUMN undo_history TEXT DEFAULT NULL;
+        ),
+        sql! (
+            CREATE TABLE editor_file_states (
+                workspace_id INTEGER NOT NULL,
+                abs_path BLOB NOT NULL,
+                state TEXT NOT NULL,
+                PRIMARY KEY(workspace_id, abs_path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
     ];
 }
 
@@ -233,6 +283,26 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_undo_history(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT undo_history
+            FROM editors
+            WHERE item_id = ? AND workspace_id = ? AND undo_history IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_undo_history(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            undo_history: Option<String>
+        ) -> Result<()> {
+            UPDATE OR IGNORE editors
+            SET undo_history = ?3
+            WHERE item_id = ?1 AND workspace_id = ?2
+        }
+    }
+
     // Returns the scroll top row, and offset
     query! {
         pub fn get_scroll_position(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<(u32, f32, f32)>> {
@@ -259,6 +329,29 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_file_state(workspace_id: WorkspaceId, abs_path: PathBuf) -> Result<Option<String>> {
+            SELECT state
+            FROM editor_file_states
+            WHERE workspace_id = ? AND abs_path = ?
+        }
+    }
+
+    query! {
+        pub async fn save_file_state(
+            workspace_id: WorkspaceId,
+            abs_path: PathBuf,
+            state: String
+        ) -> Result<()> {
+            INSERT INTO editor_file_states
+                (workspace_id, abs_path, state)
+            VALUES
+                (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                state = ?3
+        }
+    }
+
     query! {
         pub fn get_editor_selections(
             editor_id: ItemId,
@@ -1,14 +1,17 @@
-use crate::{ApplyAllDiffHunks, Editor, EditorEvent, SelectionEffects, SemanticsProvider};
+use crate::{
+    ApplyAllDiffHunks, Editor, EditorEvent, SelectionEffects, SemanticsProvider,
+    UndoApplyAllDiffHunks, multibuffer_context_lines,
+};
 use buffer_diff::BufferDiff;
 use collections::HashSet;
 use futures::{channel::mpsc, future::join_all};
 use gpui::{App, Entity, EventEmitter, Focusable, Render, Subscription, Task};
-use language::{Buffer, BufferEvent, Capability};
-use multi_buffer::{ExcerptRange, MultiBuffer};
+use language::{Buffer, BufferEvent, Capability, Point};
+use multi_buffer::{ExcerptRange, MultiBuffer, PathKey};
 use project::Project;
 use smol::stream::StreamExt;
 use std::{any::TypeId, ops::Range, rc::Rc, time::Duration};
-use text::ToOffset;
+use text::{ToOffset, TransactionId};
 use ui::{ButtonLike, KeyBinding, prelude::*};
 use workspace::{
     Item, ItemHandle as _, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
@@ -20,6 +23,9 @@ pub struct ProposedChangesEditor {
     multibuffer: Entity<MultiBuffer>,
     title: SharedString,
     buffer_entries: Vec<BufferEntry>,
+    /// The transactions in each base buffer created by the last "Apply All",
+    /// so that it can be undone in one step.
+    applied_transactions: Vec<(Entity<Buffer>, TransactionId)>,
     _recalculate_diffs_task: Task<Option<()>>,
     recalculate_diffs_tx: mpsc::UnboundedSender<RecalculateDiff>,
 }
@@ -37,6 +43,7 @@ struct BufferEntry {
 
 pub struct ProposedChangesEditorToolbar {
     current_editor: Option<Entity<ProposedChangesEditor>>,
+    _observe_current_editor: Option<Subscription>,
 }
 
 struct RecalculateDiff {
@@ -76,6 +83,7 @@ impl ProposedChangesEditor {
             multibuffer,
             title: title.into(),
             buffer_entries: Vec::new(),
+            applied_transactions: Vec::new(),
             recalculate_diffs_tx,
             _recalculate_diffs_task: cx.spawn_in(window, async move |this, cx| {
                 let mut buffers_to_diff = HashSet::default();
@@ -182,21 +190,10 @@ impl ProposedChangesEditor {
                 branch_buffer = entry.branch.clone();
                 buffer_entries.push(entry);
             } else {
-                branch_buffer = location.buffer.update(cx, |buffer, cx| buffer.branch(cx));
-                new_diffs.push(cx.new(|cx| {
-                    let mut diff = BufferDiff::new(&branch_buffer.read(cx).snapshot(), cx);
-                    let _ = diff.set_base_text_buffer(
-                        location.buffer.clone(),
-                        branch_buffer.read(cx).text_snapshot(),
-                        cx,
-                    );
-                    diff
-                }));
-                buffer_entries.push(BufferEntry {
-                    branch: branch_buffer.clone(),
-                    base: location.buffer.clone(),
-                    _subscription: cx.subscribe(&branch_buffer, Self::on_buffer_event),
-                });
+                let (entry, diff) = Self::new_buffer_entry(&location.buffer, cx);
+                branch_buffer = entry.branch.clone();
+                new_diffs.push(diff);
+                buffer_entries.push(entry);
             }
 
             self.multibuffer.update(cx, |multibuffer, cx| {
@@ -224,6 +221,127 @@ impl ProposedChangesEditor {
         });
     }
 
+    fn new_buffer_entry(
+        base: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> (BufferEntry, Entity<BufferDiff>) {
+        let branch = base.update(cx, |buffer, cx| buffer.branch(cx));
+        let diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&branch.read(cx).snapshot(), cx);
+            let _ = diff.set_base_text_buffer(base.clone(), branch.read(cx).text_snapshot(), cx);
+            diff
+        });
+        let entry = BufferEntry {
+            branch: branch.clone(),
+            base: base.clone(),
+            _subscription: cx.subscribe(&branch, Self::on_buffer_event),
+        };
+        (entry, diff)
+    }
+
+    /// Stages `edits` to `buffer` for review, without touching the buffer
+    /// itself. The edits show up as hunks that can be applied or restored one
+    /// by one, or all at once.
+    ///
+    /// Staging more edits to the same buffer adds to the ones already staged.
+    pub fn stage_edits<T: ToOffset>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        edits: Vec<(Range<T>, String)>,
+        cx: &mut Context<Self>,
+    ) {
+        let branch = match self.branch_buffer_for_base(buffer) {
+            Some(branch) => branch,
+            None => {
+                let (entry, diff) = Self::new_buffer_entry(buffer, cx);
+                let branch = entry.branch.clone();
+                self.buffer_entries.push(entry);
+                self.multibuffer
+                    .update(cx, |multibuffer, cx| multibuffer.add_diff(diff, cx));
+                branch
+            }
+        };
+        branch.update(cx, |branch, cx| {
+            branch.edit(edits, None, cx);
+        });
+
+        let base_version = buffer.read(cx).version();
+        let staged_ranges = branch
+            .read(cx)
+            .edits_since::<Point>(&base_version)
+            .map(|edit| edit.new)
+            .collect::<Vec<_>>();
+        let context_lines = multibuffer_context_lines(cx);
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            multibuffer.set_excerpts_for_path(
+                PathKey::for_buffer(&branch, cx),
+                branch,
+                staged_ranges,
+                context_lines,
+                cx,
+            );
+        });
+    }
+
+    /// Applies every staged change to its base buffer, as one transaction per
+    /// buffer that [`Self::undo_apply_all`] can revert together.
+    pub(crate) fn apply_all(
+        &mut self,
+        _: &ApplyAllDiffHunks,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.applied_transactions.clear();
+        for entry in &self.buffer_entries {
+            entry.base.update(cx, |base, _| base.start_transaction());
+            entry.branch.update(cx, |branch, cx| {
+                branch.merge_into_base(Vec::new(), cx);
+            });
+            if let Some(transaction_id) = entry.base.update(cx, |base, cx| base.end_transaction(cx))
+            {
+                entry
+                    .base
+                    .update(cx, |base, _| base.finalize_last_transaction());
+                self.applied_transactions
+                    .push((entry.base.clone(), transaction_id));
+            }
+        }
+        cx.notify();
+
+        let project = self.editor.read(cx).project.clone();
+        if let Some(project) = project {
+            self.save(
+                SaveOptions {
+                    format: true,
+                    autosave: false,
+                },
+                project,
+                window,
+                cx,
+            )
+            .detach_and_log_err(cx);
+        }
+    }
+
+    pub(crate) fn undo_apply_all(
+        &mut self,
+        _: &UndoApplyAllDiffHunks,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for (base, transaction_id) in self.applied_transactions.drain(..) {
+            base.update(cx, |base, cx| {
+                base.undo_transaction(transaction_id, cx);
+            });
+        }
+        self.recalculate_all_buffer_diffs();
+        cx.notify();
+    }
+
+    pub fn can_undo_apply_all(&self) -> bool {
+        !self.applied_transactions.is_empty()
+    }
+
     pub fn recalculate_all_buffer_diffs(&self) {
         for (ix, entry) in self.buffer_entries.iter().enumerate().rev() {
             self.recalculate_diffs_tx
@@ -253,10 +371,15 @@ impl ProposedChangesEditor {
 }
 
 impl Render for ProposedChangesEditor {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .size_full()
             .key_context("ProposedChangesEditor")
+            .capture_action(cx.listener(|this, action: &ApplyAllDiffHunks, window, cx| {
+                this.apply_all(action, window, cx);
+                cx.stop_propagation();
+            }))
+            .on_action(cx.listener(Self::undo_apply_all))
             .child(self.editor.clone())
     }
 }
@@ -357,6 +480,7 @@ impl ProposedChangesEditorToolbar {
     pub fn new() -> Self {
         Self {
             current_editor: None,
+            _observe_current_editor: None,
         }
     }
 
@@ -380,13 +504,24 @@ impl Render for ProposedChangesEditorToolbar {
                     KeyBinding::for_action_in(&ApplyAllDiffHunks, &focus_handle, window, cx)
                         .map(|binding| binding.into_any_element());
 
-                button_like.children(keybinding).on_click({
-                    move |_event, window, cx| {
-                        focus_handle.dispatch_action(&ApplyAllDiffHunks, window, cx)
-                    }
-                })
+                h_flex()
+                    .gap_1()
+                    .when(editor.read(cx).can_undo_apply_all(), |this| {
+                        let focus_handle = focus_handle.clone();
+                        this.child(Button::new("undo-apply-changes", "Undo Apply").on_click(
+                            move |_event, window, cx| {
+                                focus_handle.dispatch_action(&UndoApplyAllDiffHunks, window, cx)
+                            },
+                        ))
+                    })
+                    .child(button_like.children(keybinding).on_click({
+                        move |_event, window, cx| {
+                            focus_handle.dispatch_action(&ApplyAllDiffHunks, window, cx)
+                        }
+                    }))
+                    .into_any_element()
             }
-            None => button_like.disabled(true),
+            None => button_like.disabled(true).into_any_element(),
         }
     }
 }
@@ -398,10 +533,14 @@ impl ToolbarItemView for ProposedChangesEditorToolbar {
         &mut self,
         active_pane_item: Option<&dyn workspace::ItemHandle>,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> workspace::ToolbarItemLocation {
         self.current_editor =
             active_pane_item.and_then(|item| item.downcast::<ProposedChangesEditor>());
+        self._observe_current_editor = self
+            .current_editor
+            .as_ref()
+            .map(|editor| cx.observe(editor, |_, _, cx| cx.notify()));
         self.get_toolbar_item_location()
     }
 }
@@ -0,0 +1,220 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use gpui::{Context, Window};
+use itertools::Itertools as _;
+use language::language_settings::language_settings;
+use project::ProjectPath;
+use settings::RelatedFileRule;
+
+use crate::{Editor, ToggleRelatedFile};
+
+/// A file related to another one by a [`RelatedFileRule`].
+#[derive(Debug, PartialEq, Eq)]
+struct RelatedPath {
+    path: Arc<Path>,
+    /// The text to create the file with, if it doesn't exist yet.
+    template: Option<String>,
+}
+
+impl Editor {
+    /// Opens the file related to the one being edited, as configured by the `related_files`
+    /// setting of its language, creating it if none of the candidates exist.
+    pub fn toggle_related_file(
+        &mut self,
+        _: &ToggleRelatedFile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some((_, buffer, _)) = self.active_excerpt(cx) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(file) = buffer.file() else {
+            return;
+        };
+        let worktree_id = file.worktree_id(cx);
+        let rules =
+            &language_settings(buffer.language().map(|l| l.name()), Some(file), cx).related_files;
+        let candidates = related_paths(file.path(), rules);
+
+        let project = workspace.read(cx).project().read(cx);
+        let existing = candidates.iter().find(|candidate| {
+            let project_path = ProjectPath {
+                worktree_id,
+                path: candidate.path.clone(),
+            };
+            project.entry_for_path(&project_path, cx).is_some()
+        });
+        if let Some(existing) = existing {
+            let project_path = ProjectPath {
+                worktree_id,
+                path: existing.path.clone(),
+            };
+            workspace.update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_log_err(cx);
+            });
+            return;
+        }
+
+        let Some(RelatedPath { path, template }) = candidates.into_iter().next() else {
+            return;
+        };
+        let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let create_entry = worktree.update(cx, |worktree, cx| {
+            worktree.create_entry(path.clone(), false, template.map(String::into_bytes), cx)
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            create_entry
+                .await
+                .with_context(|| format!("creating related file {path:?}"))?;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let project_path = ProjectPath { worktree_id, path };
+                    workspace.open_path(project_path, None, true, window, cx)
+                })?
+                .await?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// Returns the files related to the one at `path` by `rules`, in order of preference.
+///
+/// Files that `path` is the related file of come first, so that e.g. with the rule
+/// `*.go` → `*_test.go`, `foo_test.go` leads back to `foo.go` rather than to `foo_test_test.go`.
+fn related_paths(path: &Path, rules: &[RelatedFileRule]) -> Vec<RelatedPath> {
+    let related_to_source = rules
+        .iter()
+        .filter_map(|rule| {
+            Some(RelatedPath {
+                path: apply_rule(path, &rule.related, &rule.source)?,
+                template: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    let source_to_related = rules.iter().filter_map(|rule| {
+        Some(RelatedPath {
+            path: apply_rule(path, &rule.source, &rule.related)?,
+            template: rule.template.clone(),
+        })
+    });
+
+    let skip_sources = !related_to_source.is_empty();
+    related_to_source
+        .into_iter()
+        .chain(source_to_related.filter(|_| !skip_sources))
+        .filter(|related| related.path.as_ref() != path)
+        .unique_by(|related| related.path.clone())
+        .collect()
+}
+
+/// Rewrites `path` from matching the `from` pattern to matching the `to` pattern, keeping the
+/// part matched by the `*`.
+fn apply_rule(path: &Path, from: &str, to: &str) -> Option<Arc<Path>> {
+    if from.contains('/') || to.contains('/') {
+        let path = path
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .join("/");
+        let captured = match_pattern(from, &path)?;
+        Some(PathBuf::from(to.replacen('*', captured, 1)).into())
+    } else {
+        let file_name = path.file_name()?.to_string_lossy();
+        let captured = match_pattern(from, &file_name)?;
+        let related_name = to.replacen('*', captured, 1);
+        Some(match path.parent() {
+            Some(parent) => parent.join(related_name).into(),
+            None => PathBuf::from(related_name).into(),
+        })
+    }
+}
+
+/// Returns the non-empty text matched by the single `*` in `pattern`, if `text` matches it.
+fn match_pattern<'a>(pattern: &str, text: &'a str) -> Option<&'a str> {
+    let (prefix, suffix) = pattern.split_once('*')?;
+    if suffix.contains('*') || text.len() <= prefix.len() + suffix.len() {
+        return None;
+    }
+    text.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(source: &str, related: &str) -> RelatedFileRule {
+        RelatedFileRule {
+            source: source.to_string(),
+            related: related.to_string(),
+            template: None,
+        }
+    }
+
+    fn related(path: &str, rules: &[RelatedFileRule]) -> Vec<String> {
+        related_paths(Path::new(path), rules)
+            .into_iter()
+            .map(|related| related.path.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_related_paths() {
+        let c = [rule("*.c", "*.h")];
+        assert_eq!(related("src/foo.c", &c), ["src/foo.h"]);
+        assert_eq!(related("src/foo.h", &c), ["src/foo.c"]);
+        assert_eq!(related("src/foo.rs", &c), Vec::<String>::new());
+
+        let go = [rule("*.go", "*_test.go")];
+        assert_eq!(related("foo.go", &go), ["foo_test.go"]);
+        assert_eq!(related("foo_test.go", &go), ["foo.go"]);
+
+        let rust = [rule("src/*.rs", "tests/*.rs"), rule("*.rs", "*_test.rs")];
+        assert_eq!(
+            related("src/parser/lexer.rs", &rust),
+            ["tests/parser/lexer.rs", "src/parser/lexer_test.rs"]
+        );
+        assert_eq!(related("tests/lexer.rs", &rust), ["src/lexer.rs"]);
+        assert_eq!(related("src/lexer_test.rs", &rust), ["src/lexer.rs"]);
+
+        let ts = [rule("*.ts", "*.test.ts"), rule("*.ts", "*.spec.ts")];
+        assert_eq!(
+            related("app/button.ts", &ts),
+            ["app/button.test.ts", "app/button.spec.ts"]
+        );
+        assert_eq!(related("app/button.spec.ts", &ts), ["app/button.ts"]);
+    }
+
+    #[test]
+    fn test_related_path_templates() {
+        let rules = [RelatedFileRule {
+            template: Some("#pragma once\n".to_string()),
+            ..rule("*.c", "*.h")
+        }];
+        assert_eq!(
+            related_paths(Path::new("foo.c"), &rules),
+            [RelatedPath {
+                path: Path::new("foo.h").into(),
+                template: Some("#pragma once\n".to_string()),
+            }]
+        );
+        assert_eq!(
+            related_paths(Path::new("foo.h"), &rules),
+            [RelatedPath {
+                path: Path::new("foo.c").into(),
+                template: None,
+            }]
+        );
+    }
+}
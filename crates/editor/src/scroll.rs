@@ -6,9 +6,13 @@ use crate::editor_settings::ScrollBeyondLastLine;
 use crate::{
     Anchor, DisplayPoint, DisplayRow, Editor, EditorEvent, EditorMode, EditorSettings,
     InlayHintRefreshReason, MultiBufferSnapshot, RowExt, ToPoint,
+    bracket_colorization::refresh_bracket_colors,
     display_map::{DisplaySnapshot, ToDisplayPoint},
     hover_popover::hide_hover,
+    injection_tints::refresh_injection_tints,
+    invisible_characters::refresh_invisible_character_warnings,
     persistence::DB,
+    spell_check::refresh_spell_check,
 };
 pub use autoscroll::{Autoscroll, AutoscrollStrategy};
 use core::fmt::Debug;
@@ -494,6 +498,10 @@ impl Editor {
                     .update_in(cx, |editor, window, cx| {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
                         editor.refresh_colors(false, None, window, cx);
+                        refresh_bracket_colors(editor, cx);
+                        refresh_injection_tints(editor, cx);
+                        refresh_invisible_character_warnings(editor, cx);
+                        refresh_spell_check(editor, cx);
                     })
                     .ok()
             })
@@ -609,6 +617,10 @@ impl Editor {
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
         self.refresh_colors(false, None, window, cx);
+        refresh_bracket_colors(self, cx);
+        refresh_injection_tints(self, cx);
+        refresh_invisible_character_warnings(self, cx);
+        refresh_spell_check(self, cx);
         editor_was_scrolled
     }
 
@@ -698,7 +710,7 @@ impl Editor {
         let settings = AllLanguageSettings::get_global(cx);
         if matches!(
             settings.defaults.soft_wrap,
-            SoftWrap::PreferredLineLength | SoftWrap::Bounded
+            SoftWrap::PreferredLineLength | SoftWrap::Bounded | SoftWrap::HangingIndent
         ) && (settings.defaults.preferred_line_length as f32) < visible_column_count
         {
             visible_column_count = settings.defaults.preferred_line_length as f32;
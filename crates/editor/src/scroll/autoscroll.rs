@@ -208,6 +208,18 @@ impl Editor {
                 }
             }
         };
+        // Typewriter scrolling keeps the cursor line centered instead of scrolling minimally.
+        let strategy = match strategy {
+            AutoscrollStrategy::Fit | AutoscrollStrategy::Newest
+                if local
+                    && self
+                        .reading_mode(cx)
+                        .is_some_and(|reading_mode| reading_mode.typewriter_scrolling) =>
+            {
+                AutoscrollStrategy::Center
+            }
+            strategy => strategy,
+        };
         if let Autoscroll::Strategy(_, Some(anchor)) = autoscroll {
             target_top = anchor.to_display_point(&display_map).row().as_f32();
             target_bottom = target_top + 1.;
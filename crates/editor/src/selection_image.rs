@@ -0,0 +1,636 @@
+use std::{io::Cursor, ops::RangeInclusive, sync::Arc};
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{
+    App, Bounds, ClipboardItem, Context, DevicePixels, FontId, GlyphId, Hsla, Image, ImageFormat,
+    Pixels, Rgba, ShapedLine, TextRun, TextSystem, Window, px, rgba,
+};
+use image::RgbaImage;
+use language::{Point, Selection};
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+use settings::Settings as _;
+use theme::ActiveTheme as _;
+use util::ResultExt as _;
+use workspace::{Toast, notifications::NotificationId};
+
+use crate::{CopySelectionAsImage, Editor, EditorSettings, EditorStyle};
+
+/// Space around the window frame, in logical pixels.
+const OUTER_PADDING: f32 = 32.;
+/// Space around the code inside the window frame, in logical pixels.
+const CODE_PADDING: f32 = 16.;
+const TITLE_BAR_HEIGHT: f32 = 36.;
+const CORNER_RADIUS: f32 = 10.;
+const TRAFFIC_LIGHT_RADIUS: f32 = 6.;
+const TRAFFIC_LIGHT_SPACING: f32 = 20.;
+const TRAFFIC_LIGHT_COLORS: [u32; 3] = [0xff5f57ff, 0xfebc2eff, 0x28c840ff];
+/// Images are rendered at least at this scale so that they stay sharp when shared.
+const MIN_SCALE_FACTOR: f32 = 2.;
+/// Selections spanning more lines are cut off, as the lines are laid out on the main thread.
+const MAX_LINES: u32 = 500;
+/// Longer lines are cut off, which also bounds the width of the image.
+const MAX_COLUMNS: usize = 200;
+
+/// A glyph rasterized for an image, positioned in device pixels.
+struct ImageGlyph {
+    origin: gpui::Point<DevicePixels>,
+    bounds: Bounds<DevicePixels>,
+    pixels: Arc<Vec<u8>>,
+    color: Rgba,
+    is_emoji: bool,
+}
+
+/// Everything needed to paint the image, which happens on a background thread.
+struct SelectionImage {
+    size: gpui::Size<DevicePixels>,
+    scale_factor: f32,
+    background: Rgba,
+    window_background: Rgba,
+    border: Rgba,
+    window_bounds: Bounds<f32>,
+    glyphs: Vec<ImageGlyph>,
+}
+
+impl Editor {
+    /// Copies a PNG of the selected lines, rendered with the current theme and buffer font in a
+    /// window frame, to the clipboard.
+    pub fn copy_selection_as_image(
+        &mut self,
+        action: &CopySelectionAsImage,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(style) = self.style.clone() else {
+            return;
+        };
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let (rows, rows_truncated) = selected_rows(&self.selections.newest::<Point>(cx));
+        let show_line_numbers = action
+            .line_numbers
+            .unwrap_or(EditorSettings::get_global(cx).gutter.line_numbers);
+        let title = self.buffer.read(cx).title(cx).to_string();
+
+        let (image, columns_truncated) = layout_selection_image(
+            &snapshot,
+            rows,
+            show_line_numbers,
+            &title,
+            &style,
+            window,
+            cx,
+        );
+        let task = cx.background_spawn(async move { paint_selection_image(image) });
+        let workspace = self
+            .workspace
+            .as_ref()
+            .map(|(workspace, _)| workspace.clone());
+        cx.spawn(async move |_, cx| {
+            let png = task.await?;
+            cx.update(|cx| {
+                cx.write_to_clipboard(ClipboardItem::new_image(&Image::from_bytes(
+                    ImageFormat::Png,
+                    png,
+                )))
+            })?;
+
+            if (rows_truncated || columns_truncated)
+                && let Some(workspace) = workspace
+            {
+                let message = match (rows_truncated, columns_truncated) {
+                    (true, true) => format!(
+                        "The image only shows the first {MAX_LINES} lines and {MAX_COLUMNS} columns of the selection"
+                    ),
+                    (true, false) => {
+                        format!("The image only shows the first {MAX_LINES} lines of the selection")
+                    }
+                    _ => format!("Lines longer than {MAX_COLUMNS} columns were cut off in the image"),
+                };
+                workspace.update(cx, |workspace, cx| {
+                    struct CopySelectionAsImageTruncated;
+
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<CopySelectionAsImageTruncated>(),
+                            message,
+                        ),
+                        cx,
+                    )
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// The rows to render for a selection: the cursor's line when nothing is selected, or the
+/// selected lines, without a last line that the selection only ends at the start of. Also
+/// returns whether rows were left out because of [`MAX_LINES`].
+fn selected_rows(selection: &Selection<Point>) -> (RangeInclusive<u32>, bool) {
+    let start_row = selection.start.row;
+    let end_row = if selection.end.column == 0 && selection.end.row > start_row {
+        selection.end.row - 1
+    } else {
+        selection.end.row
+    };
+    let last_row = start_row + MAX_LINES - 1;
+    (start_row..=end_row.min(last_row), end_row > last_row)
+}
+
+fn layout_selection_image(
+    snapshot: &MultiBufferSnapshot,
+    rows: RangeInclusive<u32>,
+    show_line_numbers: bool,
+    title: &str,
+    style: &EditorStyle,
+    window: &Window,
+    cx: &App,
+) -> (SelectionImage, bool) {
+    let colors = cx.theme().colors();
+    let font = style.text.font();
+    let font_size = style.text.font_size.to_pixels(window.rem_size());
+    let line_height = style.text.line_height_in_pixels(window.rem_size());
+    let scale_factor = window.scale_factor().max(MIN_SCALE_FACTOR);
+    let tab_size = snapshot
+        .language_settings_at(Point::new(*rows.start(), 0), cx)
+        .tab_size
+        .get() as usize;
+
+    let mut lines = rows
+        .clone()
+        .map(|row| highlighted_line(snapshot, row, tab_size, style))
+        .collect::<Vec<_>>();
+    let indent = lines
+        .iter()
+        .filter(|(text, _)| !text.trim().is_empty())
+        .map(|(text, _)| text.len() - text.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+    let mut columns_truncated = false;
+    for (text, runs) in &mut lines {
+        dedent_line(text, runs, indent);
+        columns_truncated |= truncate_line(text, runs, MAX_COLUMNS);
+    }
+
+    let text_system = window.text_system().clone();
+    let shape = |text: String, runs: &[(usize, Hsla)]| {
+        let runs = runs
+            .iter()
+            .map(|(len, color)| TextRun {
+                len: *len,
+                font: font.clone(),
+                color: *color,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            })
+            .collect::<Vec<_>>();
+        text_system.shape_line(text.into(), font_size, &runs, None)
+    };
+    let code_lines = lines
+        .into_iter()
+        .map(|(text, runs)| (shape(text, &runs), runs))
+        .collect::<Vec<_>>();
+    let line_numbers = if show_line_numbers {
+        snapshot
+            .row_infos(MultiBufferRow(*rows.start()))
+            .zip(rows.clone())
+            .map(|(row_info, row)| {
+                let number = (row_info.buffer_row.unwrap_or(row) + 1).to_string();
+                let runs = vec![(number.len(), colors.editor_line_number)];
+                (shape(number, &runs), runs)
+            })
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    let title_runs = vec![(title.len(), colors.text_muted)];
+    let title_line = shape(title.to_string(), &title_runs);
+
+    let gutter_width = line_numbers
+        .iter()
+        .map(|(line, _)| line.width)
+        .max()
+        .map_or(px(0.), |width| width + px(CODE_PADDING));
+    let code_width = code_lines
+        .iter()
+        .map(|(line, _)| line.width)
+        .max()
+        .unwrap_or_default()
+        .max(title_line.width + px(2. * TRAFFIC_LIGHT_SPACING * 4.));
+    let window_width = gutter_width + code_width + px(2. * CODE_PADDING);
+    let window_height = px(TITLE_BAR_HEIGHT + CODE_PADDING)
+        + line_height * code_lines.len() as f32
+        + px(CODE_PADDING);
+    let window_bounds = Bounds {
+        origin: gpui::point(OUTER_PADDING, OUTER_PADDING),
+        size: gpui::size(window_width.0, window_height.0),
+    };
+    let size = gpui::size(
+        DevicePixels(((window_width.0 + 2. * OUTER_PADDING) * scale_factor).ceil() as i32),
+        DevicePixels(((window_height.0 + 2. * OUTER_PADDING) * scale_factor).ceil() as i32),
+    );
+
+    let mut glyphs = Vec::new();
+    let mut rasterized = HashMap::default();
+    let mut push_line = |line: &ShapedLine,
+                         runs: &[(usize, Hsla)],
+                         origin: gpui::Point<Pixels>,
+                         glyphs: &mut Vec<ImageGlyph>| {
+        let baseline = origin.y + (line_height - line.ascent - line.descent) / 2. + line.ascent;
+        for run in &line.runs {
+            for glyph in &run.glyphs {
+                let Some((bounds, pixels)) = rasterize_glyph(
+                    cx.text_system(),
+                    &mut rasterized,
+                    run.font_id,
+                    glyph.id,
+                    font_size,
+                    scale_factor,
+                    glyph.is_emoji,
+                ) else {
+                    continue;
+                };
+                let glyph_origin = gpui::point(origin.x + glyph.position.x, baseline);
+                glyphs.push(ImageGlyph {
+                    origin: gpui::point(
+                        DevicePixels((glyph_origin.x.0 * scale_factor).floor() as i32),
+                        DevicePixels((glyph_origin.y.0 * scale_factor).floor() as i32),
+                    ),
+                    bounds,
+                    pixels,
+                    color: color_at(runs, glyph.index).into(),
+                    is_emoji: glyph.is_emoji,
+                });
+            }
+        }
+    };
+
+    let title_origin = gpui::point(
+        px(window_bounds.origin.x) + (window_width - title_line.width) / 2.,
+        px(window_bounds.origin.y) + (px(TITLE_BAR_HEIGHT) - line_height) / 2.,
+    );
+    push_line(&title_line, &title_runs, title_origin, &mut glyphs);
+    let code_origin = gpui::point(
+        px(window_bounds.origin.x + CODE_PADDING),
+        px(window_bounds.origin.y + TITLE_BAR_HEIGHT),
+    );
+    for (ix, (line, runs)) in code_lines.iter().enumerate() {
+        let y = code_origin.y + line_height * ix as f32;
+        if let Some((line_number, runs)) = line_numbers.get(ix) {
+            let x = code_origin.x + gutter_width - px(CODE_PADDING) - line_number.width;
+            push_line(line_number, runs, gpui::point(x, y), &mut glyphs);
+        }
+        push_line(
+            line,
+            runs,
+            gpui::point(code_origin.x + gutter_width, y),
+            &mut glyphs,
+        );
+    }
+
+    let image = SelectionImage {
+        size,
+        scale_factor,
+        background: cx.theme().accents().color_for_index(0).into(),
+        window_background: style.background.into(),
+        border: colors.border.into(),
+        window_bounds,
+        glyphs,
+    };
+    (image, columns_truncated)
+}
+
+/// Returns the text of the given row with tabs expanded to spaces, along with the lengths and
+/// syntax highlighting colors of its runs.
+fn highlighted_line(
+    snapshot: &MultiBufferSnapshot,
+    row: u32,
+    tab_size: usize,
+    style: &EditorStyle,
+) -> (String, Vec<(usize, Hsla)>) {
+    let mut text = String::new();
+    let mut runs: Vec<(usize, Hsla)> = Vec::new();
+    let mut column = 0;
+    let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+    for chunk in snapshot.chunks(Point::new(row, 0)..line_end, true) {
+        let color = chunk
+            .syntax_highlight_id
+            .and_then(|id| id.style(&style.syntax)?.color)
+            .unwrap_or(style.text.color);
+        let start = text.len();
+        for ch in chunk.text.chars() {
+            if ch == '\t' {
+                let spaces = tab_size - column % tab_size;
+                text.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            } else {
+                text.push(ch);
+                column += 1;
+            }
+        }
+        let len = text.len() - start;
+        match runs.last_mut() {
+            Some((last_len, last_color)) if *last_color == color => *last_len += len,
+            _ if len > 0 => runs.push((len, color)),
+            _ => {}
+        }
+    }
+    (text, runs)
+}
+
+/// Removes `indent` leading spaces from a line, which all lines share.
+fn dedent_line(text: &mut String, runs: &mut Vec<(usize, Hsla)>, indent: usize) {
+    let indent = indent.min(text.len());
+    text.drain(..indent);
+    let mut remaining = indent;
+    while remaining > 0
+        && let Some((len, _)) = runs.first_mut()
+    {
+        let removed = remaining.min(*len);
+        *len -= removed;
+        remaining -= removed;
+        if *len == 0 {
+            runs.remove(0);
+        }
+    }
+}
+
+/// Cuts a line off after `max_columns` characters, ending it with an ellipsis in the color of
+/// the last character kept. Returns whether the line was cut off.
+fn truncate_line(text: &mut String, runs: &mut Vec<(usize, Hsla)>, max_columns: usize) -> bool {
+    let Some((end, _)) = text.char_indices().nth(max_columns) else {
+        return false;
+    };
+    text.truncate(end);
+    let mut remaining = end;
+    runs.retain_mut(|(len, _)| {
+        if remaining == 0 {
+            return false;
+        }
+        *len = (*len).min(remaining);
+        remaining -= *len;
+        true
+    });
+    text.push('…');
+    if let Some((len, _)) = runs.last_mut() {
+        *len += '…'.len_utf8();
+    }
+    true
+}
+
+fn color_at(runs: &[(usize, Hsla)], index: usize) -> Hsla {
+    let mut end = 0;
+    for (len, color) in runs {
+        end += len;
+        if index < end {
+            return *color;
+        }
+    }
+    Hsla::default()
+}
+
+fn rasterize_glyph(
+    text_system: &Arc<TextSystem>,
+    rasterized: &mut HashMap<(FontId, GlyphId, bool), Option<(Bounds<DevicePixels>, Arc<Vec<u8>>)>>,
+    font_id: FontId,
+    glyph_id: GlyphId,
+    font_size: Pixels,
+    scale_factor: f32,
+    is_emoji: bool,
+) -> Option<(Bounds<DevicePixels>, Arc<Vec<u8>>)> {
+    rasterized
+        .entry((font_id, glyph_id, is_emoji))
+        .or_insert_with(|| {
+            text_system
+                .rasterize_glyph_image(font_id, glyph_id, font_size, scale_factor, is_emoji)
+                .log_err()
+                .flatten()
+                .map(|(bounds, pixels)| (bounds, Arc::new(pixels)))
+        })
+        .clone()
+}
+
+fn paint_selection_image(image: SelectionImage) -> Result<Vec<u8>> {
+    let mut canvas = RgbaImage::new(image.size.width.0 as u32, image.size.height.0 as u32);
+    let scale = |bounds: Bounds<f32>| Bounds {
+        origin: gpui::point(
+            bounds.origin.x * image.scale_factor,
+            bounds.origin.y * image.scale_factor,
+        ),
+        size: gpui::size(
+            bounds.size.width * image.scale_factor,
+            bounds.size.height * image.scale_factor,
+        ),
+    };
+
+    fill_rounded_rect(
+        &mut canvas,
+        Bounds {
+            origin: gpui::point(0., 0.),
+            size: gpui::size(image.size.width.0 as f32, image.size.height.0 as f32),
+        },
+        0.,
+        image.background,
+    );
+    let window_bounds = scale(image.window_bounds);
+    fill_rounded_rect(
+        &mut canvas,
+        window_bounds,
+        CORNER_RADIUS * image.scale_factor,
+        image.border,
+    );
+    let border_width = image.scale_factor;
+    fill_rounded_rect(
+        &mut canvas,
+        Bounds {
+            origin: gpui::point(
+                window_bounds.origin.x + border_width,
+                window_bounds.origin.y + border_width,
+            ),
+            size: gpui::size(
+                window_bounds.size.width - 2. * border_width,
+                window_bounds.size.height - 2. * border_width,
+            ),
+        },
+        (CORNER_RADIUS - 1.) * image.scale_factor,
+        image.window_background,
+    );
+    for (ix, color) in TRAFFIC_LIGHT_COLORS.into_iter().enumerate() {
+        let center = gpui::point(
+            image.window_bounds.origin.x + TRAFFIC_LIGHT_SPACING * (ix + 1) as f32,
+            image.window_bounds.origin.y + TITLE_BAR_HEIGHT / 2.,
+        );
+        fill_rounded_rect(
+            &mut canvas,
+            scale(Bounds {
+                origin: gpui::point(
+                    center.x - TRAFFIC_LIGHT_RADIUS,
+                    center.y - TRAFFIC_LIGHT_RADIUS,
+                ),
+                size: gpui::size(2. * TRAFFIC_LIGHT_RADIUS, 2. * TRAFFIC_LIGHT_RADIUS),
+            }),
+            TRAFFIC_LIGHT_RADIUS * image.scale_factor,
+            rgba(color),
+        );
+    }
+
+    for glyph in &image.glyphs {
+        let origin = glyph.origin + glyph.bounds.origin;
+        let width = glyph.bounds.size.width.0;
+        for y in 0..glyph.bounds.size.height.0 {
+            for x in 0..width {
+                let ix = (y * width + x) as usize;
+                let (color, coverage) = if glyph.is_emoji {
+                    let &[b, g, r, a] = &glyph.pixels[ix * 4..ix * 4 + 4] else {
+                        continue;
+                    };
+                    let color = Rgba {
+                        r: r as f32 / 255.,
+                        g: g as f32 / 255.,
+                        b: b as f32 / 255.,
+                        a: 1.,
+                    };
+                    (color, a as f32 / 255.)
+                } else {
+                    (glyph.color, glyph.pixels[ix] as f32 / 255.)
+                };
+                blend_pixel(&mut canvas, origin.x.0 + x, origin.y.0 + y, color, coverage);
+            }
+        }
+    }
+
+    let mut png = Vec::new();
+    canvas.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
+
+/// Fills a rectangle with rounded corners, given in device pixels, anti-aliasing its edges.
+fn fill_rounded_rect(canvas: &mut RgbaImage, bounds: Bounds<f32>, radius: f32, color: Rgba) {
+    let half_width = bounds.size.width / 2.;
+    let half_height = bounds.size.height / 2.;
+    let center_x = bounds.origin.x + half_width;
+    let center_y = bounds.origin.y + half_height;
+    let radius = radius.min(half_width).min(half_height);
+    for y in bounds.origin.y.floor() as i32..(center_y + half_height).ceil() as i32 {
+        for x in bounds.origin.x.floor() as i32..(center_x + half_width).ceil() as i32 {
+            // The signed distance from the pixel's center to the edge of the shape, which is
+            // negative inside of it.
+            let qx = (x as f32 + 0.5 - center_x).abs() - half_width + radius;
+            let qy = (y as f32 + 0.5 - center_y).abs() - half_height + radius;
+            let distance = qx.max(0.).hypot(qy.max(0.)) + qx.max(qy).min(0.) - radius;
+            blend_pixel(canvas, x, y, color, (0.5 - distance).clamp(0., 1.));
+        }
+    }
+}
+
+fn blend_pixel(canvas: &mut RgbaImage, x: i32, y: i32, color: Rgba, coverage: f32) {
+    if coverage <= 0. || x < 0 || y < 0 || x >= canvas.width() as i32 || y >= canvas.height() as i32
+    {
+        return;
+    }
+    let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+    let alpha = color.a * coverage;
+    let dst_alpha = pixel[3] as f32 / 255.;
+    let out_alpha = alpha + dst_alpha * (1. - alpha);
+    if out_alpha <= 0. {
+        return;
+    }
+    for (channel, source) in [color.r, color.g, color.b].into_iter().enumerate() {
+        let destination = pixel[channel] as f32 / 255.;
+        let out = (source * alpha + destination * dst_alpha * (1. - alpha)) / out_alpha;
+        pixel[channel] = (out * 255.).round() as u8;
+    }
+    pixel[3] = (out_alpha * 255.).round() as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{black, white};
+
+    #[test]
+    fn test_dedent_line() {
+        let mut text = "    let a = 1;".to_string();
+        let mut runs = vec![(2, black()), (6, white()), (6, black())];
+        dedent_line(&mut text, &mut runs, 4);
+        assert_eq!(text, "let a = 1;");
+        assert_eq!(runs, [(4, white()), (6, black())]);
+    }
+
+    #[test]
+    fn test_selected_rows() {
+        let selection = |start: Point, end: Point| Selection {
+            id: 0,
+            start,
+            end,
+            reversed: false,
+            goal: language::SelectionGoal::None,
+        };
+        assert_eq!(
+            selected_rows(&selection(Point::new(7, 3), Point::new(7, 3))),
+            (7..=7, false)
+        );
+        assert_eq!(
+            selected_rows(&selection(Point::new(2, 0), Point::new(5, 0))),
+            (2..=4, false)
+        );
+        assert_eq!(
+            selected_rows(&selection(Point::new(2, 4), Point::new(5, 1))),
+            (2..=5, false)
+        );
+        assert_eq!(
+            selected_rows(&selection(Point::new(10, 0), Point::new(10 + MAX_LINES, 0))),
+            (10..=10 + MAX_LINES - 1, false)
+        );
+        assert_eq!(
+            selected_rows(&selection(Point::new(10, 0), Point::new(10_000, 0))),
+            (10..=10 + MAX_LINES - 1, true)
+        );
+    }
+
+    #[test]
+    fn test_truncate_line() {
+        let mut text = "let a = 1;".to_string();
+        let mut runs = vec![(4, black()), (6, white())];
+        assert!(!truncate_line(&mut text, &mut runs, 10));
+        assert_eq!(text, "let a = 1;");
+
+        assert!(truncate_line(&mut text, &mut runs, 3));
+        assert_eq!(text, "let…");
+        assert_eq!(runs, [(3 + '…'.len_utf8(), black())]);
+
+        let mut text = "ab→cd".to_string();
+        let mut runs = vec![(2, black()), (3, white()), (2, black())];
+        assert!(truncate_line(&mut text, &mut runs, 3));
+        assert_eq!(text, "ab→…");
+        assert_eq!(runs, [(2, black()), (3 + '…'.len_utf8(), white())]);
+    }
+
+    #[test]
+    fn test_paint_selection_image() {
+        let background = rgba(0x336699ff);
+        let png = paint_selection_image(SelectionImage {
+            size: gpui::size(DevicePixels(200), DevicePixels(200)),
+            scale_factor: 2.,
+            background,
+            window_background: rgba(0x000000ff),
+            border: rgba(0xffffffff),
+            window_bounds: Bounds {
+                origin: gpui::point(OUTER_PADDING / 2., OUTER_PADDING / 4.),
+                size: gpui::size(80., 80.),
+            },
+            glyphs: Vec::new(),
+        })
+        .unwrap();
+
+        let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(image.dimensions(), (200, 200));
+        assert_eq!(image.get_pixel(0, 0).0, [0x33, 0x66, 0x99, 0xff]);
+        // The middle of the window frame, below its title bar.
+        assert_eq!(image.get_pixel(100, 150).0, [0x00, 0x00, 0x00, 0xff]);
+    }
+}
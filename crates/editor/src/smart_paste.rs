@@ -0,0 +1,256 @@
+use gpui::{ClipboardEntry, Context, Window};
+use language::{IndentSize, LineEnding};
+use multi_buffer::{MultiBufferRow, ToPoint as _};
+
+use crate::{ClipboardSelection, Editor, HideMouseCursorOrigin, actions::SmartPaste};
+
+/// The quotes that string literals are rewritten from, when their language's default quote is
+/// another one of them.
+const REWRITABLE_QUOTES: [char; 2] = ['"', '\''];
+
+impl Editor {
+    pub fn smart_paste(
+        &mut self,
+        action: &SmartPaste,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+        let (text, clipboard_selections) = match item.entries() {
+            [ClipboardEntry::String(clipboard_string)] => (
+                clipboard_string.text().clone(),
+                clipboard_string.metadata_json::<Vec<ClipboardSelection>>(),
+            ),
+            _ => (item.text().unwrap_or_default(), None),
+        };
+
+        let selections = self.selections.all::<usize>(cx);
+        // Like a regular paste, each cursor gets its own segment of text copied from as many
+        // selections.
+        let segments = match clipboard_selections {
+            Some(clipboard_selections) if clipboard_selections.len() == selections.len() => {
+                let mut start = 0;
+                clipboard_selections
+                    .iter()
+                    .map(|clipboard_selection| {
+                        let end = (start + clipboard_selection.len).min(text.len());
+                        let segment = text.get(start..end).unwrap_or_default();
+                        start = end + 1;
+                        segment
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => vec![text.as_str(); selections.len()],
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = selections
+            .iter()
+            .zip(segments)
+            .map(|(selection, segment)| {
+                let mut segment = segment.to_string();
+                LineEnding::normalize(&mut segment);
+                let row = selection.start.to_point(&snapshot).row;
+                let indent = snapshot.indent_size_for_line(MultiBufferRow(row));
+                let mut text = reindent_pasted_text(&segment, indent);
+
+                let quote = snapshot
+                    .language_scope_at(selection.start)
+                    .filter(|scope| {
+                        scope
+                            .override_name()
+                            .is_none_or(|name| name != "string" && !name.starts_with("comment"))
+                    })
+                    .and_then(|scope| scope.string_quote());
+                if action.rewrite_quotes
+                    && let Some(quote) = quote
+                {
+                    text = rewrite_string_quotes(&text, quote);
+                }
+                (selection.range(), text)
+            })
+            .collect::<Vec<_>>();
+        drop(snapshot);
+
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            let selections = this.selections.all::<usize>(cx);
+            this.change_selections(Default::default(), window, cx, |s| s.select(selections));
+        });
+    }
+}
+
+/// Replaces the indentation shared by the lines of `text` with `indent`, leaving its first line
+/// unindented, as the cursor it's pasted at already has the indentation before it.
+///
+/// A first line that isn't indented is ignored when finding the shared indentation, as it was
+/// likely copied from the middle of a line.
+fn reindent_pasted_text(text: &str, indent: IndentSize) -> String {
+    let leading_whitespace =
+        |line: &str| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+    let mut lines = text.split('\n');
+    let first_line = lines.next().unwrap_or_default();
+
+    let mut common_indent = None::<&str>;
+    for (ix, line) in text.split('\n').enumerate() {
+        let line_indent = leading_whitespace(line);
+        if line.trim().is_empty() || (ix == 0 && line_indent.is_empty()) {
+            continue;
+        }
+        common_indent = Some(match common_indent {
+            Some(common_indent) => {
+                let common_len = common_indent
+                    .bytes()
+                    .zip(line_indent.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                &common_indent[..common_len]
+            }
+            None => line_indent,
+        });
+    }
+    let common_indent = common_indent.unwrap_or_default();
+
+    let mut result = first_line
+        .strip_prefix(common_indent)
+        .unwrap_or(first_line.trim_start_matches([' ', '\t']))
+        .to_string();
+    for line in lines {
+        result.push('\n');
+        if line.trim().is_empty() {
+            continue;
+        }
+        result.extend(indent.chars());
+        result.push_str(line.strip_prefix(common_indent).unwrap_or(line));
+    }
+    result
+}
+
+/// Rewrites the string literals in `text` that are delimited by another quote than `quote` to
+/// be delimited by it instead.
+///
+/// Strings that contain `quote`, that span multiple lines, or that are triple-quoted are left
+/// alone, as are quotes that directly follow a letter or digit, like apostrophes and the quotes
+/// of prefixed string literals.
+fn rewrite_string_quotes(text: &str, quote: char) -> String {
+    if !REWRITABLE_QUOTES.contains(&quote) {
+        return text.to_string();
+    }
+
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(text.len());
+    let mut ix = 0;
+    while ix < chars.len() {
+        let c = chars[ix];
+        if !REWRITABLE_QUOTES.contains(&c) {
+            result.push(c);
+            ix += 1;
+            continue;
+        }
+
+        let run_len = chars[ix..].iter().take_while(|next| **next == c).count();
+        if run_len >= 3 {
+            let end_ix = (ix + 3..chars.len().saturating_sub(2))
+                .find(|&end_ix| chars[end_ix..end_ix + 3] == [c; 3])
+                .map_or(chars.len(), |end_ix| end_ix + 3);
+            result.extend(&chars[ix..end_ix]);
+            ix = end_ix;
+            continue;
+        }
+        let Some(end_ix) = string_end(&chars, ix) else {
+            result.push(c);
+            ix += 1;
+            continue;
+        };
+
+        let body = &chars[ix + 1..end_ix];
+        let follows_word = ix
+            .checked_sub(1)
+            .is_some_and(|prev_ix| chars[prev_ix].is_alphanumeric());
+        if c == quote || follows_word || body.contains(&quote) {
+            result.extend(&chars[ix..=end_ix]);
+        } else {
+            result.push(quote);
+            let mut body_ix = 0;
+            while body_ix < body.len() {
+                // Quotes no longer need to be escaped once they aren't the delimiter.
+                if body[body_ix] == '\\' && body.get(body_ix + 1) == Some(&c) {
+                    body_ix += 1;
+                } else if body[body_ix] == '\\' {
+                    result.push('\\');
+                    body_ix += 1;
+                    if body_ix == body.len() {
+                        break;
+                    }
+                }
+                result.push(body[body_ix]);
+                body_ix += 1;
+            }
+            result.push(quote);
+        }
+        ix = end_ix + 1;
+    }
+    result
+}
+
+/// Returns the index of the quote that closes the string opened at `start_ix`, if it's on the
+/// same line.
+fn string_end(chars: &[char], start_ix: usize) -> Option<usize> {
+    let quote = chars[start_ix];
+    let mut ix = start_ix + 1;
+    while let Some(&c) = chars.get(ix) {
+        match c {
+            '\\' => ix += 2,
+            '\n' => return None,
+            c if c == quote => return Some(ix),
+            _ => ix += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reindent_pasted_text() {
+        assert_eq!(
+            reindent_pasted_text("    if a {\n        b();\n\n    }\n", IndentSize::spaces(2)),
+            "if a {\n      b();\n\n  }\n"
+        );
+        // The first line was copied from the middle of a line.
+        assert_eq!(
+            reindent_pasted_text("foo(\n        a,\n    )", IndentSize::tab()),
+            "foo(\n\t    a,\n\t)"
+        );
+        assert_eq!(
+            reindent_pasted_text("  single", IndentSize::spaces(4)),
+            "single"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_string_quotes() {
+        assert_eq!(
+            rewrite_string_quotes(r#"f('a', 'it\'s', 'say "hi"', "b")"#, '"'),
+            r#"f("a", "it's", 'say "hi"', "b")"#
+        );
+        // Apostrophes, prefixed strings and triple-quoted strings are left alone.
+        assert_eq!(
+            rewrite_string_quotes("# don't\nx = f'{y}' + '''z'''", '"'),
+            "# don't\nx = f'{y}' + '''z'''"
+        );
+        assert_eq!(
+            rewrite_string_quotes(r#"["a", 'b']"#, '\''),
+            r#"['a', 'b']"#
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use collections::HashMap;
+use fs::Fs;
+use futures::{
+    FutureExt as _,
+    future::{Shared, join_all},
+};
+use gpui::{App, Context, Entity, Global, HighlightStyle, Task, UnderlineStyle, Window, px};
+use language::{
+    Buffer, BufferSnapshot, LanguageServerId, SpellCheckDictionary,
+    language_settings::{SpellCheckSettings, language_settings},
+    words_to_spell_check,
+};
+use multi_buffer::{ExcerptId, ToPoint as _};
+use project::{CodeAction, LspAction, ProjectTransaction};
+use text::{Bias, Point, ToOffset as _};
+use theme::ActiveTheme as _;
+
+use crate::{CodeActionProvider, Editor};
+
+enum SpellCheckHighlight {}
+
+/// How many corrections are offered for a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+type DictionaryKey = (Vec<PathBuf>, Vec<String>);
+
+/// The dictionaries loaded for spell checking, which are shared by all editors, keyed by the
+/// word lists and words they were built from.
+#[derive(Default)]
+struct SpellCheckDictionaries(HashMap<DictionaryKey, Shared<Task<Arc<SpellCheckDictionary>>>>);
+
+impl Global for SpellCheckDictionaries {}
+
+fn dictionary_key(settings: &SpellCheckSettings) -> DictionaryKey {
+    (settings.dictionaries.clone(), settings.words.clone())
+}
+
+fn dictionary_for(
+    settings: &SpellCheckSettings,
+    fs: &Arc<dyn Fs>,
+    cx: &mut App,
+) -> Shared<Task<Arc<SpellCheckDictionary>>> {
+    let key = dictionary_key(settings);
+    if let Some(dictionary) = cx.default_global::<SpellCheckDictionaries>().0.get(&key) {
+        return dictionary.clone();
+    }
+
+    let fs = fs.clone();
+    let (paths, words) = key.clone();
+    let dictionary = cx
+        .background_spawn(async move {
+            let mut dictionary = SpellCheckDictionary::default();
+            for path in paths {
+                let text = match fs.load(&path).await {
+                    Ok(text) => text,
+                    Err(error) => {
+                        log::error!("failed to load spell check dictionary {path:?}: {error:#}");
+                        continue;
+                    }
+                };
+                if path.extension().is_some_and(|extension| extension == "dic") {
+                    let affix_path = path.with_extension("aff");
+                    let affixes = fs.load(&affix_path).await.unwrap_or_else(|error| {
+                        log::error!("failed to load spell check affixes {affix_path:?}: {error:#}");
+                        String::new()
+                    });
+                    dictionary.add_hunspell_dictionary(&text, &affixes);
+                } else {
+                    dictionary.add_word_list(&text);
+                }
+            }
+            for word in words {
+                dictionary.add_word(&word);
+            }
+            Arc::new(dictionary)
+        })
+        .shared();
+    cx.default_global::<SpellCheckDictionaries>()
+        .0
+        .insert(key, dictionary.clone());
+    dictionary
+}
+
+fn misspelled_words(
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+    check_all_text: bool,
+    dictionary: &SpellCheckDictionary,
+) -> Vec<(Range<usize>, String)> {
+    let mut misspelled_words = Vec::new();
+    for range in buffer.spell_check_ranges(range, check_all_text) {
+        let text = buffer.text_for_range(range.clone()).collect::<String>();
+        for (word_range, word) in words_to_spell_check(&text) {
+            if !dictionary.contains(word) {
+                misspelled_words.push((
+                    range.start + word_range.start..range.start + word_range.end,
+                    word.to_string(),
+                ));
+            }
+        }
+    }
+    misspelled_words
+}
+
+/// Underlines the visible words that aren't in the dictionaries configured by the
+/// `spell_check` setting of their language, within comments and strings, or within all of
+/// the text of languages that set `check_all_text`.
+pub fn refresh_spell_check(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let fs = editor
+        .project
+        .as_ref()
+        .filter(|_| editor.mode().is_full())
+        .map(|project| project.read(cx).fs().clone());
+    let Some(fs) = fs else {
+        editor.clear_highlights::<SpellCheckHighlight>(cx);
+        return;
+    };
+
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let visible_start = editor.scroll_manager.anchor().anchor.to_point(&snapshot);
+    let visible_end = snapshot.clip_point(
+        visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+        Bias::Left,
+    );
+
+    let mut ranges = Vec::new();
+    let mut loading_dictionaries = Vec::new();
+    for (buffer, buffer_range, excerpt_id) in
+        snapshot.range_to_buffer_ranges(visible_start..visible_end)
+    {
+        let settings = language_settings(
+            buffer.language().map(|language| language.name()),
+            buffer.file(),
+            cx,
+        )
+        .spell_check
+        .clone();
+        if !settings.enabled {
+            continue;
+        }
+        let dictionary = dictionary_for(&settings, &fs, cx);
+        let Some(dictionary) = dictionary.clone().now_or_never() else {
+            loading_dictionaries.push(dictionary);
+            continue;
+        };
+        if dictionary.is_empty() {
+            continue;
+        }
+
+        for (range, _) in
+            misspelled_words(buffer, buffer_range, settings.check_all_text, &dictionary)
+        {
+            let (Some(start), Some(end)) = (
+                snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_after(range.start)),
+                snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_before(range.end)),
+            ) else {
+                continue;
+            };
+            ranges.push(start..end);
+        }
+    }
+
+    if !loading_dictionaries.is_empty() {
+        editor.spell_check_task = Some(cx.spawn(async move |editor, cx| {
+            join_all(loading_dictionaries).await;
+            editor
+                .update(cx, |editor, cx| refresh_spell_check(editor, cx))
+                .ok();
+        }));
+    }
+
+    if ranges.is_empty() {
+        editor.clear_highlights::<SpellCheckHighlight>(cx);
+        return;
+    }
+    let style = HighlightStyle {
+        underline: Some(UnderlineStyle {
+            color: Some(cx.theme().status().info),
+            thickness: px(1.),
+            wavy: true,
+        }),
+        ..HighlightStyle::default()
+    };
+    editor.highlight_text::<SpellCheckHighlight>(ranges, style, cx);
+}
+
+/// Offers the dictionary words closest to the misspelled words around the cursor as code
+/// actions.
+pub(crate) struct SpellCheckCodeActionProvider;
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        "spell_check".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let buffer = buffer.read(cx);
+        let settings = language_settings(
+            buffer
+                .language_at(range.start)
+                .map(|language| language.name()),
+            buffer.file(),
+            cx,
+        )
+        .spell_check
+        .clone();
+        let dictionary = settings
+            .enabled
+            .then(|| cx.try_global::<SpellCheckDictionaries>())
+            .flatten()
+            .and_then(|dictionaries| dictionaries.0.get(&dictionary_key(&settings)))
+            .and_then(|dictionary| dictionary.clone().now_or_never());
+        let Some(dictionary) = dictionary else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let snapshot = buffer.snapshot();
+        cx.background_spawn(async move {
+            let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+            let start_row = snapshot.offset_to_point(range.start).row;
+            let end_row = snapshot.offset_to_point(range.end).row;
+            let lines = Point::new(start_row, 0).to_offset(&snapshot)
+                ..Point::new(end_row, snapshot.line_len(end_row)).to_offset(&snapshot);
+
+            let mut actions = Vec::new();
+            for (word_range, word) in
+                misspelled_words(&snapshot, lines, settings.check_all_text, &dictionary)
+            {
+                if word_range.start > range.end || word_range.end < range.start {
+                    continue;
+                }
+                let word_anchors =
+                    snapshot.anchor_after(word_range.start)..snapshot.anchor_before(word_range.end);
+                for suggestion in dictionary.suggestions(&word, MAX_SUGGESTIONS) {
+                    actions.push(CodeAction {
+                        server_id: LanguageServerId(0),
+                        range: word_anchors.clone(),
+                        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                            title: format!("Change \"{word}\" to \"{suggestion}\""),
+                            kind: Some(lsp::CodeActionKind::QUICKFIX),
+                            data: Some(suggestion.into()),
+                            ..Default::default()
+                        })),
+                        resolved: true,
+                    });
+                }
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _: ExcerptId,
+        _: bool,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        if let LspAction::Action(lsp_action) = &action.lsp_action
+            && let Some(suggestion) = lsp_action.data.as_ref().and_then(|data| data.as_str())
+        {
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit([(action.range.clone(), suggestion)], None, cx);
+            });
+        }
+        Task::ready(Ok(ProjectTransaction::default()))
+    }
+}
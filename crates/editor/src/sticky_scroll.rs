@@ -0,0 +1,132 @@
+use language::{OutlineItem, Point};
+use multi_buffer::{Anchor, ToPoint as _};
+use theme::SyntaxTheme;
+
+use crate::{DisplayPoint, DisplayRow, EditorSnapshot};
+
+/// The most headers pinned to the top of the editor at once.
+const MAX_STICKY_HEADERS: usize = 5;
+
+impl EditorSnapshot {
+    /// The outline items whose first line is pinned to the top of the editor
+    /// when it's scrolled to the given row, from the outermost to the innermost.
+    ///
+    /// The `n`th header is pinned while the line right below the `n` headers
+    /// above it is inside of the item, past its first line. As the outline
+    /// items come from all syntax layers, this includes items of injected
+    /// languages, such as functions in a `<script>` tag.
+    pub fn sticky_scroll_headers(
+        &self,
+        scroll_top: f32,
+        theme: Option<&SyntaxTheme>,
+    ) -> Vec<OutlineItem<Anchor>> {
+        let buffer = &self.buffer_snapshot;
+        let top_row = scroll_top.max(0.) as u32;
+        let mut headers = Vec::<OutlineItem<Anchor>>::new();
+        while headers.len() < MAX_STICKY_HEADERS {
+            let display_row = DisplayRow(top_row + headers.len() as u32);
+            if display_row > self.max_point().row() {
+                break;
+            }
+            let row = DisplayPoint::new(display_row, 0).to_point(self).row;
+            let Some((_, items)) = buffer.symbols_containing(Point::new(row, 0), theme) else {
+                break;
+            };
+
+            let mut enclosing = items.into_iter().filter(|item| {
+                item.range.start.to_point(buffer).row < row
+                    && item.range.end.to_point(buffer).row > row
+            });
+            // The items enclosing this line must still include the ones pinned
+            // above it, or else the next header is left out.
+            if !headers.iter().all(|header| {
+                enclosing
+                    .next()
+                    .is_some_and(|item| item.range == header.range)
+            }) {
+                break;
+            }
+            match enclosing.next() {
+                Some(item) => headers.push(item),
+                None => break,
+            }
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MultiBuffer, editor_tests::init_test, test::build_editor};
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Buffer, Language, LanguageConfig};
+    use std::sync::Arc;
+    use unindent::Unindent as _;
+
+    #[gpui::test]
+    async fn test_sticky_scroll_headers(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig::default(),
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+
+        let text = r#"
+            mod a {
+                fn b() {
+                    1;
+                    2;
+                    3;
+                }
+            }
+            fn c() {
+                4;
+            }
+        "#
+        .unindent();
+
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+        editor
+            .condition::<crate::EditorEvent>(cx, |editor, cx| {
+                !editor.buffer.read(cx).is_parsing(cx)
+            })
+            .await;
+
+        editor.update_in(cx, |editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            let headers = |scroll_top: f32| {
+                snapshot
+                    .sticky_scroll_headers(scroll_top, None)
+                    .into_iter()
+                    .map(|item| item.text)
+                    .collect::<Vec<_>>()
+            };
+
+            // Nothing is pinned while the first line of the item is visible.
+            assert!(headers(0.).is_empty());
+            // Once it scrolls past, the first header covers the line below,
+            // so the function starting there is pinned too.
+            assert_eq!(headers(1.), ["mod a", "fn b"]);
+            assert_eq!(headers(2.5), ["mod a", "fn b"]);
+            assert_eq!(headers(3.), ["mod a", "fn b"]);
+            // The function's header is unpinned when its last line would be
+            // covered by it.
+            assert_eq!(headers(4.), ["mod a"]);
+            assert!(headers(6.).is_empty());
+            assert_eq!(headers(8.), ["fn c"]);
+        });
+    }
+}
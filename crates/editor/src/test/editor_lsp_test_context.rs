@@ -74,6 +74,10 @@ pub(crate) fn rust_lang() -> Arc<Language> {
                     "{"
                     (_)* @function.inside
                     "}" )) @function.around
+
+            (line_comment)+ @comment.around
+
+            (use_declaration)+ @import.around
         "#})),
         ..Default::default()
     })
@@ -223,6 +227,7 @@ impl EditorLspTestContext {
                         newline: true,
                     }],
                     disabled_scopes_by_bracket_ix: Default::default(),
+                    enabled_scopes_by_bracket_ix: Default::default(),
                 },
                 word_characters,
                 ..Default::default()
@@ -20,7 +20,7 @@ use gpui::{
     Window, actions, rems,
 };
 use open_path_prompt::OpenPathPrompt;
-use picker::{Picker, PickerDelegate};
+use picker::{Picker, PickerDelegate, PickerPreview, PickerPreviewSettings};
 use project::{PathMatchCandidateSet, Project, ProjectPath, WorktreeId};
 use search::ToggleIncludeIgnored;
 use settings::Settings;
@@ -85,6 +85,7 @@ pub struct FileFinder {
 
 pub fn init_settings(cx: &mut App) {
     FileFinderSettings::register(cx);
+    PickerPreviewSettings::register(cx);
 }
 
 pub fn init(cx: &mut App) {
@@ -413,6 +414,7 @@ pub struct FileFinderDelegate {
     focus_handle: FocusHandle,
     include_ignored: Option<bool>,
     include_ignored_refresh: Task<()>,
+    preview: Option<Entity<PickerPreview>>,
 }
 
 /// Use a custom ordering for file finder: the regular one
@@ -812,6 +814,9 @@ impl FileFinderDelegate {
         cx: &mut Context<FileFinder>,
     ) -> Self {
         Self::subscribe_to_updates(&project, window, cx);
+        let preview = PickerPreviewSettings::get_global(cx)
+            .file_finder
+            .then(|| cx.new(|_| PickerPreview::new(project.clone())));
         Self {
             file_finder,
             workspace,
@@ -833,6 +838,7 @@ impl FileFinderDelegate {
             focus_handle: cx.focus_handle(),
             include_ignored: FileFinderSettings::get_global(cx).include_ignored,
             include_ignored_refresh: Task::ready(()),
+            preview,
         }
     }
 
@@ -1644,6 +1650,43 @@ impl PickerDelegate for FileFinderDelegate {
         )
     }
 
+    fn update_preview(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(preview) = self.preview.clone() else {
+            return;
+        };
+        let project_path = match self.matches.get(self.selected_index) {
+            Some(Match::History { path, .. }) => Some(path.project.clone()),
+            Some(Match::Search(m)) => Some(ProjectPath {
+                worktree_id: WorktreeId::from_usize(m.0.worktree_id),
+                path: m.0.path.clone(),
+            }),
+            Some(Match::CreateNew(_)) | None => None,
+        };
+        let row = self
+            .latest_search_query
+            .as_ref()
+            .and_then(|query| query.path_position.row)
+            .map(|row| row.saturating_sub(1));
+
+        preview.update(cx, |preview, cx| match project_path {
+            Some(project_path) => {
+                preview.show_path(project_path, row.map(|row| row..row), window, cx);
+            }
+            None => preview.clear(cx),
+        });
+    }
+
+    fn render_preview(
+        &self,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.matches.len() == 0 {
+            return None;
+        }
+        Some(self.preview.clone()?.into_any_element())
+    }
+
     fn render_footer(
         &self,
         window: &mut Window,
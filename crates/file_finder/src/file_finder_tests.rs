@@ -7,6 +7,7 @@ use menu::{Confirm, SelectNext, SelectPrevious};
 use pretty_assertions::assert_eq;
 use project::{FS_WATCH_LATENCY, RemoveOptions};
 use serde_json::json;
+use settings::SettingsStore;
 use util::path;
 use workspace::{AppState, CloseActiveItem, OpenOptions, ToggleFileFinder, Workspace};
 
@@ -506,6 +507,55 @@ async fn test_row_column_numbers_query_inside_file(cx: &mut TestAppContext) {
         });
 }
 
+#[gpui::test]
+async fn test_preview_selected_match(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.picker_preview.get_or_insert_default().file_finder = Some(true);
+            });
+        });
+    });
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/src"),
+            json!({
+                "first.rs": "// First Rust file",
+                "second.rs": "// Second Rust file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/src").as_ref()], cx).await;
+    let (picker, workspace, cx) = build_find_picker(project, cx);
+
+    picker
+        .update_in(cx, |picker, window, cx| {
+            picker
+                .delegate
+                .update_matches("second".to_string(), window, cx)
+        })
+        .await;
+    cx.run_until_parked();
+
+    picker.update(cx, |picker, cx| {
+        let preview = picker.delegate.preview.as_ref().unwrap().read(cx);
+        let editor = preview
+            .active_editor()
+            .expect("selected match is previewed");
+        assert_eq!(editor.read(cx).text(cx), "// Second Rust file");
+    });
+    workspace.update(cx, |workspace, cx| {
+        assert!(
+            workspace.active_item(cx).is_none(),
+            "Previewing a match should not open it"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_row_column_numbers_query_outside_file(cx: &mut TestAppContext) {
     let app_state = init_test(cx);
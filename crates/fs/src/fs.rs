@@ -188,6 +188,8 @@ pub struct RemoveOptions {
 
 #[derive(Copy, Clone, Debug)]
 pub struct Metadata {
+    /// The device that the file is on, as inodes are only unique per device.
+    pub device: u64,
     pub inode: u64,
     pub mtime: MTime,
     pub is_symlink: bool,
@@ -680,10 +682,10 @@ impl Fs for RealFs {
         };
 
         #[cfg(unix)]
-        let inode = metadata.ino();
+        let (device, inode) = (metadata.dev(), metadata.ino());
 
         #[cfg(windows)]
-        let inode = file_id(path).await?;
+        let (device, inode) = file_id(path).await?;
 
         #[cfg(windows)]
         let is_fifo = false;
@@ -692,6 +694,7 @@ impl Fs for RealFs {
         let is_fifo = metadata.file_type().is_fifo();
 
         Ok(Some(Metadata {
+            device,
             inode,
             mtime: MTime(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)),
             len: metadata.len(),
@@ -2348,6 +2351,7 @@ impl Fs for FakeFs {
                 FakeFsEntry::File {
                     inode, mtime, len, ..
                 } => Metadata {
+                    device: 0,
                     inode: *inode,
                     mtime: *mtime,
                     len: *len,
@@ -2358,6 +2362,7 @@ impl Fs for FakeFs {
                 FakeFsEntry::Dir {
                     inode, mtime, len, ..
                 } => Metadata {
+                    device: 0,
                     inode: *inode,
                     mtime: *mtime,
                     len: *len,
@@ -2618,7 +2623,7 @@ fn read_recursive<'a>(
 // can we get file id not open the file twice?
 // https://github.com/rust-lang/rust/issues/63010
 #[cfg(target_os = "windows")]
-async fn file_id(path: impl AsRef<Path>) -> Result<u64> {
+async fn file_id(path: impl AsRef<Path>) -> Result<(u64, u64)> {
     use std::os::windows::io::AsRawHandle;
 
     use smol::fs::windows::OpenOptionsExt;
@@ -2641,7 +2646,10 @@ async fn file_id(path: impl AsRef<Path>) -> Result<u64> {
     smol::unblock(move || {
         unsafe { GetFileInformationByHandle(HANDLE(file.as_raw_handle() as _), &mut info)? };
 
-        Ok(((info.nFileIndexHigh as u64) << 32) | (info.nFileIndexLow as u64))
+        Ok((
+            info.dwVolumeSerialNumber as u64,
+            ((info.nFileIndexHigh as u64) << 32) | (info.nFileIndexLow as u64),
+        ))
     })
     .await
 }
@@ -0,0 +1,199 @@
+use editor::{Direction, Editor, MultiBufferRow, SelectionEffects, scroll::Autoscroll};
+use gpui::{App, Context, Entity, Window};
+use language::Point;
+use project::ProjectPath;
+use workspace::{GoToNextChangedHunk, GoToPreviousChangedHunk, Workspace};
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &GoToNextChangedHunk, window, cx| {
+        go_to_changed_hunk(workspace, Direction::Next, window, cx);
+    });
+    workspace.register_action(|workspace, _: &GoToPreviousChangedHunk, window, cx| {
+        go_to_changed_hunk(workspace, Direction::Prev, window, cx);
+    });
+}
+
+/// Moves to the next or previous hunk of the active editor, or, when there are
+/// no more hunks in that direction, to the first or last hunk of the adjacent
+/// changed file across all of the project's repositories.
+fn go_to_changed_hunk(
+    workspace: &mut Workspace,
+    direction: Direction,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let active_editor = workspace.active_item_as::<Editor>(cx);
+    if let Some(editor) = &active_editor
+        && let Some(row) = adjacent_hunk_row(editor, direction, cx)
+    {
+        editor.update(cx, |editor, cx| select_row(editor, row, window, cx));
+        return;
+    }
+
+    let changed_paths = changed_paths(workspace, cx);
+    let current_path = active_editor.and_then(|editor| editor.read(cx).project_path(cx));
+    let Some(target_path) = adjacent_path(&changed_paths, current_path.as_ref(), direction) else {
+        return;
+    };
+
+    let project = workspace.project().clone();
+    let open_task = workspace.open_path(target_path, None, true, window, cx);
+    cx.spawn_in(window, async move |_, cx| {
+        let Some(editor) = open_task.await?.downcast::<Editor>() else {
+            return anyhow::Ok(());
+        };
+        let buffer = editor.read_with(cx, |editor, cx| editor.buffer().read(cx).as_singleton())?;
+        if let Some(buffer) = buffer {
+            // Hunks are only known once the diff has loaded, which may not have
+            // happened yet for a freshly opened buffer.
+            let diff = project
+                .update(cx, |project, cx| project.open_uncommitted_diff(buffer, cx))?
+                .await?;
+            editor.update(cx, |editor, cx| {
+                editor
+                    .buffer()
+                    .update(cx, |multi_buffer, cx| multi_buffer.add_diff(diff, cx))
+            })?;
+        }
+
+        editor.update_in(cx, |editor, window, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let row = match direction {
+                Direction::Next => snapshot.diff_hunks().next(),
+                Direction::Prev => snapshot.diff_hunks().last(),
+            }
+            .map(|hunk| hunk.row_range.start);
+            if let Some(row) = row {
+                select_row(editor, row, window, cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Returns the row of the hunk after or before the newest cursor, without
+/// wrapping around the buffer.
+fn adjacent_hunk_row(
+    editor: &Entity<Editor>,
+    direction: Direction,
+    cx: &mut Context<Workspace>,
+) -> Option<MultiBufferRow> {
+    editor.update(cx, |editor, cx| {
+        let position = editor.selections.newest::<Point>(cx).head();
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        match direction {
+            Direction::Next => snapshot
+                .diff_hunks_in_range(position..snapshot.max_point())
+                .find(|hunk| hunk.row_range.start.0 > position.row)
+                .map(|hunk| hunk.row_range.start),
+            Direction::Prev => snapshot
+                .diff_hunks_in_range(Point::zero()..position)
+                .filter(|hunk| hunk.row_range.start.0 < position.row)
+                .last()
+                .map(|hunk| hunk.row_range.start),
+        }
+    })
+}
+
+/// Every changed file in the project that can still be opened, in a stable order.
+fn changed_paths(workspace: &Workspace, cx: &App) -> Vec<ProjectPath> {
+    let git_store = workspace.project().read(cx).git_store().read(cx);
+    let mut paths = git_store
+        .repositories()
+        .values()
+        .flat_map(|repo| {
+            let repo = repo.read(cx);
+            repo.cached_status()
+                .filter(|entry| !entry.status.is_deleted())
+                .filter_map(|entry| repo.repo_path_to_project_path(&entry.repo_path, cx))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// The changed path after or before `current`, wrapping around at either end.
+fn adjacent_path(
+    changed_paths: &[ProjectPath],
+    current: Option<&ProjectPath>,
+    direction: Direction,
+) -> Option<ProjectPath> {
+    let (first, last) = (changed_paths.first()?, changed_paths.last()?);
+    let Some(current) = current else {
+        return Some(match direction {
+            Direction::Next => first.clone(),
+            Direction::Prev => last.clone(),
+        });
+    };
+
+    let path = match direction {
+        Direction::Next => changed_paths
+            .iter()
+            .find(|path| *path > current)
+            .unwrap_or(first),
+        Direction::Prev => changed_paths
+            .iter()
+            .rfind(|path| *path < current)
+            .unwrap_or(last),
+    };
+    Some(path.clone())
+}
+
+fn select_row(
+    editor: &mut Editor,
+    row: MultiBufferRow,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let destination = Point::new(row.0, 0);
+    editor.unfold_ranges(&[destination..destination], false, false, cx);
+    editor.change_selections(
+        SelectionEffects::scroll(Autoscroll::center()),
+        window,
+        cx,
+        |s| s.select_ranges([destination..destination]),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use project::WorktreeId;
+    use std::path::Path;
+
+    fn project_path(path: &str) -> ProjectPath {
+        ProjectPath {
+            worktree_id: WorktreeId::from_usize(0),
+            path: Path::new(path).into(),
+        }
+    }
+
+    #[test]
+    fn test_adjacent_path() {
+        let paths = [project_path("a.rs"), project_path("c.rs")];
+
+        assert_eq!(adjacent_path(&[], None, Direction::Next), None);
+        assert_eq!(
+            adjacent_path(&paths, None, Direction::Prev),
+            Some(project_path("c.rs"))
+        );
+        assert_eq!(
+            adjacent_path(&paths, Some(&project_path("a.rs")), Direction::Next),
+            Some(project_path("c.rs"))
+        );
+        assert_eq!(
+            adjacent_path(&paths, Some(&project_path("b.rs")), Direction::Prev),
+            Some(project_path("a.rs"))
+        );
+        assert_eq!(
+            adjacent_path(&paths, Some(&project_path("c.rs")), Direction::Next),
+            Some(project_path("a.rs"))
+        );
+        assert_eq!(
+            adjacent_path(&paths, Some(&project_path("a.rs")), Direction::Prev),
+            Some(project_path("c.rs"))
+        );
+    }
+}
@@ -32,6 +32,7 @@ use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
 pub mod branch_picker;
+mod changed_hunks;
 mod commit_modal;
 pub mod commit_tooltip;
 mod commit_view;
@@ -71,6 +72,7 @@ pub fn init(cx: &mut App) {
         git_panel::register(workspace);
         repository_selector::register(workspace);
         branch_picker::register(workspace);
+        changed_hunks::register(workspace);
         stash_picker::register(workspace);
 
         let project = workspace.project().read(cx);
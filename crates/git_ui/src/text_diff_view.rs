@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
-use editor::{Editor, EditorEvent, MultiBuffer, ToPoint, actions::DiffClipboardWithSelectionData};
+use editor::{Editor, EditorEvent, MultiBuffer, actions::DiffClipboardWithSelectionData};
 use futures::{FutureExt, select_biased};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
@@ -48,19 +48,24 @@ impl TextDiffView {
 
         let selection_data = source_editor.update(cx, |editor, cx| {
             let multibuffer = editor.buffer().read(cx);
-            let source_buffer = multibuffer.as_singleton()?;
+            let is_singleton = multibuffer.is_singleton();
             let selections = editor.selections.all::<Point>(cx);
-            let buffer_snapshot = source_buffer.read(cx);
             let first_selection = selections.first()?;
-            let max_point = buffer_snapshot.max_point();
+            // In multi-buffers, the clipboard is diffed against the buffer in which the
+            // selection starts.
+            let (source_buffer, start, _) =
+                multibuffer.point_to_buffer_point(first_selection.start, cx)?;
+            let max_point = source_buffer.read(cx).max_point();
 
             if first_selection.is_empty() {
                 let full_range = Point::new(0, 0)..max_point;
-                return Some((source_buffer, full_range));
+                return Some((source_buffer, full_range, is_singleton));
             }
 
-            let start = first_selection.start;
-            let end = first_selection.end;
+            let end = match multibuffer.point_to_buffer_point(first_selection.end, cx) {
+                Some((end_buffer, end, _)) if end_buffer == source_buffer => end,
+                _ => max_point,
+            };
             let expanded_start = Point::new(start.row, 0);
 
             let expanded_end = if end.column > 0 {
@@ -69,21 +74,25 @@ impl TextDiffView {
             } else {
                 end
             };
-            Some((source_buffer, expanded_start..expanded_end))
+            Some((source_buffer, expanded_start..expanded_end, is_singleton))
         });
 
-        let Some((source_buffer, expanded_selection_range)) = selection_data else {
+        let Some((source_buffer, expanded_selection_range, is_singleton)) = selection_data else {
             log::warn!("There should always be at least one selection in Zed. This is a bug.");
             return None;
         };
 
-        source_editor.update(cx, |source_editor, cx| {
-            source_editor.change_selections(Default::default(), window, cx, |s| {
-                s.select_ranges(vec![
-                    expanded_selection_range.start..expanded_selection_range.end,
-                ]);
-            })
-        });
+        // The expanded range is in the source buffer's coordinates, which only match the
+        // editor's when it isn't showing multiple excerpts.
+        if is_singleton {
+            source_editor.update(cx, |source_editor, cx| {
+                source_editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select_ranges(vec![
+                        expanded_selection_range.start..expanded_selection_range.end,
+                    ]);
+                })
+            });
+        }
 
         let source_buffer_snapshot = source_buffer.read(cx).snapshot();
         let mut clipboard_text = diff_data.clipboard_text.clone();
@@ -147,7 +156,7 @@ impl TextDiffView {
 
             multibuffer.push_excerpts(
                 source_buffer.clone(),
-                [editor::ExcerptRange::new(source_range)],
+                [editor::ExcerptRange::new(source_range.clone())],
                 cx,
             );
 
@@ -178,28 +187,25 @@ impl TextDiffView {
         })
         .detach();
 
-        let editor = source_editor.read(cx);
-        let title = editor.buffer().read(cx).title(cx).to_string();
-        let selection_location_text = selection_location_text(editor, cx);
-        let selection_location_title = selection_location_text
-            .as_ref()
-            .map(|text| format!("{} @ {}", title, text))
-            .unwrap_or(title);
-
-        let path = editor
-            .buffer()
-            .read(cx)
-            .as_singleton()
-            .and_then(|b| {
-                b.read(cx)
-                    .file()
-                    .map(|f| f.full_path(cx).compact().to_string_lossy().to_string())
-            })
+        let source_file = source_buffer.read(cx).file();
+        let title = match source_file {
+            Some(file) => file.file_name(cx).to_string_lossy().to_string(),
+            None => {
+                let multibuffer = source_editor.read(cx).buffer().read(cx);
+                if multibuffer.is_singleton() {
+                    multibuffer.title(cx).to_string()
+                } else {
+                    "untitled".to_string()
+                }
+            }
+        };
+        let path = source_file
+            .map(|file| file.full_path(cx).compact().to_string_lossy().to_string())
             .unwrap_or("untitled".into());
 
-        let selection_location_path = selection_location_text
-            .map(|text| format!("{} @ {}", path, text))
-            .unwrap_or(path);
+        let location_text = range_location_text(&source_range);
+        let selection_location_title = format!("{title} @ {location_text}");
+        let selection_location_path = format!("{path} @ {location_text}");
 
         Self {
             diff_editor,
@@ -413,32 +419,20 @@ impl Item for TextDiffView {
     }
 }
 
-pub fn selection_location_text(editor: &Editor, cx: &App) -> Option<String> {
-    let buffer = editor.buffer().read(cx);
-    let buffer_snapshot = buffer.snapshot(cx);
-    let first_selection = editor.selections.disjoint_anchors().first()?;
-
-    let selection_start = first_selection.start.to_point(&buffer_snapshot);
-    let selection_end = first_selection.end.to_point(&buffer_snapshot);
-
-    let start_row = selection_start.row;
-    let start_column = selection_start.column;
-    let end_row = selection_end.row;
-    let end_column = selection_end.column;
-
-    let range_text = if start_row == end_row {
-        format!("L{}:{}-{}", start_row + 1, start_column + 1, end_column + 1)
+fn range_location_text(range: &Range<Point>) -> String {
+    let start = range.start;
+    let end = range.end;
+    if start.row == end.row {
+        format!("L{}:{}-{}", start.row + 1, start.column + 1, end.column + 1)
     } else {
         format!(
             "L{}:{}-L{}:{}",
-            start_row + 1,
-            start_column + 1,
-            end_row + 1,
-            end_column + 1
+            start.row + 1,
+            start.column + 1,
+            end.row + 1,
+            end.column + 1
         )
-    };
-
-    Some(range_text)
+    }
 }
 
 impl Render for TextDiffView {
@@ -657,6 +651,96 @@ mod tests {
         .await;
     }
 
+    #[gpui::test]
+    async fn test_diffing_clipboard_against_buffer_in_multibuffer(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            json!({
+                "a.txt": "a\nb\n",
+                "b.txt": "c\nd\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let mut buffers = Vec::new();
+        for file_path in [path!("/test/a.txt"), path!("/test/b.txt")] {
+            buffers.push(
+                project
+                    .update(cx, |project, cx| project.open_local_buffer(file_path, cx))
+                    .await
+                    .unwrap(),
+            );
+        }
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::new(language::Capability::ReadWrite);
+            for buffer in buffers {
+                multibuffer.push_excerpts(
+                    buffer,
+                    [editor::ExcerptRange::new(
+                        Point::new(0, 0)..Point::new(2, 0),
+                    )],
+                    cx,
+                );
+            }
+            multibuffer
+        });
+        let editor = cx.new_window_entity(|window, cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            // The end of the multibuffer is in the excerpt of `b.txt`.
+            let end = multibuffer.read(cx).len(cx);
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.select_ranges([end..end])
+            });
+            editor
+        });
+
+        let diff_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                TextDiffView::open(
+                    &DiffClipboardWithSelectionData {
+                        clipboard_text: "c\nx\n".to_string(),
+                        editor,
+                    },
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+
+        assert_state_with_diff(
+            &diff_view.read_with(cx, |diff_view, _| diff_view.diff_editor.clone()),
+            cx,
+            &unindent(
+                "
+                  ˇc
+                - x
+                + d
+                ",
+            ),
+        );
+        diff_view.read_with(cx, |diff_view, cx| {
+            assert_eq!(
+                diff_view.tab_content_text(0, cx),
+                "Clipboard ↔ b.txt @ L1:1-L3:1"
+            );
+            assert_eq!(
+                diff_view.tab_tooltip_text(cx).unwrap(),
+                format!("Clipboard ↔ {} @ L1:1-L3:1", path!("test/b.txt"))
+            );
+        });
+    }
+
     async fn base_test(
         project_root: &str,
         file_path: &str,
@@ -4,8 +4,8 @@ use settings::Settings;
 use std::{fmt::Write, num::NonZeroU32, time::Duration};
 use text::{Point, Selection};
 use ui::{
-    Button, ButtonCommon, Clickable, Context, FluentBuilder, IntoElement, LabelSize, ParentElement,
-    Render, Tooltip, Window, div,
+    Button, Color, Context, ContextMenu, FluentBuilder, IntoElement, Label, LabelCommon, LabelSize,
+    ParentElement, PopoverMenu, Render, Styled, Tooltip, Window, h_flex,
 };
 use util::paths::FILE_ROW_COLUMN_DELIMITER;
 use workspace::{StatusItemView, Workspace, item::ItemHandle};
@@ -14,12 +14,28 @@ use workspace::{StatusItemView, Workspace, item::ItemHandle};
 pub(crate) struct SelectionStats {
     pub lines: usize,
     pub characters: usize,
+    pub words: usize,
     pub selections: usize,
 }
 
+/// The extent and size of a single selection, listed in the cursor position menu.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct SelectionDetails {
+    start: UserCaretPosition,
+    end: UserCaretPosition,
+    characters: usize,
+    words: usize,
+}
+
+/// How many selections are listed in the cursor position menu.
+const MAX_LISTED_SELECTIONS: usize = 20;
+
 pub struct CursorPosition {
     position: Option<UserCaretPosition>,
     selected_count: SelectionStats,
+    selection_details: Vec<SelectionDetails>,
+    /// The number of words in the buffer, which is only counted in reading mode.
+    word_count: Option<usize>,
     context: Option<FocusHandle>,
     workspace: WeakEntity<Workspace>,
     update_position: Task<()>,
@@ -41,7 +57,10 @@ impl UserCaretPosition {
         selection: &Selection<Point>,
         snapshot: &MultiBufferSnapshot,
     ) -> Self {
-        let selection_end = selection.head();
+        Self::at_point(selection.head(), snapshot)
+    }
+
+    fn at_point(selection_end: Point, snapshot: &MultiBufferSnapshot) -> Self {
         let (line, character) = if let Some((buffer_snapshot, point, _)) =
             snapshot.point_to_buffer_point(selection_end)
         {
@@ -73,6 +92,8 @@ impl CursorPosition {
             position: None,
             context: None,
             selected_count: Default::default(),
+            selection_details: Vec::new(),
+            word_count: None,
             workspace: workspace.weak_handle(),
             update_position: Task::ready(()),
             _observe_active_editor: None,
@@ -102,11 +123,13 @@ impl CursorPosition {
                     cursor_position.update(cx, |cursor_position, cx| {
                         cursor_position.selected_count = SelectionStats::default();
                         cursor_position.selected_count.selections = editor.selections.count();
+                        cursor_position.selection_details.clear();
                         match editor.mode() {
                             editor::EditorMode::AutoHeight { .. }
                             | editor::EditorMode::SingleLine
                             | editor::EditorMode::Minimap { .. } => {
                                 cursor_position.position = None;
+                                cursor_position.word_count = None;
                                 cursor_position.context = None;
                             }
                             editor::EditorMode::Full { .. } => {
@@ -118,8 +141,32 @@ impl CursorPosition {
                                             .text_summary_for_range::<text::TextSummary, _>(
                                                 selection.start..selection.end,
                                             );
+                                        let words = count_words(
+                                            snapshot
+                                                .text_for_range(selection.start..selection.end)
+                                                .flat_map(str::chars),
+                                        );
                                         cursor_position.selected_count.characters +=
                                             selection_summary.chars;
+                                        cursor_position.selected_count.words += words;
+                                        if cursor_position.selection_details.len()
+                                            < MAX_LISTED_SELECTIONS
+                                        {
+                                            cursor_position.selection_details.push(
+                                                SelectionDetails {
+                                                    start: UserCaretPosition::at_point(
+                                                        selection.start,
+                                                        &snapshot,
+                                                    ),
+                                                    end: UserCaretPosition::at_point(
+                                                        selection.end,
+                                                        &snapshot,
+                                                    ),
+                                                    characters: selection_summary.chars,
+                                                    words,
+                                                },
+                                            );
+                                        }
                                         if selection.end != selection.start {
                                             cursor_position.selected_count.lines +=
                                                 (selection.end.row - selection.start.row) as usize;
@@ -136,6 +183,9 @@ impl CursorPosition {
                                 }
                                 cursor_position.position = last_selection
                                     .map(|s| UserCaretPosition::at_selection_end(&s, &snapshot));
+                                cursor_position.word_count = editor
+                                    .reading_mode(cx)
+                                    .map(|_| count_words(snapshot.chars_at(0)));
                                 cursor_position.context = Some(editor.focus_handle(cx));
                             }
                         }
@@ -163,12 +213,14 @@ impl CursorPosition {
         let SelectionStats {
             lines,
             characters,
+            words,
             selections,
         } = self.selected_count;
         let format = LineIndicatorFormat::get(None, cx);
         let is_short_format = format == &LineIndicatorFormat::Short;
         let lines = (lines > 1).then_some((lines, "line"));
         let selections = (selections > 1).then_some((selections, "selection"));
+        let words = (words > 0).then_some((words, "word"));
         let characters = (characters > 0).then_some((characters, "character"));
         if (None, None, None) == (characters, selections, lines) {
             // Nothing to display.
@@ -176,7 +228,7 @@ impl CursorPosition {
         }
         write!(text, " (").unwrap();
         let mut wrote_once = false;
-        for (count, name) in [selections, lines, characters].into_iter().flatten() {
+        for (count, name) in [selections, lines, words, characters].into_iter().flatten() {
             if wrote_once {
                 write!(text, ", ").unwrap();
             }
@@ -203,59 +255,174 @@ impl CursorPosition {
     }
 }
 
+impl std::fmt::Display for SelectionDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{FILE_ROW_COLUMN_DELIMITER}{}",
+            self.start.line, self.start.character
+        )?;
+        if self.start != self.end {
+            write!(
+                f,
+                "–{}{FILE_ROW_COLUMN_DELIMITER}{}",
+                self.end.line, self.end.character
+            )?;
+        }
+        let characters_suffix = if self.characters == 1 { "" } else { "s" };
+        let words_suffix = if self.words == 1 { "" } else { "s" };
+        write!(
+            f,
+            " ({} character{characters_suffix}, {} word{words_suffix})",
+            self.characters, self.words
+        )
+    }
+}
+
+/// Opens the go to line modal for the active editor, starting with the given query.
+fn open_go_to_line(
+    workspace: &WeakEntity<Workspace>,
+    query: Option<&str>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(workspace) = workspace.upgrade() else {
+        return;
+    };
+    workspace.update(cx, |workspace, cx| {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx) else {
+            return;
+        };
+        workspace.toggle_modal(window, cx, |window, cx| {
+            let mut go_to_line = crate::GoToLine::new(editor, buffer, window, cx);
+            if let Some(query) = query {
+                go_to_line.set_query(query, window, cx);
+            }
+            go_to_line
+        })
+    });
+}
+
+fn count_words(chars: impl Iterator<Item = char>) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in chars {
+        let is_word_char = !c.is_whitespace();
+        if is_word_char && !in_word {
+            count += 1;
+        }
+        in_word = is_word_char;
+    }
+    count
+}
+
 impl Render for CursorPosition {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         if !EditorSettings::get_global(cx)
             .status_bar
             .cursor_position_button
         {
-            return div();
+            return h_flex();
         }
 
-        div().when_some(self.position, |el, position| {
-            let mut text = format!(
-                "{}{FILE_ROW_COLUMN_DELIMITER}{}",
-                position.line, position.character,
-            );
-            self.write_position(&mut text, cx);
-
-            let context = self.context.clone();
-
-            el.child(
-                Button::new("go-to-line-column", text)
-                    .label_size(LabelSize::Small)
-                    .on_click(cx.listener(|this, _, window, cx| {
-                        if let Some(workspace) = this.workspace.upgrade() {
-                            workspace.update(cx, |workspace, cx| {
-                                if let Some(editor) = workspace
-                                    .active_item(cx)
-                                    .and_then(|item| item.act_as::<Editor>(cx))
-                                    && let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx)
+        h_flex()
+            .gap_2()
+            .when_some(self.word_count, |el, word_count| {
+                let suffix = if word_count == 1 { "" } else { "s" };
+                el.child(
+                    Label::new(format!("{word_count} word{suffix}"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when_some(self.position, |el, position| {
+                let mut text = format!(
+                    "{}{FILE_ROW_COLUMN_DELIMITER}{}",
+                    position.line, position.character,
+                );
+                self.write_position(&mut text, cx);
+
+                let context = self.context.clone();
+                let workspace = self.workspace.clone();
+                let selection_details = self.selection_details.clone();
+                let unlisted_selections = self
+                    .selected_count
+                    .selections
+                    .saturating_sub(selection_details.len());
+
+                el.child(
+                    PopoverMenu::new("cursor-position-menu")
+                        .anchor(gpui::Corner::BottomRight)
+                        .trigger_with_tooltip(
+                            Button::new("go-to-line-column", text).label_size(LabelSize::Small),
+                            move |window, cx| match context.as_ref() {
+                                Some(context) => Tooltip::for_action_in(
+                                    "Go to Line/Column",
+                                    &editor::actions::ToggleGoToLine,
+                                    context,
+                                    window,
+                                    cx,
+                                ),
+                                None => Tooltip::for_action(
+                                    "Go to Line/Column",
+                                    &editor::actions::ToggleGoToLine,
+                                    window,
+                                    cx,
+                                ),
+                            },
+                        )
+                        .menu(move |window, cx| {
+                            let workspace = workspace.clone();
+                            let selection_details = selection_details.clone();
+                            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                menu = menu
+                                    .entry(
+                                        "Go to Line/Column",
+                                        Some(Box::new(editor::actions::ToggleGoToLine)),
+                                        {
+                                            let workspace = workspace.clone();
+                                            move |window, cx| {
+                                                open_go_to_line(&workspace, None, window, cx)
+                                            }
+                                        },
+                                    )
+                                    .entry("Go to Byte Offset", None, {
+                                        let workspace = workspace.clone();
+                                        move |window, cx| {
+                                            open_go_to_line(
+                                                &workspace,
+                                                Some(crate::BYTE_OFFSET_PREFIX),
+                                                window,
+                                                cx,
+                                            )
+                                        }
+                                    });
+
+                                if selection_details.len() > 1
+                                    || selection_details
+                                        .iter()
+                                        .any(|details| details.start != details.end)
                                 {
-                                    workspace.toggle_modal(window, cx, |window, cx| {
-                                        crate::GoToLine::new(editor, buffer, window, cx)
-                                    })
+                                    menu = menu.separator().header("Selections");
+                                    for details in &selection_details {
+                                        menu = menu.label(details.to_string());
+                                    }
+                                    if unlisted_selections > 0 {
+                                        menu =
+                                            menu.label(format!("and {unlisted_selections} more"));
+                                    }
                                 }
-                            });
-                        }
-                    }))
-                    .tooltip(move |window, cx| match context.as_ref() {
-                        Some(context) => Tooltip::for_action_in(
-                            "Go to Line/Column",
-                            &editor::actions::ToggleGoToLine,
-                            context,
-                            window,
-                            cx,
-                        ),
-                        None => Tooltip::for_action(
-                            "Go to Line/Column",
-                            &editor::actions::ToggleGoToLine,
-                            window,
-                            cx,
-                        ),
-                    }),
-            )
-        })
+                                menu
+                            }))
+                        }),
+                )
+            })
     }
 }
 
@@ -17,6 +17,9 @@ use ui::prelude::*;
 use util::paths::FILE_ROW_COLUMN_DELIMITER;
 use workspace::ModalView;
 
+/// Queries starting with this go to a byte offset rather than a line and column.
+pub const BYTE_OFFSET_PREFIX: &str = "#";
+
 pub fn init(cx: &mut App) {
     LineIndicatorFormat::register(cx);
     cx.observe_new(GoToLine::register).detach();
@@ -137,6 +140,13 @@ impl GoToLine {
         }
     }
 
+    pub fn set_query(&mut self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.line_editor.update(cx, |editor, cx| {
+            editor.set_text(query, window, cx);
+            editor.move_to_end(&editor::actions::MoveToEnd, window, cx);
+        });
+    }
+
     fn release(&mut self, window: &mut Window, cx: &mut App) {
         let scroll_position = self.prev_scroll_position.take();
         self.active_editor.update(cx, |editor, cx| {
@@ -200,6 +210,12 @@ impl GoToLine {
         snapshot: &MultiBufferSnapshot,
         cx: &Context<Editor>,
     ) -> Option<Anchor> {
+        if let Some(offset) = self.byte_offset_from_query(cx) {
+            return Some(
+                snapshot
+                    .anchor_before(snapshot.clip_offset(offset.min(snapshot.len()), Bias::Left)),
+            );
+        }
         let (query_row, query_char) = self.line_and_char_from_query(cx)?;
         let row = query_row.saturating_sub(1);
         let character = query_char.unwrap_or(0).saturating_sub(1);
@@ -231,6 +247,16 @@ impl GoToLine {
         Some(snapshot.anchor_before(snapshot.clip_offset(end_offset, Bias::Left)))
     }
 
+    fn byte_offset_from_query(&self, cx: &App) -> Option<usize> {
+        let input = self.line_editor.read(cx).text(cx);
+        input
+            .trim()
+            .strip_prefix(BYTE_OFFSET_PREFIX)?
+            .trim()
+            .parse()
+            .ok()
+    }
+
     fn line_and_char_from_query(&self, cx: &App) -> Option<(u32, Option<u32>)> {
         let input = self.line_editor.read(cx).text(cx);
         let mut components = input
@@ -269,12 +295,16 @@ impl GoToLine {
 
 impl Render for GoToLine {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let help_text = match self.line_and_char_from_query(cx) {
-            Some((line, Some(character))) => {
-                format!("Go to line {line}, character {character}").into()
+        let help_text = if let Some(offset) = self.byte_offset_from_query(cx) {
+            format!("Go to byte offset {offset}").into()
+        } else {
+            match self.line_and_char_from_query(cx) {
+                Some((line, Some(character))) => {
+                    format!("Go to line {line}, character {character}").into()
+                }
+                Some((line, None)) => format!("Go to line {line}").into(),
+                None => self.current_text.clone(),
             }
-            Some((line, None)) => format!("Go to line {line}").into(),
-            None => self.current_text.clone(),
         };
 
         v_flex()
@@ -424,6 +454,39 @@ mod tests {
         assert_single_caret_at_row(&editor, expected_highlighted_row, cx);
     }
 
+    #[gpui::test]
+    async fn test_go_to_byte_offset(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "abc\ndef\nghi\n" }))
+            .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, "a.rs"), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        let go_to_line_view = open_go_to_line_view(&workspace, cx);
+        cx.simulate_input("#9");
+        assert_eq!(highlighted_display_rows(&editor, cx), vec![2]);
+        cx.dispatch_action(menu::Confirm);
+        drop(go_to_line_view);
+        editor.update(cx, |_, _| {});
+        assert_single_caret_at_row(&editor, 2, cx);
+    }
+
     #[gpui::test]
     async fn test_unicode_characters_selection(cx: &mut TestAppContext) {
         init_test(cx);
@@ -473,6 +536,7 @@ mod tests {
                 &SelectionStats {
                     lines: 0,
                     characters: 0,
+                    words: 0,
                     selections: 1,
                 },
                 workspace
@@ -494,6 +558,7 @@ mod tests {
                 &SelectionStats {
                     lines: 1,
                     characters: 3,
+                    words: 1,
                     selections: 1,
                 },
                 workspace
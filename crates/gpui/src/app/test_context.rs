@@ -3,7 +3,7 @@ use crate::{
     BackgroundExecutor, BorrowAppContext, Bounds, Capslock, ClipboardItem, DrawPhase, Drawable,
     Element, Empty, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Modifiers,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels,
-    Platform, Point, Render, Result, Size, Task, TestDispatcher, TestPlatform,
+    Platform, Point, Render, RequestFrameOptions, Result, Size, Task, TestDispatcher, TestPlatform,
     TestScreenCaptureSource, TestWindow, TextSystem, VisualContext, Window, WindowBounds,
     WindowHandle, WindowOptions,
 };
@@ -844,6 +844,30 @@ impl VisualTestContext {
         self.background_executor.run_until_parked();
     }
 
+    /// Simulates the platform requesting a frame, which runs the callbacks scheduled
+    /// with [`Window::on_next_frame`].
+    pub fn simulate_frame(&mut self) {
+        let callback = self
+            .test_window(self.window)
+            .0
+            .lock()
+            .request_frame_callback
+            .take();
+        if let Some(mut callback) = callback {
+            callback(RequestFrameOptions::default());
+            self.test_window(self.window)
+                .0
+                .lock()
+                .request_frame_callback = Some(callback);
+        }
+        self.background_executor.run_until_parked();
+    }
+
+    /// Returns the bounds of the text caret last reported to accessibility tools.
+    pub fn accessibility_caret(&self) -> Option<Bounds<Pixels>> {
+        self.test_window(self.window).0.lock().accessibility_caret
+    }
+
     /// Simulates the user blurring the window.
     pub fn deactivate_window(&mut self) {
         if Some(self.window) == self.test_platform.active_window() {
@@ -550,6 +550,12 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
 
     fn update_ime_position(&self, _bounds: Bounds<Pixels>);
 
+    /// Reports the bounds of the text caret, so that accessibility tools such as
+    /// screen magnifiers can follow it as it moves. Only Windows implements this:
+    /// on macOS, Zoom follows the caret through the focused view's NSAccessibility
+    /// text element, which gpui doesn't provide yet.
+    fn update_accessibility_caret(&self, _bounds: Bounds<Pixels>) {}
+
     #[cfg(any(test, feature = "test-support"))]
     fn as_test(&mut self) -> Option<&mut TestWindow> {
         None
@@ -29,6 +29,8 @@ pub(crate) struct TestWindowState {
     moved_callback: Option<Box<dyn FnMut()>>,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
+    pub(crate) request_frame_callback: Option<Box<dyn FnMut(RequestFrameOptions)>>,
+    pub(crate) accessibility_caret: Option<Bounds<Pixels>>,
 }
 
 #[derive(Clone)]
@@ -74,6 +76,8 @@ impl TestWindow {
             moved_callback: None,
             input_handler: None,
             is_fullscreen: false,
+            request_frame_callback: None,
+            accessibility_caret: None,
         })))
     }
 
@@ -232,7 +236,9 @@ impl PlatformWindow for TestWindow {
         self.0.lock().is_fullscreen
     }
 
-    fn on_request_frame(&self, _callback: Box<dyn FnMut(RequestFrameOptions)>) {}
+    fn on_request_frame(&self, callback: Box<dyn FnMut(RequestFrameOptions)>) {
+        self.0.lock().request_frame_callback = Some(callback)
+    }
 
     fn on_input(&self, callback: Box<dyn FnMut(crate::PlatformInput) -> DispatchEventResult>) {
         self.0.lock().input_callback = Some(callback)
@@ -291,6 +297,10 @@ impl PlatformWindow for TestWindow {
 
     fn update_ime_position(&self, _bounds: Bounds<Pixels>) {}
 
+    fn update_accessibility_caret(&self, bounds: Bounds<Pixels>) {
+        self.0.lock().accessibility_caret = Some(bounds);
+    }
+
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         None
     }
@@ -734,6 +734,11 @@ impl WindowsWindowInner {
 
     fn handle_activate_msg(self: &Rc<Self>, wparam: WPARAM) -> Option<isize> {
         let activated = wparam.loword() > 0;
+        if !activated {
+            // The caret kept for accessibility tools belongs to the focused window only.
+            self.state.borrow_mut().accessibility_caret_size = None;
+            unsafe { DestroyCaret().ok() };
+        }
         let this = self.clone();
         self.executor
             .spawn(async move {
@@ -56,6 +56,8 @@ pub struct WindowsWindowState {
     pub nc_button_pressed: Option<u32>,
 
     pub display: WindowsDisplay,
+    /// The size of the hidden caret kept for accessibility tools, if it was created.
+    pub accessibility_caret_size: Option<(i32, i32)>,
     fullscreen: Option<StyleAndBounds>,
     initial_placement: Option<WindowOpenStatus>,
     hwnd: HWND,
@@ -117,6 +119,7 @@ impl WindowsWindowState {
         let click_state = ClickState::new();
         let system_settings = WindowsSystemSettings::new(display);
         let nc_button_pressed = None;
+        let accessibility_caret_size = None;
         let fullscreen = None;
         let initial_placement = None;
 
@@ -142,6 +145,7 @@ impl WindowsWindowState {
             current_cursor,
             nc_button_pressed,
             display,
+            accessibility_caret_size,
             fullscreen,
             initial_placement,
             hwnd,
@@ -850,6 +854,30 @@ impl PlatformWindow for WindowsWindow {
     fn update_ime_position(&self, _bounds: Bounds<Pixels>) {
         // There is no such thing on Windows.
     }
+
+    fn update_accessibility_caret(&self, bounds: Bounds<Pixels>) {
+        // Screen magnifiers and other accessibility tools follow the system caret, so a hidden
+        // one is kept at the position of the text cursor. It's only created again when its size
+        // changes, as creating a caret replaces the previous one.
+        let mut state = self.0.state.borrow_mut();
+        let bounds = bounds.scale(state.scale_factor);
+        let size = (
+            (bounds.size.width.0 as i32).max(1),
+            (bounds.size.height.0 as i32).max(1),
+        );
+        unsafe {
+            if state.accessibility_caret_size != Some(size) {
+                if CreateCaret(self.0.hwnd, None, size.0, size.1)
+                    .log_err()
+                    .is_none()
+                {
+                    return;
+                }
+                state.accessibility_caret_size = Some(size);
+            }
+            SetCaretPos(bounds.origin.x.0 as i32, bounds.origin.y.0 as i32).log_err();
+        }
+    }
 }
 
 #[implement(IDropTarget)]
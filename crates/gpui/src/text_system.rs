@@ -13,8 +13,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Bounds, DevicePixels, Hsla, Pixels, PlatformTextSystem, Point, Result, SharedString, Size,
-    StrikethroughStyle, UnderlineStyle, px,
+    Bounds, DevicePixels, Hsla, IsZero, Pixels, PlatformTextSystem, Point, Result, SharedString,
+    Size, StrikethroughStyle, UnderlineStyle, px,
 };
 use anyhow::{Context as _, anyhow};
 use collections::FxHashMap;
@@ -316,6 +316,40 @@ impl TextSystem {
         self.platform_text_system
             .rasterize_glyph(params, raster_bounds)
     }
+
+    /// Rasterizes a shaped glyph outside of a window's scene, such as to render text into an
+    /// image. Returns the bounds of the glyph's pixels relative to its origin on the baseline,
+    /// along with the pixels themselves, which are an alpha mask for regular glyphs and BGRA
+    /// for emojis. Glyphs without any pixels, such as spaces, return `None`.
+    pub fn rasterize_glyph_image(
+        &self,
+        font_id: FontId,
+        glyph_id: GlyphId,
+        font_size: Pixels,
+        scale_factor: f32,
+        is_emoji: bool,
+    ) -> Result<Option<(Bounds<DevicePixels>, Vec<u8>)>> {
+        let params = RenderGlyphParams {
+            font_id,
+            glyph_id,
+            font_size,
+            subpixel_variant: Point::default(),
+            scale_factor,
+            is_emoji,
+        };
+        let raster_bounds = self.raster_bounds(&params)?;
+        if raster_bounds.is_zero() {
+            return Ok(None);
+        }
+        let (size, bytes) = self.rasterize_glyph(&params)?;
+        Ok(Some((
+            Bounds {
+                origin: raster_bounds.origin,
+                size,
+            },
+            bytes,
+        )))
+    }
 }
 
 /// The GPUI text layout subsystem.
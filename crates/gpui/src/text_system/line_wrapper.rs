@@ -34,6 +34,17 @@ impl LineWrapper {
         &'a mut self,
         fragments: &'a [LineFragment],
         wrap_width: Pixels,
+    ) -> impl Iterator<Item = Boundary> + 'a {
+        self.wrap_line_with_hanging_indent(fragments, wrap_width, 0)
+    }
+
+    /// Wrap a line of text to the given width with this wrapper's font and font size,
+    /// indenting the lines after the first by `hanging_indent` more than the first one.
+    pub fn wrap_line_with_hanging_indent<'a>(
+        &'a mut self,
+        fragments: &'a [LineFragment],
+        wrap_width: Pixels,
+        hanging_indent: u32,
     ) -> impl Iterator<Item = Boundary> + 'a {
         let mut width = px(0.);
         let mut first_non_whitespace_ix = None;
@@ -100,9 +111,8 @@ impl LineWrapper {
                 if width > wrap_width && ix > last_wrap_ix {
                     if let (None, Some(first_non_whitespace_ix)) = (indent, first_non_whitespace_ix)
                     {
-                        indent = Some(
-                            Self::MAX_INDENT.min((first_non_whitespace_ix - last_wrap_ix) as u32),
-                        );
+                        let line_indent = (first_non_whitespace_ix - last_wrap_ix) as u32;
+                        indent = Some(Self::MAX_INDENT.min(line_indent + hanging_indent));
                     }
 
                     if last_candidate_ix > 0 {
@@ -489,6 +499,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wrap_line_with_hanging_indent() {
+        let mut wrapper = build_wrapper();
+
+        assert_eq!(
+            wrapper
+                .wrap_line_with_hanging_indent(&[LineFragment::text("  aa bb cc dd")], px(72.), 2)
+                .collect::<Vec<_>>(),
+            &[
+                Boundary::new(5, 4),
+                Boundary::new(8, 4),
+                Boundary::new(11, 4)
+            ],
+        );
+        assert_eq!(
+            wrapper
+                .wrap_line_with_hanging_indent(&[LineFragment::text("aaaaaaaaaa")], px(72.), 2)
+                .collect::<Vec<_>>(),
+            &[Boundary::new(7, 2)],
+        );
+    }
+
     #[test]
     fn test_truncate_line() {
         let mut wrapper = build_wrapper();
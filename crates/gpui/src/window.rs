@@ -4091,12 +4091,14 @@ impl Window {
         self.platform_window.toggle_fullscreen();
     }
 
-    /// Updates the IME panel position suggestions for languages like japanese, chinese.
+    /// Updates the IME panel position suggestions for languages like japanese, chinese,
+    /// and the caret position reported to accessibility tools.
     pub fn invalidate_character_coordinates(&self) {
         self.on_next_frame(|window, cx| {
             if let Some(mut input_handler) = window.platform_window.take_input_handler() {
                 if let Some(bounds) = input_handler.selected_bounds(window, cx) {
                     window.platform_window.update_ime_position(bounds);
+                    window.platform_window.update_accessibility_caret(bounds);
                 }
                 window.platform_window.set_input_handler(input_handler);
             }
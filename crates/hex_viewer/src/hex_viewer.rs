@@ -0,0 +1,575 @@
+//! A read-only view of a file's raw bytes, shown as rows of offsets, hex bytes and their
+//! ASCII rendering.
+//!
+//! Files with well-known binary extensions open here instead of in an editor, and any
+//! other file can be opened here with [`OpenAsHex`]. The view never writes to disk.
+
+use std::{ffi::OsStr, ops::Range, path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle,
+    ScrollStrategy, StyledText, Subscription, Task, UniformListScrollHandle, Window, actions,
+    uniform_list,
+};
+use language::File as _;
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings;
+use ui::prelude::*;
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings, Pane, Workspace,
+    item::{Item, ProjectItem, TabContentParams},
+};
+use worktree::LoadedBinaryFile;
+
+actions!(
+    hex_viewer,
+    [
+        /// Opens the active file in the hex viewer.
+        OpenAsHex
+    ]
+);
+
+const BYTES_PER_ROW: usize = 16;
+
+/// Extensions of files that are opened in the hex viewer rather than in an editor.
+const BINARY_EXTENSIONS: &[&str] = &[
+    "a", "bin", "class", "dat", "dll", "dylib", "exe", "jar", "lib", "o", "obj", "pdb", "pyc",
+    "so", "wasm",
+];
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexView>(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_as_hex);
+    })
+    .detach();
+}
+
+fn open_as_hex(
+    workspace: &mut Workspace,
+    _: &OpenAsHex,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(project_path) = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+    else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let open_file = HexFile::open(&project, &project_path, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let hex_file = open_file.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let hex_view =
+                cx.new(|cx| HexView::new(hex_file, workspace.project().clone(), window, cx));
+            workspace.add_item_to_active_pane(Box::new(hex_view), None, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn is_binary_file(path: &ProjectPath) -> bool {
+    path.path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| {
+            BINARY_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+}
+
+/// The contents of a file, as loaded for the hex viewer.
+pub struct HexFile {
+    file: Arc<worktree::File>,
+    content: Arc<[u8]>,
+}
+
+impl HexFile {
+    pub fn open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let Some(worktree) = project.read(cx).worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("no worktree found for {:?}", path.path)));
+        };
+        let load_file =
+            worktree.update(cx, |worktree, cx| worktree.load_binary_file(&path.path, cx));
+        cx.spawn(async move |cx| {
+            let LoadedBinaryFile { file, content } = load_file.await?;
+            cx.new(|_| Self {
+                file,
+                content: content.into(),
+            })
+        })
+    }
+
+    pub fn content(&self) -> &Arc<[u8]> {
+        &self.content
+    }
+
+    fn abs_path(&self, cx: &App) -> Option<PathBuf> {
+        Some(self.file.as_local()?.abs_path(cx))
+    }
+}
+
+impl project::ProjectItem for HexFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        is_binary_file(path).then(|| Self::open(project, path, cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.file.entry_id
+    }
+
+    fn project_path(&self, cx: &App) -> Option<ProjectPath> {
+        Some(ProjectPath {
+            worktree_id: self.file.worktree_id(cx),
+            path: self.file.path().clone(),
+        })
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+/// Parses a search query into the bytes to look for.
+///
+/// A query wrapped in double quotes matches its text literally, anything else is read as
+/// hex bytes, optionally separated by whitespace and prefixed with `0x`.
+fn parse_byte_pattern(query: &str) -> Option<Vec<u8>> {
+    let query = query.trim();
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+    {
+        return (!text.is_empty()).then(|| text.as_bytes().to_vec());
+    }
+
+    let digits = query
+        .split_whitespace()
+        .map(|token| {
+            token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token)
+        })
+        .collect::<String>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|ix| u8::from_str_radix(digits.get(ix..ix + 2)?, 16).ok())
+        .collect()
+}
+
+/// Returns the non-overlapping occurrences of `pattern` in `content`.
+fn find_matches(content: &[u8], pattern: &[u8]) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+    if pattern.is_empty() {
+        return matches;
+    }
+    let mut offset = 0;
+    while offset + pattern.len() <= content.len() {
+        if content[offset..].starts_with(pattern) {
+            matches.push(offset..offset + pattern.len());
+            offset += pattern.len();
+        } else {
+            offset += 1;
+        }
+    }
+    matches
+}
+
+pub struct HexView {
+    hex_file: Entity<HexFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    search_editor: Entity<Editor>,
+    matches: Arc<[Range<usize>]>,
+    active_match_index: Option<usize>,
+    invalid_query: bool,
+    search_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HexView {
+    pub fn new(
+        hex_file: Entity<HexFile>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search bytes: de ad be ef, or \"text\"", window, cx);
+            editor
+        });
+        let subscriptions = vec![cx.subscribe_in(
+            &search_editor,
+            window,
+            |this, _, event: &EditorEvent, _, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_matches(cx);
+                }
+            },
+        )];
+        Self {
+            hex_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            search_editor,
+            matches: Arc::default(),
+            active_match_index: None,
+            invalid_query: false,
+            search_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn update_matches(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        self.matches = Arc::default();
+        self.active_match_index = None;
+        self.search_task = None;
+        self.invalid_query = false;
+        if query.trim().is_empty() {
+            cx.notify();
+            return;
+        }
+        let Some(pattern) = parse_byte_pattern(&query) else {
+            self.invalid_query = true;
+            cx.notify();
+            return;
+        };
+
+        let content = self.hex_file.read(cx).content.clone();
+        let search = cx.background_spawn(async move { find_matches(&content, &pattern) });
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let matches = search.await;
+            this.update(cx, |this, cx| {
+                this.matches = matches.into();
+                this.search_task = None;
+                if !this.matches.is_empty() {
+                    this.activate_match(0, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn activate_match(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(range) = self.matches.get(index) else {
+            return;
+        };
+        self.active_match_index = Some(index);
+        self.scroll_handle
+            .scroll_to_item(range.start / BYTES_PER_ROW, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn select_next_match(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let index = self
+            .active_match_index
+            .map_or(0, |index| (index + 1) % self.matches.len());
+        self.activate_match(index, cx);
+    }
+
+    fn select_previous_match(
+        &mut self,
+        _: &menu::SecondaryConfirm,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let index = self.active_match_index.map_or(0, |index| {
+            index.checked_sub(1).unwrap_or(self.matches.len() - 1)
+        });
+        self.activate_match(index, cx);
+    }
+
+    fn render_search_status(&self, cx: &App) -> Label {
+        if self.invalid_query {
+            Label::new("Invalid byte pattern").color(Color::Error)
+        } else if self.search_task.is_some() {
+            Label::new("Searching…").color(Color::Muted)
+        } else if let Some(index) = self.active_match_index {
+            Label::new(format!("{}/{}", index + 1, self.matches.len())).color(Color::Muted)
+        } else if self.search_editor.read(cx).text(cx).trim().is_empty() {
+            Label::new("")
+        } else {
+            Label::new("No matches").color(Color::Muted)
+        }
+    }
+}
+
+/// Formats a row of bytes as `offset  hex bytes  |ascii|`, returning the text along with
+/// the ranges of each byte's hex digits and ASCII character within it.
+fn format_row(offset: usize, bytes: &[u8]) -> (String, Vec<(Range<usize>, Range<usize>)>) {
+    let mut text = format!("{offset:08x}  ");
+    let mut hex_ranges = Vec::with_capacity(bytes.len());
+    for column in 0..BYTES_PER_ROW {
+        if column == BYTES_PER_ROW / 2 {
+            text.push(' ');
+        }
+        match bytes.get(column) {
+            Some(byte) => {
+                let start = text.len();
+                text.push_str(&format!("{byte:02x}"));
+                hex_ranges.push(start..text.len());
+            }
+            None => text.push_str("  "),
+        }
+        text.push(' ');
+    }
+
+    text.push_str(" |");
+    let mut byte_ranges = Vec::with_capacity(bytes.len());
+    for (byte, hex_range) in bytes.iter().zip(hex_ranges) {
+        let start = text.len();
+        text.push(if byte.is_ascii_graphic() || *byte == b' ' {
+            *byte as char
+        } else {
+            '.'
+        });
+        byte_ranges.push((hex_range, start..text.len()));
+    }
+    text.push('|');
+    (text, byte_ranges)
+}
+
+impl Render for HexView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = self.hex_file.read(cx).content.clone();
+        let row_count = content.len().div_ceil(BYTES_PER_ROW);
+        let matches = self.matches.clone();
+        let active_match = self
+            .active_match_index
+            .and_then(|index| matches.get(index).cloned());
+        let colors = cx.theme().colors();
+        let match_highlight = HighlightStyle {
+            background_color: Some(colors.search_match_background),
+            ..Default::default()
+        };
+        let active_match_highlight = HighlightStyle {
+            background_color: Some(colors.text_accent.opacity(0.4)),
+            ..Default::default()
+        };
+
+        v_flex()
+            .key_context("HexView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next_match))
+            .on_action(cx.listener(Self::select_previous_match))
+            .size_full()
+            .bg(colors.editor_background)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(div().flex_1().child(self.search_editor.clone()))
+                    .child(self.render_search_status(cx))
+                    .child(
+                        Label::new(format!("{} bytes, read-only", content.len()))
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    "hex-rows",
+                    row_count,
+                    cx.processor(move |_, range: Range<usize>, _, _| {
+                        range
+                            .map(|row| {
+                                let row_start = row * BYTES_PER_ROW;
+                                let row_end = (row_start + BYTES_PER_ROW).min(content.len());
+                                let (text, byte_ranges) =
+                                    format_row(row_start, &content[row_start..row_end]);
+
+                                let first_match =
+                                    matches.partition_point(|range| range.end <= row_start);
+                                let mut highlights = Vec::new();
+                                for range in matches[first_match..]
+                                    .iter()
+                                    .take_while(|range| range.start < row_end)
+                                {
+                                    let highlight = if active_match.as_ref() == Some(range) {
+                                        active_match_highlight
+                                    } else {
+                                        match_highlight
+                                    };
+                                    let columns = range.start.max(row_start) - row_start
+                                        ..range.end.min(row_end) - row_start;
+                                    for (hex_range, ascii_range) in &byte_ranges[columns] {
+                                        highlights.push((hex_range.clone(), highlight));
+                                        highlights.push((ascii_range.clone(), highlight));
+                                    }
+                                }
+                                highlights.sort_by_key(|(range, _)| range.start);
+
+                                div()
+                                    .id(row)
+                                    .px_2()
+                                    .whitespace_nowrap()
+                                    .child(StyledText::new(text).with_highlights(highlights))
+                            })
+                            .collect()
+                    }),
+                )
+                .font_buffer(cx)
+                .flex_grow()
+                .track_scroll(self.scroll_handle.clone()),
+            )
+    }
+}
+
+impl Focusable for HexView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.search_editor.focus_handle(cx)
+    }
+}
+
+pub enum HexViewEvent {}
+
+impl EventEmitter<HexViewEvent> for HexView {}
+
+impl Item for HexView {
+    type Event = HexViewEvent;
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.hex_file.entity_id(), self.hex_file.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.hex_file.read(cx).abs_path(cx)?;
+        Some(abs_path.compact().to_string_lossy().to_string().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(params.text_color())
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.hex_file
+            .read(cx)
+            .file
+            .file_name(cx)
+            .to_string_lossy()
+            .to_string()
+            .into()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.hex_file.read(cx).abs_path(cx)?;
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new(|cx| Self::new(self.hex_file.clone(), self.project.clone(), window, cx)))
+    }
+}
+
+impl ProjectItem for HexView {
+    type Item = HexFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_pattern() {
+        assert_eq!(
+            parse_byte_pattern("de ad BE ef"),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(parse_byte_pattern("0x7f 0x45"), Some(vec![0x7f, 0x45]));
+        assert_eq!(
+            parse_byte_pattern("7f454c46"),
+            Some(vec![0x7f, 0x45, 0x4c, 0x46])
+        );
+        assert_eq!(parse_byte_pattern("\"ELF\""), Some(b"ELF".to_vec()));
+        assert_eq!(parse_byte_pattern("abc"), None);
+        assert_eq!(parse_byte_pattern("zz"), None);
+        assert_eq!(parse_byte_pattern("\"\""), None);
+    }
+
+    #[test]
+    fn test_find_matches() {
+        assert_eq!(find_matches(b"aaaa", b"aa"), vec![0..2, 2..4]);
+        assert_eq!(
+            find_matches(b"\x00\x7fELF\x7fELF", b"\x7fELF"),
+            vec![1..5, 5..9]
+        );
+        assert_eq!(find_matches(b"abc", b"abcd"), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn test_format_row() {
+        let (text, byte_ranges) = format_row(0x10, b"AB\x00");
+        assert!(text.starts_with("00000010  41 42 00 "));
+        assert!(text.ends_with(" |AB.|"));
+        assert_eq!(&text[byte_ranges[1].0.clone()], "42");
+        assert_eq!(&text[byte_ranges[2].1.clone()], ".");
+    }
+}
@@ -76,6 +76,11 @@ pub async fn latest_github_release(
         .filter(|release| !require_assets || !release.assets.is_empty())
         .find(|release| release.pre_release == pre_release)
         .context("finding a prerelease")?;
+    strip_digest_prefixes(&mut release);
+    Ok(release)
+}
+
+fn strip_digest_prefixes(release: &mut GithubRelease) {
     release.assets.iter_mut().for_each(|asset| {
         if let Some(digest) = &mut asset.digest
             && let Some(stripped) = digest.strip_prefix("sha256:")
@@ -83,7 +88,6 @@ pub async fn latest_github_release(
             *digest = stripped.to_owned();
         }
     });
-    Ok(release)
 }
 
 pub async fn get_release_by_tag_name(
@@ -116,7 +120,7 @@ pub async fn get_release_by_tag_name(
         );
     }
 
-    let release = serde_json::from_slice::<GithubRelease>(body.as_slice()).map_err(|err| {
+    let mut release = serde_json::from_slice::<GithubRelease>(body.as_slice()).map_err(|err| {
         log::error!("Error deserializing: {err:?}");
         log::error!(
             "GitHub API response text: {:?}",
@@ -124,6 +128,7 @@ pub async fn get_release_by_tag_name(
         );
         anyhow!("error deserializing GitHub release: {err:?}")
     })?;
+    strip_digest_prefixes(&mut release);
 
     Ok(release)
 }
@@ -1,12 +1,14 @@
 use crate::{
-    DebuggerTextObject, LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag,
-    TextObject, TreeSitterOptions,
+    ColorLiteral, DebuggerTextObject, LanguageScope, Outline, OutlineConfig, RunnableCapture,
+    RunnableTag, TextObject, TreeSitterOptions,
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
-    language_settings::{LanguageSettings, language_settings},
+    language_settings::{
+        LanguageSettings, language_settings, language_settings_with_detected_indent,
+    },
     outline::OutlineItem,
     syntax_map::{
-        SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatch,
-        SyntaxMapMatches, SyntaxSnapshot, ToTreeSitterPoint,
+        CaptureStack, ChangedSyntaxRegion, SyntaxLayer, SyntaxMap, SyntaxMapCapture,
+        SyntaxMapCaptures, SyntaxMapMatch, SyntaxMapMatches, SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
     text_diff::text_diff,
@@ -121,6 +123,11 @@ pub struct Buffer {
     completion_triggers_timestamp: clock::Lamport,
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
+    /// Whether syntax parsing is skipped to keep a large file responsive.
+    large_file: bool,
+    /// The indentation inferred from the file's contents when it was opened.
+    detected_indent: Option<IndentSize>,
+    has_detected_indent: bool,
     has_conflict: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
@@ -149,6 +156,7 @@ pub struct BufferSnapshot {
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
     language: Option<Arc<Language>>,
+    detected_indent: Option<IndentSize>,
     non_text_state_update_count: usize,
 }
 
@@ -331,6 +339,8 @@ pub enum BufferEvent {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The buffer entered or left large file mode.
+    LargeFileModeChanged,
 }
 
 /// The file associated with a buffer.
@@ -471,7 +481,7 @@ struct IndentSuggestion {
 struct BufferChunkHighlights<'a> {
     captures: SyntaxMapCaptures<'a>,
     next_capture: Option<SyntaxMapCapture<'a>>,
-    stack: Vec<(usize, HighlightId)>,
+    stack: CaptureStack<HighlightId>,
     highlight_maps: Vec<HighlightMap>,
 }
 
@@ -802,6 +812,8 @@ pub struct BracketMatch {
     pub open_range: Range<usize>,
     pub close_range: Range<usize>,
     pub newline_only: bool,
+    /// Whether the pair shouldn't be colored by its depth, as is the case for quotes.
+    pub rainbow_exclude: bool,
 }
 
 impl Buffer {
@@ -960,6 +972,9 @@ impl Buffer {
             branch_state: None,
             file,
             capability,
+            large_file: false,
+            detected_indent: None,
+            has_detected_indent: false,
             syntax_map,
             reparse: None,
             non_text_state_update_count: 0,
@@ -1005,6 +1020,7 @@ impl Buffer {
                 diagnostics: Default::default(),
                 remote_selections: Default::default(),
                 language,
+                detected_indent: None,
                 non_text_state_update_count: 0,
             }
         }
@@ -1023,6 +1039,7 @@ impl Buffer {
             diagnostics: Default::default(),
             remote_selections: Default::default(),
             language: None,
+            detected_indent: None,
             non_text_state_update_count: 0,
         }
     }
@@ -1048,6 +1065,7 @@ impl Buffer {
             diagnostics: Default::default(),
             remote_selections: Default::default(),
             language,
+            detected_indent: None,
             non_text_state_update_count: 0,
         }
     }
@@ -1067,6 +1085,7 @@ impl Buffer {
             remote_selections: self.remote_selections.clone(),
             diagnostics: self.diagnostics.clone(),
             language: self.language.clone(),
+            detected_indent: self.detected_indent,
             non_text_state_update_count: self.non_text_state_update_count,
         }
     }
@@ -1286,6 +1305,31 @@ impl Buffer {
         }
     }
 
+    /// Whether the buffer was opened in large file mode, in which it isn't
+    /// parsed and isn't sent to language servers.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Turns large file mode on or off. While it is on, the buffer's syntax
+    /// trees are dropped and it isn't reparsed.
+    pub fn set_large_file(&mut self, large_file: bool, cx: &mut Context<Self>) {
+        if self.large_file == large_file {
+            return;
+        }
+        self.large_file = large_file;
+        if large_file {
+            self.reparse = None;
+            self.syntax_map.lock().clear(&self.text);
+            self.non_text_state_update_count += 1;
+            self.parse_status.0.send(ParseStatus::Idle).unwrap();
+        } else {
+            self.reparse(cx);
+        }
+        cx.emit(BufferEvent::LargeFileModeChanged);
+        cx.notify();
+    }
+
     /// This method is called to signal that the buffer has been saved.
     pub fn did_save(
         &mut self,
@@ -1505,7 +1549,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut Context<Self>) {
-        if self.reparse.is_some() {
+        if self.reparse.is_some() || self.large_file {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1573,12 +1617,29 @@ impl Buffer {
         self.was_changed();
         self.non_text_state_update_count += 1;
         self.syntax_map.lock().did_parse(syntax_snapshot);
+        self.detect_indent_once();
         self.request_autoindent(cx);
         self.parse_status.0.send(ParseStatus::Idle).unwrap();
         cx.emit(BufferEvent::Reparsed);
         cx.notify();
     }
 
+    /// Infers the buffer's indentation the first time it's parsed, so that the file's own
+    /// indentation can be used instead of the configured one. Buffers that aren't backed
+    /// by a file always use the configured indentation.
+    fn detect_indent_once(&mut self) {
+        if self.has_detected_indent || self.file.is_none() {
+            return;
+        }
+        self.has_detected_indent = true;
+        self.detected_indent = self.snapshot().detect_indent();
+    }
+
+    /// The indentation inferred from the buffer's contents when it was opened, if any.
+    pub fn detected_indent(&self) -> Option<IndentSize> {
+        self.detected_indent
+    }
+
     pub fn parse_status(&self) -> watch::Receiver<ParseStatus> {
         self.parse_status.1.clone()
     }
@@ -1899,11 +1960,41 @@ impl Buffer {
     /// Spawns a background task that searches the buffer for any whitespace
     /// at the ends of a lines, and returns a `Diff` that removes that whitespace.
     pub fn remove_trailing_whitespace(&self, cx: &App) -> Task<Diff> {
+        self.remove_trailing_whitespace_outside_of(Vec::new(), cx)
+    }
+
+    /// Like [`Buffer::remove_trailing_whitespace`], but keeps the whitespace at the end
+    /// of the lines containing one of this replica's cursors.
+    pub fn remove_trailing_whitespace_except_on_cursor_lines(&self, cx: &App) -> Task<Diff> {
+        let cursor_lines = self
+            .remote_selections
+            .get(&self.text.replica_id())
+            .into_iter()
+            .flat_map(|set| set.selections.iter())
+            .map(|selection| {
+                let row = selection.head().to_point(self).row;
+                self.point_to_offset(Point::new(row, 0))
+                    ..self.point_to_offset(Point::new(row, self.line_len(row)))
+            })
+            .collect();
+        self.remove_trailing_whitespace_outside_of(cursor_lines, cx)
+    }
+
+    fn remove_trailing_whitespace_outside_of(
+        &self,
+        kept_lines: Vec<Range<usize>>,
+        cx: &App,
+    ) -> Task<Diff> {
         let old_text = self.as_rope().clone();
         let line_ending = self.line_ending();
         let base_version = self.version();
         cx.background_spawn(async move {
-            let ranges = trailing_whitespace_ranges(&old_text);
+            let mut ranges = trailing_whitespace_ranges(&old_text);
+            ranges.retain(|range| {
+                !kept_lines
+                    .iter()
+                    .any(|line| line.start <= range.start && range.end <= line.end)
+            });
             let empty = Arc::<str>::from("");
             Diff {
                 base_version,
@@ -2936,11 +3027,7 @@ impl BufferSnapshot {
     /// Returns [`IndentSize`] for a given position that respects user settings
     /// and language preferences.
     pub fn language_indent_size_at<T: ToOffset>(&self, position: T, cx: &App) -> IndentSize {
-        let settings = language_settings(
-            self.language_at(position).map(|l| l.name()),
-            self.file(),
-            cx,
-        );
+        let settings = self.settings_at(position, cx);
         if settings.hard_tabs {
             IndentSize::tab()
         } else {
@@ -2981,6 +3068,37 @@ impl BufferSnapshot {
         result
     }
 
+    /// Returns the edits that reindent the non-blank lines intersecting the given ranges
+    /// according to the language's indentation rules.
+    pub fn reindent_edits<T: ToPoint>(
+        &self,
+        ranges: impl IntoIterator<Item = Range<T>>,
+        cx: &App,
+    ) -> Vec<(Range<Point>, String)> {
+        let mut rows = BTreeSet::new();
+        for range in ranges {
+            let start = range.start.to_point(self);
+            let end = range.end.to_point(self);
+            rows.extend(start.row..=end.row);
+        }
+        let Some(&first_row) = rows.first() else {
+            return Vec::new();
+        };
+
+        let single_indent_size = self.language_indent_size_at(Point::new(first_row, 0), cx);
+        self.suggested_indents(rows.into_iter(), single_indent_size)
+            .into_iter()
+            .filter(|(row, _)| !self.is_line_blank(*row))
+            .filter_map(|(row, indent_size)| {
+                Buffer::edit_for_indent_size_adjustment(
+                    row,
+                    self.indent_size_for_line(row),
+                    indent_size,
+                )
+            })
+            .collect()
+    }
+
     fn suggest_autoindents(
         &self,
         row_range: Range<u32>,
@@ -3378,13 +3496,90 @@ impl BufferSnapshot {
         position: D,
         cx: &'a App,
     ) -> Cow<'a, LanguageSettings> {
-        language_settings(
+        language_settings_with_detected_indent(
             self.language_at(position).map(|l| l.name()),
             self.file.as_ref(),
+            self.detected_indent,
             cx,
         )
     }
 
+    /// The indentation inferred from the buffer's contents when it was opened, if any.
+    pub fn detected_indent(&self) -> Option<IndentSize> {
+        self.detected_indent
+    }
+
+    /// Infers the indentation used in the buffer by looking at how much the first line of
+    /// each indented block is indented relative to the line that starts the block. Block
+    /// starts are found using the language's indents query, or by looking for lines that
+    /// are followed by a more indented line when the language doesn't have one.
+    pub fn detect_indent(&self) -> Option<IndentSize> {
+        /// Only the beginning of the buffer is looked at, to keep this cheap for large files.
+        const MAX_DETECTION_ROWS: u32 = 1000;
+        /// Larger steps are more likely to be alignment than indentation.
+        const MAX_INDENT_WIDTH: u32 = 8;
+
+        let max_row = self.max_point().row.min(MAX_DETECTION_ROWS);
+        let mut block_start_rows = BTreeSet::new();
+        let mut matches = self.syntax.matches(
+            0..Point::new(max_row, 0).to_offset(self),
+            &self.text,
+            |grammar| Some(&grammar.indents_config.as_ref()?.query),
+        );
+        let indent_configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.indents_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+        while let Some(mat) = matches.peek() {
+            let config = indent_configs[mat.grammar_index];
+            for capture in mat.captures {
+                if capture.index == config.indent_capture_ix {
+                    let start_row = capture.node.start_position().row as u32;
+                    if capture.node.end_position().row as u32 > start_row {
+                        block_start_rows.insert(start_row);
+                    }
+                }
+            }
+            matches.advance();
+        }
+        if indent_configs.is_empty() {
+            block_start_rows.extend(0..max_row);
+        }
+
+        let mut votes = HashMap::<IndentSize, usize>::default();
+        for row in block_start_rows {
+            if self.is_line_blank(row) {
+                continue;
+            }
+            let Some(next_row) = (row + 1..=max_row).find(|row| !self.is_line_blank(*row)) else {
+                continue;
+            };
+            let indent = self.indent_size_for_line(row);
+            let next_indent = self.indent_size_for_line(next_row);
+            if next_indent.len <= indent.len || (indent.len > 0 && indent.kind != next_indent.kind)
+            {
+                continue;
+            }
+            let vote = match next_indent.kind {
+                IndentKind::Tab => IndentSize::tab(),
+                IndentKind::Space => {
+                    let width = next_indent.len - indent.len;
+                    if width > MAX_INDENT_WIDTH {
+                        continue;
+                    }
+                    IndentSize::spaces(width)
+                }
+            };
+            *votes.entry(vote).or_default() += 1;
+        }
+
+        votes
+            .into_iter()
+            .max_by_key(|(indent, count)| (*count, Reverse(indent.len)))
+            .map(|(indent, _)| indent)
+    }
+
     pub fn char_classifier_at<T: ToOffset>(&self, point: T) -> CharClassifier {
         CharClassifier::new(self.language_scope_at(point))
     }
@@ -3534,6 +3729,105 @@ impl BufferSnapshot {
         }
     }
 
+    /// Returns the ranges of the identifiers that have the same kind and text as the identifier
+    /// spanning `range`, within the same syntax layer. This is used to highlight the occurrences
+    /// of a symbol when there's no language server to ask for them.
+    ///
+    /// Returns an empty list when `range` doesn't span exactly one identifier.
+    pub fn matching_identifier_ranges<T: ToOffset>(&self, range: Range<T>) -> Vec<Range<usize>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let Some(layer) = self.smallest_syntax_layer_containing(range.clone()) else {
+            return Vec::new();
+        };
+        let Some(identifier) = layer
+            .node()
+            .descendant_for_byte_range(range.start, range.end)
+        else {
+            return Vec::new();
+        };
+        if identifier.byte_range() != range
+            || identifier.child_count() > 0
+            || !identifier.kind().ends_with("identifier")
+        {
+            return Vec::new();
+        }
+        let text = self.text_for_range(range.clone()).collect::<String>();
+
+        let mut ranges = Vec::new();
+        let mut cursor = layer.node().walk();
+        loop {
+            let node = cursor.node();
+            if node.kind_id() == identifier.kind_id()
+                && node.byte_range().len() == range.len()
+                && self.contains_str_at(node.start_byte(), &text)
+            {
+                ranges.push(node.byte_range());
+            }
+            if cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return ranges;
+                }
+            }
+        }
+    }
+
+    /// Returns the parts of `range` that contain prose to spell check, which are the comments
+    /// and strings found in the syntax tree, unless `check_all_text` is set.
+    pub fn spell_check_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        check_all_text: bool,
+    ) -> Vec<Range<usize>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        if check_all_text {
+            return vec![range];
+        }
+
+        let mut ranges = Vec::new();
+        for layer in self
+            .syntax
+            .layers_for_range(range.clone(), &self.text, true)
+        {
+            let mut cursor = layer.node().walk();
+            loop {
+                let node = cursor.node();
+                let overlaps = node.start_byte() < range.end && node.end_byte() > range.start;
+                let kind = node.kind();
+                let is_prose = kind.contains("comment") || kind.contains("string");
+                if overlaps && is_prose {
+                    ranges.push(node.start_byte().max(range.start)..node.end_byte().min(range.end));
+                } else if overlaps && cursor.goto_first_child() {
+                    continue;
+                }
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break;
+                    }
+                }
+                if cursor.node() == layer.node() {
+                    break;
+                }
+            }
+        }
+
+        // Injected layers can overlap the comments and strings of the layers they're in.
+        ranges.sort_unstable_by_key(|range| range.start);
+        let mut merged_ranges: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            match merged_ranges.last_mut() {
+                Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                _ => merged_ranges.push(range),
+            }
+        }
+        merged_ranges
+    }
+
     pub fn syntax_ancestor<'a, T: ToOffset>(
         &'a self,
         range: Range<T>,
@@ -3693,6 +3987,64 @@ impl BufferSnapshot {
         result
     }
 
+    /// Finds the syntax node around the given range that can be swapped with its next (or
+    /// previous) sibling, returning the ranges of both nodes.
+    ///
+    /// Only named siblings separated by whitespace, commas or semicolons are swapped, such as
+    /// the statements of a block or the elements of a list, so that the separators between
+    /// them stay in place. When the innermost node has no such sibling, its ancestors are
+    /// tried instead.
+    pub fn syntax_node_to_swap<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        next: bool,
+    ) -> Option<(Range<usize>, Range<usize>)> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let layer = self.smallest_syntax_layer_containing(range.clone())?;
+        let mut node = layer
+            .node()
+            .descendant_for_byte_range(range.start, range.end)?;
+        loop {
+            if node.is_named() && !node.is_extra() {
+                if let Some(sibling) = Self::swappable_sibling(node, next) {
+                    return Some((node.byte_range(), sibling.byte_range()));
+                }
+            }
+            node = node.parent()?;
+        }
+    }
+
+    fn swappable_sibling(node: tree_sitter::Node, next: bool) -> Option<tree_sitter::Node> {
+        let mut sibling = node;
+        let mut separated = false;
+        loop {
+            sibling = if next {
+                sibling.next_sibling()?
+            } else {
+                sibling.prev_sibling()?
+            };
+            if sibling.is_extra() {
+                continue;
+            }
+            if sibling.is_named() {
+                break;
+            }
+            if matches!(sibling.kind(), "," | ";") {
+                separated = true;
+            } else {
+                return None;
+            }
+        }
+        // Adjacent nodes, like a function and its arguments, are parts of a larger node
+        // rather than items of a list.
+        let gap = if next {
+            node.end_byte()..sibling.start_byte()
+        } else {
+            sibling.end_byte()..node.start_byte()
+        };
+        (separated || !gap.is_empty()).then_some(sibling)
+    }
+
     /// Returns the root syntax node within the given row
     pub fn syntax_root_ancestor(&self, position: Anchor) -> Option<tree_sitter::Node<'_>> {
         let start_offset = position.to_offset(self);
@@ -4094,6 +4446,7 @@ impl BufferSnapshot {
                     open_range,
                     close_range,
                     newline_only: pattern.newline_only,
+                    rainbow_exclude: pattern.rainbow_exclude,
                 });
             }
             None
@@ -4112,6 +4465,112 @@ impl BufferSnapshot {
             .filter(|pair| !pair.newline_only)
     }
 
+    /// Returns the keywords matched by the language's keyword pairs query that belong to the
+    /// innermost construct with a keyword containing `range`, such as the `#if`, `#else` and
+    /// `#endif` of a preprocessor conditional, sorted by their position.
+    pub fn matching_keyword_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Option<Vec<Range<usize>>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let group_len = |group: &[Range<usize>]| {
+            let start = group.iter().map(|keyword| keyword.start).min().unwrap_or(0);
+            let end = group.iter().map(|keyword| keyword.end).max().unwrap_or(0);
+            end - start
+        };
+        let mut keywords = self
+            .keyword_groups(range.start.saturating_sub(1)..self.len().min(range.end + 1))
+            .into_iter()
+            .filter(|group| {
+                group
+                    .iter()
+                    .any(|keyword| keyword.start <= range.start && range.end <= keyword.end)
+            })
+            .min_by_key(|group| group_len(group))?;
+
+        // Constructs such as chains of `#elif`s are matched in parts that share a keyword, and
+        // the parts that don't contain `range` are only matched when querying their extent.
+        loop {
+            let extent = keywords.iter().map(|keyword| keyword.start).min()?
+                ..keywords.iter().map(|keyword| keyword.end).max()?;
+            let mut merged = false;
+            for group in self.keyword_groups(extent) {
+                if group.iter().any(|keyword| keywords.contains(keyword)) {
+                    for keyword in group {
+                        if !keywords.contains(&keyword) {
+                            keywords.push(keyword);
+                            merged = true;
+                        }
+                    }
+                }
+            }
+            if !merged {
+                break;
+            }
+        }
+
+        keywords.sort_by_key(|keyword| keyword.start);
+        Some(keywords)
+    }
+
+    fn keyword_groups(&self, range: Range<usize>) -> Vec<Vec<Range<usize>>> {
+        let mut matches = self.syntax.matches(range, &self.text, |grammar| {
+            grammar.keyword_pairs_config.as_ref().map(|c| &c.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.keyword_pairs_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut groups = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            let group = mat
+                .captures
+                .iter()
+                .filter(|capture| capture.index == config.keyword_capture_ix)
+                .map(|capture| capture.node.byte_range())
+                .collect::<Vec<_>>();
+            if group.len() > 1 {
+                groups.push(group);
+            }
+            matches.advance();
+        }
+        groups
+    }
+
+    /// Returns the color literals in the given range that the language's colors query
+    /// captures and that can be parsed, such as `#f80` or `rgb(255 136 0)`.
+    pub fn color_literals(&self, range: Range<usize>) -> Vec<(Range<usize>, ColorLiteral)> {
+        let mut matches = self.syntax.matches(range, &self.text, |grammar| {
+            grammar.colors_config.as_ref().map(|c| &c.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.colors_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut colors = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            for capture in mat.captures {
+                if capture.index == config.color_capture_ix {
+                    let range = capture.node.byte_range();
+                    let text = self.text_for_range(range.clone()).collect::<String>();
+                    if let Some(literal) = ColorLiteral::parse(&text) {
+                        colors.push((range, literal));
+                    }
+                }
+            }
+            matches.advance();
+        }
+        colors.sort_by_key(|(range, _)| range.start);
+        colors.dedup_by_key(|(range, _)| range.start);
+        colors
+    }
+
     pub fn debug_variables_query<T: ToOffset>(
         &self,
         range: Range<T>,
@@ -4623,6 +5082,12 @@ impl BufferSnapshot {
         self.syntax.update_count()
     }
 
+    /// Returns the syntactic regions that changed between `old` and this
+    /// snapshot, or `None` if the whole buffer should be considered changed.
+    pub fn syntax_changed_since(&self, old: &BufferSnapshot) -> Option<Vec<ChangedSyntaxRegion>> {
+        self.syntax.changed_since(&old.syntax)
+    }
+
     /// Returns a snapshot of underlying file.
     pub fn file(&self) -> Option<&Arc<dyn File>> {
         self.file.as_ref()
@@ -4736,6 +5201,7 @@ impl Clone for BufferSnapshot {
             remote_selections: self.remote_selections.clone(),
             diagnostics: self.diagnostics.clone(),
             language: self.language.clone(),
+            detected_indent: self.detected_indent,
             non_text_state_update_count: self.non_text_state_update_count,
         }
     }
@@ -4796,18 +5262,15 @@ impl<'a> BufferChunks<'a> {
         if let Some(highlights) = self.highlights.as_mut() {
             if old_range.start <= self.range.start && old_range.end >= self.range.end {
                 // Reuse existing highlights stack, as the new range is a subrange of the old one.
-                highlights
-                    .stack
-                    .retain(|(end_offset, _)| *end_offset > range.start);
+                highlights.stack.remove_ended(range.start);
                 if let Some(capture) = &highlights.next_capture
                     && range.start >= capture.node.start_byte()
                 {
-                    let next_capture_end = capture.node.end_byte();
-                    if range.start < next_capture_end {
-                        highlights.stack.push((
-                            next_capture_end,
+                    if range.start < capture.node.end_byte() {
+                        highlights.stack.push(
+                            capture,
                             highlights.highlight_maps[capture.grammar_index].get(capture.index),
-                        ));
+                        );
                     }
                     highlights.next_capture.take();
                 }
@@ -4923,13 +5386,7 @@ impl<'a> Iterator for BufferChunks<'a> {
         let mut next_diagnostic_endpoint = usize::MAX;
 
         if let Some(highlights) = self.highlights.as_mut() {
-            while let Some((parent_capture_end, _)) = highlights.stack.last() {
-                if *parent_capture_end <= self.range.start {
-                    highlights.stack.pop();
-                } else {
-                    break;
-                }
-            }
+            highlights.stack.remove_ended(self.range.start);
 
             if highlights.next_capture.is_none() {
                 highlights.next_capture = highlights.captures.next();
@@ -4942,9 +5399,7 @@ impl<'a> Iterator for BufferChunks<'a> {
                 } else {
                     let highlight_id =
                         highlights.highlight_maps[capture.grammar_index].get(capture.index);
-                    highlights
-                        .stack
-                        .push((capture.node.end_byte(), highlight_id));
+                    highlights.stack.push(capture, highlight_id);
                     highlights.next_capture = highlights.captures.next();
                 }
             }
@@ -4977,10 +5432,10 @@ impl<'a> Iterator for BufferChunks<'a> {
                 .min(next_diagnostic_endpoint);
             let mut highlight_id = None;
             if let Some(highlights) = self.highlights.as_ref()
-                && let Some((parent_capture_end, parent_highlight_id)) = highlights.stack.last()
+                && let Some(parent_capture_end) = highlights.stack.next_end()
             {
-                chunk_end = chunk_end.min(*parent_capture_end);
-                highlight_id = Some(*parent_highlight_id);
+                chunk_end = chunk_end.min(parent_capture_end);
+                highlight_id = highlights.stack.winner().copied();
             }
 
             let slice =
@@ -279,6 +279,10 @@ async fn test_language_for_file_with_custom_file_types(cx: &mut TestAppContext)
                     "Dockerfile".into(),
                     vec!["Dockerfile".into(), "Dockerfile.*".into()].into(),
                 ),
+                (
+                    "Containerfile.*".into(),
+                    settings::FileTypeAssociation::Language("Dockerfile".into()),
+                ),
             ]);
         })
     });
@@ -376,6 +380,12 @@ async fn test_language_for_file_with_custom_file_types(cx: &mut TestAppContext)
         .read(|cx| languages.language_for_file(&file("Dockerfile.dev"), None, cx))
         .unwrap();
     assert_eq!(language.name(), "Dockerfile".into());
+
+    // globs can also be mapped to languages
+    let language = cx
+        .read(|cx| languages.language_for_file(&file("Containerfile.prod"), None, cx))
+        .unwrap();
+    assert_eq!(language.name(), "Dockerfile".into());
 }
 
 fn file(path: &str) -> Arc<dyn File> {
@@ -525,6 +535,34 @@ async fn test_apply_diff(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_remove_trailing_whitespace_except_on_cursor_lines(cx: &mut gpui::TestAppContext) {
+    let text = ["one  ", "two  ", "three  "].join("\n");
+    let buffer = cx.new(|cx| Buffer::local(text, cx));
+
+    let diff = buffer.update(cx, |buffer, cx| {
+        let cursor = buffer.anchor_before(Point::new(1, 5));
+        buffer.set_active_selections(
+            Arc::from([Selection {
+                id: 0,
+                start: cursor,
+                end: cursor,
+                reversed: false,
+                goal: SelectionGoal::None,
+            }]),
+            false,
+            Default::default(),
+            cx,
+        );
+        buffer.remove_trailing_whitespace_except_on_cursor_lines(cx)
+    });
+    let diff = diff.await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx);
+        assert_eq!(buffer.text(), ["one", "two  ", "three"].join("\n"));
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_normalize_whitespace(cx: &mut gpui::TestAppContext) {
     let text = [
@@ -1235,6 +1273,120 @@ fn test_enclosing_bracket_ranges_where_brackets_are_not_outermost_children(cx: &
     );
 }
 
+#[gpui::test]
+fn test_matching_keyword_ranges(cx: &mut App) {
+    let text = indoc! {"
+        if a
+          while b
+            c
+          end
+        elsif d
+          e
+        else
+          f
+        end
+    "};
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(ruby_lang()), cx));
+    let snapshot = buffer.read(cx).snapshot();
+    let empty_range_at = |part: &str| {
+        let offset = text.find(part).unwrap();
+        offset..offset
+    };
+    let keywords = |ranges: Option<Vec<Range<usize>>>| {
+        ranges.map(|ranges| {
+            ranges
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let if_keywords = Some(vec!["if", "elsif", "else", "end"]);
+    assert_eq!(
+        keywords(snapshot.matching_keyword_ranges(empty_range_at("if"))),
+        if_keywords
+    );
+    assert_eq!(
+        keywords(snapshot.matching_keyword_ranges(empty_range_at(" d\n"))),
+        if_keywords
+    );
+    assert_eq!(
+        keywords(snapshot.matching_keyword_ranges(empty_range_at("else\n"))),
+        if_keywords
+    );
+    assert_eq!(
+        keywords(snapshot.matching_keyword_ranges(empty_range_at("while"))),
+        Some(vec!["while", "end"])
+    );
+    assert_eq!(
+        keywords(snapshot.matching_keyword_ranges(empty_range_at("c\n"))),
+        None
+    );
+}
+
+#[gpui::test]
+fn test_matching_identifier_ranges(cx: &mut App) {
+    let text = indoc! {"
+        fn a(b: B) -> B {
+            let c = b.b;
+            b
+        }
+    "};
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.read(cx).snapshot();
+    let identifiers_matching = |offset: usize, len: usize| {
+        snapshot
+            .matching_identifier_ranges(offset..offset + len)
+            .into_iter()
+            .map(|range| range.start)
+            .collect::<Vec<_>>()
+    };
+
+    // Field accesses and type names aren't occurrences of the variable `b`.
+    let b = text.find("b:").unwrap();
+    assert_eq!(
+        identifiers_matching(b, 1),
+        vec![b, text.find("b.").unwrap(), text.rfind("b\n").unwrap()]
+    );
+    let c = text.find('c').unwrap();
+    assert_eq!(identifiers_matching(c, 1), vec![c]);
+    // Ranges that aren't a whole identifier don't match anything.
+    assert_eq!(identifiers_matching(text.find("let").unwrap(), 3), vec![]);
+    assert_eq!(identifiers_matching(b, 2), vec![]);
+}
+
+#[gpui::test]
+fn test_spell_check_ranges(cx: &mut App) {
+    let text = indoc! {r#"
+        // Teh comment
+        fn a() -> &'static str {
+            "a strng"
+        }
+    "#};
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    let ranges = snapshot.spell_check_ranges(0..text.len(), false);
+    assert_eq!(
+        ranges
+            .into_iter()
+            .map(|range| text[range].trim_end())
+            .collect::<Vec<_>>(),
+        ["// Teh comment", "\"a strng\""]
+    );
+
+    // Ranges are clipped to the given range.
+    let start = text.find("strng").unwrap();
+    assert_eq!(
+        snapshot.spell_check_ranges(start..start + 2, false),
+        [start..start + 2]
+    );
+    assert_eq!(
+        snapshot.spell_check_ranges(3..text.len(), true),
+        [3..text.len()]
+    );
+}
+
 #[gpui::test]
 fn test_range_for_syntax_ancestor(cx: &mut App) {
     cx.new(|cx| {
@@ -1367,6 +1519,56 @@ fn test_autoindent_with_hard_tabs(cx: &mut App) {
     });
 }
 
+#[gpui::test]
+fn test_detect_indent(cx: &mut App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        // Continuation lines are indented further, but most blocks use two spaces.
+        let text = indoc! {"
+            fn a() {
+              if b {
+                c()
+                    .d();
+              }
+              e(
+                f,
+              );
+            }
+        "};
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx);
+        assert_eq!(
+            buffer.snapshot().detect_indent(),
+            Some(IndentSize::spaces(2))
+        );
+        buffer
+    });
+
+    cx.new(|cx| {
+        let text = "fn a() {\n\tif b {\n\t\tc();\n\t}\n}\n";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx);
+        assert_eq!(buffer.snapshot().detect_indent(), Some(IndentSize::tab()));
+        buffer
+    });
+
+    cx.new(|cx| {
+        // Without a grammar, any line followed by a more indented one starts a block.
+        let text = "a:\n   b:\n      c\n   d\n";
+        let buffer = Buffer::local(text, cx);
+        assert_eq!(
+            buffer.snapshot().detect_indent(),
+            Some(IndentSize::spaces(3))
+        );
+        buffer
+    });
+
+    cx.new(|cx| {
+        let buffer = Buffer::local("a\nb\n", cx);
+        assert_eq!(buffer.snapshot().detect_indent(), None);
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_autoindent_does_not_adjust_lines_with_unchanged_suggestion(cx: &mut App) {
     init_settings(cx, |_| {});
@@ -2358,10 +2560,23 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
                             surround: true,
                             newline: false,
                         },
+                        BracketPair {
+                            start: "<".into(),
+                            end: ">".into(),
+                            close: true,
+                            surround: true,
+                            newline: false,
+                        },
                     ],
                     disabled_scopes_by_bracket_ix: vec![
                         Vec::new(),                              //
                         vec!["string".into(), "comment".into()], // single quotes disabled
+                        Vec::new(),
+                    ],
+                    enabled_scopes_by_bracket_ix: vec![
+                        Vec::new(),
+                        Vec::new(),
+                        vec!["element".into()], // angle brackets only within JSX
                     ],
                 },
                 overrides: [(
@@ -2420,10 +2635,10 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
             })
         );
 
-        // Both bracket pairs are enabled
+        // Angle brackets are only enabled within JSX elements
         assert_eq!(
             config.brackets().map(|e| e.1).collect::<Vec<_>>(),
-            &[true, true]
+            &[true, true, false]
         );
 
         let comment_config = snapshot
@@ -2431,7 +2646,7 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
             .unwrap();
         assert_eq!(
             comment_config.brackets().map(|e| e.1).collect::<Vec<_>>(),
-            &[true, false]
+            &[true, false, false]
         );
 
         let string_config = snapshot
@@ -2447,10 +2662,10 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
                 tab_size: 1,
             })
         );
-        // Second bracket pair is disabled
+        // Second and third bracket pairs are disabled
         assert_eq!(
             string_config.brackets().map(|e| e.1).collect::<Vec<_>>(),
-            &[true, false]
+            &[true, false, false]
         );
 
         // In between JSX tags: use the `element` override.
@@ -2466,7 +2681,7 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
         // );
         assert_eq!(
             element_config.brackets().map(|e| e.1).collect::<Vec<_>>(),
-            &[true, true]
+            &[true, true, true]
         );
 
         // Within a JSX tag: use the default config.
@@ -2485,7 +2700,7 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
         );
         assert_eq!(
             tag_config.brackets().map(|e| e.1).collect::<Vec<_>>(),
-            &[true, true]
+            &[true, true, false]
         );
 
         // In a JSX expression: use the default config.
@@ -2510,7 +2725,7 @@ fn test_language_scope_at_with_javascript(cx: &mut App) {
                 .brackets()
                 .map(|e| e.1)
                 .collect::<Vec<_>>(),
-            &[true, true]
+            &[true, true, false]
         );
 
         buffer
@@ -2546,6 +2761,7 @@ fn test_language_scope_at_with_rust(cx: &mut App) {
                         Vec::new(), //
                         vec!["string".into()],
                     ],
+                    enabled_scopes_by_bracket_ix: Vec::new(),
                 },
                 ..Default::default()
             },
@@ -2731,6 +2947,152 @@ fn test_language_at_for_markdown_code_block(cx: &mut App) {
     });
 }
 
+#[gpui::test]
+fn test_highlight_text_with_injections(cx: &mut App) {
+    let theme = SyntaxTheme {
+        highlights: vec![("keyword".to_string(), HighlightStyle::default())],
+    };
+    let rust = Arc::new(
+        rust_lang()
+            .with_highlights_query(r#""let" @keyword"#)
+            .unwrap(),
+    );
+    rust.set_theme(&theme);
+
+    let language_registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    language_registry.add(Arc::new(markdown_lang()));
+    language_registry.add(Arc::new(markdown_inline_lang()));
+    language_registry.add(rust);
+    let markdown = language_registry
+        .language_for_name("Markdown")
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+
+    let text = r#"
+        ```rs
+        let a = 2;
+        ```
+    "#
+    .unindent();
+    let rope = Rope::from(text.as_str());
+
+    let highlights =
+        markdown.highlight_text_with_injections(&rope, 0..text.len(), language_registry);
+    assert_eq!(
+        highlights
+            .iter()
+            .map(|(range, id)| (&text[range.clone()], id.name(&theme)))
+            .collect::<Vec<_>>(),
+        [("let", Some("keyword"))]
+    );
+
+    // Highlighting without injections only sees the markdown layer.
+    assert!(markdown.highlight_text(&rope, 0..text.len()).is_empty());
+}
+
+#[gpui::test]
+fn test_highlight_capture_precedence(cx: &mut App) {
+    let theme = SyntaxTheme {
+        highlights: ["string", "emphasis", "keyword", "variable"]
+            .into_iter()
+            .map(|name| (name.to_string(), HighlightStyle::default()))
+            .collect(),
+    };
+
+    // Merges adjacent ranges with the same highlight, as chunks also break
+    // where captures that lose to others start or end.
+    let highlighted_text = |language: &Language, text: &str, registry: Arc<LanguageRegistry>| {
+        let highlights =
+            language.highlight_text_with_injections(&Rope::from(text), 0..text.len(), registry);
+        let mut result = Vec::<(String, Option<&str>)>::new();
+        let mut end = 0;
+        for (range, id) in highlights {
+            let name = id.name(&theme);
+            match result.last_mut() {
+                Some((last_text, last_name)) if range.start == end && *last_name == name => {
+                    last_text.push_str(&text[range.clone()]);
+                }
+                _ => result.push((text[range.clone()].to_string(), name)),
+            }
+            end = range.end;
+        }
+        result
+    };
+
+    // Within a layer, the innermost capture wins unless another one has a higher priority.
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    let rust = rust_lang()
+        .with_highlights_query(
+            r#"
+            (identifier) @variable
+            (let_declaration) @keyword
+            "#,
+        )
+        .unwrap();
+    rust.set_theme(&theme);
+    assert_eq!(
+        highlighted_text(&rust, "let a = 2;", registry.clone()),
+        [
+            ("let ".to_string(), Some("keyword")),
+            ("a".to_string(), Some("variable")),
+            (" = 2;".to_string(), Some("keyword")),
+        ]
+    );
+
+    let rust = rust_lang()
+        .with_highlights_query(
+            r#"
+            (identifier) @variable
+            ((let_declaration) @keyword (#set! priority 110))
+            "#,
+        )
+        .unwrap();
+    rust.set_theme(&theme);
+    assert_eq!(
+        highlighted_text(&rust, "let a = 2;", registry),
+        [("let a = 2;".to_string(), Some("keyword"))]
+    );
+
+    // Across layers, the injected layer's captures win over the captures of the
+    // text they're injected into, unless those have a higher priority.
+    for (markdown_query, expected) in [
+        (
+            "(inline) @string",
+            vec![
+                ("a ".to_string(), Some("string")),
+                ("*b*".to_string(), Some("emphasis")),
+                (" c".to_string(), Some("string")),
+            ],
+        ),
+        (
+            "((inline) @string (#set! priority 110))",
+            vec![("a *b* c".to_string(), Some("string"))],
+        ),
+    ] {
+        let markdown = Arc::new(
+            markdown_lang()
+                .with_highlights_query(markdown_query)
+                .unwrap(),
+        );
+        let markdown_inline = Arc::new(
+            markdown_inline_lang()
+                .with_highlights_query("(emphasis) @emphasis")
+                .unwrap(),
+        );
+        markdown.set_theme(&theme);
+        markdown_inline.set_theme(&theme);
+
+        let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+        registry.add(markdown.clone());
+        registry.add(markdown_inline);
+        assert_eq!(
+            highlighted_text(markdown.as_ref(), "a *b* c", registry),
+            expected
+        );
+    }
+}
+
 #[gpui::test]
 fn test_serialization(cx: &mut gpui::App) {
     let mut now = Instant::now();
@@ -3583,6 +3945,16 @@ fn ruby_lang() -> Language {
         "#,
     )
     .unwrap()
+    .with_keyword_pairs_query(
+        r#"
+            (if "if" @keyword "end" @keyword)
+            (_
+                ["if" "elsif"] @keyword
+                alternative: [(elsif "elsif" @keyword) (else "else" @keyword)])
+            (while "while" @keyword body: (do "end" @keyword))
+        "#,
+    )
+    .unwrap()
 }
 
 fn html_lang() -> Language {
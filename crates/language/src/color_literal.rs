@@ -0,0 +1,316 @@
+use gpui::{Hsla, Rgba};
+
+/// A color written out in source code, such as `#f80`, `rgb(255 136 0)` or
+/// `hsl(32deg 100% 50% / 0.5)`, along with how it was written so that an edited
+/// color can be written back the same way.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorLiteral {
+    pub color: Rgba,
+    pub format: ColorFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`.
+    Hex {
+        short: bool,
+        alpha: bool,
+        uppercase: bool,
+    },
+    /// `rgb(…)`, `rgba(…)`, `hsl(…)` or `hsla(…)`.
+    Function {
+        space: ColorSpace,
+        /// Whether the function was spelled `rgba` or `hsla`.
+        alpha_name: bool,
+        /// Whether the arguments were separated by commas, rather than spaces and a slash.
+        commas: bool,
+        alpha: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    Rgb,
+    Hsl,
+}
+
+impl ColorLiteral {
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+
+        let (name, arguments) = text.split_once('(')?;
+        let arguments = arguments.strip_suffix(')')?;
+        let name = name.trim().to_ascii_lowercase();
+        let (space, alpha_name) = match name.as_str() {
+            "rgb" => (ColorSpace::Rgb, false),
+            "rgba" => (ColorSpace::Rgb, true),
+            "hsl" => (ColorSpace::Hsl, false),
+            "hsla" => (ColorSpace::Hsl, true),
+            _ => return None,
+        };
+
+        let commas = arguments.contains(',');
+        let (channels, alpha) = if commas {
+            let mut values = arguments.split(',').map(str::trim).collect::<Vec<_>>();
+            let alpha = if values.len() == 4 {
+                values.pop()
+            } else {
+                None
+            };
+            (values, alpha)
+        } else {
+            let (channels, alpha) = match arguments.split_once('/') {
+                Some((channels, alpha)) => (channels, Some(alpha.trim())),
+                None => (arguments, None),
+            };
+            (channels.split_whitespace().collect(), alpha)
+        };
+        let [a, b, c] = channels.as_slice() else {
+            return None;
+        };
+        let alpha_value = match alpha {
+            Some(alpha) => parse_fraction(alpha)?,
+            None => 1.,
+        };
+
+        let color = match space {
+            ColorSpace::Rgb => Rgba {
+                r: parse_rgb_channel(a)?,
+                g: parse_rgb_channel(b)?,
+                b: parse_rgb_channel(c)?,
+                a: alpha_value,
+            },
+            ColorSpace::Hsl => Rgba::from(Hsla {
+                h: parse_hue(a)?,
+                s: parse_percentage(b)?,
+                l: parse_percentage(c)?,
+                a: alpha_value,
+            }),
+        };
+        Some(Self {
+            color,
+            format: ColorFormat::Function {
+                space,
+                alpha_name,
+                commas,
+                alpha: alpha.is_some(),
+            },
+        })
+    }
+}
+
+impl ColorFormat {
+    /// Writes `color` in this format, adding an alpha component if the color needs one.
+    pub fn format(&self, color: Rgba) -> String {
+        let needs_alpha = color.a < 1.;
+        match *self {
+            ColorFormat::Hex {
+                short,
+                alpha,
+                uppercase,
+            } => {
+                let alpha = alpha || needs_alpha;
+                let mut channels = vec![color.r, color.g, color.b];
+                if alpha {
+                    channels.push(color.a);
+                }
+                let bytes = channels
+                    .into_iter()
+                    .map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8)
+                    .collect::<Vec<_>>();
+                let short = short && bytes.iter().all(|byte| byte >> 4 == byte & 0xf);
+                let mut hex = String::from("#");
+                for byte in bytes {
+                    if short {
+                        hex.push_str(&format!("{:x}", byte & 0xf));
+                    } else {
+                        hex.push_str(&format!("{byte:02x}"));
+                    }
+                }
+                if uppercase {
+                    hex.make_ascii_uppercase();
+                }
+                hex
+            }
+            ColorFormat::Function {
+                space,
+                alpha_name,
+                commas,
+                alpha,
+            } => {
+                let alpha = alpha || needs_alpha;
+                let channels = match space {
+                    ColorSpace::Rgb => [color.r, color.g, color.b]
+                        .map(|channel| format!("{}", (channel.clamp(0., 1.) * 255.).round()))
+                        .to_vec(),
+                    ColorSpace::Hsl => {
+                        let hsla = Hsla::from(color);
+                        vec![
+                            format!("{}", (hsla.h * 360.).round() % 360.),
+                            format!("{}%", (hsla.s * 100.).round()),
+                            format!("{}%", (hsla.l * 100.).round()),
+                        ]
+                    }
+                };
+                let name = match space {
+                    ColorSpace::Rgb => "rgb",
+                    ColorSpace::Hsl => "hsl",
+                };
+                // The legacy comma syntax needs the `a` suffix for an alpha component.
+                let suffix = if alpha_name || (commas && alpha) {
+                    "a"
+                } else {
+                    ""
+                };
+                let alpha_value = format_fraction(color.a);
+                if commas {
+                    let mut arguments = channels;
+                    if alpha {
+                        arguments.push(alpha_value);
+                    }
+                    format!("{name}{suffix}({})", arguments.join(", "))
+                } else if alpha {
+                    format!("{name}{suffix}({} / {alpha_value})", channels.join(" "))
+                } else {
+                    format!("{name}{suffix}({})", channels.join(" "))
+                }
+            }
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<ColorLiteral> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let (short, alpha) = match hex.len() {
+        3 => (true, false),
+        4 => (true, true),
+        6 => (false, false),
+        8 => (false, true),
+        _ => return None,
+    };
+    let digits = hex
+        .chars()
+        .map(|c| c.to_digit(16).unwrap() as u8)
+        .collect::<Vec<_>>();
+    let bytes = if short {
+        digits.iter().map(|digit| (digit << 4) | digit).collect()
+    } else {
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] << 4) | pair[1])
+            .collect::<Vec<_>>()
+    };
+    let channel = |ix: usize| bytes.get(ix).map_or(1., |byte| *byte as f32 / 255.);
+    Some(ColorLiteral {
+        color: Rgba {
+            r: channel(0),
+            g: channel(1),
+            b: channel(2),
+            a: channel(3),
+        },
+        format: ColorFormat::Hex {
+            short,
+            alpha,
+            uppercase: hex.chars().any(|c| c.is_ascii_uppercase()),
+        },
+    })
+}
+
+fn parse_rgb_channel(text: &str) -> Option<f32> {
+    match text.strip_suffix('%') {
+        Some(percentage) => Some(percentage.parse::<f32>().ok()? / 100.),
+        None => Some(text.parse::<f32>().ok()? / 255.),
+    }
+    .map(|channel| channel.clamp(0., 1.))
+}
+
+fn parse_percentage(text: &str) -> Option<f32> {
+    let value = text.strip_suffix('%').unwrap_or(text).parse::<f32>().ok()?;
+    Some((value / 100.).clamp(0., 1.))
+}
+
+fn parse_fraction(text: &str) -> Option<f32> {
+    match text.strip_suffix('%') {
+        Some(percentage) => Some(percentage.parse::<f32>().ok()? / 100.),
+        None => text.parse::<f32>().ok(),
+    }
+    .map(|fraction| fraction.clamp(0., 1.))
+}
+
+fn parse_hue(text: &str) -> Option<f32> {
+    let degrees = text
+        .strip_suffix("deg")
+        .unwrap_or(text)
+        .parse::<f32>()
+        .ok()?;
+    Some(degrees.rem_euclid(360.) / 360.)
+}
+
+fn format_fraction(value: f32) -> String {
+    let value = (value.clamp(0., 1.) * 100.).round() / 100.;
+    format!("{value}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn assert_round_trips(text: &str) {
+        let literal = ColorLiteral::parse(text).unwrap();
+        assert_eq!(literal.format.format(literal.color), text);
+    }
+
+    #[test]
+    fn test_color_literal_round_trip() {
+        assert_round_trips("#f80");
+        assert_round_trips("#F80A");
+        assert_round_trips("#ff8800");
+        assert_round_trips("#ff880080");
+        assert_round_trips("rgb(255 136 0)");
+        assert_round_trips("rgb(255 136 0 / 0.5)");
+        assert_round_trips("rgba(255, 136, 0, 0.5)");
+        assert_round_trips("rgb(255, 136, 0)");
+        assert_round_trips("hsl(120 100% 50%)");
+        assert_round_trips("hsla(120, 100%, 50%, 0.25)");
+        assert_eq!(ColorLiteral::parse("red"), None);
+        assert_eq!(ColorLiteral::parse("#ff88zz"), None);
+        assert_eq!(ColorLiteral::parse("rgb(1 2)"), None);
+    }
+
+    #[test]
+    fn test_color_literal_keeps_format() {
+        let literal = ColorLiteral::parse("#f80").unwrap();
+        let edited = Rgba {
+            r: 0.,
+            g: 0.2,
+            b: 1.,
+            a: 1.,
+        };
+        assert_eq!(literal.format.format(edited), "#03f");
+
+        // Colors that can't be written in the short form fall back to the long one, and an
+        // alpha component is added when needed.
+        let edited = Rgba { a: 0.5, ..edited };
+        assert_eq!(literal.format.format(edited), "#0033ff80");
+        assert_eq!(
+            ColorLiteral::parse("rgb(255, 0, 0)")
+                .unwrap()
+                .format
+                .format(edited),
+            "rgba(0, 51, 255, 0.5)"
+        );
+        assert_eq!(
+            ColorLiteral::parse("hsl(0 100% 50%)")
+                .unwrap()
+                .format
+                .format(edited),
+            "hsl(228 100% 50% / 0.5)"
+        );
+    }
+}
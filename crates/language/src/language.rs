@@ -7,6 +7,7 @@
 //!
 //! Notably we do *not* assign a single language to a single file; in real world a single file can consist of multiple programming languages - HTML is a good example of that - and `language` crate tends to reflect that status quo in its API.
 mod buffer;
+mod color_literal;
 mod diagnostic_set;
 mod highlight_map;
 mod language_registry;
@@ -14,6 +15,7 @@ pub mod language_settings;
 mod manifest;
 mod outline;
 pub mod proto;
+mod spell_check;
 mod syntax_map;
 mod task_context;
 mod text_diff;
@@ -27,6 +29,7 @@ pub use crate::language_settings::{EditPredictionsMode, IndentGuideSettings};
 use anyhow::{Context as _, Result};
 use async_trait::async_trait;
 use collections::{HashMap, HashSet, IndexSet};
+pub use color_literal::{ColorFormat, ColorLiteral, ColorSpace};
 use futures::Future;
 use gpui::{App, AsyncApp, Entity, SharedString};
 pub use highlight_map::HighlightMap;
@@ -58,7 +61,7 @@ use std::{
         atomic::{AtomicUsize, Ordering::SeqCst},
     },
 };
-use syntax_map::{QueryCursorHandle, SyntaxSnapshot};
+use syntax_map::{QueryCursorHandle, SyntaxMap, SyntaxSnapshot};
 use task::RunnableTag;
 pub use task_context::{ContextLocation, ContextProvider, RunnableRange};
 pub use text_diff::{
@@ -75,14 +78,19 @@ use util::serde::default_true;
 pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::{DiagnosticEntry, DiagnosticGroup};
+use language_registry::InjectionRule;
 pub use language_registry::{
     AvailableLanguage, BinaryStatus, LanguageNotFound, LanguageQueries, LanguageRegistry,
     QUERY_FILENAME_PREFIXES,
 };
 pub use lsp::{LanguageServerId, LanguageServerName};
 pub use outline::*;
+pub use spell_check::{SpellCheckDictionary, words_to_spell_check};
+#[cfg(any(test, feature = "test-support"))]
+pub use syntax_map::check_random_syntax_map_edits;
 pub use syntax_map::{
-    OwnedSyntaxLayer, SyntaxLayer, SyntaxMapMatches, ToTreeSitterPoint, TreeSitterOptions,
+    CaptureStack, ChangedSyntaxRegion, DEFAULT_CAPTURE_PRIORITY, OwnedSyntaxLayer, SyntaxLayer,
+    SyntaxMapMatches, ToTreeSitterPoint, TreeSitterOptions,
 };
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
@@ -157,7 +165,8 @@ pub struct Location {
     pub range: Range<Anchor>,
 }
 
-type ServerBinaryCache = futures::lock::Mutex<Option<(bool, LanguageServerBinary)>>;
+type ServerBinaryCache =
+    futures::lock::Mutex<Option<(LanguageServerBinaryOptions, LanguageServerBinary)>>;
 
 /// Represents a Language Server, with certain cached sync properties.
 /// Uses [`LspAdapter`] under the hood, but calls all 'static' methods
@@ -497,6 +506,18 @@ pub trait LspInstaller {
         cx: &mut AsyncApp,
     ) -> impl Future<Output = Result<Self::BinaryVersion>>;
 
+    /// Resolves a specific version of the server, for when the user has pinned one
+    /// in their settings. Adapters that can't fetch a particular version fail.
+    fn fetch_server_version(
+        &self,
+        version: &str,
+        _delegate: &dyn LspAdapterDelegate,
+        _cx: &mut AsyncApp,
+    ) -> impl Future<Output = Result<Self::BinaryVersion>> {
+        let version = version.to_string();
+        async move { anyhow::bail!("this language server can't be pinned to version {version}") }
+    }
+
     fn check_if_version_installed(
         &self,
         _version: &Self::BinaryVersion,
@@ -527,6 +548,7 @@ pub trait DynLspInstaller {
         delegate: &Arc<dyn LspAdapterDelegate>,
         container_dir: PathBuf,
         pre_release: bool,
+        pinned_version: Option<&str>,
         cx: &mut AsyncApp,
     ) -> Result<LanguageServerBinary>;
     fn get_language_server_command<'a>(
@@ -534,7 +556,7 @@ pub trait DynLspInstaller {
         delegate: Arc<dyn LspAdapterDelegate>,
         toolchains: Option<Toolchain>,
         binary_options: LanguageServerBinaryOptions,
-        cached_binary: &'a mut Option<(bool, LanguageServerBinary)>,
+        cached_binary: &'a mut Option<(LanguageServerBinaryOptions, LanguageServerBinary)>,
         cx: &'a mut AsyncApp,
     ) -> Pin<Box<dyn 'a + Future<Output = Result<LanguageServerBinary>>>>;
 }
@@ -549,16 +571,24 @@ where
         delegate: &Arc<dyn LspAdapterDelegate>,
         container_dir: PathBuf,
         pre_release: bool,
+        pinned_version: Option<&str>,
         cx: &mut AsyncApp,
     ) -> Result<LanguageServerBinary> {
         let name = self.name();
 
-        log::debug!("fetching latest version of language server {:?}", name.0);
         delegate.update_status(name.clone(), BinaryStatus::CheckingForUpdate);
-
-        let latest_version = self
-            .fetch_latest_server_version(delegate.as_ref(), pre_release, cx)
-            .await?;
+        let latest_version = if let Some(pinned_version) = pinned_version {
+            log::debug!(
+                "fetching pinned version {pinned_version} of language server {:?}",
+                name.0
+            );
+            self.fetch_server_version(pinned_version, delegate.as_ref(), cx)
+                .await?
+        } else {
+            log::debug!("fetching latest version of language server {:?}", name.0);
+            self.fetch_latest_server_version(delegate.as_ref(), pre_release, cx)
+                .await?
+        };
 
         if let Some(binary) = self
             .check_if_version_installed(&latest_version, &container_dir, delegate.as_ref())
@@ -583,7 +613,7 @@ where
         delegate: Arc<dyn LspAdapterDelegate>,
         toolchain: Option<Toolchain>,
         binary_options: LanguageServerBinaryOptions,
-        cached_binary: &'a mut Option<(bool, LanguageServerBinary)>,
+        cached_binary: &'a mut Option<(LanguageServerBinaryOptions, LanguageServerBinary)>,
         cx: &'a mut AsyncApp,
     ) -> Pin<Box<dyn 'a + Future<Output = Result<LanguageServerBinary>>>> {
         async move {
@@ -617,8 +647,9 @@ where
                 "downloading language servers disabled"
             );
 
-            if let Some((pre_release, cached_binary)) = cached_binary
-                && *pre_release == binary_options.pre_release
+            if let Some((cached_options, cached_binary)) = cached_binary
+                && cached_options.pre_release == binary_options.pre_release
+                && cached_options.version == binary_options.version
             {
                 return Ok(cached_binary.clone());
             }
@@ -633,6 +664,7 @@ where
                     &delegate,
                     container_dir.to_path_buf(),
                     binary_options.pre_release,
+                    binary_options.version.as_deref(),
                     cx,
                 )
                 .await;
@@ -661,7 +693,7 @@ where
             }
 
             if let Ok(binary) = &binary {
-                *cached_binary = Some((binary_options.pre_release, binary.clone()));
+                *cached_binary = Some((binary_options, binary.clone()));
             }
 
             binary
@@ -780,6 +812,10 @@ pub struct LanguageConfig {
     /// A list of characters that Zed should treat as word characters for completion queries.
     #[serde(default)]
     pub completion_query_characters: HashSet<char>,
+    /// The quote that this language's string literals are written with by default. String
+    /// literals in text pasted with `editor::SmartPaste` are rewritten to use it.
+    #[serde(default)]
+    pub string_quote: Option<char>,
     /// A list of preferred debuggers for this language.
     #[serde(default)]
     pub debuggers: IndexSet<SharedString>,
@@ -974,6 +1010,7 @@ impl Default for LanguageConfig {
             hidden: false,
             jsx_tag_auto_close: None,
             completion_query_characters: Default::default(),
+            string_quote: None,
             debuggers: Default::default(),
         }
     }
@@ -1070,6 +1107,10 @@ pub struct BracketPairConfig {
     /// A list of tree-sitter scopes for which a given bracket should not be active.
     /// N-th entry in `[Self::disabled_scopes_by_bracket_ix]` contains a list of disabled scopes for an n-th entry in `[Self::pairs]`
     pub disabled_scopes_by_bracket_ix: Vec<Vec<String>>,
+    /// A list of tree-sitter scopes to which a given bracket is restricted.
+    /// N-th entry in `[Self::enabled_scopes_by_bracket_ix]` contains a list of scopes for an n-th entry in `[Self::pairs]`,
+    /// an empty list means that the bracket is active in every scope not listed in `[Self::disabled_scopes_by_bracket_ix]`.
+    pub enabled_scopes_by_bracket_ix: Vec<Vec<String>>,
 }
 
 impl BracketPairConfig {
@@ -1084,6 +1125,8 @@ pub struct BracketPairContent {
     pub bracket_pair: BracketPair,
     #[serde(default)]
     pub not_in: Vec<String>,
+    #[serde(default)]
+    pub only_in: Vec<String>,
 }
 
 impl<'de> Deserialize<'de> for BracketPairConfig {
@@ -1092,15 +1135,13 @@ impl<'de> Deserialize<'de> for BracketPairConfig {
         D: Deserializer<'de>,
     {
         let result = Vec::<BracketPairContent>::deserialize(deserializer)?;
-        let (brackets, disabled_scopes_by_bracket_ix) = result
-            .into_iter()
-            .map(|entry| (entry.bracket_pair, entry.not_in))
-            .unzip();
-
-        Ok(BracketPairConfig {
-            pairs: brackets,
-            disabled_scopes_by_bracket_ix,
-        })
+        let mut config = BracketPairConfig::default();
+        for entry in result {
+            config.pairs.push(entry.bracket_pair);
+            config.disabled_scopes_by_bracket_ix.push(entry.not_in);
+            config.enabled_scopes_by_bracket_ix.push(entry.only_in);
+        }
+        Ok(config)
     }
 }
 
@@ -1155,6 +1196,8 @@ pub struct Grammar {
     pub(crate) error_query: Option<Query>,
     pub highlights_config: Option<HighlightsConfig>,
     pub(crate) brackets_config: Option<BracketsConfig>,
+    pub(crate) keyword_pairs_config: Option<KeywordPairsConfig>,
+    pub(crate) colors_config: Option<ColorsConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
@@ -1216,6 +1259,7 @@ pub enum TextObject {
     AroundClass,
     InsideComment,
     AroundComment,
+    AroundImport,
 }
 
 impl TextObject {
@@ -1227,6 +1271,7 @@ impl TextObject {
             "class.around" => Some(TextObject::AroundClass),
             "comment.inside" => Some(TextObject::InsideComment),
             "comment.around" => Some(TextObject::AroundComment),
+            "import.around" => Some(TextObject::AroundImport),
             _ => None,
         }
     }
@@ -1296,6 +1341,11 @@ struct OverrideEntry {
 struct InjectionPatternConfig {
     language: Option<Box<str>>,
     combined: bool,
+    /// Extracts the injected language's name from the text of the `injection.language`
+    /// capture, such as a variable name or a comment preceding a string. The first capture
+    /// group is used if there is one, and the whole match otherwise. When the regex doesn't
+    /// match, nothing is injected.
+    language_regex: Option<Regex>,
 }
 
 #[derive(Debug)]
@@ -1309,6 +1359,23 @@ struct BracketsConfig {
 #[derive(Clone, Debug, Default)]
 struct BracketsPatternConfig {
     newline_only: bool,
+    rainbow_exclude: bool,
+}
+
+/// Keywords that delimit a construct the way brackets do, such as `if`, `else` and `fi`, or
+/// `#ifdef` and `#endif`. The `@keyword` captures of a match belong together, as do the
+/// captures of matches that share a keyword.
+#[derive(Debug)]
+struct KeywordPairsConfig {
+    query: Query,
+    keyword_capture_ix: u32,
+}
+
+/// Color literals, such as `#f80` or `rgb(255 136 0)`, captured as `@color`.
+#[derive(Debug)]
+struct ColorsConfig {
+    query: Query,
+    color_capture_ix: u32,
 }
 
 pub struct DebugVariablesConfig {
@@ -1338,6 +1405,8 @@ impl Language {
                     id: GrammarId::new(),
                     highlights_config: None,
                     brackets_config: None,
+                    keyword_pairs_config: None,
+                    colors_config: None,
                     outline_config: None,
                     text_object_config: None,
                     embedding_config: None,
@@ -1384,6 +1453,16 @@ impl Language {
                 .with_brackets_query(query.as_ref())
                 .context("Error loading brackets query")?;
         }
+        if let Some(query) = queries.keyword_pairs {
+            self = self
+                .with_keyword_pairs_query(query.as_ref())
+                .context("Error loading keyword pairs query")?;
+        }
+        if let Some(query) = queries.colors {
+            self = self
+                .with_colors_query(query.as_ref())
+                .context("Error loading colors query")?;
+        }
         if let Some(query) = queries.indents {
             self = self
                 .with_indents_query(query.as_ref())
@@ -1615,8 +1694,10 @@ impl Language {
                 .map(|ix| {
                     let mut config = BracketsPatternConfig::default();
                     for setting in query.property_settings(ix) {
-                        if setting.key.as_ref() == "newline.only" {
-                            config.newline_only = true
+                        match setting.key.as_ref() {
+                            "newline.only" => config.newline_only = true,
+                            "rainbow.exclude" => config.rainbow_exclude = true,
+                            _ => {}
                         }
                     }
                     config
@@ -1632,6 +1713,42 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_keyword_pairs_query(mut self, source: &str) -> Result<Self> {
+        let query = Query::new(&self.expect_grammar()?.ts_language, source)?;
+        let mut keyword_capture_ix = 0;
+        if populate_capture_indices(
+            &query,
+            &self.config.name,
+            "keyword_pairs",
+            &[],
+            &mut [Capture::Required("keyword", &mut keyword_capture_ix)],
+        ) {
+            self.grammar_mut()?.keyword_pairs_config = Some(KeywordPairsConfig {
+                query,
+                keyword_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn with_colors_query(mut self, source: &str) -> Result<Self> {
+        let query = Query::new(&self.expect_grammar()?.ts_language, source)?;
+        let mut color_capture_ix = 0;
+        if populate_capture_indices(
+            &query,
+            &self.config.name,
+            "colors",
+            &[],
+            &mut [Capture::Required("color", &mut color_capture_ix)],
+        ) {
+            self.grammar_mut()?.colors_config = Some(ColorsConfig {
+                query,
+                color_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_indents_query(mut self, source: &str) -> Result<Self> {
         let query = Query::new(&self.expect_grammar()?.ts_language, source)?;
         let mut indent_capture_ix = 0;
@@ -1712,12 +1829,29 @@ impl Language {
                             "combined" | "injection.combined" => {
                                 config.combined = true;
                             }
+                            "injection.language-regex" => {
+                                let source = setting.value.as_deref().with_context(|| {
+                                    format!(
+                                        "missing value for injection.language-regex in {} \
+                                        injections query",
+                                        self.config.name
+                                    )
+                                })?;
+                                config.language_regex =
+                                    Some(Regex::new(source).with_context(|| {
+                                        format!(
+                                            "invalid injection.language-regex in {} \
+                                            injections query",
+                                            self.config.name
+                                        )
+                                    })?);
+                            }
                             _ => {}
                         }
                     }
-                    config
+                    Ok(config)
                 })
-                .collect();
+                .collect::<Result<Vec<_>>>()?;
             if let Some(content_capture_ix) = content_capture_ix {
                 self.grammar_mut()?.injection_config = Some(InjectionConfig {
                     query,
@@ -1736,6 +1870,61 @@ impl Language {
         Ok(self)
     }
 
+    /// Builds the injection query from the language's own `injections.scm` source
+    /// together with injections registered at runtime. Rules whose query doesn't
+    /// compile against this language's grammar are logged and skipped.
+    pub(crate) fn with_injection_rules(
+        self,
+        source: Option<&str>,
+        rules: &[InjectionRule],
+    ) -> Result<Self> {
+        let mut source = source.unwrap_or_default().to_string();
+        let mut rule_pattern_counts = Vec::new();
+        {
+            let ts_language = &self.expect_grammar()?.ts_language;
+            // The captures of all patterns in an injection query have to use the same names.
+            let legacy_captures = !source.is_empty()
+                && Query::new(ts_language, &source).is_ok_and(|query| {
+                    query
+                        .capture_names()
+                        .iter()
+                        .any(|name| matches!(*name, "content" | "language"))
+                });
+            for rule in rules {
+                let rule_query = normalize_injection_captures(&rule.query, legacy_captures);
+                match Query::new(ts_language, &rule_query) {
+                    Ok(query) => {
+                        source.push('\n');
+                        source.push_str(&rule_query);
+                        rule_pattern_counts.push((rule, query.pattern_count()));
+                    }
+                    Err(error) => log::error!(
+                        "failed to compile {} injection for {}: {error}",
+                        rule.language,
+                        self.config.name,
+                    ),
+                }
+            }
+        }
+        if source.is_empty() {
+            return Ok(self);
+        }
+
+        let mut this = self.with_injection_query(&source)?;
+        if let Some(config) = this.grammar_mut()?.injection_config.as_mut() {
+            // Registered rules come after the language's own patterns, in order.
+            let mut patterns = config.patterns.iter_mut().rev();
+            for (rule, pattern_count) in rule_pattern_counts.into_iter().rev() {
+                for pattern in patterns.by_ref().take(pattern_count) {
+                    if pattern.language.is_none() {
+                        pattern.language = Some(rule.language.as_ref().into());
+                    }
+                }
+            }
+        }
+        Ok(this)
+    }
+
     pub fn with_override_query(mut self, source: &str) -> anyhow::Result<Self> {
         let query = Query::new(&self.expect_grammar()?.ts_language, source)?;
 
@@ -1778,6 +1967,7 @@ impl Language {
                 .brackets
                 .disabled_scopes_by_bracket_ix
                 .iter()
+                .chain(&self.config.brackets.enabled_scopes_by_bracket_ix)
                 .flatten(),
         );
 
@@ -1888,22 +2078,51 @@ impl Language {
                         .map(|config| &config.query)
                 });
             let highlight_maps = vec![grammar.highlight_map()];
-            let mut offset = 0;
-            for chunk in
-                BufferChunks::new(text, range, Some((captures, highlight_maps)), false, None)
-            {
-                let end_offset = offset + chunk.text.len();
-                if let Some(highlight_id) = chunk.syntax_highlight_id
-                    && !highlight_id.is_default()
-                {
-                    result.push((offset..end_offset, highlight_id));
-                }
-                offset = end_offset;
-            }
+            result = collect_highlights(text, range, captures, highlight_maps);
         }
         result
     }
 
+    /// Like [`Language::highlight_text`], but parses the text into a throwaway
+    /// syntax map so that injected languages (e.g. a `<script>` tag in HTML, or
+    /// a fenced code block in Markdown) are highlighted too.
+    ///
+    /// Injections whose language hasn't been loaded by the registry yet are left
+    /// unhighlighted, so this never blocks on loading a language.
+    pub fn highlight_text_with_injections(
+        self: &Arc<Self>,
+        text: &Rope,
+        range: Range<usize>,
+        language_registry: Arc<LanguageRegistry>,
+    ) -> Vec<(Range<usize>, HighlightId)> {
+        if self.grammar.is_none() {
+            return Vec::new();
+        }
+
+        let buffer = TextBuffer::new_normalized(
+            0,
+            BufferId::new(1).unwrap(),
+            LineEnding::default(),
+            text.clone(),
+        );
+        let snapshot = buffer.snapshot();
+        let mut syntax = SyntaxMap::new(&snapshot).snapshot();
+        syntax.reparse(&snapshot, Some(language_registry), self.clone());
+
+        let captures = syntax.captures(range.clone(), &snapshot, |grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+        let highlight_maps = captures
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.highlight_map())
+            .collect();
+        collect_highlights(snapshot.as_rope(), range, captures, highlight_maps)
+    }
+
     pub fn path_suffixes(&self) -> &[String] {
         &self.config.matcher.path_suffixes
     }
@@ -2029,6 +2248,9 @@ impl LanguageScope {
         let mut disabled_ids = self
             .config_override()
             .map_or(&[] as _, |o| o.disabled_bracket_ixs.as_slice());
+        let enabled_scopes_by_bracket_ix =
+            &self.language.config.brackets.enabled_scopes_by_bracket_ix;
+        let override_name = self.override_name();
         self.language
             .config
             .brackets
@@ -2043,6 +2265,12 @@ impl LanguageScope {
                     disabled_ids = &disabled_ids[1..];
                     is_enabled = false;
                 }
+                if let Some(enabled_scopes) = enabled_scopes_by_bracket_ix.get(ix)
+                    && !enabled_scopes.is_empty()
+                {
+                    is_enabled &= override_name
+                        .is_some_and(|name| enabled_scopes.iter().any(|scope| scope == name));
+                }
                 (bracket, is_enabled)
             })
     }
@@ -2051,6 +2279,11 @@ impl LanguageScope {
         c.is_whitespace() || self.language.config.autoclose_before.contains(c)
     }
 
+    /// Returns the quote that string literals are written with by default in this language.
+    pub fn string_quote(&self) -> Option<char> {
+        self.language.config.string_quote
+    }
+
     pub fn language_allowed(&self, name: &LanguageServerName) -> bool {
         let config = &self.language.config;
         let opt_in_servers = &config.scope_opt_in_language_servers;
@@ -2371,6 +2604,43 @@ impl LspAdapter for FakeLspAdapter {
     }
 }
 
+/// Collects the non-default highlights of `range` in `text`, with ranges
+/// relative to the start of `range`.
+fn collect_highlights<'a>(
+    text: &'a Rope,
+    range: Range<usize>,
+    captures: syntax_map::SyntaxMapCaptures<'a>,
+    highlight_maps: Vec<HighlightMap>,
+) -> Vec<(Range<usize>, HighlightId)> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    for chunk in BufferChunks::new(text, range, Some((captures, highlight_maps)), false, None) {
+        let end_offset = offset + chunk.text.len();
+        if let Some(highlight_id) = chunk.syntax_highlight_id
+            && !highlight_id.is_default()
+        {
+            result.push((offset..end_offset, highlight_id));
+        }
+        offset = end_offset;
+    }
+    result
+}
+
+/// Renames the injection captures of `query` to the legacy `@content` and `@language`
+/// names when `legacy` is set, and to `@injection.content` and `@injection.language`
+/// otherwise.
+fn normalize_injection_captures(query: &str, legacy: bool) -> std::borrow::Cow<'_, str> {
+    static INJECTION_CAPTURE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"@injection\.(content|language)\b").unwrap());
+    static LEGACY_CAPTURE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"@(content|language)\b").unwrap());
+    if legacy {
+        INJECTION_CAPTURE.replace_all(query, "@$1")
+    } else {
+        LEGACY_CAPTURE.replace_all(query, "@injection.$1")
+    }
+}
+
 enum Capture<'a> {
     Required(&'static str, &'a mut u32),
     Optional(&'static str, &'a mut Option<u32>),
@@ -2535,6 +2805,135 @@ mod tests {
         assert!(languages.language_for_name("Unknown").await.is_err());
     }
 
+    #[gpui::test]
+    async fn test_registering_injections(cx: &mut TestAppContext) {
+        let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+        languages.register_native_grammars([
+            ("json", tree_sitter_json::LANGUAGE),
+            ("rust", tree_sitter_rust::LANGUAGE),
+        ]);
+        languages.register_test_language(LanguageConfig {
+            name: "JSON".into(),
+            grammar: Some("json".into()),
+            ..Default::default()
+        });
+        languages.register_test_language(LanguageConfig {
+            name: "Rust".into(),
+            grammar: Some("rust".into()),
+            ..Default::default()
+        });
+
+        let rust = languages.language_for_name("Rust").await.unwrap();
+        assert!(rust.grammar().unwrap().injection_config.is_none());
+
+        let reload_count = languages.reload_count();
+        let version = languages.version();
+        languages.register_injection(
+            "Rust".into(),
+            "JSON".into(),
+            r#"
+            (macro_invocation
+                macro: (identifier) @_macro_name
+                (#eq? @_macro_name "json")
+                (token_tree) @injection.content)
+            "#,
+        );
+        // Only the parent language is reloaded.
+        assert_eq!(languages.reload_count(), reload_count);
+        assert_eq!(
+            languages.languages_reloaded_since(version),
+            [LanguageName::new("Rust")]
+        );
+
+        let reloaded_rust = languages.language_for_name("Rust").await.unwrap();
+        assert!(!Arc::ptr_eq(&rust, &reloaded_rust));
+        assert_eq!(rust.id(), reloaded_rust.id());
+        let config = reloaded_rust
+            .grammar()
+            .unwrap()
+            .injection_config
+            .as_ref()
+            .unwrap();
+        assert_eq!(config.patterns.len(), 1);
+        assert_eq!(config.patterns[0].language.as_deref(), Some("JSON"));
+
+        // Rules that don't compile against the parent's grammar are skipped.
+        languages.register_injection("Rust".into(), "JSON".into(), "(not_a_node) @content");
+        let reloaded_rust = languages.language_for_name("Rust").await.unwrap();
+        let config = reloaded_rust
+            .grammar()
+            .unwrap()
+            .injection_config
+            .as_ref()
+            .unwrap();
+        assert_eq!(config.patterns.len(), 1);
+
+        languages.remove_injections(&"Rust".into());
+        let reloaded_rust = languages.language_for_name("Rust").await.unwrap();
+        assert!(reloaded_rust.grammar().unwrap().injection_config.is_none());
+    }
+
+    #[gpui::test]
+    async fn test_registering_injections_with_legacy_captures(cx: &mut TestAppContext) {
+        let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+        languages.register_native_grammars([("rust", tree_sitter_rust::LANGUAGE)]);
+        languages.register_language(
+            "Rust".into(),
+            Some("rust".into()),
+            LanguageMatcher::default(),
+            false,
+            None,
+            Arc::new(|| {
+                Ok(LoadedLanguage {
+                    config: LanguageConfig {
+                        name: "Rust".into(),
+                        grammar: Some("rust".into()),
+                        ..Default::default()
+                    },
+                    queries: LanguageQueries {
+                        injections: Some(
+                            r#"((line_comment) @content (#set! "language" "comment"))"#.into(),
+                        ),
+                        ..Default::default()
+                    },
+                    context_provider: None,
+                    toolchain_provider: None,
+                    manifest_name: None,
+                })
+            }),
+        );
+
+        // The rule's captures are renamed to match the legacy names used by the
+        // language's own injections.
+        languages.register_injection(
+            "Rust".into(),
+            "JSON".into(),
+            r#"
+            (macro_invocation
+                macro: (identifier) @_macro_name
+                (#eq? @_macro_name "json")
+                (token_tree) @injection.content)
+            "#,
+        );
+        let rust = languages.language_for_name("Rust").await.unwrap();
+        let config = rust.grammar().unwrap().injection_config.as_ref().unwrap();
+        assert_eq!(config.patterns.len(), 2);
+        assert_eq!(config.patterns[0].language.as_deref(), Some("comment"));
+        assert_eq!(config.patterns[1].language.as_deref(), Some("JSON"));
+    }
+
+    #[test]
+    fn test_normalize_injection_captures() {
+        assert_eq!(
+            normalize_injection_captures("(string) @injection.content", true),
+            "(string) @content"
+        );
+        assert_eq!(
+            normalize_injection_captures("(string) @content (#eq? @content_kind \"x\")", false),
+            "(string) @injection.content (#eq? @content_kind \"x\")"
+        );
+    }
+
     #[gpui::test]
     async fn test_completion_label_omits_duplicate_data() {
         let regular_completion_item_1 = lsp::CompletionItem {
@@ -2701,4 +3100,152 @@ mod tests {
             assert_eq!(config.tab_size, 0);
         }
     }
+
+    #[gpui::test]
+    async fn test_pinned_language_server_version(cx: &mut TestAppContext) {
+        let adapter = Arc::new(VersionedLspAdapter::default());
+        let delegate: Arc<dyn LspAdapterDelegate> = Arc::new(DownloadDirDelegate);
+        let mut cached_binary = None;
+        let mut cx = cx.to_async();
+        let mut server_path = |version: Option<&str>| {
+            let binary = adapter.clone().get_language_server_command(
+                delegate.clone(),
+                None,
+                LanguageServerBinaryOptions {
+                    allow_path_lookup: false,
+                    allow_binary_download: true,
+                    pre_release: false,
+                    version: version.map(ToString::to_string),
+                },
+                &mut cached_binary,
+                &mut cx,
+            );
+            futures::executor::block_on(binary).unwrap().path
+        };
+
+        assert_eq!(server_path(None), PathBuf::from("/servers/2.0.0"));
+        assert_eq!(server_path(None), PathBuf::from("/servers/2.0.0"));
+        assert_eq!(*adapter.fetched_versions.lock(), ["2.0.0"]);
+
+        // Pinning a version invalidates the cached binary and fetches that version.
+        assert_eq!(server_path(Some("1.0.0")), PathBuf::from("/servers/1.0.0"));
+        assert_eq!(server_path(Some("1.0.0")), PathBuf::from("/servers/1.0.0"));
+        assert_eq!(*adapter.fetched_versions.lock(), ["2.0.0", "1.0.0"]);
+
+        // Unpinning it goes back to the latest version.
+        assert_eq!(server_path(None), PathBuf::from("/servers/2.0.0"));
+        assert_eq!(
+            *adapter.fetched_versions.lock(),
+            ["2.0.0", "1.0.0", "2.0.0"]
+        );
+    }
+
+    #[derive(Default)]
+    struct VersionedLspAdapter {
+        fetched_versions: Mutex<Vec<String>>,
+    }
+
+    impl LspInstaller for VersionedLspAdapter {
+        type BinaryVersion = String;
+
+        async fn fetch_latest_server_version(
+            &self,
+            _: &dyn LspAdapterDelegate,
+            _: bool,
+            _: &mut AsyncApp,
+        ) -> Result<String> {
+            Ok("2.0.0".into())
+        }
+
+        async fn fetch_server_version(
+            &self,
+            version: &str,
+            _: &dyn LspAdapterDelegate,
+            _: &mut AsyncApp,
+        ) -> Result<String> {
+            Ok(version.into())
+        }
+
+        async fn fetch_server_binary(
+            &self,
+            version: String,
+            container_dir: PathBuf,
+            _: &dyn LspAdapterDelegate,
+        ) -> Result<LanguageServerBinary> {
+            let path = container_dir.join(&version);
+            self.fetched_versions.lock().push(version);
+            Ok(LanguageServerBinary {
+                path,
+                arguments: Vec::new(),
+                env: None,
+            })
+        }
+
+        async fn cached_server_binary(
+            &self,
+            _: PathBuf,
+            _: &dyn LspAdapterDelegate,
+        ) -> Option<LanguageServerBinary> {
+            None
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl LspAdapter for VersionedLspAdapter {
+        fn name(&self) -> LanguageServerName {
+            LanguageServerName("versioned-language-server".into())
+        }
+    }
+
+    struct DownloadDirDelegate;
+
+    #[async_trait]
+    impl LspAdapterDelegate for DownloadDirDelegate {
+        fn show_notification(&self, _: &str, _: &mut App) {}
+
+        fn http_client(&self) -> Arc<dyn HttpClient> {
+            unimplemented!()
+        }
+
+        fn worktree_id(&self) -> WorktreeId {
+            WorktreeId::from_usize(0)
+        }
+
+        fn worktree_root_path(&self) -> &Path {
+            Path::new("/root")
+        }
+
+        fn update_status(&self, _: LanguageServerName, _: BinaryStatus) {}
+
+        fn registered_lsp_adapters(&self) -> Vec<Arc<dyn LspAdapter>> {
+            Vec::new()
+        }
+
+        async fn language_server_download_dir(&self, _: &LanguageServerName) -> Option<Arc<Path>> {
+            Some(Path::new("/servers").into())
+        }
+
+        async fn npm_package_installed_version(
+            &self,
+            _: &str,
+        ) -> Result<Option<(PathBuf, String)>> {
+            Ok(None)
+        }
+
+        async fn which(&self, _: &OsStr) -> Option<PathBuf> {
+            None
+        }
+
+        async fn shell_env(&self) -> HashMap<String, String> {
+            HashMap::default()
+        }
+
+        async fn read_text_file(&self, _: PathBuf) -> Result<String> {
+            unimplemented!()
+        }
+
+        async fn try_exec(&self, _: LanguageServerBinary) -> Result<()> {
+            unimplemented!()
+        }
+    }
 }
@@ -120,6 +120,10 @@ struct LanguageRegistryState {
     loading_languages: HashMap<LanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
     subscription: (watch::Sender<()>, watch::Receiver<()>),
     theme: Option<Arc<Theme>>,
+    injection_rules: HashMap<LanguageName, Vec<InjectionRule>>,
+    /// The languages that were reloaded on their own, with the version of the registry
+    /// when that last happened.
+    reloaded_languages: HashMap<LanguageName, usize>,
     version: usize,
     reload_count: usize,
 
@@ -220,6 +224,8 @@ pub const QUERY_FILENAME_PREFIXES: &[(
 )] = &[
     ("highlights", |q| &mut q.highlights),
     ("brackets", |q| &mut q.brackets),
+    ("keyword_pairs", |q| &mut q.keyword_pairs),
+    ("colors", |q| &mut q.colors),
     ("outline", |q| &mut q.outline),
     ("indents", |q| &mut q.indents),
     ("embedding", |q| &mut q.embedding),
@@ -236,6 +242,8 @@ pub const QUERY_FILENAME_PREFIXES: &[(
 pub struct LanguageQueries {
     pub highlights: Option<Cow<'static, str>>,
     pub brackets: Option<Cow<'static, str>>,
+    pub keyword_pairs: Option<Cow<'static, str>>,
+    pub colors: Option<Cow<'static, str>>,
     pub indents: Option<Cow<'static, str>>,
     pub outline: Option<Cow<'static, str>>,
     pub embedding: Option<Cow<'static, str>>,
@@ -247,6 +255,15 @@ pub struct LanguageQueries {
     pub debugger: Option<Cow<'static, str>>,
 }
 
+/// An injection registered at runtime with [`LanguageRegistry::register_injection`].
+#[derive(Clone, Debug)]
+pub(crate) struct InjectionRule {
+    /// The language injected by patterns that don't set their own `injection.language`.
+    pub language: LanguageName,
+    /// A Tree-sitter query against the parent language's grammar, capturing `@injection.content`.
+    pub query: Arc<str>,
+}
+
 #[derive(Clone, Default)]
 struct ServerStatusSender {
     txs: Arc<Mutex<Vec<mpsc::UnboundedSender<(LanguageServerName, BinaryStatus)>>>>,
@@ -275,6 +292,8 @@ impl LanguageRegistry {
                 available_lsp_adapters: HashMap::default(),
                 subscription: watch::channel(),
                 theme: Default::default(),
+                injection_rules: Default::default(),
+                reloaded_languages: Default::default(),
                 version: 0,
                 reload_count: 0,
 
@@ -301,6 +320,43 @@ impl LanguageRegistry {
         self.state.write().reload();
     }
 
+    /// Registers an injection for `parent_language` in addition to the ones from its
+    /// `injections.scm`, e.g. to highlight the contents of `sql!()` macros in Rust as SQL.
+    ///
+    /// `query` is a Tree-sitter query against the parent language's grammar that
+    /// captures the injected text as `@injection.content`. Each of its patterns injects
+    /// `language`, unless the pattern sets its own `injection.language`.
+    ///
+    /// If the parent language has already been loaded, it is reloaded, so that the open
+    /// buffers in that language are reparsed with the new injection. Unlike
+    /// [`LanguageRegistry::reload`], this doesn't affect buffers in other languages.
+    pub fn register_injection(
+        &self,
+        parent_language: LanguageName,
+        language: LanguageName,
+        query: impl Into<Arc<str>>,
+    ) {
+        let mut state = self.state.write();
+        state
+            .injection_rules
+            .entry(parent_language.clone())
+            .or_default()
+            .push(InjectionRule {
+                language,
+                query: query.into(),
+            });
+        state.reload_languages(&[parent_language]);
+    }
+
+    /// Removes all injections registered for `parent_language` with
+    /// [`LanguageRegistry::register_injection`].
+    pub fn remove_injections(&self, parent_language: &LanguageName) {
+        let mut state = self.state.write();
+        if state.injection_rules.remove(parent_language).is_some() {
+            state.reload_languages(&[parent_language.clone()]);
+        }
+    }
+
     /// Reorders the list of language servers for the given language.
     ///
     /// Uses the provided list of ordered [`CachedLspAdapters`] as the desired order.
@@ -616,6 +672,18 @@ impl LanguageRegistry {
         self.state.read().reload_count
     }
 
+    /// Returns the languages that were reloaded on their own after the registry
+    /// had the given [`LanguageRegistry::version`].
+    pub fn languages_reloaded_since(&self, version: usize) -> Vec<LanguageName> {
+        self.state
+            .read()
+            .reloaded_languages
+            .iter()
+            .filter(|(_, reloaded_at)| **reloaded_at > version)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
     pub fn set_theme(&self, theme: Arc<Theme>) {
         let mut state = self.state.write();
         state.theme = Some(theme.clone());
@@ -945,15 +1013,35 @@ impl LanguageRegistry {
                 self.executor
                     .spawn(async move {
                         let language = async {
-                            let loaded_language = (language_load)()?;
+                            let mut loaded_language = (language_load)()?;
                             if let Some(grammar) = loaded_language.config.grammar.clone() {
                                 let grammar = Some(this.get_or_load_grammar(grammar).await?);
-
-                                Language::new_with_id(id, loaded_language.config, grammar)
-                                    .with_context_provider(loaded_language.context_provider)
-                                    .with_toolchain_lister(loaded_language.toolchain_provider)
-                                    .with_manifest(loaded_language.manifest_name)
-                                    .with_queries(loaded_language.queries)
+                                let injection_rules = this
+                                    .state
+                                    .read()
+                                    .injection_rules
+                                    .get(&name)
+                                    .cloned()
+                                    .unwrap_or_default();
+                                let injections = if injection_rules.is_empty() {
+                                    None
+                                } else {
+                                    Some(loaded_language.queries.injections.take())
+                                };
+
+                                let language =
+                                    Language::new_with_id(id, loaded_language.config, grammar)
+                                        .with_context_provider(loaded_language.context_provider)
+                                        .with_toolchain_lister(loaded_language.toolchain_provider)
+                                        .with_manifest(loaded_language.manifest_name)
+                                        .with_queries(loaded_language.queries)?;
+                                match injections {
+                                    Some(injections) => language.with_injection_rules(
+                                        injections.as_deref(),
+                                        &injection_rules,
+                                    ),
+                                    None => Ok(language),
+                                }
                             } else {
                                 Ok(Language::new_with_id(id, loaded_language.config, None)
                                     .with_context_provider(loaded_language.context_provider)
@@ -1204,6 +1292,24 @@ impl LanguageRegistryState {
         *self.subscription.0.borrow_mut() = ();
     }
 
+    /// Unloads the given languages, so that they're loaded again from their
+    /// [`AvailableLanguage`] the next time they're requested.
+    fn reload_languages(&mut self, languages_to_reload: &[LanguageName]) {
+        self.languages
+            .retain(|language| !languages_to_reload.contains(&language.name()));
+        for language in &mut self.available_languages {
+            if languages_to_reload.contains(&language.name) {
+                language.loaded = false;
+            }
+        }
+        self.version += 1;
+        for language in languages_to_reload {
+            self.reloaded_languages
+                .insert(language.clone(), self.version);
+        }
+        *self.subscription.0.borrow_mut() = ();
+    }
+
     fn reload(&mut self) {
         self.languages.clear();
         self.version += 1;
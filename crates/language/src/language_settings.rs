@@ -4,20 +4,32 @@ use crate::{File, Language, LanguageName, LanguageServerName};
 use collections::{FxHashMap, HashMap, HashSet};
 use ec4rs::{
     Properties as EditorconfigProperties,
-    property::{FinalNewline, IndentSize, IndentStyle, MaxLineLen, TabWidth, TrimTrailingWs},
+    property::{
+        Charset, EndOfLine, FinalNewline, IndentSize, IndentStyle, MaxLineLen, TabWidth,
+        TrimTrailingWs,
+    },
 };
 use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
 use gpui::{App, Modifiers};
 use itertools::{Either, Itertools};
 
 pub use settings::{
-    CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
-    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, SelectedFormatter, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    CodeActionsOnFormatOrder, CompletionSettingsContent, EditPredictionProvider,
+    EditPredictionsMode, FormatOnSave, Formatter, FormatterList, InlayHintKind,
+    LanguageSettingsContent, LspInsertMode, RewrapBehavior, SelectedFormatter,
+    ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
 };
-use settings::{ExtendingVec, Settings, SettingsContent, SettingsLocation, SettingsStore};
+use settings::{FileTypeAssociation, Settings, SettingsContent, SettingsLocation, SettingsStore};
 use shellexpand;
-use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use text::LineEnding;
+use util::ResultExt as _;
 
 /// Initializes the language settings.
 pub fn init(cx: &mut App) {
@@ -37,6 +49,33 @@ pub fn language_settings<'a>(
     AllLanguageSettings::get(location, cx).language(location, language.as_ref(), cx)
 }
 
+/// Returns the settings for the specified language from the provided file, with the
+/// indentation detected in the buffer's contents used in place of the configured one
+/// when `detect_indentation` is enabled.
+pub fn language_settings_with_detected_indent<'a>(
+    language: Option<LanguageName>,
+    file: Option<&'a Arc<dyn File>>,
+    detected_indent: Option<crate::IndentSize>,
+    cx: &'a App,
+) -> Cow<'a, LanguageSettings> {
+    let mut settings = language_settings(language, file, cx);
+    if let Some(indent) = detected_indent
+        && settings.detect_indentation
+    {
+        let settings = settings.to_mut();
+        match indent.kind {
+            crate::IndentKind::Tab => settings.hard_tabs = true,
+            crate::IndentKind::Space => {
+                settings.hard_tabs = false;
+                if let Some(tab_size) = NonZeroU32::new(indent.len) {
+                    settings.tab_size = tab_size;
+                }
+            }
+        }
+    }
+    settings
+}
+
 /// Returns the settings for all languages from the provided file.
 pub fn all_language_settings<'a>(
     file: Option<&'a Arc<dyn File>>,
@@ -57,6 +96,7 @@ pub struct AllLanguageSettings {
     pub defaults: LanguageSettings,
     languages: HashMap<LanguageName, LanguageSettings>,
     pub(crate) file_types: FxHashMap<Arc<str>, GlobSet>,
+    file_type_patterns: Arc<BTreeMap<Arc<str>, Vec<String>>>,
 }
 
 /// The settings for a particular language.
@@ -67,11 +107,17 @@ pub struct LanguageSettings {
     /// Whether to indent lines using tab characters, as opposed to multiple
     /// spaces.
     pub hard_tabs: bool,
+    /// Whether to infer the indentation of files from their contents when they're opened,
+    /// using it instead of `tab_size` and `hard_tabs`.
+    pub detect_indentation: bool,
     /// How to soft-wrap long lines of text.
     pub soft_wrap: settings::SoftWrap,
     /// The column at which to soft-wrap lines, for buffers where soft-wrap
     /// is enabled.
     pub preferred_line_length: u32,
+    /// How many columns further than the line they continue to indent soft-wrapped lines,
+    /// for buffers where soft-wrap is set to `hanging_indent`.
+    pub soft_wrap_hanging_indent: u32,
     /// Whether to show wrap guides (vertical rulers) in the editor.
     /// Setting this to true will show a guide at the 'preferred_line_length' value
     /// if softwrap is set to 'preferred_line_length', and will show any
@@ -81,14 +127,27 @@ pub struct LanguageSettings {
     pub wrap_guides: Vec<usize>,
     /// Indent guide related settings.
     pub indent_guides: IndentGuideSettings,
+    /// Reading mode related settings.
+    pub reading_mode: ReadingModeSettings,
+    /// Rules pairing files with their related files.
+    pub related_files: Vec<settings::RelatedFileRule>,
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
     /// before saving it.
     pub remove_trailing_whitespace_on_save: bool,
+    /// Whether to leave the trailing whitespace on the lines with a cursor alone
+    /// when removing trailing whitespace on save.
+    pub keep_trailing_whitespace_on_cursor_line: bool,
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// The line ending to convert a buffer to when saving it, from the `end_of_line`
+    /// property of `.editorconfig` files.
+    pub line_ending_on_save: Option<LineEnding>,
+    /// Whether a buffer should start with a UTF-8 byte order mark when it's saved, from
+    /// the `charset` property of `.editorconfig` files. Only UTF-8 charsets are supported.
+    pub byte_order_mark_on_save: Option<bool>,
     /// How to perform a buffer format.
     pub formatter: settings::SelectedFormatter,
     /// Zed's Prettier integration settings.
@@ -121,6 +180,9 @@ pub struct LanguageSettings {
     pub whitespace_map: settings::WhitespaceMap,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
     pub extend_comment_on_newline: bool,
+    /// Whether to tint the background of regions where this language is embedded
+    /// in another one.
+    pub tint_injections: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
     /// Whether to automatically close brackets.
@@ -138,6 +200,10 @@ pub struct LanguageSettings {
     pub always_treat_brackets_as_autoclosed: bool,
     /// Which code actions to run on save
     pub code_actions_on_format: HashMap<String, bool>,
+    /// Whether to run the code actions on save before or after the formatter.
+    pub code_actions_on_format_order: CodeActionsOnFormatOrder,
+    /// How long to wait for the code actions on save, in milliseconds. 0 waits indefinitely.
+    pub code_actions_on_format_timeout_ms: u64,
     /// Whether to perform linked edits
     pub linked_edits: bool,
     /// Task configuration for this language.
@@ -150,6 +216,8 @@ pub struct LanguageSettings {
     pub show_completion_documentation: bool,
     /// Completion settings for this language.
     pub completions: CompletionSettings,
+    /// Spell checking settings for this language.
+    pub spell_check: SpellCheckSettings,
     /// Preferred debuggers for this language.
     pub debuggers: Vec<String>,
 }
@@ -179,6 +247,31 @@ pub struct CompletionSettings {
     ///
     /// Default: "replace_suffix"
     pub lsp_insert_mode: LspInsertMode,
+    /// Whether to accept a completion automatically once typing narrows the
+    /// completions menu down to that single completion.
+    ///
+    /// Default: false
+    pub auto_confirm_single_match: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpellCheckSettings {
+    /// Whether to underline misspelled words and suggest corrections for them.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// Whether to check all of the text, rather than only comments and strings.
+    ///
+    /// Default: false
+    pub check_all_text: bool,
+    /// Paths to the word lists of correctly spelled words.
+    ///
+    /// Default: []
+    pub dictionaries: Vec<PathBuf>,
+    /// Additional words that are spelled correctly.
+    ///
+    /// Default: []
+    pub words: Vec<String>,
 }
 
 /// The settings for indent guides.
@@ -206,6 +299,23 @@ pub struct IndentGuideSettings {
     pub background_coloring: settings::IndentGuideBackgroundColoring,
 }
 
+/// The settings for reading mode, a prose-focused presentation of the editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadingModeSettings {
+    /// Whether to present buffers in reading mode.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The column at which to soft wrap lines in reading mode.
+    ///
+    /// Default: 72
+    pub measure: u32,
+    /// Whether to keep the line with the cursor vertically centered in reading mode.
+    ///
+    /// Default: false
+    pub typewriter_scrolling: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct LanguageTaskSettings {
     /// Extra task variables to set for a particular language.
@@ -219,6 +329,9 @@ pub struct LanguageTaskSettings {
     /// * Zed task from either of the task config file
     /// * Zed task from history (e.g. one-off task was spawned before)
     pub prefer_lsp: bool,
+    /// The interpreter that `task: quick eval` runs code with: a command, followed by
+    /// the arguments that come before the script path. Empty if the language has none.
+    pub interpreter: Vec<String>,
 }
 
 /// Allows to enable/disable formatting with Prettier
@@ -407,6 +520,12 @@ pub struct CopilotSettings {
 }
 
 impl AllLanguageSettings {
+    /// Returns the patterns the `file_types` setting associates with each language, which can
+    /// be compared to tell whether the languages of files need to be detected again.
+    pub fn file_type_patterns(&self) -> &Arc<BTreeMap<Arc<str>, Vec<String>>> {
+        &self.file_type_patterns
+    }
+
     /// Returns the [`LanguageSettings`] for the language with the specified name.
     pub fn language<'a>(
         &'a self,
@@ -475,6 +594,19 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
             TrimTrailingWs::Value(b) => b,
         })
         .ok();
+    let line_ending_on_save = cfg.get::<EndOfLine>().ok().and_then(|v| match v {
+        EndOfLine::Lf => Some(LineEnding::Unix),
+        EndOfLine::CrLf => Some(LineEnding::Windows),
+        EndOfLine::Cr => None,
+    });
+    let byte_order_mark_on_save = cfg.get::<Charset>().ok().and_then(|v| match v {
+        Charset::Utf8 => Some(false),
+        Charset::Utf8Bom => Some(true),
+        _ => None,
+    });
+    // Indentation that's spelled out in an `.editorconfig` takes precedence over the
+    // indentation detected from a file's contents.
+    let detect_indentation = (hard_tabs.is_some() || tab_size.is_some()).then_some(false);
     fn merge<T>(target: &mut T, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
@@ -491,6 +623,15 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
         &mut settings.ensure_final_newline_on_save,
         ensure_final_newline_on_save,
     );
+    merge(
+        &mut settings.line_ending_on_save,
+        line_ending_on_save.map(Some),
+    );
+    merge(
+        &mut settings.byte_order_mark_on_save,
+        byte_order_mark_on_save.map(Some),
+    );
+    merge(&mut settings.detect_indentation, detect_indentation);
 }
 
 impl settings::Settings for AllLanguageSettings {
@@ -500,14 +641,18 @@ impl settings::Settings for AllLanguageSettings {
         fn load_from_content(settings: LanguageSettingsContent) -> LanguageSettings {
             let inlay_hints = settings.inlay_hints.unwrap();
             let completions = settings.completions.unwrap();
+            let spell_check = settings.spell_check.unwrap();
             let prettier = settings.prettier.unwrap();
             let indent_guides = settings.indent_guides.unwrap();
+            let reading_mode = settings.reading_mode.unwrap();
             let tasks = settings.tasks.unwrap();
             LanguageSettings {
                 tab_size: settings.tab_size.unwrap(),
                 hard_tabs: settings.hard_tabs.unwrap(),
+                detect_indentation: settings.detect_indentation.unwrap(),
                 soft_wrap: settings.soft_wrap.unwrap(),
                 preferred_line_length: settings.preferred_line_length.unwrap(),
+                soft_wrap_hanging_indent: settings.soft_wrap_hanging_indent.unwrap(),
                 show_wrap_guides: settings.show_wrap_guides.unwrap(),
                 wrap_guides: settings.wrap_guides.unwrap(),
                 indent_guides: IndentGuideSettings {
@@ -517,11 +662,22 @@ impl settings::Settings for AllLanguageSettings {
                     coloring: indent_guides.coloring.unwrap(),
                     background_coloring: indent_guides.background_coloring.unwrap(),
                 },
+                reading_mode: ReadingModeSettings {
+                    enabled: reading_mode.enabled.unwrap(),
+                    measure: reading_mode.measure.unwrap(),
+                    typewriter_scrolling: reading_mode.typewriter_scrolling.unwrap(),
+                },
+                related_files: settings.related_files.unwrap(),
                 format_on_save: settings.format_on_save.unwrap(),
                 remove_trailing_whitespace_on_save: settings
                     .remove_trailing_whitespace_on_save
                     .unwrap(),
+                keep_trailing_whitespace_on_cursor_line: settings
+                    .keep_trailing_whitespace_on_cursor_line
+                    .unwrap(),
                 ensure_final_newline_on_save: settings.ensure_final_newline_on_save.unwrap(),
+                line_ending_on_save: None,
+                byte_order_mark_on_save: None,
                 formatter: settings.formatter.unwrap(),
                 prettier: PrettierSettings {
                     allowed: prettier.allowed.unwrap(),
@@ -538,6 +694,7 @@ impl settings::Settings for AllLanguageSettings {
                 show_whitespaces: settings.show_whitespaces.unwrap(),
                 whitespace_map: settings.whitespace_map.unwrap(),
                 extend_comment_on_newline: settings.extend_comment_on_newline.unwrap(),
+                tint_injections: settings.tint_injections.unwrap(),
                 inlay_hints: InlayHintSettings {
                     enabled: inlay_hints.enabled.unwrap(),
                     show_value_hints: inlay_hints.show_value_hints.unwrap(),
@@ -558,11 +715,16 @@ impl settings::Settings for AllLanguageSettings {
                     .always_treat_brackets_as_autoclosed
                     .unwrap(),
                 code_actions_on_format: settings.code_actions_on_format.unwrap(),
+                code_actions_on_format_order: settings.code_actions_on_format_order.unwrap(),
+                code_actions_on_format_timeout_ms: settings
+                    .code_actions_on_format_timeout_ms
+                    .unwrap(),
                 linked_edits: settings.linked_edits.unwrap(),
                 tasks: LanguageTaskSettings {
                     variables: tasks.variables,
                     enabled: tasks.enabled.unwrap(),
                     prefer_lsp: tasks.prefer_lsp.unwrap(),
+                    interpreter: tasks.interpreter.unwrap(),
                 },
                 show_completions_on_input: settings.show_completions_on_input.unwrap(),
                 show_completion_documentation: settings.show_completion_documentation.unwrap(),
@@ -572,6 +734,18 @@ impl settings::Settings for AllLanguageSettings {
                     lsp: completions.lsp.unwrap(),
                     lsp_fetch_timeout_ms: completions.lsp_fetch_timeout_ms.unwrap(),
                     lsp_insert_mode: completions.lsp_insert_mode.unwrap(),
+                    auto_confirm_single_match: completions.auto_confirm_single_match.unwrap(),
+                },
+                spell_check: SpellCheckSettings {
+                    enabled: spell_check.enabled.unwrap(),
+                    check_all_text: spell_check.check_all_text.unwrap(),
+                    dictionaries: spell_check
+                        .dictionaries
+                        .unwrap()
+                        .iter()
+                        .map(|path| PathBuf::from(shellexpand::tilde(path).as_ref()))
+                        .collect(),
+                    words: spell_check.words.unwrap(),
                 },
                 debuggers: settings.debuggers.unwrap(),
             }
@@ -613,16 +787,43 @@ impl settings::Settings for AllLanguageSettings {
 
         let enabled_in_text_threads = edit_predictions.enabled_in_text_threads.unwrap();
 
-        let mut file_types: FxHashMap<Arc<str>, GlobSet> = FxHashMap::default();
+        let mut file_type_patterns: FxHashMap<Arc<str>, Vec<&str>> = FxHashMap::default();
+        for (key, association) in &all_languages.file_types {
+            match association {
+                FileTypeAssociation::Globs(patterns) => file_type_patterns
+                    .entry(key.clone())
+                    .or_default()
+                    .extend(patterns.0.iter().map(String::as_str)),
+                FileTypeAssociation::Language(language) => file_type_patterns
+                    .entry(language.as_str().into())
+                    .or_default()
+                    .push(key),
+            }
+        }
 
-        for (language, patterns) in &all_languages.file_types {
+        let sorted_file_type_patterns = file_type_patterns
+            .iter()
+            .map(|(language, patterns)| {
+                let patterns = patterns.iter().map(|pattern| pattern.to_string()).sorted();
+                (language.clone(), patterns.collect())
+            })
+            .collect();
+        let mut file_types: FxHashMap<Arc<str>, GlobSet> = FxHashMap::default();
+        for (language, patterns) in file_type_patterns {
             let mut builder = GlobSetBuilder::new();
-
-            for pattern in &patterns.0 {
-                builder.add(Glob::new(pattern).unwrap());
+            for pattern in patterns {
+                match Glob::new(pattern) {
+                    Ok(glob) => {
+                        builder.add(glob);
+                    }
+                    Err(error) => {
+                        log::error!("invalid `file_types` glob {pattern:?} for {language}: {error}")
+                    }
+                }
+            }
+            if let Some(glob_set) = builder.build().log_err() {
+                file_types.insert(language, glob_set);
             }
-
-            file_types.insert(language.clone(), builder.build().unwrap());
         }
 
         Self {
@@ -649,6 +850,7 @@ impl settings::Settings for AllLanguageSettings {
             defaults: default_language_settings,
             languages,
             file_types,
+            file_type_patterns: Arc::new(sorted_file_type_patterns),
         }
     }
 
@@ -664,6 +866,7 @@ impl settings::Settings for AllLanguageSettings {
         if let Some(v) = vscode.read_bool("editor.insertSpaces") {
             d.hard_tabs = Some(!v);
         }
+        vscode.bool_setting("editor.detectIndentation", &mut d.detect_indentation);
 
         vscode.enum_setting("editor.wordWrap", &mut d.soft_wrap, |s| match s {
             "on" => Some(SoftWrap::EditorWidth),
@@ -736,15 +939,15 @@ impl settings::Settings for AllLanguageSettings {
         }
         // TODO: pull ^ out into helper and reuse for per-language settings
 
-        // vscodes file association map is inverted from ours, so we flip the mapping before merging
-        let mut associations: HashMap<Arc<str>, ExtendingVec<String>> = HashMap::default();
+        // vscode's file associations map globs to languages, like ours can
+        let mut associations: HashMap<Arc<str>, FileTypeAssociation> = HashMap::default();
         if let Some(map) = vscode
             .read_value("files.associations")
             .and_then(|v| v.as_object())
         {
             for (k, v) in map {
                 let Some(v) = v.as_str() else { continue };
-                associations.entry(v.into()).or_default().0.push(k.clone());
+                associations.insert(k.as_str().into(), FileTypeAssociation::Language(v.into()));
             }
         }
 
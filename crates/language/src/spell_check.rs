@@ -0,0 +1,513 @@
+use std::ops::Range;
+
+use collections::HashMap;
+
+/// Words that are further than this many edits from a misspelled word aren't suggested.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// A set of correctly spelled words, used to find misspelled words and to suggest corrections
+/// for them.
+#[derive(Debug, Default)]
+pub struct SpellCheckDictionary {
+    /// The words, along with their lowercase characters, which are compared against misspelled
+    /// words when suggesting corrections.
+    words: HashMap<String, Box<[char]>>,
+}
+
+impl SpellCheckDictionary {
+    /// Adds the words of a plain word list with one word per line, such as
+    /// `/usr/share/dict/words`.
+    pub fn add_word_list(&mut self, text: &str) {
+        for line in text.lines() {
+            let word = line.trim();
+            if !word.is_empty() {
+                self.add_word(word);
+            }
+        }
+    }
+
+    /// Adds the words of a Hunspell dictionary, given the contents of its `.dic` and `.aff`
+    /// files.
+    ///
+    /// Each word is added along with the forms produced by the prefix and suffix rules its
+    /// flags refer to. Compounding and the other rules of the affix file aren't supported.
+    pub fn add_hunspell_dictionary(&mut self, dic: &str, aff: &str) {
+        let affixes = AffixFile::parse(aff);
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, affixes.flags(flags)),
+                None => (entry, Vec::new()),
+            };
+            if word.is_empty() {
+                continue;
+            }
+            self.add_word(word);
+            for form in affixes.expand(word, &flags) {
+                self.add_word(&form);
+            }
+        }
+    }
+
+    pub fn add_word(&mut self, word: &str) {
+        if !self.words.contains_key(word) {
+            let lowercase = word.to_lowercase().chars().collect();
+            self.words.insert(word.to_string(), lowercase);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns whether `word` is spelled correctly.
+    ///
+    /// Lowercase words in the dictionary also match their capitalized and uppercase spellings,
+    /// while words with capitals in the dictionary, like names, have to be written with them.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.words.contains_key(word) {
+            return true;
+        }
+        let lowercase = word.to_lowercase();
+        if lowercase != word && self.words.contains_key(&lowercase) {
+            return true;
+        }
+        word.chars().all(|c| !c.is_lowercase()) && self.words.contains_key(&capitalize(&lowercase))
+    }
+
+    /// Returns up to `limit` words from the dictionary that are the fewest edits away from
+    /// `word`, written with the same case as it.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let target = word.to_lowercase().chars().collect::<Vec<_>>();
+        let mut candidates = self
+            .words
+            .iter()
+            .filter_map(|(candidate, candidate_chars)| {
+                let distance = edit_distance(&target, candidate_chars, MAX_SUGGESTION_DISTANCE)?;
+                Some((distance, candidate.as_str()))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+
+        let mut suggestions = Vec::new();
+        for (_, candidate) in candidates {
+            let suggestion = match_case(word, candidate);
+            if !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+            if suggestions.len() == limit {
+                break;
+            }
+        }
+        suggestions
+    }
+}
+
+/// The prefix and suffix rules of a Hunspell `.aff` file.
+#[derive(Default)]
+struct AffixFile {
+    flag_format: FlagFormat,
+    /// The sets of flags that words can refer to by their one-based index, instead of listing
+    /// their flags.
+    flag_aliases: Vec<Vec<String>>,
+    classes: HashMap<String, AffixClass>,
+}
+
+/// How the flags of a word are written, set with the `FLAG` option.
+#[derive(Clone, Copy, Default)]
+enum FlagFormat {
+    /// Each character is a flag.
+    #[default]
+    Char,
+    /// Each pair of characters is a flag.
+    Long,
+    /// Flags are comma-separated numbers.
+    Numeric,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+/// The rules that a single flag applies to a word.
+struct AffixClass {
+    kind: AffixKind,
+    /// Whether the class's prefixes and suffixes can be combined with those of other
+    /// classes that allow it.
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+}
+
+enum ConditionPart {
+    Any,
+    Char(char),
+    Set { negated: bool, chars: Vec<char> },
+}
+
+impl AffixFile {
+    fn parse(text: &str) -> Self {
+        let mut affix_file = Self::default();
+        let mut seen_alias_count = false;
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("FLAG") => {
+                    affix_file.flag_format = match fields.next() {
+                        Some("long") => FlagFormat::Long,
+                        Some("num") => FlagFormat::Numeric,
+                        _ => FlagFormat::Char,
+                    };
+                }
+                Some("AF") => {
+                    // The first `AF` line holds the number of aliases that follow it.
+                    if !seen_alias_count {
+                        seen_alias_count = true;
+                    } else if let Some(flags) = fields.next() {
+                        let flags = affix_file.parse_flags(flags);
+                        affix_file.flag_aliases.push(flags);
+                    }
+                }
+                Some(kind @ ("PFX" | "SFX")) => {
+                    let (Some(flag), Some(first), Some(second)) =
+                        (fields.next(), fields.next(), fields.next())
+                    else {
+                        continue;
+                    };
+                    // The first line of a class says whether it allows cross products, and
+                    // each following line is one of its rules.
+                    let Some(class) = affix_file.classes.get_mut(flag) else {
+                        affix_file.classes.insert(
+                            flag.to_string(),
+                            AffixClass {
+                                kind: if kind == "PFX" {
+                                    AffixKind::Prefix
+                                } else {
+                                    AffixKind::Suffix
+                                },
+                                cross_product: first == "Y",
+                                rules: Vec::new(),
+                            },
+                        );
+                        continue;
+                    };
+                    // Continuation flags, which allow further affixes, aren't supported.
+                    let add = second.split('/').next().unwrap_or_default();
+                    class.rules.push(AffixRule {
+                        strip: if first == "0" { "" } else { first }.to_string(),
+                        add: if add == "0" { "" } else { add }.to_string(),
+                        condition: parse_condition(fields.next().unwrap_or(".")),
+                    });
+                }
+                _ => {}
+            }
+        }
+        affix_file
+    }
+
+    /// Returns the flags of a word in a `.dic` file, written after its `/`.
+    fn flags(&self, flags: &str) -> Vec<String> {
+        if !self.flag_aliases.is_empty()
+            && let Ok(index) = flags.parse::<usize>()
+        {
+            return index
+                .checked_sub(1)
+                .and_then(|index| self.flag_aliases.get(index))
+                .cloned()
+                .unwrap_or_default();
+        }
+        self.parse_flags(flags)
+    }
+
+    fn parse_flags(&self, flags: &str) -> Vec<String> {
+        match self.flag_format {
+            FlagFormat::Char => flags.chars().map(String::from).collect(),
+            FlagFormat::Long => flags
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|flag| flag.iter().collect())
+                .collect(),
+            FlagFormat::Numeric => flags
+                .split(',')
+                .map(str::trim)
+                .filter(|flag| !flag.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    /// Returns the forms of `word` produced by the prefixes and suffixes of its flags.
+    fn expand(&self, word: &str, flags: &[String]) -> Vec<String> {
+        let classes = flags
+            .iter()
+            .filter_map(|flag| self.classes.get(flag))
+            .collect::<Vec<_>>();
+        let prefixes = || {
+            classes
+                .iter()
+                .filter(|class| class.kind == AffixKind::Prefix)
+        };
+
+        let mut forms = Vec::new();
+        for prefix in prefixes() {
+            forms.extend(prefix.apply(word));
+        }
+        for suffix in classes
+            .iter()
+            .filter(|class| class.kind == AffixKind::Suffix)
+        {
+            for form in suffix.apply(word) {
+                if suffix.cross_product {
+                    for prefix in prefixes().filter(|prefix| prefix.cross_product) {
+                        forms.extend(prefix.apply(&form));
+                    }
+                }
+                forms.push(form);
+            }
+        }
+        forms
+    }
+}
+
+impl AffixClass {
+    fn apply<'a>(&'a self, word: &'a str) -> impl Iterator<Item = String> + 'a {
+        self.rules.iter().filter_map(move |rule| match self.kind {
+            AffixKind::Prefix => {
+                let stem = word.strip_prefix(rule.strip.as_str())?;
+                (!stem.is_empty() && condition_matches(rule.condition.iter(), word.chars()))
+                    .then(|| format!("{}{stem}", rule.add))
+            }
+            AffixKind::Suffix => {
+                let stem = word.strip_suffix(rule.strip.as_str())?;
+                (!stem.is_empty()
+                    && condition_matches(rule.condition.iter().rev(), word.chars().rev()))
+                .then(|| format!("{stem}{}", rule.add))
+            }
+        })
+    }
+}
+
+/// Parses the condition of an affix rule, which is written like a regular expression made of
+/// characters, `.` and bracketed character sets.
+fn parse_condition(condition: &str) -> Vec<ConditionPart> {
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        parts.push(match c {
+            '.' => ConditionPart::Any,
+            '[' => {
+                let mut set = chars.by_ref().take_while(|c| *c != ']').peekable();
+                let negated = set.next_if_eq(&'^').is_some();
+                ConditionPart::Set {
+                    negated,
+                    chars: set.collect(),
+                }
+            }
+            c => ConditionPart::Char(c),
+        });
+    }
+    parts
+}
+
+/// Whether each part of a condition matches the corresponding character of a word.
+fn condition_matches<'a>(
+    mut parts: impl Iterator<Item = &'a ConditionPart>,
+    mut chars: impl Iterator<Item = char>,
+) -> bool {
+    parts.all(|part| {
+        chars.next().is_some_and(|c| match part {
+            ConditionPart::Any => true,
+            ConditionPart::Char(expected) => c == *expected,
+            ConditionPart::Set {
+                negated,
+                chars: set,
+            } => set.contains(&c) != *negated,
+        })
+    })
+}
+
+/// Returns the words in `text` that should be spell checked, along with their ranges.
+///
+/// Words that look like code are skipped, such as identifiers written in camel or snake case,
+/// words containing digits, and the parts of paths and URLs.
+pub fn words_to_spell_check(text: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    text.split_whitespace()
+        .filter(|chunk| !looks_like_code(chunk))
+        .flat_map(move |chunk| {
+            let chunk_offset = chunk.as_ptr() as usize - text.as_ptr() as usize;
+            words_in_chunk(chunk).map(move |(range, word)| {
+                (chunk_offset + range.start..chunk_offset + range.end, word)
+            })
+        })
+        .filter(|(_, word)| {
+            let mut chars = word.chars().skip(1);
+            word.chars().count() > 1
+                && word.chars().all(|c| c.is_alphabetic() || is_apostrophe(c))
+                // Camel case identifiers have capitals after their first letter, unlike
+                // acronyms, which are all capitals.
+                && (chars.all(|c| !c.is_uppercase()) || word.chars().all(|c| !c.is_lowercase()))
+        })
+}
+
+fn looks_like_code(chunk: &str) -> bool {
+    chunk.contains([
+        '/', '\\', '@', '`', '=', '<', '>', '{', '}', '[', ']', '_', '#', '$',
+    ]) || chunk.contains("::")
+        || chunk.contains("()")
+        || chunk
+            .split('.')
+            .skip(1)
+            .any(|part| part.starts_with(|c: char| c.is_alphanumeric()))
+}
+
+fn words_in_chunk(chunk: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut chars = chunk.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, _) = chars.find(|(_, c)| c.is_alphanumeric())?;
+        let mut end = chunk.len();
+        while let Some((ix, c)) = chars.next() {
+            let continues_word = c.is_alphanumeric()
+                || (is_apostrophe(c) && chars.peek().is_some_and(|(_, c)| c.is_alphabetic()));
+            if !continues_word {
+                end = ix;
+                break;
+            }
+        }
+        Some((start..end, &chunk[start..end]))
+    })
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Writes `suggestion` in uppercase or capitalized when `word` is.
+fn match_case(word: &str, suggestion: &str) -> String {
+    let mut chars = word.chars();
+    let first_is_uppercase = chars.next().is_some_and(|c| c.is_uppercase());
+    if first_is_uppercase && chars.all(|c| !c.is_lowercase()) {
+        suggestion.to_uppercase()
+    } else if first_is_uppercase {
+        capitalize(suggestion)
+    } else {
+        suggestion.to_string()
+    }
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent characters
+/// needed to turn `a` into `b`, or `None` if it's more than `max_distance`.
+fn edit_distance(a: &[char], b: &[char], max_distance: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+
+    let mut previous_row = Vec::new();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for i in 1..=a.len() {
+        let previous_previous_row = std::mem::replace(&mut previous_row, row.clone());
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            row[j] = (previous_row[j] + 1)
+                .min(row[j - 1] + 1)
+                .min(previous_row[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(previous_previous_row[j - 2] + 1);
+            }
+        }
+        if row.iter().all(|distance| *distance > max_distance) {
+            return None;
+        }
+    }
+    let distance = row[b.len()];
+    (distance <= max_distance).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spell_check_dictionary() {
+        let mut dictionary = SpellCheckDictionary::default();
+        dictionary.add_word_list("hello\nworld\nParis\nreceive\n");
+
+        assert!(dictionary.contains("hello"));
+        assert!(dictionary.contains("Hello"));
+        assert!(dictionary.contains("WORLD"));
+        assert!(dictionary.contains("Paris"));
+        assert!(dictionary.contains("PARIS"));
+        assert!(!dictionary.contains("paris"));
+        assert!(!dictionary.contains("helo"));
+
+        assert_eq!(dictionary.suggestions("recieve", 5), ["receive"]);
+        assert_eq!(dictionary.suggestions("Wrold", 5), ["World"]);
+        assert_eq!(dictionary.suggestions("HELO", 5), ["HELLO"]);
+        assert!(dictionary.suggestions("xyz", 5).is_empty());
+    }
+
+    #[test]
+    fn test_hunspell_dictionary() {
+        let aff = "\
+SET UTF-8
+PFX U Y 1
+PFX U 0 un .
+SFX D Y 3
+SFX D 0 d e
+SFX D y ied [^aeiou]y
+SFX D 0 ed [^ey]
+SFX M N 1
+SFX M 0 's .
+";
+        let mut dictionary = SpellCheckDictionary::default();
+        dictionary.add_hunspell_dictionary("4\nlock/DU\ncarry/D\nbake/D\nParis/M\n", aff);
+
+        for word in [
+            "lock", "locked", "unlock", "unlocked", "carry", "carried", "bake", "baked", "Paris",
+            "Paris's",
+        ] {
+            assert!(dictionary.contains(word), "{word}");
+        }
+        for word in ["carryed", "bakeed", "uncarry", "4"] {
+            assert!(!dictionary.contains(word), "{word}");
+        }
+
+        let mut dictionary = SpellCheckDictionary::default();
+        dictionary.add_hunspell_dictionary(
+            "1\nwalk/1\n",
+            "FLAG long\nAF 1\nAF DdSs\nSFX Dd N 1\nSFX Dd 0 ed .\nSFX Ss N 1\nSFX Ss 0 s .\n",
+        );
+        assert!(dictionary.contains("walked"));
+        assert!(dictionary.contains("walks"));
+    }
+
+    #[test]
+    fn test_words_to_spell_check() {
+        let text = "Teh cat's fooBar, see https://zed.dev and foo_bar or NASA v2 plan.";
+        let words = words_to_spell_check(text)
+            .map(|(range, word)| {
+                assert_eq!(&text[range], word);
+                word
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(words, ["Teh", "cat's", "see", "and", "or", "NASA", "plan"]);
+    }
+}
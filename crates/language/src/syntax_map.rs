@@ -1,6 +1,11 @@
+#[cfg(any(test, feature = "test-support"))]
+mod random_edits;
 #[cfg(test)]
 mod syntax_map_tests;
 
+#[cfg(any(test, feature = "test-support"))]
+pub use random_edits::check_random_syntax_map_edits;
+
 use crate::{
     Grammar, InjectionConfig, Language, LanguageId, LanguageRegistry, QUERY_CURSORS, with_parser,
 };
@@ -11,8 +16,8 @@ use gpui::SharedString;
 use std::{
     borrow::Cow,
     cmp::{self, Ordering, Reverse},
-    collections::BinaryHeap,
-    fmt, iter,
+    collections::{BinaryHeap, VecDeque},
+    fmt, iter, mem,
     ops::{Deref, DerefMut, Range},
     sync::Arc,
 };
@@ -33,6 +38,22 @@ pub struct SyntaxSnapshot {
     interpolated_version: clock::Global,
     language_registry_version: usize,
     update_count: usize,
+    changed_regions: Vec<ChangedSyntaxRegion>,
+    /// The regions changed by each of the most recent reparses, keyed by the
+    /// update count they produced, so that [`SyntaxSnapshot::changed_since`]
+    /// can answer for snapshots taken a few reparses ago.
+    change_history: Arc<VecDeque<(usize, Arc<[ChangedSyntaxRegion]>)>>,
+}
+
+/// How many reparses [`SyntaxSnapshot::changed_since`] can look back over.
+const MAX_CHANGE_HISTORY_LEN: usize = 32;
+
+/// A region of the buffer whose syntax tree changed while reparsing, at a
+/// given injection depth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangedSyntaxRegion {
+    pub depth: usize,
+    pub range: Range<Anchor>,
 }
 
 #[derive(Default)]
@@ -54,6 +75,32 @@ pub struct SyntaxMapCapture<'a> {
     pub node: Node<'a>,
     pub index: u32,
     pub grammar_index: usize,
+    /// The depth of the syntax layer the capture comes from.
+    pub depth: usize,
+    /// The priority of the capture's pattern, set with `(#set! priority <n>)`.
+    pub priority: u32,
+}
+
+/// The priority of captures whose pattern doesn't set one, as in nvim-treesitter.
+pub const DEFAULT_CAPTURE_PRIORITY: u32 = 100;
+
+/// The captures enclosing a position in the text, which decide the position's
+/// highlight.
+///
+/// Where captures overlap, the one with the highest priority wins. Between
+/// captures of the same priority, the one from the deeper syntax layer wins, so
+/// that an injected language's tokens take precedence over a capture of the
+/// text they're injected into, such as a string. Otherwise, the capture that
+/// was pushed last wins, which is the innermost one.
+pub struct CaptureStack<T> {
+    entries: Vec<CaptureStackEntry<T>>,
+}
+
+struct CaptureStackEntry<T> {
+    end: usize,
+    priority: u32,
+    depth: usize,
+    value: T,
 }
 
 #[derive(Debug)]
@@ -67,8 +114,10 @@ pub struct SyntaxMapMatch<'a> {
 
 struct SyntaxMapCapturesLayer<'a> {
     depth: usize,
+    query: &'a Query,
     captures: QueryCaptures<'a, 'a, TextProvider<'a>, &'a [u8]>,
     next_capture: Option<QueryCapture<'a>>,
+    next_priority: u32,
     grammar_index: usize,
     _query_cursor: QueryCursorHandle,
 }
@@ -247,7 +296,7 @@ impl SyntaxMap {
         self.snapshot.interpolate(text);
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-support"))]
     pub fn reparse(&mut self, language: Arc<Language>, text: &BufferSnapshot) {
         self.snapshot
             .reparse(text, self.language_registry.clone(), language);
@@ -272,6 +321,8 @@ impl SyntaxSnapshot {
             interpolated_version: clock::Global::default(),
             language_registry_version: 0,
             update_count: 0,
+            changed_regions: Vec::new(),
+            change_history: Arc::default(),
         }
     }
 
@@ -283,6 +334,31 @@ impl SyntaxSnapshot {
         self.update_count
     }
 
+    /// Returns the regions whose syntax changed between `old` and this
+    /// snapshot, ordered by depth and then by position within each reparse.
+    ///
+    /// Returns `None` when that can't be determined, either because `old` is
+    /// too many reparses behind or because the syntax map was cleared in
+    /// between, in which case the whole buffer should be considered changed.
+    pub fn changed_since(&self, old: &SyntaxSnapshot) -> Option<Vec<ChangedSyntaxRegion>> {
+        match old.update_count.cmp(&self.update_count) {
+            Ordering::Equal => return Some(Vec::new()),
+            Ordering::Greater => return None,
+            Ordering::Less => {}
+        }
+        let (oldest_tracked_update, _) = self.change_history.front()?;
+        if old.update_count + 1 < *oldest_tracked_update {
+            return None;
+        }
+        Some(
+            self.change_history
+                .iter()
+                .filter(|(update_count, _)| *update_count > old.update_count)
+                .flat_map(|(_, regions)| regions.iter().cloned())
+                .collect(),
+        )
+    }
+
     pub fn interpolate(&mut self, text: &BufferSnapshot) {
         let edits = text
             .anchored_edits_since::<Dimensions<usize, Point>>(&self.interpolated_version)
@@ -408,6 +484,7 @@ impl SyntaxSnapshot {
         registry: Option<Arc<LanguageRegistry>>,
         root_language: Arc<Language>,
     ) {
+        self.changed_regions.clear();
         let edit_ranges = text
             .edits_since::<usize>(&self.parsed_version)
             .map(|edit| edit.new)
@@ -452,7 +529,33 @@ impl SyntaxSnapshot {
             self.language_registry_version = registry.version();
         }
 
+        self.changed_regions.sort_by(|a, b| {
+            a.depth
+                .cmp(&b.depth)
+                .then_with(|| a.range.start.cmp(&b.range.start, text))
+        });
+        self.changed_regions.dedup_by(|region, prev_region| {
+            if region.depth == prev_region.depth
+                && region.range.start.cmp(&prev_region.range.end, text).is_le()
+            {
+                if region.range.end.cmp(&prev_region.range.end, text).is_gt() {
+                    prev_region.range.end = region.range.end;
+                }
+                true
+            } else {
+                false
+            }
+        });
+
         self.update_count += 1;
+        let change_history = Arc::make_mut(&mut self.change_history);
+        change_history.push_back((
+            self.update_count,
+            mem::take(&mut self.changed_regions).into(),
+        ));
+        if change_history.len() > MAX_CHANGE_HISTORY_LEN {
+            change_history.pop_front();
+        }
     }
 
     fn reparse_with_ranges(
@@ -551,6 +654,10 @@ impl SyntaxSnapshot {
                             },
                             text,
                         );
+                        self.changed_regions.push(ChangedSyntaxRegion {
+                            depth: layer.depth,
+                            range: layer.range.clone(),
+                        });
                     } else {
                         layers.push(layer.clone(), text);
                     }
@@ -719,6 +826,12 @@ impl SyntaxSnapshot {
                         changed_ranges = vec![step_start_byte..step_end_byte];
                     }
 
+                    self.changed_regions
+                        .extend(changed_ranges.iter().map(|range| ChangedSyntaxRegion {
+                            depth: step.depth,
+                            range: text.anchor_before(range.start)..text.anchor_after(range.end),
+                        }));
+
                     if let (Some((config, registry)), false) = (
                         grammar.injection_config.as_ref().zip(registry.as_ref()),
                         changed_ranges.is_empty(),
@@ -891,7 +1004,7 @@ impl SyntaxSnapshot {
         )
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-support"))]
     pub fn layers<'a>(&'a self, buffer: &'a BufferSnapshot) -> Vec<SyntaxLayer<'a>> {
         self.layers_for_range(0..buffer.len(), buffer, true)
             .collect()
@@ -1001,8 +1114,10 @@ impl<'a> SyntaxMapCaptures<'a> {
                 });
             let mut layer = SyntaxMapCapturesLayer {
                 depth: layer.depth,
+                query,
                 grammar_index,
                 next_capture: None,
+                next_priority: DEFAULT_CAPTURE_PRIORITY,
                 captures,
                 _query_cursor: query_cursor,
             };
@@ -1036,6 +1151,8 @@ impl<'a> SyntaxMapCaptures<'a> {
             grammar_index: layer.grammar_index,
             index: capture.index,
             node: capture.node,
+            depth: layer.depth,
+            priority: layer.next_priority,
         })
     }
 
@@ -1206,7 +1323,17 @@ impl<'a> SyntaxMapMatches<'a> {
 
 impl SyntaxMapCapturesLayer<'_> {
     fn advance(&mut self) {
-        self.next_capture = self.captures.next().map(|(mat, ix)| mat.captures[*ix]);
+        self.next_capture = None;
+        if let Some((mat, ix)) = self.captures.next() {
+            self.next_capture = Some(mat.captures[*ix]);
+            self.next_priority = self
+                .query
+                .property_settings(mat.pattern_index)
+                .iter()
+                .find(|setting| setting.key.as_ref() == "priority")
+                .and_then(|setting| setting.value.as_ref()?.parse().ok())
+                .unwrap_or(DEFAULT_CAPTURE_PRIORITY);
+        }
     }
 
     fn sort_key(&self) -> (usize, Reverse<usize>, usize) {
@@ -1246,6 +1373,44 @@ impl SyntaxMapMatchesLayer<'_> {
     }
 }
 
+impl<T> Default for CaptureStack<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> CaptureStack<T> {
+    pub fn push(&mut self, capture: &SyntaxMapCapture, value: T) {
+        self.entries.push(CaptureStackEntry {
+            end: capture.node.end_byte(),
+            priority: capture.priority,
+            depth: capture.depth,
+            value,
+        });
+    }
+
+    /// Removes the captures that end at or before the given offset.
+    pub fn remove_ended(&mut self, offset: usize) {
+        self.entries.retain(|entry| entry.end > offset);
+    }
+
+    /// The offset at which the first of the captures ends, after which the
+    /// winning capture may change.
+    pub fn next_end(&self) -> Option<usize> {
+        self.entries.iter().map(|entry| entry.end).min()
+    }
+
+    /// The value of the capture that takes precedence over the others.
+    pub fn winner(&self) -> Option<&T> {
+        self.entries
+            .iter()
+            .max_by_key(|entry| (entry.priority, entry.depth))
+            .map(|entry| &entry.value)
+    }
+}
+
 impl<'a> Iterator for SyntaxMapCaptures<'a> {
     type Item = SyntaxMapCapture<'a>;
 
@@ -1364,30 +1529,39 @@ fn get_injections(
             }
 
             prev_match = Some((mat.pattern_index, content_range.clone()));
-            let combined = config.patterns[mat.pattern_index].combined;
+            let pattern = &config.patterns[mat.pattern_index];
+            let combined = pattern.combined;
 
             let mut step_range = content_range.clone();
-            let language_name =
-                if let Some(name) = config.patterns[mat.pattern_index].language.as_ref() {
-                    Some(Cow::Borrowed(name.as_ref()))
-                } else if let Some(language_node) = config
-                    .language_capture_ix
-                    .and_then(|ix| mat.nodes_for_capture_index(ix).next())
-                {
-                    step_range.start = cmp::min(content_range.start, language_node.start_byte());
-                    step_range.end = cmp::max(content_range.end, language_node.end_byte());
-                    let language_name: String =
-                        text.text_for_range(language_node.byte_range()).collect();
-
+            let language_name = if let Some(name) = pattern.language.as_ref() {
+                Some(Cow::Borrowed(name.as_ref()))
+            } else if let Some(language_node) = config
+                .language_capture_ix
+                .and_then(|ix| mat.nodes_for_capture_index(ix).next())
+            {
+                step_range.start = cmp::min(content_range.start, language_node.start_byte());
+                step_range.end = cmp::max(content_range.end, language_node.end_byte());
+                let language_name: String =
+                    text.text_for_range(language_node.byte_range()).collect();
+
+                if let Some(language_regex) = pattern.language_regex.as_ref() {
+                    // Hints such as `user_sql` or `/* language=sql */` name the language
+                    // somewhere within the captured text.
+                    language_regex
+                        .captures(&language_name)
+                        .and_then(|captures| {
+                            let name = captures.get(1).or_else(|| captures.get(0))?;
+                            Some(Cow::Owned(name.as_str().to_lowercase()))
+                        })
+                } else if let Some(last_dot_pos) = language_name.rfind('.') {
                     // Enable paths ending in a language extension to represent a language name: e.g. "foo/bar/baz.rs"
-                    if let Some(last_dot_pos) = language_name.rfind('.') {
-                        Some(Cow::Owned(language_name[last_dot_pos + 1..].to_string()))
-                    } else {
-                        Some(Cow::Owned(language_name))
-                    }
+                    Some(Cow::Owned(language_name[last_dot_pos + 1..].to_string()))
                 } else {
-                    None
-                };
+                    Some(Cow::Owned(language_name))
+                }
+            } else {
+                None
+            };
 
             if let Some(language_name) = language_name {
                 let language = language_registry
@@ -1610,6 +1784,21 @@ impl<'a> SyntaxLayer<'a> {
             .root_node_with_offset(self.offset.0, self.offset.1)
     }
 
+    /// How deeply this layer is nested within injections, 0 being the buffer's own language.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The byte ranges of the buffer that this layer's tree was parsed from. For an
+    /// injected layer these are the ranges of its injected content, while the root
+    /// layer's range is unbounded.
+    pub fn included_ranges(&self) -> impl Iterator<Item = Range<usize>> + use<'a> {
+        let offset = self.offset.0;
+        self.tree.included_ranges().into_iter().map(move |range| {
+            offset.saturating_add(range.start_byte)..offset.saturating_add(range.end_byte)
+        })
+    }
+
     pub(crate) fn override_id(&self, offset: usize, text: &text::BufferSnapshot) -> Option<u32> {
         let text = TextProvider(text.as_rope());
         let config = self.language.grammar.as_ref()?.override_config.as_ref()?;
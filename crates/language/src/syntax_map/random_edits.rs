@@ -0,0 +1,315 @@
+//! A randomized harness for checking [`SyntaxMap`]'s incremental reparsing.
+//!
+//! Language authors can use [`check_random_syntax_map_edits`] to exercise
+//! their grammars and injection queries against random edits.
+
+use super::*;
+use rand::rngs::StdRng;
+use std::panic::{self, AssertUnwindSafe};
+use text::{Buffer, BufferId};
+
+type EditBatch = Vec<(Range<usize>, Arc<str>)>;
+
+/// Applies `operations` batches of random edits to `text`, interpolating and
+/// reparsing a [`SyntaxMap`] after each batch. After every batch, this checks
+/// that:
+///
+/// * interpolation preserves the layers' ranges and marks edited nodes as changed,
+/// * layers that no edit touches keep their existing syntax trees, and
+/// * the incrementally reparsed layers match those of a map parsed from scratch.
+///
+/// The edits are derived entirely from `rng`, so a failure can be reproduced
+/// from its seed. When a check fails, the edit history is first minimized to
+/// the smallest sequence that still fails, and then reported.
+pub fn check_random_syntax_map_edits(
+    text: &str,
+    language: Arc<Language>,
+    registry: Arc<LanguageRegistry>,
+    rng: &mut StdRng,
+    operations: usize,
+) {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), text);
+    let batches = (0..operations)
+        .map(|_| buffer.randomly_edit(rng, 3).0)
+        .collect::<Vec<_>>();
+
+    let Err(error) = replay_edits(text, &batches, &language, &registry) else {
+        return;
+    };
+    let minimized = minimize_edits(text, batches, &language, &registry);
+    panic!(
+        "syntax map check failed: {error}\ninitial text:\n{text}\nminimized edits:\n{}",
+        minimized
+            .iter()
+            .enumerate()
+            .map(|(ix, batch)| format!("  {ix}: {batch:?}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
+
+/// Greedily removes whole batches, then individual edits within batches, as long
+/// as the remaining edits still fail.
+fn minimize_edits(
+    text: &str,
+    mut batches: Vec<EditBatch>,
+    language: &Arc<Language>,
+    registry: &Arc<LanguageRegistry>,
+) -> Vec<EditBatch> {
+    let mut batch_ix = 0;
+    while batch_ix < batches.len() {
+        let mut candidate = batches.clone();
+        candidate.remove(batch_ix);
+        if replay_edits(text, &candidate, language, registry).is_err() {
+            batches = candidate;
+        } else {
+            batch_ix += 1;
+        }
+    }
+
+    for batch_ix in 0..batches.len() {
+        let mut edit_ix = 0;
+        while edit_ix < batches[batch_ix].len() && batches[batch_ix].len() > 1 {
+            let mut candidate = batches.clone();
+            candidate[batch_ix].remove(edit_ix);
+            if replay_edits(text, &candidate, language, registry).is_err() {
+                batches = candidate;
+            } else {
+                edit_ix += 1;
+            }
+        }
+    }
+
+    batches
+}
+
+fn replay_edits(
+    text: &str,
+    batches: &[EditBatch],
+    language: &Arc<Language>,
+    registry: &Arc<LanguageRegistry>,
+) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), text);
+        let mut syntax_map = SyntaxMap::new(&buffer);
+        syntax_map.set_language_registry(registry.clone());
+        syntax_map.reparse(language.clone(), &buffer);
+
+        for (batch_ix, batch) in batches.iter().enumerate() {
+            let prev_buffer = buffer.snapshot();
+            let prev_syntax_map = syntax_map.snapshot();
+
+            // Batches may no longer line up with the text once earlier ones have been
+            // removed during minimization, so keep them valid for the current text.
+            let mut prev_end = 0;
+            let edits = batch
+                .iter()
+                .map(|(range, new_text)| {
+                    let start = buffer.clip_offset(range.start.max(prev_end), Bias::Left);
+                    let end = buffer.clip_offset(range.end.max(start), Bias::Right);
+                    prev_end = end;
+                    (start..end, new_text.clone())
+                })
+                .collect::<Vec<_>>();
+            buffer.edit(edits);
+
+            syntax_map.interpolate(&buffer);
+            check_interpolation(&prev_syntax_map, &syntax_map, &prev_buffer, &buffer);
+            check_unchanged_trees_reused(&prev_syntax_map, &syntax_map, &prev_buffer, &buffer);
+
+            syntax_map.reparse(language.clone(), &buffer);
+            let mut reference_syntax_map = SyntaxMap::new(&buffer);
+            reference_syntax_map.set_language_registry(registry.clone());
+            reference_syntax_map.reparse(language.clone(), &buffer);
+            check_layers_match(&syntax_map, &reference_syntax_map, &buffer, batch_ix);
+        }
+    }))
+    .map_err(|payload| {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "unknown panic".into())
+    })
+}
+
+/// Interpolation must not touch the trees of layers that lie entirely outside of
+/// the edited ranges, so that reparsing can reuse them as-is.
+fn check_unchanged_trees_reused(
+    old_syntax_map: &SyntaxSnapshot,
+    new_syntax_map: &SyntaxSnapshot,
+    old_buffer: &BufferSnapshot,
+    new_buffer: &BufferSnapshot,
+) {
+    let edits = new_buffer
+        .edits_since::<usize>(old_buffer.version())
+        .collect::<Vec<_>>();
+
+    for (old_layer, new_layer) in old_syntax_map
+        .layers
+        .iter()
+        .zip(new_syntax_map.layers.iter())
+    {
+        let (Some(old_tree), Some(new_tree)) = (old_layer.content.tree(), new_layer.content.tree())
+        else {
+            continue;
+        };
+        let range = new_layer.range.to_offset(new_buffer);
+        let is_edited = edits
+            .iter()
+            .any(|edit| edit.new.start <= range.end && edit.new.end >= range.start);
+        if !is_edited {
+            assert_eq!(
+                old_tree.root_node().id(),
+                new_tree.root_node().id(),
+                "unedited layer's tree was not reused. layer depth: {}, range: {:?}",
+                new_layer.depth,
+                range,
+            );
+        }
+    }
+}
+
+fn check_layers_match(
+    syntax_map: &SyntaxSnapshot,
+    reference_syntax_map: &SyntaxSnapshot,
+    buffer: &BufferSnapshot,
+    batch_ix: usize,
+) {
+    let layers = syntax_map.layers(buffer);
+    let reference_layers = reference_syntax_map.layers(buffer);
+    assert_eq!(
+        layers.len(),
+        reference_layers.len(),
+        "wrong number of layers after edit batch {batch_ix}"
+    );
+    for (layer, reference_layer) in layers.iter().zip(reference_layers.iter()) {
+        assert_eq!(
+            (
+                layer.depth,
+                layer.language.name(),
+                layer.node().byte_range()
+            ),
+            (
+                reference_layer.depth,
+                reference_layer.language.name(),
+                reference_layer.node().byte_range()
+            ),
+            "mismatched layer after edit batch {batch_ix}"
+        );
+        assert_eq!(
+            layer.node().to_sexp(),
+            reference_layer.node().to_sexp(),
+            "mismatched syntax tree after edit batch {batch_ix}"
+        );
+    }
+}
+
+pub(super) fn check_interpolation(
+    old_syntax_map: &SyntaxSnapshot,
+    new_syntax_map: &SyntaxSnapshot,
+    old_buffer: &BufferSnapshot,
+    new_buffer: &BufferSnapshot,
+) {
+    let edits = new_buffer
+        .edits_since::<usize>(old_buffer.version())
+        .collect::<Vec<_>>();
+
+    for (old_layer, new_layer) in old_syntax_map
+        .layers
+        .iter()
+        .zip(new_syntax_map.layers.iter())
+    {
+        assert_eq!(old_layer.range, new_layer.range);
+        let Some(old_tree) = old_layer.content.tree() else {
+            continue;
+        };
+        let Some(new_tree) = new_layer.content.tree() else {
+            continue;
+        };
+        let old_start_byte = old_layer.range.start.to_offset(old_buffer);
+        let new_start_byte = new_layer.range.start.to_offset(new_buffer);
+        let old_start_point = old_layer.range.start.to_point(old_buffer).to_ts_point();
+        let new_start_point = new_layer.range.start.to_point(new_buffer).to_ts_point();
+        let old_node = old_tree.root_node_with_offset(old_start_byte, old_start_point);
+        let new_node = new_tree.root_node_with_offset(new_start_byte, new_start_point);
+        check_node_edits(
+            old_layer.depth,
+            &old_layer.range,
+            old_node,
+            new_node,
+            old_buffer,
+            new_buffer,
+            &edits,
+        );
+    }
+
+    fn check_node_edits(
+        depth: usize,
+        range: &Range<Anchor>,
+        old_node: Node,
+        new_node: Node,
+        old_buffer: &BufferSnapshot,
+        new_buffer: &BufferSnapshot,
+        edits: &[text::Edit<usize>],
+    ) {
+        assert_eq!(old_node.kind(), new_node.kind());
+
+        let old_range = old_node.byte_range();
+        let new_range = new_node.byte_range();
+
+        let is_edited = edits
+            .iter()
+            .any(|edit| edit.new.start < new_range.end && edit.new.end > new_range.start);
+        if is_edited {
+            assert!(
+                new_node.has_changes(),
+                concat!(
+                    "failed to mark node as edited.\n",
+                    "layer depth: {}, old layer range: {:?}, new layer range: {:?},\n",
+                    "node kind: {}, old node range: {:?}, new node range: {:?}",
+                ),
+                depth,
+                range.to_offset(old_buffer),
+                range.to_offset(new_buffer),
+                new_node.kind(),
+                old_range,
+                new_range,
+            );
+        }
+
+        if !new_node.has_changes() {
+            assert_eq!(
+                old_buffer
+                    .text_for_range(old_range.clone())
+                    .collect::<String>(),
+                new_buffer
+                    .text_for_range(new_range.clone())
+                    .collect::<String>(),
+                concat!(
+                    "mismatched text for node\n",
+                    "layer depth: {}, old layer range: {:?}, new layer range: {:?},\n",
+                    "node kind: {}, old node range:{:?}, new node range:{:?}",
+                ),
+                depth,
+                range.to_offset(old_buffer),
+                range.to_offset(new_buffer),
+                new_node.kind(),
+                old_range,
+                new_range,
+            );
+        }
+
+        for i in 0..new_node.child_count() {
+            check_node_edits(
+                depth,
+                range,
+                old_node.child(i).unwrap(),
+                new_node.child(i).unwrap(),
+                old_buffer,
+                new_buffer,
+                edits,
+            )
+        }
+    }
+}
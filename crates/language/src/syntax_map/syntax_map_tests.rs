@@ -1,3 +1,4 @@
+use super::random_edits::check_interpolation;
 use super::*;
 use crate::{
     LanguageConfig, LanguageMatcher,
@@ -7,7 +8,6 @@ use gpui::App;
 use rand::rngs::StdRng;
 use std::{env, ops::Range, sync::Arc};
 use text::{Buffer, BufferId};
-use tree_sitter::Node;
 use unindent::Unindent as _;
 use util::test::marked_text_ranges;
 
@@ -266,6 +266,201 @@ fn test_dynamic_language_injection(cx: &mut App) {
     assert!(!syntax_map.contains_unknown_injections());
 }
 
+#[gpui::test]
+fn test_language_injection_from_hints(cx: &mut App) {
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    registry.add(Arc::new(ruby_lang()));
+    registry.add(Arc::new(html_lang()));
+    let rust = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_injection_query(
+            r#"
+                (let_declaration
+                    pattern: (identifier) @injection.language
+                    value: (string_literal) @injection.content
+                    (#set! injection.language-regex "(?:^|_)(rb|html)$"))
+
+                ((block_comment) @injection.language
+                    .
+                    (string_literal) @injection.content
+                    (#set! injection.language-regex "language=([a-z]+)"))
+            "#,
+        )
+        .unwrap(),
+    );
+    registry.add(rust.clone());
+
+    let buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        r#"
+            fn main() {
+                let script_rb = "puts 1";
+                let page = /* language=html */ "<p>";
+                let plain = "text";
+            }
+        "#
+        .unindent(),
+    );
+
+    let mut syntax_map = SyntaxMap::new(&buffer);
+    syntax_map.set_language_registry(registry);
+    syntax_map.reparse(rust, &buffer);
+
+    let layer_languages = |text: &str| {
+        syntax_map
+            .layers_for_range(range_for_text(&buffer, text), &buffer, true)
+            .map(|layer| layer.language.name().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(layer_languages("puts"), ["Rust", "Ruby"]);
+    assert_eq!(layer_languages("<p>"), ["Rust", "HTML"]);
+    assert_eq!(layer_languages("text"), ["Rust"]);
+}
+
+#[gpui::test]
+fn test_injected_layer_ranges(cx: &mut App) {
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    let language = Arc::new(rust_lang());
+    registry.add(language.clone());
+
+    let buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        "fn a() { vec![b(), c()]; }".to_string(),
+    );
+    let mut syntax_map = SyntaxMap::new(&buffer);
+    syntax_map.set_language_registry(registry);
+    syntax_map.reparse(language, &buffer);
+
+    let injected_ranges = syntax_map
+        .layers_for_range(0..buffer.len(), &buffer, true)
+        .filter(|layer| layer.depth() > 0)
+        .map(|layer| (layer.depth(), layer.included_ranges().collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        injected_ranges,
+        [(1, vec![range_for_text(&buffer, "[b(), c()]")])]
+    );
+}
+
+#[gpui::test]
+fn test_changed_regions_after_reparse(cx: &mut App) {
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    let language = Arc::new(rust_lang());
+    registry.add(language.clone());
+
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        r#"
+            fn a() {}
+
+            fn b() {
+                vec![1, 2];
+            }
+        "#
+        .unindent(),
+    );
+
+    let mut syntax_map = SyntaxMap::new(&buffer);
+    syntax_map.set_language_registry(registry);
+    syntax_map.reparse(language.clone(), &buffer);
+    let old_snapshot = syntax_map.snapshot();
+
+    let edit_range = range_for_text(&buffer, "2");
+    buffer.edit([(edit_range.clone(), "2, 3")]);
+    syntax_map.interpolate(&buffer);
+    syntax_map.reparse(language, &buffer);
+
+    let regions = syntax_map.snapshot().changed_since(&old_snapshot).unwrap();
+    let depths = regions
+        .iter()
+        .map(|region| region.depth)
+        .collect::<Vec<_>>();
+    assert!(depths.contains(&0), "missing root layer region: {depths:?}");
+    assert!(depths.contains(&1), "missing injection region: {depths:?}");
+
+    let unchanged_range = range_for_text(&buffer, "fn a() {}");
+    for region in &regions {
+        let range = region.range.to_offset(&buffer);
+        assert!(
+            range.start >= unchanged_range.end,
+            "region {range:?} at depth {} overlaps unchanged function",
+            region.depth
+        );
+        assert!(range.start <= edit_range.start && range.end >= edit_range.start);
+    }
+}
+
+#[gpui::test]
+fn test_changed_since_older_snapshot(cx: &mut App) {
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    let language = Arc::new(rust_lang());
+    registry.add(language.clone());
+
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        r#"
+            fn a() {}
+
+            fn b() {
+                let c = 1;
+            }
+        "#
+        .unindent(),
+    );
+
+    let mut syntax_map = SyntaxMap::new(&buffer);
+    syntax_map.set_language_registry(registry);
+    syntax_map.reparse(language.clone(), &buffer);
+    let initial_snapshot = syntax_map.snapshot();
+    assert_eq!(
+        initial_snapshot.changed_since(&initial_snapshot),
+        Some(Vec::new())
+    );
+
+    // Changes from several reparses are combined.
+    for (old_text, new_text) in [("1", "(1 + 2)"), ("2", "2 * 3")] {
+        let edit_range = range_for_text(&buffer, old_text);
+        buffer.edit([(edit_range, new_text)]);
+        syntax_map.interpolate(&buffer);
+        syntax_map.reparse(language.clone(), &buffer);
+    }
+    let snapshot = syntax_map.snapshot();
+    let regions = snapshot.changed_since(&initial_snapshot).unwrap();
+    assert!(!regions.is_empty());
+    let unchanged_range = range_for_text(&buffer, "fn a() {}");
+    for region in &regions {
+        assert!(region.range.to_offset(&buffer).start >= unchanged_range.end);
+    }
+    assert_eq!(initial_snapshot.changed_since(&snapshot), None);
+
+    // Snapshots older than the tracked history are reported as entirely changed.
+    for _ in 0..MAX_CHANGE_HISTORY_LEN {
+        let edit_range = range_for_text(&buffer, "c");
+        buffer.edit([(edit_range, "d")]);
+        syntax_map.interpolate(&buffer);
+        syntax_map.reparse(language.clone(), &buffer);
+        let edit_range = range_for_text(&buffer, "d");
+        buffer.edit([(edit_range, "c")]);
+        syntax_map.interpolate(&buffer);
+        syntax_map.reparse(language.clone(), &buffer);
+    }
+    assert_eq!(syntax_map.snapshot().changed_since(&initial_snapshot), None);
+    assert!(syntax_map.snapshot().changed_since(&snapshot).is_none());
+
+    syntax_map.clear(&buffer);
+    assert_eq!(syntax_map.snapshot().changed_since(&initial_snapshot), None);
+}
+
 #[gpui::test]
 fn test_typing_multiple_new_injections(cx: &mut App) {
     let (buffer, syntax_map) = test_edit_sequence(
@@ -968,6 +1163,32 @@ fn test_random_syntax_map_edits_with_heex(rng: StdRng, cx: &mut App) {
     test_random_edits(text, registry, language, rng);
 }
 
+#[gpui::test(iterations = 50)]
+fn test_random_syntax_map_edits_match_reference(mut rng: StdRng, cx: &mut App) {
+    let text = r#"
+        fn main() {
+            println!("{}", vec![1, 2, 3].len());
+            let items = vec![
+                format!("{:?}", Some(1)),
+                String::from("two"),
+            ];
+            assert_eq!(items.len(), 2);
+        }
+    "#
+    .unindent()
+    .repeat(2);
+
+    let operations = env::var("OPERATIONS")
+        .map(|i| i.parse().expect("invalid `OPERATIONS` variable"))
+        .unwrap_or(10);
+
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    let language = Arc::new(rust_lang());
+    registry.add(language.clone());
+
+    check_random_syntax_map_edits(&text, language, registry, &mut rng, operations);
+}
+
 fn test_random_edits(
     text: String,
     registry: Arc<LanguageRegistry>,
@@ -1034,115 +1255,6 @@ fn test_random_edits(
     }
 }
 
-fn check_interpolation(
-    old_syntax_map: &SyntaxSnapshot,
-    new_syntax_map: &SyntaxSnapshot,
-    old_buffer: &BufferSnapshot,
-    new_buffer: &BufferSnapshot,
-) {
-    let edits = new_buffer
-        .edits_since::<usize>(old_buffer.version())
-        .collect::<Vec<_>>();
-
-    for (old_layer, new_layer) in old_syntax_map
-        .layers
-        .iter()
-        .zip(new_syntax_map.layers.iter())
-    {
-        assert_eq!(old_layer.range, new_layer.range);
-        let Some(old_tree) = old_layer.content.tree() else {
-            continue;
-        };
-        let Some(new_tree) = new_layer.content.tree() else {
-            continue;
-        };
-        let old_start_byte = old_layer.range.start.to_offset(old_buffer);
-        let new_start_byte = new_layer.range.start.to_offset(new_buffer);
-        let old_start_point = old_layer.range.start.to_point(old_buffer).to_ts_point();
-        let new_start_point = new_layer.range.start.to_point(new_buffer).to_ts_point();
-        let old_node = old_tree.root_node_with_offset(old_start_byte, old_start_point);
-        let new_node = new_tree.root_node_with_offset(new_start_byte, new_start_point);
-        check_node_edits(
-            old_layer.depth,
-            &old_layer.range,
-            old_node,
-            new_node,
-            old_buffer,
-            new_buffer,
-            &edits,
-        );
-    }
-
-    fn check_node_edits(
-        depth: usize,
-        range: &Range<Anchor>,
-        old_node: Node,
-        new_node: Node,
-        old_buffer: &BufferSnapshot,
-        new_buffer: &BufferSnapshot,
-        edits: &[text::Edit<usize>],
-    ) {
-        assert_eq!(old_node.kind(), new_node.kind());
-
-        let old_range = old_node.byte_range();
-        let new_range = new_node.byte_range();
-
-        let is_edited = edits
-            .iter()
-            .any(|edit| edit.new.start < new_range.end && edit.new.end > new_range.start);
-        if is_edited {
-            assert!(
-                new_node.has_changes(),
-                concat!(
-                    "failed to mark node as edited.\n",
-                    "layer depth: {}, old layer range: {:?}, new layer range: {:?},\n",
-                    "node kind: {}, old node range: {:?}, new node range: {:?}",
-                ),
-                depth,
-                range.to_offset(old_buffer),
-                range.to_offset(new_buffer),
-                new_node.kind(),
-                old_range,
-                new_range,
-            );
-        }
-
-        if !new_node.has_changes() {
-            assert_eq!(
-                old_buffer
-                    .text_for_range(old_range.clone())
-                    .collect::<String>(),
-                new_buffer
-                    .text_for_range(new_range.clone())
-                    .collect::<String>(),
-                concat!(
-                    "mismatched text for node\n",
-                    "layer depth: {}, old layer range: {:?}, new layer range: {:?},\n",
-                    "node kind: {}, old node range:{:?}, new node range:{:?}",
-                ),
-                depth,
-                range.to_offset(old_buffer),
-                range.to_offset(new_buffer),
-                new_node.kind(),
-                old_range,
-                new_range,
-            );
-        }
-
-        for i in 0..new_node.child_count() {
-            check_node_edits(
-                depth,
-                range,
-                old_node.child(i).unwrap(),
-                new_node.child(i).unwrap(),
-                old_buffer,
-                new_buffer,
-                edits,
-            )
-        }
-    }
-}
-
 fn test_edit_sequence(language_name: &str, steps: &[&str], cx: &mut App) -> (Buffer, SyntaxMap) {
     let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
     registry.add(Arc::new(elixir_lang()));
@@ -39,7 +39,8 @@ pub fn line_diff(old_text: &str, new_text: &str) -> Vec<(Range<u32>, Range<u32>)
 /// The edits are represented as tuples of byte ranges and replacement strings.
 ///
 /// Internally, this function first performs a line-based diff, and then performs a second
-/// word-based diff within hunks that replace small numbers of lines.
+/// word-based diff within hunks that replace small numbers of lines, and within each line of
+/// larger hunks that rewrite lines in place.
 pub fn text_diff(old_text: &str, new_text: &str) -> Vec<(Range<usize>, Arc<str>)> {
     text_diff_with_options(old_text, new_text, DiffOptions::default())
 }
@@ -84,29 +85,45 @@ pub fn text_diff_with_options(
                 &new_byte_range,
                 &options,
             ) {
-                let old_offset = old_byte_range.start;
-                let new_offset = new_byte_range.start;
-                hunk_input.clear();
-                hunk_input.update_before(tokenize(
-                    &old_text[old_byte_range],
-                    options.language_scope.clone(),
-                ));
-                hunk_input.update_after(tokenize(
-                    &new_text[new_byte_range],
-                    options.language_scope.clone(),
-                ));
-                diff_internal(&hunk_input, |old_byte_range, new_byte_range, _, _| {
-                    let old_byte_range =
-                        old_offset + old_byte_range.start..old_offset + old_byte_range.end;
-                    let new_byte_range =
-                        new_offset + new_byte_range.start..new_offset + new_byte_range.end;
-                    let replacement_text = if new_byte_range.is_empty() {
-                        empty.clone()
+                push_word_diff(
+                    (old_text, old_byte_range),
+                    (new_text, new_byte_range),
+                    &options,
+                    &mut hunk_input,
+                    &empty,
+                    &mut edits,
+                );
+            } else if old_rows.len() == new_rows.len() {
+                // Hunks that rewrite many lines in place, such as a formatter changing
+                // indentation, are diffed line by line so that positions within unchanged
+                // words are preserved.
+                let old_lines = lines_with_terminator(&old_text[old_byte_range.clone()]);
+                let new_lines = lines_with_terminator(&new_text[new_byte_range.clone()]);
+                let mut old_offset = old_byte_range.start;
+                let mut new_offset = new_byte_range.start;
+                for (old_line, new_line) in old_lines.zip(new_lines) {
+                    let old_line_range = old_offset..old_offset + old_line.len();
+                    let new_line_range = new_offset..new_offset + new_line.len();
+                    old_offset = old_line_range.end;
+                    new_offset = new_line_range.end;
+                    if old_line == new_line {
+                        continue;
+                    }
+                    if old_line.len() <= options.max_word_diff_len
+                        && new_line.len() <= options.max_word_diff_len
+                    {
+                        push_word_diff(
+                            (old_text, old_line_range),
+                            (new_text, new_line_range),
+                            &options,
+                            &mut hunk_input,
+                            &empty,
+                            &mut edits,
+                        );
                     } else {
-                        new_text[new_byte_range].into()
-                    };
-                    edits.push((old_byte_range, replacement_text));
-                });
+                        edits.push((old_line_range, new_text[new_line_range].into()));
+                    }
+                }
             } else {
                 let replacement_text = if new_byte_range.is_empty() {
                     empty.clone()
@@ -126,6 +143,38 @@ pub fn apply_diff_patch(base_text: &str, patch: &str) -> Result<String, anyhow::
     result.map_err(|err| anyhow!(err))
 }
 
+/// Diffs the words of a hunk, pushing edits expressed in offsets of the whole text.
+fn push_word_diff<'a>(
+    (old_text, old_byte_range): (&'a str, Range<usize>),
+    (new_text, new_byte_range): (&'a str, Range<usize>),
+    options: &DiffOptions,
+    hunk_input: &mut InternedInput<&'a str>,
+    empty: &Arc<str>,
+    edits: &mut Vec<(Range<usize>, Arc<str>)>,
+) {
+    let old_offset = old_byte_range.start;
+    let new_offset = new_byte_range.start;
+    hunk_input.clear();
+    hunk_input.update_before(tokenize(
+        &old_text[old_byte_range],
+        options.language_scope.clone(),
+    ));
+    hunk_input.update_after(tokenize(
+        &new_text[new_byte_range],
+        options.language_scope.clone(),
+    ));
+    diff_internal(hunk_input, |old_byte_range, new_byte_range, _, _| {
+        let old_byte_range = old_offset + old_byte_range.start..old_offset + old_byte_range.end;
+        let new_byte_range = new_offset + new_byte_range.start..new_offset + new_byte_range.end;
+        let replacement_text = if new_byte_range.is_empty() {
+            empty.clone()
+        } else {
+            new_text[new_byte_range].into()
+        };
+        edits.push((old_byte_range, replacement_text));
+    });
+}
+
 fn should_perform_word_diff_within_hunk(
     old_row_range: &Range<u32>,
     old_byte_range: &Range<usize>,
@@ -278,6 +327,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_text_diff_reindenting_many_lines() {
+        let old_text = (0..10).map(|ix| format!("line{ix}\n")).collect::<String>();
+        let new_text = (0..10)
+            .map(|ix| format!("    line{ix}\n"))
+            .collect::<String>();
+        assert_eq!(
+            text_diff(&old_text, &new_text),
+            (0..10)
+                .map(|ix| (ix * 6..ix * 6, "    ".into()))
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn test_apply_diff_patch() {
         let old_text = "one two\nthree four five\nsix seven eight nine\nten\n";
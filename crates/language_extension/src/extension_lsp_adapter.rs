@@ -160,7 +160,7 @@ impl DynLspInstaller for ExtensionLspAdapter {
         delegate: Arc<dyn LspAdapterDelegate>,
         _: Option<Toolchain>,
         _: LanguageServerBinaryOptions,
-        _: &'a mut Option<(bool, LanguageServerBinary)>,
+        _: &'a mut Option<(LanguageServerBinaryOptions, LanguageServerBinary)>,
         _: &'a mut AsyncApp,
     ) -> Pin<Box<dyn 'a + Future<Output = Result<LanguageServerBinary>>>> {
         async move {
@@ -205,6 +205,7 @@ impl DynLspInstaller for ExtensionLspAdapter {
         _: &Arc<dyn LspAdapterDelegate>,
         _: PathBuf,
         _: bool,
+        _: Option<&str>,
         _: &mut AsyncApp,
     ) -> Result<LanguageServerBinary> {
         unreachable!("get_language_server_command is overridden")
@@ -0,0 +1,86 @@
+use editor::Editor;
+use gpui::{Context, Entity, IntoElement, ParentElement, Render, Subscription, Window, div};
+use language::language_settings::language_settings_with_detected_indent;
+use ui::{Button, ButtonCommon, FluentBuilder, LabelSize, Tooltip};
+use workspace::{StatusItemView, item::ItemHandle};
+
+struct Indentation {
+    label: String,
+    detected: bool,
+}
+
+/// Shows the indentation used when editing the active buffer, and whether it was detected
+/// from the file's contents.
+#[derive(Default)]
+pub struct ActiveBufferIndentation {
+    indentation: Option<Indentation>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl ActiveBufferIndentation {
+    fn update_indentation(
+        &mut self,
+        editor: Entity<Editor>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.indentation = None;
+
+        let editor = editor.read(cx);
+        if let Some((_, buffer, _)) = editor.active_excerpt(cx) {
+            let buffer = buffer.read(cx);
+            let settings = language_settings_with_detected_indent(
+                buffer.language().map(|language| language.name()),
+                buffer.file(),
+                buffer.detected_indent(),
+                cx,
+            );
+            let label = if settings.hard_tabs {
+                format!("Tab Size: {}", settings.tab_size)
+            } else {
+                format!("Spaces: {}", settings.tab_size)
+            };
+            let detected = settings.detect_indentation && buffer.detected_indent().is_some();
+            self.indentation = Some(Indentation { label, detected });
+        }
+
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferIndentation {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.indentation.as_ref(), |el, indentation| {
+            let tooltip = if indentation.detected {
+                "Indentation detected from the file's contents"
+            } else {
+                "Indentation from settings"
+            };
+            el.child(
+                Button::new("active-buffer-indentation", indentation.label.clone())
+                    .label_size(LabelSize::Small)
+                    .tooltip(Tooltip::text(tooltip)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferIndentation {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor =
+                Some(cx.observe_in(&editor, window, Self::update_indentation));
+            self.update_indentation(editor, window, cx);
+        } else {
+            self.indentation = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
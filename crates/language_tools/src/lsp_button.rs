@@ -216,6 +216,15 @@ impl LanguageServerState {
                 .update(cx, |lsp_store, _| lsp_store.as_remote().is_some())
                 .unwrap_or(false);
             let has_logs = is_remote || lsp_logs.read(cx).has_server_logs(&server_selector);
+            let server_version = server_info
+                .id
+                .and_then(|id| {
+                    self.lsp_store
+                        .read_with(cx, |lsp_store, _| lsp_store.language_server_for_id(id))
+                        .ok()
+                        .flatten()
+                })
+                .and_then(|server| server.version());
 
             let status_color = server_info
                 .binary_status
@@ -263,7 +272,14 @@ impl LanguageServerState {
                             h_flex()
                                 .gap_2()
                                 .child(Indicator::dot().color(status_color))
-                                .child(Label::new(server_name.0.clone())),
+                                .child(Label::new(server_name.0.clone()))
+                                .when_some(server_version.clone(), |div, version| {
+                                    div.child(
+                                        Label::new(version)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                }),
                         )
                         .when_some(hover_label, |div, hover_label| {
                             div.child(
@@ -1,8 +1,8 @@
 ("(" @open ")" @close)
 ("[" @open "]" @close)
 ("{" @open "}" @close)
-("\"" @open "\"" @close)
-("`" @open "`" @close)
+(("\"" @open "\"" @close) (#set! rainbow.exclude))
+(("`" @open "`" @close) (#set! rainbow.exclude))
 (("do" @open "done" @close) (#set! newline.only))
 ((case_statement ("in" @open "esac" @close)) (#set! newline.only))
 ((if_statement (elif_clause ("then" @open)) (else_clause ("else" @close))) (#set! newline.only))
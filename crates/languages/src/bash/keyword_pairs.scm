@@ -0,0 +1,37 @@
+(if_statement
+  "if" @keyword
+  "then" @keyword
+  "fi" @keyword)
+
+(if_statement
+  "if" @keyword
+  (elif_clause "elif" @keyword))
+
+(if_statement
+  "if" @keyword
+  (else_clause "else" @keyword))
+
+(elif_clause
+  "elif" @keyword
+  "then" @keyword)
+
+(case_statement
+  "case" @keyword
+  "in" @keyword
+  "esac" @keyword)
+
+(do_group
+  "do" @keyword
+  "done" @keyword)
+
+(for_statement
+  ["for" "select"] @keyword
+  body: (do_group "do" @keyword))
+
+(c_style_for_statement
+  "for" @keyword
+  body: (do_group "do" @keyword))
+
+(while_statement
+  ["while" "until"] @keyword
+  body: (do_group "do" @keyword))
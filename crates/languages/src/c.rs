@@ -2,7 +2,10 @@ use anyhow::{Context as _, Result, bail};
 use async_trait::async_trait;
 use futures::StreamExt;
 use gpui::{App, AsyncApp};
-use http_client::github::{AssetKind, GitHubLspBinaryVersion, latest_github_release};
+use http_client::github::{
+    AssetKind, GitHubLspBinaryVersion, GithubRelease, get_release_by_tag_name,
+    latest_github_release,
+};
 pub use language::*;
 use lsp::{InitializeParams, LanguageServerBinary, LanguageServerName};
 use project::lsp_store::clangd_ext;
@@ -17,20 +20,8 @@ pub struct CLspAdapter;
 
 impl CLspAdapter {
     const SERVER_NAME: LanguageServerName = LanguageServerName::new_static("clangd");
-}
 
-impl LspInstaller for CLspAdapter {
-    type BinaryVersion = GitHubLspBinaryVersion;
-
-    async fn fetch_latest_server_version(
-        &self,
-        delegate: &dyn LspAdapterDelegate,
-        pre_release: bool,
-        _: &mut AsyncApp,
-    ) -> Result<GitHubLspBinaryVersion> {
-        let release =
-            latest_github_release("clangd/clangd", true, pre_release, delegate.http_client())
-                .await?;
+    fn binary_version_for_release(release: GithubRelease) -> Result<GitHubLspBinaryVersion> {
         let os_suffix = match consts::OS {
             "macos" => "mac",
             "linux" => "linux",
@@ -50,6 +41,33 @@ impl LspInstaller for CLspAdapter {
         };
         Ok(version)
     }
+}
+
+impl LspInstaller for CLspAdapter {
+    type BinaryVersion = GitHubLspBinaryVersion;
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+        pre_release: bool,
+        _: &mut AsyncApp,
+    ) -> Result<GitHubLspBinaryVersion> {
+        let release =
+            latest_github_release("clangd/clangd", true, pre_release, delegate.http_client())
+                .await?;
+        Self::binary_version_for_release(release)
+    }
+
+    async fn fetch_server_version(
+        &self,
+        version: &str,
+        delegate: &dyn LspAdapterDelegate,
+        _: &mut AsyncApp,
+    ) -> Result<GitHubLspBinaryVersion> {
+        let release =
+            get_release_by_tag_name("clangd/clangd", version, delegate.http_client()).await?;
+        Self::binary_version_for_release(release)
+    }
 
     async fn check_if_user_installed(
         &self,
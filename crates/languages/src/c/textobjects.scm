@@ -23,3 +23,5 @@
         "{"
         [(_) ","?]* @class.inside
         "}")) @class.around
+
+(preproc_include)+ @import.around
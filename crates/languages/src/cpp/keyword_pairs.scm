@@ -0,0 +1,23 @@
+(preproc_if
+  "#if" @keyword
+  "#endif" @keyword)
+
+(preproc_ifdef
+  ["#ifdef" "#ifndef"] @keyword
+  "#endif" @keyword)
+
+((_
+  ["#if" "#ifdef" "#ifndef" "#elif"] @keyword
+  alternative: (preproc_else "#else" @keyword)))
+
+((_
+  ["#if" "#ifdef" "#ifndef" "#elif"] @keyword
+  alternative: (preproc_elif "#elif" @keyword)))
+
+(if_statement
+  "if" @keyword
+  alternative: (else_clause "else" @keyword))
+
+(do_statement
+  "do" @keyword
+  "while" @keyword)
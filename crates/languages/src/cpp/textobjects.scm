@@ -29,3 +29,5 @@
       "{"
       [(_) ":"? ";"?]* @class.inside
       "}"?)) @class.around
+
+(preproc_include)+ @import.around
@@ -0,0 +1,5 @@
+(color_value) @color
+
+((call_expression
+  (function_name) @_name) @color
+  (#match? @_name "^(rgba?|hsla?)$"))
@@ -23,3 +23,5 @@
 (type_declaration) @class.around
 
 (comment)+ @comment.around
+
+(import_declaration)+ @import.around
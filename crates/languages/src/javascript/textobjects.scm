@@ -49,3 +49,5 @@
         "{"
         [(_) ";"?]* @class.inside
         "}" )) @class.around
+
+(import_statement)+ @import.around
@@ -1,3 +1,3 @@
 ("[" @open "]" @close)
 ("{" @open "}" @close)
-("\"" @open "\"" @close)
+(("\"" @open "\"" @close) (#set! rainbow.exclude))
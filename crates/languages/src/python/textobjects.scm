@@ -5,3 +5,9 @@
 
 (class_definition
   body: (_) @class.inside) @class.around
+
+[
+  (import_statement)
+  (import_from_statement)
+  (future_import_statement)
+]+ @import.around
@@ -4,7 +4,9 @@ use collections::HashMap;
 use futures::StreamExt;
 use gpui::{App, AppContext, AsyncApp, SharedString, Task};
 use http_client::github::AssetKind;
-use http_client::github::{GitHubLspBinaryVersion, latest_github_release};
+use http_client::github::{
+    GitHubLspBinaryVersion, GithubRelease, get_release_by_tag_name, latest_github_release,
+};
 pub use language::*;
 use lsp::{InitializeParams, LanguageServerBinary};
 use project::lsp_store::rust_analyzer_ext::CARGO_DIAGNOSTICS_SOURCE_NAME;
@@ -72,6 +74,20 @@ impl RustLspAdapter {
             extension
         )
     }
+
+    fn binary_version_for_release(release: GithubRelease) -> Result<GitHubLspBinaryVersion> {
+        let asset_name = Self::build_asset_name();
+        let asset = release
+            .assets
+            .into_iter()
+            .find(|asset| asset.name == asset_name)
+            .with_context(|| format!("no asset found matching `{asset_name:?}`"))?;
+        Ok(GitHubLspBinaryVersion {
+            name: release.tag_name,
+            url: asset.browser_download_url,
+            digest: asset.digest,
+        })
+    }
 }
 
 pub(crate) struct CargoManifestProvider;
@@ -412,17 +428,19 @@ impl LspInstaller for RustLspAdapter {
             delegate.http_client(),
         )
         .await?;
-        let asset_name = Self::build_asset_name();
-        let asset = release
-            .assets
-            .into_iter()
-            .find(|asset| asset.name == asset_name)
-            .with_context(|| format!("no asset found matching `{asset_name:?}`"))?;
-        Ok(GitHubLspBinaryVersion {
-            name: release.tag_name,
-            url: asset.browser_download_url,
-            digest: asset.digest,
-        })
+        Self::binary_version_for_release(release)
+    }
+
+    async fn fetch_server_version(
+        &self,
+        version: &str,
+        delegate: &dyn LspAdapterDelegate,
+        _: &mut AsyncApp,
+    ) -> Result<GitHubLspBinaryVersion> {
+        let release =
+            get_release_by_tag_name("rust-lang/rust-analyzer", version, delegate.http_client())
+                .await?;
+        Self::binary_version_for_release(release)
     }
 
     async fn fetch_server_binary(
@@ -13,3 +13,29 @@
     (token_tree) @injection.content
     (#set! injection.language "rstml")
     )
+
+; Strings assigned to a variable named after their language, e.g. `let user_sql = "..."`
+(let_declaration
+    pattern: (identifier) @injection.language
+    value: [
+        (string_literal (string_content) @injection.content)
+        (raw_string_literal (string_content) @injection.content)
+    ]
+    (#set! injection.language-regex "(?:^|_)(?i:(css|graphql|html|sql))$"))
+
+(let_declaration
+    pattern: (identifier) @_name (#match? @_name "(?i)(^|_)query$")
+    value: [
+        (string_literal (string_content) @injection.content)
+        (raw_string_literal (string_content) @injection.content)
+    ]
+    (#set! injection.language "sql"))
+
+; Strings preceded by a comment naming their language, e.g. `/* language=sql */ "..."`
+((block_comment) @injection.language
+    .
+    [
+        (string_literal (string_content) @injection.content)
+        (raw_string_literal (string_content) @injection.content)
+    ]
+    (#set! injection.language-regex "^/[*][ ]*language=([a-zA-Z0-9_+#-]+)[ ]*[*]/$"))
@@ -0,0 +1,3 @@
+(if_expression
+  "if" @keyword
+  alternative: (else_clause "else" @keyword))
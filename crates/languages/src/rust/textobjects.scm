@@ -49,3 +49,9 @@
 (line_comment)+ @comment.around
 
 (block_comment) @comment.around
+
+; imports
+
+(use_declaration)+ @import.around
+
+(extern_crate_declaration)+ @import.around
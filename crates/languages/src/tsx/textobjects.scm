@@ -77,3 +77,5 @@
         "}" )) @class.around
 
 (type_alias_declaration) @class.around
+
+(import_statement)+ @import.around
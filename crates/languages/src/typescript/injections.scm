@@ -120,3 +120,29 @@
       ]
     )))
   (#set! injection.language "css"))
+
+;; Strings assigned to a variable named after their language, e.g. `const user_sql = "..."`
+(variable_declarator
+  name: (identifier) @injection.language
+  value: [
+    (string (string_fragment) @injection.content)
+    (template_string (string_fragment) @injection.content)
+  ]
+  (#set! injection.language-regex "(?:^|_)(?i:(css|graphql|html|sql))$"))
+
+(variable_declarator
+  name: (identifier) @_name (#match? @_name "(?i)(^|_)query$")
+  value: [
+    (string (string_fragment) @injection.content)
+    (template_string (string_fragment) @injection.content)
+  ]
+  (#set! injection.language "sql"))
+
+;; Strings preceded by a comment naming their language, e.g. `/* language=sql */ "..."`
+((comment) @injection.language
+  .
+  [
+    (string (string_fragment) @injection.content)
+    (template_string (string_fragment) @injection.content)
+  ]
+  (#set! injection.language-regex "^/[*][ ]*language=([a-zA-Z0-9_+#-]+)[ ]*[*]/$"))
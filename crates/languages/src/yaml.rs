@@ -50,6 +50,15 @@ impl LspInstaller for YamlLspAdapter {
             .await
     }
 
+    async fn fetch_server_version(
+        &self,
+        version: &str,
+        _: &dyn LspAdapterDelegate,
+        _: &mut AsyncApp,
+    ) -> Result<String> {
+        node_runtime::pinned_npm_package_version(Self::PACKAGE_NAME, version)
+    }
+
     async fn check_if_user_installed(
         &self,
         delegate: &dyn LspAdapterDelegate,
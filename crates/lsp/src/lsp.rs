@@ -78,6 +78,8 @@ pub struct LanguageServerBinaryOptions {
     pub allow_binary_download: bool,
     /// Whether the adapter should download a pre-release version
     pub pre_release: bool,
+    /// The version the adapter should download instead of the latest one, if pinned
+    pub version: Option<String>,
 }
 
 /// A running language server process.
@@ -87,6 +89,7 @@ pub struct LanguageServer {
     outbound_tx: channel::Sender<String>,
     name: LanguageServerName,
     process_name: Arc<str>,
+    version: Option<SharedString>,
     binary: LanguageServerBinary,
     capabilities: RwLock<ServerCapabilities>,
     /// Configuration sent to the server, stored for display in the language server logs
@@ -491,6 +494,7 @@ impl LanguageServer {
                 .file_name()
                 .map(|name| Arc::from(name.to_string_lossy()))
                 .unwrap_or_default(),
+            version: None,
             binary,
             capabilities: Default::default(),
             configuration,
@@ -740,6 +744,7 @@ impl LanguageServer {
                     completion: Some(CompletionClientCapabilities {
                         completion_item: Some(CompletionItemCapability {
                             snippet_support: Some(true),
+                            commit_characters_support: Some(true),
                             resolve_support: Some(CompletionItemCapabilityResolveSupport {
                                 properties: vec![
                                     "additionalTextEdits".to_string(),
@@ -846,6 +851,9 @@ impl LanguageServer {
                         dynamic_registration: Some(true),
                         ..DocumentSymbolClientCapabilities::default()
                     }),
+                    call_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
                     diagnostic: Some(DiagnosticClientCapabilities {
                         dynamic_registration: Some(true),
                         related_document_support: Some(true),
@@ -905,6 +913,7 @@ impl LanguageServer {
                 })?;
             if let Some(info) = response.server_info {
                 self.process_name = info.name.into();
+                self.version = info.version.map(SharedString::from);
             }
             self.capabilities = RwLock::new(response.capabilities);
             self.configuration = configuration;
@@ -1136,6 +1145,11 @@ impl LanguageServer {
         &self.process_name
     }
 
+    /// Get the version of the running language server, as reported when it was initialized.
+    pub fn version(&self) -> Option<SharedString> {
+        self.version.clone()
+    }
+
     /// Get the reported capabilities of the running language server.
     pub fn capabilities(&self) -> ServerCapabilities {
         self.capabilities.read().clone()
@@ -0,0 +1,233 @@
+use std::{
+    iter::{self, Peekable},
+    ops::Range,
+    sync::Arc,
+};
+
+use gpui::{HighlightStyle, Hsla};
+use language::{Language, LanguageRegistry, Rope};
+use theme::SyntaxTheme;
+
+/// The colors used for the lines of diff code blocks.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DiffColors {
+    pub inserted: Hsla,
+    pub inserted_background: Hsla,
+    pub deleted: Hsla,
+    pub deleted_background: Hsla,
+    pub hunk_header: Hsla,
+}
+
+/// If `name` is the language of a diff code block, like `diff` or `diff-rust`, returns the name
+/// of the language of the diffed code, which is empty when the code block doesn't specify one.
+pub(crate) fn diff_code_language(name: &str) -> Option<&str> {
+    let prefix = name.get(..4)?;
+    if !prefix.eq_ignore_ascii_case("diff") {
+        return None;
+    }
+    match &name[4..] {
+        "" => Some(""),
+        rest => rest.strip_prefix('-'),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffLineKind {
+    Header,
+    HunkHeader,
+    Context,
+    Inserted,
+    Deleted,
+}
+
+fn diff_line_kinds(text: &str) -> impl Iterator<Item = (Range<usize>, DiffLineKind)> + '_ {
+    let mut seen_hunk = false;
+    let mut offset = 0;
+    text.split_inclusive('\n').map(move |line| {
+        let range = offset..offset + line.len();
+        offset = range.end;
+        let kind = if line.starts_with("@@") {
+            seen_hunk = true;
+            DiffLineKind::HunkHeader
+        } else if !seen_hunk
+            && ["diff ", "index ", "+++ ", "--- "]
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        {
+            DiffLineKind::Header
+        } else if line.starts_with('+') {
+            DiffLineKind::Inserted
+        } else if line.starts_with('-') {
+            DiffLineKind::Deleted
+        } else {
+            DiffLineKind::Context
+        };
+        (range, kind)
+    })
+}
+
+/// Highlights the lines of a diff code block by whether they were inserted or deleted, applying
+/// the highlighting of the diffed code's language to their contents when it's known.
+pub(crate) fn highlight_diff(
+    text: &str,
+    language: Option<&Arc<Language>>,
+    language_registry: Option<&Arc<LanguageRegistry>>,
+    syntax_theme: &SyntaxTheme,
+    colors: &DiffColors,
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    let code_highlights = match language {
+        Some(language) => {
+            // Blank out everything that isn't code, keeping offsets the same, so that the
+            // code can be highlighted as a whole.
+            let mut code = String::with_capacity(text.len());
+            for (range, kind) in diff_line_kinds(text) {
+                let line = &text[range];
+                match kind {
+                    DiffLineKind::Header | DiffLineKind::HunkHeader => {
+                        let content_len = line.trim_end_matches('\n').len();
+                        code.extend(iter::repeat_n(' ', content_len));
+                        code.push_str(&line[content_len..]);
+                    }
+                    DiffLineKind::Inserted | DiffLineKind::Deleted => {
+                        code.push(' ');
+                        code.push_str(&line[1..]);
+                    }
+                    DiffLineKind::Context => code.push_str(line),
+                }
+            }
+            let rope = Rope::from(code.as_str());
+            let highlights = match language_registry {
+                Some(registry) => {
+                    language.highlight_text_with_injections(&rope, 0..code.len(), registry.clone())
+                }
+                None => language.highlight_text(&rope, 0..code.len()),
+            };
+            highlights
+                .into_iter()
+                .filter_map(|(range, highlight_id)| {
+                    Some((range, highlight_id.style(syntax_theme)?))
+                })
+                .collect::<Vec<_>>()
+        }
+        None => Vec::new(),
+    };
+
+    let mut code_highlights = code_highlights.into_iter().peekable();
+    let mut highlights = Vec::new();
+    for (line_range, kind) in diff_line_kinds(text) {
+        let (marker_color, background_color) = match kind {
+            DiffLineKind::Header => {
+                highlights.push((
+                    line_range,
+                    HighlightStyle {
+                        font_weight: Some(gpui::FontWeight::BOLD),
+                        ..Default::default()
+                    },
+                ));
+                continue;
+            }
+            DiffLineKind::HunkHeader => {
+                highlights.push((line_range, colors.hunk_header.into()));
+                continue;
+            }
+            DiffLineKind::Context => {
+                push_code_highlights(
+                    line_range,
+                    HighlightStyle::default(),
+                    &mut code_highlights,
+                    &mut highlights,
+                );
+                continue;
+            }
+            DiffLineKind::Inserted => (colors.inserted, colors.inserted_background),
+            DiffLineKind::Deleted => (colors.deleted, colors.deleted_background),
+        };
+
+        let line_style = HighlightStyle {
+            background_color: Some(background_color),
+            // Without a language, the lines are colored entirely like their markers.
+            color: language.is_none().then_some(marker_color),
+            ..Default::default()
+        };
+        highlights.push((
+            line_range.start..line_range.start + 1,
+            HighlightStyle {
+                color: Some(marker_color),
+                ..line_style
+            },
+        ));
+        push_code_highlights(
+            line_range.start + 1..line_range.end,
+            line_style,
+            &mut code_highlights,
+            &mut highlights,
+        );
+    }
+    highlights
+}
+
+/// Highlights `range` with `line_style`, combined with the code highlights that intersect it.
+fn push_code_highlights(
+    range: Range<usize>,
+    line_style: HighlightStyle,
+    code_highlights: &mut Peekable<impl Iterator<Item = (Range<usize>, HighlightStyle)>>,
+    highlights: &mut Vec<(Range<usize>, HighlightStyle)>,
+) {
+    let mut offset = range.start;
+    while let Some((code_range, code_style)) = code_highlights.peek() {
+        if code_range.start >= range.end {
+            break;
+        }
+        let start = code_range.start.max(offset);
+        let end = code_range.end.min(range.end);
+        if start < end {
+            if start > offset {
+                highlights.push((offset..start, line_style));
+            }
+            highlights.push((start..end, line_style.highlight(*code_style)));
+            offset = end;
+        }
+        if code_range.end > range.end {
+            break;
+        }
+        code_highlights.next();
+    }
+    if offset < range.end {
+        highlights.push((offset..range.end, line_style));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_code_language() {
+        assert_eq!(diff_code_language("diff"), Some(""));
+        assert_eq!(diff_code_language("Diff"), Some(""));
+        assert_eq!(diff_code_language("diff-rust"), Some("rust"));
+        assert_eq!(diff_code_language("diffrust"), None);
+        assert_eq!(diff_code_language("rust"), None);
+        assert_eq!(diff_code_language("di"), None);
+    }
+
+    #[test]
+    fn test_diff_line_kinds() {
+        let text = "--- a/x\n+++ b/x\n@@ -1 +1 @@\n a\n-b\n+c\n--- d\n";
+        let kinds = diff_line_kinds(text)
+            .map(|(range, kind)| (&text[range], kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                ("--- a/x\n", DiffLineKind::Header),
+                ("+++ b/x\n", DiffLineKind::Header),
+                ("@@ -1 +1 @@\n", DiffLineKind::HunkHeader),
+                (" a\n", DiffLineKind::Context),
+                ("-b\n", DiffLineKind::Deleted),
+                ("+c\n", DiffLineKind::Inserted),
+                ("--- d\n", DiffLineKind::Deleted),
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod diff_highlighting;
 pub mod parser;
 mod path_range;
 
@@ -35,6 +36,7 @@ use theme::SyntaxTheme;
 use ui::{Tooltip, prelude::*};
 use util::ResultExt;
 
+use crate::diff_highlighting::{DiffColors, diff_code_language, highlight_diff};
 use crate::parser::CodeBlockKind;
 
 /// A callback function that can be used to customize the style of links based on the destination URL.
@@ -322,7 +324,17 @@ impl Markdown {
             let mut languages_by_path = TreeMap::default();
             if let Some(registry) = language_registry.as_ref() {
                 for name in language_names {
-                    let language = if !name.is_empty() {
+                    // Diff code blocks, like `diff-rust`, are highlighted using the language
+                    // of the diffed code.
+                    let diff_language = diff_code_language(&name).map(str::to_string);
+                    let language = if let Some(diff_language) = diff_language {
+                        if diff_language.is_empty() {
+                            continue;
+                        }
+                        registry
+                            .language_for_name_or_extension(&diff_language)
+                            .left_future()
+                    } else if !name.is_empty() {
                         registry.language_for_name_or_extension(&name).left_future()
                     } else if let Some(fallback) = &fallback {
                         registry.language_for_name(fallback.as_ref()).right_future()
@@ -748,11 +760,21 @@ impl Element for MarkdownElement {
         window: &mut Window,
         cx: &mut App,
     ) -> (gpui::LayoutId, Self::RequestLayoutState) {
+        let markdown = self.markdown.read(cx);
+        let status_colors = cx.theme().status();
+        let diff_colors = DiffColors {
+            inserted: status_colors.created,
+            inserted_background: status_colors.created_background,
+            deleted: status_colors.deleted,
+            deleted_background: status_colors.deleted_background,
+            hunk_header: cx.theme().colors().text_muted,
+        };
         let mut builder = MarkdownElementBuilder::new(
             self.style.base_text_style.clone(),
             self.style.syntax.clone(),
+            diff_colors,
+            markdown.language_registry.clone(),
         );
-        let markdown = self.markdown.read(cx);
         let parsed_markdown = &markdown.parsed_markdown;
         let images = &markdown.images_by_source_offset;
         let markdown_end = if let Some(last) = parsed_markdown.events.last() {
@@ -835,6 +857,17 @@ impl Element for MarkdownElement {
                                     .cloned(),
                                 _ => None,
                             };
+                            let highlighting = match kind {
+                                CodeBlockKind::FencedLang(name)
+                                    if diff_code_language(name).is_some() =>
+                                {
+                                    CodeBlockHighlighting::Diff(language)
+                                }
+                                _ => language.map_or(
+                                    CodeBlockHighlighting::Plain,
+                                    CodeBlockHighlighting::Language,
+                                ),
+                            };
 
                             current_code_block_metadata = Some(metadata.clone());
 
@@ -876,7 +909,7 @@ impl Element for MarkdownElement {
                                     {
                                         builder.push_text_style(code_block_text_style.to_owned());
                                     }
-                                    builder.push_code_block(language);
+                                    builder.push_code_block(highlighting);
                                     builder.push_div(code_block, range, markdown_end);
                                 }
                                 (CodeBlockRenderer::Custom { render, .. }, _) => {
@@ -914,7 +947,7 @@ impl Element for MarkdownElement {
                                         builder.push_text_style(code_block_text_style.to_owned());
                                     }
 
-                                    builder.push_code_block(language);
+                                    builder.push_code_block(highlighting);
                                     builder.push_div(code_block, range, markdown_end);
                                 }
                             }
@@ -1419,10 +1452,20 @@ struct MarkdownElementBuilder {
     html_comment: bool,
     base_text_style: TextStyle,
     text_style_stack: Vec<TextStyleRefinement>,
-    code_block_stack: Vec<Option<Arc<Language>>>,
+    code_block_stack: Vec<CodeBlockHighlighting>,
     list_stack: Vec<ListStackEntry>,
     table_alignments: Vec<Alignment>,
     syntax_theme: Arc<SyntaxTheme>,
+    diff_colors: DiffColors,
+    language_registry: Option<Arc<LanguageRegistry>>,
+}
+
+/// How the text of a code block is highlighted.
+enum CodeBlockHighlighting {
+    Plain,
+    Language(Arc<Language>),
+    /// A diff, whose lines are highlighted using the language of the diffed code, if known.
+    Diff(Option<Arc<Language>>),
 }
 
 #[derive(Default)]
@@ -1437,7 +1480,12 @@ struct ListStackEntry {
 }
 
 impl MarkdownElementBuilder {
-    fn new(base_text_style: TextStyle, syntax_theme: Arc<SyntaxTheme>) -> Self {
+    fn new(
+        base_text_style: TextStyle,
+        syntax_theme: Arc<SyntaxTheme>,
+        diff_colors: DiffColors,
+        language_registry: Option<Arc<LanguageRegistry>>,
+    ) -> Self {
         Self {
             div_stack: vec![div().debug_selector(|| "inner".into()).into()],
             rendered_lines: Vec::new(),
@@ -1451,6 +1499,8 @@ impl MarkdownElementBuilder {
             list_stack: Vec::new(),
             table_alignments: Vec::new(),
             syntax_theme,
+            diff_colors,
+            language_registry,
         }
     }
 
@@ -1531,8 +1581,8 @@ impl MarkdownElementBuilder {
         self.list_stack.pop();
     }
 
-    fn push_code_block(&mut self, language: Option<Arc<Language>>) {
-        self.code_block_stack.push(language);
+    fn push_code_block(&mut self, highlighting: CodeBlockHighlighting) {
+        self.code_block_stack.push(highlighting);
     }
 
     fn pop_code_block(&mut self) {
@@ -1554,32 +1604,49 @@ impl MarkdownElementBuilder {
         self.pending_line.text.push_str(text);
         self.current_source_index = source_range.end;
 
-        if let Some(Some(language)) = self.code_block_stack.last() {
-            let mut offset = 0;
-            for (range, highlight_id) in language.highlight_text(&Rope::from(text), 0..text.len()) {
-                if range.start > offset {
-                    self.pending_line
-                        .runs
-                        .push(self.text_style().to_run(range.start - offset));
-                }
-
-                let mut run_style = self.text_style();
-                if let Some(highlight) = highlight_id.style(&self.syntax_theme) {
-                    run_style = run_style.highlight(highlight);
-                }
-                self.pending_line.runs.push(run_style.to_run(range.len()));
-                offset = range.end;
+        let highlights = match self.code_block_stack.last() {
+            Some(CodeBlockHighlighting::Language(language)) => {
+                let rope = Rope::from(text);
+                let highlights = match &self.language_registry {
+                    Some(registry) => language.highlight_text_with_injections(
+                        &rope,
+                        0..text.len(),
+                        registry.clone(),
+                    ),
+                    None => language.highlight_text(&rope, 0..text.len()),
+                };
+                highlights
+                    .into_iter()
+                    .filter_map(|(range, highlight_id)| {
+                        Some((range, highlight_id.style(&self.syntax_theme)?))
+                    })
+                    .collect()
             }
+            Some(CodeBlockHighlighting::Diff(language)) => highlight_diff(
+                text,
+                language.as_ref(),
+                self.language_registry.as_ref(),
+                &self.syntax_theme,
+                &self.diff_colors,
+            ),
+            Some(CodeBlockHighlighting::Plain) | None => Vec::new(),
+        };
 
-            if offset < text.len() {
+        let mut offset = 0;
+        for (range, highlight) in highlights {
+            if range.start > offset {
                 self.pending_line
                     .runs
-                    .push(self.text_style().to_run(text.len() - offset));
+                    .push(self.text_style().to_run(range.start - offset));
             }
-        } else {
+            let run_style = self.text_style().highlight(highlight);
+            self.pending_line.runs.push(run_style.to_run(range.len()));
+            offset = range.end;
+        }
+        if offset < text.len() {
             self.pending_line
                 .runs
-                .push(self.text_style().to_run(text.len()));
+                .push(self.text_style().to_run(text.len() - offset));
         }
     }
 
@@ -21,7 +21,9 @@ use language::{
     IndentSize, Language, LanguageScope, OffsetRangeExt, OffsetUtf16, Outline, OutlineItem, Point,
     PointUtf16, Selection, TextDimension, TextObject, ToOffset as _, ToPoint as _, TransactionId,
     TreeSitterOptions, Unclipped,
-    language_settings::{LanguageSettings, language_settings},
+    language_settings::{
+        LanguageSettings, language_settings, language_settings_with_detected_indent,
+    },
 };
 
 use rope::DimensionPair;
@@ -2442,7 +2444,9 @@ impl MultiBuffer {
                 self.capability = buffer.read(cx).capability();
                 return;
             }
-            BufferEvent::Operation { .. } | BufferEvent::ReloadNeeded => return,
+            BufferEvent::Operation { .. }
+            | BufferEvent::ReloadNeeded
+            | BufferEvent::LargeFileModeChanged => return,
         });
     }
 
@@ -2567,7 +2571,12 @@ impl MultiBuffer {
             .and_then(|buffer_id| self.buffer(buffer_id))
             .map(|buffer| {
                 let buffer = buffer.read(cx);
-                language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                language_settings_with_detected_indent(
+                    buffer.language().map(|l| l.name()),
+                    buffer.file(),
+                    buffer.detected_indent(),
+                    cx,
+                )
             })
             .unwrap_or_else(move || self.language_settings_at(0, cx))
     }
@@ -2579,12 +2588,19 @@ impl MultiBuffer {
     ) -> Cow<'a, LanguageSettings> {
         let mut language = None;
         let mut file = None;
+        let mut detected_indent = None;
         if let Some((buffer, offset)) = self.point_to_buffer_offset(point, cx) {
             let buffer = buffer.read(cx);
             language = buffer.language_at(offset);
             file = buffer.file();
+            detected_indent = buffer.detected_indent();
         }
-        language_settings(language.map(|l| l.name()), file, cx)
+        language_settings_with_detected_indent(
+            language.map(|l| l.name()),
+            file,
+            detected_indent,
+            cx,
+        )
     }
 
     pub fn for_each_buffer(&self, mut f: impl FnMut(&Entity<Buffer>)) {
@@ -5487,6 +5503,30 @@ impl MultiBufferSnapshot {
         ))
     }
 
+    /// Returns the ranges of the keywords that belong together with the keyword containing the given
+    /// range, such as `if`, `else` and `fi`, or None if there is no such keyword or the range is not
+    /// contained in a single excerpt
+    pub fn matching_keyword_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Option<Vec<Range<usize>>> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut excerpt = self.excerpt_containing(range.clone())?;
+        let keywords = excerpt
+            .buffer()
+            .matching_keyword_ranges(excerpt.map_range_to_buffer(range))?;
+        Some(
+            keywords
+                .into_iter()
+                .filter_map(|keyword| {
+                    excerpt
+                        .contains_buffer_range(keyword.clone())
+                        .then(|| excerpt.map_range_from_buffer(keyword))
+                })
+                .collect(),
+        )
+    }
+
     /// Returns enclosing bracket ranges containing the given range or returns None if the range is
     /// not contained in a single excerpt
     pub fn enclosing_bracket_ranges<T: ToOffset>(
@@ -5815,6 +5855,56 @@ impl MultiBufferSnapshot {
         Some((start_row..end_row, indent))
     }
 
+    /// Like [`Self::enclosing_indent`], but determines the block containing the given row from
+    /// the syntax tree rather than from the indentation of the surrounding lines. Returns `None`
+    /// when the row is blank or there is no syntax node around it whose body is indented.
+    pub fn enclosing_syntax_indent(
+        &self,
+        row: MultiBufferRow,
+    ) -> Option<(Range<MultiBufferRow>, LineIndent)> {
+        let line_indent = self.line_indent_for_row(row);
+        if line_indent.is_line_empty() {
+            return None;
+        }
+
+        let offset = self.point_to_offset(Point::new(row.0, line_indent.raw_len()));
+        let mut range = offset..offset;
+        loop {
+            let (_, node_range) = self.syntax_ancestor(range)?;
+            let MultiOrSingleBufferOffsetRange::Multi(node_range) = node_range else {
+                return None;
+            };
+            range = node_range.clone();
+
+            let start_row = self.offset_to_point(node_range.start).row;
+            let end_row = self.offset_to_point(node_range.end).row;
+            let start_indent = self.line_indent_for_row(MultiBufferRow(start_row));
+            // The line closing a block, such as the one with its closing brace, belongs to the
+            // block around it.
+            if row.0 != start_row && line_indent.raw_len() <= start_indent.raw_len() {
+                continue;
+            }
+            let has_indented_body = (start_row + 1..=end_row).any(|body_row| {
+                let indent = self.line_indent_for_row(MultiBufferRow(body_row));
+                !indent.is_line_empty() && indent.raw_len() > start_indent.raw_len()
+            });
+            if !has_indented_body {
+                continue;
+            }
+
+            let end_indent = self.line_indent_for_row(MultiBufferRow(end_row));
+            let end_row = if end_indent.raw_len() > start_indent.raw_len() {
+                end_row
+            } else {
+                end_row - 1
+            };
+            return Some((
+                MultiBufferRow(start_row)..MultiBufferRow(end_row),
+                start_indent,
+            ));
+        }
+    }
+
     pub fn indent_guides_in_range<T: ToPoint>(
         &self,
         range: Range<T>,
@@ -5851,7 +5941,12 @@ impl MultiBufferSnapshot {
                 .get_or_insert_with(|| {
                     (
                         buffer.remote_id(),
-                        language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx),
+                        language_settings_with_detected_indent(
+                            buffer.language().map(|l| l.name()),
+                            buffer.file(),
+                            buffer.detected_indent(),
+                            cx,
+                        ),
                     )
                 })
                 .1;
@@ -5948,9 +6043,10 @@ impl MultiBufferSnapshot {
             .first()
             .map(|excerpt| &excerpt.buffer)
             .map(|buffer| {
-                language_settings(
+                language_settings_with_detected_indent(
                     buffer.language().map(|language| language.name()),
                     buffer.file(),
+                    buffer.detected_indent(),
                     cx,
                 )
             })
@@ -5964,11 +6060,18 @@ impl MultiBufferSnapshot {
     ) -> Cow<'a, LanguageSettings> {
         let mut language = None;
         let mut file = None;
+        let mut detected_indent = None;
         if let Some((buffer, offset)) = self.point_to_buffer_offset(point) {
             language = buffer.language_at(offset);
             file = buffer.file();
+            detected_indent = buffer.detected_indent();
         }
-        language_settings(language.map(|l| l.name()), file, cx)
+        language_settings_with_detected_indent(
+            language.map(|l| l.name()),
+            file,
+            detected_indent,
+            cx,
+        )
     }
 
     pub fn language_scope_at<T: ToOffset>(&self, point: T) -> Option<LanguageScope> {
@@ -6114,6 +6217,29 @@ impl MultiBufferSnapshot {
             .syntax_prev_sibling(excerpt.map_range_to_buffer(range))
     }
 
+    /// Returns the range of the syntax node around the given range that can be swapped with
+    /// its next or previous sibling, along with the range of that sibling.
+    pub fn syntax_node_to_swap<T: ToOffset>(
+        &self,
+        range: Range<T>,
+        next: bool,
+    ) -> Option<(Range<usize>, Range<usize>)> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut excerpt = self.excerpt_containing(range.clone())?;
+        let (node, sibling) = excerpt
+            .buffer()
+            .syntax_node_to_swap(excerpt.map_range_to_buffer(range), next)?;
+        if !excerpt.contains_buffer_range(node.clone())
+            || !excerpt.contains_buffer_range(sibling.clone())
+        {
+            return None;
+        }
+        Some((
+            excerpt.map_range_from_buffer(node),
+            excerpt.map_range_from_buffer(sibling),
+        ))
+    }
+
     pub fn outline(&self, theme: Option<&SyntaxTheme>) -> Option<Outline<Anchor>> {
         let (excerpt_id, _, buffer) = self.as_singleton()?;
         let outline = buffer.outline(theme);
@@ -756,6 +756,16 @@ pub async fn read_package_installed_version(
     Ok(Some(package_json.version))
 }
 
+/// Checks that `version` names a single release of the npm package `name`,
+/// rather than a range, a dist-tag or a URL, so that a pinned version can't
+/// make npm install something other than what the user asked for.
+pub fn pinned_npm_package_version(name: &str, version: &str) -> Result<String> {
+    let version = Version::parse(version.trim().trim_start_matches('v')).with_context(|| {
+        format!("{version:?} is not an exact version of npm package {name}, e.g. \"1.2.3\"")
+    })?;
+    Ok(version.to_string())
+}
+
 #[derive(Clone)]
 pub struct UnavailableNodeRuntime {
     error_message: Arc<String>,
@@ -830,3 +840,39 @@ fn configure_npm_command(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_npm_package_version() {
+        assert_eq!(
+            pinned_npm_package_version("pyright", "1.1.404").unwrap(),
+            "1.1.404"
+        );
+        assert_eq!(
+            pinned_npm_package_version("pyright", " v1.1.404 ").unwrap(),
+            "1.1.404"
+        );
+        assert_eq!(
+            pinned_npm_package_version("yaml-language-server", "1.19.0-next.1").unwrap(),
+            "1.19.0-next.1"
+        );
+        for version in [
+            "",
+            "latest",
+            "^1.1.404",
+            "1.1.x",
+            "1.1.404 || 1.1.405",
+            "file:../pyright",
+            "https://example.com/pyright.tgz",
+            "npm:evil@1.0.0",
+        ] {
+            assert!(
+                pinned_npm_package_version("pyright", version).is_err(),
+                "{version:?} should be rejected"
+            );
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use editor::{Anchor, AnchorRangeExt, Editor, scroll::Autoscroll};
+use editor::{Anchor, AnchorRangeExt, Editor, ToPoint, scroll::Autoscroll};
 use editor::{RowHighlightOptions, SelectionEffects};
 use fuzzy::StringMatch;
 use gpui::{
@@ -14,7 +14,7 @@ use gpui::{
 };
 use language::{Outline, OutlineItem};
 use ordered_float::OrderedFloat;
-use picker::{Picker, PickerDelegate};
+use picker::{Picker, PickerDelegate, PickerPreview, PickerPreviewSettings};
 use settings::Settings;
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{ListItem, ListItemSpacing, prelude::*};
@@ -22,6 +22,7 @@ use util::ResultExt;
 use workspace::{DismissDecision, ModalView};
 
 pub fn init(cx: &mut App) {
+    PickerPreviewSettings::register(cx);
     cx.observe_new(OutlineView::register).detach();
     zed_actions::outline::TOGGLE_OUTLINE
         .set(|view, window, cx| {
@@ -133,6 +134,8 @@ struct OutlineViewDelegate {
     prev_scroll_position: Option<Point<f32>>,
     matches: Vec<StringMatch>,
     last_query: String,
+    /// When set, the highlighted item is shown here instead of by scrolling the active editor.
+    preview: Option<Entity<PickerPreview>>,
 }
 
 enum OutlineRowHighlights {}
@@ -145,8 +148,15 @@ impl OutlineViewDelegate {
 
         cx: &mut Context<OutlineView>,
     ) -> Self {
+        let preview = editor
+            .read(cx)
+            .project()
+            .cloned()
+            .filter(|_| PickerPreviewSettings::get_global(cx).outline)
+            .map(|project| cx.new(|_| PickerPreview::new(project)));
         Self {
             outline_view,
+            preview,
             last_query: Default::default(),
             matches: Default::default(),
             selected_match_index: 0,
@@ -216,7 +226,41 @@ impl PickerDelegate for OutlineViewDelegate {
         _: &mut Window,
         cx: &mut Context<Picker<OutlineViewDelegate>>,
     ) {
-        self.set_selected_index(ix, true, cx);
+        self.set_selected_index(ix, self.preview.is_none(), cx);
+    }
+
+    fn update_preview(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(preview) = self.preview.clone() else {
+            return;
+        };
+        let multi_buffer = self.active_editor.read(cx).buffer().read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let target = multi_buffer.as_singleton().zip(
+            self.matches
+                .get(self.selected_match_index)
+                .and_then(|mat| self.outline.items.get(mat.candidate_id)),
+        );
+        let target = target.map(|(buffer, outline_item)| {
+            let rows = outline_item.range.start.to_point(&snapshot).row
+                ..outline_item.range.end.to_point(&snapshot).row;
+            (buffer, rows)
+        });
+
+        preview.update(cx, |preview, cx| match target {
+            Some((buffer, rows)) => preview.show_buffer(buffer, Some(rows), window, cx),
+            None => preview.clear(cx),
+        });
+    }
+
+    fn render_preview(
+        &self,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(self.preview.clone()?.into_any_element())
     }
 
     fn update_matches(
@@ -281,7 +325,8 @@ impl PickerDelegate for OutlineViewDelegate {
                 .unwrap_or(0);
         }
         self.last_query = query;
-        self.set_selected_index(selected_index, !self.last_query.is_empty(), cx);
+        let navigate = !self.last_query.is_empty() && self.preview.is_none();
+        self.set_selected_index(selected_index, navigate, cx);
         Task::ready(())
     }
 
@@ -14,12 +14,12 @@ use file_icons::FileIcons;
 use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
 use gpui::{
     Action, AnyElement, App, AppContext as _, AsyncWindowContext, Bounds, ClipboardItem, Context,
-    DismissEvent, Div, ElementId, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle,
-    InteractiveElement, IntoElement, KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior,
-    MouseButton, MouseDownEvent, ParentElement, Pixels, Point, Render, ScrollStrategy,
-    SharedString, Stateful, StatefulInteractiveElement as _, Styled, Subscription, Task,
-    UniformListScrollHandle, WeakEntity, Window, actions, anchored, deferred, div, point, px, size,
-    uniform_list,
+    Corner, DismissEvent, Div, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    HighlightStyle, InteractiveElement, IntoElement, KeyContext, ListHorizontalSizingBehavior,
+    ListSizingBehavior, MouseButton, MouseDownEvent, ParentElement, Pixels, Point, Render,
+    ScrollStrategy, SharedString, Stateful, StatefulInteractiveElement as _, Styled, Subscription,
+    Task, UniformListScrollHandle, WeakEntity, Window, actions, anchored, deferred, div, point, px,
+    size, uniform_list,
 };
 use itertools::Itertools;
 use language::{Anchor, BufferId, BufferSnapshot, OffsetRangeExt, OutlineItem};
@@ -28,6 +28,7 @@ use std::{
     cmp,
     collections::BTreeMap,
     hash::Hash,
+    iter,
     ops::Range,
     path::{MAIN_SEPARATOR_STR, Path, PathBuf},
     sync::{
@@ -47,9 +48,9 @@ use smol::channel;
 use theme::{SyntaxTheme, ThemeSettings};
 use ui::{
     ActiveTheme, ButtonCommon, Clickable, Color, ContextMenu, DynamicSpacing, FluentBuilder,
-    HighlightedLabel, Icon, IconButton, IconButtonShape, IconName, IconSize, IndentGuideColors,
-    IndentGuideLayout, Label, LabelCommon, ListItem, ScrollAxes, Scrollbars, StyledExt,
-    StyledTypography, Toggleable, Tooltip, WithScrollbar, h_flex, v_flex,
+    HighlightedLabel, Icon, IconButton, IconButtonShape, IconName, IconPosition, IconSize,
+    IndentGuideColors, IndentGuideLayout, Label, LabelCommon, ListItem, PopoverMenu, ScrollAxes,
+    Scrollbars, StyledExt, StyledTypography, Toggleable, Tooltip, WithScrollbar, h_flex, v_flex,
 };
 use util::{RangeExt, ResultExt, TryFutureExt, debug_panic};
 use workspace::{
@@ -123,6 +124,8 @@ pub struct OutlinePanel {
     excerpts: HashMap<BufferId, HashMap<ExcerptId, Excerpt>>,
     cached_entries: Vec<CachedEntry>,
     filter_editor: Entity<Editor>,
+    /// Only outline items declared with this keyword (e.g. `fn` or `struct`) are shown.
+    kind_filter: Option<SharedString>,
     mode: ItemsDisplayMode,
     max_width_item_index: Option<usize>,
     preserve_selection_on_buffer_fold_toggles: HashSet<BufferId>,
@@ -863,6 +866,7 @@ impl OutlinePanel {
                 scroll_handle,
                 focus_handle,
                 filter_editor,
+                kind_filter: None,
                 fs_entries: Vec::new(),
                 fs_entries_depth: HashMap::default(),
                 fs_children_count: HashMap::default(),
@@ -4315,6 +4319,13 @@ impl OutlinePanel {
                     .or_default()
                     .extend(outline_has_children);
 
+                if let Some(kind_filter) = &self.kind_filter {
+                    visible_outlines = all_outlines
+                        .into_iter()
+                        .filter(|outline| outline_kind(outline) == Some(kind_filter.as_ref()))
+                        .collect();
+                }
+
                 for outline in visible_outlines {
                     let outline_entry = OutlineEntryOutline {
                         buffer_id,
@@ -4715,12 +4726,82 @@ impl OutlinePanel {
         v_flex().w_full().flex_1().overflow_hidden().child(contents)
     }
 
+    /// All outline item kinds present in the panel's excerpts, sorted.
+    fn outline_kinds(&self) -> BTreeSet<SharedString> {
+        self.excerpts
+            .values()
+            .flat_map(|excerpts| excerpts.values())
+            .flat_map(|excerpt| excerpt.iter_outlines())
+            .filter_map(|outline| Some(SharedString::from(outline_kind(outline)?.to_string())))
+            .collect()
+    }
+
+    fn set_kind_filter(
+        &mut self,
+        kind_filter: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.kind_filter != kind_filter {
+            self.kind_filter = kind_filter;
+            self.update_cached_entries(None, window, cx);
+        }
+    }
+
+    fn render_kind_filter_menu(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let outline_panel = cx.weak_entity();
+        PopoverMenu::new("outline-panel-kind-filter")
+            .trigger_with_tooltip(
+                IconButton::new("outline-panel-kind-filter-button", IconName::Filter)
+                    .shape(IconButtonShape::Square)
+                    .toggle_state(self.kind_filter.is_some()),
+                Tooltip::text("Filter by Kind"),
+            )
+            .anchor(Corner::BottomRight)
+            .menu(move |window, cx| {
+                let (kinds, kind_filter) = outline_panel
+                    .read_with(cx, |outline_panel, _| {
+                        (
+                            outline_panel.outline_kinds(),
+                            outline_panel.kind_filter.clone(),
+                        )
+                    })
+                    .ok()?;
+                let outline_panel = outline_panel.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    let entries = iter::once(None).chain(kinds.into_iter().map(Some));
+                    for kind in entries {
+                        let toggled = kind == kind_filter;
+                        let label = kind.clone().unwrap_or_else(|| "All".into());
+                        let outline_panel = outline_panel.clone();
+                        menu = menu.toggleable_entry(
+                            label,
+                            toggled,
+                            IconPosition::End,
+                            None,
+                            move |window, cx| {
+                                outline_panel
+                                    .update(cx, |outline_panel, cx| {
+                                        outline_panel.set_kind_filter(kind.clone(), window, cx);
+                                    })
+                                    .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+    }
+
     fn render_filter_footer(&mut self, pinned: bool, cx: &mut Context<Self>) -> Div {
         v_flex().flex_none().child(horizontal_separator(cx)).child(
             h_flex()
                 .p_2()
                 .w_full()
                 .child(self.filter_editor.clone())
+                .when(matches!(self.mode, ItemsDisplayMode::Outline), |this| {
+                    this.child(self.render_kind_filter_menu(cx))
+                })
                 .child(
                     div().child(
                         IconButton::new(
@@ -4820,6 +4901,22 @@ fn back_to_common_visited_parent(
     None
 }
 
+/// The keyword an outline item is declared with, e.g. `fn` for `pub async fn main`.
+fn outline_kind(outline: &Outline) -> Option<&str> {
+    let name_start = outline.name_ranges.first()?.start;
+    outline
+        .text
+        .get(..name_start)?
+        .split_whitespace()
+        .filter_map(|word| {
+            let keyword_len = word
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '!')
+                .unwrap_or(word.len());
+            Some(&word[..keyword_len]).filter(|keyword| !keyword.is_empty())
+        })
+        .last()
+}
+
 fn file_name(path: &Path) -> String {
     let mut current_path = path;
     loop {
@@ -6360,6 +6457,133 @@ outline: struct OutlineEntryExcerpt
         });
     }
 
+    #[gpui::test]
+    async fn test_kind_filter(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let root = path!("/root");
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            root,
+            json!({
+                "src": {
+                    "lib.rs": indoc!("
+pub struct Point {}
+
+impl Point {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn main() {}"),
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [Path::new(root)], cx).await;
+        project.read_with(cx, |project, _| {
+            project.languages().add(Arc::new(
+                rust_lang()
+                    .with_outline_query(
+                        r#"
+                (struct_item
+                    (visibility_modifier)? @context
+                    "struct" @context
+                    name: (_) @name) @item
+
+                (impl_item
+                    "impl" @context
+                    type: (_) @name) @item
+
+                (function_item
+                    (visibility_modifier)? @context
+                    "fn" @context
+                    name: (_) @name) @item
+"#,
+                    )
+                    .unwrap(),
+            ))
+        });
+        let workspace = add_outline_panel(&project, cx).await;
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let outline_panel = outline_panel(&workspace, cx);
+        cx.update(|window, cx| {
+            outline_panel.update(cx, |outline_panel, cx| {
+                outline_panel.set_active(true, window, cx)
+            });
+        });
+
+        workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.open_abs_path(
+                    PathBuf::from(path!("/root/src/lib.rs")),
+                    OpenOptions {
+                        visible: Some(OpenVisible::All),
+                        ..Default::default()
+                    },
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .expect("Failed to open Rust source file");
+
+        cx.executor()
+            .advance_clock(UPDATE_DEBOUNCE + Duration::from_millis(100));
+        cx.run_until_parked();
+        outline_panel.update(cx, |outline_panel, cx| {
+            assert_eq!(
+                outline_panel
+                    .outline_kinds()
+                    .iter()
+                    .map(|kind| kind.as_ref())
+                    .collect::<Vec<_>>(),
+                ["fn", "impl", "struct"]
+            );
+            assert_eq!(
+                display_entries(
+                    &project,
+                    &snapshot(outline_panel, cx),
+                    &outline_panel.cached_entries,
+                    outline_panel.selected_entry(),
+                    cx,
+                ),
+                indoc!(
+                    "
+outline: pub struct Point
+outline: impl Point
+  outline: pub fn new
+outline: fn main"
+                )
+            );
+        });
+
+        cx.update(|window, cx| {
+            outline_panel.update(cx, |outline_panel, cx| {
+                outline_panel.set_kind_filter(Some("fn".into()), window, cx);
+            });
+        });
+        cx.run_until_parked();
+        outline_panel.update(cx, |outline_panel, cx| {
+            assert_eq!(
+                display_entries(
+                    &project,
+                    &snapshot(outline_panel, cx),
+                    &outline_panel.cached_entries,
+                    outline_panel.selected_entry(),
+                    cx,
+                ),
+                indoc!(
+                    "
+  outline: pub fn new
+outline: fn main"
+                )
+            );
+        });
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_frontend_repo_structure(cx: &mut TestAppContext) {
         init_test(cx);
@@ -417,6 +417,11 @@ pub fn local_tasks_file_relative_path() -> &'static Path {
     Path::new(".zed/tasks.json")
 }
 
+/// Returns the relative path to a `snippets` folder within a project.
+pub fn local_snippets_folder_relative_path() -> &'static Path {
+    Path::new(".zed/snippets")
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static Path {
     Path::new(".vscode/tasks.json")
@@ -1,6 +1,7 @@
 mod head;
 pub mod highlighted_match_with_paths;
 pub mod popover_menu;
+mod preview;
 
 use anyhow::Result;
 use editor::{
@@ -24,6 +25,8 @@ use ui::{
 };
 use workspace::ModalView;
 
+pub use preview::{PickerPreview, PickerPreviewSettings};
+
 enum ElementContainer {
     List(ListState),
     UniformList(UniformListScrollHandle),
@@ -222,6 +225,19 @@ pub trait PickerDelegate: Sized + 'static {
     ) -> Option<AnyElement> {
         None
     }
+
+    /// Called whenever the matches or the selected match change, so that the
+    /// delegate can point its [`PickerPreview`] at the selected match.
+    fn update_preview(&mut self, _window: &mut Window, _cx: &mut Context<Picker<Self>>) {}
+
+    /// Rendered between the matches and the footer, e.g. a [`PickerPreview`].
+    fn render_preview(
+        &self,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        None
+    }
 }
 
 impl<D: PickerDelegate> Focusable for Picker<D> {
@@ -411,6 +427,7 @@ impl<D: PickerDelegate> Picker<D> {
             if scroll_to_index {
                 self.scroll_to_item_index(ix);
             }
+            self.delegate.update_preview(window, cx);
         }
     }
 
@@ -648,6 +665,7 @@ impl<D: PickerDelegate> Picker<D> {
 
         let index = self.delegate.selected_index();
         self.scroll_to_item_index(index);
+        self.delegate.update_preview(window, cx);
         self.pending_update_matches = None;
         if let Some(secondary) = self.confirm_on_update.take() {
             self.do_confirm(secondary, window, cx);
@@ -855,6 +873,7 @@ impl<D: PickerDelegate> Render for Picker<D> {
                     )
                 })
             })
+            .children(self.delegate.render_preview(window, cx))
             .children(self.delegate.render_footer(window, cx))
             .children(match &self.head {
                 Head::Editor(editor) => {
@@ -0,0 +1,257 @@
+use std::{collections::VecDeque, ops::Range};
+
+use editor::{Editor, RowHighlightOptions, scroll::Autoscroll};
+use fs::Fs as _;
+use gpui::{App, Context, Entity, EntityId, Render, Task, Window};
+use language::{Buffer, Capability, Point};
+use project::{Project, ProjectPath};
+use settings::Settings;
+use ui::{prelude::*, v_flex};
+
+/// Files larger than this are not previewed.
+const MAX_PREVIEW_FILE_SIZE: u64 = 1024 * 1024;
+/// How many previewed files are kept in memory while the picker is open.
+const MAX_CACHED_PREVIEWS: usize = 16;
+
+/// Which pickers show a [`PickerPreview`] below their matches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickerPreviewSettings {
+    pub file_finder: bool,
+    pub project_symbols: bool,
+    pub outline: bool,
+}
+
+impl Settings for PickerPreviewSettings {
+    fn from_settings(content: &settings::SettingsContent, _cx: &mut App) -> Self {
+        let picker_preview = content.picker_preview.as_ref().unwrap();
+
+        Self {
+            file_finder: picker_preview.file_finder.unwrap(),
+            project_symbols: picker_preview.project_symbols.unwrap(),
+            outline: picker_preview.outline.unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PreviewKey {
+    Path(ProjectPath),
+    Buffer(EntityId),
+}
+
+/// A read-only, syntax-highlighted excerpt of the candidate highlighted in a picker.
+///
+/// Files that aren't open yet are loaded straight from disk into detached buffers,
+/// so previewing them neither opens a tab nor starts language servers. Editors for
+/// recently previewed files are cached, so moving between matches stays cheap.
+pub struct PickerPreview {
+    project: Entity<Project>,
+    /// Recently previewed files, the most recently used last.
+    editors: VecDeque<(PreviewKey, Entity<Editor>)>,
+    target: Option<(PreviewKey, Option<Range<u32>>)>,
+    active_editor: Option<Entity<Editor>>,
+    load_task: Task<()>,
+}
+
+impl PickerPreview {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self {
+            project,
+            editors: VecDeque::new(),
+            target: None,
+            active_editor: None,
+            load_task: Task::ready(()),
+        }
+    }
+
+    /// Previews the file at `path`, highlighting `rows` if given.
+    pub fn show_path(
+        &mut self,
+        path: ProjectPath,
+        rows: Option<Range<u32>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let key = PreviewKey::Path(path.clone());
+        if !self.set_target(key.clone(), rows.clone()) {
+            return;
+        }
+
+        if let Some(editor) = self.cached_editor(&key) {
+            self.activate(editor, rows, window, cx);
+            return;
+        }
+
+        if let Some(buffer) = self.project.read(cx).get_open_buffer(&path, cx) {
+            let editor = self.build_editor(buffer, window, cx);
+            self.cache_editor(key, editor.clone());
+            self.activate(editor, rows, window, cx);
+            return;
+        }
+
+        let project = self.project.read(cx);
+        let Some(abs_path) = project.absolute_path(&path, cx) else {
+            self.active_editor = None;
+            cx.notify();
+            return;
+        };
+        let fs = project.fs().clone();
+        let languages = project.languages().clone();
+        self.active_editor = None;
+        self.load_task = cx.spawn_in(window, async move |this, cx| {
+            let Some(metadata) = fs.metadata(&abs_path).await.ok().flatten() else {
+                return;
+            };
+            if metadata.is_dir || metadata.len > MAX_PREVIEW_FILE_SIZE {
+                return;
+            }
+            // Binary files fail to load as text and are simply not previewed.
+            let Some(text) = fs.load(&abs_path).await.ok() else {
+                return;
+            };
+            let language = languages.language_for_file_path(&path.path).await.ok();
+
+            this.update_in(cx, |this, window, cx| {
+                let buffer = cx.new(|cx| {
+                    let mut buffer = Buffer::local(text, cx);
+                    buffer.set_language_registry(languages);
+                    buffer.set_language(language, cx);
+                    buffer.set_capability(Capability::ReadOnly, cx);
+                    buffer
+                });
+                let editor = this.build_editor(buffer, window, cx);
+                this.cache_editor(key.clone(), editor.clone());
+                if let Some((target, rows)) = this.target.clone()
+                    && target == key
+                {
+                    this.activate(editor, rows, window, cx);
+                }
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    /// Previews an already loaded `buffer`, highlighting `rows` if given.
+    pub fn show_buffer(
+        &mut self,
+        buffer: Entity<Buffer>,
+        rows: Option<Range<u32>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let key = PreviewKey::Buffer(buffer.entity_id());
+        if !self.set_target(key.clone(), rows.clone()) {
+            return;
+        }
+
+        let editor = match self.cached_editor(&key) {
+            Some(editor) => editor,
+            None => {
+                let editor = self.build_editor(buffer, window, cx);
+                self.cache_editor(key, editor.clone());
+                editor
+            }
+        };
+        self.activate(editor, rows, window, cx);
+    }
+
+    /// Hides the preview, e.g. when the highlighted candidate isn't a file.
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.target = None;
+        self.active_editor = None;
+        self.load_task = Task::ready(());
+        cx.notify();
+    }
+
+    /// Returns whether the target changed.
+    fn set_target(&mut self, key: PreviewKey, rows: Option<Range<u32>>) -> bool {
+        let target = Some((key, rows));
+        if self.target == target {
+            return false;
+        }
+        self.target = target;
+        true
+    }
+
+    fn cached_editor(&mut self, key: &PreviewKey) -> Option<Entity<Editor>> {
+        let ix = self.editors.iter().position(|(cached, _)| cached == key)?;
+        let entry = self.editors.remove(ix)?;
+        let editor = entry.1.clone();
+        self.editors.push_back(entry);
+        Some(editor)
+    }
+
+    fn cache_editor(&mut self, key: PreviewKey, editor: Entity<Editor>) {
+        if self.editors.len() >= MAX_CACHED_PREVIEWS {
+            self.editors.pop_front();
+        }
+        self.editors.push_back((key, editor));
+    }
+
+    fn build_editor(
+        &self,
+        buffer: Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        cx.new(|cx| {
+            let mut editor = Editor::for_buffer(buffer, None, window, cx);
+            editor.set_read_only(true);
+            editor.set_show_gutter(false, cx);
+            editor.disable_scrollbars_and_minimap(window, cx);
+            editor
+        })
+    }
+
+    fn activate(
+        &mut self,
+        editor: Entity<Editor>,
+        rows: Option<Range<u32>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        editor.update(cx, |editor, cx| {
+            editor.clear_row_highlights::<Self>();
+            match rows {
+                Some(rows) => {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    editor.highlight_rows::<Self>(
+                        snapshot.anchor_before(Point::new(rows.start, 0))
+                            ..snapshot.anchor_after(Point::new(rows.end, 0)),
+                        cx.theme().colors().editor_highlighted_line_background,
+                        RowHighlightOptions {
+                            autoscroll: true,
+                            ..Default::default()
+                        },
+                        cx,
+                    );
+                    editor.request_autoscroll(Autoscroll::center(), cx);
+                }
+                None => {
+                    editor.set_scroll_position(gpui::Point::default(), window, cx);
+                }
+            }
+        });
+        self.active_editor = Some(editor);
+        cx.notify();
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn active_editor(&self) -> Option<&Entity<Editor>> {
+        self.active_editor.as_ref()
+    }
+}
+
+impl Render for PickerPreview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("picker-preview")
+            .h(rems(16.))
+            .flex_none()
+            .overflow_hidden()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(self.active_editor.clone())
+    }
+}
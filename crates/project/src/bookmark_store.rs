@@ -0,0 +1,320 @@
+//! Module for managing bookmarks in a project.
+//!
+//! A bookmark marks a line in a file, optionally with a label. Bookmarks are anchored
+//! to their buffer so that they follow the line they were placed on as the file is edited.
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{App, Context, Entity, EventEmitter, SharedString, Subscription, Task};
+use language::{Buffer, BufferEvent, BufferSnapshot, DiskState};
+use std::{ops::Range, path::Path, sync::Arc};
+use text::{Point, ToPoint as _};
+
+use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position: text::Anchor,
+    pub label: Option<SharedString>,
+}
+
+/// A bookmark as it is persisted with the workspace.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SourceBookmark {
+    pub row: u32,
+    pub path: Arc<Path>,
+    pub label: Option<Arc<str>>,
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+impl BookmarksInFile {
+    fn new(buffer: Entity<Buffer>, cx: &mut Context<BookmarkStore>) -> Self {
+        let subscription = cx.subscribe(&buffer, |bookmark_store, buffer, event, cx| {
+            if let BufferEvent::FileHandleChanged = event {
+                bookmark_store.on_file_handle_changed(buffer, cx);
+            }
+        });
+        Self {
+            buffer,
+            bookmarks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+pub struct BookmarkStore {
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+    worktree_store: Entity<WorktreeStore>,
+    buffer_store: Entity<BufferStore>,
+}
+
+impl BookmarkStore {
+    pub fn new(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+        Self {
+            bookmarks: BTreeMap::new(),
+            worktree_store,
+            buffer_store,
+        }
+    }
+
+    fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .and_then(|file| file.worktree.read(cx).absolutize(&file.path).ok())
+            .map(Arc::<Path>::from)
+    }
+
+    /// Adds a bookmark on the line containing `position`, or removes the one
+    /// that is already there.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        let bookmarks_in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+
+        let len_before = bookmarks_in_file.bookmarks.len();
+        bookmarks_in_file
+            .bookmarks
+            .retain(|bookmark| bookmark.position.to_point(&snapshot).row != row);
+        if len_before == bookmarks_in_file.bookmarks.len() {
+            bookmarks_in_file.bookmarks.push(Bookmark {
+                position: snapshot.anchor_before(Point::new(row, 0)),
+                label: None,
+            });
+            bookmarks_in_file
+                .bookmarks
+                .sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+        }
+        if bookmarks_in_file.bookmarks.is_empty() {
+            self.bookmarks.remove(&abs_path);
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    /// Sets the label of the bookmark on the line containing `position`,
+    /// adding a bookmark there if there isn't one yet.
+    pub fn set_label(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        let bookmarks_in_file = self
+            .bookmarks
+            .entry(abs_path)
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+
+        let label = label.filter(|label| !label.trim().is_empty());
+        if let Some(bookmark) = bookmarks_in_file
+            .bookmarks
+            .iter_mut()
+            .find(|bookmark| bookmark.position.to_point(&snapshot).row == row)
+        {
+            bookmark.label = label;
+        } else {
+            bookmarks_in_file.bookmarks.push(Bookmark {
+                position: snapshot.anchor_before(Point::new(row, 0)),
+                label,
+            });
+            bookmarks_in_file
+                .bookmarks
+                .sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    pub fn remove_bookmark(&mut self, path: &Arc<Path>, row: u32, cx: &mut Context<Self>) {
+        let Some(bookmarks_in_file) = self.bookmarks.get_mut(path) else {
+            return;
+        };
+        let snapshot = bookmarks_in_file.buffer.read(cx).snapshot();
+        bookmarks_in_file
+            .bookmarks
+            .retain(|bookmark| bookmark.position.to_point(&snapshot).row != row);
+        if bookmarks_in_file.bookmarks.is_empty() {
+            self.bookmarks.remove(path);
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    /// Returns the bookmarks in `buffer`, in order, optionally limited to those within `range`.
+    pub fn bookmarks<'a>(
+        &'a self,
+        buffer: &'a Entity<Buffer>,
+        range: Option<Range<text::Anchor>>,
+        buffer_snapshot: &'a BufferSnapshot,
+        cx: &App,
+    ) -> impl Iterator<Item = &'a Bookmark> + 'a {
+        Self::abs_path_from_buffer(buffer, cx)
+            .and_then(|path| self.bookmarks.get(&path))
+            .into_iter()
+            .flat_map(move |bookmarks_in_file| {
+                bookmarks_in_file.bookmarks.iter().filter({
+                    let range = range.clone();
+                    move |bookmark| {
+                        range.as_ref().is_none_or(|range| {
+                            bookmark.position.cmp(&range.start, buffer_snapshot).is_ge()
+                                && bookmark.position.cmp(&range.end, buffer_snapshot).is_le()
+                        })
+                    }
+                })
+            })
+    }
+
+    /// Returns the buffers that have bookmarks, along with their bookmarks, ordered by path.
+    pub fn all_bookmarks(
+        &self,
+    ) -> impl Iterator<Item = (&Arc<Path>, &Entity<Buffer>, &[Bookmark])> {
+        self.bookmarks.iter().map(|(path, bookmarks_in_file)| {
+            (
+                path,
+                &bookmarks_in_file.buffer,
+                bookmarks_in_file.bookmarks.as_slice(),
+            )
+        })
+    }
+
+    pub fn all_source_bookmarks(&self, cx: &App) -> BTreeMap<Arc<Path>, Vec<SourceBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(path, bookmarks_in_file)| {
+                let snapshot = bookmarks_in_file.buffer.read(cx).snapshot();
+                (
+                    path.clone(),
+                    bookmarks_in_file
+                        .bookmarks
+                        .iter()
+                        .map(|bookmark| SourceBookmark {
+                            row: bookmark.position.to_point(&snapshot).row,
+                            path: path.clone(),
+                            label: bookmark.label.as_ref().map(|label| label.as_ref().into()),
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn with_serialized_bookmarks(
+        &self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SourceBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_store = self.worktree_store.clone();
+        let buffer_store = self.buffer_store.clone();
+        cx.spawn(async move |this, cx| {
+            let mut new_bookmarks = BTreeMap::default();
+            for (path, source_bookmarks) in bookmarks {
+                if source_bookmarks.is_empty() {
+                    continue;
+                }
+                let (worktree, relative_path) = worktree_store
+                    .update(cx, |this, cx| {
+                        this.find_or_create_worktree(&path, false, cx)
+                    })?
+                    .await?;
+                let buffer = buffer_store
+                    .update(cx, |this, cx| {
+                        let path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: relative_path.into(),
+                        };
+                        this.open_buffer(path, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::error!("failed to open {path:?} to restore its bookmarks");
+                    continue;
+                };
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+
+                let mut bookmarks_in_file =
+                    this.update(cx, |_, cx| BookmarksInFile::new(buffer, cx))?;
+                for source_bookmark in source_bookmarks {
+                    if source_bookmark.row > snapshot.max_point().row {
+                        log::error!("skipping a deserialized bookmark that's out of range");
+                        continue;
+                    }
+                    bookmarks_in_file.bookmarks.push(Bookmark {
+                        position: snapshot.anchor_before(Point::new(source_bookmark.row, 0)),
+                        label: source_bookmark.label.map(SharedString::from),
+                    });
+                }
+                new_bookmarks.insert(path, bookmarks_in_file);
+            }
+
+            this.update(cx, |this, cx| {
+                this.bookmarks = new_bookmarks;
+                cx.notify();
+            })
+        })
+    }
+
+    fn on_file_handle_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let entity_id = buffer.entity_id();
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, bookmarks_in_file)| bookmarks_in_file.buffer.entity_id() == entity_id)
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+
+        if buffer
+            .read(cx)
+            .file()
+            .is_none_or(|file| file.disk_state() == DiskState::Deleted)
+        {
+            self.bookmarks.remove(&old_path);
+        } else if let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx)
+            && new_path != old_path
+            && !self.bookmarks.contains_key(&new_path)
+            && let Some(bookmarks_in_file) = self.bookmarks.remove(&old_path)
+        {
+            self.bookmarks.insert(new_path, bookmarks_in_file);
+        } else {
+            return;
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+}
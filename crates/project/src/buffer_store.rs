@@ -1,6 +1,7 @@
 use crate::{
     ProjectItem as _, ProjectPath,
     lsp_store::OpenLspBufferHandle,
+    project_settings::ProjectSettings,
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
@@ -23,6 +24,7 @@ use rpc::{
     AnyProtoClient, ErrorCode, ErrorExt as _, TypedEnvelope,
     proto::{self, ToProto},
 };
+use settings::Settings as _;
 use smol::channel::Receiver;
 use std::{io, path::Path, pin::pin, sync::Arc, time::Instant};
 use text::BufferId;
@@ -65,6 +67,9 @@ struct RemoteBufferStore {
 
 struct LocalBufferStore {
     local_buffer_ids_by_entry_id: HashMap<ProjectEntryId, BufferId>,
+    /// Paths that were opened as other names for the file of an already open buffer, such
+    /// as symlinks or hard links to it.
+    path_aliases: HashMap<ProjectPath, BufferId>,
     worktree_store: Entity<WorktreeStore>,
     _subscription: Subscription,
 }
@@ -86,6 +91,12 @@ pub enum BufferStoreEvent {
         buffer: Entity<Buffer>,
         old_file: Option<Arc<dyn language::File>>,
     },
+    /// The file of an open buffer turned out to be the same file on disk as that of another
+    /// open buffer, e.g. after it was replaced by a link to it.
+    DuplicateBuffer {
+        duplicate: Entity<Buffer>,
+        buffer: Entity<Buffer>,
+    },
 }
 
 #[derive(Default, Debug, Clone)]
@@ -472,6 +483,17 @@ impl LocalBufferStore {
             path: path.clone(),
         };
 
+        // A change to another name of an open buffer's file may mean that it's no longer the
+        // same file, so the alias is dropped and checked again when the path is next opened.
+        if let Some(local) = this.as_local_mut()
+            && let Some(buffer_id) = local.path_aliases.remove(&project_path)
+        {
+            if this.path_to_buffer_id.get(&project_path) == Some(&buffer_id) {
+                this.path_to_buffer_id.remove(&project_path);
+            }
+            return None;
+        }
+
         let buffer_id = this
             .as_local_mut()
             .and_then(|local| local.local_buffer_ids_by_entry_id.get(&entry_id))
@@ -495,7 +517,7 @@ impl LocalBufferStore {
             return None;
         };
 
-        let events = buffer.update(cx, |buffer, cx| {
+        let (events, entry_changed) = buffer.update(cx, |buffer, cx| {
             let file = buffer.file()?;
             let old_file = File::from_dyn(Some(file))?;
             if old_file.worktree != *worktree {
@@ -535,6 +557,9 @@ impl LocalBufferStore {
             }
 
             let mut events = Vec::new();
+            if new_file.path != old_file.path || new_file.disk_state == DiskState::Deleted {
+                this.remove_path_aliases(buffer_id);
+            }
             if new_file.path != old_file.path {
                 this.path_to_buffer_id.remove(&ProjectPath {
                     path: old_file.path.clone(),
@@ -553,7 +578,8 @@ impl LocalBufferStore {
                 });
             }
             let local = this.as_local_mut()?;
-            if new_file.entry_id != old_file.entry_id {
+            let entry_changed = new_file.entry_id != old_file.entry_id;
+            if entry_changed {
                 if let Some(entry_id) = old_file.entry_id {
                     local.local_buffer_ids_by_entry_id.remove(&entry_id);
                 }
@@ -575,12 +601,21 @@ impl LocalBufferStore {
             }
 
             buffer.file_updated(Arc::new(new_file), cx);
-            Some(events)
+            Some((events, entry_changed))
         })?;
 
         for event in events {
             cx.emit(event);
         }
+        if entry_changed
+            && let Some(file) = File::from_dyn(buffer.read(cx).file())
+            && let Some(existing_buffer) = this.open_buffer_for_same_file(file, cx)
+        {
+            cx.emit(BufferStoreEvent::DuplicateBuffer {
+                duplicate: buffer,
+                buffer: existing_buffer,
+            });
+        }
 
         None
     }
@@ -619,17 +654,45 @@ impl LocalBufferStore {
         worktree: Entity<Worktree>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
+        let large_file_mode = ProjectSettings::get_global(cx).large_file_mode;
         let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
             let reservation = cx.reserve_entity();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
+
+            // Files that are already known to be large are streamed straight into
+            // a rope, instead of being read into a string and then copied.
+            let size = worktree.entry_for_path(&path).map(|entry| entry.size);
+            if size.is_some_and(|size| large_file_mode.is_large_file_size(size)) {
+                let load_file = worktree.load_large_file(path.as_ref(), cx);
+                return cx.spawn(async move |_, cx| {
+                    let loaded = load_file.await?;
+                    let text_buffer =
+                        text::Buffer::new_normalized(0, buffer_id, loaded.line_ending, loaded.text);
+                    cx.insert_entity(reservation, |cx| {
+                        let mut buffer =
+                            Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite);
+                        buffer.set_large_file(true, cx);
+                        buffer
+                    })
+                });
+            }
+
+            let load_file = worktree.load_file(path.as_ref(), cx);
             cx.spawn(async move |_, cx| {
                 let loaded = load_file.await?;
-                let text_buffer = cx
-                    .background_spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                let (text_buffer, is_large_file) = cx
+                    .background_spawn(async move {
+                        let text_buffer = text::Buffer::new(0, buffer_id, loaded.text);
+                        let is_large_file = large_file_mode
+                            .is_large_file(text_buffer.len(), text_buffer.max_point().row + 1);
+                        (text_buffer, is_large_file)
+                    })
                     .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                cx.insert_entity(reservation, |cx| {
+                    let mut buffer =
+                        Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite);
+                    buffer.set_large_file(is_large_file, cx);
+                    buffer
                 })
             })
         });
@@ -656,6 +719,25 @@ impl LocalBufferStore {
                 Err(e) => Err(e),
             }?;
             this.update(cx, |this, cx| {
+                if let Some(file) = File::from_dyn(buffer.read(cx).file())
+                    && let Some(existing_buffer) = this.open_buffer_for_same_file(file, cx)
+                {
+                    // The path is another name for a file that is already open, such as a
+                    // hard link, a symlink or a differently-cased path on a case-insensitive
+                    // file system. Reuse that buffer, so the file can't diverge between two.
+                    let project_path = ProjectPath {
+                        worktree_id: file.worktree_id(cx),
+                        path: file.path.clone(),
+                    };
+                    let existing_buffer_id = existing_buffer.read(cx).remote_id();
+                    this.path_to_buffer_id
+                        .insert(project_path.clone(), existing_buffer_id);
+                    if let Some(local) = this.as_local_mut() {
+                        local.path_aliases.insert(project_path, existing_buffer_id);
+                    }
+                    return anyhow::Ok(existing_buffer);
+                }
+
                 this.add_buffer(buffer.clone(), cx)?;
                 let buffer_id = buffer.read(cx).remote_id();
                 if let Some(file) = File::from_dyn(buffer.read(cx).file()) {
@@ -673,10 +755,8 @@ impl LocalBufferStore {
                     }
                 }
 
-                anyhow::Ok(())
-            })??;
-
-            Ok(buffer)
+                anyhow::Ok(buffer)
+            })?
         })
     }
 
@@ -739,6 +819,7 @@ impl BufferStore {
         Self {
             state: BufferStoreState::Local(LocalBufferStore {
                 local_buffer_ids_by_entry_id: Default::default(),
+                path_aliases: Default::default(),
                 worktree_store: worktree_store.clone(),
                 _subscription: cx.subscribe(&worktree_store, |this, _, event, cx| {
                     if let WorktreeStoreEvent::WorktreeAdded(worktree) = event {
@@ -997,6 +1078,41 @@ impl BufferStore {
             .and_then(|buffer_id| self.get(*buffer_id))
     }
 
+    /// Returns an open buffer whose file is the same file on disk as `file`, but under
+    /// a different path.
+    fn open_buffer_for_same_file(&self, file: &File, cx: &App) -> Option<Entity<Buffer>> {
+        let entry = local_file_entry(file, cx)?;
+        self.buffers().find(|buffer| {
+            let Some(other_file) = File::from_dyn(buffer.read(cx).file()) else {
+                return false;
+            };
+            if other_file.worktree == file.worktree && other_file.path == file.path {
+                return false;
+            }
+            // Inodes are only unique per device.
+            local_file_entry(other_file, cx).is_some_and(|other_entry| {
+                (other_entry.device, other_entry.inode) == (entry.device, entry.inode)
+            })
+        })
+    }
+
+    /// Forgets the other names that a buffer's file was opened through.
+    fn remove_path_aliases(&mut self, buffer_id: BufferId) {
+        let BufferStoreState::Local(local) = &mut self.state else {
+            return;
+        };
+        let path_to_buffer_id = &mut self.path_to_buffer_id;
+        local.path_aliases.retain(|path, alias_buffer_id| {
+            if *alias_buffer_id != buffer_id {
+                return true;
+            }
+            if path_to_buffer_id.get(path) == Some(&buffer_id) {
+                path_to_buffer_id.remove(path);
+            }
+            false
+        });
+    }
+
     pub fn get(&self, buffer_id: BufferId) -> Option<Entity<Buffer>> {
         self.opened_buffers.get(&buffer_id)?.upgrade()
     }
@@ -1726,6 +1842,14 @@ impl OpenBuffer {
     }
 }
 
+fn local_file_entry<'a>(file: &File, cx: &'a App) -> Option<&'a worktree::Entry> {
+    if !file.is_local {
+        return None;
+    }
+    let entry = file.worktree.read(cx).entry_for_id(file.entry_id?)?;
+    entry.is_file().then_some(entry)
+}
+
 fn is_not_found_error(error: &anyhow::Error) -> bool {
     error
         .root_cause()
@@ -1,11 +1,11 @@
 mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
-    ProjectTransaction, PulledDiagnostics, ResolveState,
+    CallHierarchyCall, CallHierarchyItem, CodeAction, CompletionSource, CoreCompletion,
+    CoreCompletionResponse, DocumentColor, DocumentHighlight, DocumentSymbol, Hover, HoverBlock,
+    HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip,
+    InlayHintTooltip, LinkedEditingRanges, Location, LocationLink, LspAction, LspPullDiagnostics,
+    MarkupContent, PrepareRenameResponse, ProjectTransaction, PulledDiagnostics, ResolveState,
     lsp_store::{LocalLspStore, LspStore},
 };
 use anyhow::{Context as _, Result};
@@ -204,6 +204,21 @@ pub(crate) struct GetDocumentHighlights {
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentSymbols;
 
+#[derive(Debug)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub item: CallHierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: CallHierarchyItem,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetSignatureHelp {
     pub position: PointUtf16,
@@ -1798,6 +1813,391 @@ impl LspCommand for GetDocumentSymbols {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::CallHierarchyItem>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        Ok(lsp_items
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_item| CallHierarchyItem {
+                server_id,
+                lsp_item,
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyItem>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response.iter().map(serialize_call_hierarchy_item).collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        message
+            .items
+            .into_iter()
+            .map(deserialize_call_hierarchy_item)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        Ok(lsp_calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_call| CallHierarchyCall {
+                item: CallHierarchyItem {
+                    server_id,
+                    lsp_item: lsp_call.from,
+                },
+                ranges: lsp_call
+                    .from_ranges
+                    .into_iter()
+                    .map(range_from_lsp)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(serialize_call_hierarchy_item(&self.item)),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing call hierarchy item")?;
+        Ok(Self {
+            item: deserialize_call_hierarchy_item(item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: response
+                .into_iter()
+                .map(serialize_call_hierarchy_call)
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        message
+            .calls
+            .into_iter()
+            .map(deserialize_call_hierarchy_call)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        Ok(lsp_calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_call| CallHierarchyCall {
+                item: CallHierarchyItem {
+                    server_id,
+                    lsp_item: lsp_call.to,
+                },
+                ranges: lsp_call
+                    .from_ranges
+                    .into_iter()
+                    .map(range_from_lsp)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(serialize_call_hierarchy_item(&self.item)),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing call hierarchy item")?;
+        Ok(Self {
+            item: deserialize_call_hierarchy_item(item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: response
+                .into_iter()
+                .map(serialize_call_hierarchy_call)
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        message
+            .calls
+            .into_iter()
+            .map(deserialize_call_hierarchy_call)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+fn supports_call_hierarchy(capabilities: AdapterServerCapabilities) -> bool {
+    capabilities
+        .server_capabilities
+        .call_hierarchy_provider
+        .is_some_and(|capability| match capability {
+            lsp::CallHierarchyServerCapability::Simple(supported) => supported,
+            lsp::CallHierarchyServerCapability::Options(_) => true,
+        })
+}
+
+fn serialize_call_hierarchy_item(item: &CallHierarchyItem) -> proto::CallHierarchyItem {
+    proto::CallHierarchyItem {
+        server_id: item.server_id.to_proto(),
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap(),
+    }
+}
+
+fn deserialize_call_hierarchy_item(item: proto::CallHierarchyItem) -> Result<CallHierarchyItem> {
+    Ok(CallHierarchyItem {
+        server_id: LanguageServerId::from_proto(item.server_id),
+        lsp_item: serde_json::from_slice(&item.lsp_item)?,
+    })
+}
+
+fn serialize_call_hierarchy_call(call: CallHierarchyCall) -> proto::CallHierarchyCall {
+    let (range_starts, range_ends) = call
+        .ranges
+        .into_iter()
+        .map(|range| {
+            (
+                proto::PointUtf16 {
+                    row: range.start.0.row,
+                    column: range.start.0.column,
+                },
+                proto::PointUtf16 {
+                    row: range.end.0.row,
+                    column: range.end.0.column,
+                },
+            )
+        })
+        .unzip();
+    proto::CallHierarchyCall {
+        item: Some(serialize_call_hierarchy_item(&call.item)),
+        range_starts,
+        range_ends,
+    }
+}
+
+fn deserialize_call_hierarchy_call(call: proto::CallHierarchyCall) -> Result<CallHierarchyCall> {
+    let item = call.item.context("missing call hierarchy item")?;
+    Ok(CallHierarchyCall {
+        item: deserialize_call_hierarchy_item(item)?,
+        ranges: call
+            .range_starts
+            .into_iter()
+            .zip(call.range_ends)
+            .map(|(start, end)| {
+                Unclipped(PointUtf16::new(start.row, start.column))
+                    ..Unclipped(PointUtf16::new(end.row, end.column))
+            })
+            .collect(),
+    })
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetSignatureHelp {
     type Response = Option<SignatureHelp>;
@@ -3587,7 +3987,7 @@ impl LinkedEditingRange {
 
 #[async_trait(?Send)]
 impl LspCommand for LinkedEditingRange {
-    type Response = Vec<Range<Anchor>>;
+    type Response = LinkedEditingRanges;
     type LspRequest = lsp::request::LinkedEditingRange;
     type ProtoRequest = proto::LinkedEditingRange;
 
@@ -3620,11 +4020,15 @@ impl LspCommand for LinkedEditingRange {
         buffer: Entity<Buffer>,
         _server_id: LanguageServerId,
         cx: AsyncApp,
-    ) -> Result<Vec<Range<Anchor>>> {
-        if let Some(lsp::LinkedEditingRanges { mut ranges, .. }) = message {
+    ) -> Result<LinkedEditingRanges> {
+        if let Some(lsp::LinkedEditingRanges {
+            mut ranges,
+            word_pattern,
+        }) = message
+        {
             ranges.sort_by_key(|range| range.start);
 
-            buffer.read_with(&cx, |buffer, _| {
+            let ranges = buffer.read_with(&cx, |buffer, _| {
                 ranges
                     .into_iter()
                     .map(|range| {
@@ -3634,9 +4038,13 @@ impl LspCommand for LinkedEditingRange {
                         buffer.anchor_before(start)..buffer.anchor_after(end)
                     })
                     .collect()
+            })?;
+            Ok(LinkedEditingRanges {
+                ranges,
+                word_pattern,
             })
         } else {
-            Ok(vec![])
+            Ok(LinkedEditingRanges::default())
         }
     }
 
@@ -3669,7 +4077,7 @@ impl LspCommand for LinkedEditingRange {
     }
 
     fn response_to_proto(
-        response: Vec<Range<Anchor>>,
+        response: LinkedEditingRanges,
         _: &mut LspStore,
         _: PeerId,
         buffer_version: &clock::Global,
@@ -3677,12 +4085,14 @@ impl LspCommand for LinkedEditingRange {
     ) -> proto::LinkedEditingRangeResponse {
         proto::LinkedEditingRangeResponse {
             items: response
+                .ranges
                 .into_iter()
                 .map(|range| proto::AnchorRange {
                     start: Some(serialize_anchor(&range.start)),
                     end: Some(serialize_anchor(&range.end)),
                 })
                 .collect(),
+            word_pattern: response.word_pattern,
             version: serialize_version(buffer_version),
         }
     }
@@ -3693,7 +4103,7 @@ impl LspCommand for LinkedEditingRange {
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
-    ) -> Result<Vec<Range<Anchor>>> {
+    ) -> Result<LinkedEditingRanges> {
         buffer
             .update(&mut cx, |buffer, _| {
                 buffer.wait_for_version(deserialize_version(&message.version))
@@ -3715,7 +4125,10 @@ impl LspCommand for LinkedEditingRange {
                 })?
                 .await?;
         }
-        Ok(items)
+        Ok(LinkedEditingRanges {
+            ranges: items,
+            word_pattern: message.word_pattern,
+        })
     }
 
     fn buffer_id_from_proto(message: &proto::LinkedEditingRange) -> Result<BufferId> {
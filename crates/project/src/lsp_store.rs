@@ -17,9 +17,9 @@ pub mod rust_analyzer_ext;
 
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, LocationLink, LspAction,
-    LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
+    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, LinkedEditingRanges,
+    LocationLink, LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem,
+    ProjectPath, ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -51,8 +51,8 @@ use futures::{
 };
 use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use gpui::{
-    App, AppContext, AsyncApp, Context, Entity, EventEmitter, PromptLevel, SharedString, Task,
-    WeakEntity,
+    App, AppContext, AsyncApp, BackgroundExecutor, Context, Entity, EventEmitter, FutureExt as _,
+    PromptLevel, SharedString, Task, WeakEntity,
 };
 use http_client::HttpClient;
 use itertools::Itertools as _;
@@ -63,7 +63,8 @@ use language::{
     ManifestName, Patch, PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Toolchain,
     Transaction, Unclipped,
     language_settings::{
-        FormatOnSave, Formatter, LanguageSettings, SelectedFormatter, language_settings,
+        CodeActionsOnFormatOrder, FormatOnSave, Formatter, LanguageSettings, SelectedFormatter,
+        all_language_settings, language_settings, language_settings_with_detected_indent,
     },
     point_to_lsp,
     proto::{
@@ -82,6 +83,7 @@ use lsp::{
     TextDocumentSyncSaveOptions, TextEdit, Uri, WillRenameFiles, WorkDoneProgressCancelParams,
     WorkspaceFolder, notification::DidRenameFiles,
 };
+use markdown::Markdown;
 use node_runtime::read_package_installed_version;
 use parking_lot::Mutex;
 use postage::{mpsc, sink::Sink, stream::Stream, watch};
@@ -131,6 +133,7 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const BYTE_ORDER_MARK: char = '\u{feff}';
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -539,6 +542,7 @@ impl LocalLspStore {
                 .as_ref()
                 .and_then(|f| f.pre_release)
                 .unwrap_or(false),
+            version: settings.fetch.as_ref().and_then(|f| f.version.clone()),
         };
 
         cx.spawn(async move |cx| {
@@ -824,6 +828,8 @@ impl LocalLspStore {
                     let mut cx = cx.clone();
                     async move {
                         this.update(&mut cx, |this, cx| {
+                            // The cached lenses are outdated, regardless of the buffer versions.
+                            this.lsp_code_lens.clear();
                             cx.emit(LspStoreEvent::RefreshCodeLens);
                             this.downstream_client.as_ref().map(|(client, project_id)| {
                                 client.send(proto::RefreshCodeLens {
@@ -1290,9 +1296,13 @@ impl LocalLspStore {
                     .language_servers_for_buffer(buffer, cx)
                     .map(|(adapter, lsp)| (adapter.clone(), lsp.clone()))
                     .collect::<Vec<_>>();
-                let settings =
-                    language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
-                        .into_owned();
+                let settings = language_settings_with_detected_indent(
+                    buffer.language().map(|l| l.name()),
+                    buffer.file(),
+                    buffer.detected_indent(),
+                    cx,
+                )
+                .into_owned();
                 (adapters_and_servers, settings)
             })
         })?;
@@ -1319,12 +1329,35 @@ impl LocalLspStore {
             })?
         }
 
+        /// Waits for a language server to respond while running code actions, failing once
+        /// `deadline` has passed so that a slow server can't hang saving.
+        async fn before_deadline<T>(
+            request: impl Future<Output = anyhow::Result<T>>,
+            deadline: Option<Instant>,
+            executor: &BackgroundExecutor,
+        ) -> anyhow::Result<T> {
+            let Some(deadline) = deadline else {
+                return request.await;
+            };
+            let timeout = deadline.saturating_duration_since(executor.now());
+            request
+                .with_timeout(timeout, executor)
+                .await
+                .map_err(|_| anyhow!("timed out running code actions on format"))?
+        }
+
         // handle whitespace formatting
         if settings.remove_trailing_whitespace_on_save {
             zlog::trace!(logger => "removing trailing whitespace");
             let diff = buffer
                 .handle
-                .read_with(cx, |buffer, cx| buffer.remove_trailing_whitespace(cx))?
+                .read_with(cx, |buffer, cx| {
+                    if settings.keep_trailing_whitespace_on_cursor_line {
+                        buffer.remove_trailing_whitespace_except_on_cursor_lines(cx)
+                    } else {
+                        buffer.remove_trailing_whitespace(cx)
+                    }
+                })?
                 .await;
             extend_formatting_transaction(buffer, formatting_transaction_id, cx, |buffer, cx| {
                 buffer.apply_diff(diff, cx);
@@ -1338,6 +1371,26 @@ impl LocalLspStore {
             })?;
         }
 
+        if let Some(line_ending) = settings.line_ending_on_save {
+            buffer.handle.update(cx, |buffer, cx| {
+                if buffer.line_ending() != line_ending {
+                    zlog::trace!(logger => "converting line endings");
+                    buffer.set_line_ending(line_ending, cx);
+                }
+            })?;
+        }
+
+        if let Some(byte_order_mark) = settings.byte_order_mark_on_save {
+            extend_formatting_transaction(buffer, formatting_transaction_id, cx, |buffer, cx| {
+                let has_byte_order_mark = buffer.chars_at(0).next() == Some(BYTE_ORDER_MARK);
+                if byte_order_mark && !has_byte_order_mark {
+                    buffer.edit([(0..0, BYTE_ORDER_MARK.to_string())], None, cx);
+                } else if !byte_order_mark && has_byte_order_mark {
+                    buffer.edit([(0..BYTE_ORDER_MARK.len_utf8(), "")], None, cx);
+                }
+            })?;
+        }
+
         // Formatter for `code_actions_on_format` that runs before
         // the rest of the formatters
         let mut code_actions_on_format_formatter = None;
@@ -1377,7 +1430,15 @@ impl LocalLspStore {
             }
         };
 
-        let formatters = code_actions_on_format_formatter.iter().chain(formatters);
+        let mut formatters = formatters.iter().collect::<Vec<_>>();
+        if let Some(formatter) = code_actions_on_format_formatter.as_ref() {
+            match settings.code_actions_on_format_order {
+                CodeActionsOnFormatOrder::Before => formatters.insert(0, formatter),
+                CodeActionsOnFormatOrder::After => formatters.push(formatter),
+            }
+        }
+        let code_actions_timeout = (settings.code_actions_on_format_timeout_ms > 0)
+            .then(|| Duration::from_millis(settings.code_actions_on_format_timeout_ms));
 
         for formatter in formatters {
             match formatter {
@@ -1440,11 +1501,6 @@ impl LocalLspStore {
                     zlog::trace!(logger => "formatting");
                     let _timer = zlog::time!(logger => "Formatting buffer using language server");
 
-                    let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
-                        zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using language servers. Skipping");
-                        continue;
-                    };
-
                     let language_server = if let Some(name) = name.as_deref() {
                         adapters_and_servers.iter().find_map(|(adapter, server)| {
                             if adapter.name.0.as_ref() == name {
@@ -1454,7 +1510,44 @@ impl LocalLspStore {
                             }
                         })
                     } else {
-                        adapters_and_servers.first().map(|e| e.1.clone())
+                        // Format with the most preferred server that can, as the first server of
+                        // a language, like Tailwind's, doesn't always format its files.
+                        adapters_and_servers
+                            .iter()
+                            .find(|(_, server)| {
+                                if buffer.ranges.is_some() {
+                                    Self::supports_range_formatting(server)
+                                } else {
+                                    Self::supports_formatting(server)
+                                }
+                            })
+                            .map(|(_, server)| server.clone())
+                    };
+
+                    // Reindent the ranges using the language's indentation rules when
+                    // there is no language server that can format them.
+                    if let Some(ranges) = buffer.ranges.as_ref()
+                        && language_server.as_ref().is_none_or(|language_server| {
+                            !Self::supports_range_formatting(language_server)
+                        })
+                    {
+                        zlog::trace!(logger => "No language server can format ranges, reindenting them instead");
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
+                            cx,
+                            |buffer, cx| {
+                                let edits =
+                                    buffer.snapshot().reindent_edits(ranges.iter().cloned(), cx);
+                                buffer.edit(edits, None, cx);
+                            },
+                        )?;
+                        continue;
+                    }
+
+                    let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
+                        zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using language servers. Skipping");
+                        continue;
                     };
 
                     let Some(language_server) = language_server else {
@@ -1533,15 +1626,21 @@ impl LocalLspStore {
                     }
                     zlog::trace!(logger => "Attempting to resolve code actions {:?}", &code_action_kinds);
 
+                    let executor = cx.background_executor().clone();
+                    let deadline = code_actions_timeout.map(|timeout| executor.now() + timeout);
                     let mut actions_and_servers = Vec::new();
 
                     for (index, (_, language_server)) in adapters_and_servers.iter().enumerate() {
-                        let actions_result = Self::get_server_code_actions_from_action_kinds(
-                            &lsp_store,
-                            language_server.server_id(),
-                            code_action_kinds.clone(),
-                            &buffer.handle,
-                            cx,
+                        let actions_result = before_deadline(
+                            Self::get_server_code_actions_from_action_kinds(
+                                &lsp_store,
+                                language_server.server_id(),
+                                code_action_kinds.clone(),
+                                &buffer.handle,
+                                cx,
+                            ),
+                            deadline,
+                            &executor,
                         )
                         .await
                         .with_context(
@@ -1588,7 +1687,13 @@ impl LocalLspStore {
 
                         zlog::trace!(logger => "Executing {}", describe_code_action(&action));
 
-                        if let Err(err) = Self::try_resolve_code_action(server, &mut action).await {
+                        let resolve_result = before_deadline(
+                            Self::try_resolve_code_action(server, &mut action),
+                            deadline,
+                            &executor,
+                        )
+                        .await;
+                        if let Err(err) = resolve_result {
                             zlog::error!(
                                 logger =>
                                 "Failed to resolve {}. Error: {}",
@@ -1785,16 +1890,23 @@ impl LocalLspStore {
                                     .remove(&server.server_id());
                             })?;
 
-                            let execute_command_result = server
-                                .request::<lsp::request::ExecuteCommand>(
-                                    lsp::ExecuteCommandParams {
-                                        command: command.command.clone(),
-                                        arguments: command.arguments.clone().unwrap_or_default(),
-                                        ..Default::default()
-                                    },
-                                )
-                                .await
-                                .into_response();
+                            let execute_command_result = before_deadline(
+                                server
+                                    .request::<lsp::request::ExecuteCommand>(
+                                        lsp::ExecuteCommandParams {
+                                            command: command.command.clone(),
+                                            arguments: command
+                                                .arguments
+                                                .clone()
+                                                .unwrap_or_default(),
+                                            ..Default::default()
+                                        },
+                                    )
+                                    .map(|response| response.into_response()),
+                                deadline,
+                                &executor,
+                            )
+                            .await;
 
                             if execute_command_result.is_err() {
                                 zlog::error!(
@@ -1872,6 +1984,21 @@ impl LocalLspStore {
         Ok(())
     }
 
+    fn supports_formatting(language_server: &LanguageServer) -> bool {
+        language_server
+            .capabilities()
+            .document_formatting_provider
+            .is_some_and(|provider| provider != OneOf::Left(false))
+            || Self::supports_range_formatting(language_server)
+    }
+
+    fn supports_range_formatting(language_server: &LanguageServer) -> bool {
+        language_server
+            .capabilities()
+            .document_range_formatting_provider
+            .is_some_and(|provider| provider != OneOf::Left(false))
+    }
+
     pub async fn format_ranges_via_lsp(
         this: &WeakEntity<LspStore>,
         buffer_handle: &Entity<Buffer>,
@@ -2329,6 +2456,9 @@ impl LocalLspStore {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_large_file() {
+            return;
+        }
 
         let Some(file) = File::from_dyn(buffer.file()) else {
             return;
@@ -3488,6 +3618,10 @@ pub struct LspStore {
     lsp_document_colors: HashMap<BufferId, DocumentColorData>,
     lsp_code_lens: HashMap<BufferId, CodeLensData>,
     running_lsp_requests: HashMap<TypeId, (Global, HashMap<LspRequestId, Task<()>>)>,
+    /// Buffers whose language was chosen explicitly, rather than detected.
+    pub(crate) buffers_with_explicit_language: HashSet<BufferId>,
+    /// The `file_types` patterns each buffer's language was last detected with.
+    pub(crate) buffer_file_type_patterns: HashMap<BufferId, Arc<BTreeMap<Arc<str>, Vec<String>>>>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -3594,6 +3728,7 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_inlay_hints);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_code_lens);
         client.add_entity_request_handler(Self::handle_get_color_presentation);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
@@ -3606,6 +3741,9 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -3753,6 +3891,8 @@ impl LspStore {
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
             running_lsp_requests: HashMap::default(),
+            buffers_with_explicit_language: HashSet::default(),
+            buffer_file_type_patterns: HashMap::default(),
             active_entry: None,
             _maintain_workspace_config,
             _maintain_buffer_languages: Self::maintain_buffer_languages(languages, cx),
@@ -3814,6 +3954,8 @@ impl LspStore {
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
             running_lsp_requests: HashMap::default(),
+            buffers_with_explicit_language: HashSet::default(),
+            buffer_file_type_patterns: HashMap::default(),
             active_entry: None,
 
             _maintain_workspace_config,
@@ -3946,6 +4088,18 @@ impl LspStore {
                 self.on_buffer_saved(buffer, cx);
             }
 
+            language::BufferEvent::LargeFileModeChanged => {
+                // Buffers opened in large file mode were skipped when registered
+                // with language servers, so catch up once the mode is turned off.
+                let buffer_id = buffer.read(cx).remote_id();
+                if !buffer.read(cx).is_large_file()
+                    && let Some(local) = self.as_local_mut()
+                    && local.registered_buffers.contains_key(&buffer_id)
+                {
+                    local.register_buffer_with_language_servers(&buffer, HashSet::default(), cx);
+                }
+            }
+
             _ => {}
         }
     }
@@ -3959,6 +4113,12 @@ impl LspStore {
             this.on_buffer_event(buffer, event, cx);
         })
         .detach();
+        let buffer_id = buffer.read(cx).remote_id();
+        cx.observe_release(buffer, move |this, _, _| {
+            this.buffers_with_explicit_language.remove(&buffer_id);
+            this.buffer_file_type_patterns.remove(&buffer_id);
+        })
+        .detach();
 
         self.detect_language_for_buffer(buffer, cx);
         if let Some(local) = self.as_local_mut() {
@@ -4159,9 +4319,13 @@ impl LspStore {
     ) -> Task<()> {
         let mut subscription = languages.subscribe();
         let mut prev_reload_count = languages.reload_count();
+        let mut prev_version = languages.version();
         cx.spawn(async move |this, cx| {
             while let Some(()) = subscription.next().await {
                 if let Some(this) = this.upgrade() {
+                    let reloaded_languages = languages.languages_reloaded_since(prev_version);
+                    prev_version = languages.version();
+
                     // If the language registry has been reloaded, then remove and
                     // re-assign the languages on all open buffers.
                     let reload_count = languages.reload_count();
@@ -4193,6 +4357,36 @@ impl LspStore {
                             });
                         })
                         .ok();
+                    } else if !reloaded_languages.is_empty() {
+                        // Languages that were reloaded on their own, such as for a new
+                        // injection, only have to be replaced in the buffers using them.
+                        let buffers = this
+                            .read_with(cx, |this, cx| {
+                                this.buffer_store
+                                    .read(cx)
+                                    .buffers()
+                                    .filter_map(|buffer| {
+                                        let language_name = buffer.read(cx).language()?.name();
+                                        reloaded_languages
+                                            .contains(&language_name)
+                                            .then_some((buffer, language_name))
+                                    })
+                                    .collect::<Vec<_>>()
+                            })
+                            .unwrap_or_default();
+                        for (buffer, language_name) in buffers {
+                            if let Some(language) = languages
+                                .language_for_name(language_name.as_ref())
+                                .await
+                                .log_err()
+                            {
+                                buffer
+                                    .update(cx, |buffer, cx| {
+                                        buffer.set_language(Some(language), cx)
+                                    })
+                                    .ok();
+                            }
+                        }
                     }
 
                     this.update(cx, |this, cx| {
@@ -4253,6 +4447,12 @@ impl LspStore {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
         let file = buffer.file()?;
+        self.buffer_file_type_patterns.insert(
+            buffer.remote_id(),
+            all_language_settings(Some(file), cx)
+                .file_type_patterns()
+                .clone(),
+        );
 
         let content = buffer.as_rope();
         let available_language = self.languages.language_for_file(file, Some(content), cx);
@@ -4393,7 +4593,7 @@ impl LspStore {
         &self,
         buffer: &Entity<Buffer>,
         check: F,
-        cx: &Context<Self>,
+        cx: &App,
     ) -> bool
     where
         F: Fn(&lsp::ServerCapabilities) -> bool,
@@ -4568,6 +4768,9 @@ impl LspStore {
         }
 
         self.request_workspace_config_refresh();
+        if self.as_local().is_some() {
+            self.redetect_buffer_languages(cx);
+        }
 
         if let Some(prettier_store) = self.as_local().map(|s| s.prettier_store.clone()) {
             prettier_store.update(cx, |prettier_store, cx| {
@@ -4578,6 +4781,49 @@ impl LspStore {
         cx.notify();
     }
 
+    /// Detects the languages of open buffers again if the `file_types` setting changed for them.
+    /// Languages that were chosen explicitly are kept.
+    fn redetect_buffer_languages(&mut self, cx: &mut Context<Self>) {
+        let redetected = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter_map(|buffer_handle| {
+                let buffer = buffer_handle.read(cx);
+                let buffer_id = buffer.remote_id();
+                if self.buffers_with_explicit_language.contains(&buffer_id) {
+                    return None;
+                }
+                let file_type_patterns = all_language_settings(buffer.file(), cx)
+                    .file_type_patterns()
+                    .clone();
+                if self.buffer_file_type_patterns.get(&buffer_id) == Some(&file_type_patterns) {
+                    return None;
+                }
+                self.buffer_file_type_patterns
+                    .insert(buffer_id, file_type_patterns);
+                let available_language =
+                    self.languages
+                        .language_for_file(buffer.file()?, Some(buffer.as_rope()), cx)?;
+                let changed = buffer
+                    .language()
+                    .is_none_or(|language| language.name() != available_language.name());
+                changed.then(|| (buffer_handle, available_language))
+            })
+            .collect::<Vec<_>>();
+
+        for (buffer, available_language) in redetected {
+            let language = self.languages.load_language(&available_language);
+            cx.spawn(async move |this, cx| {
+                let language = language.await??;
+                this.update(cx, |this, cx| {
+                    this.set_language_for_buffer(&buffer, language, cx)
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
     fn refresh_server_tree(&mut self, cx: &mut Context<Self>) {
         let buffer_store = self.buffer_store.clone();
         let Some(local) = self.as_local_mut() else {
@@ -4725,6 +4971,45 @@ impl LspStore {
         }
     }
 
+    /// Resolves a code lens fetched without a command, to get the title to display it with.
+    /// Remote code lenses are resolved by the host.
+    pub fn resolve_code_lens(
+        &self,
+        buffer_handle: &Entity<Buffer>,
+        mut action: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        if action.resolved || !matches!(action.lsp_action, LspAction::CodeLens(_)) {
+            return Task::ready(Ok(action));
+        }
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveCodeLens {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                lens: Some(Self::serialize_code_action(&action)),
+            };
+            return cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("resolving a code lens")?;
+                Self::deserialize_code_action(response.lens.context("missing code lens")?)
+            });
+        }
+        let Some(lang_server) = buffer_handle.update(cx, |buffer, cx| {
+            self.language_server_for_local_buffer(buffer, action.server_id, cx)
+                .map(|(_, server)| server.clone())
+        }) else {
+            return Task::ready(Ok(action));
+        };
+        cx.background_spawn(async move {
+            LocalLspStore::try_resolve_code_action(&lang_server, &mut action)
+                .await
+                .context("resolving a code lens")?;
+            Ok(action)
+        })
+    }
+
     pub fn apply_code_action(
         &self,
         buffer_handle: Entity<Buffer>,
@@ -5045,7 +5330,7 @@ impl LspStore {
         buffer: &Entity<Buffer>,
         position: Anchor,
         cx: &mut Context<Self>,
-    ) -> Task<Result<Vec<Range<Anchor>>>> {
+    ) -> Task<Result<LinkedEditingRanges>> {
         let snapshot = buffer.read(cx).snapshot();
         let scope = snapshot.language_scope_at(position);
         let Some(server_id) = self
@@ -5082,7 +5367,7 @@ impl LspStore {
                 }) == Some(true)
             })
         else {
-            return Task::ready(Ok(Vec::new()));
+            return Task::ready(Ok(LinkedEditingRanges::default()));
         };
 
         self.request_lsp(
@@ -5180,9 +5465,10 @@ impl LspStore {
     ) -> Task<Result<Option<Transaction>>> {
         let options = buffer.update(cx, |buffer, cx| {
             lsp_command::lsp_formatting_options(
-                language_settings(
+                language_settings_with_detected_indent(
                     buffer.language_at(position).map(|l| l.name()),
                     buffer.file(),
+                    buffer.detected_indent(),
                     cx,
                 )
                 .as_ref(),
@@ -6629,14 +6915,15 @@ impl LspStore {
                         if Some(cached_data.cache_version) == known_cache_version {
                             return None;
                         } else {
+                            let colors = cached_data
+                                .colors
+                                .values()
+                                .flatten()
+                                .cloned()
+                                .collect::<HashSet<_>>();
                             return Some(
                                 Task::ready(Ok(DocumentColors {
-                                    colors: cached_data
-                                        .colors
-                                        .values()
-                                        .flatten()
-                                        .cloned()
-                                        .collect(),
+                                    colors: with_syntax_colors(colors, &buffer.read(cx).snapshot()),
                                     cache_version: Some(cached_data.cache_version),
                                 }))
                                 .shared(),
@@ -6695,7 +6982,7 @@ impl LspStore {
                 };
 
                 lsp_store
-                    .update(cx, |lsp_store, _| {
+                    .update(cx, |lsp_store, cx| {
                         let lsp_data = lsp_store.lsp_document_colors.entry(buffer_id).or_default();
 
                         if let Some(fetched_colors) = fetched_colors {
@@ -6719,7 +7006,7 @@ impl LspStore {
                             .cloned()
                             .collect::<HashSet<_>>();
                         DocumentColors {
-                            colors,
+                            colors: with_syntax_colors(colors, &buffer.read(cx).snapshot()),
                             cache_version: Some(lsp_data.cache_version),
                         }
                     })
@@ -6871,6 +7158,36 @@ impl LspStore {
         }
     }
 
+    /// Whether typing `text` should request signature help from the buffer's
+    /// language servers. While signature help is already shown, the servers'
+    /// retrigger characters count as well.
+    pub fn is_signature_help_trigger(
+        &self,
+        buffer: &Entity<Buffer>,
+        text: &str,
+        is_retrigger: bool,
+        cx: &App,
+    ) -> bool {
+        self.check_if_capable_for_proto_request(
+            buffer,
+            |capabilities| {
+                capabilities
+                    .signature_help_provider
+                    .as_ref()
+                    .is_some_and(|provider| {
+                        let is_listed = |characters: &Option<Vec<String>>| {
+                            characters
+                                .as_ref()
+                                .is_some_and(|characters| characters.iter().any(|c| c == text))
+                        };
+                        is_listed(&provider.trigger_characters)
+                            || (is_retrigger && is_listed(&provider.retrigger_characters))
+                    })
+            },
+            cx,
+        )
+    }
+
     pub fn hover(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -7222,6 +7539,11 @@ impl LspStore {
                 .get_mut(&buffer.remote_id())
                 .and_then(|m| m.get_mut(&language_server.server_id()))?;
             let previous_snapshot = buffer_snapshots.last()?;
+            // Edits made in the same update emit their events together, after the last one. The
+            // first of these events reports all of the edits, so the rest have nothing to send.
+            if previous_snapshot.snapshot.version() == next_snapshot.version() {
+                continue;
+            }
 
             let build_incremental_change = || {
                 buffer
@@ -9417,12 +9739,34 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_code_lens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveCodeLens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveCodeLensResponse> {
+        let lens =
+            Self::deserialize_code_action(envelope.payload.lens.context("missing code lens")?)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let lens = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_code_lens(&buffer, lens, cx)
+            })?
+            .await?;
+        Ok(proto::ResolveCodeLensResponse {
+            lens: Some(Self::serialize_code_action(&lens)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
-        this.update(&mut cx, |_, cx| {
+        this.update(&mut cx, |this, cx| {
+            this.lsp_code_lens.clear();
             cx.emit(LspStoreEvent::RefreshCodeLens);
         })?;
         Ok(proto::Ack {})
@@ -10367,9 +10711,13 @@ impl LspStore {
                             .as_ref()
                             .and_then(|d| d.href.clone()),
                         severity: diagnostic.severity.unwrap_or(DiagnosticSeverity::ERROR),
-                        markdown: adapter.as_ref().and_then(|adapter| {
-                            adapter.diagnostic_message_to_markdown(&diagnostic.message)
-                        }),
+                        markdown: with_external_related_information(
+                            adapter.as_ref().and_then(|adapter| {
+                                adapter.diagnostic_message_to_markdown(&diagnostic.message)
+                            }),
+                            diagnostic,
+                            &lsp_diagnostics.uri,
+                        ),
                         message: diagnostic.message.trim().to_string(),
                         group_id,
                         is_primary: true,
@@ -11436,6 +11784,17 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareCallHierarchy" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::CallHierarchyServerCapability::Simple(value),
+                        OneOf::Right(caps) => lsp::CallHierarchyServerCapability::Options(caps),
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 "textDocument/signatureHelp" => {
                     if let Some(caps) = reg
                         .register_options
@@ -11644,6 +12003,12 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareCallHierarchy" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 "textDocument/signatureHelp" => {
                     server.update_capabilities(|capabilities| {
                         capabilities.signature_help_provider = None;
@@ -11992,6 +12357,35 @@ fn lsp_workspace_diagnostics_refresh(
     })
 }
 
+/// Falls back to the color literals found by the buffer's `colors` query when none of its
+/// language servers report document colors.
+fn with_syntax_colors(
+    lsp_colors: HashSet<DocumentColor>,
+    snapshot: &BufferSnapshot,
+) -> HashSet<DocumentColor> {
+    if !lsp_colors.is_empty() {
+        return lsp_colors;
+    }
+    snapshot
+        .color_literals(0..snapshot.len())
+        .into_iter()
+        .map(|(range, literal)| DocumentColor {
+            lsp_range: lsp::Range {
+                start: point_to_lsp(range.start.to_point_utf16(snapshot)),
+                end: point_to_lsp(range.end.to_point_utf16(snapshot)),
+            },
+            color: lsp::Color {
+                red: literal.color.r,
+                green: literal.color.g,
+                blue: literal.color.b,
+                alpha: literal.color.a,
+            },
+            resolved: true,
+            color_presentations: Vec::new(),
+        })
+        .collect()
+}
+
 fn resolve_word_completion(snapshot: &BufferSnapshot, completion: &mut Completion) {
     let CompletionSource::BufferWord {
         word_range,
@@ -12028,6 +12422,46 @@ fn resolve_word_completion(snapshot: &BufferSnapshot, completion: &mut Completio
 
 impl EventEmitter<LspStoreEvent> for LspStore {}
 
+/// Appends links to the related information of the diagnostic that is located in other documents
+/// to its markdown, as only the related information within its own document is turned into
+/// diagnostics of its group.
+fn with_external_related_information(
+    markdown: Option<String>,
+    diagnostic: &lsp::Diagnostic,
+    document_uri: &Uri,
+) -> Option<String> {
+    let external_infos = diagnostic
+        .related_information
+        .iter()
+        .flatten()
+        .filter(|info| &info.location.uri != document_uri && !info.message.is_empty())
+        .collect::<Vec<_>>();
+    if external_infos.is_empty() {
+        return markdown;
+    }
+
+    let mut markdown =
+        markdown.unwrap_or_else(|| Markdown::escape(diagnostic.message.trim()).into_owned());
+    markdown.push('\n');
+    for info in external_infos {
+        let line = info.location.range.start.line + 1;
+        let file_name = info
+            .location
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| info.location.uri.to_string());
+        markdown.push_str(&format!(
+            "\n- [{}:{line}]({}#L{line}): {}",
+            Markdown::escape(&file_name),
+            info.location.uri,
+            Markdown::escape(info.message.trim()),
+        ));
+    }
+    Some(markdown)
+}
+
 fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     hover
         .contents
@@ -1,4 +1,5 @@
 pub mod agent_server_store;
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
@@ -46,6 +47,7 @@ pub use git_store::{
 pub use manifest_tree::ManifestTree;
 
 use anyhow::{Context as _, Result, anyhow};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{Client, Collaborator, PendingEntitySubscription, TypedEnvelope, UserStore, proto};
 use clock::ReplicaId;
@@ -183,6 +185,7 @@ pub struct Project {
     agent_server_store: Entity<AgentServerStore>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
     collab_client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -325,6 +328,12 @@ pub enum Event {
     DisconnectedFromSshRemote,
     Closed,
     DeletedEntry(WorktreeId, ProjectEntryId),
+    /// An open buffer's file is the same file on disk as that of another open buffer, so
+    /// items showing the duplicate should show the other buffer instead.
+    DuplicateBuffer {
+        duplicate: Entity<Buffer>,
+        buffer: Entity<Buffer>,
+    },
     CollaboratorUpdated {
         old_peer_id: proto::PeerId,
         new_peer_id: proto::PeerId,
@@ -738,6 +747,15 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+/// Ranges that mirror each other's edits, such as the names of an HTML element's tags.
+#[derive(Clone, Debug, Default)]
+pub struct LinkedEditingRanges {
+    pub ranges: Vec<Range<language::Anchor>>,
+    /// A regular expression describing the valid contents of the ranges, when the language
+    /// server provides one. Edits that make a range stop matching it end the linked editing.
+    pub word_pattern: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -760,6 +778,30 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A symbol in a call hierarchy, whose incoming and outgoing calls can be requested.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    /// The language server that returned the item, which its calls are requested from.
+    pub server_id: LanguageServerId,
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+/// Calls between two symbols of a call hierarchy.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The calling symbol for incoming calls, or the called symbol for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The ranges of the calls, within the file of the calling symbol.
+    pub ranges: Vec<Range<Unclipped<PointUtf16>>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CallHierarchyDirection {
+    #[default]
+    Incoming,
+    Outgoing,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -1071,6 +1113,8 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -1179,6 +1223,7 @@ impl Project {
                 fs,
                 remote_client: None,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 agent_server_store,
 
@@ -1296,6 +1341,8 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::remote(REMOTE_SERVER_PROJECT_ID, remote_proto.clone()));
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_remote(
@@ -1331,6 +1378,7 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
@@ -1490,6 +1538,8 @@ impl Project {
 
         let breakpoint_store =
             cx.new(|_| BreakpointStore::remote(remote_id, client.clone().into()))?;
+        let bookmark_store =
+            cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()))?;
         let dap_store = cx.new(|cx| {
             DapStore::new_collab(
                 remote_id,
@@ -1607,6 +1657,7 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store,
+                bookmark_store,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 agent_server_store,
@@ -1814,6 +1865,10 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -2904,6 +2959,12 @@ impl Project {
                         .log_err();
                 }
             }
+            BufferStoreEvent::DuplicateBuffer { duplicate, buffer } => {
+                cx.emit(Event::DuplicateBuffer {
+                    duplicate: duplicate.clone(),
+                    buffer: buffer.clone(),
+                });
+            }
             _ => {}
         }
     }
@@ -3124,9 +3185,11 @@ impl Project {
         match event {
             WorktreeStoreEvent::WorktreeAdded(worktree) => {
                 self.on_worktree_added(worktree, cx);
+                self.update_local_snippet_directories(cx);
                 cx.emit(Event::WorktreeAdded(worktree.read(cx).id()));
             }
             WorktreeStoreEvent::WorktreeRemoved(_, id) => {
+                self.update_local_snippet_directories(cx);
                 cx.emit(Event::WorktreeRemoved(*id));
             }
             WorktreeStoreEvent::WorktreeReleased(_, id) => {
@@ -3155,6 +3218,26 @@ impl Project {
         }
     }
 
+    /// Watches the `.zed/snippets` folders of the visible worktrees, so that snippets checked
+    /// into a project are offered alongside the user's own.
+    fn update_local_snippet_directories(&mut self, cx: &mut Context<Self>) {
+        if !self.is_local() {
+            return;
+        }
+        let snippet_dirs = self
+            .visible_worktrees(cx)
+            .map(|worktree| {
+                worktree
+                    .read(cx)
+                    .abs_path()
+                    .join(paths::local_snippets_folder_relative_path())
+            })
+            .collect();
+        self.snippets.update(cx, |snippets, cx| {
+            snippets.set_watched_directories(snippet_dirs, cx)
+        });
+    }
+
     fn on_worktree_released(&mut self, id_to_remove: WorktreeId, cx: &mut Context<Self>) {
         if let Some(remote) = &self.remote_client {
             remote
@@ -3303,6 +3386,9 @@ impl Project {
         cx: &mut Context<Self>,
     ) {
         self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .buffers_with_explicit_language
+                .insert(buffer.read(cx).remote_id());
             lsp_store.set_language_for_buffer(buffer, new_language, cx)
         })
     }
@@ -3636,6 +3722,41 @@ impl Project {
         )
     }
 
+    /// Returns the symbols at `position` that a call hierarchy can be shown for.
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Returns the calls to or from `item`, which was returned for a position in `buffer`.
+    pub fn call_hierarchy_calls(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        item: CallHierarchyItem,
+        direction: CallHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let server = LanguageServerToQuery::Other(item.server_id);
+        match direction {
+            CallHierarchyDirection::Incoming => {
+                self.request_lsp(buffer.clone(), server, GetIncomingCalls { item }, cx)
+            }
+            CallHierarchyDirection::Outgoing => {
+                self.request_lsp(buffer.clone(), server, GetOutgoingCalls { item }, cx)
+            }
+        }
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3709,7 +3830,7 @@ impl Project {
         buffer: &Entity<Buffer>,
         position: Anchor,
         cx: &mut Context<Self>,
-    ) -> Task<Result<Vec<Range<Anchor>>>> {
+    ) -> Task<Result<LinkedEditingRanges>> {
         self.lsp_store.update(cx, |lsp_store, cx| {
             lsp_store.linked_edits(buffer, position, cx)
         })
@@ -3793,6 +3914,17 @@ impl Project {
         })
     }
 
+    pub fn resolve_code_lens(
+        &self,
+        buffer_handle: &Entity<Buffer>,
+        action: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_code_lens(buffer_handle, action, cx)
+        })
+    }
+
     pub fn apply_code_action_kind(
         &self,
         buffers: HashSet<Entity<Buffer>>,
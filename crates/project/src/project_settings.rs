@@ -76,6 +76,44 @@ pub struct ProjectSettings {
 
     /// Configuration for session-related features
     pub session: SessionSettings,
+
+    /// Tasks to run after saving files matching a glob.
+    pub tasks_on_save: Vec<settings::TaskOnSaveRule>,
+
+    /// Configuration for opening large files with expensive features turned off.
+    pub large_file_mode: LargeFileModeSettings,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct LargeFileModeSettings {
+    /// Whether to open large files with syntax highlighting, language servers,
+    /// soft wrap and inlay hints turned off.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// Files of at least this many bytes are opened in large file mode.
+    ///
+    /// Default: 20000000
+    pub file_size_threshold: u64,
+    /// Files with at least this many lines are opened in large file mode.
+    ///
+    /// Default: 500000
+    pub line_count_threshold: u32,
+}
+
+impl LargeFileModeSettings {
+    /// Whether a file with the given size and number of lines should be opened
+    /// in large file mode.
+    pub fn is_large_file(&self, len: usize, line_count: u32) -> bool {
+        self.is_large_file_size(len as u64)
+            || (self.enabled && line_count >= self.line_count_threshold)
+    }
+
+    /// Whether a file of the given size on disk should be opened in large file
+    /// mode, before its contents have been loaded.
+    pub fn is_large_file_size(&self, size: u64) -> bool {
+        self.enabled && size >= self.file_size_threshold
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -87,6 +125,12 @@ pub struct SessionSettings {
     ///
     /// Default: true
     pub restore_unsaved_buffers: bool,
+    /// Whether or not to restore the undo history of files on restart.
+    ///
+    /// The history is discarded if the file changed on disk in the meantime.
+    ///
+    /// Default: true
+    pub restore_undo_history: bool,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -415,6 +459,37 @@ pub struct InlineDiagnosticsSettings {
     pub min_column: u32,
 
     pub max_severity: Option<DiagnosticSeverity>,
+    /// Which severities of diagnostics to show inline, on top of `max_severity`.
+    pub severities: InlineDiagnosticsSeverities,
+    /// Whether to show inline diagnostics as dimmed text, rather than in the
+    /// color of their severity over a tinted background.
+    ///
+    /// Default: false
+    pub dimmed: bool,
+    /// The maximum number of characters of a diagnostic message to show inline.
+    ///
+    /// Default: None
+    pub max_length: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InlineDiagnosticsSeverities {
+    pub error: bool,
+    pub warning: bool,
+    pub info: bool,
+    pub hint: bool,
+}
+
+impl InlineDiagnosticsSeverities {
+    pub fn shows(&self, severity: lsp::DiagnosticSeverity) -> bool {
+        match severity {
+            lsp::DiagnosticSeverity::ERROR => self.error,
+            lsp::DiagnosticSeverity::WARNING => self.warning,
+            lsp::DiagnosticSeverity::INFORMATION => self.info,
+            lsp::DiagnosticSeverity::HINT => self.hint,
+            _ => true,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -499,6 +574,17 @@ impl Settings for ProjectSettings {
                     padding: inline_diagnostics.padding.unwrap(),
                     min_column: inline_diagnostics.min_column.unwrap(),
                     max_severity: inline_diagnostics.max_severity.map(Into::into),
+                    severities: {
+                        let severities = inline_diagnostics.severities.unwrap();
+                        InlineDiagnosticsSeverities {
+                            error: severities.error.unwrap(),
+                            warning: severities.warning.unwrap(),
+                            info: severities.info.unwrap(),
+                            hint: severities.hint.unwrap(),
+                        }
+                    },
+                    dimmed: inline_diagnostics.dimmed.unwrap(),
+                    max_length: inline_diagnostics.max_length,
                 },
             },
             git: git_settings,
@@ -506,6 +592,16 @@ impl Settings for ProjectSettings {
             load_direnv: project.load_direnv.clone().unwrap(),
             session: SessionSettings {
                 restore_unsaved_buffers: content.session.unwrap().restore_unsaved_buffers.unwrap(),
+                restore_undo_history: content.session.unwrap().restore_undo_history.unwrap(),
+            },
+            tasks_on_save: project.tasks_on_save.clone().unwrap(),
+            large_file_mode: {
+                let large_file_mode = content.large_file_mode.unwrap();
+                LargeFileModeSettings {
+                    enabled: large_file_mode.enabled.unwrap(),
+                    file_size_threshold: large_file_mode.file_size_threshold.unwrap(),
+                    line_count_threshold: large_file_mode.line_count_threshold.unwrap(),
+                }
             },
         }
     }
@@ -145,6 +145,7 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
         [*.js]
             tab_width = 10
             max_line_length = off
+            charset = utf-8-bom
         "#,
         ".zed": {
             "settings.json": r#"{
@@ -211,6 +212,15 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
         assert_eq!(settings_a.ensure_final_newline_on_save, true);
         assert_eq!(settings_a.remove_trailing_whitespace_on_save, true);
         assert_eq!(settings_a.preferred_line_length, 120);
+        assert_eq!(settings_a.line_ending_on_save, Some(LineEnding::Unix));
+        assert_eq!(settings_a.byte_order_mark_on_save, None);
+
+        // Indentation from .editorconfig isn't replaced by the detected one
+        assert_eq!(settings_a.detect_indentation, false);
+        assert_eq!(settings_readme.detect_indentation, true);
+
+        assert_eq!(settings_c.byte_order_mark_on_save, Some(true));
+        assert_eq!(settings_c.line_ending_on_save, None);
 
         // .editorconfig in b/ overrides .editorconfig in root
         assert_eq!(Some(settings_b.tab_size), NonZeroU32::new(2));
@@ -799,6 +809,210 @@ async fn test_running_multiple_instances_of_a_single_server_in_one_worktree(
     assert_eq!(server.server_id(), LanguageServerId(1));
 }
 
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings
+                    .large_file_mode
+                    .get_or_insert_default()
+                    .line_count_threshold = Some(3);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "small.rs": "const A: i32 = 1;",
+            "large.rs": "const A: i32 = 1;\nconst B: i32 = 2;\nconst C: i32 = 3;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_rust_servers =
+        language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (small_buffer, _small_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/small.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_rust_server = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Uri::from_file_path(path!("/dir/small.rs")).unwrap(),
+    );
+
+    // The large file is neither parsed nor opened in the language server.
+    let (large_buffer, _large_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/large.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    small_buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert!(buffer.snapshot().syntax_layers().next().is_some());
+    });
+    large_buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert_eq!(buffer.language().map(|l| l.name()), Some("Rust".into()));
+        assert!(buffer.snapshot().syntax_layers().next().is_none());
+    });
+
+    // Turning large file mode off parses the file and opens it in the server.
+    large_buffer.update(cx, |buffer, cx| buffer.set_large_file(false, cx));
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Uri::from_file_path(path!("/dir/large.rs")).unwrap(),
+    );
+    cx.executor().run_until_parked();
+    large_buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.snapshot().syntax_layers().next().is_some());
+    });
+}
+
+#[gpui::test]
+async fn test_large_file_mode_by_size(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings
+                    .large_file_mode
+                    .get_or_insert_default()
+                    .file_size_threshold = Some(16);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "small.txt": "one\r\ntwo\r\n",
+            "large.txt": "one\r\ntwo\r\nthree\r\nfour\r\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    // Files over the size threshold are streamed in, and still have their line
+    // endings normalized.
+    for (path, is_large_file) in [
+        (path!("/dir/small.txt"), false),
+        (path!("/dir/large.txt"), true),
+    ] {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.is_large_file(), is_large_file);
+            assert_eq!(buffer.line_ending(), LineEnding::Windows);
+            assert!(!buffer.text().contains('\r'));
+        });
+    }
+}
+
+#[gpui::test]
+async fn test_redetecting_languages_when_file_types_change(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.conf": "{}", "b.conf": "{}" }))
+        .await;
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| {
+        project.languages().add(json_lang());
+        project.languages().add(rust_lang());
+    });
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.conf"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/b.conf"), cx)
+        })
+        .await
+        .unwrap();
+    let language_name = |buffer: &Entity<Buffer>, cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer.language().map(|language| language.name())
+        })
+    };
+    assert_eq!(language_name(&buffer_a, cx), None);
+
+    // Languages chosen explicitly are kept when `file_types` changes.
+    project.update(cx, |project, cx| {
+        project.set_language_for_buffer(&buffer_b, rust_lang(), cx)
+    });
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings
+                    .project
+                    .all_languages
+                    .file_types
+                    .insert("JSON".into(), vec!["*.conf".to_string()].into());
+            });
+        });
+    });
+    cx.run_until_parked();
+    assert_eq!(language_name(&buffer_a, cx), Some("JSON".into()));
+    assert_eq!(language_name(&buffer_b, cx), Some("Rust".into()));
+
+    // Other settings changes don't detect languages again.
+    project.update(cx, |project, cx| {
+        project.lsp_store().update(cx, |lsp_store, cx| {
+            lsp_store.set_language_for_buffer(&buffer_a, rust_lang(), cx)
+        })
+    });
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings.project.all_languages.defaults.tab_size = NonZeroU32::new(8);
+            });
+        });
+    });
+    cx.run_until_parked();
+    assert_eq!(language_name(&buffer_a, cx), Some("Rust".into()));
+
+    // Released buffers are forgotten.
+    let buffer_b_id = buffer_b.read_with(cx, |buffer, _| buffer.remote_id());
+    drop(buffer_b);
+    cx.run_until_parked();
+    project.read_with(cx, |project, cx| {
+        let lsp_store = project.lsp_store().read(cx);
+        assert!(lsp_store.buffers_with_explicit_language.is_empty());
+        assert!(
+            !lsp_store
+                .buffer_file_type_patterns
+                .contains_key(&buffer_b_id)
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2298,6 +2512,66 @@ async fn test_toggling_enable_language_server(cx: &mut gpui::TestAppContext) {
         .await;
 }
 
+#[gpui::test]
+async fn test_coalescing_buffer_change_notifications(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn a() {}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+    fake_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+
+    // Edits made in a single update are reported to the language server in a single notification.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(3..4, "b")], None, cx);
+        buffer.edit([(9..9, "\n")], None, cx);
+        buffer.edit([(0..0, "pub ")], None, cx);
+    });
+    let notification = fake_server
+        .receive_notification::<lsp::notification::DidChangeTextDocument>()
+        .await;
+    assert_eq!(notification.text_document.version, 1);
+    assert_eq!(
+        notification
+            .content_changes
+            .iter()
+            .map(|change| change.text.as_str())
+            .collect::<Vec<_>>(),
+        ["pub ", "b", "\n"]
+    );
+
+    // The other events of the burst don't send empty notifications, so the next edit's
+    // notification has the next version.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    let notification = fake_server
+        .receive_notification::<lsp::notification::DidChangeTextDocument>()
+        .await;
+    assert_eq!(notification.text_document.version, 2);
+    assert_eq!(
+        notification
+            .content_changes
+            .iter()
+            .map(|change| change.text.as_str())
+            .collect::<Vec<_>>(),
+        ["// a\n"]
+    );
+}
+
 #[gpui::test(iterations = 3)]
 async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2528,6 +2802,15 @@ async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
     assert!(
         change_notification_2.text_document.version > change_notification_1.text_document.version
     );
+    // The edits were made in a single update, so they're reported in a single notification.
+    assert_eq!(
+        change_notification_2
+            .content_changes
+            .iter()
+            .map(|change| change.text.as_str())
+            .collect::<Vec<_>>(),
+        ["    ", "(x: usize)", "xxx"]
+    );
 
     // Handle out-of-order diagnostics
     fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
@@ -3273,6 +3556,127 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }\nfn b() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let uri = lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap();
+    let item = |name: &str, row: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 14)),
+        selection_range: lsp::Range::new(lsp::Position::new(row, 3), lsp::Position::new(row, 4)),
+        data: None,
+    };
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item = item("b", 1);
+        move |params, _| {
+            let item = item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 3)
+                );
+                Ok(Some(vec![item]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let caller = item("a", 0);
+        move |params, _| {
+            let caller = caller.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: caller,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(1, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].lsp_item.name, "b");
+    assert_eq!(items[0].server_id, fake_server.server.server_id());
+
+    let calls = project
+        .update(cx, |project, cx| {
+            project.call_hierarchy_calls(
+                &buffer,
+                items[0].clone(),
+                CallHierarchyDirection::Incoming,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.lsp_item.name, "a");
+    assert_eq!(
+        calls[0].ranges,
+        [Unclipped(PointUtf16::new(0, 9))..Unclipped(PointUtf16::new(0, 10))]
+    );
+
+    // The server doesn't report outgoing calls.
+    let calls = project
+        .update(cx, |project, cx| {
+            project.call_hierarchy_calls(
+                &buffer,
+                items[0].clone(),
+                CallHierarchyDirection::Outgoing,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert!(calls.is_empty());
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4396,6 +4800,87 @@ async fn test_buffer_deduping(cx: &mut gpui::TestAppContext) {
     assert_eq!(buffer_a_3.entity_id(), buffer_a_id);
 }
 
+#[gpui::test]
+async fn test_buffer_deduping_across_links(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.txt": "contents",
+            "b.txt": "contents",
+        }),
+    )
+    .await;
+    fs.create_symlink(path!("/dir/link.txt").as_ref(), "a.txt".into())
+        .await
+        .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer_a = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/a.txt"), cx))
+        .await
+        .unwrap();
+
+    // Opening the file through the symlink reuses the buffer of its target.
+    let buffer_link = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/link.txt"), cx))
+        .await
+        .unwrap();
+    assert_eq!(buffer_link.entity_id(), buffer_a.entity_id());
+
+    // Files that merely have the same contents still get their own buffers.
+    let buffer_b = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/b.txt"), cx))
+        .await
+        .unwrap();
+    assert_ne!(buffer_b.entity_id(), buffer_a.entity_id());
+
+    // Once the link is replaced by another file, opening it no longer reuses the buffer.
+    fs.remove_file(path!("/dir/link.txt").as_ref(), RemoveOptions::default())
+        .await
+        .unwrap();
+    fs.insert_file(path!("/dir/link.txt"), b"other contents".to_vec())
+        .await;
+    cx.run_until_parked();
+    let buffer_link = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/link.txt"), cx))
+        .await
+        .unwrap();
+    assert_ne!(buffer_link.entity_id(), buffer_a.entity_id());
+    buffer_link.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "other contents"));
+
+    // When an open buffer's file is replaced by a link to another open buffer's file, the
+    // project reports the duplicate, so that its items can be moved over.
+    let events = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        cx.subscribe(&project, {
+            let events = events.clone();
+            move |_, event, _| {
+                if let Event::DuplicateBuffer { .. } = event {
+                    events.lock().push(event.clone());
+                }
+            }
+        })
+        .detach();
+    });
+    fs.remove_file(path!("/dir/b.txt").as_ref(), RemoveOptions::default())
+        .await
+        .unwrap();
+    fs.create_symlink(path!("/dir/b.txt").as_ref(), "a.txt".into())
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        *events.lock(),
+        [Event::DuplicateBuffer {
+            duplicate: buffer_b,
+            buffer: buffer_a,
+        }]
+    );
+}
+
 #[gpui::test]
 async fn test_buffer_is_dirty(cx: &mut gpui::TestAppContext) {
     init_test(cx);
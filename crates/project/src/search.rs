@@ -59,6 +59,7 @@ pub enum SearchQuery {
     Text {
         search: AhoCorasick,
         replacement: Option<String>,
+        preserve_case: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
@@ -67,6 +68,7 @@ pub enum SearchQuery {
     Regex {
         regex: Regex,
         replacement: Option<String>,
+        preserve_case: bool,
         multiline: bool,
         whole_word: bool,
         case_sensitive: bool,
@@ -127,6 +129,7 @@ impl SearchQuery {
         Ok(Self::Text {
             search,
             replacement: None,
+            preserve_case: false,
             whole_word,
             case_sensitive,
             include_ignored,
@@ -190,6 +193,7 @@ impl SearchQuery {
         Ok(Self::Regex {
             regex,
             replacement: None,
+            preserve_case: false,
             multiline,
             whole_word,
             case_sensitive,
@@ -305,6 +309,24 @@ impl SearchQuery {
         }
     }
 
+    /// Makes replacements follow the case of the text they replace, so that replacing `foo`
+    /// with `bar` turns `Foo` into `Bar` and `FOO` into `BAR`.
+    pub fn with_preserve_case(mut self, new_preserve_case: bool) -> Self {
+        match self {
+            Self::Text {
+                ref mut preserve_case,
+                ..
+            }
+            | Self::Regex {
+                ref mut preserve_case,
+                ..
+            } => {
+                *preserve_case = new_preserve_case;
+                self
+            }
+        }
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         let files_to_include = self.files_to_include().sources().to_vec();
         let files_to_exclude = self.files_to_exclude().sources().to_vec();
@@ -372,6 +394,18 @@ impl SearchQuery {
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
+        let replacement = self.replacement_ignoring_case_for(text)?;
+        match self {
+            SearchQuery::Text { preserve_case, .. } | SearchQuery::Regex { preserve_case, .. }
+                if *preserve_case =>
+            {
+                Some(Cow::Owned(preserve_case_of(text, &replacement)))
+            }
+            _ => Some(replacement),
+        }
+    }
+
+    fn replacement_ignoring_case_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
@@ -595,6 +629,29 @@ impl SearchQuery {
     }
 }
 
+/// Changes the case of `replacement` to follow the case of the `replaced` text when it's all
+/// uppercase, all lowercase or capitalized.
+fn preserve_case_of(replaced: &str, replacement: &str) -> String {
+    let mut letters = replaced.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return replacement.to_string();
+    };
+    let rest = letters.collect::<Vec<_>>();
+    if first.is_uppercase() && !rest.is_empty() && rest.iter().all(|c| c.is_uppercase()) {
+        replacement.to_uppercase()
+    } else if first.is_lowercase() && rest.iter().all(|c| c.is_lowercase()) {
+        replacement.to_lowercase()
+    } else if first.is_uppercase() {
+        let mut chars = replacement.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    } else {
+        replacement.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -725,4 +782,28 @@ mod tests {
             "Case sensitivity should not be enabled when \\C pattern item is preceded by a backslash."
         );
     }
+
+    #[test]
+    fn test_replacement_preserving_case() {
+        let query = SearchQuery::regex(
+            "foo(\\d)",
+            false,
+            false,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_replacement("bar_$1".to_string());
+        assert_eq!(query.replacement_for("FOO1").as_deref(), Some("bar_1"));
+
+        let query = query.with_preserve_case(true);
+        assert_eq!(query.replacement_for("foo1").as_deref(), Some("bar_1"));
+        assert_eq!(query.replacement_for("Foo2").as_deref(), Some("Bar_2"));
+        assert_eq!(query.replacement_for("FOO3").as_deref(), Some("BAR_3"));
+        assert_eq!(query.replacement_for("fOo4").as_deref(), Some("bar_4"));
+    }
 }
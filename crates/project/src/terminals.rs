@@ -257,7 +257,7 @@ impl Project {
                     env,
                     settings.cursor_shape.unwrap_or_default(),
                     settings.alternate_scroll,
-                    settings.max_scroll_history_lines,
+                    settings.scrollback_lines,
                     is_via_remote,
                     cx.entity_id().as_u64(),
                     Some(completion_tx),
@@ -395,7 +395,7 @@ impl Project {
                     env,
                     settings.cursor_shape.unwrap_or_default(),
                     settings.alternate_scroll,
-                    settings.max_scroll_history_lines,
+                    settings.scrollback_lines,
                     is_via_remote,
                     cx.entity_id().as_u64(),
                     None,
@@ -435,29 +435,80 @@ impl Project {
         cx: &mut Context<'_, Project>,
         cwd: impl FnOnce() -> Option<PathBuf>,
     ) -> Result<Entity<Terminal>> {
-        terminal.read(cx).clone_builder(cx, cwd).map(|builder| {
-            let terminal_handle = cx.new(|cx| builder.subscribe(cx));
-
-            self.terminals
-                .local_handles
-                .push(terminal_handle.downgrade());
-
-            let id = terminal_handle.entity_id();
-            cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
-                let handles = &mut project.terminals.local_handles;
-
-                if let Some(index) = handles
-                    .iter()
-                    .position(|terminal| terminal.entity_id() == id)
-                {
-                    handles.remove(index);
-                    cx.notify();
-                }
-            })
-            .detach();
+        terminal
+            .read(cx)
+            .clone_builder(cx, cwd)
+            .map(|builder| self.add_local_terminal(builder, cx))
+    }
+
+    /// Creates a terminal running an SSH session to a host from the SSH config,
+    /// independently of whether the project itself is remote.
+    pub fn create_ssh_host_terminal(
+        &mut self,
+        host: String,
+        cx: &mut Context<Self>,
+    ) -> Result<Entity<Terminal>> {
+        let settings = TerminalSettings::get_global(cx).clone();
+        let shell = Shell::WithArguments {
+            program: "ssh".to_string(),
+            args: vec![
+                // Notice a dropped connection within a minute, so that the
+                // session can be reconnected.
+                "-o".to_string(),
+                "ServerAliveInterval=15".to_string(),
+                "-o".to_string(),
+                "ServerAliveCountMax=3".to_string(),
+                "-t".to_string(),
+                // Hosts typed into the picker could otherwise be taken for options.
+                "--".to_string(),
+                host.clone(),
+            ],
+            title_override: Some(format!("{host} — Terminal").into()),
+        };
+        let builder = TerminalBuilder::new(
+            None,
+            None,
+            shell,
+            settings.env,
+            settings.cursor_shape.unwrap_or_default(),
+            settings.alternate_scroll,
+            settings.scrollback_lines,
+            true,
+            cx.entity_id().as_u64(),
+            None,
+            cx,
+            Vec::new(),
+        )?
+        .with_ssh_host(host);
+        Ok(self.add_local_terminal(builder, cx))
+    }
 
-            terminal_handle
+    fn add_local_terminal(
+        &mut self,
+        builder: TerminalBuilder,
+        cx: &mut Context<Self>,
+    ) -> Entity<Terminal> {
+        let terminal_handle = cx.new(|cx| builder.subscribe(cx));
+
+        self.terminals
+            .local_handles
+            .push(terminal_handle.downgrade());
+
+        let id = terminal_handle.entity_id();
+        cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
+            let handles = &mut project.terminals.local_handles;
+
+            if let Some(index) = handles
+                .iter()
+                .position(|terminal| terminal.entity_id() == id)
+            {
+                handles.remove(index);
+                cx.notify();
+            }
         })
+        .detach();
+
+        terminal_handle
     }
 
     pub fn terminal_settings<'a>(
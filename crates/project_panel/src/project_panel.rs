@@ -14,11 +14,12 @@ use editor::{
     },
 };
 use file_icons::FileIcons;
+use fuzzy::{StringMatchCandidate, match_strings};
 use git::status::GitSummary;
 use git_ui::file_diff_view::FileDiffView;
 use gpui::{
-    Action, AnyElement, App, ArcCow, AsyncWindowContext, Bounds, ClipboardItem, Context,
-    CursorStyle, DismissEvent, Div, DragMoveEvent, Entity, EventEmitter, ExternalPaths,
+    Action, AnyElement, App, ArcCow, AsyncWindowContext, BackgroundExecutor, Bounds, ClipboardItem,
+    Context, CursorStyle, DismissEvent, Div, DragMoveEvent, Entity, EventEmitter, ExternalPaths,
     FocusHandle, Focusable, Hsla, InteractiveElement, KeyContext, ListHorizontalSizingBehavior,
     ListSizingBehavior, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent,
     ParentElement, Pixels, Point, PromptLevel, Render, ScrollStrategy, Stateful, Styled,
@@ -52,7 +53,7 @@ use std::{
     ffi::OsStr,
     ops::Range,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, atomic::AtomicBool},
     time::Duration,
 };
 use theme::ThemeSettings;
@@ -105,6 +106,10 @@ pub struct ProjectPanel {
     context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     edit_state: Option<EditState>,
     filename_editor: Entity<Editor>,
+    filter_editor: Entity<Editor>,
+    show_filter: bool,
+    filter: Option<EntryFilter>,
+    filter_task: Task<()>,
     clipboard: Option<ClipboardEntry>,
     _dragged_entry_destination: Option<Arc<Path>>,
     workspace: WeakEntity<Workspace>,
@@ -167,6 +172,21 @@ impl EditState {
     }
 }
 
+/// The entries left visible while the filter field has a query.
+#[derive(Debug, Default)]
+struct EntryFilter {
+    /// Entries whose names match the query.
+    matched_entry_ids: HashSet<ProjectEntryId>,
+    /// Directories containing a match, which are shown expanded.
+    expanded_entry_ids: HashSet<ProjectEntryId>,
+}
+
+impl EntryFilter {
+    fn contains(&self, entry_id: ProjectEntryId) -> bool {
+        self.matched_entry_ids.contains(&entry_id) || self.expanded_entry_ids.contains(&entry_id)
+    }
+}
+
 #[derive(Clone, Debug)]
 enum ClipboardEntry {
     Copied(BTreeSet<SelectedEntry>),
@@ -294,6 +314,8 @@ actions!(
         SelectPrevDirectory,
         /// Opens a diff view to compare two marked files.
         CompareMarkedFiles,
+        /// Shows or focuses the field for filtering the project tree by file name.
+        ToggleFilter,
     ]
 );
 
@@ -527,6 +549,9 @@ impl ProjectPanel {
                 project::Event::WorktreeUpdatedEntries(_, _)
                 | project::Event::WorktreeAdded(_)
                 | project::Event::WorktreeOrderChanged => {
+                    if this.filter.is_some() {
+                        this.update_filter(false, cx);
+                    }
                     this.update_visible_entries(None, cx);
                     cx.notify();
                 }
@@ -606,6 +631,18 @@ impl ProjectPanel {
             )
             .detach();
 
+            let filter_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Filter...", window, cx);
+                editor
+            });
+            cx.subscribe(&filter_editor, |project_panel, _, event, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    project_panel.update_filter(true, cx);
+                }
+            })
+            .detach();
+
             cx.observe_global::<FileIcons>(|_, cx| {
                 cx.notify();
             })
@@ -649,6 +686,10 @@ impl ProjectPanel {
                 edit_state: None,
                 context_menu: None,
                 filename_editor,
+                filter_editor,
+                show_filter: false,
+                filter: None,
+                filter_task: Task::ready(()),
                 clipboard: None,
                 _dragged_entry_destination: None,
                 workspace: workspace.weak_handle(),
@@ -1627,6 +1668,110 @@ impl ProjectPanel {
         cx.notify();
     }
 
+    fn toggle_filter(&mut self, _: &ToggleFilter, window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_filter && self.filter_editor.focus_handle(cx).is_focused(window) {
+            self.dismiss_filter(&menu::Cancel, window, cx);
+        } else {
+            self.show_filter = true;
+            self.filter_editor.focus_handle(cx).focus(window);
+            cx.notify();
+        }
+    }
+
+    fn confirm_filter(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_internal(false, true, None, window, cx);
+    }
+
+    fn dismiss_filter(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_filter = false;
+        self.filter_editor
+            .update(cx, |editor, cx| editor.clear(window, cx));
+        // Keep the entry picked while filtering visible once the whole tree is back.
+        if let Some(selection) = self.selection {
+            self.expand_entry(selection.worktree_id, selection.entry_id, cx);
+        }
+        self.filter_task = Task::ready(());
+        self.filter = None;
+        self.update_visible_entries(None, cx);
+        self.autoscroll(cx);
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Matches the filter field's query against the names of the entries of
+    /// every visible worktree, in the background.
+    fn update_filter(&mut self, select_first_match: bool, cx: &mut Context<Self>) {
+        let query = self.filter_editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            self.filter_task = Task::ready(());
+            if self.filter.take().is_some() {
+                self.update_visible_entries(None, cx);
+                self.autoscroll(cx);
+                cx.notify();
+            }
+            return;
+        }
+
+        let include_ignored = !ProjectPanelSettings::get_global(cx).hide_gitignore;
+        let snapshots = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).snapshot())
+            .collect::<Vec<_>>();
+        let executor = cx.background_executor().clone();
+        self.filter_task = cx.spawn(async move |this, cx| {
+            let filter = cx
+                .background_spawn(filter_entries(snapshots, query, include_ignored, executor))
+                .await;
+            this.update(cx, |this, cx| {
+                this.filter = Some(filter);
+                this.update_visible_entries(None, cx);
+                if select_first_match {
+                    this.select_first_filter_match();
+                    this.autoscroll(cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn select_first_filter_match(&mut self) {
+        let Some(filter) = &self.filter else {
+            return;
+        };
+        self.selection = self.visible_entries.iter().find_map(|visible| {
+            let entry = visible
+                .entries
+                .iter()
+                .find(|entry| filter.matched_entry_ids.contains(&entry.id))?;
+            Some(SelectedEntry {
+                worktree_id: visible.worktree_id,
+                entry_id: entry.id,
+            })
+        });
+    }
+
+    fn render_filter(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .flex_none()
+            .w_full()
+            .gap_1p5()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .on_action(cx.listener(Self::confirm_filter))
+            .on_action(cx.listener(Self::dismiss_filter))
+            .child(
+                Icon::new(IconName::MagnifyingGlass)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.filter_editor.clone())
+    }
+
     fn open_entry(
         &mut self,
         entry_id: ProjectEntryId,
@@ -3048,6 +3193,7 @@ impl ProjectPanel {
                 id: NEW_ENTRY_ID,
                 kind: new_entry_kind,
                 path: parent_entry.path.join("\0").into(),
+                device: 0,
                 inode: 0,
                 mtime: parent_entry.mtime,
                 size: parent_entry.size,
@@ -3122,6 +3268,14 @@ impl ProjectPanel {
                 GitTraversal::new(&repo_snapshots, worktree_snapshot.entries(true, 0));
             let mut auto_folded_ancestors = vec![];
             while let Some(entry) = entry_iter.entry() {
+                if self
+                    .filter
+                    .as_ref()
+                    .is_some_and(|filter| !filter.contains(entry.id))
+                {
+                    entry_iter.advance_to_sibling();
+                    continue;
+                }
                 if hide_root && Some(entry.entry) == worktree.read(cx).root_entry() {
                     if new_entry_parent_id == Some(entry.id) {
                         visible_worktree_entries.push(Self::create_new_git_entry(
@@ -3256,9 +3410,11 @@ impl ProjectPanel {
                     }
                 }
 
-                if expanded_dir_ids.binary_search(&entry.id).is_err()
-                    && entry_iter.advance_to_sibling()
-                {
+                let is_expanded = match &self.filter {
+                    Some(filter) => filter.expanded_entry_ids.contains(&entry.id),
+                    None => expanded_dir_ids.binary_search(&entry.id).is_ok(),
+                };
+                if !is_expanded && entry_iter.advance_to_sibling() {
                     continue;
                 }
                 entry_iter.advance();
@@ -4786,7 +4942,10 @@ impl ProjectPanel {
             .get(&worktree_id)
             .map(Vec::as_slice)
             .unwrap_or(&[]);
-        let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
+        let is_expanded = match &self.filter {
+            Some(filter) => filter.expanded_entry_ids.contains(&entry.id),
+            None => expanded_entry_ids.binary_search(&entry.id).is_ok(),
+        };
 
         let icon = match entry.kind {
             EntryKind::File => {
@@ -4872,7 +5031,9 @@ impl ProjectPanel {
         dispatch_context.add("ProjectPanel");
         dispatch_context.add("menu");
 
-        let identifier = if self.filename_editor.focus_handle(cx).is_focused(window) {
+        let identifier = if self.filename_editor.focus_handle(cx).is_focused(window)
+            || self.filter_editor.focus_handle(cx).is_focused(window)
+        {
             "editing"
         } else {
             "not_editing"
@@ -5118,6 +5279,56 @@ impl StickyCandidate for StickyProjectPanelCandidate {
     }
 }
 
+/// Fuzzy-matches `query` against the names of the entries of `snapshots`,
+/// keeping the matches and the directories containing them.
+async fn filter_entries(
+    snapshots: Vec<worktree::Snapshot>,
+    query: String,
+    include_ignored: bool,
+    executor: BackgroundExecutor,
+) -> EntryFilter {
+    let mut entries = Vec::new();
+    let mut candidates = Vec::new();
+    for snapshot in &snapshots {
+        for entry in snapshot.entries(include_ignored, 0) {
+            if let Some(file_name) = entry.path.file_name() {
+                candidates.push(StringMatchCandidate::new(
+                    entries.len(),
+                    &file_name.to_string_lossy(),
+                ));
+                entries.push((snapshot, entry));
+            }
+        }
+    }
+
+    let matches = match_strings(
+        &candidates,
+        &query,
+        true,
+        true,
+        usize::MAX,
+        &AtomicBool::default(),
+        executor,
+    )
+    .await;
+
+    let mut filter = EntryFilter::default();
+    for string_match in matches {
+        let (snapshot, entry) = entries[string_match.candidate_id];
+        filter.matched_entry_ids.insert(entry.id);
+        for ancestor_path in entry.path.ancestors().skip(1) {
+            let Some(ancestor) = snapshot.entry_for_path(ancestor_path) else {
+                continue;
+            };
+            // Each directory's own ancestors were added along with it.
+            if !filter.expanded_entry_ids.insert(ancestor.id) {
+                break;
+            }
+        }
+    }
+    filter
+}
+
 fn item_width_estimate(depth: usize, item_text_chars: usize, is_symlink: bool) -> usize {
     const ICON_SIZE_FACTOR: usize = 2;
     let mut item_width = depth * ICON_SIZE_FACTOR + item_text_chars;
@@ -5262,6 +5473,7 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::fold_directory))
                 .on_action(cx.listener(Self::remove_from_project))
                 .on_action(cx.listener(Self::compare_marked_files))
+                .on_action(cx.listener(Self::toggle_filter))
                 .when(!project.is_read_only(cx), |el| {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
@@ -5306,6 +5518,7 @@ impl Render for ProjectPanel {
                 .track_focus(&self.focus_handle(cx))
                 .child(
                     v_flex()
+                        .when(self.show_filter, |this| this.child(self.render_filter(cx)))
                         .child(
                             uniform_list("entries", item_count, {
                                 cx.processor(|this, range: Range<usize>, window, cx| {
@@ -6526,6 +6526,66 @@ async fn test_compare_files_context_menu(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_filter_entries(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "src": {
+                "main.rs": "",
+                "lib.rs": "",
+                "util": { "parse.rs": "" },
+            },
+            "tests": { "integration.rs": "" },
+            "README.md": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+
+    panel.update_in(cx, |panel, window, cx| {
+        panel.toggle_filter(&ToggleFilter, window, cx);
+        panel
+            .filter_editor
+            .update(cx, |editor, cx| editor.set_text("par", window, cx));
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src",
+            "        v util",
+            "              parse.rs  <== selected",
+        ]
+    );
+
+    panel.update_in(cx, |panel, window, cx| {
+        panel.dismiss_filter(&menu::Cancel, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src",
+            "        v util",
+            "              parse.rs  <== selected",
+            "          lib.rs",
+            "          main.rs",
+            "    > tests",
+            "      README.md",
+        ]
+    );
+}
+
 fn select_path(panel: &Entity<ProjectPanel>, path: impl AsRef<Path>, cx: &mut VisualTestContext) {
     let path = path.as_ref();
     panel.update(cx, |panel, cx| {
@@ -1,11 +1,15 @@
-use editor::{Bias, Editor, SelectionEffects, scroll::Autoscroll, styled_runs_for_code_label};
+use collections::HashSet;
+use editor::{
+    Bias, Editor, SelectionEffects, icon_for_symbol_kind, scroll::Autoscroll,
+    styled_runs_for_code_label,
+};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     App, Context, DismissEvent, Entity, HighlightStyle, ParentElement, StyledText, Task, TextStyle,
     WeakEntity, Window, relative, rems,
 };
 use ordered_float::OrderedFloat;
-use picker::{Picker, PickerDelegate};
+use picker::{Picker, PickerDelegate, PickerPreview, PickerPreviewSettings};
 use project::{Project, Symbol};
 use settings::Settings;
 use std::{borrow::Cow, cmp::Reverse, sync::Arc};
@@ -17,6 +21,7 @@ use workspace::{
 };
 
 pub fn init(cx: &mut App) {
+    PickerPreviewSettings::register(cx);
     cx.observe_new(
         |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
             workspace.register_action(
@@ -24,7 +29,7 @@ pub fn init(cx: &mut App) {
                     let project = workspace.project().clone();
                     let handle = cx.entity().downgrade();
                     workspace.toggle_modal(window, cx, move |window, cx| {
-                        let delegate = ProjectSymbolsDelegate::new(handle, project);
+                        let delegate = ProjectSymbolsDelegate::new(handle, project, cx);
                         Picker::uniform_list(delegate, window, cx).width(rems(34.))
                     })
                 },
@@ -45,13 +50,18 @@ pub struct ProjectSymbolsDelegate {
     external_match_candidates: Vec<StringMatchCandidate>,
     show_worktree_root_name: bool,
     matches: Vec<StringMatch>,
+    preview: Option<Entity<PickerPreview>>,
 }
 
 impl ProjectSymbolsDelegate {
-    fn new(workspace: WeakEntity<Workspace>, project: Entity<Project>) -> Self {
+    fn new(workspace: WeakEntity<Workspace>, project: Entity<Project>, cx: &mut App) -> Self {
+        let preview = PickerPreviewSettings::get_global(cx)
+            .project_symbols
+            .then(|| cx.new(|_| PickerPreview::new(project.clone())));
         Self {
             workspace,
             project,
+            preview,
             selected_match_index: 0,
             symbols: Default::default(),
             visible_match_candidates: Default::default(),
@@ -171,6 +181,36 @@ impl PickerDelegate for ProjectSymbolsDelegate {
         self.selected_match_index = ix;
     }
 
+    fn update_preview(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(preview) = self.preview.clone() else {
+            return;
+        };
+        let symbol = self
+            .matches
+            .get(self.selected_match_index)
+            .map(|mat| &self.symbols[mat.candidate_id]);
+        let target = symbol.map(|symbol| {
+            let rows = symbol.range.start.0.row..symbol.range.end.0.row;
+            (symbol.path.clone(), rows)
+        });
+
+        preview.update(cx, |preview, cx| match target {
+            Some((path, rows)) => preview.show_path(path, Some(rows), window, cx),
+            None => preview.clear(cx),
+        });
+    }
+
+    fn render_preview(
+        &self,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(self.preview.clone()?.into_any_element())
+    }
+
     fn update_matches(
         &mut self,
         query: String,
@@ -184,7 +224,16 @@ impl PickerDelegate for ProjectSymbolsDelegate {
             .update(cx, |project, cx| project.symbols(&query, cx));
         cx.spawn_in(window, async move |this, cx| {
             let symbols = symbols.await.log_err();
-            if let Some(symbols) = symbols {
+            if let Some(mut symbols) = symbols {
+                // Several language servers for the same language may report the same symbol.
+                let mut seen_symbols = HashSet::default();
+                symbols.retain(|symbol| {
+                    seen_symbols.insert((
+                        symbol.path.clone(),
+                        symbol.range.start,
+                        symbol.name.clone(),
+                    ))
+                });
                 this.update_in(cx, |this, window, cx| {
                     let delegate = &mut this.delegate;
                     let project = delegate.project.read(cx);
@@ -265,6 +314,11 @@ impl PickerDelegate for ProjectSymbolsDelegate {
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .toggle_state(selected)
+                .start_slot(
+                    Icon::new(icon_for_symbol_kind(symbol.kind))
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
                 .child(
                     v_flex()
                         .child(LabelLike::new().child(
@@ -378,7 +432,7 @@ mod tests {
         // Create the project symbols view.
         let symbols = cx.new_window_entity(|window, cx| {
             Picker::uniform_list(
-                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone()),
+                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone(), cx),
                 window,
                 cx,
             )
@@ -424,6 +478,103 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_project_symbols_from_multiple_servers(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "test.rs": "" }))
+            .await;
+
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let capabilities = lsp::ServerCapabilities {
+            workspace_symbol_provider: Some(OneOf::Left(true)),
+            ..Default::default()
+        };
+        let mut first_fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                name: "first-language-server",
+                capabilities: capabilities.clone(),
+                ..Default::default()
+            },
+        );
+        let mut second_fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                name: "second-language-server",
+                capabilities,
+                ..Default::default()
+            },
+        );
+
+        let _buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/dir/test.rs"), cx)
+            })
+            .await
+            .unwrap();
+
+        // Both servers report `one`, only the second one reports `two`.
+        let first_fake_server = first_fake_servers.next().await.unwrap();
+        first_fake_server.set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(
+            move |_, _| async move {
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![symbol(
+                    "one",
+                    path!("/dir/test.rs"),
+                )])))
+            },
+        );
+        let second_fake_server = second_fake_servers.next().await.unwrap();
+        second_fake_server.set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(
+            move |_, _| async move {
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![
+                    symbol("one", path!("/dir/test.rs")),
+                    symbol("two", path!("/dir/test.rs")),
+                ])))
+            },
+        );
+
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let symbols = cx.new_window_entity(|window, cx| {
+            Picker::uniform_list(
+                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone(), cx),
+                window,
+                cx,
+            )
+        });
+
+        symbols.update_in(cx, |p, window, cx| {
+            p.update_matches("o".to_string(), window, cx);
+        });
+        cx.run_until_parked();
+        symbols.read_with(cx, |symbols, _| {
+            let delegate = &symbols.delegate;
+            assert_eq!(delegate.symbols.len(), 2);
+            let mut names = delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.as_str())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            assert_eq!(names, ["one", "two"]);
+        });
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let store = SettingsStore::test(cx);
@@ -434,6 +585,7 @@ mod tests {
             Project::init_settings(cx);
             workspace::init_settings(cx);
             editor::init(cx);
+            PickerPreviewSettings::register(cx);
         });
     }
 
@@ -97,6 +97,10 @@ messages!(
     (GetDocumentSymbolsResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
     (GetNotifications, Foreground),
     (GetNotificationsResponse, Foreground),
     (GetCrashFiles, Background),
@@ -172,6 +176,9 @@ messages!(
     (LspExtClearFlycheck, Background),
     (MarkNotificationRead, Foreground),
     (MoveChannel, Foreground),
+    (MuteRoomParticipant, Foreground),
+    (MutedInRoom, Foreground),
+    (UpdateFocusStatus, Foreground),
     (ReorderChannel, Foreground),
     (LspQuery, Background),
     (LspQueryResponse, Background),
@@ -190,6 +197,8 @@ messages!(
     (PerformRename, Background),
     (PerformRenameResponse, Background),
     (Ping, Foreground),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
     (PrepareRename, Background),
     (PrepareRenameResponse, Background),
     (ProjectEntryResponse, Foreground),
@@ -207,6 +216,8 @@ messages!(
     (RemoveChannelMessage, Foreground),
     (RemoveContact, Foreground),
     (RemoveProjectCollaborator, Foreground),
+    (RemoveRoomParticipant, Foreground),
+    (RemovedFromRoom, Foreground),
     (RemoveWorktree, Foreground),
     (RenameChannel, Foreground),
     (RenameChannelResponse, Foreground),
@@ -223,6 +234,8 @@ messages!(
     (RefreshCodeLens, Background),
     (GetCodeLens, Background),
     (GetCodeLensResponse, Background),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
     (RespondToChannelInvite, Foreground),
     (RespondToContactRequest, Foreground),
     (RestartLanguageServers, Foreground),
@@ -358,6 +371,8 @@ request_messages!(
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
@@ -392,6 +407,7 @@ request_messages!(
     (OpenNewBuffer, OpenBufferResponse),
     (PerformRename, PerformRenameResponse),
     (Ping, Ack),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
     (PrepareRename, PrepareRenameResponse),
     (RefreshInlayHints, Ack),
     (RefreshCodeLens, Ack),
@@ -406,6 +422,7 @@ request_messages!(
     (RenameProjectEntry, ProjectEntryResponse),
     (ReorderChannel, Ack),
     (RequestContact, Ack),
+    (ResolveCodeLens, ResolveCodeLensResponse),
     (
         ResolveCompletionDocumentation,
         ResolveCompletionDocumentationResponse
@@ -440,6 +457,8 @@ request_messages!(
     (LspExtOpenDocs, LspExtOpenDocsResponse),
     (LspExtRunnables, LspExtRunnablesResponse),
     (SetRoomParticipantRole, Ack),
+    (RemoveRoomParticipant, Ack),
+    (MuteRoomParticipant, Ack),
     (BlameBuffer, BlameBufferResponse),
     (RejoinRemoteProjects, RejoinRemoteProjectsResponse),
     (LspQuery, Ack),
@@ -545,6 +564,8 @@ entity_messages!(
     GetDocumentHighlights,
     GetDocumentSymbols,
     GetHover,
+    GetIncomingCalls,
+    GetOutgoingCalls,
     GetProjectSymbols,
     GetReferences,
     GetSignatureHelp,
@@ -567,12 +588,14 @@ entity_messages!(
     OpenBufferForSymbol,
     OpenCommitMessageBuffer,
     PerformRename,
+    PrepareCallHierarchy,
     PrepareRename,
     RefreshInlayHints,
     RefreshCodeLens,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    ResolveCodeLens,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
     SaveBuffer,
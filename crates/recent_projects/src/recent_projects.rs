@@ -1,7 +1,6 @@
 pub mod disconnected_overlay;
 mod remote_connections;
 mod remote_servers;
-mod ssh_config;
 
 #[cfg(target_os = "windows")]
 mod wsl_picker;
@@ -1,9 +1,6 @@
-use crate::{
-    remote_connections::{
-        Connection, RemoteConnectionModal, RemoteConnectionPrompt, SshConnection,
-        SshConnectionHeader, SshSettings, connect, connect_over_ssh, open_remote_project,
-    },
-    ssh_config::parse_ssh_config_hosts,
+use crate::remote_connections::{
+    Connection, RemoteConnectionModal, RemoteConnectionPrompt, SshConnection, SshConnectionHeader,
+    SshSettings, connect, connect_over_ssh, open_remote_project,
 };
 use editor::Editor;
 use file_finder::OpenPathDelegate;
@@ -19,7 +16,7 @@ use picker::Picker;
 use project::{Fs, Project};
 use remote::{
     RemoteClient, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
-    remote_client::ConnectionIdentifier,
+    remote_client::ConnectionIdentifier, ssh_config::parse_ssh_config_hosts,
 };
 use settings::{
     RemoteSettingsContent, Settings as _, SettingsStore, SshProject, update_settings_file,
@@ -2,6 +2,7 @@ pub mod json_log;
 pub mod protocol;
 pub mod proxy;
 pub mod remote_client;
+pub mod ssh_config;
 mod transport;
 
 pub use remote_client::{
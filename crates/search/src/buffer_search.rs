@@ -10,7 +10,7 @@ use any_vec::AnyVec;
 use anyhow::Context as _;
 use collections::HashMap;
 use editor::{
-    DisplayPoint, Editor, EditorSettings,
+    DisplayPoint, Editor, EditorSettings, OccurrenceSearchOptions,
     actions::{Backtab, Tab},
 };
 use futures::channel::oneshot;
@@ -66,6 +66,9 @@ actions!(
         DeployReplace,
         /// Dismisses the search bar.
         Dismiss,
+        /// Dismisses the search bar, moving the cursor back to where it was when the search
+        /// bar was opened.
+        DismissAndRestoreCursor,
         /// Focuses back on the editor.
         FocusEditor
     ]
@@ -572,6 +575,11 @@ impl BufferSearchBar {
         registrar.register_handler(ForDeployed(|this, _: &Dismiss, window, cx| {
             this.dismiss(&Dismiss, window, cx);
         }));
+        registrar.register_handler(ForDeployed(
+            |this, action: &DismissAndRestoreCursor, window, cx| {
+                this.dismiss_and_restore_cursor(action, window, cx);
+            },
+        ));
 
         // register deploy buffer search for both search bar states, since we want to focus into the search bar
         // when the deploy action is triggered in the buffer.
@@ -700,6 +708,18 @@ impl BufferSearchBar {
         cx.notify();
     }
 
+    pub fn dismiss_and_restore_cursor(
+        &mut self,
+        _: &DismissAndRestoreCursor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(active_item) = self.active_searchable_item.as_ref() {
+            active_item.restore_search_origin(window, cx);
+        }
+        self.dismiss(&Dismiss, window, cx);
+    }
+
     pub fn deploy(&mut self, deploy: &Deploy, window: &mut Window, cx: &mut Context<Self>) -> bool {
         if self.show(window, cx) {
             if let Some(active_item) = self.active_searchable_item.as_mut() {
@@ -890,6 +910,12 @@ impl BufferSearchBar {
     ) {
         self.search_options.toggle(search_option);
         self.default_options = self.search_options;
+        // Selecting occurrences of the selection finds the same matches as buffer search.
+        OccurrenceSearchOptions {
+            case_sensitive: self.search_options.contains(SearchOptions::CASE_SENSITIVE),
+            whole_word: self.search_options.contains(SearchOptions::WHOLE_WORD),
+        }
+        .set(cx);
         drop(self.update_matches(false, false, window, cx));
         self.adjust_query_regex_language(cx);
         cx.notify();
@@ -2172,6 +2198,37 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_dismiss_and_restore_cursor(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+
+        search_bar
+            .update_in(cx, |search_bar, window, cx| {
+                search_bar.search("pattern", None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        search_bar.update_in(cx, |search_bar, window, cx| {
+            search_bar.activate_current_match(window, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.selections.display_ranges(cx),
+                [DisplayPoint::new(DisplayRow(2), 0)..DisplayPoint::new(DisplayRow(2), 7)]
+            );
+        });
+
+        search_bar.update_in(cx, |search_bar, window, cx| {
+            search_bar.dismiss_and_restore_cursor(&DismissAndRestoreCursor, window, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.selections.display_ranges(cx),
+                [DisplayPoint::zero()..DisplayPoint::zero()]
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_search_query_history(cx: &mut TestAppContext) {
         let (_editor, search_bar, cx) = init_test(cx);
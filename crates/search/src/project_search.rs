@@ -1,10 +1,12 @@
 use crate::{
     BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
     SearchOption, SearchOptions, SearchSource, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleIncludeTerminals, TogglePreserveCase,
+    ToggleRegex, ToggleReplace, ToggleWholeWord,
     buffer_search::Deploy,
     search_bar::{ActionButtonState, input_base_styles, render_action_button, render_text_input},
 };
+use any_vec::AnyVec;
 use anyhow::Context as _;
 use collections::HashMap;
 use editor::{
@@ -15,34 +17,41 @@ use editor::{
 };
 use futures::{StreamExt, stream::FuturesOrdered};
 use gpui::{
-    Action, AnyElement, AnyView, App, Axis, Context, Entity, EntityId, EventEmitter, FocusHandle,
-    Focusable, Global, Hsla, InteractiveElement, IntoElement, KeyContext, ParentElement, Point,
-    Render, SharedString, Styled, Subscription, Task, UpdateGlobal, WeakEntity, Window, actions,
-    div,
+    Action, AnyElement, AnyView, AnyWindowHandle, App, Axis, Context, Entity, EntityId,
+    EventEmitter, FocusHandle, Focusable, Global, Hsla, InteractiveElement, IntoElement,
+    KeyContext, ParentElement, Point, Render, SharedString, Styled, Subscription, Task,
+    UniformListScrollHandle, UpdateGlobal, WeakEntity, Window, actions, div, uniform_list,
 };
-use language::{Buffer, Language};
+use language::{Buffer, Language, ToPoint as _};
 use menu::Confirm;
 use project::{
     Project, ProjectPath,
     search::{SearchInputKind, SearchQuery},
     search_history::SearchHistoryCursor,
 };
+use schemars::JsonSchema;
+use serde::Deserialize;
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
     mem,
     ops::{Not, Range},
-    path::Path,
+    path::{Path, PathBuf},
     pin::pin,
     sync::Arc,
 };
-use ui::{IconButtonShape, KeyBinding, Toggleable, Tooltip, prelude::*, utils::SearchInputWidth};
+use task::TaskContext;
+use ui::{
+    IconButtonShape, KeyBinding, ListHeader, ListItem, Toggleable, Tooltip, prelude::*,
+    utils::SearchInputWidth,
+};
 use util::{ResultExt as _, paths::PathMatcher};
 use workspace::{
     DeploySearch, ItemNavHistory, NewSearch, ToolbarItemEvent, ToolbarItemLocation,
     ToolbarItemView, Workspace, WorkspaceId,
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle, SaveOptions},
-    searchable::{Direction, SearchableItem, SearchableItemHandle},
+    notifications::NotifyTaskExt as _,
+    searchable::{Direction, SearchableItem, SearchableItemHandle, WeakSearchableItemHandle},
 };
 
 actions!(
@@ -55,10 +64,21 @@ actions!(
         /// Moves to the next input field.
         NextField,
         /// Toggles the search filters panel.
-        ToggleFilters
+        ToggleFilters,
+        /// Opens the search results in a new buffer, one `path:line: text` line per matched line.
+        ExportResults
     ]
 );
 
+/// Spawns a task with the files containing search results appended to its arguments.
+#[derive(PartialEq, Clone, Deserialize, JsonSchema, Action)]
+#[action(namespace = project_search)]
+#[serde(deny_unknown_fields)]
+pub struct SpawnTaskWithMatchedFiles {
+    /// The label of the task to spawn.
+    pub task_name: String,
+}
+
 #[derive(Default)]
 struct ActiveSettings(HashMap<WeakEntity<Project>, ProjectSearchSettings>);
 
@@ -100,6 +120,12 @@ pub fn init(cx: &mut App) {
                 search_bar.toggle_replace(action, window, cx)
             },
         );
+        register_workspace_action(
+            workspace,
+            move |search_bar, action: &TogglePreserveCase, window, cx| {
+                search_bar.toggle_preserve_case(action, window, cx)
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &SelectPreviousMatch, window, cx| {
@@ -112,6 +138,16 @@ pub fn init(cx: &mut App) {
                 search_bar.select_next_match(action, window, cx)
             },
         );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ExportResults, window, cx| search_bar.export_results(window, cx),
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, action: &SpawnTaskWithMatchedFiles, window, cx| {
+                search_bar.spawn_task_with_matched_files(action, window, cx)
+            },
+        );
 
         // Only handle search_in_new if there is a search present
         register_workspace_action_for_present_search(workspace, |workspace, action, window, cx| {
@@ -215,11 +251,35 @@ pub struct ProjectSearchView {
     excluded_files_editor: Entity<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    preserve_case: bool,
     included_opened_only: bool,
     regex_language: Option<Arc<Language>>,
+    window_handle: AnyWindowHandle,
+    terminal_matches: Vec<TerminalMatches>,
+    /// The terminal and match index of each row in the list of terminal matches.
+    terminal_match_rows: Vec<(usize, usize)>,
+    terminal_matches_scroll_handle: UniformListScrollHandle,
+    pending_terminal_search: Option<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
+/// Matches found in a searchable item whose contents aren't backed by a
+/// project buffer, such as a terminal's scrollback.
+struct TerminalMatches {
+    item: Box<dyn WeakSearchableItemHandle>,
+    title: SharedString,
+    matches: AnyVec<dyn Send>,
+    previews: Vec<Option<String>>,
+}
+
+/// A line containing matches in the project search results.
+struct MatchedLine {
+    project_path: ProjectPath,
+    full_path: PathBuf,
+    row: u32,
+    text: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProjectSearchSettings {
     search_options: SearchOptions,
@@ -377,6 +437,76 @@ impl ProjectSearch {
         }));
         cx.notify();
     }
+
+    /// Returns the lines containing matches, in the order they appear in the results.
+    fn matched_lines(&self, cx: &App) -> Vec<MatchedLine> {
+        let snapshot = self.excerpts.read(cx).snapshot(cx);
+        let mut match_starts = self
+            .match_ranges
+            .iter()
+            .map(|range| range.start)
+            .collect::<Vec<_>>();
+        match_starts.sort_by(|a, b| a.cmp(b, &snapshot));
+
+        let mut lines = Vec::<MatchedLine>::new();
+        for match_start in match_starts {
+            let Some(buffer) = snapshot.buffer_for_excerpt(match_start.excerpt_id) else {
+                continue;
+            };
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            let project_path = ProjectPath::from_file(file.as_ref(), cx);
+            let row = match_start.text_anchor.to_point(buffer).row;
+            if lines
+                .last()
+                .is_some_and(|line| line.project_path == project_path && line.row == row)
+            {
+                continue;
+            }
+            let line_range =
+                language::Point::new(row, 0)..language::Point::new(row, buffer.line_len(row));
+            lines.push(MatchedLine {
+                project_path,
+                full_path: file.full_path(cx),
+                row,
+                text: buffer.text_for_range(line_range).collect(),
+            });
+        }
+        lines
+    }
+
+    /// Formats the lines containing matches the way `grep -n` prints them.
+    fn export_text(&self, cx: &App) -> String {
+        let include_worktree_root = self.project.read(cx).visible_worktrees(cx).count() > 1;
+        self.matched_lines(cx)
+            .into_iter()
+            .map(|line| {
+                let path = if include_worktree_root {
+                    line.full_path
+                } else {
+                    line.project_path.path.to_path_buf()
+                };
+                format!("{}:{}: {}\n", path.display(), line.row + 1, line.text)
+            })
+            .collect()
+    }
+
+    /// Returns the absolute paths of the files containing matches, in the order they appear in
+    /// the results.
+    fn matched_files(&self, cx: &App) -> Vec<PathBuf> {
+        let mut project_paths = self
+            .matched_lines(cx)
+            .into_iter()
+            .map(|line| line.project_path)
+            .collect::<Vec<_>>();
+        project_paths.dedup();
+        let project = self.project.read(cx);
+        project_paths
+            .iter()
+            .filter_map(|project_path| project.absolute_path(project_path, cx))
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -391,12 +521,21 @@ impl EventEmitter<ViewEvent> for ProjectSearchView {}
 
 impl Render for ProjectSearchView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        if self.has_matches() {
+        if self.has_matches() && self.terminal_matches.is_empty() {
             div()
                 .flex_1()
                 .size_full()
                 .track_focus(&self.focus_handle(cx))
                 .child(self.results_editor.clone())
+        } else if !self.terminal_matches.is_empty() {
+            v_flex()
+                .flex_1()
+                .size_full()
+                .track_focus(&self.focus_handle(cx))
+                .when(self.has_matches(), |this| {
+                    this.child(div().flex_1().min_h_0().child(self.results_editor.clone()))
+                })
+                .child(self.render_terminal_matches(cx))
         } else {
             let model = self.entity.read(cx);
             let has_no_results = model.no_results.unwrap_or(false);
@@ -700,7 +839,9 @@ impl ProjectSearchView {
 
         let query = self.entity.read(cx).active_query.clone();
         if let Some(query) = query {
-            let query = query.with_replacement(self.replacement(cx));
+            let query = query
+                .with_replacement(self.replacement(cx))
+                .with_preserve_case(self.preserve_case);
 
             // TODO: Do we need the clone here?
             let mat = self.entity.read(cx).match_ranges[active_index].clone();
@@ -724,7 +865,10 @@ impl ProjectSearchView {
         let Some(query) = self.entity.read(cx).active_query.as_ref() else {
             return;
         };
-        let query = query.clone().with_replacement(self.replacement(cx));
+        let query = query
+            .clone()
+            .with_replacement(self.replacement(cx))
+            .with_preserve_case(self.preserve_case);
 
         let match_ranges = self
             .entity
@@ -742,6 +886,80 @@ impl ProjectSearchView {
         });
     }
 
+    fn export_results(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let search = self.entity.read(cx);
+        if search.match_ranges.is_empty() {
+            return;
+        }
+        let text = search.export_text(cx);
+        let project = search.project.clone();
+        let create_buffer = project.update(cx, |project, cx| project.create_buffer(false, cx));
+        cx.spawn_in(window, async move |_, cx| {
+            let buffer = create_buffer.await?;
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_text(text, cx);
+            })?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let editor = cx.new(|cx| Editor::for_buffer(buffer, Some(project), window, cx));
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn spawn_task_with_matched_files(
+        &mut self,
+        action: &SpawnTaskWithMatchedFiles,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let search = self.entity.read(cx);
+        let matched_files = search.matched_files(cx);
+        if matched_files.is_empty() {
+            return;
+        }
+        let project = search.project.read(cx);
+        let Some(task_inventory) = project.task_store().read(cx).task_inventory().cloned() else {
+            return;
+        };
+        let worktree = project.visible_worktrees(cx).next().map(|worktree| {
+            let worktree = worktree.read(cx);
+            (worktree.id(), worktree.abs_path())
+        });
+        let tasks = task_inventory.read(cx).list_tasks(
+            None,
+            None,
+            worktree.as_ref().map(|(worktree_id, _)| *worktree_id),
+            cx,
+        );
+        let task_name = action.task_name.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let (task_source_kind, mut task) = tasks
+                .await
+                .into_iter()
+                .find(|(_, task)| task.label == task_name)
+                .with_context(|| format!("no task named {task_name:?}"))?;
+            task.args.extend(
+                matched_files
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned()),
+            );
+            let task_context = TaskContext {
+                cwd: worktree.map(|(_, abs_path)| abs_path.to_path_buf()),
+                ..TaskContext::default()
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.schedule_task(task_source_kind, &task, &task_context, false, window, cx)
+            })
+        })
+        .detach_and_notify_err(window, cx);
+    }
     pub fn new(
         workspace: WeakEntity<Workspace>,
         entity: Entity<ProjectSearch>,
@@ -771,7 +989,8 @@ impl ProjectSearchView {
             if let Some(active_query) = entity.active_query.as_ref() {
                 query_text = active_query.as_str().to_string();
                 replacement_text = active_query.replacement().map(ToOwned::to_owned);
-                options = SearchOptions::from_query(active_query);
+                options = SearchOptions::from_query(active_query)
+                    | (options & SearchOptions::INCLUDE_TERMINALS);
             }
         }
         subscriptions.push(cx.observe_in(&entity, window, |this, _, window, cx| {
@@ -898,8 +1117,14 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            preserve_case: false,
             included_opened_only: false,
             regex_language: None,
+            window_handle: window.window_handle(),
+            terminal_matches: Vec::new(),
+            terminal_match_rows: Vec::new(),
+            terminal_matches_scroll_handle: UniformListScrollHandle::new(),
+            pending_terminal_search: None,
             _subscriptions: subscriptions,
         };
         this.entity_changed(window, cx);
@@ -1135,10 +1360,175 @@ impl ProjectSearchView {
 
     fn search(&mut self, cx: &mut Context<Self>) {
         if let Some(query) = self.build_search_query(cx) {
+            self.search_terminals(query.clone(), cx);
             self.entity.update(cx, |model, cx| model.search(query, cx));
         }
     }
 
+    fn search_terminals(&mut self, query: SearchQuery, cx: &mut Context<Self>) {
+        self.terminal_matches.clear();
+        self.terminal_match_rows.clear();
+        if !self
+            .search_options
+            .contains(SearchOptions::INCLUDE_TERMINALS)
+        {
+            self.pending_terminal_search = None;
+            return;
+        }
+
+        let window_handle = self.window_handle;
+        let workspace = self.workspace.clone();
+        let query = Arc::new(query);
+        self.pending_terminal_search = Some(cx.spawn(async move |this, cx| {
+            let Some(searches) = cx
+                .update_window(window_handle, |_, window, cx| {
+                    let workspace = workspace.upgrade()?;
+                    let items = searchable_items_outside_project(workspace.read(cx), cx);
+                    Some(
+                        items
+                            .into_iter()
+                            .map(|item| {
+                                let search = item.find_matches(query.clone(), window, cx);
+                                (item, search)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            let mut results = Vec::new();
+            for (item, search) in searches {
+                let matches = search.await;
+                if !matches.is_empty() {
+                    results.push((item, matches));
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                this.terminal_matches = results
+                    .into_iter()
+                    .map(|(item, matches)| TerminalMatches {
+                        title: item.tab_content_text(0, cx),
+                        previews: item.match_previews(&matches, cx),
+                        item: item.downgrade(),
+                        matches,
+                    })
+                    .collect();
+                this.terminal_match_rows = this
+                    .terminal_matches
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(terminal_ix, terminal)| {
+                        (0..terminal.previews.len()).map(move |match_ix| (terminal_ix, match_ix))
+                    })
+                    .collect();
+                this.pending_terminal_search = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn activate_terminal_match(
+        &mut self,
+        terminal_ix: usize,
+        match_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(terminal) = self.terminal_matches.get(terminal_ix) else {
+            return;
+        };
+        let Some(item) = terminal.item.upgrade(cx) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let activated = workspace.update(cx, |workspace, cx| {
+            workspace.activate_item_in_any_pane(item.as_ref(), window, cx)
+        });
+        if activated {
+            item.update_matches(&terminal.matches, window, cx);
+            item.activate_match(match_ix, &terminal.matches, window, cx);
+        }
+    }
+
+    fn render_terminal_match(
+        &self,
+        row_ix: usize,
+        terminal_ix: usize,
+        match_ix: usize,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let terminal = &self.terminal_matches[terminal_ix];
+        ListItem::new(("terminal-match", row_ix))
+            .inset(true)
+            .spacing(ui::ListItemSpacing::Sparse)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(terminal.title.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(terminal.previews[match_ix].clone().unwrap_or_default())
+                            .single_line()
+                            .truncate(),
+                    ),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.activate_terminal_match(terminal_ix, match_ix, window, cx);
+            }))
+            .into_any_element()
+    }
+
+    fn render_terminal_matches(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let match_count = self.terminal_match_rows.len();
+        v_flex()
+            .w_full()
+            .map(|this| {
+                if self.has_matches() {
+                    this.h(rems(16.))
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border)
+                } else {
+                    this.flex_1().size_full()
+                }
+            })
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                ListHeader::new("Terminals").end_slot(
+                    Label::new(match_count.to_string())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .child(
+                // Scrollback can have many matches, so only the visible rows are rendered.
+                uniform_list(
+                    "project-search-terminal-matches",
+                    match_count,
+                    cx.processor(|this, range: Range<usize>, _window, cx| {
+                        range
+                            .map(|row_ix| {
+                                let (terminal_ix, match_ix) = this.terminal_match_rows[row_ix];
+                                this.render_terminal_match(row_ix, terminal_ix, match_ix, cx)
+                            })
+                            .collect()
+                    }),
+                )
+                .flex_1()
+                .track_scroll(self.terminal_matches_scroll_handle.clone()),
+            )
+    }
+
     pub fn search_query_text(&self, cx: &App) -> String {
         self.query_editor.read(cx).text(cx)
     }
@@ -1724,6 +2114,39 @@ impl ProjectSearchBar {
         }
     }
 
+    fn toggle_preserve_case(
+        &mut self,
+        _: &TogglePreserveCase,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(search) = &self.active_project_search {
+            search.update(cx, |this, cx| {
+                this.preserve_case = !this.preserve_case;
+                cx.notify();
+            });
+        }
+    }
+
+    fn export_results(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| search_view.export_results(window, cx));
+        }
+    }
+
+    fn spawn_task_with_matched_files(
+        &mut self,
+        action: &SpawnTaskWithMatchedFiles,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                search_view.spawn_task_with_matched_files(action, window, cx)
+            });
+        }
+    }
+
     fn toggle_filters(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
@@ -2096,6 +2519,14 @@ impl Render for ProjectSearchBar {
             let replace_actions = h_flex()
                 .min_w_64()
                 .gap_1()
+                .child(render_action_button(
+                    "project-search-replace-button",
+                    IconName::CaseSensitive,
+                    search.preserve_case.then_some(ActionButtonState::Toggled),
+                    "Preserve Case",
+                    &TogglePreserveCase,
+                    focus_handle.clone(),
+                ))
                 .child(render_action_button(
                     "project-search-replace-button",
                     IconName::ReplaceNext,
@@ -2153,6 +2584,11 @@ impl Render for ProjectSearchBar {
                     search.search_options,
                     SearchSource::Project(cx),
                     focus_handle.clone(),
+                ))
+                .child(SearchOption::IncludeTerminals.as_button(
+                    search.search_options,
+                    SearchSource::Project(cx),
+                    focus_handle.clone(),
                 ));
             h_flex()
                 .w_full()
@@ -2217,6 +2653,7 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, action, window, cx| {
                 this.toggle_replace(action, window, cx);
             }))
+            .on_action(cx.listener(Self::toggle_preserve_case))
             .on_action(cx.listener(|this, _: &ToggleWholeWord, window, cx| {
                 this.toggle_search_option(SearchOptions::WHOLE_WORD, window, cx);
             }))
@@ -2241,6 +2678,11 @@ impl Render for ProjectSearchBar {
                 this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, window, cx| {
                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, window, cx);
                 }))
+                .on_action(cx.listener(
+                    |this, _: &ToggleIncludeTerminals, window, cx| {
+                        this.toggle_search_option(SearchOptions::INCLUDE_TERMINALS, window, cx);
+                    },
+                ))
             })
             .on_action(cx.listener(Self::select_next_match))
             .on_action(cx.listener(Self::select_prev_match))
@@ -2328,6 +2770,36 @@ fn register_workspace_action_for_present_search<A: Action>(
     });
 }
 
+/// Collects the open items, including those in dock panels, whose contents
+/// project search should look through alongside the project's files.
+fn searchable_items_outside_project(
+    workspace: &Workspace,
+    cx: &App,
+) -> Vec<Box<dyn SearchableItemHandle>> {
+    let dock_panes = workspace
+        .all_docks()
+        .into_iter()
+        .flat_map(|dock| {
+            dock.read(cx)
+                .panels()
+                .filter_map(|panel| panel.pane(cx))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    workspace
+        .panes()
+        .iter()
+        .chain(dock_panes.iter())
+        .flat_map(|pane| {
+            pane.read(cx)
+                .items()
+                .filter_map(|item| item.to_searchable_item_handle(cx))
+                .collect::<Vec<_>>()
+        })
+        .filter(|item| item.include_in_project_search(cx))
+        .collect()
+}
+
 #[cfg(any(test, feature = "test-support"))]
 pub fn perform_project_search(
     search_view: &Entity<ProjectSearchView>,
@@ -2355,7 +2827,52 @@ pub mod tests {
     use serde_json::json;
     use settings::SettingsStore;
     use util::path;
-    use workspace::DeploySearch;
+    use workspace::{DeploySearch, searchable::SearchEvent};
+
+    #[gpui::test]
+    async fn test_project_search_export(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "// Three\nconst THREE: usize = one::ONE + two::TWO;",
+                "four.rs": "const FOUR: usize = 4;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|window, cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), window, cx, None)
+        });
+
+        perform_search(search_view, "ONE", cx);
+        search.read_with(cx, |search, cx| {
+            assert_eq!(
+                search.export_text(cx),
+                [
+                    "one.rs:1: const ONE: usize = 1;\n",
+                    "three.rs:2: const THREE: usize = one::ONE + two::TWO;\n",
+                    "two.rs:1: const TWO: usize = one::ONE + one::ONE;\n",
+                ]
+                .concat()
+            );
+            assert_eq!(
+                search.matched_files(cx),
+                [
+                    PathBuf::from(path!("/dir/one.rs")),
+                    PathBuf::from(path!("/dir/three.rs")),
+                    PathBuf::from(path!("/dir/two.rs")),
+                ]
+            );
+        });
+    }
 
     #[gpui::test]
     async fn test_project_search(cx: &mut TestAppContext) {
@@ -4146,6 +4663,192 @@ pub mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_project_search_includes_terminals(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let search = cx.new(|cx| ProjectSearch::new(project.clone(), cx));
+        let (terminal, search_view) = window
+            .update(cx, |workspace, window, cx| {
+                let terminal = cx.new(|cx| TestTerminal {
+                    text: "$ cargo build\nwarning: ONE is never used\n$ ".into(),
+                    focus_handle: cx.focus_handle(),
+                    activated_match: None,
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(terminal.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                let search_view = cx.new(|cx| {
+                    ProjectSearchView::new(workspace.weak_handle(), search, window, cx, None)
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(search_view.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                (terminal, search_view)
+            })
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(*window.deref(), cx);
+
+        search_view.update_in(cx, |search_view, window, cx| {
+            search_view.toggle_search_option(SearchOptions::INCLUDE_TERMINALS, cx);
+            search_view.query_editor.update(cx, |query_editor, cx| {
+                query_editor.set_text("ONE", window, cx)
+            });
+            search_view.search(cx);
+        });
+        cx.run_until_parked();
+
+        search_view.update_in(cx, |search_view, window, cx| {
+            assert_eq!(search_view.terminal_match_rows, [(0, 0)]);
+            assert_eq!(
+                search_view.terminal_matches[0].previews,
+                [Some("warning: ONE is never used".to_string())]
+            );
+            search_view.activate_terminal_match(0, 0, window, cx);
+        });
+        terminal.read_with(cx, |terminal, _| {
+            assert_eq!(terminal.activated_match, Some(0))
+        });
+
+        // The results don't keep closed terminals alive.
+        window
+            .update(cx, |workspace, window, cx| {
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.remove_item(terminal.entity_id(), false, false, window, cx)
+                });
+            })
+            .unwrap();
+        let weak_terminal = terminal.downgrade();
+        drop(terminal);
+        cx.run_until_parked();
+        assert!(weak_terminal.upgrade().is_none());
+        search_view.update_in(cx, |search_view, window, cx| {
+            search_view.activate_terminal_match(0, 0, window, cx);
+        });
+    }
+
+    /// A searchable item whose contents aren't in any project buffer, like a terminal.
+    struct TestTerminal {
+        text: String,
+        focus_handle: FocusHandle,
+        activated_match: Option<usize>,
+    }
+
+    impl EventEmitter<()> for TestTerminal {}
+
+    impl EventEmitter<SearchEvent> for TestTerminal {}
+
+    impl Focusable for TestTerminal {
+        fn focus_handle(&self, _: &App) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestTerminal {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    impl Item for TestTerminal {
+        type Event = ();
+
+        fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+            "Terminal".into()
+        }
+
+        fn as_searchable(&self, handle: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+            Some(Box::new(handle.clone()))
+        }
+    }
+
+    impl SearchableItem for TestTerminal {
+        type Match = Range<usize>;
+
+        fn clear_matches(&mut self, _: &mut Window, _: &mut Context<Self>) {}
+
+        fn update_matches(&mut self, _: &[Range<usize>], _: &mut Window, _: &mut Context<Self>) {}
+
+        fn query_suggestion(&mut self, _: &mut Window, _: &mut Context<Self>) -> String {
+            String::new()
+        }
+
+        fn activate_match(
+            &mut self,
+            index: usize,
+            _: &[Range<usize>],
+            _: &mut Window,
+            _: &mut Context<Self>,
+        ) {
+            self.activated_match = Some(index);
+        }
+
+        fn select_matches(&mut self, _: &[Range<usize>], _: &mut Window, _: &mut Context<Self>) {}
+
+        fn replace(
+            &mut self,
+            _: &Range<usize>,
+            _: &SearchQuery,
+            _: &mut Window,
+            _: &mut Context<Self>,
+        ) {
+        }
+
+        fn find_matches(
+            &mut self,
+            query: Arc<SearchQuery>,
+            _: &mut Window,
+            _: &mut Context<Self>,
+        ) -> Task<Vec<Range<usize>>> {
+            Task::ready(
+                self.text
+                    .match_indices(query.as_str())
+                    .map(|(ix, text)| ix..ix + text.len())
+                    .collect(),
+            )
+        }
+
+        fn active_match_index(
+            &mut self,
+            _: Direction,
+            _: &[Range<usize>],
+            _: &mut Window,
+            _: &mut Context<Self>,
+        ) -> Option<usize> {
+            None
+        }
+
+        fn include_in_project_search(&self, _: &App) -> bool {
+            true
+        }
+
+        fn match_preview(&self, mat: &Range<usize>, _: &App) -> Option<String> {
+            let start = self.text[..mat.start].rfind('\n').map_or(0, |ix| ix + 1);
+            let end = self.text[mat.end..]
+                .find('\n')
+                .map_or(self.text.len(), |ix| mat.end + ix);
+            Some(self.text[start..end].to_string())
+        }
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
@@ -35,10 +35,14 @@ actions!(
         ToggleCaseSensitive,
         /// Toggles searching in ignored files.
         ToggleIncludeIgnored,
+        /// Toggles searching in terminal scrollback.
+        ToggleIncludeTerminals,
         /// Toggles regular expression mode.
         ToggleRegex,
         /// Toggles the replace interface.
         ToggleReplace,
+        /// Toggles preserving the case of the text being replaced.
+        TogglePreserveCase,
         /// Toggles searching within selection only.
         ToggleSelection,
         /// Selects the next search match.
@@ -71,6 +75,7 @@ bitflags! {
         const ONE_MATCH_PER_LINE = 1 << SearchOption::OneMatchPerLine as u8;
         /// If set, reverse direction when finding the active match
         const BACKWARDS = 1 << SearchOption::Backwards as u8;
+        const INCLUDE_TERMINALS = 1 << SearchOption::IncludeTerminals as u8;
     }
 }
 
@@ -83,6 +88,7 @@ pub enum SearchOption {
     Regex,
     OneMatchPerLine,
     Backwards,
+    IncludeTerminals,
 }
 
 pub(crate) enum SearchSource<'a, 'b> {
@@ -103,6 +109,7 @@ impl SearchOption {
             SearchOption::Regex => "Use Regular Expressions",
             SearchOption::OneMatchPerLine => "One Match Per Line",
             SearchOption::Backwards => "Search Backwards",
+            SearchOption::IncludeTerminals => "Also search terminal scrollback",
         }
     }
 
@@ -112,6 +119,7 @@ impl SearchOption {
             SearchOption::CaseSensitive => ui::IconName::CaseSensitive,
             SearchOption::IncludeIgnored => ui::IconName::Sliders,
             SearchOption::Regex => ui::IconName::Regex,
+            SearchOption::IncludeTerminals => ui::IconName::Terminal,
             _ => panic!("{self:?} is not a named SearchOption"),
         }
     }
@@ -122,6 +130,7 @@ impl SearchOption {
             SearchOption::CaseSensitive => &ToggleCaseSensitive,
             SearchOption::IncludeIgnored => &ToggleIncludeIgnored,
             SearchOption::Regex => &ToggleRegex,
+            SearchOption::IncludeTerminals => &ToggleIncludeTerminals,
             _ => panic!("{self:?} is not a toggle action"),
         }
     }
@@ -54,6 +54,9 @@ pub struct SettingsContent {
     /// Settings related to the file finder.
     pub file_finder: Option<FileFinderSettingsContent>,
 
+    /// Settings related to the preview pane shown below picker results.
+    pub picker_preview: Option<PickerPreviewSettingsContent>,
+
     pub git_panel: Option<GitPanelSettingsContent>,
 
     pub tabs: Option<ItemSettingsContent>,
@@ -67,6 +70,10 @@ pub struct SettingsContent {
     /// Configuration of audio in Zed.
     pub audio: Option<AudioSettingsContent>,
 
+    /// Configuration of the local automation server, which lets external tools
+    /// script Zed.
+    pub automation: Option<AutomationSettingsContent>,
+
     /// Whether or not to automatically check for updates.
     ///
     /// Default: true
@@ -87,6 +94,9 @@ pub struct SettingsContent {
     /// Configuration for Diagnostics-related features.
     pub diagnostics: Option<DiagnosticsSettingsContent>,
 
+    /// Configuration for focus sessions, during which collaboration notifications are held back.
+    pub focus_timer: Option<FocusTimerSettingsContent>,
+
     /// Configuration for Git-related features
     pub git: Option<GitSettings>,
 
@@ -122,6 +132,9 @@ pub struct SettingsContent {
     /// Configuration for the Message Editor
     pub message_editor: Option<MessageEditorSettings>,
 
+    /// Configuration for opening large files with expensive features turned off.
+    pub large_file_mode: Option<LargeFileModeSettingsContent>,
+
     /// Configuration for Node-related features
     pub node: Option<NodeBinarySettings>,
 
@@ -143,6 +156,9 @@ pub struct SettingsContent {
 
     pub title_bar: Option<TitleBarSettingsContent>,
 
+    /// Configuration of the local, session-long usage statistics.
+    pub usage_stats: Option<UsageStatsSettingsContent>,
+
     /// Whether or not to enable Vim mode.
     ///
     /// Default: false
@@ -276,6 +292,12 @@ pub struct TitleBarSettingsContent {
     ///
     /// Default: false
     pub show_menus: Option<bool>,
+    /// Whether to use the platform's native title bar and window decorations
+    /// instead of Zed's custom ones. Only supported on macOS and Linux, and only
+    /// applies to newly opened windows.
+    ///
+    /// Default: false
+    pub native: Option<bool>,
 }
 
 #[derive(Copy, Clone, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom, Debug)]
@@ -308,6 +330,18 @@ pub struct AudioSettingsContent {
     pub control_output_volume: Option<bool>,
 }
 
+/// Configuration of the local automation server.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug)]
+pub struct AutomationSettingsContent {
+    /// Whether to listen for JSON-RPC requests on a Unix socket in Zed's data
+    /// directory. Clients authenticate with a token that's written next to the
+    /// socket, readable only by the current user.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+}
+
 /// Control what info is collected by Zed.
 #[skip_serializing_none]
 #[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, MergeFrom)]
@@ -322,6 +356,17 @@ pub struct TelemetrySettingsContent {
     pub metrics: Option<bool>,
 }
 
+/// Control the usage statistics Zed collects locally, without sending them anywhere.
+#[skip_serializing_none]
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, MergeFrom)]
+pub struct UsageStatsSettingsContent {
+    /// Count the actions invoked and the time spent per language and project
+    /// during the session, for the `zed: show usage stats` view.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+}
+
 #[skip_serializing_none]
 #[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone, MergeFrom)]
 pub struct DebuggerSettingsContent {
@@ -500,6 +545,25 @@ pub struct NotificationPanelSettingsContent {
     pub default_width: Option<f32>,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct FocusTimerSettingsContent {
+    /// Whether to show the focus timer button in the status bar when no focus
+    /// session is running.
+    ///
+    /// Default: false
+    pub button: Option<bool>,
+    /// How long a focus session lasts, in minutes, between 1 and 1440.
+    ///
+    /// Default: 25
+    pub duration_minutes: Option<u64>,
+    /// Whether to hold back incoming calls, shared project invitations and
+    /// notification toasts while a focus session is running.
+    ///
+    /// Default: true
+    pub suppress_notifications: Option<bool>,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct PanelSettingsContent {
@@ -572,6 +636,23 @@ pub enum FileFinderWidthContent {
     Full,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct PickerPreviewSettingsContent {
+    /// Whether to preview the highlighted file in the file finder.
+    ///
+    /// Default: false
+    pub file_finder: Option<bool>,
+    /// Whether to preview the highlighted symbol in the project symbols picker.
+    ///
+    /// Default: false
+    pub project_symbols: Option<bool>,
+    /// Whether to preview the highlighted item in the outline picker.
+    ///
+    /// Default: false
+    pub outline: Option<bool>,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Debug, JsonSchema, MergeFrom)]
 pub struct VimSettingsContent {
@@ -187,6 +187,24 @@ pub struct EditorSettingsContent {
     /// Drag and drop related settings
     pub drag_and_drop_selection: Option<DragAndDropSelectionContent>,
 
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
+
+    /// How the cursors and selections of collaborators are shown.
+    pub collaborator_cursors: Option<CollaboratorCursorsContent>,
+
+    /// Whether to show the code lenses of language servers, such as reference
+    /// counts, above the lines they belong to.
+    ///
+    /// Default: false
+    pub code_lens: Option<bool>,
+
+    /// Which brackets to color by their nesting depth, using the accent
+    /// colors of the theme.
+    ///
+    /// Default: off
+    pub colorize_brackets: Option<ColorizeBrackets>,
+
     /// How to render LSP `textDocument/documentColor` colors in the editor.
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
@@ -303,6 +321,13 @@ pub struct MinimapContent {
     ///
     /// Default: 80
     pub max_width_columns: Option<num::NonZeroU32>,
+
+    /// Whether to show git diff, search result and diagnostic markers along
+    /// the side of the minimap. The kinds of markers shown follow the
+    /// `scrollbar` settings.
+    ///
+    /// Default: true
+    pub markers: Option<bool>,
 }
 
 /// Forcefully enable or disable the scrollbar for each axis
@@ -388,6 +413,23 @@ pub enum SeedQuerySetting {
     Never,
 }
 
+/// Which brackets to color by their nesting depth.
+///
+/// Default: off
+#[derive(
+    Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorizeBrackets {
+    /// Don't color brackets.
+    #[default]
+    Off,
+    /// Color all bracket pairs.
+    All,
+    /// Only color the innermost bracket pair enclosing the cursor.
+    Enclosing,
+}
+
 /// What to do when multibuffer is double clicked in some of its excerpts (parts of singleton buffers).
 #[derive(
     Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom,
@@ -591,6 +633,35 @@ pub struct DragAndDropSelectionContent {
     pub delay: Option<u64>,
 }
 
+/// Whether to pin the headers of the scopes enclosing the top of the editor.
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// When true, the first lines of the functions, classes and other outline
+    /// items that enclose the topmost visible line stay pinned to the top of
+    /// the editor.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+}
+
+/// How the cursors and selections of collaborators are shown.
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct CollaboratorCursorsContent {
+    /// Whether to highlight the text selected by collaborators, in addition to
+    /// their cursors.
+    ///
+    /// Default: true
+    pub show_selections: Option<bool>,
+
+    /// The number of milliseconds after which the cursors of collaborators who
+    /// stopped moving them fade out, or 0 to never fade them.
+    ///
+    /// Default: 10000
+    pub fade_after_ms: Option<u64>,
+}
+
 /// When to show the minimap in the editor.
 ///
 /// Default: never
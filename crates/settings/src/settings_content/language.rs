@@ -30,8 +30,39 @@ pub struct AllLanguageSettingsContent {
     pub languages: LanguageToSettingsMap,
     /// Settings for associating file extensions and filenames
     /// with languages.
+    ///
+    /// Either maps a language to globs, like `{"C++": ["c", "*.dev"]}`,
+    /// or a glob to a language, like `{"*.gohtml": "HTML"}`.
     #[serde(default)]
-    pub file_types: HashMap<Arc<str>, ExtendingVec<String>>,
+    pub file_types: HashMap<Arc<str>, FileTypeAssociation>,
+}
+
+/// An entry of the `file_types` setting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FileTypeAssociation {
+    /// The globs of the files in the language the entry is keyed by.
+    Globs(ExtendingVec<String>),
+    /// The language of the files matching the glob the entry is keyed by.
+    Language(String),
+}
+
+impl From<Vec<String>> for FileTypeAssociation {
+    fn from(globs: Vec<String>) -> Self {
+        FileTypeAssociation::Globs(globs.into())
+    }
+}
+
+impl merge_from::MergeFrom for FileTypeAssociation {
+    fn merge_from(&mut self, other: Option<&Self>) {
+        let Some(other) = other else { return };
+        match (self, other) {
+            (FileTypeAssociation::Globs(globs), FileTypeAssociation::Globs(other)) => {
+                globs.merge_from(Some(other))
+            }
+            (this, other) => *this = other.clone(),
+        }
+    }
 }
 
 fn merge_option<T: merge_from::MergeFrom + Clone>(this: &mut Option<T>, other: Option<&T>) {
@@ -160,6 +191,9 @@ pub enum SoftWrap {
     PreferredLineLength,
     /// Soft wrap line at the preferred line length or the editor width (whichever is smaller).
     Bounded,
+    /// Soft wrap lines at the preferred line length, indenting the continuation lines by
+    /// `soft_wrap_hanging_indent` columns more than the line they continue.
+    HangingIndent,
 }
 
 /// The settings for a particular language.
@@ -175,6 +209,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: false
     pub hard_tabs: Option<bool>,
+    /// Whether to infer the indentation of files from their contents when they're opened,
+    /// using it instead of `tab_size` and `hard_tabs`.
+    ///
+    /// Default: true
+    pub detect_indentation: Option<bool>,
     /// How to soft-wrap long lines of text.
     ///
     /// Default: none
@@ -184,6 +223,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: 80
     pub preferred_line_length: Option<u32>,
+    /// How many columns further than the line they continue to indent soft-wrapped lines,
+    /// for buffers where soft-wrap is set to `hanging_indent`.
+    ///
+    /// Default: 4
+    pub soft_wrap_hanging_indent: Option<u32>,
     /// Whether to show wrap guides in the editor. Setting this to true will
     /// show a guide at the 'preferred_line_length' value if softwrap is set to
     /// 'preferred_line_length', and will show any additional guides as specified
@@ -197,6 +241,13 @@ pub struct LanguageSettingsContent {
     pub wrap_guides: Option<Vec<usize>>,
     /// Indent guide related settings.
     pub indent_guides: Option<IndentGuideSettingsContent>,
+    /// Reading mode related settings.
+    pub reading_mode: Option<ReadingModeSettingsContent>,
+    /// Rules pairing files with their related files, such as sources with
+    /// their headers or tests, for `editor: toggle related file`.
+    ///
+    /// Default: []
+    pub related_files: Option<Vec<RelatedFileRule>>,
     /// Whether or not to perform a buffer format before saving.
     ///
     /// Default: on
@@ -206,6 +257,12 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub remove_trailing_whitespace_on_save: Option<bool>,
+    /// Whether to leave the trailing whitespace on the lines with a cursor alone
+    /// when removing trailing whitespace on save, so that saving while typing
+    /// doesn't remove a space that was just typed.
+    ///
+    /// Default: false
+    pub keep_trailing_whitespace_on_cursor_line: Option<bool>,
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     ///
@@ -265,6 +322,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub extend_comment_on_newline: Option<bool>,
+    /// Whether to tint the background of regions where this language is embedded
+    /// in another one, such as CSS in an HTML `<style>` tag.
+    ///
+    /// Default: false
+    pub tint_injections: Option<bool>,
     /// Inlay hint related settings.
     pub inlay_hints: Option<InlayHintSettingsContent>,
     /// Whether to automatically type closing characters for you. For example,
@@ -290,11 +352,21 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_on_type_format: Option<bool>,
-    /// Which code actions to run on save after the formatter.
+    /// Which code actions to run on save alongside the formatter.
     /// These are not run if formatting is off.
     ///
     /// Default: {} (or {"source.organizeImports": true} for Go).
     pub code_actions_on_format: Option<HashMap<String, bool>>,
+    /// Whether to run the `code_actions_on_format` before or after the formatter.
+    ///
+    /// Default: before
+    pub code_actions_on_format_order: Option<CodeActionsOnFormatOrder>,
+    /// How long to wait for a language server to provide, resolve or execute the
+    /// `code_actions_on_format`, after which they are skipped so that saving doesn't hang.
+    /// When set to 0, waits indefinitely.
+    ///
+    /// Default: 2000
+    pub code_actions_on_format_timeout_ms: Option<u64>,
     /// Whether to perform linked edits of associated ranges, if the language server supports it.
     /// For example, when editing opening <html> tag, the contents of the closing </html> tag will be edited as well.
     ///
@@ -324,6 +396,8 @@ pub struct LanguageSettingsContent {
     pub show_completion_documentation: Option<bool>,
     /// Controls how completions are processed for this language.
     pub completions: Option<CompletionSettingsContent>,
+    /// Controls how the words in this language are spell checked.
+    pub spell_check: Option<SpellCheckSettingsContent>,
     /// Preferred debuggers for this language.
     ///
     /// Default: []
@@ -502,6 +576,36 @@ pub struct CompletionSettingsContent {
     ///
     /// Default: "replace_suffix"
     pub lsp_insert_mode: Option<LspInsertMode>,
+    /// Whether to accept a completion automatically once typing narrows the
+    /// completions menu down to that single completion.
+    ///
+    /// Default: false
+    pub auto_confirm_single_match: Option<bool>,
+}
+
+/// Controls how the words in a language are spell checked.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SpellCheckSettingsContent {
+    /// Whether to underline misspelled words and suggest corrections for them.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Whether to check all of the text, rather than only comments and strings.
+    ///
+    /// Default: false
+    pub check_all_text: Option<bool>,
+    /// Paths to the word lists of correctly spelled words, with one word per line.
+    /// Hunspell `.dic` files can be used too, and their prefixes and suffixes are read from
+    /// the `.aff` file next to them.
+    ///
+    /// Default: []
+    pub dictionaries: Option<Vec<String>>,
+    /// Additional words that are spelled correctly.
+    ///
+    /// Default: []
+    pub words: Option<Vec<String>>,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
@@ -774,6 +878,19 @@ impl AsRef<[Formatter]> for FormatterList {
     }
 }
 
+/// Controls when the `code_actions_on_format` run relative to the formatter.
+#[derive(
+    Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeActionsOnFormatOrder {
+    /// Run the code actions before the formatter, so that it formats their edits.
+    #[default]
+    Before,
+    /// Run the code actions after the formatter.
+    After,
+}
+
 /// Controls which formatter should be used when formatting code. If there are multiple formatters, they are executed in the order of declaration.
 #[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
 #[serde(rename_all = "snake_case")]
@@ -820,6 +937,42 @@ pub struct IndentGuideSettingsContent {
     pub background_coloring: Option<IndentGuideBackgroundColoring>,
 }
 
+/// The settings for reading mode, a prose-focused presentation of the editor.
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct ReadingModeSettingsContent {
+    /// Whether to present buffers in reading mode, which soft wraps lines at
+    /// the measure, hides the gutter and shows a word count in the status bar.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The column at which to soft wrap lines in reading mode.
+    ///
+    /// Default: 72
+    pub measure: Option<u32>,
+    /// Whether to keep the line with the cursor vertically centered in reading mode.
+    ///
+    /// Default: false
+    pub typewriter_scrolling: Option<bool>,
+}
+
+/// A rule pairing files matching one glob pattern with files matching another.
+///
+/// Each pattern contains a single `*`, which stands for the part of the path
+/// shared by both files. When either pattern contains a `/`, both are matched
+/// against the path relative to the worktree root. Otherwise they are matched
+/// against the file name, and related files live in the same directory.
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct RelatedFileRule {
+    /// The pattern matching source files, e.g. `*.c`.
+    pub source: String,
+    /// The pattern matching the related files, e.g. `*.h`.
+    pub related: String,
+    /// The text to insert into the related file when it has to be created.
+    pub template: Option<String>,
+}
+
 /// The task settings for a particular language.
 #[skip_serializing_none]
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize, JsonSchema, MergeFrom)]
@@ -836,6 +989,12 @@ pub struct LanguageTaskSettingsContent {
     /// * Zed task from either of the task config file
     /// * Zed task from history (e.g. one-off task was spawned before)
     pub prefer_lsp: Option<bool>,
+    /// The interpreter that `task: quick eval` runs the selection or buffer with,
+    /// as a command followed by its arguments, e.g. `["python3"]`. The code is
+    /// written to a temporary file, whose path is passed as the last argument.
+    ///
+    /// Default: []
+    pub interpreter: Option<Vec<String>>,
 }
 
 /// Map from language name to settings.
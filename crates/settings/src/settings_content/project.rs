@@ -45,6 +45,32 @@ pub struct ProjectSettingsContent {
 
     /// The list of custom Git hosting providers.
     pub git_hosting_providers: Option<ExtendingVec<GitHostingProviderConfig>>,
+
+    /// Tasks to run after saving files matching a glob, e.g. to regenerate code
+    /// or run a linter. Only read from the user settings.
+    ///
+    /// Default: []
+    pub tasks_on_save: Option<Vec<TaskOnSaveRule>>,
+}
+
+/// A task to run after saving a file that matches `glob`.
+///
+/// Either `task` or `command` has to be set. Task variables, such as `$ZED_FILE`,
+/// refer to the saved file.
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct TaskOnSaveRule {
+    /// The pattern matched against the saved file's path relative to its worktree
+    /// root, e.g. `**/*.proto`.
+    pub glob: String,
+    /// The label of a task defined in a `tasks.json` file.
+    pub task: Option<String>,
+    /// A command to run in a terminal when no `task` is given.
+    pub command: Option<String>,
+    /// How long to wait after the last matching save before running, in milliseconds.
+    ///
+    /// Default: 500
+    pub debounce_ms: Option<u64>,
 }
 
 #[skip_serializing_none]
@@ -56,6 +82,13 @@ pub struct WorktreeSettingsContent {
     /// Default: none
     pub project_name: Option<String>,
 
+    /// The color used to tint the title bar and status bar of this project's
+    /// windows, so that they are easier to tell apart. Either a hex color such as
+    /// `"#3b82f6"`, or `"auto"` to derive a color from the project's path.
+    ///
+    /// Default: none
+    pub project_color: Option<String>,
+
     /// Completely ignore files matching globs from `file_scan_exclusions`. Overrides
     /// `file_scan_inclusions`.
     ///
@@ -130,6 +163,10 @@ pub struct BinarySettings {
 pub struct FetchSettings {
     // Whether to consider pre-releases for fetching
     pub pre_release: Option<bool>,
+    /// A version of the language server to download and keep using instead of
+    /// the latest one, e.g. `"2025-08-25"` for rust-analyzer or `"1.1.404"`
+    /// for pyright.
+    pub version: Option<String>,
 }
 
 /// Common language server settings.
@@ -163,6 +200,32 @@ pub struct SessionSettingsContent {
     ///
     /// Default: true
     pub restore_unsaved_buffers: Option<bool>,
+    /// Whether or not to restore the undo history of files on restart.
+    ///
+    /// The history is discarded if the file changed on disk in the meantime.
+    ///
+    /// Default: true
+    pub restore_undo_history: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(
+    Default, Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema, MergeFrom,
+)]
+pub struct LargeFileModeSettingsContent {
+    /// Whether to open large files with syntax highlighting, language servers,
+    /// soft wrap and inlay hints turned off.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// Files of at least this many bytes are opened in large file mode.
+    ///
+    /// Default: 20000000
+    pub file_size_threshold: Option<u64>,
+    /// Files with at least this many lines are opened in large file mode.
+    ///
+    /// Default: 500000
+    pub line_count_threshold: Option<u32>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, MergeFrom, Debug)]
@@ -380,6 +443,42 @@ pub struct InlineDiagnosticsSettingsContent {
     pub min_column: Option<u32>,
 
     pub max_severity: Option<DiagnosticSeverityContent>,
+    /// Which severities of diagnostics to show inline, on top of `max_severity`.
+    pub severities: Option<InlineDiagnosticsSeveritiesContent>,
+    /// Whether to show inline diagnostics as dimmed text, rather than in the
+    /// color of their severity over a tinted background.
+    ///
+    /// Default: false
+    pub dimmed: Option<bool>,
+    /// The maximum number of characters of a diagnostic message to show inline.
+    /// Longer messages are truncated with an ellipsis. Only the first line of a
+    /// message is ever shown.
+    ///
+    /// Default: null
+    pub max_length: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom, Eq,
+)]
+pub struct InlineDiagnosticsSeveritiesContent {
+    /// Whether to show errors inline.
+    ///
+    /// Default: true
+    pub error: Option<bool>,
+    /// Whether to show warnings inline.
+    ///
+    /// Default: true
+    pub warning: Option<bool>,
+    /// Whether to show information diagnostics inline.
+    ///
+    /// Default: true
+    pub info: Option<bool>,
+    /// Whether to show hints inline.
+    ///
+    /// Default: true
+    pub hint: Option<bool>,
 }
 
 #[skip_serializing_none]
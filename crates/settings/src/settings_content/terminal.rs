@@ -106,6 +106,16 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// The number of lines to keep in the scrollback history, or "spill_to_file" to
+    /// keep all of the output. In that mode, once the in-memory history is full, its
+    /// oldest lines are moved to a temporary file on disk, so memory stays bounded.
+    /// The terminal doesn't scroll back to those lines, but they can be opened in
+    /// an editor.
+    /// Takes precedence over `max_scroll_history_lines` when set.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: null
+    pub scrollback_lines: Option<ScrollbackLinesContent>,
     /// Toolbar related settings
     pub toolbar: Option<TerminalToolbarContent>,
     /// Scrollbar-related settings
@@ -125,6 +135,11 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 45
     pub minimum_contrast: Option<f32>,
+    /// The height of the dropdown terminal, as a fraction of the height of the
+    /// display it drops down on.
+    ///
+    /// Default: 0.4
+    pub dropdown_height: Option<f32>,
 }
 
 /// Shell configuration to open the terminal with.
@@ -163,6 +178,23 @@ pub enum WorkingDirectory {
     Always { directory: String },
 }
 
+/// The amount of scrollback history a terminal keeps.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+#[serde(untagged)]
+pub enum ScrollbackLinesContent {
+    /// Keep at most this many lines.
+    Lines(usize),
+    /// Move the oldest lines out of the terminal into a temporary file, which
+    /// `terminal::OpenSpilledScrollback` opens, instead of discarding them.
+    SpillToFile(SpillToFileScrollback),
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum SpillToFileScrollback {
+    SpillToFile,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
 pub struct ScrollbarSettingsContent {
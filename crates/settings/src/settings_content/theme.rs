@@ -62,16 +62,31 @@ pub struct ThemeSettingsContent {
     #[serde(default)]
     pub icon_theme: Option<IconThemeSelection>,
 
-    /// UNSTABLE: Expect many elements to be broken.
+    /// Controls the density of the UI: "compact" | "default" | "comfortable".
     ///
-    // Controls the density of the UI.
-    #[serde(rename = "unstable.ui_density", default)]
+    /// "compact" shrinks paddings, tab heights, and row heights throughout the
+    /// workspace chrome to leave more room for code on small screens.
+    #[serde(alias = "unstable.ui_density", default)]
     pub ui_density: Option<UiDensity>,
 
     /// How much to fade out unused code.
     #[serde(default)]
     pub unnecessary_code_fade: Option<f32>,
 
+    /// The appearance of the window background, overriding the one set by the theme:
+    /// "opaque" | "transparent" | "blurred".
+    ///
+    /// Blurring is only supported on macOS, Windows, and Wayland compositors
+    /// that implement the blur protocol; elsewhere it behaves like "transparent".
+    #[serde(default)]
+    pub window_background: Option<WindowBackgroundContent>,
+
+    /// The opacity of the window background, from 0.0 (fully transparent) to 1.0 (opaque).
+    ///
+    /// Only takes effect when the window background is "transparent" or "blurred".
+    #[serde(default)]
+    pub window_background_opacity: Option<f32>,
+
     /// EXPERIMENTAL: Overrides for the current theme.
     ///
     /// These values will override the ones on the current theme specified in `theme`.
@@ -250,6 +265,35 @@ pub struct ThemeStyleContent {
     /// The styles for syntax nodes.
     #[serde(default)]
     pub syntax: IndexMap<String, HighlightStyleContent>,
+
+    /// The styles for specific UI components, which take precedence over the
+    /// colors those components share with the rest of the UI.
+    #[serde(default)]
+    pub components: ComponentStylesContent,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+#[serde(default)]
+pub struct ComponentStylesContent {
+    /// The bar holding a pane's tabs.
+    pub tab_bar: Option<ComponentStyleContent>,
+    /// The bar along the bottom of the window.
+    pub status_bar: Option<ComponentStyleContent>,
+    /// Popovers, such as menus and hover cards.
+    pub popover: Option<ComponentStyleContent>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+#[serde(default)]
+pub struct ComponentStyleContent {
+    /// The background color of the component.
+    pub background: Option<String>,
+    /// The color of the component's borders.
+    pub border: Option<String>,
+    /// The padding around the component's contents, in pixels.
+    pub padding: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
@@ -48,6 +48,7 @@ pub struct WorkspaceSettingsContent {
     /// When disabled, defaults are applied instead of the state restoration.
     ///
     /// E.g. for editors, selections, folds and scroll positions are restored, if the same file is closed and, later, opened again in the same pane.
+    /// Files that weren't open in the pane get the state they last had in the workspace, even before a restart.
     /// When disabled, a single selection in the very beginning of the file, zero scroll position and no folds state is used as a default.
     ///
     /// Default: true
@@ -16,9 +16,27 @@ pub struct TabStop {
 
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_with_variables(source, &|_| None)
+    }
+
+    /// Parses a snippet, replacing variables such as `$TM_FILENAME` or
+    /// `${CLIPBOARD:default}` with the value returned by `resolve_variable`.
+    ///
+    /// Variables that can't be resolved are replaced with their default, or
+    /// with their name when they have none.
+    pub fn parse_with_variables(
+        source: &str,
+        resolve_variable: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
         let mut text = String::with_capacity(source.len());
         let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        let mut parser = Parser {
+            text: &mut text,
+            tabstops: &mut tabstops,
+            resolve_variable,
+        };
+        parser
+            .parse_snippet(source, false)
             .context("failed to parse snippet")?;
 
         let len = text.len() as isize;
@@ -42,95 +60,144 @@ impl Snippet {
     }
 }
 
-fn parse_snippet<'a>(
-    mut source: &'a str,
-    nested: bool,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    loop {
-        match source.chars().next() {
-            None => return Ok(""),
-            Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
-            }
-            Some('\\') => {
-                // As specified in the LSP spec (`Grammar` section),
-                // backslashes can escape some characters:
-                // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax
-                source = &source[1..];
-                if let Some(c) = source.chars().next() {
-                    if c == '$' || c == '\\' || c == '}' {
-                        text.push(c);
-                        // All escapable characters are 1 byte long:
-                        source = &source[1..];
+struct Parser<'a> {
+    text: &'a mut String,
+    tabstops: &'a mut BTreeMap<usize, TabStop>,
+    resolve_variable: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl Parser<'_> {
+    fn parse_snippet<'a>(&mut self, mut source: &'a str, nested: bool) -> Result<&'a str> {
+        loop {
+            match source.chars().next() {
+                None => return Ok(""),
+                Some('$') => {
+                    source = self.parse_tabstop_or_variable(&source[1..])?;
+                }
+                Some('\\') => {
+                    // As specified in the LSP spec (`Grammar` section),
+                    // backslashes can escape some characters:
+                    // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax
+                    source = &source[1..];
+                    if let Some(c) = source.chars().next() {
+                        if c == '$' || c == '\\' || c == '}' {
+                            self.text.push(c);
+                            // All escapable characters are 1 byte long:
+                            source = &source[1..];
+                        } else {
+                            self.text.push('\\');
+                        }
                     } else {
-                        text.push('\\');
+                        self.text.push('\\');
                     }
-                } else {
-                    text.push('\\');
                 }
-            }
-            Some('}') => {
-                if nested {
-                    return Ok(source);
-                } else {
-                    text.push('}');
-                    source = &source[1..];
+                Some('}') => {
+                    if nested {
+                        return Ok(source);
+                    } else {
+                        self.text.push('}');
+                        source = &source[1..];
+                    }
+                }
+                Some(_) => {
+                    let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
+                    let (chunk, rest) = source.split_at(chunk_end);
+                    self.text.push_str(chunk);
+                    source = rest;
                 }
             }
-            Some(_) => {
-                let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
-                let (chunk, rest) = source.split_at(chunk_end);
-                text.push_str(chunk);
-                source = rest;
+        }
+    }
+
+    fn parse_tabstop_or_variable<'a>(&mut self, source: &'a str) -> Result<&'a str> {
+        let name_start = source.strip_prefix('{').unwrap_or(source);
+        if name_start.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            self.parse_variable(source)
+        } else {
+            self.parse_tabstop(source)
+        }
+    }
+
+    fn parse_tabstop<'a>(&mut self, mut source: &'a str) -> Result<&'a str> {
+        let tabstop_start = self.text.len();
+        let tabstop_index;
+        let mut choices = None;
+
+        if source.starts_with('{') {
+            let (index, rest) = parse_int(&source[1..])?;
+            tabstop_index = index;
+            source = rest;
+
+            if source.starts_with("|") {
+                (source, choices) = parse_choices(&source[1..], self.text)?;
+            }
+
+            if source.starts_with(':') {
+                source = self.parse_snippet(&source[1..], true)?;
             }
+
+            if source.starts_with('}') {
+                source = &source[1..];
+            } else {
+                anyhow::bail!("expected a closing brace");
+            }
+        } else {
+            let (index, rest) = parse_int(source)?;
+            tabstop_index = index;
+            source = rest;
         }
+
+        self.tabstops
+            .entry(tabstop_index)
+            .or_insert_with(|| TabStop {
+                ranges: Default::default(),
+                choices,
+            })
+            .ranges
+            .push(tabstop_start as isize..self.text.len() as isize);
+        Ok(source)
     }
-}
 
-fn parse_tabstop<'a>(
-    mut source: &'a str,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    let tabstop_start = text.len();
-    let tabstop_index;
-    let mut choices = None;
-
-    if source.starts_with('{') {
-        let (index, rest) = parse_int(&source[1..])?;
-        tabstop_index = index;
+    fn parse_variable<'a>(&mut self, mut source: &'a str) -> Result<&'a str> {
+        let braced = source.starts_with('{');
+        if braced {
+            source = &source[1..];
+        }
+        let (name, rest) = parse_variable_name(source);
         source = rest;
+        let value = (self.resolve_variable)(name);
 
-        if source.starts_with("|") {
-            (source, choices) = parse_choices(&source[1..], text)?;
+        if !braced {
+            self.text.push_str(value.as_deref().unwrap_or(name));
+            return Ok(source);
         }
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            match value {
+                Some(value) => {
+                    // The default is still parsed to find where it ends, but
+                    // neither its text nor its tabstops are kept.
+                    let text_len = self.text.len();
+                    let tabstops = self.tabstops.clone();
+                    source = self.parse_snippet(&source[1..], true)?;
+                    self.text.truncate(text_len);
+                    *self.tabstops = tabstops;
+                    self.text.push_str(&value);
+                }
+                None => source = self.parse_snippet(&source[1..], true)?,
+            }
+        } else if source.starts_with('/') {
+            anyhow::bail!("variable transforms are not supported");
+        } else {
+            self.text.push_str(value.as_deref().unwrap_or(name));
         }
 
         if source.starts_with('}') {
-            source = &source[1..];
+            Ok(&source[1..])
         } else {
-            anyhow::bail!("expected a closing brace");
+            anyhow::bail!("expected a closing brace")
         }
-    } else {
-        let (index, rest) = parse_int(source)?;
-        tabstop_index = index;
-        source = rest;
     }
-
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
-        .ranges
-        .push(tabstop_start as isize..text.len() as isize);
-    Ok(source)
 }
 
 fn parse_int(source: &str) -> Result<(usize, &str)> {
@@ -142,6 +209,13 @@ fn parse_int(source: &str) -> Result<(usize, &str)> {
     Ok((prefix.parse()?, suffix))
 }
 
+fn parse_variable_name(source: &str) -> (&str, &str) {
+    let len = source
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .unwrap_or(source.len());
+    source.split_at(len)
+}
+
 fn parse_choices<'a>(
     mut source: &'a str,
     text: &mut String,
@@ -324,6 +398,36 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let resolve = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "CLIPBOARD" => Some("copied".to_string()),
+            _ => None,
+        };
+
+        let snippet =
+            Snippet::parse_with_variables("// $TM_FILENAME: ${CLIPBOARD}$1", &resolve).unwrap();
+        assert_eq!(snippet.text, "// main.rs: copied");
+        assert_eq!(tabstops(&snippet), &[vec![18..18]]);
+
+        // Defaults are only used when the variable can't be resolved, and may
+        // contain tabstops of their own.
+        let snippet = Snippet::parse_with_variables(
+            "${CLIPBOARD:${1:none}} ${TM_SELECTED_TEXT:${2:nothing}}",
+            &resolve,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "copied nothing");
+        assert_eq!(tabstops(&snippet), &[vec![7..14], vec![14..14]]);
+
+        // Unknown variables without a default are replaced with their name.
+        let snippet = Snippet::parse("${UNKNOWN} $OTHER_1").unwrap();
+        assert_eq!(snippet.text, "UNKNOWN OTHER_1");
+
+        assert!(Snippet::parse("${TM_FILENAME/(.*)/$1/}").is_err());
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
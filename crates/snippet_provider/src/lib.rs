@@ -66,14 +66,19 @@ pub struct Snippet {
 
 async fn process_updates(
     this: WeakEntity<SnippetProvider>,
+    dir: &Path,
     entries: Vec<PathBuf>,
     mut cx: AsyncApp,
 ) -> Result<()> {
     let fs = this.read_with(&cx, |this, _| this.fs.clone())?;
     for entry_path in entries {
-        if entry_path
-            .extension()
-            .is_none_or(|extension| extension != "json")
+        // Directories that don't exist yet are watched through their parent, whose other files
+        // are not snippets. Only the names are compared, as events for symlinked directories
+        // have the paths of their targets.
+        if entry_path.parent().and_then(|parent| parent.file_name()) != dir.file_name()
+            || entry_path
+                .extension()
+                .is_none_or(|extension| extension != "json")
         {
             continue;
         }
@@ -128,7 +133,7 @@ async fn initial_scan(
             .await
             .into_iter()
             .collect::<Result<Vec<_>>>()?;
-        process_updates(this, entries, cx).await?;
+        process_updates(this, &path, entries, cx).await?;
     }
     Ok(())
 }
@@ -136,7 +141,7 @@ async fn initial_scan(
 pub struct SnippetProvider {
     fs: Arc<dyn Fs>,
     snippets: HashMap<SnippetKind, BTreeMap<PathBuf, Vec<Arc<Snippet>>>>,
-    watch_tasks: Vec<Task<Result<()>>>,
+    watch_tasks: HashMap<Arc<Path>, Task<Result<()>>>,
 }
 
 // Watches global snippet directory, is created just once and reused across multiple projects
@@ -148,7 +153,7 @@ impl GlobalSnippetWatcher {
         let provider = cx.new(|_cx| SnippetProvider {
             fs,
             snippets: Default::default(),
-            watch_tasks: vec![],
+            watch_tasks: HashMap::default(),
         });
         provider.update(cx, |this, cx| this.watch_directory(global_snippets_dir, cx));
         Self(provider)
@@ -166,7 +171,7 @@ impl SnippetProvider {
             }
             let mut this = Self {
                 fs,
-                watch_tasks: Vec::new(),
+                watch_tasks: HashMap::default(),
                 snippets: Default::default(),
             };
 
@@ -178,27 +183,61 @@ impl SnippetProvider {
         })
     }
 
+    /// Replaces the directories watched for snippets, dropping the snippets loaded from the
+    /// directories that are no longer watched.
+    pub fn set_watched_directories(&mut self, dirs: BTreeSet<PathBuf>, cx: &Context<Self>) {
+        let unwatched_dirs = self
+            .watch_tasks
+            .keys()
+            .filter(|dir| !dirs.contains(dir.as_ref()))
+            .cloned()
+            .collect::<Vec<_>>();
+        for dir in unwatched_dirs {
+            self.unwatch_directory(&dir);
+        }
+        for dir in dirs {
+            self.watch_directory(&dir, cx);
+        }
+    }
+
     /// Add directory to be watched for content changes
     fn watch_directory(&mut self, path: &Path, cx: &Context<Self>) {
+        if self.watch_tasks.contains_key(path) {
+            return;
+        }
         let path: Arc<Path> = Arc::from(path);
 
-        self.watch_tasks.push(cx.spawn(async move |this, cx| {
-            let fs = this.read_with(cx, |this, _| this.fs.clone())?;
-            let watched_path = path.clone();
-            let watcher = fs.watch(&watched_path, Duration::from_secs(1));
-            initial_scan(this.clone(), path, cx.clone()).await?;
-
-            let (mut entries, _) = watcher.await;
-            while let Some(entries) = entries.next().await {
-                process_updates(
-                    this.clone(),
-                    entries.into_iter().map(|event| event.path).collect(),
-                    cx.clone(),
-                )
-                .await?;
+        let task = cx.spawn({
+            let path = path.clone();
+            async move |this, cx| {
+                let fs = this.read_with(cx, |this, _| this.fs.clone())?;
+                let watched_path = path.clone();
+                let watcher = fs.watch(&watched_path, Duration::from_secs(1));
+                initial_scan(this.clone(), path, cx.clone()).await?;
+
+                let (mut entries, _) = watcher.await;
+                while let Some(entries) = entries.next().await {
+                    process_updates(
+                        this.clone(),
+                        &watched_path,
+                        entries.into_iter().map(|event| event.path).collect(),
+                        cx.clone(),
+                    )
+                    .await?;
+                }
+                Ok(())
             }
-            Ok(())
-        }));
+        });
+        self.watch_tasks.insert(path, task);
+    }
+
+    fn unwatch_directory(&mut self, path: &Path) {
+        if self.watch_tasks.remove(path).is_none() {
+            return;
+        }
+        for snippets_of_kind in self.snippets.values_mut() {
+            snippets_of_kind.retain(|snippets_path, _| snippets_path.parent() != Some(path));
+        }
     }
 
     fn lookup_snippets<'a, const LOOKUP_GLOBALS: bool>(
@@ -279,4 +318,60 @@ mod tests {
             });
         });
     }
+
+    #[gpui::test]
+    async fn test_project_snippet_directories(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/project",
+            serde_json::json!({
+                ".zed": {
+                    "tasks.json": "[]",
+                    "snippets": {
+                        "rust.json": r#"{
+                            "Print": {
+                                "prefix": "pr",
+                                "body": ["println!(\"$1\");"]
+                            }
+                        }"#,
+                    },
+                },
+            }),
+        )
+        .await;
+
+        let provider = cx.update(|cx| SnippetProvider::new(fs.clone(), Default::default(), cx));
+        let project_snippets_dir = PathBuf::from("/project/.zed/snippets");
+        provider.update(cx, |provider, cx| {
+            provider.set_watched_directories(BTreeSet::from_iter([project_snippets_dir]), cx)
+        });
+        cx.run_until_parked();
+        provider.update(cx, |provider, cx| {
+            let snippets = provider.snippets_for(Some("rust".to_owned()), cx);
+            assert_eq!(
+                snippets
+                    .iter()
+                    .map(|snippet| snippet.name.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["Print"]
+            );
+            assert!(
+                provider
+                    .snippets_for(Some("tasks".to_owned()), cx)
+                    .is_empty()
+            );
+        });
+
+        provider.update(cx, |provider, cx| {
+            provider.set_watched_directories(BTreeSet::default(), cx)
+        });
+        provider.update(cx, |provider, cx| {
+            assert!(
+                provider
+                    .snippets_for(Some("rust".to_owned()), cx)
+                    .is_empty(),
+                "Should drop the snippets of directories that are no longer watched"
+            );
+        });
+    }
 }
@@ -0,0 +1,355 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use collections::HashMap;
+use gpui::{Context, Entity, IntoElement, Render, Subscription, Task, WeakEntity, Window};
+use language::{Anchor, Buffer, BufferEvent};
+use project::{
+    Location, TaskSourceKind, buffer_store::BufferStoreEvent, project_settings::ProjectSettings,
+};
+use settings::{Settings as _, TaskOnSaveRule};
+use task::{HideStrategy, RevealStrategy, TaskTemplate, TaskVariables};
+use ui::{Tooltip, prelude::*};
+use util::paths::PathMatcher;
+use workspace::{ItemHandle, StatusItemView, Workspace};
+
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Runs the `tasks_on_save` rules matching each saved file, and shows in the
+/// status bar how many of them are still running.
+pub struct TasksOnSave {
+    workspace: WeakEntity<Workspace>,
+    /// Runs waiting for their rule's debounce to elapse. Saving another matching
+    /// file restarts the wait.
+    pending_runs: HashMap<TaskOnSaveRule, Task<()>>,
+    running_tasks: Vec<String>,
+    _subscription: Subscription,
+}
+
+impl TasksOnSave {
+    pub fn new(workspace: &Workspace, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let buffer_store = workspace.project().read(cx).buffer_store().clone();
+        let buffers = buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        for buffer in &buffers {
+            Self::watch_buffer(buffer, window, cx);
+        }
+        let subscription = cx.subscribe_in(&buffer_store, window, |_, _, event, window, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                Self::watch_buffer(buffer, window, cx);
+            }
+        });
+
+        Self {
+            workspace: workspace.weak_handle(),
+            pending_runs: HashMap::default(),
+            running_tasks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+
+    fn watch_buffer(buffer: &Entity<Buffer>, window: &mut Window, cx: &mut Context<Self>) {
+        cx.subscribe_in(buffer, window, |this, buffer, event, window, cx| {
+            if let BufferEvent::Saved = event {
+                this.buffer_saved(buffer.clone(), window, cx);
+            }
+        })
+        .detach();
+    }
+
+    fn buffer_saved(
+        &mut self,
+        buffer: Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(file) = project::File::from_dyn(buffer.read(cx).file()) else {
+            return;
+        };
+        // The rules are only read from the user's settings, so that opening a project can't make
+        // Zed run commands from its `.zed/settings.json` without the user starting them.
+        let rules = ProjectSettings::get_global(cx)
+            .tasks_on_save
+            .iter()
+            .filter(|rule| match PathMatcher::new([&rule.glob]) {
+                Ok(matcher) => matcher.is_match(&file.path),
+                Err(error) => {
+                    log::error!("invalid glob {:?} in tasks_on_save: {error}", rule.glob);
+                    false
+                }
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for rule in rules {
+            let debounce = rule
+                .debounce_ms
+                .map_or(DEFAULT_DEBOUNCE, Duration::from_millis);
+            let buffer = buffer.clone();
+            let run = cx.spawn_in(window, {
+                let rule = rule.clone();
+                async move |this, cx| {
+                    cx.background_executor().timer(debounce).await;
+                    this.update_in(cx, |this, window, cx| {
+                        this.pending_runs.remove(&rule);
+                        this.run(rule, buffer, window, cx)
+                    })
+                    .ok();
+                }
+            });
+            self.pending_runs.insert(rule, run);
+        }
+    }
+
+    fn run(
+        &mut self,
+        rule: TaskOnSaveRule,
+        buffer: Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+            .ok()
+        else {
+            return;
+        };
+        let task_store = project.read(cx).task_store().clone();
+        let location = Location {
+            buffer: buffer.clone(),
+            range: Anchor::MIN..Anchor::MIN,
+        };
+        let task_context = task_store.update(cx, |task_store, cx| {
+            task_store.task_context_for_location(TaskVariables::default(), location, cx)
+        });
+
+        let template = match (&rule.task, &rule.command) {
+            (Some(task_name), _) => {
+                let Some(task_inventory) = task_store.read(cx).task_inventory().cloned() else {
+                    return;
+                };
+                let buffer = buffer.read(cx);
+                let worktree_id = buffer.file().map(|file| file.worktree_id(cx));
+                let tasks = task_inventory.read(cx).list_tasks(
+                    buffer.file().cloned(),
+                    buffer.language().cloned(),
+                    worktree_id,
+                    cx,
+                );
+                let task_name = task_name.clone();
+                cx.background_spawn(async move {
+                    tasks
+                        .await
+                        .into_iter()
+                        .find(|(_, task)| task.label == task_name)
+                        .with_context(|| format!("no task named {task_name:?}"))
+                })
+            }
+            (None, Some(command)) => Task::ready(Ok((
+                TaskSourceKind::UserInput,
+                TaskTemplate {
+                    label: command.clone(),
+                    command: command.clone(),
+                    reveal: RevealStrategy::Never,
+                    hide: HideStrategy::OnSuccess,
+                    show_summary: true,
+                    show_command: true,
+                    ..TaskTemplate::default()
+                },
+            ))),
+            (None, None) => {
+                log::error!(
+                    "tasks_on_save rule for {:?} has neither a task nor a command",
+                    rule.glob
+                );
+                return;
+            }
+        };
+
+        let workspace = self.workspace.clone();
+        let label = rule
+            .task
+            .clone()
+            .or(rule.command.clone())
+            .unwrap_or_default();
+        self.running_tasks.push(label.clone());
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let (task_source_kind, template) = template.await?;
+                let task_context = task_context.await.unwrap_or_default();
+                let resolved_task = template
+                    .resolve_task(&task_source_kind.to_id_base(), &task_context)
+                    .with_context(|| format!("failed to resolve task {:?}", template.label))?;
+                let status = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.spawn_in_terminal(resolved_task.resolved, window, cx)
+                    })?
+                    .await;
+                if let Some(status) = status {
+                    let status = status?;
+                    anyhow::ensure!(status.success(), "exited with {status}");
+                }
+                anyhow::Ok(())
+            }
+            .await;
+            if let Err(error) = result {
+                log::error!("tasks_on_save: {label}: {error:#}");
+            }
+
+            this.update(cx, |this, cx| {
+                if let Some(ix) = this.running_tasks.iter().position(|task| *task == label) {
+                    this.running_tasks.remove(ix);
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
+
+impl Render for TasksOnSave {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        if self.running_tasks.is_empty() {
+            return div().into_any_element();
+        }
+
+        let label = match self.running_tasks.as_slice() {
+            [task] => task.clone(),
+            tasks => format!("{} tasks", tasks.len()),
+        };
+        let tooltip = format!("Running on save: {}", self.running_tasks.join(", "));
+        h_flex()
+            .id("tasks-on-save")
+            .gap_1()
+            .child(
+                Icon::new(IconName::ArrowCircle)
+                    .size(IconSize::Small)
+                    .color(Color::Muted)
+                    .with_rotate_animation(2),
+            )
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+            .tooltip(Tooltip::text(tooltip))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for TasksOnSave {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        process::ExitStatus,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use gpui::{App, AppContext as _, Entity, Task, TestAppContext, Window};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::{SettingsStore, TaskOnSaveRule};
+    use task::SpawnInTerminal;
+    use util::path;
+    use workspace::{TerminalProvider, Workspace};
+
+    use super::TasksOnSave;
+    use crate::tests::init_test;
+
+    struct FakeTerminalProvider(Arc<Mutex<Vec<String>>>);
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<Option<anyhow::Result<ExitStatus>>> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(task.command.unwrap_or_default());
+            Task::ready(Some(Ok(ExitStatus::default())))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_tasks_on_save(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.project.tasks_on_save = Some(vec![TaskOnSaveRule {
+                        glob: "**/*.proto".to_string(),
+                        task: None,
+                        command: Some("make".to_string()),
+                        debounce_ms: Some(100),
+                    }]);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".zed": {
+                    "settings.json": r#"{
+                        "tasks_on_save": [{ "glob": "**/*.proto", "command": "rm -rf ~" }]
+                    }"#,
+                },
+                "a.proto": "",
+                "b.rs": "",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let spawned = Arc::new(Mutex::new(Vec::new()));
+        let tasks_on_save = window
+            .update(cx, |workspace, window, cx| {
+                workspace.set_terminal_provider(FakeTerminalProvider(spawned.clone()));
+                cx.new(|cx| TasksOnSave::new(workspace, window, cx))
+            })
+            .unwrap();
+
+        // Saving again before the debounce elapses restarts it, and runs the task once.
+        save(&project, path!("/dir/a.proto"), cx).await;
+        save(&project, path!("/dir/b.rs"), cx).await;
+        cx.executor().advance_clock(Duration::from_millis(50));
+        save(&project, path!("/dir/a.proto"), cx).await;
+        cx.executor().advance_clock(Duration::from_millis(50));
+        cx.run_until_parked();
+        assert!(spawned.lock().unwrap().is_empty());
+
+        cx.executor().advance_clock(Duration::from_millis(100));
+        cx.run_until_parked();
+        assert_eq!(*spawned.lock().unwrap(), ["make"]);
+        tasks_on_save.read_with(cx, |tasks_on_save, _| {
+            assert!(tasks_on_save.pending_runs.is_empty());
+            assert!(tasks_on_save.running_tasks.is_empty());
+        });
+    }
+
+    async fn save(project: &Entity<Project>, path: &str, cx: &mut TestAppContext) {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "a")], None, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))
+            .await
+            .unwrap();
+    }
+}
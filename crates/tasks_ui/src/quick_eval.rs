@@ -0,0 +1,320 @@
+use std::path::{Path, PathBuf};
+
+use editor::Editor;
+use gpui::{Context, Window, actions};
+use itertools::Itertools as _;
+use project::Fs;
+use task::{RevealStrategy, RevealTarget, Shell, SpawnInTerminal, TaskId};
+use workspace::{Toast, Workspace, notifications::NotificationId};
+
+actions!(
+    task,
+    [
+        /// Runs the selection, or the whole buffer if nothing is selected, with the
+        /// interpreter configured for its language, showing the output in a terminal.
+        QuickEval
+    ]
+);
+
+pub(crate) fn quick_eval(
+    workspace: &mut Workspace,
+    _: &QuickEval,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let (language, interpreter, code) = editor.update(cx, |editor, cx| {
+        let selection = editor.selections.newest::<usize>(cx);
+        let multi_buffer = editor.buffer().read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let language = multi_buffer.language_at(selection.head(), cx);
+        let interpreter = multi_buffer
+            .language_settings_at(selection.head(), cx)
+            .tasks
+            .interpreter
+            .clone();
+        let code = if selection.is_empty() {
+            snapshot.text()
+        } else {
+            snapshot.text_for_range(selection.range()).collect()
+        };
+        (language, interpreter, code)
+    });
+    let language_name = language.as_ref().map(|language| language.name());
+
+    if interpreter.is_empty() {
+        struct NoInterpreterToast;
+        let language = language_name
+            .as_ref()
+            .map_or("this buffer", |name| name.as_ref());
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoInterpreterToast>(),
+                format!(
+                    "No interpreter is configured for {language}. Set `tasks.interpreter` in its language settings to run it."
+                ),
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    }
+    if code.trim().is_empty() {
+        return;
+    }
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        struct RemoteProjectToast;
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<RemoteProjectToast>(),
+                "Quick eval is only available in local projects.",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    }
+
+    let fs = project.fs().clone();
+    let extension = language
+        .as_ref()
+        .and_then(|language| language.path_suffixes().first().cloned());
+    let script_path = script_path(extension.as_deref());
+    let cwd = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .and_then(|project_path| {
+            workspace
+                .project()
+                .read(cx)
+                .worktree_for_id(project_path.worktree_id, cx)
+        })
+        .or_else(|| workspace.visible_worktrees(cx).next())
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+    let Some(spawn_in_terminal) = quick_eval_task(
+        &interpreter,
+        &script_path,
+        language_name.as_ref().map(|name| name.as_ref()),
+        cwd,
+    ) else {
+        return;
+    };
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        if let Err(error) = write_script(fs.as_ref(), &script_path, code).await {
+            return workspace.update(cx, |workspace, cx| {
+                workspace.show_error(
+                    &error.context(format!("writing {}", script_path.display())),
+                    cx,
+                )
+            });
+        }
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+            })?
+            .await;
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The file that quick eval writes the code to. It is overwritten on every run, and keeps the
+/// language's extension for interpreters that pick a mode based on it.
+fn script_path(extension: Option<&str>) -> PathBuf {
+    let file_name = match extension {
+        Some(extension) => format!("quick_eval.{extension}"),
+        None => "quick_eval".to_string(),
+    };
+    paths::temp_dir().join("quick_eval").join(file_name)
+}
+
+async fn write_script(fs: &dyn Fs, script_path: &Path, code: String) -> anyhow::Result<()> {
+    if let Some(parent) = script_path.parent() {
+        fs.create_dir(parent).await?;
+    }
+    fs.atomic_write(script_path.to_path_buf(), code).await
+}
+
+fn quick_eval_task(
+    interpreter: &[String],
+    script_path: &Path,
+    language_name: Option<&str>,
+    cwd: Option<PathBuf>,
+) -> Option<SpawnInTerminal> {
+    let (command, leading_args) = interpreter.split_first()?;
+    let label = match language_name {
+        Some(language_name) => format!("Quick eval: {language_name}"),
+        None => "Quick eval".to_string(),
+    };
+    let args = leading_args
+        .iter()
+        .cloned()
+        .chain([script_path.to_string_lossy().into_owned()])
+        .collect();
+    Some(SpawnInTerminal {
+        id: TaskId(format!("quick-eval-{label}")),
+        full_label: label.clone(),
+        label,
+        command_label: std::iter::once(command.as_str())
+            .chain(leading_args.iter().map(String::as_str))
+            .join(" "),
+        // Run the interpreter itself instead of going through the user's shell, so that
+        // the script path reaches it unchanged whichever shell is configured.
+        command: None,
+        shell: Shell::WithArguments {
+            program: command.clone(),
+            args,
+            title_override: None,
+        },
+        cwd,
+        // Re-running replaces the output of the previous run instead of piling up terminals.
+        use_new_terminal: false,
+        allow_concurrent_runs: true,
+        reveal: RevealStrategy::Always,
+        reveal_target: RevealTarget::Dock,
+        show_summary: true,
+        show_command: false,
+        show_rerun: false,
+        ..SpawnInTerminal::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        process::ExitStatus,
+        sync::{Arc, Mutex},
+    };
+
+    use gpui::{App, Task, TestAppContext, Window};
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use project::{FakeFs, Fs as _, Project};
+    use serde_json::json;
+    use settings::{LanguageSettingsContent, LanguageTaskSettingsContent, SettingsStore};
+    use task::{Shell, ShellBuilder, SpawnInTerminal};
+    use util::path;
+    use workspace::{OpenOptions, TerminalProvider, Workspace};
+
+    use super::{QuickEval, quick_eval_task, script_path};
+    use crate::tests::init_test;
+
+    struct FakeTerminalProvider(Arc<Mutex<Vec<SpawnInTerminal>>>);
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<Option<anyhow::Result<ExitStatus>>> {
+            self.0.lock().unwrap().push(task);
+            Task::ready(Some(Ok(ExitStatus::default())))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_quick_eval_writes_code_to_script(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.project.all_languages.languages.0.insert(
+                        "Python".into(),
+                        LanguageSettingsContent {
+                            tasks: Some(LanguageTaskSettingsContent {
+                                variables: Default::default(),
+                                enabled: None,
+                                prefer_lsp: None,
+                                interpreter: Some(vec!["python3".to_string()]),
+                            }),
+                            ..Default::default()
+                        },
+                    );
+                });
+            });
+        });
+
+        // Code that can't be passed as an argument, since it contains a NUL byte, quotes
+        // and shell metacharacters.
+        let code = "print('a\0b')\nprint(\"$HOME\" if True else `ls`)\n";
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.py": code })).await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        project.read_with(cx, |project, _| {
+            project.languages().add(Arc::new(Language::new(
+                LanguageConfig {
+                    name: "Python".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["py".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                None,
+            )))
+        });
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let spawned = Arc::new(Mutex::new(Vec::new()));
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.set_terminal_provider(FakeTerminalProvider(spawned.clone()));
+                workspace.open_abs_path(
+                    PathBuf::from(path!("/dir/a.py")),
+                    OpenOptions::default(),
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        cx.dispatch_action(QuickEval);
+        cx.run_until_parked();
+
+        let script_path = script_path(Some("py"));
+        let spawned = spawned.lock().unwrap().clone();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].label, "Quick eval: Python");
+        assert_eq!(spawned[0].cwd.as_deref(), Some(Path::new(path!("/dir"))));
+        assert_eq!(
+            spawned[0].shell,
+            Shell::WithArguments {
+                program: "python3".to_string(),
+                args: vec![script_path.to_string_lossy().into_owned()],
+                title_override: None,
+            }
+        );
+        assert_eq!(fs.load(&script_path).await.unwrap(), code);
+    }
+
+    #[test]
+    fn test_quick_eval_task_runs_interpreter_directly() {
+        let script_path = Path::new("/home/some user/.cache/zed/quick_eval/quick_eval.py");
+        let interpreter = ["python3".to_string(), "-X".to_string(), "utf8".to_string()];
+        let task = quick_eval_task(&interpreter, script_path, Some("Python"), None).unwrap();
+        assert_eq!(task.command_label, "python3 -X utf8");
+
+        // The script path is handed to the interpreter as a single argument, without being
+        // quoted for, or interpreted by, a shell.
+        let (program, args) =
+            ShellBuilder::new(None, &task.shell).build(task.command.clone(), &task.args);
+        assert_eq!(program, "python3");
+        assert_eq!(
+            args,
+            [
+                "-X",
+                "utf8",
+                "/home/some user/.cache/zed/quick_eval/quick_eval.py"
+            ]
+        );
+
+        assert!(quick_eval_task(&[], script_path, None, None).is_none());
+    }
+}
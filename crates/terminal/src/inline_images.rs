@@ -0,0 +1,629 @@
+//! Support for the iTerm2 inline image protocol (`OSC 1337 ; File=... ST`).
+//!
+//! Alacritty's parser drops OSC sequences it doesn't know, so image sequences
+//! are taken out of the PTY output before it reaches the parser. Each image is
+//! decoded and replaced with a block of blank cells covering its footprint,
+//! where every row carries an OSC 8 hyperlink whose URI names the image and
+//! the row within it. Those cells scroll, get cleared, and end up in the
+//! scrollback like any other output, and the terminal element paints the image
+//! on top of them. The URIs include a nonce chosen for each terminal, so that
+//! programs can't print hyperlinks that pass for placeholders.
+//!
+//! Sixel graphics are not supported: they would need a sixel decoder, and
+//! alacritty's parser drops the DCS sequences carrying them too, so they'd
+//! have to be filtered out of the output here the same way.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Cursor, Read},
+    sync::{Arc, Mutex},
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use base64::Engine as _;
+use collections::HashMap;
+use gpui::{Image, ImageFormat};
+use polling::{Event, PollMode, Poller};
+
+/// The URI scheme of the hyperlinks that mark the cells covered by an image.
+const IMAGE_URI_PREFIX: &str = "zed-image:";
+
+/// The sequence introducing an iTerm2 file transfer, after the `ESC ]`.
+const SEQUENCE_PREFIX: &[u8] = b"\x1b]1337;File=";
+
+/// Image sequences larger than this are discarded instead of buffered.
+const MAX_SEQUENCE_BYTES: usize = 32 * 1024 * 1024;
+
+/// The number of decoded images kept around for rendering. Images evicted
+/// while still in the scrollback leave blank cells behind.
+const MAX_STORED_IMAGES: usize = 256;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A decoded image and the block of cells it covers.
+#[derive(Clone, Debug)]
+pub struct InlineImage {
+    pub image: Arc<Image>,
+    pub width: u32,
+    pub height: u32,
+    pub columns: usize,
+    pub rows: usize,
+    pub preserve_aspect_ratio: bool,
+}
+
+/// Where a cell sits within an inline image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InlineImageCell {
+    pub image_id: usize,
+    pub row: usize,
+}
+
+/// Returns whether a hyperlink URI uses the scheme of image placeholders.
+/// Such links are never opened or underlined, even when they were printed by
+/// a program rather than standing in for an image.
+pub fn is_inline_image_uri(uri: &str) -> bool {
+    uri.starts_with(IMAGE_URI_PREFIX)
+}
+
+/// The images decoded from a terminal's output, shared between the PTY thread
+/// and the terminal.
+#[derive(Clone)]
+pub struct InlineImages {
+    store: Arc<Mutex<InlineImageStore>>,
+    nonce: u64,
+}
+
+#[derive(Default)]
+struct InlineImageStore {
+    images: HashMap<usize, InlineImage>,
+    order: VecDeque<usize>,
+    next_id: usize,
+}
+
+impl Default for InlineImages {
+    fn default() -> Self {
+        Self {
+            store: Arc::default(),
+            nonce: rand::random(),
+        }
+    }
+}
+
+impl InlineImages {
+    pub fn get(&self, image_id: usize) -> Option<InlineImage> {
+        self.store.lock().unwrap().images.get(&image_id).cloned()
+    }
+
+    /// Recognizes the placeholder hyperlink URI of a cell covered by one of
+    /// these images.
+    pub fn cell_for_uri(&self, uri: &str) -> Option<InlineImageCell> {
+        let mut parts = uri.strip_prefix(IMAGE_URI_PREFIX)?.split(':');
+        if u64::from_str_radix(parts.next()?, 16).ok()? != self.nonce {
+            return None;
+        }
+        let cell = InlineImageCell {
+            image_id: parts.next()?.parse().ok()?,
+            row: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(cell)
+    }
+
+    fn uri_for_cell(&self, cell: InlineImageCell) -> String {
+        format!(
+            "{IMAGE_URI_PREFIX}{:016x}:{}:{}",
+            self.nonce, cell.image_id, cell.row
+        )
+    }
+
+    fn insert(&self, image: InlineImage) -> usize {
+        let mut store = self.store.lock().unwrap();
+        let image_id = store.next_id;
+        store.next_id += 1;
+        store.images.insert(image_id, image);
+        store.order.push_back(image_id);
+        while store.order.len() > MAX_STORED_IMAGES {
+            if let Some(evicted) = store.order.pop_front() {
+                store.images.remove(&evicted);
+            }
+        }
+        image_id
+    }
+}
+
+/// The grid geometry used to convert image sizes into cells.
+#[derive(Clone, Copy, Debug)]
+struct CellGeometry {
+    columns: usize,
+    lines: usize,
+    cell_width: f32,
+    cell_height: f32,
+}
+
+impl From<WindowSize> for CellGeometry {
+    fn from(size: WindowSize) -> Self {
+        Self {
+            columns: (size.num_cols as usize).max(1),
+            lines: (size.num_lines as usize).max(1),
+            cell_width: (size.cell_width as f32).max(1.),
+            cell_height: (size.cell_height as f32).max(1.),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Dimension {
+    Auto,
+    Cells(f32),
+    Pixels(f32),
+    Percent(f32),
+}
+
+impl Dimension {
+    fn parse(value: &str) -> Option<Self> {
+        if value == "auto" {
+            Some(Self::Auto)
+        } else if let Some(pixels) = value.strip_suffix("px") {
+            pixels.parse().ok().map(Self::Pixels)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse().ok().map(Self::Percent)
+        } else {
+            value.parse().ok().map(Self::Cells)
+        }
+    }
+
+    /// Resolves the dimension to pixels, given the cell size and the size of
+    /// the terminal in that direction.
+    fn to_pixels(self, cell_size: f32, cells: usize) -> Option<f32> {
+        match self {
+            Self::Auto => None,
+            Self::Cells(count) => Some(count * cell_size),
+            Self::Pixels(pixels) => Some(pixels),
+            Self::Percent(percent) => Some(percent / 100. * cells as f32 * cell_size),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ImageArguments {
+    width: Dimension,
+    height: Dimension,
+    preserve_aspect_ratio: bool,
+    inline: bool,
+}
+
+impl ImageArguments {
+    fn parse(arguments: &str) -> Self {
+        let mut result = Self {
+            width: Dimension::Auto,
+            height: Dimension::Auto,
+            preserve_aspect_ratio: true,
+            inline: false,
+        };
+        for argument in arguments.split(';') {
+            let Some((key, value)) = argument.split_once('=') else {
+                continue;
+            };
+            match key {
+                "width" => result.width = Dimension::parse(value).unwrap_or(Dimension::Auto),
+                "height" => result.height = Dimension::parse(value).unwrap_or(Dimension::Auto),
+                "preserveAspectRatio" => result.preserve_aspect_ratio = value != "0",
+                "inline" => result.inline = value == "1",
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+fn to_gpui_format(format: image::ImageFormat) -> Option<ImageFormat> {
+    match format {
+        image::ImageFormat::Png => Some(ImageFormat::Png),
+        image::ImageFormat::Jpeg => Some(ImageFormat::Jpeg),
+        image::ImageFormat::WebP => Some(ImageFormat::Webp),
+        image::ImageFormat::Gif => Some(ImageFormat::Gif),
+        image::ImageFormat::Bmp => Some(ImageFormat::Bmp),
+        image::ImageFormat::Tiff => Some(ImageFormat::Tiff),
+        _ => None,
+    }
+}
+
+/// Decodes the body of an image sequence (`<arguments>:<base64 data>`), and
+/// computes the size of the image in cells.
+fn decode_image(sequence: &[u8], geometry: CellGeometry) -> Option<InlineImage> {
+    let separator = sequence.iter().position(|byte| *byte == b':')?;
+    let arguments = ImageArguments::parse(std::str::from_utf8(&sequence[..separator]).ok()?);
+    if !arguments.inline {
+        // Downloading files to disk is not supported.
+        return None;
+    }
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&sequence[separator + 1..])
+        .ok()?;
+    let reader = image::ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .ok()?;
+    let format = to_gpui_format(reader.format()?)?;
+    let (width, height) = reader.into_dimensions().ok()?;
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let (columns, rows) = image_cells(&arguments, width, height, geometry);
+    Some(InlineImage {
+        image: Arc::new(Image::from_bytes(format, bytes)),
+        width,
+        height,
+        columns,
+        rows,
+        preserve_aspect_ratio: arguments.preserve_aspect_ratio,
+    })
+}
+
+fn image_cells(
+    arguments: &ImageArguments,
+    width: u32,
+    height: u32,
+    geometry: CellGeometry,
+) -> (usize, usize) {
+    let (width, height) = (width as f32, height as f32);
+    let requested_width = arguments
+        .width
+        .to_pixels(geometry.cell_width, geometry.columns);
+    let requested_height = arguments
+        .height
+        .to_pixels(geometry.cell_height, geometry.lines);
+
+    let (mut pixel_width, mut pixel_height) = match (requested_width, requested_height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) if arguments.preserve_aspect_ratio => (w, w * height / width),
+        (None, Some(h)) if arguments.preserve_aspect_ratio => (h * width / height, h),
+        (w, h) => (w.unwrap_or(width), h.unwrap_or(height)),
+    };
+
+    // Never let an image wrap: scale it down to fit the terminal's width.
+    let max_width = geometry.columns as f32 * geometry.cell_width;
+    if pixel_width > max_width {
+        if arguments.preserve_aspect_ratio {
+            pixel_height *= max_width / pixel_width;
+        }
+        pixel_width = max_width;
+    }
+
+    let columns = (pixel_width / geometry.cell_width).ceil() as usize;
+    let rows = (pixel_height / geometry.cell_height).ceil() as usize;
+    (columns.clamp(1, geometry.columns), rows.max(1))
+}
+
+/// Writes the cells standing in for an image: `rows` lines of `columns` blank
+/// cells, each line tagged with a hyperlink to the image. The cursor ends up
+/// right after the image's last line, as it does in iTerm2.
+fn write_placeholder(
+    images: &InlineImages,
+    image_id: usize,
+    columns: usize,
+    rows: usize,
+    output: &mut Vec<u8>,
+) {
+    for row in 0..rows {
+        if row > 0 {
+            output.extend_from_slice(format!("\n\x1b[{columns}D").as_bytes());
+        }
+        let uri = images.uri_for_cell(InlineImageCell { image_id, row });
+        output.extend_from_slice(format!("\x1b]8;;{uri}\x1b\\").as_bytes());
+        output.extend(std::iter::repeat_n(b' ', columns));
+        output.extend_from_slice(b"\x1b]8;;\x1b\\");
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterState {
+    Ground,
+    /// Matching the bytes read so far against `SEQUENCE_PREFIX`.
+    Prefix,
+    /// Inside an image sequence.
+    Sequence,
+    /// Inside an image sequence, right after an `ESC`.
+    SequenceEscape,
+    /// Inside an image sequence that grew too large to keep.
+    Discard,
+    /// Inside a discarded image sequence, right after an `ESC`.
+    DiscardEscape,
+}
+
+/// Incrementally removes image sequences from a stream of PTY output, replacing
+/// them with placeholder cells.
+struct ImageSequenceFilter {
+    state: FilterState,
+    pending: Vec<u8>,
+    images: InlineImages,
+    geometry: CellGeometry,
+}
+
+impl ImageSequenceFilter {
+    fn new(images: InlineImages, window_size: WindowSize) -> Self {
+        Self {
+            state: FilterState::Ground,
+            pending: Vec::new(),
+            images,
+            geometry: window_size.into(),
+        }
+    }
+
+    fn filter(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        let mut input = input.iter().copied();
+        let mut reprocessed = None;
+        while let Some(byte) = reprocessed.take().or_else(|| input.next()) {
+            match self.state {
+                FilterState::Ground => {
+                    if byte == 0x1b {
+                        self.pending.push(byte);
+                        self.state = FilterState::Prefix;
+                    } else {
+                        output.push(byte);
+                    }
+                }
+                FilterState::Prefix => {
+                    if SEQUENCE_PREFIX[self.pending.len()] == byte {
+                        self.pending.push(byte);
+                        if self.pending.len() == SEQUENCE_PREFIX.len() {
+                            self.pending.clear();
+                            self.state = FilterState::Sequence;
+                        }
+                    } else {
+                        // Some other escape sequence: pass it through untouched.
+                        output.append(&mut self.pending);
+                        self.state = FilterState::Ground;
+                        reprocessed = Some(byte);
+                    }
+                }
+                FilterState::Sequence => match byte {
+                    0x07 => self.finish_sequence(output),
+                    0x1b => self.state = FilterState::SequenceEscape,
+                    _ if self.pending.len() >= MAX_SEQUENCE_BYTES => {
+                        self.pending = Vec::new();
+                        self.state = FilterState::Discard;
+                    }
+                    _ => self.pending.push(byte),
+                },
+                FilterState::Discard => match byte {
+                    0x07 => self.state = FilterState::Ground,
+                    0x1b => self.state = FilterState::DiscardEscape,
+                    _ => {}
+                },
+                FilterState::SequenceEscape | FilterState::DiscardEscape => {
+                    // Any escape terminates the sequence, just like in alacritty's parser.
+                    if self.state == FilterState::SequenceEscape {
+                        self.finish_sequence(output);
+                    }
+                    self.state = FilterState::Ground;
+                    if byte != b'\\' {
+                        reprocessed = Some(byte);
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish_sequence(&mut self, output: &mut Vec<u8>) {
+        let sequence = std::mem::take(&mut self.pending);
+        self.state = FilterState::Ground;
+        if let Some(image) = decode_image(&sequence, self.geometry) {
+            let (columns, rows) = (image.columns, image.rows);
+            let image_id = self.images.insert(image);
+            write_placeholder(&self.images, image_id, columns, rows, output);
+        }
+    }
+}
+
+/// A PTY whose output has image sequences replaced by placeholder cells.
+pub(crate) struct InlineImagePty<T> {
+    pty: T,
+    filter: ImageSequenceFilter,
+    read_buffer: Box<[u8]>,
+    /// Filtered output that did not fit into the caller's buffer yet.
+    output: Vec<u8>,
+    output_start: usize,
+}
+
+impl<T> InlineImagePty<T> {
+    pub fn new(pty: T, images: InlineImages, window_size: WindowSize) -> Self {
+        Self {
+            pty,
+            filter: ImageSequenceFilter::new(images, window_size),
+            read_buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            output: Vec::new(),
+            output_start: 0,
+        }
+    }
+}
+
+impl<T: EventedReadWrite> Read for InlineImagePty<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let pending = &self.output[self.output_start..];
+            if !pending.is_empty() {
+                let len = pending.len().min(buf.len());
+                buf[..len].copy_from_slice(&pending[..len]);
+                self.output_start += len;
+                if self.output_start == self.output.len() {
+                    self.output.clear();
+                    self.output_start = 0;
+                }
+                return Ok(len);
+            }
+
+            // Reading stops at `WouldBlock` or the end of the output, both of
+            // which are passed on to the event loop. Input consisting only of
+            // part of an image sequence produces no output, so keep reading.
+            let len = self.pty.reader().read(&mut self.read_buffer)?;
+            if len == 0 {
+                return Ok(0);
+            }
+            self.filter
+                .filter(&self.read_buffer[..len], &mut self.output);
+        }
+    }
+}
+
+impl<T: EventedReadWrite> EventedReadWrite for InlineImagePty<T> {
+    type Reader = Self;
+    type Writer = T::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<T: EventedPty> EventedPty for InlineImagePty<T> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<T: OnResize> OnResize for InlineImagePty<T> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.filter.geometry = window_size.into();
+        self.pty.on_resize(window_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_PIXEL_PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+    fn window_size() -> WindowSize {
+        WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 10,
+            cell_height: 20,
+        }
+    }
+
+    fn filter_chunks(filter: &mut ImageSequenceFilter, chunks: &[&[u8]]) -> Vec<u8> {
+        let mut output = Vec::new();
+        for chunk in chunks {
+            filter.filter(chunk, &mut output);
+        }
+        output
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        let mut filter = ImageSequenceFilter::new(InlineImages::default(), window_size());
+        let input: &[u8] = b"a\x1b[31mb\x1b]0;title\x07c\x1b]8;;https://zed.dev\x1b\\d\x1b\x1b]1e";
+        assert_eq!(filter_chunks(&mut filter, &[input]), input);
+    }
+
+    #[test]
+    fn test_image_sequence_is_replaced_by_placeholder() {
+        let images = InlineImages::default();
+        let mut filter = ImageSequenceFilter::new(images.clone(), window_size());
+        let sequence =
+            format!("\x1b]1337;File=name=eA==;width=3;height=2;inline=1:{ONE_PIXEL_PNG}\x07");
+
+        // Split the sequence at every possible point to exercise the incremental parsing.
+        for split in 0..sequence.len() {
+            let (head, tail) = sequence.as_bytes().split_at(split);
+            let output = filter_chunks(&mut filter, &[b"x", head, tail, b"y"]);
+            let output = String::from_utf8(output).unwrap();
+            let nonce = images.nonce;
+            assert_eq!(
+                output,
+                format!(
+                    "x\x1b]8;;zed-image:{nonce:016x}:{split}:0\x1b\\   \x1b]8;;\x1b\\\
+                     \n\x1b[3D\x1b]8;;zed-image:{nonce:016x}:{split}:1\x1b\\   \x1b]8;;\x1b\\y"
+                )
+            );
+        }
+
+        let image = images.get(0).unwrap();
+        assert_eq!((image.width, image.height), (1, 1));
+        assert_eq!((image.columns, image.rows), (3, 2));
+        assert_eq!(image.image.format, ImageFormat::Png);
+    }
+
+    #[test]
+    fn test_invalid_and_non_inline_images_are_dropped() {
+        let images = InlineImages::default();
+        let mut filter = ImageSequenceFilter::new(images.clone(), window_size());
+        let input = format!(
+            "a\x1b]1337;File=inline=0:{ONE_PIXEL_PNG}\x1b\\b\x1b]1337;File=inline=1:!!!\x07c"
+        );
+        assert_eq!(filter_chunks(&mut filter, &[input.as_bytes()]), b"abc");
+        assert!(images.get(0).is_none());
+    }
+
+    #[test]
+    fn test_image_size_in_cells() {
+        let geometry = CellGeometry::from(window_size());
+        let cells = |arguments: &str, width, height| {
+            image_cells(&ImageArguments::parse(arguments), width, height, geometry)
+        };
+
+        // Natural size, rounded up to whole cells.
+        assert_eq!(cells("inline=1", 95, 41), (10, 3));
+        // One dimension given, the other follows the aspect ratio.
+        assert_eq!(cells("width=20", 100, 50), (20, 5));
+        assert_eq!(cells("height=100px", 100, 50), (20, 5));
+        assert_eq!(cells("width=50%", 100, 100), (40, 20));
+        assert_eq!(cells("width=20;preserveAspectRatio=0", 100, 50), (20, 3));
+        // Too wide images are scaled down to the terminal's width.
+        assert_eq!(cells("inline=1", 1600, 400), (80, 10));
+    }
+
+    #[test]
+    fn test_inline_image_uri() {
+        let images = InlineImages::default();
+        let cell = InlineImageCell {
+            image_id: 12,
+            row: 3,
+        };
+        let uri = images.uri_for_cell(cell);
+        assert_eq!(images.cell_for_uri(&uri), Some(cell));
+        assert!(is_inline_image_uri(&uri));
+        assert_eq!(images.cell_for_uri("https://zed.dev"), None);
+
+        // Placeholders printed by programs, or by another terminal, don't
+        // know the nonce and aren't taken for images.
+        assert_eq!(images.cell_for_uri("zed-image:12:3"), None);
+        let other_images = InlineImages {
+            nonce: images.nonce.wrapping_add(1),
+            ..InlineImages::default()
+        };
+        assert_eq!(other_images.cell_for_uri(&uri), None);
+        assert_eq!(images.cell_for_uri(&format!("{uri}:0")), None);
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use alacritty_terminal::{
+    grid::Row,
+    index::Column,
+    term::cell::{Cell, Flags},
+};
+use tempfile::NamedTempFile;
+
+/// Scrollback lines that no longer fit into a terminal's in-memory history,
+/// stored in a temporary file that can be opened in an editor.
+pub(crate) struct ScrollbackSpill {
+    file: NamedTempFile,
+}
+
+impl ScrollbackSpill {
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            file: tempfile::Builder::new()
+                .prefix("zed-terminal-scrollback-")
+                .tempfile()?,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Appends the text of the rows to the file, one line per row. Files can be
+    /// written through a shared reference, so that this can run on a background
+    /// thread, away from the terminal lock the rows were copied under.
+    pub fn append(&self, rows: &[Row<Cell>]) -> io::Result<()> {
+        let mut writer = BufWriter::new(self.file.as_file());
+        for row in rows {
+            writer.write_all(row_text(row).trim_end().as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+}
+
+fn row_text(row: &Row<Cell>) -> String {
+    let mut text = String::with_capacity(row.len());
+    for cell in &row[..Column(row.len())] {
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        text.push(cell.c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            text.extend(zerowidth);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(text: &str) -> Row<Cell> {
+        let mut row = Row::new(10);
+        for (ix, c) in text.chars().enumerate() {
+            row[Column(ix)].c = c;
+        }
+        row
+    }
+
+    #[test]
+    fn test_appending_spilled_lines() {
+        let spill = ScrollbackSpill::new().unwrap();
+        spill.append(&[row("line 0"), row("line 1")]).unwrap();
+
+        // Wide characters are followed by a spacer cell, which isn't part of the text.
+        let mut wide = row("a界 b");
+        wide[Column(1)].flags.insert(Flags::WIDE_CHAR);
+        wide[Column(2)].flags.insert(Flags::WIDE_CHAR_SPACER);
+        spill.append(&[wide]).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(spill.path()).unwrap(),
+            "line 0\nline 1\na界b\n"
+        );
+    }
+}
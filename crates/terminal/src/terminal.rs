@@ -2,7 +2,9 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+pub mod inline_images;
 mod pty_info;
+mod scrollback_spill;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
@@ -40,23 +42,27 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use inline_images::{
+    InlineImage, InlineImageCell, InlineImagePty, InlineImages, is_inline_image_uri,
+};
 use pty_info::PtyProcessInfo;
+use scrollback_spill::ScrollbackSpill;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, TaskId};
 use terminal_hyperlinks::RegexSearches;
-use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
+use terminal_settings::{AlternateScroll, CursorShape, ScrollbackLines, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
-use util::truncate_and_trailoff;
+use util::{ResultExt as _, truncate_and_trailoff};
 
 use std::{
     borrow::Cow,
     cmp::{self, min},
     fmt::Display,
     ops::{Deref, RangeInclusive},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::ExitStatus,
     sync::Arc,
     time::Instant,
@@ -125,6 +131,8 @@ pub enum Event {
     TitleChanged,
     BreadcrumbsChanged,
     CloseTerminal,
+    /// The process running in the terminal has exited with the given exit code.
+    ChildExited(i32),
     Bell,
     Wakeup,
     BlinkChanged(bool),
@@ -331,6 +339,15 @@ impl Display for TerminalError {
 // https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213
 const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
 pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
+/// The number of history lines kept in memory when spilling scrollback to disk.
+const SCROLLBACK_SPILL_RETAIN: usize = 50_000;
+/// The most lines moved to disk per wakeup, which bounds how long the terminal
+/// lock is held for.
+const SCROLLBACK_SPILL_BATCH: usize = 1_000;
+/// The in-memory history size at which all lines beyond [`SCROLLBACK_SPILL_RETAIN`]
+/// are moved to disk at once, because output arrives faster than the batches keep
+/// up with and alacritty would soon discard the oldest lines.
+const SCROLLBACK_SPILL_THRESHOLD: usize = 90_000;
 
 pub struct TerminalBuilder {
     terminal: Terminal,
@@ -345,7 +362,7 @@ impl TerminalBuilder {
         mut env: HashMap<String, String>,
         cursor_shape: CursorShape,
         alternate_scroll: AlternateScroll,
-        max_scroll_history_lines: Option<usize>,
+        scrollback_lines: Option<ScrollbackLines>,
         is_ssh_terminal: bool,
         window_id: u64,
         completion_tx: Option<Sender<Option<ExitStatus>>>,
@@ -437,9 +454,11 @@ impl TerminalBuilder {
             // cause excessive memory usage over time.
             MAX_SCROLL_HISTORY_LINES
         } else {
-            max_scroll_history_lines
-                .unwrap_or(DEFAULT_SCROLL_HISTORY_LINES)
-                .min(MAX_SCROLL_HISTORY_LINES)
+            match scrollback_lines {
+                Some(ScrollbackLines::Limited(lines)) => lines.min(MAX_SCROLL_HISTORY_LINES),
+                Some(ScrollbackLines::SpillToFile) => MAX_SCROLL_HISTORY_LINES,
+                None => DEFAULT_SCROLL_HISTORY_LINES,
+            }
         };
         let config = Config {
             scrolling_history,
@@ -479,6 +498,8 @@ impl TerminalBuilder {
         };
 
         let pty_info = PtyProcessInfo::new(&pty);
+        let inline_images = InlineImages::default();
+        let pty = InlineImagePty::new(pty, inline_images.clone(), TerminalBounds::default().into());
 
         //And connect them together
         let event_loop = EventLoop::new(
@@ -517,6 +538,10 @@ impl TerminalBuilder {
             hyperlink_regex_searches: RegexSearches::new(),
             vi_mode_enabled: false,
             is_ssh_terminal,
+            spill_scrollback_to_file: scrollback_lines == Some(ScrollbackLines::SpillToFile),
+            scrollback_spill: None,
+            scrollback_spill_task: None,
+            inline_images,
             last_mouse_move_time: Instant::now(),
             last_hyperlink_search_position: None,
             #[cfg(windows)]
@@ -527,10 +552,11 @@ impl TerminalBuilder {
                 env,
                 cursor_shape,
                 alternate_scroll,
-                max_scroll_history_lines,
+                scrollback_lines,
                 window_id,
             },
             child_exited: None,
+            ssh_host: None,
         };
 
         if cfg!(not(target_os = "windows")) && !activation_script.is_empty() && no_task {
@@ -547,6 +573,13 @@ impl TerminalBuilder {
         })
     }
 
+    /// Marks the terminal as running an SSH session to the given host from the
+    /// SSH config, rather than a local shell.
+    pub fn with_ssh_host(mut self, host: String) -> Self {
+        self.terminal.ssh_host = Some(host);
+        self
+    }
+
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(async move |terminal, cx| {
@@ -719,6 +752,10 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    spill_scrollback_to_file: bool,
+    scrollback_spill: Option<Arc<ScrollbackSpill>>,
+    scrollback_spill_task: Option<Task<()>>,
+    inline_images: InlineImages,
     last_mouse_move_time: Instant,
     last_hyperlink_search_position: Option<Point<Pixels>>,
     #[cfg(windows)]
@@ -726,6 +763,7 @@ pub struct Terminal {
     template: CopyTemplate,
     activation_script: Vec<String>,
     child_exited: Option<ExitStatus>,
+    ssh_host: Option<String>,
 }
 
 struct CopyTemplate {
@@ -733,7 +771,7 @@ struct CopyTemplate {
     env: HashMap<String, String>,
     cursor_shape: CursorShape,
     alternate_scroll: AlternateScroll,
-    max_scroll_history_lines: Option<usize>,
+    scrollback_lines: Option<ScrollbackLines>,
     window_id: u64,
 }
 
@@ -831,6 +869,7 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                self.spill_scrollback(cx);
                 cx.emit(Event::Wakeup);
 
                 if self.pty_info.has_changed() {
@@ -852,10 +891,79 @@ impl Terminal {
             }
             AlacTermEvent::ChildExit(error_code) => {
                 self.register_task_finished(Some(error_code), cx);
+                cx.emit(Event::ChildExited(error_code));
             }
         }
     }
 
+    /// When spilling scrollback to a file, moves the oldest lines of the in-memory
+    /// history to it before the history fills up and alacritty discards them.
+    fn spill_scrollback(&mut self, cx: &mut Context<Self>) {
+        if !self.spill_scrollback_to_file {
+            return;
+        }
+
+        let mut term = self.term.lock();
+        let history_size = term.history_size();
+        if history_size <= SCROLLBACK_SPILL_RETAIN {
+            return;
+        }
+        if self.scrollback_spill.is_none() {
+            self.scrollback_spill = ScrollbackSpill::new().map(Arc::new).log_err();
+        }
+        let Some(spill) = self.scrollback_spill.clone() else {
+            return;
+        };
+
+        let mut spill_count = history_size - SCROLLBACK_SPILL_RETAIN;
+        if history_size < SCROLLBACK_SPILL_THRESHOLD {
+            spill_count = spill_count.min(SCROLLBACK_SPILL_BATCH);
+        }
+        // Only the cells are copied while the lock is held; they're turned into
+        // text when written out in the background.
+        let topmost_line = term.topmost_line();
+        let grid = term.grid();
+        let rows = (0..spill_count)
+            .map(|ix| grid[Line(topmost_line.0 + ix as i32)].clone())
+            .collect::<Vec<_>>();
+
+        // Shrinking the history drops its oldest lines, which were copied above.
+        let grid = term.grid_mut();
+        grid.update_history(history_size - spill_count);
+        grid.update_history(MAX_SCROLL_HISTORY_LINES);
+        term.scroll_display(AlacScroll::Delta(0));
+        term.selection = None;
+        drop(term);
+
+        // Matches and selections point into the history, so they are now stale.
+        self.matches.clear();
+        self.selection_head = None;
+
+        // Writes are chained, so that lines end up in the file in order.
+        let previous_task = self.scrollback_spill_task.take();
+        self.scrollback_spill_task = Some(cx.background_spawn(async move {
+            if let Some(previous_task) = previous_task {
+                previous_task.await;
+            }
+            spill.append(&rows).log_err();
+        }));
+    }
+
+    /// The path of the temporary file holding the scrollback lines moved to disk.
+    pub fn spilled_scrollback_path(&self) -> Option<&Path> {
+        self.scrollback_spill.as_ref().map(|spill| spill.path())
+    }
+
+    /// The image printed with the iTerm2 inline image protocol under the given id.
+    pub fn inline_image(&self, image_id: usize) -> Option<InlineImage> {
+        self.inline_images.get(image_id)
+    }
+
+    /// Where a cell sits within an inline image, given the URI of its hyperlink.
+    pub fn inline_image_cell(&self, uri: &str) -> Option<InlineImageCell> {
+        self.inline_images.cell_for_uri(uri)
+    }
+
     pub fn selection_started(&self) -> bool {
         self.selection_phase == SelectionPhase::Selecting
     }
@@ -1439,6 +1547,15 @@ impl Terminal {
         term.bounds_to_string(start, end)
     }
 
+    /// Returns the text of a single grid line, which may be in the scrollback.
+    pub fn line_text(&self, line: Line) -> String {
+        let term = self.term.lock_unfair();
+        let line = line.clamp(term.topmost_line(), term.bottommost_line());
+        let start = AlacPoint::new(line, Column(0));
+        let end = AlacPoint::new(line, term.last_column());
+        term.bounds_to_string(start, end).trim_end().to_string()
+    }
+
     pub fn last_n_non_empty_lines(&self, n: usize) -> Vec<String> {
         let term = self.term.clone();
         let terminal = term.lock_unfair();
@@ -1718,7 +1835,10 @@ impl Terminal {
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index =
                     content_index_for_mouse(position, &self.last_content.terminal_bounds);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                if let Some(link) = self.last_content.cells[mouse_cell_index]
+                    .hyperlink()
+                    .filter(|link| !is_inline_image_uri(link.uri()))
+                {
                     cx.open_url(link.uri());
                 } else if e.modifiers.secondary() {
                     self.events
@@ -1808,6 +1928,12 @@ impl Terminal {
         })
     }
 
+    /// The host of the SSH session running in this terminal, if it was opened
+    /// to a host from the SSH config.
+    pub fn ssh_host(&self) -> Option<&str> {
+        self.ssh_host.as_deref()
+    }
+
     pub fn working_directory(&self) -> Option<PathBuf> {
         if self.is_ssh_terminal {
             // We can't yet reliably detect the working directory of a shell on the
@@ -1992,13 +2118,17 @@ impl Terminal {
             self.template.env.clone(),
             self.template.cursor_shape,
             self.template.alternate_scroll,
-            self.template.max_scroll_history_lines,
+            self.template.scrollback_lines,
             self.is_ssh_terminal,
             self.template.window_id,
             None,
             cx,
             self.activation_script.clone(),
         )
+        .map(|builder| match self.ssh_host.clone() {
+            Some(host) => builder.with_ssh_host(host),
+            None => builder,
+        })
     }
 }
 
@@ -2247,6 +2377,81 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_scrollback_spills_to_file(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let line_count = SCROLLBACK_SPILL_THRESHOLD + 5_000;
+        let (completion_tx, completion_rx) = smol::channel::unbounded();
+        let (program, args) = ShellBuilder::new(None, &Shell::System)
+            .build(Some("seq".to_owned()), &[line_count.to_string()]);
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new(
+                None,
+                None,
+                task::Shell::WithArguments {
+                    program,
+                    args,
+                    title_override: None,
+                },
+                HashMap::default(),
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(ScrollbackLines::SpillToFile),
+                false,
+                0,
+                Some(completion_tx),
+                cx,
+                vec![],
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        assert_eq!(
+            completion_rx.recv().await.unwrap(),
+            Some(ExitStatus::default())
+        );
+
+        // Each wakeup spills at most a batch of lines.
+        let spill_task = terminal.update(cx, |terminal, cx| {
+            let history_size = terminal.term.lock().history_size();
+            terminal.spill_scrollback(cx);
+            assert!(history_size - terminal.term.lock().history_size() <= SCROLLBACK_SPILL_BATCH);
+            while terminal.term.lock().history_size() > SCROLLBACK_SPILL_RETAIN {
+                terminal.spill_scrollback(cx);
+            }
+            terminal.scrollback_spill_task.take()
+        });
+        spill_task.expect("scrollback should have spilled").await;
+
+        let spill_path = terminal.read_with(cx, |terminal, _| {
+            terminal.spilled_scrollback_path().unwrap().to_path_buf()
+        });
+        let spilled = std::fs::read_to_string(spill_path).unwrap();
+        let spilled = spilled.lines().collect::<Vec<_>>();
+        assert!(spilled.len() > SCROLLBACK_SPILL_BATCH);
+        let expected = (1..=spilled.len())
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(spilled, expected);
+
+        // The in-memory history continues where the spilled lines end.
+        terminal.update(cx, |terminal, _| {
+            let term = terminal.term.lock();
+            assert!(term.history_size() <= SCROLLBACK_SPILL_RETAIN);
+            let topmost_line = term.topmost_line();
+            assert_eq!(
+                term.bounds_to_string(
+                    AlacPoint::new(topmost_line, Column(0)),
+                    AlacPoint::new(topmost_line, term.last_column()),
+                )
+                .trim_end(),
+                (spilled.len() + 1).to_string()
+            );
+        });
+    }
+
     #[test]
     fn test_rgb_for_index() {
         // Test every possible value in the color cube.
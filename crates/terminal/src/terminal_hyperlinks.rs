@@ -6,6 +6,8 @@ use alacritty_terminal::{
     term::search::{Match, RegexIter, RegexSearch},
 };
 use regex::Regex;
+
+use crate::inline_images::is_inline_image_uri;
 use std::{ops::Index, sync::LazyLock};
 
 const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`']+"#;
@@ -51,7 +53,10 @@ pub(super) fn find_from_grid_point<T: EventListener>(
     regex_searches: &mut RegexSearches,
 ) -> Option<(String, bool, Match)> {
     let grid = term.grid();
-    let link = grid.index(point).hyperlink();
+    let link = grid
+        .index(point)
+        .hyperlink()
+        .filter(|link| !is_inline_image_uri(link.uri()));
     let found_word = if let Some(ref url) = link {
         let mut min_index = point;
         loop {
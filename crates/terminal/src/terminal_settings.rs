@@ -8,8 +8,9 @@ use serde::{Deserialize, Serialize};
 
 pub use settings::AlternateScroll;
 use settings::{
-    CursorShapeContent, SettingsContent, ShowScrollbar, TerminalBlink, TerminalDockPosition,
-    TerminalLineHeight, TerminalSettingsContent, VenvSettings, WorkingDirectory,
+    CursorShapeContent, ScrollbackLinesContent, SettingsContent, ShowScrollbar, TerminalBlink,
+    TerminalDockPosition, TerminalLineHeight, TerminalSettingsContent, VenvSettings,
+    WorkingDirectory,
 };
 use task::Shell;
 use theme::FontFamilyName;
@@ -41,10 +42,21 @@ pub struct TerminalSettings {
     pub default_width: Pixels,
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
-    pub max_scroll_history_lines: Option<usize>,
+    pub scrollback_lines: Option<ScrollbackLines>,
     pub toolbar: Toolbar,
     pub scrollbar: ScrollbarSettings,
     pub minimum_contrast: f32,
+    pub dropdown_height: f32,
+}
+
+/// The amount of scrollback history a terminal keeps.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScrollbackLines {
+    /// Keep at most this many lines in memory, discarding older ones.
+    Limited(usize),
+    /// Keep a bounded history in memory, moving its oldest lines to a temporary
+    /// file instead of discarding them.
+    SpillToFile,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -102,7 +114,13 @@ impl settings::Settings for TerminalSettings {
             default_width: px(content.default_width.unwrap()),
             default_height: px(content.default_height.unwrap()),
             detect_venv: content.detect_venv.unwrap(),
-            max_scroll_history_lines: content.max_scroll_history_lines,
+            scrollback_lines: match content.scrollback_lines {
+                Some(ScrollbackLinesContent::Lines(lines)) => Some(ScrollbackLines::Limited(lines)),
+                Some(ScrollbackLinesContent::SpillToFile(_)) => Some(ScrollbackLines::SpillToFile),
+                None => content
+                    .max_scroll_history_lines
+                    .map(ScrollbackLines::Limited),
+            },
             toolbar: Toolbar {
                 breadcrumbs: content.toolbar.unwrap().breadcrumbs.unwrap(),
             },
@@ -110,6 +128,7 @@ impl settings::Settings for TerminalSettings {
                 show: content.scrollbar.unwrap().show,
             },
             minimum_contrast: content.minimum_contrast.unwrap(),
+            dropdown_height: content.dropdown_height.unwrap().clamp(0.1, 1.),
         }
     }
 
@@ -0,0 +1,276 @@
+use std::rc::Rc;
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use gpui::{
+    App, Bounds, Context, Entity, EntityId, Focusable, Global, PlatformDisplay, Render,
+    Subscription, Task, WeakEntity, Window, WindowBounds, WindowDecorations, WindowHandle,
+    WindowKind, WindowOptions, actions, div, size,
+};
+use project::Project;
+use settings::Settings;
+use terminal::{Event, Terminal, terminal_settings::TerminalSettings};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{Workspace, WorkspaceId};
+
+use crate::{TerminalView, default_working_directory};
+
+actions!(
+    terminal,
+    [
+        /// Toggles a terminal that drops down from the top of the display, over
+        /// other windows. Each project has its own, whose shell keeps running
+        /// while it's hidden.
+        ToggleDropdownTerminal
+    ]
+);
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(|workspace, _: &ToggleDropdownTerminal, window, cx| {
+            toggle(workspace, window, cx).detach_and_log_err(cx);
+        });
+    })
+    .detach();
+}
+
+/// The dropdown terminals, which are shared by all workspaces of a project and
+/// outlive the windows they're shown in.
+#[derive(Default)]
+struct DropdownTerminal {
+    terminals: HashMap<EntityId, ProjectTerminal>,
+    /// The window the dropdown is shown in, and the project whose terminal it shows.
+    window: Option<(WindowHandle<DropdownTerminalWindow>, EntityId)>,
+}
+
+impl Global for DropdownTerminal {}
+
+struct ProjectTerminal {
+    terminal: Entity<Terminal>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl DropdownTerminal {
+    /// Drops the project's terminal, hiding the dropdown if it shows it.
+    fn remove_terminal(project_id: EntityId, cx: &mut App) {
+        let state = cx.default_global::<Self>();
+        state.terminals.remove(&project_id);
+        if let Some((window, _)) = state
+            .window
+            .take_if(|(_, shown_project_id)| *shown_project_id == project_id)
+        {
+            window
+                .update(cx, |_, window, _| window.remove_window())
+                .log_err();
+        }
+    }
+}
+
+fn toggle(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let project_id = project.entity_id();
+
+    // The window may have been closed without us hearing about it, in which
+    // case the dropdown is shown again. When it shows another project's
+    // terminal, this project's terminal is shown in its place.
+    if let Some((dropdown_window, shown_project_id)) =
+        cx.default_global::<DropdownTerminal>().window.take()
+        && dropdown_window
+            .update(cx, |_, window, _| window.remove_window())
+            .is_ok()
+        && shown_project_id == project_id
+    {
+        return Task::ready(Ok(()));
+    }
+
+    // Show the dropdown on the display the workspace is on.
+    let display = window.display(cx);
+    let workspace_handle = workspace.weak_handle();
+    let workspace_id = workspace.database_id();
+
+    if let Some(terminal) = cx
+        .default_global::<DropdownTerminal>()
+        .terminals
+        .get(&project_id)
+        .map(|project_terminal| project_terminal.terminal.clone())
+    {
+        return Task::ready(show(
+            terminal,
+            workspace_handle,
+            workspace_id,
+            project.downgrade(),
+            display,
+            cx,
+        ));
+    }
+
+    let working_directory = default_working_directory(workspace, cx);
+    let create_terminal = project.update(cx, |project, cx| {
+        project.create_terminal_shell(working_directory, cx)
+    });
+    cx.spawn_in(window, async move |_, cx| {
+        let terminal = create_terminal.await?;
+        cx.update(|_, cx| {
+            let subscriptions = [
+                cx.subscribe(&terminal, move |_, event, cx| {
+                    if let Event::CloseTerminal = event {
+                        DropdownTerminal::remove_terminal(project_id, cx);
+                    }
+                }),
+                cx.observe_release(&project, move |_, cx| {
+                    DropdownTerminal::remove_terminal(project_id, cx);
+                }),
+            ];
+            cx.default_global::<DropdownTerminal>().terminals.insert(
+                project_id,
+                ProjectTerminal {
+                    terminal: terminal.clone(),
+                    _subscriptions: subscriptions,
+                },
+            );
+
+            show(
+                terminal,
+                workspace_handle,
+                workspace_id,
+                project.downgrade(),
+                display,
+                cx,
+            )
+        })?
+    })
+}
+
+fn show(
+    terminal: Entity<Terminal>,
+    workspace: WeakEntity<Workspace>,
+    workspace_id: Option<WorkspaceId>,
+    project: WeakEntity<Project>,
+    display: Option<Rc<dyn PlatformDisplay>>,
+    cx: &mut App,
+) -> Result<()> {
+    let display = display
+        .or_else(|| cx.primary_display())
+        .context("no display to show the dropdown terminal on")?;
+    let display_bounds = display.bounds();
+    let height = display_bounds.size.height * TerminalSettings::get_global(cx).dropdown_height;
+    let options = WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(Bounds::new(
+            display_bounds.origin,
+            size(display_bounds.size.width, height),
+        ))),
+        titlebar: None,
+        focus: true,
+        show: true,
+        kind: WindowKind::PopUp,
+        is_movable: false,
+        is_resizable: false,
+        is_minimizable: false,
+        display_id: Some(display.id()),
+        window_decorations: Some(WindowDecorations::Client),
+        ..Default::default()
+    };
+
+    let project_id = project.entity_id();
+    let dropdown_window = cx.open_window(options, |window, cx| {
+        let terminal_view =
+            cx.new(|cx| TerminalView::new(terminal, workspace, workspace_id, project, window, cx));
+        window.focus(&terminal_view.focus_handle(cx));
+        cx.new(|_| DropdownTerminalWindow { terminal_view })
+    })?;
+    cx.default_global::<DropdownTerminal>().window = Some((dropdown_window, project_id));
+    Ok(())
+}
+
+/// The root view of the window the dropdown terminal is shown in. A new one is
+/// created every time the dropdown is shown, for the same terminal.
+struct DropdownTerminalWindow {
+    terminal_view: Entity<TerminalView>,
+}
+
+impl Render for DropdownTerminalWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .bg(cx.theme().colors().terminal_background)
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .on_action(|_: &ToggleDropdownTerminal, window, cx| {
+                cx.default_global::<DropdownTerminal>().window = None;
+                window.remove_window();
+            })
+            .child(self.terminal_view.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+    use workspace::AppState;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_toggling_dropdown_terminal(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let app_state = cx.update(AppState::test);
+        cx.update(|cx| {
+            terminal::init(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            Project::init_settings(cx);
+            language::init(cx);
+        });
+        let project_a = Project::test(app_state.fs.clone(), [], cx).await;
+        let project_b = Project::test(app_state.fs.clone(), [], cx).await;
+        let window_a =
+            cx.add_window(|window, cx| Workspace::test_new(project_a.clone(), window, cx));
+        let window_b =
+            cx.add_window(|window, cx| Workspace::test_new(project_b.clone(), window, cx));
+
+        async fn toggle_in(window: WindowHandle<Workspace>, cx: &mut TestAppContext) {
+            window
+                .update(cx, |workspace, window, cx| toggle(workspace, window, cx))
+                .unwrap()
+                .await
+                .unwrap();
+        }
+        fn shown_terminal(cx: &mut TestAppContext) -> Option<(Entity<Terminal>, EntityId)> {
+            cx.update(|cx| {
+                let (window, project_id) = cx.default_global::<DropdownTerminal>().window?;
+                let terminal = window
+                    .read(cx)
+                    .ok()?
+                    .terminal_view
+                    .read(cx)
+                    .terminal()
+                    .clone();
+                Some((terminal, project_id))
+            })
+        }
+
+        toggle_in(window_a, cx).await;
+        let (terminal_a, shown_project_id) = shown_terminal(cx).unwrap();
+        assert_eq!(shown_project_id, project_a.entity_id());
+
+        toggle_in(window_a, cx).await;
+        assert!(shown_terminal(cx).is_none());
+
+        // Toggling from another project shows that project's terminal.
+        toggle_in(window_b, cx).await;
+        let (terminal_b, shown_project_id) = shown_terminal(cx).unwrap();
+        assert_eq!(shown_project_id, project_b.entity_id());
+        assert_ne!(terminal_a, terminal_b);
+
+        // Even when the other project's terminal is shown, and each project's
+        // terminal is kept while hidden.
+        toggle_in(window_a, cx).await;
+        let (terminal, shown_project_id) = shown_terminal(cx).unwrap();
+        assert_eq!(shown_project_id, project_a.entity_id());
+        assert_eq!(terminal, terminal_a);
+    }
+}
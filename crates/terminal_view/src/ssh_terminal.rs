@@ -0,0 +1,427 @@
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Task,
+    WeakEntity, Window, actions,
+};
+use paths::{global_ssh_config_file, user_ssh_config_file};
+use picker::{Picker, PickerDelegate};
+use remote::ssh_config::parse_ssh_config_hosts;
+use smol::Timer;
+use task::RevealStrategy;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace, item::ItemEvent};
+
+use crate::{TerminalView, terminal_panel::TerminalPanel};
+
+actions!(
+    terminal,
+    [
+        /// Opens a terminal with an SSH session to a host from the SSH config.
+        NewRemoteTerminal
+    ]
+);
+
+/// The exit code of `ssh` when the connection failed or dropped, as opposed to
+/// the exit code of the remote shell.
+const SSH_CONNECTION_ERROR_CODE: i32 = 255;
+/// How long to wait before reconnecting a dropped session.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+/// How many times in a row to reconnect sessions that drop shortly after being
+/// opened, such as when the host is unreachable.
+const MAX_RECONNECT_ATTEMPTS: usize = 5;
+/// How long a session has to last for its drop to not count against
+/// [`MAX_RECONNECT_ATTEMPTS`].
+const ESTABLISHED_SESSION_DURATION: Duration = Duration::from_secs(30);
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(|workspace, _: &NewRemoteTerminal, window, cx| {
+            toggle(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let mut hosts = BTreeSet::new();
+        for path in [global_ssh_config_file().to_owned(), user_ssh_config_file()] {
+            // It's fine for either of the files to not exist.
+            if let Ok(contents) = fs.load(&path).await {
+                hosts.extend(parse_ssh_config_hosts(&contents));
+            }
+        }
+        workspace.update_in(cx, |workspace, window, cx| {
+            let workspace_handle = cx.weak_entity();
+            workspace.toggle_modal(window, cx, |window, cx| {
+                SshHostPicker::new(hosts, workspace_handle, window, cx)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn open_ssh_terminal(
+    host: String,
+    workspace: &WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    workspace
+        .update(cx, |workspace, cx| {
+            let Some(terminal_panel) = workspace.panel::<TerminalPanel>(cx) else {
+                return;
+            };
+            terminal_panel
+                .update(cx, |terminal_panel, cx| {
+                    terminal_panel.add_terminal(
+                        RevealStrategy::Always,
+                        window,
+                        cx,
+                        move |project, cx| Task::ready(project.create_ssh_host_terminal(host, cx)),
+                    )
+                })
+                .detach_and_log_err(cx);
+        })
+        .log_err();
+}
+
+/// Reconnection state of a terminal view's SSH session to a host from the SSH
+/// config.
+pub(crate) struct SshReconnect {
+    attempts: usize,
+    connected_at: Instant,
+    _task: Option<Task<()>>,
+}
+
+impl SshReconnect {
+    pub(crate) fn new() -> Self {
+        Self {
+            attempts: 0,
+            connected_at: Instant::now(),
+            _task: None,
+        }
+    }
+
+    /// Records the session exiting, and returns whether to reconnect it.
+    fn should_reconnect(&mut self, exit_code: i32, now: Instant) -> bool {
+        if exit_code != SSH_CONNECTION_ERROR_CODE {
+            return false;
+        }
+        if now.duration_since(self.connected_at) >= ESTABLISHED_SESSION_DURATION {
+            self.attempts = 0;
+        }
+        if self.attempts >= MAX_RECONNECT_ATTEMPTS {
+            return false;
+        }
+        self.attempts += 1;
+        true
+    }
+}
+
+impl TerminalView {
+    /// Opens a new session to the same host when the terminal's SSH session was
+    /// dropped, rather than closed by the remote shell exiting.
+    pub(crate) fn reconnect_dropped_ssh_session(
+        &mut self,
+        exit_code: i32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.terminal.read(cx).ssh_host().is_none()
+            || !self
+                .ssh_reconnect
+                .should_reconnect(exit_code, Instant::now())
+        {
+            return;
+        }
+
+        let terminal = self.terminal.clone();
+        let project = self.project.clone();
+        self.ssh_reconnect._task = Some(cx.spawn_in(window, async move |terminal_view, cx| {
+            Timer::after(RECONNECT_DELAY).await;
+            terminal_view
+                .update_in(cx, |terminal_view, window, cx| {
+                    if terminal_view.terminal != terminal {
+                        return Ok(());
+                    }
+                    let new_terminal = project.update(cx, |project, cx| {
+                        project.clone_terminal(&terminal, cx, || None)
+                    })??;
+                    terminal_view.ssh_reconnect.connected_at = Instant::now();
+                    terminal_view.set_terminal(new_terminal, window, cx);
+                    cx.emit(ItemEvent::UpdateTab);
+                    cx.notify();
+                    anyhow::Ok(())
+                })
+                .log_err();
+        }));
+    }
+}
+
+struct SshHostPicker {
+    picker: Entity<Picker<SshHostPickerDelegate>>,
+}
+
+impl SshHostPicker {
+    fn new(
+        hosts: BTreeSet<String>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let candidates = hosts
+            .iter()
+            .enumerate()
+            .map(|(id, host)| StringMatchCandidate::new(id, host))
+            .collect();
+        let delegate = SshHostPickerDelegate {
+            ssh_host_picker: cx.entity().downgrade(),
+            workspace,
+            candidates,
+            matches: Vec::new(),
+            query: String::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for SshHostPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SshHostPicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for SshHostPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SshHostPicker {}
+impl ModalView for SshHostPicker {}
+
+struct SshHostPickerDelegate {
+    ssh_host_picker: WeakEntity<SshHostPicker>,
+    workspace: WeakEntity<Workspace>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    query: String,
+    selected_index: usize,
+}
+
+impl SshHostPickerDelegate {
+    /// The host to connect to on confirmation. Hosts that aren't in the SSH
+    /// config, such as `user@host`, are connected to as typed.
+    fn selected_host(&self) -> Option<String> {
+        match self.matches.get(self.selected_index) {
+            Some(mat) => Some(mat.string.clone()),
+            None => Some(self.query.trim().to_string()).filter(|query| !query.is_empty()),
+        }
+    }
+}
+
+impl PickerDelegate for SshHostPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a host or type one to connect to…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.query.is_empty() {
+            Some("No hosts in the SSH config".into())
+        } else {
+            Some(format!("Press enter to connect to {}", self.query.trim()).into())
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(host) = self.selected_host() {
+            open_ssh_terminal(host, &self.workspace, window, cx);
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.ssh_host_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        self.query = query.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Server).color(Color::Muted))
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, VisualTestContext};
+    use workspace::AppState;
+
+    use super::*;
+
+    #[test]
+    fn test_reconnecting_dropped_sessions() {
+        let mut reconnect = SshReconnect::new();
+        let connected_at = reconnect.connected_at;
+
+        // The remote shell exiting doesn't mean that the connection dropped.
+        assert!(!reconnect.should_reconnect(0, connected_at));
+        assert!(!reconnect.should_reconnect(1, connected_at));
+
+        // Sessions that keep dropping right after being opened are given up on.
+        for _ in 0..MAX_RECONNECT_ATTEMPTS {
+            assert!(reconnect.should_reconnect(SSH_CONNECTION_ERROR_CODE, connected_at));
+        }
+        assert!(!reconnect.should_reconnect(SSH_CONNECTION_ERROR_CODE, connected_at));
+
+        // Unless they were established for a while before dropping.
+        assert!(reconnect.should_reconnect(
+            SSH_CONNECTION_ERROR_CODE,
+            connected_at + ESTABLISHED_SESSION_DURATION
+        ));
+        assert_eq!(reconnect.attempts, 1);
+    }
+
+    #[gpui::test]
+    async fn test_ssh_host_picker(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+        });
+        let hosts = BTreeSet::from_iter(["dev-box", "example.com", "staging"].map(String::from));
+        let (ssh_host_picker, cx) = cx.add_window_view(|window, cx| {
+            SshHostPicker::new(hosts, WeakEntity::new_invalid(), window, cx)
+        });
+
+        let search = |query: &str, cx: &mut VisualTestContext| {
+            ssh_host_picker.update_in(cx, |ssh_host_picker, window, cx| {
+                ssh_host_picker.picker.update(cx, |picker, cx| {
+                    picker.update_matches(query.to_string(), window, cx)
+                })
+            });
+            cx.run_until_parked();
+            ssh_host_picker.read_with(cx, |ssh_host_picker, cx| {
+                let delegate = &ssh_host_picker.picker.read(cx).delegate;
+                let matches = delegate
+                    .matches
+                    .iter()
+                    .map(|mat| mat.string.clone())
+                    .collect::<Vec<_>>();
+                (matches, delegate.selected_host())
+            })
+        };
+
+        assert_eq!(
+            search("", cx),
+            (
+                vec!["dev-box".into(), "example.com".into(), "staging".into()],
+                Some("dev-box".into())
+            )
+        );
+        assert_eq!(
+            search("stag", cx),
+            (vec!["staging".into()], Some("staging".into()))
+        );
+        // Hosts that aren't in the SSH config are connected to as typed.
+        assert_eq!(
+            search(" user@10.0.0.1 ", cx),
+            (Vec::new(), Some("user@10.0.0.1".into()))
+        );
+    }
+}
@@ -1,12 +1,12 @@
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, RenderImage, ShapedLine, Size, StatefulInteractiveElement,
+    StrikethroughStyle, Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity,
+    WhiteSpace, Window, div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
@@ -23,6 +23,7 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
+    inline_images::{InlineImage, is_inline_image_uri},
     terminal_settings::TerminalSettings,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
@@ -32,7 +33,7 @@ use util::ResultExt;
 use workspace::Workspace;
 
 use std::mem;
-use std::{fmt::Debug, ops::RangeInclusive, rc::Rc};
+use std::{fmt::Debug, ops::RangeInclusive, rc::Rc, sync::Arc};
 
 use crate::{BlockContext, BlockProperties, ContentMode, TerminalMode, TerminalView};
 
@@ -41,6 +42,7 @@ pub struct LayoutState {
     hitbox: Hitbox,
     batched_text_runs: Vec<BatchedTextRun>,
     rects: Vec<LayoutRect>,
+    images: Vec<LayoutImage>,
     relative_highlighted_ranges: Vec<(RangeInclusive<AlacPoint>, Hsla)>,
     cursor: Option<CursorLayout>,
     background_color: Hsla,
@@ -54,6 +56,32 @@ pub struct LayoutState {
     content_mode: ContentMode,
 }
 
+/// An image printed with the iTerm2 inline image protocol, placed on the grid.
+struct LayoutImage {
+    line: i32,
+    column: usize,
+    size: Size<Pixels>,
+    data: Arc<RenderImage>,
+}
+
+impl LayoutImage {
+    fn paint(&self, origin: Point<Pixels>, dimensions: &TerminalBounds, window: &mut Window) {
+        let position = Point::new(
+            origin.x + self.column as f32 * dimensions.cell_width,
+            origin.y + self.line as f32 * dimensions.line_height,
+        );
+        window
+            .paint_image(
+                Bounds::new(position, self.size),
+                Corners::default(),
+                self.data.clone(),
+                0,
+                false,
+            )
+            .log_err();
+    }
+}
+
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
 struct DisplayCursor {
     line: i32,
@@ -305,6 +333,54 @@ impl TerminalElement {
         .track_focus(&focus)
     }
 
+    /// Finds the inline images covering the visible cells, along with the display
+    /// line and column of their top left corner.
+    fn inline_image_placements(
+        cells: &[IndexedCell],
+        display_offset: usize,
+        terminal: &Terminal,
+    ) -> Vec<(InlineImage, i32, usize)> {
+        let mut placements = Vec::new();
+        let mut placed = Vec::new();
+        for cell in cells {
+            let Some(image_cell) = cell
+                .hyperlink()
+                .and_then(|link| terminal.inline_image_cell(link.uri()))
+            else {
+                continue;
+            };
+            // Cells come in reading order, so the first cell of an image is the
+            // leftmost one of its topmost visible row.
+            let line = cell.point.line.0 + display_offset as i32 - image_cell.row as i32;
+            if placed.contains(&(image_cell.image_id, line)) {
+                continue;
+            }
+            placed.push((image_cell.image_id, line));
+            if let Some(image) = terminal.inline_image(image_cell.image_id) {
+                placements.push((image, line, cell.point.column.0));
+            }
+        }
+        placements
+    }
+
+    /// The size of an inline image: its block of cells, or the largest size with the
+    /// image's aspect ratio that fits into them.
+    fn inline_image_size(image: &InlineImage, dimensions: &TerminalBounds) -> Size<Pixels> {
+        let cells = size(
+            dimensions.cell_width * image.columns as f32,
+            dimensions.line_height * image.rows as f32,
+        );
+        if !image.preserve_aspect_ratio {
+            return cells;
+        }
+        let scale = (f32::from(cells.width) / image.width as f32)
+            .min(f32::from(cells.height) / image.height as f32);
+        size(
+            px(image.width as f32 * scale),
+            px(image.height as f32 * scale),
+        )
+    }
+
     //Vec<Range<AlacPoint>> -> Clip out the parts of the ranges
 
     pub fn layout_grid(
@@ -544,7 +620,10 @@ impl TerminalElement {
         }
 
         let underline = (flags.intersects(Flags::ALL_UNDERLINES)
-            || indexed.cell.hyperlink().is_some())
+            || indexed
+                .cell
+                .hyperlink()
+                .is_some_and(|link| !is_inline_image_uri(link.uri())))
         .then(|| UnderlineStyle {
             color: Some(fg),
             thickness: Pixels::from(1.0),
@@ -1019,6 +1098,11 @@ impl Element for TerminalElement {
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
+                let image_placements = TerminalElement::inline_image_placements(
+                    cells,
+                    display_offset,
+                    self.terminal.read(cx),
+                );
 
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
@@ -1121,6 +1205,19 @@ impl Element for TerminalElement {
                     )
                 };
 
+                let images = image_placements
+                    .into_iter()
+                    .filter_map(|(image, line, column)| {
+                        let data = image.image.clone().use_render_image(window, cx)?;
+                        Some(LayoutImage {
+                            line,
+                            column,
+                            size: TerminalElement::inline_image_size(&image, &dimensions),
+                            data,
+                        })
+                    })
+                    .collect();
+
                 let block_below_cursor_element = if let Some(block) = &self.block_below_cursor {
                     let terminal = self.terminal.read(cx);
                     if terminal.last_content.display_offset == 0 {
@@ -1160,6 +1257,7 @@ impl Element for TerminalElement {
                     background_color,
                     dimensions,
                     rects,
+                    images,
                     relative_highlighted_ranges,
                     mode,
                     display_offset,
@@ -1251,6 +1349,10 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for image in &layout.images {
+                        image.paint(origin, &layout.dimensions, window);
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -1470,7 +1572,10 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
         return false;
     }
 
-    if cell.hyperlink().is_some() {
+    if cell
+        .hyperlink()
+        .is_some_and(|link| !is_inline_image_uri(link.uri()))
+    {
         return false;
     }
 
@@ -1,4 +1,6 @@
+pub mod dropdown_terminal;
 mod persistence;
+mod ssh_terminal;
 pub mod terminal_element;
 pub mod terminal_panel;
 mod terminal_path_like_target;
@@ -16,6 +18,7 @@ use gpui::{
 use persistence::TERMINAL_DB;
 use project::{Project, search::SearchQuery};
 use schemars::JsonSchema;
+use ssh_terminal::SshReconnect;
 use task::TaskId;
 use terminal::{
     Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
@@ -40,8 +43,8 @@ use ui::{
 };
 use util::ResultExt;
 use workspace::{
-    CloseActiveItem, NewCenterTerminal, NewTerminal, ToolbarItemLocation, Workspace, WorkspaceId,
-    delete_unloaded_items,
+    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenOptions, ToolbarItemLocation, Workspace,
+    WorkspaceId, delete_unloaded_items,
     item::{
         BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams, TabTooltipContent,
     },
@@ -88,13 +91,17 @@ actions!(
     terminal,
     [
         /// Reruns the last executed task in the terminal.
-        RerunTask
+        RerunTask,
+        /// Opens the terminal's earlier output that was moved out of memory to disk.
+        OpenSpilledScrollback
     ]
 );
 
 pub fn init(cx: &mut App) {
     assistant_slash_command::init(cx);
     terminal_panel::init(cx);
+    dropdown_terminal::init(cx);
+    ssh_terminal::init(cx);
     terminal::init(cx);
 
     register_serializable_item::<TerminalView>(cx);
@@ -141,6 +148,7 @@ pub struct TerminalView {
     scroll_top: Pixels,
     scroll_handle: TerminalScrollHandle,
     ime_state: Option<ImeState>,
+    ssh_reconnect: SshReconnect,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -262,6 +270,7 @@ impl TerminalView {
             scroll_handle,
             cwd_serialized: false,
             ime_state: None,
+            ssh_reconnect: SshReconnect::new(),
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -481,6 +490,29 @@ impl TerminalView {
         window.dispatch_action(Box::new(task), cx);
     }
 
+    fn open_spilled_scrollback(
+        &mut self,
+        _: &OpenSpilledScrollback,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(path) = self
+            .terminal
+            .read(cx)
+            .spilled_scrollback_path()
+            .map(|path| path.to_path_buf())
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(path, OpenOptions::default(), window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
     fn clear(&mut self, _: &Clear, _: &mut Window, cx: &mut Context<Self>) {
         self.scroll_top = px(0.);
         self.terminal.update(cx, |term, _| term.clear());
@@ -881,6 +913,10 @@ fn subscribe_for_terminal_events(
 
             match event {
                 Event::Wakeup => {
+                    // Output can move the cursor, which accessibility tools should follow.
+                    if terminal_view.focus_handle.is_focused(window) {
+                        window.invalidate_character_coordinates();
+                    }
                     cx.notify();
                     cx.emit(Event::Wakeup);
                     cx.emit(ItemEvent::UpdateTab);
@@ -962,6 +998,9 @@ fn subscribe_for_terminal_events(
                 },
                 Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
                 Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
+                Event::ChildExited(exit_code) => {
+                    terminal_view.reconnect_dropped_ssh_session(*exit_code, window, cx)
+                }
                 Event::SelectionsChanged => {
                     window.invalidate_character_coordinates();
                     cx.emit(SearchEvent::ActiveMatchChanged)
@@ -1079,6 +1118,7 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_action(cx.listener(TerminalView::rerun_task))
+            .on_action(cx.listener(TerminalView::open_spilled_scrollback))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1537,6 +1577,14 @@ impl SearchableItem for TerminalView {
     ) {
         // Replacement is not supported in terminal view, so this is a no-op.
     }
+
+    fn include_in_project_search(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn match_preview(&self, mat: &Self::Match, cx: &App) -> Option<String> {
+        Some(self.terminal().read(cx).line_text(mat.start().line))
+    }
 }
 
 ///Gets the working directory for the given workspace, respecting the user's settings.
@@ -1388,6 +1388,23 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    /// Returns the transactions that can be undone, from oldest to newest.
+    pub fn undo_transactions(&self) -> impl DoubleEndedIterator<Item = &Transaction> {
+        self.history
+            .undo_stack
+            .iter()
+            .map(|entry| &entry.transaction)
+    }
+
+    /// Returns the transactions that can be redone, in the order they would be redone.
+    pub fn redo_transactions(&self) -> impl DoubleEndedIterator<Item = &Transaction> {
+        self.history
+            .redo_stack
+            .iter()
+            .rev()
+            .map(|entry| &entry.transaction)
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use gpui::{FontStyle, FontWeight, HighlightStyle, Hsla, WindowBackgroundAppearance, hsla};
 
 use crate::{
-    AccentColors, Appearance, PlayerColors, StatusColors, StatusColorsRefinement, SyntaxTheme,
-    SystemColors, Theme, ThemeColors, ThemeColorsRefinement, ThemeFamily, ThemeStyles,
+    AccentColors, Appearance, ComponentStyles, PlayerColors, StatusColors, StatusColorsRefinement,
+    SyntaxTheme, SystemColors, Theme, ThemeColors, ThemeColorsRefinement, ThemeFamily, ThemeStyles,
     default_color_scales,
 };
 
@@ -343,6 +343,7 @@ pub(crate) fn zed_default_dark() -> Theme {
                     ("variant".into(), HighlightStyle::default()),
                 ],
             }),
+            components: ComponentStyles::default(),
         },
     }
 }
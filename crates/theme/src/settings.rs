@@ -8,7 +8,7 @@ use collections::HashMap;
 use derive_more::{Deref, DerefMut};
 use gpui::{
     App, Context, Font, FontFallbacks, FontStyle, FontWeight, Global, Pixels, Subscription, Window,
-    px,
+    WindowBackgroundAppearance, px,
 };
 use refineable::Refineable;
 use schemars::JsonSchema;
@@ -141,6 +141,11 @@ pub struct ThemeSettings {
     pub ui_density: UiDensity,
     /// The amount of fading applied to unnecessary code.
     pub unnecessary_code_fade: f32,
+    /// The window background appearance, overriding the one set by the theme.
+    pub window_background: Option<WindowBackgroundAppearance>,
+    /// The opacity applied to the window's background surfaces when the
+    /// window background isn't opaque.
+    pub window_background_opacity: f32,
 }
 
 impl ThemeSettings {
@@ -615,6 +620,43 @@ impl ThemeSettings {
             ThemeSettings::modify_theme(&mut theme, theme_overrides);
             self.active_theme = Arc::new(theme);
         }
+
+        self.apply_window_background();
+    }
+
+    fn apply_window_background(&mut self) {
+        let opacity = self.window_background_opacity;
+        if self.window_background.is_none() && opacity >= 1.0 {
+            return;
+        }
+
+        let mut theme = (*self.active_theme).clone();
+        if let Some(window_background) = self.window_background {
+            theme.styles.window_background_appearance = window_background;
+        }
+        if theme.styles.window_background_appearance != WindowBackgroundAppearance::Opaque
+            && opacity < 1.0
+        {
+            let colors = &mut theme.styles.colors;
+            for color in [
+                &mut colors.background,
+                &mut colors.surface_background,
+                &mut colors.title_bar_background,
+                &mut colors.title_bar_inactive_background,
+                &mut colors.status_bar_background,
+                &mut colors.toolbar_background,
+                &mut colors.tab_bar_background,
+                &mut colors.tab_inactive_background,
+                &mut colors.tab_active_background,
+                &mut colors.panel_background,
+                &mut colors.editor_background,
+                &mut colors.editor_gutter_background,
+                &mut colors.terminal_background,
+            ] {
+                *color = color.opacity(opacity);
+            }
+        }
+        self.active_theme = Arc::new(theme);
     }
 
     fn modify_theme(base_theme: &mut Theme, theme_overrides: &settings::ThemeStyleContent) {
@@ -634,6 +676,10 @@ impl ThemeSettings {
             base_theme.styles.syntax.clone(),
             syntax_overrides(&theme_overrides),
         );
+        base_theme
+            .styles
+            .components
+            .merge(&theme_overrides.components);
     }
 
     /// Switches to the icon theme with the given name, if it exists.
@@ -813,6 +859,11 @@ impl settings::Settings for ThemeSettings {
             icon_theme_selection: Some(icon_theme_selection),
             ui_density: content.ui_density.unwrap_or_default().into(),
             unnecessary_code_fade: content.unnecessary_code_fade.unwrap().clamp(0.0, 0.9),
+            window_background: content.window_background.map(Into::into),
+            window_background_opacity: content
+                .window_background_opacity
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0),
         };
         this.apply_theme_overrides();
         this
@@ -1,5 +1,6 @@
 mod accents;
 mod colors;
+mod components;
 mod players;
 mod status;
 mod syntax;
@@ -7,6 +8,7 @@ mod system;
 
 pub use accents::*;
 pub use colors::*;
+pub use components::*;
 pub use players::*;
 pub use status::*;
 pub use syntax::*;
@@ -6,8 +6,8 @@ use std::sync::Arc;
 use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
 use crate::{
-    AccentColors, ActiveTheme, PlayerColors, StatusColors, StatusColorsRefinement, SyntaxTheme,
-    SystemColors,
+    AccentColors, ActiveTheme, ComponentStyles, PlayerColors, StatusColors, StatusColorsRefinement,
+    SyntaxTheme, SystemColors,
 };
 
 #[derive(Refineable, Clone, Debug, PartialEq)]
@@ -568,6 +568,8 @@ pub struct ThemeStyles {
     pub player: PlayerColors,
 
     pub syntax: Arc<SyntaxTheme>,
+
+    pub components: ComponentStyles,
 }
 
 #[cfg(test)]
@@ -0,0 +1,105 @@
+use gpui::{Hsla, Pixels, px};
+
+use crate::try_parse_color;
+
+/// Styles for specific UI components.
+///
+/// Unlike [`ThemeColors`](crate::ThemeColors), which are shared across many parts
+/// of the UI, each of these only applies to a single component, letting themes and
+/// `theme_overrides` retarget it without affecting anything else. Components fall
+/// back to their usual styling for anything left unset.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentStyles {
+    /// The bar holding a pane's tabs.
+    pub tab_bar: ComponentStyle,
+    /// The bar along the bottom of the window.
+    pub status_bar: ComponentStyle,
+    /// Popovers, such as menus and hover cards.
+    pub popover: ComponentStyle,
+}
+
+/// The style of a single UI component.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ComponentStyle {
+    /// The background color of the component.
+    pub background: Option<Hsla>,
+    /// The color of the component's borders.
+    pub border: Option<Hsla>,
+    /// The padding around the component's contents.
+    pub padding: Option<Pixels>,
+}
+
+impl ComponentStyles {
+    /// Returns the [`ComponentStyles`] described by the given content.
+    pub fn from_content(content: &settings::ComponentStylesContent) -> Self {
+        let mut styles = Self::default();
+        styles.merge(content);
+        styles
+    }
+
+    /// Merges the given component styles into this [`ComponentStyles`] instance.
+    pub fn merge(&mut self, content: &settings::ComponentStylesContent) {
+        for (style, content) in [
+            (&mut self.tab_bar, &content.tab_bar),
+            (&mut self.status_bar, &content.status_bar),
+            (&mut self.popover, &content.popover),
+        ] {
+            if let Some(content) = content {
+                style.merge(content);
+            }
+        }
+    }
+}
+
+impl ComponentStyle {
+    fn merge(&mut self, content: &settings::ComponentStyleContent) {
+        let parse_color =
+            |color: &Option<String>| color.as_ref().and_then(|color| try_parse_color(color).ok());
+        if let Some(background) = parse_color(&content.background) {
+            self.background = Some(background);
+        }
+        if let Some(border) = parse_color(&content.border) {
+            self.border = Some(border);
+        }
+        if let Some(padding) = content.padding {
+            self.padding = Some(px(padding));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::rgba;
+
+    use super::*;
+
+    #[test]
+    fn test_merge_component_styles() {
+        let mut styles = ComponentStyles::from_content(&settings::ComponentStylesContent {
+            tab_bar: Some(settings::ComponentStyleContent {
+                background: Some("#ff0000ff".to_string()),
+                padding: Some(4.),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        styles.merge(&settings::ComponentStylesContent {
+            tab_bar: Some(settings::ComponentStyleContent {
+                border: Some("#00ff00ff".to_string()),
+                background: Some("not a color".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            styles.tab_bar,
+            ComponentStyle {
+                background: Some(rgba(0xff0000ff).into()),
+                border: Some(rgba(0x00ff00ff).into()),
+                padding: Some(px(4.)),
+            }
+        );
+        assert_eq!(styles.popover, ComponentStyle::default());
+    }
+}
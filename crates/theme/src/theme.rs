@@ -250,6 +250,7 @@ impl ThemeFamily {
                 status: refined_status_colors,
                 player: refined_player_colors,
                 syntax: syntax_theme,
+                components: ComponentStyles::from_content(&theme.style.components),
             },
         }
     }
@@ -330,6 +331,12 @@ impl Theme {
         &self.styles.status
     }
 
+    /// Returns the [`ComponentStyles`] for the theme.
+    #[inline(always)]
+    pub fn components(&self) -> &ComponentStyles {
+        &self.styles.components
+    }
+
     /// Returns the color for the syntax node with the given name.
     #[inline(always)]
     pub fn syntax_color(&self, name: &str) -> Hsla {
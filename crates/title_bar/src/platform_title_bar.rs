@@ -2,9 +2,12 @@ use gpui::{
     AnyElement, Context, Decorations, Entity, Hsla, InteractiveElement, IntoElement, MouseButton,
     ParentElement, Pixels, StatefulInteractiveElement, Styled, Window, WindowControlArea, div, px,
 };
+use settings::Settings as _;
 use smallvec::SmallVec;
 use std::mem;
+use theme::{ThemeSettings, UiDensity};
 use ui::prelude::*;
+use workspace::PROJECT_COLOR_OPACITY;
 
 use crate::{
     platforms::{platform_linux, platform_mac, platform_windows},
@@ -17,6 +20,8 @@ pub struct PlatformTitleBar {
     children: SmallVec<[AnyElement; 2]>,
     should_move: bool,
     system_window_tabs: Entity<SystemWindowTabs>,
+    tint: Option<Hsla>,
+    native: bool,
 }
 
 impl PlatformTitleBar {
@@ -30,22 +35,34 @@ impl PlatformTitleBar {
             children: SmallVec::new(),
             should_move: false,
             system_window_tabs,
+            tint: None,
+            native: false,
         }
     }
 
+    /// Renders the title bar as a toolbar at the top of the window's content, for windows whose
+    /// title bar is drawn by the platform.
+    pub fn native(mut self, native: bool) -> Self {
+        self.native = native;
+        self
+    }
+
     #[cfg(not(target_os = "windows"))]
-    pub fn height(window: &mut Window) -> Pixels {
-        (1.75 * window.rem_size()).max(px(34.))
+    pub fn height(window: &mut Window, cx: &App) -> Pixels {
+        match ThemeSettings::get_global(cx).ui_density {
+            UiDensity::Compact => (1.5 * window.rem_size()).max(px(28.)),
+            UiDensity::Default | UiDensity::Comfortable => (1.75 * window.rem_size()).max(px(34.)),
+        }
     }
 
     #[cfg(target_os = "windows")]
-    pub fn height(_window: &mut Window) -> Pixels {
+    pub fn height(_window: &mut Window, _cx: &App) -> Pixels {
         // todo(windows) instead of hard coded size report the actual size to the Windows platform API
         px(32.)
     }
 
     pub fn title_bar_color(&self, window: &mut Window, cx: &mut Context<Self>) -> Hsla {
-        if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        let color = if cfg!(any(target_os = "linux", target_os = "freebsd")) {
             if window.is_window_active() && !self.should_move {
                 cx.theme().colors().title_bar_background
             } else {
@@ -53,9 +70,18 @@ impl PlatformTitleBar {
             }
         } else {
             cx.theme().colors().title_bar_background
+        };
+        match self.tint {
+            Some(tint) => color.blend(tint.opacity(PROJECT_COLOR_OPACITY)),
+            None => color,
         }
     }
 
+    /// Sets the color the title bar is tinted with, e.g. the project's color.
+    pub fn set_tint(&mut self, tint: Option<Hsla>) {
+        self.tint = tint;
+    }
+
     pub fn set_children<T>(&mut self, children: T)
     where
         T: IntoIterator<Item = AnyElement>,
@@ -68,11 +94,26 @@ impl Render for PlatformTitleBar {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let supported_controls = window.window_controls();
         let decorations = window.window_decorations();
-        let height = Self::height(window);
+        let height = Self::height(window, cx);
         let titlebar_color = self.title_bar_color(window, cx);
         let close_action = Box::new(workspace::CloseWindow);
         let children = mem::take(&mut self.children);
 
+        if self.native {
+            return h_flex()
+                .id(self.id.clone())
+                .w_full()
+                .h(height)
+                .px_2()
+                .items_center()
+                .justify_between()
+                .bg(titlebar_color)
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .children(children)
+                .into_any_element();
+        }
+
         let title_bar = h_flex()
             .window_control_area(WindowControlArea::Drag)
             .w_full()
@@ -175,6 +216,7 @@ impl Render for PlatformTitleBar {
             .w_full()
             .child(title_bar)
             .child(self.system_window_tabs.clone().into_any_element())
+            .into_any_element()
     }
 }
 
@@ -244,6 +244,10 @@ impl Render for TitleBar {
                 .into_any_element(),
         );
 
+        let project_color = Workspace::project_color(self.project.read(cx), cx);
+        self.platform_titlebar
+            .update(cx, |this, _| this.set_tint(project_color));
+
         if show_menus {
             self.platform_titlebar.update(cx, |this, _| {
                 this.set_children(
@@ -253,7 +257,7 @@ impl Render for TitleBar {
                 );
             });
 
-            let height = PlatformTitleBar::height(window);
+            let height = PlatformTitleBar::height(window, cx);
             let title_bar_color = self.platform_titlebar.update(cx, |platform_titlebar, cx| {
                 platform_titlebar.title_bar_color(window, cx)
             });
@@ -330,7 +334,9 @@ impl TitleBar {
             .visible_when(|cx| !project::DisableAiSettings::get_global(cx).disable_ai)
         });
 
-        let platform_titlebar = cx.new(|cx| PlatformTitleBar::new(id, cx));
+        // Windows are created with the platform's title bar when this is set.
+        let native = TitleBarSettings::get_global(cx).native;
+        let platform_titlebar = cx.new(|cx| PlatformTitleBar::new(id, cx).native(native));
 
         Self {
             platform_titlebar,
@@ -12,6 +12,7 @@ pub struct TitleBarSettings {
     pub show_project_items: bool,
     pub show_sign_in: bool,
     pub show_menus: bool,
+    pub native: bool,
 }
 
 impl Settings for TitleBarSettings {
@@ -26,6 +27,7 @@ impl Settings for TitleBarSettings {
             show_project_items: content.show_project_items.unwrap(),
             show_sign_in: content.show_sign_in.unwrap(),
             show_menus: content.show_menus.unwrap(),
+            native: content.native.unwrap(),
         }
     }
 }
@@ -1117,9 +1117,12 @@ impl Render for ContextMenu {
 
         let render_menu =
             |cx: &mut Context<Self>, window: &mut Window| {
+                let style = cx.theme().components().popover;
                 WithRemSize::new(ui_font_size)
                     .occlude()
                     .elevation_2(cx)
+                    .when_some(style.background, |this, background| this.bg(background))
+                    .when_some(style.border, |this, border| this.border_color(border))
                     .flex()
                     .flex_row()
                     .flex_shrink_0()
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use gpui::{AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels, px};
 use smallvec::SmallVec;
+use theme::UiDensity;
 
-use crate::{Disclosure, prelude::*};
+use crate::{Disclosure, prelude::*, ui_density};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
 pub enum ListItemSpacing {
@@ -208,6 +209,8 @@ impl ParentElement for ListItem {
 
 impl RenderOnce for ListItem {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let ui_density = ui_density(cx);
+
         h_flex()
             .id(self.id)
             .when_some(self.group_name, |this, group| this.group(group))
@@ -249,10 +252,12 @@ impl RenderOnce for ListItem {
                     .relative()
                     .gap_1()
                     .px(DynamicSpacing::Base06.rems(cx))
-                    .map(|this| match self.spacing {
-                        ListItemSpacing::Dense => this,
-                        ListItemSpacing::ExtraDense => this.py_neg_px(),
-                        ListItemSpacing::Sparse => this.py_1(),
+                    .map(|this| match (self.spacing, ui_density) {
+                        (ListItemSpacing::Dense, UiDensity::Compact) => this.py_neg_px(),
+                        (ListItemSpacing::Dense, _) => this,
+                        (ListItemSpacing::ExtraDense, _) => this.py_neg_px(),
+                        (ListItemSpacing::Sparse, UiDensity::Compact) => this.py_0p5(),
+                        (ListItemSpacing::Sparse, _) => this.py_1(),
                     })
                     .when(self.inset && !self.disabled, |this| {
                         this
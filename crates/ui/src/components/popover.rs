@@ -43,13 +43,16 @@ pub struct Popover {
 
 impl RenderOnce for Popover {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let style = cx.theme().components().popover;
         div()
             .flex()
             .gap_1()
             .child(
                 v_flex()
                     .elevation_2(cx)
-                    .py(POPOVER_Y_PADDING / 2.)
+                    .when_some(style.background, |this, background| this.bg(background))
+                    .when_some(style.border, |this, border| this.border_color(border))
+                    .py(style.padding.unwrap_or(POPOVER_Y_PADDING / 2.))
                     .child(div().children(self.children)),
             )
             .when_some(self.aside, |this, aside| {
@@ -91,6 +91,8 @@ impl ParentElement for TabBar {
 
 impl RenderOnce for TabBar {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let style = cx.theme().components().tab_bar;
+        let border_color = style.border.unwrap_or(cx.theme().colors().border);
         div()
             .id(self.id)
             .group("tab_bar")
@@ -98,7 +100,10 @@ impl RenderOnce for TabBar {
             .flex_none()
             .w_full()
             .h(Tab::container_height(cx))
-            .bg(cx.theme().colors().tab_bar_background)
+            .bg(style
+                .background
+                .unwrap_or(cx.theme().colors().tab_bar_background))
+            .when_some(style.padding, |this, padding| this.px(padding))
             .when(!self.start_children.is_empty(), |this| {
                 this.child(
                     h_flex()
@@ -107,7 +112,7 @@ impl RenderOnce for TabBar {
                         .px(DynamicSpacing::Base06.rems(cx))
                         .border_b_1()
                         .border_r_1()
-                        .border_color(cx.theme().colors().border)
+                        .border_color(border_color)
                         .children(self.start_children),
                 )
             })
@@ -124,7 +129,7 @@ impl RenderOnce for TabBar {
                             .left_0()
                             .size_full()
                             .border_b_1()
-                            .border_color(cx.theme().colors().border),
+                            .border_color(border_color),
                     )
                     .child(
                         h_flex()
@@ -145,7 +150,7 @@ impl RenderOnce for TabBar {
                         .px(DynamicSpacing::Base06.rems(cx))
                         .border_b_1()
                         .border_l_1()
-                        .border_color(cx.theme().colors().border)
+                        .border_color(border_color)
                         .children(self.end_children),
                 )
             })
@@ -0,0 +1,317 @@
+//! Local, session-long statistics about the actions a user invokes and where
+//! their time goes, to help them find actions worth binding to a key.
+//!
+//! Nothing collected here is sent anywhere; it only lives for as long as the
+//! app is running, and can be viewed or exported on demand.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+use gpui::{App, Global};
+use serde::Serialize;
+use settings::{Settings, SettingsContent};
+use workspace::Workspace;
+
+/// Keystrokes further apart than this are treated as a break, and the time in
+/// between isn't counted towards any language or project.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How many actions to list in each section of the report.
+const MAX_REPORTED_ACTIONS: usize = 25;
+
+pub fn init(cx: &mut App) {
+    UsageStatsSettings::register(cx);
+    cx.set_global(GlobalUsageStats::default());
+
+    cx.observe_keystrokes(|event, window, cx| {
+        if !UsageStatsSettings::get_global(cx).enabled {
+            return;
+        }
+        let context = window
+            .root::<Workspace>()
+            .flatten()
+            .map(|workspace| ActivityContext::for_workspace(workspace.read(cx), cx));
+        UsageStats::update_global(cx, |stats| {
+            if let Some(action) = &event.action {
+                stats.record_action(action.name(), ActionSource::Keybinding);
+            }
+            stats.record_activity(Instant::now(), context);
+        });
+    })
+    .detach();
+}
+
+/// Records an action invoked some other way than through its key binding.
+pub fn record_action(action_name: &str, source: ActionSource, cx: &mut App) {
+    if cx.has_global::<GlobalUsageStats>() && UsageStatsSettings::get_global(cx).enabled {
+        UsageStats::update_global(cx, |stats| stats.record_action(action_name, source));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UsageStatsSettings {
+    pub enabled: bool,
+}
+
+impl Settings for UsageStatsSettings {
+    fn from_settings(content: &SettingsContent, _cx: &mut App) -> Self {
+        let usage_stats = content.usage_stats.as_ref().unwrap();
+        Self {
+            enabled: usage_stats.enabled.unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionSource {
+    Keybinding,
+    CommandPalette,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ActionCounts {
+    pub keybinding: usize,
+    pub command_palette: usize,
+}
+
+impl ActionCounts {
+    pub fn total(&self) -> usize {
+        self.keybinding + self.command_palette
+    }
+}
+
+/// Where the user was working when they pressed a key.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ActivityContext {
+    pub language: Option<String>,
+    pub project: Option<String>,
+}
+
+impl ActivityContext {
+    fn for_workspace(workspace: &Workspace, cx: &App) -> Self {
+        let project = workspace.project().read(cx);
+        let language = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+            .and_then(|project_path| project.get_open_buffer(&project_path, cx))
+            .and_then(|buffer| Some(buffer.read(cx).language()?.name().to_string()));
+        let root_names = project.worktree_root_names(cx).collect::<Vec<_>>();
+        Self {
+            language,
+            project: (!root_names.is_empty()).then(|| root_names.join(", ")),
+        }
+    }
+}
+
+#[derive(Default)]
+struct GlobalUsageStats(UsageStats);
+
+impl Global for GlobalUsageStats {}
+
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    actions: BTreeMap<String, ActionCounts>,
+    time_by_language: BTreeMap<String, Duration>,
+    time_by_project: BTreeMap<String, Duration>,
+    last_activity: Option<Instant>,
+}
+
+#[derive(Serialize)]
+struct ExportedUsageStats<'a> {
+    actions: &'a BTreeMap<String, ActionCounts>,
+    seconds_by_language: BTreeMap<&'a str, u64>,
+    seconds_by_project: BTreeMap<&'a str, u64>,
+}
+
+impl UsageStats {
+    pub fn global(cx: &App) -> &Self {
+        &cx.global::<GlobalUsageStats>().0
+    }
+
+    fn update_global(cx: &mut App, update: impl FnOnce(&mut Self)) {
+        update(&mut cx.global_mut::<GlobalUsageStats>().0);
+    }
+
+    pub fn record_action(&mut self, action_name: &str, source: ActionSource) {
+        let counts = self.actions.entry(action_name.to_string()).or_default();
+        match source {
+            ActionSource::Keybinding => counts.keybinding += 1,
+            ActionSource::CommandPalette => counts.command_palette += 1,
+        }
+    }
+
+    /// Attributes the time since the previous keystroke to the context of this
+    /// one, unless the user has been idle in between.
+    pub fn record_activity(&mut self, now: Instant, context: Option<ActivityContext>) {
+        let elapsed = self
+            .last_activity
+            .replace(now)
+            .map(|last_activity| now.saturating_duration_since(last_activity))
+            .filter(|elapsed| *elapsed <= IDLE_TIMEOUT);
+        let (Some(elapsed), Some(context)) = (elapsed, context) else {
+            return;
+        };
+        if let Some(language) = context.language {
+            *self.time_by_language.entry(language).or_default() += elapsed;
+        }
+        if let Some(project) = context.project {
+            *self.time_by_project.entry(project).or_default() += elapsed;
+        }
+    }
+
+    pub fn action_counts(&self, action_name: &str) -> ActionCounts {
+        self.actions.get(action_name).copied().unwrap_or_default()
+    }
+
+    pub fn time_by_language(&self) -> &BTreeMap<String, Duration> {
+        &self.time_by_language
+    }
+
+    pub fn time_by_project(&self) -> &BTreeMap<String, Duration> {
+        &self.time_by_project
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        fn seconds(times: &BTreeMap<String, Duration>) -> BTreeMap<&str, u64> {
+            times
+                .iter()
+                .map(|(name, time)| (name.as_str(), time.as_secs()))
+                .collect()
+        }
+        serde_json::to_string_pretty(&ExportedUsageStats {
+            actions: &self.actions,
+            seconds_by_language: seconds(&self.time_by_language),
+            seconds_by_project: seconds(&self.time_by_project),
+        })
+    }
+
+    /// Renders the statistics as Markdown. Actions that are often invoked from
+    /// the command palette and for which `has_binding` returns false are listed
+    /// first, as those are the ones most worth binding to a key.
+    pub fn to_markdown(&self, has_binding: impl Fn(&str) -> bool) -> String {
+        let mut report = String::from("# Usage Stats\n\n");
+        if self.actions.is_empty() && self.time_by_language.is_empty() {
+            report.push_str("Nothing has been recorded yet this session.\n");
+            return report;
+        }
+
+        let mut unbound_actions = self
+            .actions
+            .iter()
+            .filter(|(name, counts)| counts.command_palette > 0 && !has_binding(name.as_str()))
+            .collect::<Vec<_>>();
+        unbound_actions.sort_by_key(|(_, counts)| std::cmp::Reverse(counts.command_palette));
+        if !unbound_actions.is_empty() {
+            report.push_str("## Worth Binding to a Key\n\n");
+            report.push_str("| Action | From the command palette |\n|---|---|\n");
+            for (name, counts) in unbound_actions.iter().take(MAX_REPORTED_ACTIONS) {
+                writeln!(report, "| `{name}` | {} |", counts.command_palette).ok();
+            }
+            report.push('\n');
+        }
+
+        let mut actions = self.actions.iter().collect::<Vec<_>>();
+        actions.sort_by_key(|(_, counts)| std::cmp::Reverse(counts.total()));
+        report.push_str("## Most Used Actions\n\n");
+        report.push_str("| Action | Key binding | Command palette |\n|---|---|---|\n");
+        for (name, counts) in actions.iter().take(MAX_REPORTED_ACTIONS) {
+            writeln!(
+                report,
+                "| `{name}` | {} | {} |",
+                counts.keybinding, counts.command_palette
+            )
+            .ok();
+        }
+
+        for (title, times) in [
+            ("Time per Language", &self.time_by_language),
+            ("Time per Project", &self.time_by_project),
+        ] {
+            if times.is_empty() {
+                continue;
+            }
+            let mut times = times.iter().collect::<Vec<_>>();
+            times.sort_by_key(|(_, time)| std::cmp::Reverse(**time));
+            writeln!(report, "\n## {title}\n\n| Name | Time |\n|---|---|").ok();
+            for (name, time) in times {
+                writeln!(report, "| {name} | {} |", format_duration(*time)).ok();
+            }
+        }
+        report
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_activity() {
+        let mut stats = UsageStats::default();
+        let rust = ActivityContext {
+            language: Some("Rust".into()),
+            project: Some("zed".into()),
+        };
+        let start = Instant::now();
+
+        stats.record_activity(start, Some(rust.clone()));
+        stats.record_activity(start + Duration::from_secs(30), Some(rust.clone()));
+        // After a long break, the time in between isn't counted.
+        stats.record_activity(start + Duration::from_secs(1000), Some(rust.clone()));
+        stats.record_activity(
+            start + Duration::from_secs(1010),
+            Some(ActivityContext {
+                language: None,
+                project: Some("zed".into()),
+            }),
+        );
+
+        assert_eq!(
+            stats.time_by_language().get("Rust"),
+            Some(&Duration::from_secs(30))
+        );
+        assert_eq!(
+            stats.time_by_project().get("zed"),
+            Some(&Duration::from_secs(40))
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let mut stats = UsageStats::default();
+        for _ in 0..3 {
+            stats.record_action("editor::ToggleComments", ActionSource::CommandPalette);
+        }
+        stats.record_action("editor::ToggleComments", ActionSource::Keybinding);
+        stats.record_action("git::Blame", ActionSource::CommandPalette);
+        assert_eq!(
+            stats.action_counts("editor::ToggleComments"),
+            ActionCounts {
+                keybinding: 1,
+                command_palette: 3,
+            }
+        );
+
+        let report = stats.to_markdown(|name| name == "editor::ToggleComments");
+        let worth_binding = report
+            .split("## Most Used Actions")
+            .next()
+            .unwrap()
+            .to_string();
+        assert!(worth_binding.contains("`git::Blame`"));
+        assert!(!worth_binding.contains("`editor::ToggleComments`"));
+        assert!(report.contains("| `editor::ToggleComments` | 1 | 3 |"));
+    }
+}
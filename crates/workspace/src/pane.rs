@@ -17,11 +17,11 @@ use anyhow::Result;
 use collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use futures::{StreamExt, stream::FuturesUnordered};
 use gpui::{
-    Action, AnyElement, App, AsyncWindowContext, ClickEvent, ClipboardItem, Context, Corner, Div,
-    DragMoveEvent, Entity, EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusOutEvent,
-    Focusable, IsZero, KeyContext, MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point,
-    PromptLevel, Render, ScrollHandle, Subscription, Task, WeakEntity, WeakFocusHandle, Window,
-    actions, anchored, deferred, prelude::*,
+    Action, AnyElement, App, AsyncWindowContext, ClickEvent, ClipboardItem, Context, Corner,
+    DismissEvent, Div, DragMoveEvent, Entity, EntityId, EventEmitter, ExternalPaths, FocusHandle,
+    FocusOutEvent, Focusable, IsZero, KeyContext, MouseButton, MouseDownEvent, NavigationDirection,
+    Pixels, Point, PromptLevel, Render, ScrollHandle, Subscription, Task, WeakEntity,
+    WeakFocusHandle, Window, actions, anchored, deferred, prelude::*,
 };
 use itertools::Itertools;
 use language::DiagnosticSeverity;
@@ -200,6 +200,8 @@ actions!(
         GoBack,
         /// Navigates forward in history.
         GoForward,
+        /// Shows a list of the places going back or forward in history would visit.
+        ShowNavigationHistory,
         /// Joins this pane into the next pane.
         JoinIntoNext,
         /// Joins all panes into one.
@@ -375,6 +377,7 @@ pub struct Pane {
     close_pane_if_empty: bool,
     pub new_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
     pub split_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
+    navigation_history_menu: Option<(Entity<ContextMenu>, Subscription)>,
     pinned_tab_count: usize,
     diagnostics: HashMap<ProjectPath, DiagnosticSeverity>,
     zoom_out_on_close: bool,
@@ -517,6 +520,7 @@ impl Pane {
             close_pane_if_empty: true,
             split_item_context_menu_handle: Default::default(),
             new_item_context_menu_handle: Default::default(),
+            navigation_history_menu: None,
             pinned_tab_count: 0,
             diagnostics: Default::default(),
             zoom_out_on_close: true,
@@ -611,6 +615,10 @@ impl Pane {
     pub fn context_menu_focused(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
         self.new_item_context_menu_handle.is_focused(window, cx)
             || self.split_item_context_menu_handle.is_focused(window, cx)
+            || self
+                .navigation_history_menu
+                .as_ref()
+                .is_some_and(|(menu, _)| menu.focus_handle(cx).contains_focused(window, cx))
     }
 
     fn focus_out(&mut self, _event: FocusOutEvent, window: &mut Window, cx: &mut Context<Self>) {
@@ -823,6 +831,63 @@ impl Pane {
         }
     }
 
+    fn show_navigation_history(
+        &mut self,
+        _: &ShowNavigationHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let backward = self.nav_history.entry_labels(NavigationMode::GoingBack, cx);
+        let forward = self
+            .nav_history
+            .entry_labels(NavigationMode::GoingForward, cx);
+        if backward.is_empty() && forward.is_empty() {
+            return;
+        }
+        let current = self.active_item().map(|item| item.tab_content_text(0, cx));
+
+        let pane = cx.entity().downgrade();
+        let navigate = move |mode: NavigationMode, steps: usize| {
+            let workspace = workspace.clone();
+            let pane = pane.clone();
+            move |window: &mut Window, cx: &mut App| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace
+                        .navigate_history_by(pane.clone(), mode, steps, window, cx)
+                        .detach_and_log_err(cx)
+                })
+            }
+        };
+        // Lay the history out like a stack: the furthest forward entry at the
+        // top, the furthest backward one at the bottom.
+        let menu = ContextMenu::build(window, cx, |mut menu, _, _| {
+            for (ix, label) in forward.into_iter().enumerate().rev() {
+                menu = menu.entry(label, None, navigate(NavigationMode::GoingForward, ix + 1));
+            }
+            if let Some(current) = current {
+                menu = menu.separator().label(current).separator();
+            }
+            for (ix, label) in backward.into_iter().enumerate() {
+                menu = menu.entry(label, None, navigate(NavigationMode::GoingBack, ix + 1));
+            }
+            menu
+        });
+        window.focus(&menu.focus_handle(cx));
+        let subscription =
+            cx.subscribe_in(&menu, window, |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu_focused(window, cx) {
+                    window.focus(&this.focus_handle);
+                }
+                this.navigation_history_menu = None;
+                cx.notify();
+            });
+        self.navigation_history_menu = Some((menu, subscription));
+        cx.notify();
+    }
+
     fn history_updated(&mut self, cx: &mut Context<Self>) {
         self.toolbar.update(cx, |_, cx| cx.notify());
     }
@@ -3622,6 +3687,7 @@ impl Render for Pane {
             .on_action(cx.listener(Pane::toggle_zoom))
             .on_action(cx.listener(Self::navigate_backward))
             .on_action(cx.listener(Self::navigate_forward))
+            .on_action(cx.listener(Self::show_navigation_history))
             .on_action(
                 cx.listener(|pane: &mut Pane, action: &ActivateItem, window, cx| {
                     pane.activate_item(
@@ -3725,6 +3791,14 @@ impl Render for Pane {
                     .when(is_local, |div| {
                         div.on_drag_move::<ExternalPaths>(cx.listener(Self::handle_drag_move))
                     })
+                    .when_some(self.navigation_history_menu.as_ref(), |this, (menu, _)| {
+                        this.child(
+                            div().absolute().top_0().left_0().size_0().child(
+                                deferred(anchored().anchor(Corner::TopLeft).child(menu.clone()))
+                                    .with_priority(1),
+                            ),
+                        )
+                    })
                     .map(|div| {
                         if let Some(item) = self.active_item() {
                             div.id("pane_placeholder")
@@ -3886,6 +3960,31 @@ impl NavHistory {
             })
     }
 
+    /// Describes the entries that going back or forward would visit, nearest first.
+    pub fn entry_labels(&self, mode: NavigationMode, cx: &App) -> Vec<SharedString> {
+        let state = self.0.lock();
+        let stack = match mode {
+            NavigationMode::GoingBack => &state.backward_stack,
+            NavigationMode::GoingForward => &state.forward_stack,
+            _ => return Vec::new(),
+        };
+        stack
+            .iter()
+            .rev()
+            .map(|entry| {
+                if let Some(item) = entry.item.upgrade() {
+                    item.tab_content_text(0, cx)
+                } else if let Some((project_path, _)) = state.paths_by_item.get(&entry.item.id())
+                    && let Some(file_name) = project_path.path.file_name()
+                {
+                    file_name.to_string_lossy().into_owned().into()
+                } else {
+                    "untitled".into()
+                }
+            })
+            .collect()
+    }
+
     pub fn set_mode(&mut self, mode: NavigationMode) {
         self.0.lock().mode = mode;
     }
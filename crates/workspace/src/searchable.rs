@@ -67,6 +67,10 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
     ) {
     }
 
+    /// Moves back to where the item was when the search bar was shown, for when a search
+    /// is cancelled.
+    fn restore_search_origin(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {}
+
     fn has_filtered_search_ranges(&mut self) -> bool {
         self.supported_options().selection
     }
@@ -155,6 +159,16 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<usize>;
+    /// Whether project search should also look through this item, for contents
+    /// that don't live in any project buffer (such as a terminal's scrollback).
+    fn include_in_project_search(&self, _cx: &App) -> bool {
+        false
+    }
+    /// A one-line preview of the text containing the given match, used when the
+    /// match is listed outside of the item itself.
+    fn match_preview(&self, _mat: &Self::Match, _cx: &App) -> Option<String> {
+        None
+    }
 }
 
 pub trait SearchableItemHandle: ItemHandle {
@@ -215,8 +229,11 @@ pub trait SearchableItemHandle: ItemHandle {
         cx: &mut App,
     ) -> Option<usize>;
     fn search_bar_visibility_changed(&self, visible: bool, window: &mut Window, cx: &mut App);
+    fn restore_search_origin(&self, window: &mut Window, cx: &mut App);
 
     fn toggle_filtered_search_ranges(&mut self, enabled: bool, window: &mut Window, cx: &mut App);
+    fn include_in_project_search(&self, cx: &App) -> bool;
+    fn match_previews(&self, matches: &AnyVec<dyn Send>, cx: &App) -> Vec<Option<String>>;
 }
 
 impl<T: SearchableItem> SearchableItemHandle for Entity<T> {
@@ -362,11 +379,31 @@ impl<T: SearchableItem> SearchableItemHandle for Entity<T> {
         });
     }
 
+    fn restore_search_origin(&self, window: &mut Window, cx: &mut App) {
+        self.update(cx, |this, cx| this.restore_search_origin(window, cx));
+    }
+
     fn toggle_filtered_search_ranges(&mut self, enabled: bool, window: &mut Window, cx: &mut App) {
         self.update(cx, |this, cx| {
             this.toggle_filtered_search_ranges(enabled, window, cx)
         });
     }
+
+    fn include_in_project_search(&self, cx: &App) -> bool {
+        self.read(cx).include_in_project_search(cx)
+    }
+
+    fn match_previews(&self, matches: &AnyVec<dyn Send>, cx: &App) -> Vec<Option<String>> {
+        let Some(matches) = matches.downcast_ref::<T::Match>() else {
+            return Vec::new();
+        };
+        let this = self.read(cx);
+        matches
+            .as_slice()
+            .iter()
+            .map(|mat| this.match_preview(mat, cx))
+            .collect()
+    }
 }
 
 impl From<Box<dyn SearchableItemHandle>> for AnyView {
@@ -1,8 +1,9 @@
-use crate::{ItemHandle, Pane};
+use crate::{ItemHandle, PROJECT_COLOR_OPACITY, Pane, Workspace};
 use gpui::{
     AnyView, App, Context, Decorations, Entity, IntoElement, ParentElement, Render, Styled,
     Subscription, Window,
 };
+use project::Project;
 use std::any::TypeId;
 use theme::CLIENT_SIDE_DECORATION_ROUNDING;
 use ui::{h_flex, prelude::*};
@@ -32,18 +33,33 @@ pub struct StatusBar {
     left_items: Vec<Box<dyn StatusItemViewHandle>>,
     right_items: Vec<Box<dyn StatusItemViewHandle>>,
     active_pane: Entity<Pane>,
+    project: Entity<Project>,
     _observe_active_pane: Subscription,
 }
 
 impl Render for StatusBar {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let style = cx.theme().components().status_bar;
+        let mut background = style
+            .background
+            .unwrap_or(cx.theme().colors().status_bar_background);
+        if let Some(project_color) = Workspace::project_color(self.project.read(cx), cx) {
+            background = background.blend(project_color.opacity(PROJECT_COLOR_OPACITY));
+        }
         h_flex()
             .w_full()
             .justify_between()
             .gap(DynamicSpacing::Base08.rems(cx))
-            .py(DynamicSpacing::Base04.rems(cx))
-            .px(DynamicSpacing::Base06.rems(cx))
-            .bg(cx.theme().colors().status_bar_background)
+            .map(|el| match style.padding {
+                Some(padding) => el.p(padding),
+                None => el
+                    .py(DynamicSpacing::Base04.rems(cx))
+                    .px(DynamicSpacing::Base06.rems(cx)),
+            })
+            .bg(background)
+            .when_some(style.border, |el, border| {
+                el.border_t_1().border_color(border)
+            })
             .map(|el| match window.window_decorations() {
                 Decorations::Server => el,
                 Decorations::Client { tiling, .. } => el
@@ -56,7 +72,7 @@ impl Render for StatusBar {
                     // This border is to avoid a transparent gap in the rounded corners
                     .mb(px(-1.))
                     .border_b(px(1.0))
-                    .border_color(cx.theme().colors().status_bar_background),
+                    .border_color(background),
             })
             .child(self.render_left_tools())
             .child(self.render_right_tools())
@@ -80,11 +96,17 @@ impl StatusBar {
 }
 
 impl StatusBar {
-    pub fn new(active_pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(
+        active_pane: &Entity<Pane>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let mut this = Self {
             left_items: Default::default(),
             right_items: Default::default(),
             active_pane: active_pane.clone(),
+            project,
             _observe_active_pane: cx.observe_in(active_pane, window, |this, _, window, cx| {
                 this.update_active_pane_item(window, cx)
             }),
@@ -70,6 +70,7 @@ use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
     WorktreeSettings,
+    bookmark_store::BookmarkStoreEvent,
     debugger::{breakpoint_store::BreakpointStoreEvent, session::ThreadStatus},
     toolchain_store::ToolchainStoreEvent,
 };
@@ -119,6 +120,9 @@ use crate::persistence::{
 
 pub const SERIALIZATION_THROTTLE_TIME: Duration = Duration::from_millis(200);
 
+/// How strongly the `project_color` setting tints the title bar and status bar.
+pub const PROJECT_COLOR_OPACITY: f32 = 0.3;
+
 static ZED_WINDOW_SIZE: LazyLock<Option<Size<Pixels>>> = LazyLock::new(|| {
     env::var("ZED_WINDOW_SIZE")
         .ok()
@@ -194,6 +198,12 @@ actions!(
         Feedback,
         /// Follows the next collaborator in the session.
         FollowNextCollaborator,
+        /// Goes to the next changed hunk, moving on to the next changed file in the project
+        /// once the active one has no more changes.
+        GoToNextChangedHunk,
+        /// Goes to the previous changed hunk, moving on to the previous changed file in the
+        /// project once the active one has no more changes.
+        GoToPreviousChangedHunk,
         /// Moves the focused panel to the next position.
         MoveFocusedPanelToNextPosition,
         /// Opens a new terminal in the center.
@@ -1221,6 +1231,10 @@ impl Workspace {
                     }
                 }
 
+                project::Event::DuplicateBuffer { duplicate, buffer } => {
+                    this.replace_duplicate_buffer_items(duplicate, buffer, window, cx);
+                }
+
                 project::Event::Toast {
                     notification_id,
                     message,
@@ -1274,6 +1288,16 @@ impl Workspace {
             },
         )
         .detach();
+        cx.subscribe_in(
+            &project.read(cx).bookmark_store(),
+            window,
+            |workspace, _, event, window, cx| match event {
+                BookmarkStoreEvent::BookmarksUpdated => {
+                    workspace.serialize_workspace(window, cx);
+                }
+            },
+        )
+        .detach();
         if let Some(toolchain_store) = project.read(cx).toolchain_store() {
             cx.subscribe_in(
                 &toolchain_store,
@@ -1368,7 +1392,7 @@ impl Workspace {
         let bottom_dock_buttons = cx.new(|cx| PanelButtons::new(bottom_dock.clone(), cx));
         let right_dock_buttons = cx.new(|cx| PanelButtons::new(right_dock.clone(), cx));
         let status_bar = cx.new(|cx| {
-            let mut status_bar = StatusBar::new(&center_pane.clone(), window, cx);
+            let mut status_bar = StatusBar::new(&center_pane.clone(), project.clone(), window, cx);
             status_bar.add_left_item(left_dock_buttons, window, cx);
             status_bar.add_right_item(right_dock_buttons, window, cx);
             status_bar.add_right_item(bottom_dock_buttons, window, cx);
@@ -2018,6 +2042,27 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, window, cx)
     }
 
+    /// Goes back or forward the given number of times in a row.
+    pub fn navigate_history_by(
+        &mut self,
+        pane: WeakEntity<Pane>,
+        mode: NavigationMode,
+        steps: usize,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<()>> {
+        cx.spawn_in(window, async move |workspace, cx| {
+            for _ in 0..steps {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.navigate_history(pane.clone(), mode, window, cx)
+                    })?
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn reopen_closed_item(
         &mut self,
         window: &mut Window,
@@ -3335,6 +3380,57 @@ impl Workspace {
         self.open_path_preview(path, pane, focus_item, false, true, window, cx)
     }
 
+    /// Replaces the items that show `duplicate` with ones that show `buffer`, which is the
+    /// same file on disk, unless they have unsaved changes.
+    fn replace_duplicate_buffer_items(
+        &mut self,
+        duplicate: &Entity<Buffer>,
+        buffer: &Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project_path) = buffer
+            .read(cx)
+            .file()
+            .map(|file| ProjectPath::from_file(file.as_ref(), cx))
+        else {
+            return;
+        };
+        let duplicate_id = duplicate.entity_id();
+        for pane in self.panes.clone() {
+            let duplicate_item_ids = pane
+                .read(cx)
+                .items()
+                .filter(|item| {
+                    item.is_singleton(cx)
+                        && !item.is_dirty(cx)
+                        && item.project_item_model_ids(cx).as_slice() == [duplicate_id]
+                })
+                .map(|item| item.item_id())
+                .collect::<Vec<_>>();
+            if duplicate_item_ids.is_empty() {
+                continue;
+            }
+
+            let open_task = self.open_path(
+                project_path.clone(),
+                Some(pane.downgrade()),
+                false,
+                window,
+                cx,
+            );
+            cx.spawn_in(window, async move |_, cx| {
+                open_task.await?;
+                pane.update_in(cx, |pane, window, cx| {
+                    for item_id in duplicate_item_ids {
+                        pane.close_item_by_id(item_id, SaveIntent::Skip, window, cx)
+                            .detach_and_log_err(cx);
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
     pub fn open_path_preview(
         &mut self,
         path: impl Into<ProjectPath>,
@@ -3569,6 +3665,42 @@ impl Workspace {
         }
     }
 
+    /// Activates and focuses the given item, looking for it in the center panes
+    /// first and then in the panes of dock panels, opening the dock if needed.
+    pub fn activate_item_in_any_pane(
+        &mut self,
+        item: &dyn ItemHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.activate_item(item, true, true, window, cx) {
+            return true;
+        }
+
+        for dock in self.all_docks() {
+            let result = dock
+                .read(cx)
+                .panels()
+                .enumerate()
+                .find_map(|(panel_ix, panel)| {
+                    let pane = panel.pane(cx)?;
+                    let item_ix = pane.read(cx).index_for_item(item)?;
+                    Some((panel_ix, pane, item_ix))
+                });
+            if let Some((panel_ix, pane, item_ix)) = result {
+                dock.update(cx, |dock, cx| {
+                    dock.activate_panel(panel_ix, window, cx);
+                    dock.set_open(true, window, cx);
+                });
+                pane.update(cx, |pane, cx| {
+                    pane.activate_item(item_ix, true, true, window, cx)
+                });
+                return true;
+            }
+        }
+        false
+    }
+
     fn activate_pane_at_index(
         &mut self,
         action: &ActivatePane,
@@ -4400,6 +4532,29 @@ impl Workspace {
         self.update_window_title(window, cx);
     }
 
+    /// The color configured with the `project_color` setting of the first visible
+    /// worktree, which tints the title bar and the status bar.
+    pub fn project_color(project: &Project, cx: &App) -> Option<Hsla> {
+        let worktree = project.visible_worktrees(cx).next()?;
+        let worktree = worktree.read(cx);
+        let settings_location = SettingsLocation {
+            worktree_id: worktree.id(),
+            path: Path::new(""),
+        };
+        match WorktreeSettings::get(Some(settings_location), cx)
+            .project_color
+            .as_deref()?
+        {
+            "auto" => {
+                let mut hasher = DefaultHasher::new();
+                worktree.abs_path().hash(&mut hasher);
+                let hue = (hasher.finish() % 360) as f32 / 360.;
+                Some(gpui::hsla(hue, 0.6, 0.5, 1.))
+            }
+            color => gpui::Rgba::try_from(color).ok().map(Hsla::from),
+        }
+    }
+
     fn update_window_title(&mut self, window: &mut Window, cx: &mut App) {
         let project = self.project().read(cx);
         let mut title = String::new();
@@ -5304,6 +5459,12 @@ impl Workspace {
                         .read(cx)
                         .all_source_breakpoints(cx)
                 });
+                let bookmarks = self
+                    .project
+                    .read(cx)
+                    .bookmark_store()
+                    .read(cx)
+                    .all_source_bookmarks(cx);
                 let user_toolchains = self
                     .project
                     .read(cx)
@@ -5325,6 +5486,7 @@ impl Workspace {
                     centered_layout: self.centered_layout,
                     session_id: self.session_id.clone(),
                     breakpoints,
+                    bookmarks,
                     window_id: Some(window.window_handle().window_id().as_u64()),
                     user_toolchains,
                 };
@@ -5512,6 +5674,14 @@ impl Workspace {
                 })?
                 .await;
 
+            let _ = project
+                .update(cx, |project, cx| {
+                    project.bookmark_store().update(cx, |bookmark_store, cx| {
+                        bookmark_store.with_serialized_bookmarks(serialized_workspace.bookmarks, cx)
+                    })
+                })?
+                .await;
+
             // Clean up all the items that have _not_ been loaded. Our ItemIds aren't stable. That means
             // after loading the items, we might have different items and in order to avoid
             // the database filling up, we delete items that haven't been loaded now.
@@ -8865,6 +9035,72 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_navigate_history_by(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        let item = cx.new(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+        });
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, window, cx);
+        });
+        item.update_in(cx, |item, _, cx| {
+            item.set_state("one".to_string(), cx);
+            item.set_state("two".to_string(), cx);
+            item.set_state("three".to_string(), cx);
+        });
+        pane.read_with(cx, |pane, cx| {
+            let nav_history = pane.nav_history();
+            assert_eq!(
+                nav_history
+                    .entry_labels(NavigationMode::GoingBack, cx)
+                    .len(),
+                3
+            );
+            assert!(
+                nav_history
+                    .entry_labels(NavigationMode::GoingForward, cx)
+                    .is_empty()
+            );
+        });
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.navigate_history_by(
+                    pane.downgrade(),
+                    NavigationMode::GoingBack,
+                    2,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        item.read_with(cx, |item, _| assert_eq!(item.state, "one"));
+        pane.read_with(cx, |pane, cx| {
+            let nav_history = pane.nav_history();
+            assert_eq!(
+                nav_history
+                    .entry_labels(NavigationMode::GoingBack, cx)
+                    .len(),
+                1
+            );
+            assert_eq!(
+                nav_history
+                    .entry_labels(NavigationMode::GoingForward, cx)
+                    .len(),
+                2
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
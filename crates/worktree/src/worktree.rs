@@ -52,6 +52,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io,
     mem::{self},
     ops::{Deref, DerefMut},
     path::{Component, Path, PathBuf},
@@ -102,6 +103,14 @@ pub struct LoadedFile {
     pub text: String,
 }
 
+/// A file that was read in chunks straight into a [`Rope`], with its line
+/// endings already normalized.
+pub struct LoadedLargeFile {
+    pub file: Arc<File>,
+    pub line_ending: LineEnding,
+    pub text: Rope,
+}
+
 pub struct LoadedBinaryFile {
     pub file: Arc<File>,
     pub content: Vec<u8>,
@@ -800,6 +809,21 @@ impl Worktree {
         }
     }
 
+    /// Loads a file without holding its whole contents in memory more than
+    /// once, for files too large to go through [`Worktree::load_file`].
+    pub fn load_large_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedLargeFile>> {
+        match self {
+            Worktree::Local(this) => this.load_large_file(path, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
+            }
+        }
+    }
+
     pub fn load_binary_file(
         &self,
         path: &Path,
@@ -1548,6 +1572,57 @@ impl LocalWorktree {
         })
     }
 
+    fn load_large_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedLargeFile>> {
+        let path = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let entry = self.refresh_entry(path.clone(), None, cx);
+        let is_private = self.is_path_private(path.as_ref());
+
+        let worktree = cx.weak_entity();
+        cx.background_spawn(async move {
+            let abs_path = abs_path?;
+            let reader = fs.open_sync(&abs_path).await?;
+            let (text, line_ending) = read_normalized_rope(reader)?;
+
+            let worktree = worktree.upgrade().context("worktree was dropped")?;
+            let file = match entry.await? {
+                Some(entry) => File::for_entry(entry, worktree),
+                None => {
+                    let metadata = fs
+                        .metadata(&abs_path)
+                        .await
+                        .with_context(|| {
+                            format!("Loading metadata for excluded file {abs_path:?}")
+                        })?
+                        .with_context(|| {
+                            format!("Excluded file {abs_path:?} got removed during loading")
+                        })?;
+                    Arc::new(File {
+                        entry_id: None,
+                        worktree,
+                        path,
+                        disk_state: DiskState::Present {
+                            mtime: metadata.mtime,
+                        },
+                        is_local: true,
+                        is_private,
+                    })
+                }
+            };
+
+            Ok(LoadedLargeFile {
+                file,
+                line_ending,
+                text,
+            })
+        })
+    }
+
     /// Find the lowest path in the worktree's datastructures that is an ancestor
     fn lowest_ancestor(&self, path: &Path) -> PathBuf {
         let mut lowest_ancestor = None;
@@ -3261,6 +3336,52 @@ async fn build_gitignore(abs_path: &Path, fs: &dyn Fs) -> Result<Gitignore> {
     Ok(builder.build()?)
 }
 
+/// Reads UTF-8 text into a [`Rope`] a chunk at a time, normalizing line
+/// endings as it goes, so the file's contents are never held in a single
+/// `String`.
+fn read_normalized_rope(mut reader: impl io::Read) -> Result<(Rope, LineEnding)> {
+    const CHUNK_LEN: usize = 64 * 1024;
+
+    let mut rope = Rope::new();
+    let mut line_ending = None;
+    let mut chunk = vec![0; CHUNK_LEN];
+    // Bytes that can't be appended yet: an incomplete UTF-8 sequence, or a
+    // trailing `\r` that may be the start of a `\r\n`.
+    let mut pending = Vec::new();
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        pending.extend_from_slice(&chunk[..read]);
+
+        let mut valid_len = match std::str::from_utf8(&pending) {
+            Ok(text) => text.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(error.into()),
+        };
+        if pending[..valid_len].ends_with(b"\r") {
+            valid_len -= 1;
+        }
+        let text = String::from_utf8(pending.drain(..valid_len).collect())?;
+        push_normalized(&mut rope, &mut line_ending, text);
+    }
+    push_normalized(&mut rope, &mut line_ending, String::from_utf8(pending)?);
+    Ok((rope, line_ending.unwrap_or_default()))
+}
+
+fn push_normalized(rope: &mut Rope, line_ending: &mut Option<LineEnding>, mut text: String) {
+    // A held back `\r` always travels with the `\n` that follows it, so the
+    // first chunk with a newline tells the line ending.
+    if line_ending.is_none() && text.contains('\n') {
+        *line_ending = Some(LineEnding::detect(&text));
+    }
+    LineEnding::normalize(&mut text);
+    rope.push(&text);
+}
+
 impl Deref for Worktree {
     type Target = Snapshot;
 
@@ -3472,6 +3593,9 @@ pub struct Entry {
     pub id: ProjectEntryId,
     pub kind: EntryKind,
     pub path: Arc<Path>,
+    /// The device that the entry is on, which identifies the file along with its inode. Only
+    /// known for local worktrees.
+    pub device: u64,
     pub inode: u64,
     pub mtime: Option<MTime>,
 
@@ -3651,6 +3775,7 @@ impl Entry {
                 EntryKind::File
             },
             path,
+            device: metadata.device,
             inode: metadata.inode,
             mtime: Some(metadata.mtime),
             size: metadata.len,
@@ -5657,6 +5782,7 @@ impl From<(&CharBag, &PathMatcher, proto::Entry)> for Entry {
             id: ProjectEntryId::from_proto(entry.id),
             kind,
             path,
+            device: 0,
             inode: entry.inode,
             mtime: entry.mtime.map(|time| time.into()),
             size: entry.size.unwrap_or(0),
@@ -8,6 +8,7 @@ use util::{ResultExt, paths::PathMatcher};
 #[derive(Clone, PartialEq, Eq)]
 pub struct WorktreeSettings {
     pub project_name: Option<String>,
+    pub project_color: Option<String>,
     pub file_scan_inclusions: PathMatcher,
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
@@ -48,6 +49,7 @@ impl Settings for WorktreeSettings {
 
         Self {
             project_name: None,
+            project_color: worktree.project_color,
             file_scan_exclusions: path_matchers(file_scan_exclusions, "file_scan_exclusions")
                 .log_err()
                 .unwrap_or_default(),
@@ -1,6 +1,6 @@
 use crate::{
     Entry, EntryKind, Event, PathChange, WorkDirectory, Worktree, WorktreeModelHandle,
-    worktree_settings::WorktreeSettings,
+    read_normalized_rope, worktree_settings::WorktreeSettings,
 };
 use anyhow::Result;
 use fs::{FakeFs, Fs, RealFs, RemoveOptions};
@@ -16,10 +16,11 @@ use settings::{Settings, SettingsStore};
 use std::{
     env,
     fmt::Write,
-    mem,
+    io, mem,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use text::LineEnding;
 use util::{ResultExt, path, test::TempTree};
 
 #[gpui::test]
@@ -395,6 +396,35 @@ async fn test_renaming_case_only(cx: &mut TestAppContext) {
     });
 }
 
+#[test]
+fn test_read_normalized_rope() {
+    // Yields a single byte per read, so that `\r\n` pairs and multi-byte
+    // characters are split across chunks.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl io::Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    let (text, line_ending) =
+        read_normalized_rope(ByteReader("αβ\r\nγ\rδ\r\n".as_bytes())).unwrap();
+    assert_eq!(text.to_string(), "αβ\nγ\nδ\n");
+    assert_eq!(line_ending, LineEnding::Windows);
+
+    let (text, line_ending) = read_normalized_rope(ByteReader(b"one\ntwo")).unwrap();
+    assert_eq!(text.to_string(), "one\ntwo");
+    assert_eq!(line_ending, LineEnding::Unix);
+
+    assert!(read_normalized_rope(ByteReader(b"\xff\xfe")).is_err());
+}
+
 #[gpui::test]
 async fn test_open_gitignored_files(cx: &mut TestAppContext) {
     init_test(cx);
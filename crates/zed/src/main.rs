@@ -579,6 +579,7 @@ pub fn main() {
         app_state.languages.set_theme(cx.theme().clone());
         editor::init(cx);
         image_viewer::init(cx);
+        hex_viewer::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -593,6 +594,8 @@ pub fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        bookmarks_ui::init(cx);
+        call_hierarchy_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -602,6 +605,7 @@ pub fn main() {
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
         line_ending_selector::init(cx);
+        clipboard_history::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         settings_profile_selector::init(cx);
@@ -612,6 +616,7 @@ pub fn main() {
         git_ui::init(cx);
         jj_ui::init(cx);
         feedback::init(cx);
+        usage_stats::init(cx);
         markdown_preview::init(cx);
         svg_preview::init(cx);
         onboarding::init(cx);
@@ -726,6 +731,8 @@ pub fn main() {
         let app_state = app_state.clone();
 
         crate::zed::component_preview::init(app_state.clone(), cx);
+        #[cfg(unix)]
+        crate::zed::automation::init(app_state.clone(), cx);
 
         cx.spawn(async move |cx| {
             while let Some(urls) = open_rx.next().await {
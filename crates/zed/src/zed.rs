@@ -1,10 +1,13 @@
 mod app_menus;
+#[cfg(unix)]
+pub mod automation;
 pub mod component_preview;
 pub mod edit_prediction_registry;
 #[cfg(target_os = "macos")]
 pub(crate) mod mac_only_instance;
 mod migrate;
 mod open_listener;
+mod project_state;
 mod quick_action_bar;
 #[cfg(target_os = "windows")]
 pub(crate) mod windows_only_instance;
@@ -14,12 +17,14 @@ use anyhow::Context as _;
 pub use app_menus::*;
 use assets::Assets;
 use audio::{AudioSettings, REPLAY_DURATION};
+use bookmarks_ui::BookmarksPanel;
 use breadcrumbs::Breadcrumbs;
+use call_hierarchy_panel::CallHierarchyPanel;
 use client::zed_urls;
 use collections::VecDeque;
 use debugger_ui::debugger_panel::DebugPanel;
-use editor::ProposedChangesEditorToolbar;
 use editor::{Editor, MultiBuffer};
+use editor::{LargeFileBanner, ProposedChangesEditorToolbar};
 use feature_flags::{FeatureFlagAppExt, PanicFeatureFlag};
 use futures::future::Either;
 use futures::{StreamExt, channel::mpsc, select_biased};
@@ -69,7 +74,9 @@ use std::{
 };
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::{ActiveTheme, ThemeSettings};
+use title_bar::title_bar_settings::TitleBarSettings;
 use ui::{PopoverMenuHandle, prelude::*};
+use usage_stats::{UsageStats, UsageStatsSettings};
 use util::markdown::MarkdownString;
 use util::{ResultExt, asset_str};
 use uuid::Uuid;
@@ -190,6 +197,16 @@ pub fn init(cx: &mut App) {
             open_telemetry_log_file(workspace, window, cx);
         });
     });
+    cx.on_action(|_: &zed_actions::ShowUsageStats, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            open_usage_stats(workspace, window, cx);
+        });
+    });
+    cx.on_action(|_: &zed_actions::ExportUsageStats, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            export_usage_stats(workspace, window, cx);
+        });
+    });
     cx.on_action(|&zed_actions::OpenKeymap, cx| {
         with_active_or_new_workspace(cx, |_, window, cx| {
             open_settings_file(
@@ -281,9 +298,11 @@ pub fn build_window_options(display_uuid: Option<Uuid>, cx: &mut App) -> WindowO
             .find(|display| display.uuid().ok() == Some(uuid))
     });
     let app_id = ReleaseChannel::global(cx).app_id();
+    let native_title_bar = TitleBarSettings::get_global(cx).native;
     let window_decorations = match std::env::var("ZED_WINDOW_DECORATIONS") {
         Ok(val) if val == "server" => gpui::WindowDecorations::Server,
         Ok(val) if val == "client" => gpui::WindowDecorations::Client,
+        _ if native_title_bar => gpui::WindowDecorations::Server,
         _ => gpui::WindowDecorations::Client,
     };
 
@@ -292,8 +311,8 @@ pub fn build_window_options(display_uuid: Option<Uuid>, cx: &mut App) -> WindowO
     WindowOptions {
         titlebar: Some(TitlebarOptions {
             title: None,
-            appears_transparent: true,
-            traffic_light_position: Some(point(px(9.0), px(9.0))),
+            appears_transparent: !native_title_bar,
+            traffic_light_position: (!native_title_bar).then(|| point(px(9.0), px(9.0))),
         }),
         window_bounds: None,
         focus: false,
@@ -396,10 +415,14 @@ pub fn initialize_workspace(
         );
         let active_buffer_language =
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_indentation =
+            cx.new(|_| language_selector::ActiveBufferIndentation::default());
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
+        let tasks_on_save = cx.new(|cx| tasks_ui::TasksOnSave::new(workspace, window, cx));
+        let focus_timer = cx.new(collab_ui::focus_timer::FocusTimerIndicator::new);
 
         let lsp_button_menu_handle = PopoverMenuHandle::default();
         let lsp_button =
@@ -417,7 +440,10 @@ pub fn initialize_workspace(
             status_bar.add_left_item(lsp_button, window, cx);
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
+            status_bar.add_left_item(tasks_on_save, window, cx);
+            status_bar.add_right_item(focus_timer, window, cx);
             status_bar.add_right_item(edit_prediction_button, window, cx);
+            status_bar.add_right_item(active_buffer_indentation, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
@@ -560,6 +586,8 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let bookmarks_panel = BookmarksPanel::load(workspace_handle.clone(), cx.clone());
+        let call_hierarchy_panel = CallHierarchyPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         let (
@@ -569,6 +597,8 @@ fn initialize_panels(
             git_panel,
             channels_panel,
             notification_panel,
+            bookmarks_panel,
+            call_hierarchy_panel,
             debug_panel,
         ) = futures::try_join!(
             project_panel,
@@ -577,6 +607,8 @@ fn initialize_panels(
             terminal_panel,
             channels_panel,
             notification_panel,
+            bookmarks_panel,
+            call_hierarchy_panel,
             debug_panel,
         )?;
 
@@ -587,6 +619,8 @@ fn initialize_panels(
             workspace.add_panel(git_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
+            workspace.add_panel(bookmarks_panel, window, cx);
+            workspace.add_panel(call_hierarchy_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
         })?;
 
@@ -876,6 +910,22 @@ fn register_actions(
                 );
             },
         )
+        .register_action(
+            |workspace: &mut Workspace,
+             _: &bookmarks_ui::bookmarks_panel::ToggleFocus,
+             window: &mut Window,
+             cx: &mut Context<Workspace>| {
+                workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+            },
+        )
+        .register_action(
+            |workspace: &mut Workspace,
+             _: &call_hierarchy_panel::ToggleFocus,
+             window: &mut Window,
+             cx: &mut Context<Workspace>| {
+                workspace.toggle_panel_focus::<CallHierarchyPanel>(window, cx);
+            },
+        )
         .register_action(
             |workspace: &mut Workspace,
              _: &terminal_panel::ToggleFocus,
@@ -919,7 +969,9 @@ fn register_actions(
         })
         .register_action(|workspace, _: &CaptureRecentAudio, window, cx| {
             capture_recent_audio(workspace, window, cx);
-        });
+        })
+        .register_action(project_state::export_project_state)
+        .register_action(project_state::import_project_state);
 
     #[cfg(not(target_os = "windows"))]
     workspace.register_action(install_cli);
@@ -1000,6 +1052,8 @@ fn initialize_pane(
             toolbar.add_item(agent_diff_toolbar, window, cx);
             let basedpyright_banner = cx.new(|cx| BasedPyrightBanner::new(workspace, cx));
             toolbar.add_item(basedpyright_banner, window, cx);
+            let large_file_banner = cx.new(|_| LargeFileBanner::new());
+            toolbar.add_item(large_file_banner, window, cx);
         })
     });
 }
@@ -1778,6 +1832,61 @@ fn open_telemetry_log_file(
     }).detach();
 }
 
+fn open_usage_stats(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let report = if UsageStatsSettings::get_global(cx).enabled {
+        UsageStats::global(cx).to_markdown(|action_name| {
+            cx.build_action(action_name, None)
+                .is_ok_and(|action| !window.bindings_for_action(action.as_ref()).is_empty())
+        })
+    } else {
+        concat!(
+            "# Usage Stats\n\n",
+            "Usage stats are disabled. Set `\"usage_stats\": { \"enabled\": true }` in your settings ",
+            "to start counting the actions you invoke and where your time goes. ",
+            "They are only kept locally, for the current session.\n",
+        )
+        .to_string()
+    };
+    open_bundled_file(
+        workspace,
+        report.into(),
+        "Usage Stats",
+        "Markdown",
+        window,
+        cx,
+    );
+}
+
+fn export_usage_stats(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let json = match UsageStats::global(cx).to_json() {
+        Ok(json) => json,
+        Err(error) => {
+            log::error!("failed to serialize usage stats: {error}");
+            return;
+        }
+    };
+    let fs = workspace.app_state().fs.clone();
+    let path = cx.prompt_for_new_path(paths::home_dir(), Some("usage-stats.json"));
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = path.await?? else {
+            return anyhow::Ok(());
+        };
+        fs.atomic_write(path.clone(), json).await?;
+        workspace.update(cx, |workspace, cx| {
+            struct ExportedUsageStats;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<ExportedUsageStats>(),
+                    format!("Exported usage stats to {}", path.display()),
+                ),
+                cx,
+            )
+        })?;
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
 fn open_bundled_file(
     workspace: &Workspace,
     text: Cow<'static, str>,
@@ -4455,17 +4564,21 @@ mod tests {
                 "assistant",
                 "assistant2",
                 "auto_update",
+                "bookmarks_panel",
                 "branches",
                 "buffer_search",
+                "call_hierarchy_panel",
                 "channel_modal",
                 "cli",
                 "client",
+                "clipboard_history",
                 "collab",
                 "collab_panel",
                 "command_palette",
                 "console",
                 "context_server",
                 "copilot",
+                "debug",
                 "debug_panel",
                 "debugger",
                 "dev",
@@ -4474,10 +4587,12 @@ mod tests {
                 "editor",
                 "feedback",
                 "file_finder",
+                "focus_timer",
                 "git",
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hex_viewer",
                 "icon_theme_selector",
                 "jj",
                 "journal",
@@ -0,0 +1,635 @@
+//! # Automation
+//!
+//! An opt-in JSON-RPC 2.0 server that lets external tools script Zed. Requests
+//! and responses are newline-delimited JSON sent over a Unix socket in Zed's
+//! data directory. Every connection has to `authenticate` with the token that's
+//! written next to the socket before it can call any other method.
+
+use std::{
+    fs::{self, OpenOptions as FileOpenOptions},
+    io::Write as _,
+    os::unix::fs::OpenOptionsExt as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use editor::Editor;
+use futures::{AsyncBufReadExt as _, AsyncWriteExt as _, StreamExt as _, io::BufReader};
+use gpui::{App, AsyncApp, Global, Task, WindowHandle};
+use language::{Bias, DiagnosticSeverity, Point};
+use release_channel::RELEASE_CHANNEL_NAME;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use settings::{Settings, SettingsStore};
+use smol::net::unix::{UnixListener, UnixStream};
+use subtle::ConstantTimeEq as _;
+use util::ResultExt as _;
+use uuid::Uuid;
+use workspace::{AppState, OpenOptions, Workspace};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const UNAUTHENTICATED: i64 = -32001;
+
+#[derive(Clone, Debug)]
+pub struct AutomationSettings {
+    /// Whether the automation server is running.
+    pub enabled: bool,
+}
+
+impl Settings for AutomationSettings {
+    fn from_settings(content: &settings::SettingsContent, _cx: &mut App) -> Self {
+        let automation = content.automation.as_ref().unwrap();
+        Self {
+            enabled: automation.enabled.unwrap(),
+        }
+    }
+
+    fn import_from_vscode(
+        _vscode: &settings::VsCodeSettings,
+        _current: &mut settings::SettingsContent,
+    ) {
+    }
+}
+
+/// The path of the socket the automation server listens on.
+pub fn socket_path() -> PathBuf {
+    paths::data_dir().join(format!("zed-{}-automation.sock", *RELEASE_CHANNEL_NAME))
+}
+
+/// The path of the file containing the token clients authenticate with.
+pub fn token_path() -> PathBuf {
+    paths::data_dir().join(format!("zed-{}-automation.token", *RELEASE_CHANNEL_NAME))
+}
+
+pub fn init(app_state: Arc<AppState>, cx: &mut App) {
+    AutomationSettings::register(cx);
+    update_server(&app_state, cx);
+    cx.observe_global::<SettingsStore>(move |cx| update_server(&app_state, cx))
+        .detach();
+}
+
+fn update_server(app_state: &Arc<AppState>, cx: &mut App) {
+    let enabled = AutomationSettings::get_global(cx).enabled;
+    if enabled == cx.has_global::<AutomationServer>() {
+        return;
+    }
+    if enabled {
+        if let Some(server) = AutomationServer::start(app_state.clone(), cx).log_err() {
+            cx.set_global(server);
+        }
+    } else {
+        // Dropping the server stops it.
+        cx.remove_global::<AutomationServer>();
+    }
+}
+
+struct AutomationServer {
+    socket_path: PathBuf,
+    token_path: PathBuf,
+    _accept_task: Task<()>,
+}
+
+impl Global for AutomationServer {}
+
+impl AutomationServer {
+    fn start(app_state: Arc<AppState>, cx: &mut App) -> Result<Self> {
+        let socket_path = socket_path();
+        let token_path = token_path();
+
+        // Remove the socket left behind by a previous instance that didn't
+        // shut down cleanly.
+        if socket_path.exists() {
+            fs::remove_file(&socket_path).context("removing stale automation socket")?;
+        }
+        let listener = UnixListener::bind(&socket_path).context("binding automation socket")?;
+
+        let token: Arc<str> = Uuid::new_v4().simple().to_string().into();
+        write_token(&token_path, &token)?;
+
+        let accept_task = cx.spawn(async move |cx| {
+            let mut connections = futures::stream::FuturesUnordered::new();
+            let mut incoming = listener.incoming().fuse();
+            loop {
+                futures::select_biased! {
+                    stream = incoming.select_next_some() => {
+                        let Some(stream) = stream.log_err() else {
+                            continue;
+                        };
+                        connections.push(handle_connection(
+                            stream,
+                            token.clone(),
+                            app_state.clone(),
+                            cx.clone(),
+                        ));
+                    }
+                    result = connections.select_next_some() => {
+                        result.log_err();
+                    }
+                    complete => break,
+                }
+            }
+        });
+
+        log::info!("automation server listening on {socket_path:?}");
+        Ok(Self {
+            socket_path,
+            token_path,
+            _accept_task: accept_task,
+        })
+    }
+}
+
+/// Writes the token to a file only the current user can read.
+fn write_token(token_path: &Path, token: &str) -> Result<()> {
+    // A file left behind by a previous instance may be readable by others, and
+    // opening it wouldn't change its permissions, so it's replaced instead.
+    if token_path.exists() {
+        fs::remove_file(token_path).context("removing stale automation token file")?;
+    }
+    let mut token_file = FileOpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(token_path)
+        .context("creating automation token file")?;
+    token_file.write_all(token.as_bytes())?;
+    Ok(())
+}
+
+impl Drop for AutomationServer {
+    fn drop(&mut self) {
+        fs::remove_file(&self.socket_path).log_err();
+        fs::remove_file(&self.token_path).log_err();
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+impl ResponseError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ResponseError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(SERVER_ERROR, format!("{error:#}"))
+    }
+}
+
+async fn handle_connection(
+    stream: UnixStream,
+    token: Arc<str>,
+    app_state: Arc<AppState>,
+    mut cx: AsyncApp,
+) -> Result<()> {
+    let mut writer = stream.clone();
+    let mut lines = BufReader::new(stream).lines();
+    let mut authenticated = false;
+
+    while let Some(line) = lines.next().await {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = handle_message(&line, &mut authenticated, &token, &app_state, &mut cx).await;
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        writer.write_all(&response).await?;
+    }
+
+    Ok(())
+}
+
+/// Handles a single request, returning the response to it.
+async fn handle_message(
+    message: &str,
+    authenticated: &mut bool,
+    token: &str,
+    app_state: &Arc<AppState>,
+    cx: &mut AsyncApp,
+) -> Value {
+    let (id, result) = match serde_json::from_str::<Request>(message) {
+        Ok(request) => {
+            let result = if request.method == "authenticate" {
+                authenticate(&request.params, token).inspect(|_| *authenticated = true)
+            } else if *authenticated {
+                handle_request(&request.method, request.params, app_state, cx).await
+            } else {
+                Err(ResponseError::new(
+                    UNAUTHENTICATED,
+                    "call authenticate before any other method",
+                ))
+            };
+            (request.id, result)
+        }
+        Err(error) if error.is_data() => (
+            Value::Null,
+            Err(ResponseError::new(INVALID_REQUEST, error.to_string())),
+        ),
+        Err(error) => (
+            Value::Null,
+            Err(ResponseError::new(PARSE_ERROR, error.to_string())),
+        ),
+    };
+
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+fn authenticate(params: &Value, token: &str) -> Result<Value, ResponseError> {
+    #[derive(Deserialize)]
+    struct Params {
+        token: String,
+    }
+
+    let params: Params = parse_params(params.clone())?;
+    // Compare in constant time, so that how long the comparison takes doesn't
+    // reveal how much of a guess is right.
+    if bool::from(params.token.as_bytes().ct_eq(token.as_bytes())) {
+        Ok(Value::Null)
+    } else {
+        Err(ResponseError::new(UNAUTHENTICATED, "invalid token"))
+    }
+}
+
+async fn handle_request(
+    method: &str,
+    params: Value,
+    app_state: &Arc<AppState>,
+    cx: &mut AsyncApp,
+) -> Result<Value, ResponseError> {
+    match method {
+        "open_file" => open_file(parse_params(params)?, app_state, cx).await,
+        "run_action" => run_action(parse_params(params)?, cx),
+        "get_diagnostics" => get_diagnostics(parse_params(params)?, cx).await,
+        "apply_edit" => apply_edit(parse_params(params)?, cx).await,
+        _ => Err(ResponseError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method {method:?}"),
+        )),
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, ResponseError> {
+    // Methods without required parameters can be called without `params`.
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params)
+        .map_err(|error| ResponseError::new(INVALID_PARAMS, error.to_string()))
+}
+
+/// A zero-based position in a buffer.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+struct Position {
+    row: u32,
+    column: u32,
+}
+
+impl From<Position> for Point {
+    fn from(position: Position) -> Self {
+        Point::new(position.row, position.column)
+    }
+}
+
+impl From<Point> for Position {
+    fn from(point: Point) -> Self {
+        Self {
+            row: point.row,
+            column: point.column,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenFileParams {
+    path: PathBuf,
+    #[serde(default)]
+    row: Option<u32>,
+    #[serde(default)]
+    column: Option<u32>,
+}
+
+async fn open_file(
+    params: OpenFileParams,
+    app_state: &Arc<AppState>,
+    cx: &mut AsyncApp,
+) -> Result<Value, ResponseError> {
+    let (window, mut items) = cx
+        .update(|cx| {
+            workspace::open_paths(
+                &[params.path.clone()],
+                app_state.clone(),
+                OpenOptions::default(),
+                cx,
+            )
+        })?
+        .await?;
+    let item = items
+        .pop()
+        .flatten()
+        .ok_or_else(|| anyhow!("failed to open {:?}", params.path))??;
+
+    if let Some(row) = params.row {
+        let point = Point::new(row, params.column.unwrap_or(0));
+        window.update(cx, |_, window, cx| {
+            if let Some(editor) = item.act_as::<Editor>(cx) {
+                editor.update(cx, |editor, cx| {
+                    editor.go_to_singleton_buffer_point(point, window, cx)
+                });
+            }
+        })?;
+    }
+    Ok(Value::Null)
+}
+
+#[derive(Deserialize)]
+struct RunActionParams {
+    name: String,
+    #[serde(default)]
+    arguments: Option<Value>,
+}
+
+fn run_action(params: RunActionParams, cx: &mut AsyncApp) -> Result<Value, ResponseError> {
+    cx.update(|cx| {
+        let action = cx
+            .build_action(&params.name, params.arguments)
+            .map_err(|error| ResponseError::new(INVALID_PARAMS, error.to_string()))?;
+        let window = workspace_window(cx).context("no open workspace")?;
+        window
+            .update(cx, |_, window, cx| window.dispatch_action(action, cx))
+            .context("dispatching action")?;
+        Ok(Value::Null)
+    })?
+}
+
+#[derive(Deserialize)]
+struct GetDiagnosticsParams {
+    #[serde(default)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Diagnostic {
+    start: Position,
+    end: Position,
+    severity: &'static str,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct DiagnosticSummary {
+    path: PathBuf,
+    error_count: usize,
+    warning_count: usize,
+}
+
+async fn get_diagnostics(
+    params: GetDiagnosticsParams,
+    cx: &mut AsyncApp,
+) -> Result<Value, ResponseError> {
+    let project = cx.update(|cx| {
+        let window = workspace_window(cx).context("no open workspace")?;
+        anyhow::Ok(window.read(cx)?.project().clone())
+    })??;
+
+    let Some(path) = params.path else {
+        // Without a path, summarize the diagnostics of every file in the
+        // project.
+        let summaries = project.read_with(cx, |project, cx| {
+            let mut summaries = Vec::<DiagnosticSummary>::new();
+            for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+                let Some(path) = project.absolute_path(&project_path, cx) else {
+                    continue;
+                };
+                // Each language server reports a separate summary for a path.
+                match summaries.last_mut() {
+                    Some(last) if last.path == path => {
+                        last.error_count += summary.error_count;
+                        last.warning_count += summary.warning_count;
+                    }
+                    _ => summaries.push(DiagnosticSummary {
+                        path,
+                        error_count: summary.error_count,
+                        warning_count: summary.warning_count,
+                    }),
+                }
+            }
+            summaries
+        })?;
+        return Ok(serde_json::to_value(summaries).map_err(anyhow::Error::from)?);
+    };
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer(&path, cx))?
+        .await?;
+    let diagnostics = buffer.read_with(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        snapshot
+            .diagnostics_in_range::<_, Point>(0..snapshot.len(), false)
+            .map(|entry| Diagnostic {
+                start: entry.range.start.into(),
+                end: entry.range.end.into(),
+                severity: match entry.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => "error",
+                    DiagnosticSeverity::WARNING => "warning",
+                    DiagnosticSeverity::INFORMATION => "information",
+                    _ => "hint",
+                },
+                message: entry.diagnostic.message,
+            })
+            .collect::<Vec<_>>()
+    })?;
+    Ok(serde_json::to_value(diagnostics).map_err(anyhow::Error::from)?)
+}
+
+#[derive(Deserialize)]
+struct ApplyEditParams {
+    path: PathBuf,
+    edits: Vec<TextEdit>,
+    #[serde(default)]
+    save: bool,
+}
+
+#[derive(Deserialize)]
+struct TextEdit {
+    start: Position,
+    end: Position,
+    text: String,
+}
+
+async fn apply_edit(params: ApplyEditParams, cx: &mut AsyncApp) -> Result<Value, ResponseError> {
+    let project = cx.update(|cx| {
+        let window = workspace_window(cx).context("no open workspace")?;
+        anyhow::Ok(window.read(cx)?.project().clone())
+    })??;
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(&params.path, cx)
+        })?
+        .await?;
+    buffer.update(cx, |buffer, cx| {
+        let snapshot = buffer.snapshot();
+        let edits = params.edits.into_iter().map(|edit| {
+            let start = snapshot.clip_point(edit.start.into(), Bias::Left);
+            let end = snapshot.clip_point(edit.end.into(), Bias::Right);
+            (start..end, edit.text)
+        });
+        buffer.edit(edits, None, cx);
+    })?;
+
+    if params.save {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))?
+            .await?;
+    }
+    Ok(Value::Null)
+}
+
+/// The workspace that requests act on: the active window's, or else any local
+/// workspace's.
+fn workspace_window(cx: &App) -> Option<WindowHandle<Workspace>> {
+    cx.active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+        .or_else(|| workspace::local_workspace_windows(cx).into_iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zed::tests::init_test;
+    use gpui::TestAppContext;
+    use project::Project;
+    use std::os::unix::fs::PermissionsExt as _;
+
+    const TOKEN: &str = "the-token";
+
+    async fn send(
+        request: Value,
+        authenticated: &mut bool,
+        app_state: &Arc<AppState>,
+        cx: &mut TestAppContext,
+    ) -> Value {
+        let mut async_cx = cx.to_async();
+        let response = handle_message(
+            &request.to_string(),
+            authenticated,
+            TOKEN,
+            app_state,
+            &mut async_cx,
+        )
+        .await;
+        cx.run_until_parked();
+        response
+    }
+
+    #[gpui::test]
+    async fn test_authentication(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let mut authenticated = false;
+
+        let response = send(
+            json!({ "id": 1, "method": "run_action", "params": { "name": "workspace::NewFile" } }),
+            &mut authenticated,
+            &app_state,
+            cx,
+        )
+        .await;
+        assert_eq!(response["error"]["code"], UNAUTHENTICATED);
+
+        let response = send(
+            json!({ "id": 2, "method": "authenticate", "params": { "token": "the-tokem" } }),
+            &mut authenticated,
+            &app_state,
+            cx,
+        )
+        .await;
+        assert_eq!(response["error"]["code"], UNAUTHENTICATED);
+        assert!(!authenticated);
+
+        let response = send(
+            json!({ "id": 3, "method": "authenticate", "params": { "token": TOKEN } }),
+            &mut authenticated,
+            &app_state,
+            cx,
+        )
+        .await;
+        assert_eq!(
+            response,
+            json!({ "jsonrpc": "2.0", "id": 3, "result": null })
+        );
+        assert!(authenticated);
+    }
+
+    #[gpui::test]
+    async fn test_run_action(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project, window, cx));
+        let mut authenticated = true;
+
+        let response = send(
+            json!({ "id": 1, "method": "run_action", "params": { "name": "workspace::Nonexistent" } }),
+            &mut authenticated,
+            &app_state,
+            cx,
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = send(
+            json!({ "id": 2, "method": "run_action", "params": { "name": "workspace::NewFile" } }),
+            &mut authenticated,
+            &app_state,
+            cx,
+        )
+        .await;
+        assert_eq!(
+            response,
+            json!({ "jsonrpc": "2.0", "id": 2, "result": null })
+        );
+        window
+            .read_with(cx, |workspace, cx| {
+                let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+                assert!(editor.read(cx).text(cx).is_empty());
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_write_token_replaces_readable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("automation.token");
+        fs::write(&token_path, "stale").unwrap();
+        fs::set_permissions(&token_path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_token(&token_path, TOKEN).unwrap();
+        assert_eq!(fs::read_to_string(&token_path).unwrap(), TOKEN);
+        let mode = fs::metadata(&token_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,668 @@
+//! Exporting the state of a project, so that issues that only show up on large projects can be
+//! reproduced without having access to them, and importing it again as a project whose files
+//! mirror the original's layout and sizes.
+
+use std::{
+    io::Write as _,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use extension_host::ExtensionStore;
+use gpui::{App, AppContext as _, Context, PathPromptOptions, PromptLevel, Window, actions};
+use project::EntryKind;
+use serde::{Deserialize, Serialize};
+use settings::SettingsStore;
+use workspace::{OpenOptions, Toast, Workspace, notifications::NotificationId};
+
+actions!(
+    debug,
+    [
+        /// Exports the layout of the project's worktrees, the languages of its open buffers, the
+        /// user settings and the installed extensions to a file that can be attached to bug
+        /// reports. File names and contents are only included when chosen.
+        ExportProjectState,
+        /// Recreates a project from a file written by `debug: export project state`, filling
+        /// files whose contents weren't exported with placeholder text of the same size.
+        ImportProjectState,
+    ]
+);
+
+/// Files larger than this never have their contents exported.
+const MAX_EXPORTED_FILE_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectState {
+    zed_version: String,
+    anonymized: bool,
+    worktrees: Vec<WorktreeState>,
+    open_buffers: Vec<BufferState>,
+    user_settings: Option<serde_json::Value>,
+    extensions: Vec<ExtensionState>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WorktreeState {
+    root_name: String,
+    entries: Vec<EntryState>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntryState {
+    path: PathBuf,
+    is_dir: bool,
+    size: u64,
+    is_ignored: bool,
+    is_symlink: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contents: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BufferState {
+    worktree: usize,
+    path: PathBuf,
+    language: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExtensionState {
+    id: String,
+    version: String,
+    dev: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExportMode {
+    Anonymized,
+    FileNames,
+    FileContents,
+}
+
+/// Replaces the components of paths with placeholder names, keeping their extensions and
+/// mapping the same name to the same placeholder, so that languages are still detected.
+#[derive(Default)]
+struct PathAnonymizer {
+    names: HashMap<String, String>,
+}
+
+impl PathAnonymizer {
+    fn anonymize(&mut self, path: &Path) -> PathBuf {
+        path.components()
+            .map(|component| match component {
+                Component::Normal(name) => self.anonymize_name(&name.to_string_lossy()),
+                component => component.as_os_str().to_string_lossy().into_owned(),
+            })
+            .collect()
+    }
+
+    fn anonymize_name(&mut self, name: &str) -> String {
+        // Dotfiles like `.gitignore` or `.editorconfig` affect how the project is handled, and
+        // their names don't say anything about it.
+        if name.starts_with('.') {
+            return name.to_string();
+        }
+        let next_ix = self.names.len();
+        self.names
+            .entry(name.to_string())
+            .or_insert_with(|| match Path::new(name).extension() {
+                Some(extension) => format!("{next_ix}.{}", extension.to_string_lossy()),
+                None => next_ix.to_string(),
+            })
+            .clone()
+    }
+}
+
+/// The name of the file that the exported user settings are written to when importing a
+/// project state, next to the recreated worktrees.
+const IMPORTED_SETTINGS_FILE_NAME: &str = "imported-user-settings.json";
+
+/// Redacts string values of the settings that could identify the user, like paths or
+/// credentials, keeping the ones that configure Zed's behavior.
+fn anonymize_settings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                // Environment variables often hold credentials, whatever they're named.
+                if key == "env" || key.ends_with("_env") || key == "environment" {
+                    redact_strings(value);
+                    continue;
+                }
+                let is_secret = ["key", "token", "secret", "password", "url", "user"]
+                    .iter()
+                    .any(|secret| key.contains(secret));
+                match value {
+                    serde_json::Value::String(string) if is_secret => {
+                        *string = "<redacted>".to_string()
+                    }
+                    value => anonymize_settings(value),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(anonymize_settings),
+        serde_json::Value::String(string)
+            if string.contains(['/', '\\', '@']) || string.starts_with('~') =>
+        {
+            *string = "<redacted>".to_string()
+        }
+        _ => {}
+    }
+}
+
+fn redact_strings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => map.values_mut().for_each(redact_strings),
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_strings),
+        serde_json::Value::String(string) => *string = "<redacted>".to_string(),
+        _ => {}
+    }
+}
+
+pub(crate) fn export_project_state(
+    workspace: &mut Workspace,
+    _: &ExportProjectState,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let answer = window.prompt(
+        PromptLevel::Info,
+        "Export project state",
+        Some(
+            "The export includes the layout and sizes of the project's files, the languages of open buffers, your user settings and installed extensions. Anonymizing it replaces file names and redacts settings that could identify you.",
+        ),
+        &[
+            "Anonymized",
+            "With File Names",
+            "With File Names and Contents",
+            "Cancel",
+        ],
+        cx,
+    );
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let mode = match answer.await? {
+            0 => ExportMode::Anonymized,
+            1 => ExportMode::FileNames,
+            2 => ExportMode::FileContents,
+            _ => return anyhow::Ok(()),
+        };
+        let (mut state, files_to_load) = workspace.update(cx, |workspace, cx| {
+            collect_project_state(workspace, mode, cx)
+        })?;
+        for (worktree_ix, entry_ix, abs_path) in files_to_load {
+            if let Ok(contents) = fs.load(&abs_path).await {
+                state.worktrees[worktree_ix].entries[entry_ix].contents = Some(contents);
+            }
+        }
+        let json = serde_json::to_string_pretty(&state)?;
+
+        let path = cx.update(|_, cx| {
+            cx.prompt_for_new_path(paths::home_dir(), Some("project-state.json"))
+        })?;
+        let Some(path) = path.await?? else {
+            return Ok(());
+        };
+        fs.atomic_write(path.clone(), json).await?;
+        workspace.update(cx, |workspace, cx| {
+            struct ExportedProjectState;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<ExportedProjectState>(),
+                    format!("Exported project state to {}", path.display()),
+                ),
+                cx,
+            )
+        })?;
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Collects the state of the workspace's project, along with the worktree and entry indices and
+/// absolute paths of the files whose contents should be loaded into it.
+fn collect_project_state(
+    workspace: &Workspace,
+    mode: ExportMode,
+    cx: &App,
+) -> (ProjectState, Vec<(usize, usize, PathBuf)>) {
+    let anonymized = mode == ExportMode::Anonymized;
+    let mut anonymizer = PathAnonymizer::default();
+    let mut files_to_load = Vec::new();
+    let project = workspace.project().read(cx);
+
+    let worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+    let worktree_states = worktrees
+        .iter()
+        .enumerate()
+        .map(|(worktree_ix, worktree)| {
+            let worktree = worktree.read(cx);
+            let root_name = if anonymized {
+                format!("worktree-{worktree_ix}")
+            } else {
+                worktree.root_name().to_string()
+            };
+            let entries = worktree
+                .entries(true, 0)
+                .filter(|entry| !entry.path.as_os_str().is_empty())
+                .enumerate()
+                .map(|(entry_ix, entry)| {
+                    let is_dir = !matches!(entry.kind, EntryKind::File);
+                    if mode == ExportMode::FileContents
+                        && !is_dir
+                        && !entry.is_ignored
+                        && !entry.is_private
+                        && entry.size <= MAX_EXPORTED_FILE_SIZE
+                    {
+                        files_to_load.push((
+                            worktree_ix,
+                            entry_ix,
+                            worktree.abs_path().join(&entry.path),
+                        ));
+                    }
+                    EntryState {
+                        path: if anonymized {
+                            anonymizer.anonymize(&entry.path)
+                        } else {
+                            entry.path.to_path_buf()
+                        },
+                        is_dir,
+                        size: entry.size,
+                        is_ignored: entry.is_ignored,
+                        is_symlink: entry.canonical_path.is_some(),
+                        contents: None,
+                    }
+                })
+                .collect();
+            WorktreeState { root_name, entries }
+        })
+        .collect();
+
+    let open_buffers = project
+        .buffer_store()
+        .read(cx)
+        .buffers()
+        .filter_map(|buffer| {
+            let buffer = buffer.read(cx);
+            let file = buffer.file()?;
+            let worktree_id = file.worktree_id(cx);
+            let worktree = worktrees
+                .iter()
+                .position(|worktree| worktree.read(cx).id() == worktree_id)?;
+            Some(BufferState {
+                worktree,
+                path: if anonymized {
+                    anonymizer.anonymize(file.path())
+                } else {
+                    file.path().to_path_buf()
+                },
+                language: buffer
+                    .language()
+                    .map(|language| language.name().to_string()),
+            })
+        })
+        .collect();
+
+    let user_settings = cx
+        .global::<SettingsStore>()
+        .raw_user_settings()
+        .and_then(|settings| serde_json::to_value(settings).ok())
+        .map(|mut settings| {
+            if anonymized {
+                anonymize_settings(&mut settings);
+            }
+            settings
+        });
+
+    let extensions = ExtensionStore::try_global(cx)
+        .map(|store| {
+            store
+                .read(cx)
+                .installed_extensions()
+                .values()
+                .map(|extension| ExtensionState {
+                    id: extension.manifest.id.to_string(),
+                    version: extension.manifest.version.to_string(),
+                    dev: extension.dev,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let state = ProjectState {
+        zed_version: env!("CARGO_PKG_VERSION").to_string(),
+        anonymized,
+        worktrees: worktree_states,
+        open_buffers,
+        user_settings,
+        extensions,
+    };
+    (state, files_to_load)
+}
+
+pub(crate) fn import_project_state(
+    workspace: &mut Workspace,
+    _: &ImportProjectState,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let app_state = workspace.app_state().clone();
+    let state_path = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+        prompt: Some("Import Project State".into()),
+    });
+    cx.spawn_in(window, async move |_, cx| {
+        let Some(state_path) = state_path
+            .await??
+            .and_then(|paths| paths.into_iter().next())
+        else {
+            return anyhow::Ok(());
+        };
+        let json = app_state.fs.load(&state_path).await?;
+        let state = serde_json::from_str::<ProjectState>(&json)
+            .with_context(|| format!("parsing project state from {state_path:?}"))?;
+
+        let destination = cx.update(|_, cx| {
+            cx.prompt_for_paths(PathPromptOptions {
+                files: false,
+                directories: true,
+                multiple: false,
+                prompt: Some("Recreate Project Here".into()),
+            })
+        })?;
+        let Some(destination) = destination
+            .await??
+            .and_then(|paths| paths.into_iter().next())
+        else {
+            return Ok(());
+        };
+
+        let extensions = state
+            .extensions
+            .iter()
+            .filter(|extension| !extension.dev)
+            .map(|extension| extension.id.clone())
+            .collect::<Vec<_>>();
+        let (paths, settings_path) = cx
+            .background_spawn(async move { recreate_project(&state, &destination) })
+            .await?;
+        let missing_extensions = cx.update(|_, cx| {
+            let store = ExtensionStore::try_global(cx);
+            let installed = store
+                .as_ref()
+                .map(|store| store.read(cx).installed_extensions());
+            extensions
+                .into_iter()
+                .filter(|id| installed.is_none_or(|installed| !installed.contains_key(id.as_str())))
+                .collect::<Vec<_>>()
+        })?;
+
+        let (new_workspace, _) = cx
+            .update(|_, cx| {
+                workspace::open_paths(
+                    &paths,
+                    app_state,
+                    OpenOptions {
+                        open_new_workspace: Some(true),
+                        ..Default::default()
+                    },
+                    cx,
+                )
+            })?
+            .await?;
+
+        if let Some(settings_path) = settings_path {
+            new_workspace.update(cx, |workspace, _, cx| {
+                struct ImportedSettings;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<ImportedSettings>(),
+                        format!(
+                            "The exported user settings were written to {} and weren't applied",
+                            settings_path.display()
+                        ),
+                    ),
+                    cx,
+                )
+            })?;
+        }
+        if !missing_extensions.is_empty() {
+            new_workspace.update(cx, |workspace, _, cx| {
+                struct MissingExtensions;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<MissingExtensions>(),
+                        format!(
+                            "The imported project used extensions that aren't installed: {}",
+                            missing_extensions.join(", ")
+                        ),
+                    ),
+                    cx,
+                )
+            })?;
+        }
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Writes the worktrees of `state` into `destination`, returning the paths of their roots
+/// followed by the paths of the buffers that were open, and the path of the file that the
+/// exported user settings were written to.
+///
+/// The settings, tasks and debug configurations of the exported project aren't recreated, as
+/// they can run commands as soon as the project is opened.
+fn recreate_project(
+    state: &ProjectState,
+    destination: &Path,
+) -> Result<(Vec<PathBuf>, Option<PathBuf>)> {
+    let mut roots = Vec::new();
+    for worktree in &state.worktrees {
+        let mut root_components = Path::new(&worktree.root_name).components();
+        anyhow::ensure!(
+            matches!(root_components.next(), Some(Component::Normal(_)))
+                && root_components.next().is_none(),
+            "invalid worktree root name {:?}",
+            worktree.root_name
+        );
+        let root = destination.join(&worktree.root_name);
+        std::fs::create_dir_all(&root).with_context(|| format!("creating {root:?}"))?;
+
+        let mut ignored_paths = Vec::new();
+        for entry in &worktree.entries {
+            if entry.path.is_absolute()
+                || entry
+                    .path
+                    .components()
+                    .any(|component| matches!(component, Component::ParentDir))
+                || entry
+                    .path
+                    .starts_with(paths::local_settings_folder_relative_path())
+                || entry
+                    .path
+                    .starts_with(paths::local_vscode_folder_relative_path())
+            {
+                continue;
+            }
+            let abs_path = root.join(&entry.path);
+            // Only the outermost ignored entries need to be listed in the `.gitignore`.
+            if entry.is_ignored
+                && !ignored_paths
+                    .iter()
+                    .any(|ignored: &&Path| entry.path.starts_with(ignored))
+            {
+                ignored_paths.push(&entry.path);
+            }
+            if entry.is_dir {
+                std::fs::create_dir_all(&abs_path)
+                    .with_context(|| format!("creating {abs_path:?}"))?;
+            } else {
+                if let Some(parent) = abs_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                match &entry.contents {
+                    Some(contents) => std::fs::write(&abs_path, contents),
+                    None => write_placeholder(&abs_path, entry.size),
+                }
+                .with_context(|| format!("writing {abs_path:?}"))?;
+            }
+        }
+
+        let has_gitignore = worktree
+            .entries
+            .iter()
+            .any(|entry| entry.path == Path::new(".gitignore") && entry.contents.is_some());
+        if !has_gitignore && !ignored_paths.is_empty() {
+            let gitignore = ignored_paths
+                .iter()
+                .map(|path| format!("/{}\n", path.to_string_lossy().replace('\\', "/")))
+                .collect::<String>();
+            std::fs::write(root.join(".gitignore"), gitignore)?;
+        }
+
+        roots.push(root);
+    }
+
+    // The user settings are left for whoever imports the project to look at and apply, rather
+    // than applying settings that can start language servers and tasks from a file of unknown
+    // origin.
+    let settings_path = match &state.user_settings {
+        Some(user_settings) => {
+            let settings_path = destination.join(IMPORTED_SETTINGS_FILE_NAME);
+            std::fs::write(&settings_path, serde_json::to_string_pretty(user_settings)?)
+                .with_context(|| format!("writing {settings_path:?}"))?;
+            Some(settings_path)
+        }
+        None => None,
+    };
+
+    let buffers = state
+        .open_buffers
+        .iter()
+        .filter_map(|buffer| Some(roots.get(buffer.worktree)?.join(&buffer.path)))
+        .collect::<Vec<_>>();
+    roots.extend(buffers);
+    Ok((roots, settings_path))
+}
+
+/// Writes a file of `size` bytes made up of lines of text, so that it's treated like the text
+/// file it stands in for.
+fn write_placeholder(path: &Path, size: u64) -> std::io::Result<()> {
+    const LINE: &[u8] = b"placeholder text of an exported project file\n";
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut remaining = size as usize;
+    while remaining > 0 {
+        let len = remaining.min(LINE.len());
+        file.write_all(&LINE[LINE.len() - len..])?;
+        remaining -= len;
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_path_anonymizer() {
+        let mut anonymizer = PathAnonymizer::default();
+        assert_eq!(
+            anonymizer.anonymize(Path::new("src/secret/main.rs")),
+            PathBuf::from("0/1/2.rs")
+        );
+        assert_eq!(
+            anonymizer.anonymize(Path::new("src/.gitignore")),
+            PathBuf::from("0/.gitignore")
+        );
+        assert_eq!(
+            anonymizer.anonymize(Path::new("secret/Makefile")),
+            PathBuf::from("1/3")
+        );
+    }
+
+    #[test]
+    fn test_anonymize_settings() {
+        let mut settings = json!({
+            "theme": "One Dark",
+            "tab_size": 2,
+            "terminal": {
+                "working_directory": { "always": { "directory": "~/secret" } },
+                "env": { "AWS_SECRET": "abc", "EDITOR": "zed" },
+            },
+            "lsp": { "rust-analyzer": { "binary": { "env": { "API": "xyz" } } } },
+            "language_models": { "openai": { "api_url": "https://internal.example.com" } },
+            "file_scan_exclusions": ["**/.git", "secret"],
+        });
+        anonymize_settings(&mut settings);
+        assert_eq!(
+            settings,
+            json!({
+                "theme": "One Dark",
+                "tab_size": 2,
+                "terminal": {
+                    "working_directory": { "always": { "directory": "<redacted>" } },
+                    "env": { "AWS_SECRET": "<redacted>", "EDITOR": "<redacted>" },
+                },
+                "lsp": { "rust-analyzer": { "binary": { "env": { "API": "<redacted>" } } } },
+                "language_models": { "openai": { "api_url": "<redacted>" } },
+                "file_scan_exclusions": ["<redacted>", "secret"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_recreate_project() {
+        let state = |root_name: &str| ProjectState {
+            zed_version: String::new(),
+            anonymized: false,
+            worktrees: vec![WorktreeState {
+                root_name: root_name.to_string(),
+                entries: vec![
+                    EntryState {
+                        path: PathBuf::from("main.rs"),
+                        is_dir: false,
+                        size: 4,
+                        is_ignored: false,
+                        is_symlink: false,
+                        contents: Some("fn\n".to_string()),
+                    },
+                    EntryState {
+                        path: PathBuf::from(".zed/tasks.json"),
+                        is_dir: false,
+                        size: 2,
+                        is_ignored: false,
+                        is_symlink: false,
+                        contents: Some("[]".to_string()),
+                    },
+                ],
+            }],
+            open_buffers: Vec::new(),
+            user_settings: Some(json!({ "tasks_on_save": [] })),
+            extensions: Vec::new(),
+        };
+
+        let destination = tempfile::tempdir().unwrap();
+        for root_name in ["../escaped", "/escaped", "a/b", ""] {
+            assert!(recreate_project(&state(root_name), destination.path()).is_err());
+        }
+        assert!(!destination.path().join("escaped").exists());
+
+        let (paths, settings_path) =
+            recreate_project(&state("project"), destination.path()).unwrap();
+        let root = destination.path().join("project");
+        assert_eq!(paths, [root.clone()]);
+        assert_eq!(
+            std::fs::read_to_string(root.join("main.rs")).unwrap(),
+            "fn\n"
+        );
+        assert!(!root.join(".zed").exists());
+        assert_eq!(
+            settings_path,
+            Some(destination.path().join(IMPORTED_SETTINGS_FILE_NAME))
+        );
+    }
+}
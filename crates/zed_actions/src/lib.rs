@@ -52,6 +52,10 @@ actions!(
         OpenLicenses,
         /// Opens the telemetry log.
         OpenTelemetryLog,
+        /// Shows the actions invoked and the time spent per language and project this session.
+        ShowUsageStats,
+        /// Exports this session's usage statistics to a JSON file.
+        ExportUsageStats,
     ]
 );
 